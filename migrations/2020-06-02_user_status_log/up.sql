@@ -0,0 +1,16 @@
+CREATE TABLE user_status_log (
+    rowid      INTEGER PRIMARY KEY,
+    user_id    INTEGER NOT NULL,
+    --
+    created_at INTEGER NOT NULL,
+    created_by INTEGER,
+    --
+    active     BOOLEAN NOT NULL,
+    context    TEXT, -- system context, e.g. client IP address, ...
+    comment    TEXT, -- human-written textual comment
+    --
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (created_by) REFERENCES users(id)
+);
+
+CREATE INDEX user_status_log_idx_user_id ON user_status_log(user_id);
@@ -0,0 +1,14 @@
+CREATE TABLE category (
+    rowid INTEGER PRIMARY KEY,
+    id    TEXT NOT NULL, -- external id
+    tag   TEXT NOT NULL, -- predefined tag of the category
+    --
+    UNIQUE (id),
+    UNIQUE (tag)
+);
+
+-- Built-in categories
+INSERT INTO category (id, tag) VALUES
+    ('2cd00bebec0c48ba9db761da48678134', 'non-profit'),
+    ('77b3c33a92554bcf8e8c2c86cedd6f6f', 'commercial'),
+    ('c2dc278a2d6a4b9b8a50cb606fc017ed', 'event');
@@ -0,0 +1 @@
+-- SQLite does not support dropping columns
@@ -0,0 +1,4 @@
+-- Origin of imported places
+ALTER TABLE place_revision ADD COLUMN source_importer TEXT;
+ALTER TABLE place_revision ADD COLUMN source_external_ref TEXT;
+ALTER TABLE place_revision ADD COLUMN source_imported_at INTEGER;
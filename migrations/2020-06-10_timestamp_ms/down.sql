@@ -0,0 +1,6 @@
+-- See up.sql for distinguishing seconds from milliseconds
+UPDATE place_rating SET created_at=created_at/1000 WHERE created_at>=100000000000;
+UPDATE place_rating SET archived_at=archived_at/1000 WHERE archived_at NOT NULL AND archived_at>=100000000000;
+UPDATE place_rating_comment SET created_at=created_at/1000 WHERE created_at>=100000000000;
+UPDATE place_rating_comment SET archived_at=archived_at/1000 WHERE archived_at NOT NULL AND archived_at>=100000000000;
+UPDATE events SET archived=archived/1000 WHERE archived NOT NULL AND archived>=100000000000;
@@ -0,0 +1,13 @@
+-- Convert all remaining time stamps from seconds to milliseconds.
+-- Archived ratings and comments might already have been stored
+-- with millisecond precision and must not be converted again.
+--
+-- Both units are distinguished by their magnitude: 10^11 seconds
+-- are in the year 5138, while 10^11 milliseconds have already
+-- passed in March 1973, i.e. long before any data has been
+-- recorded. All values below 10^11 are seconds.
+UPDATE place_rating SET created_at=created_at*1000 WHERE created_at<100000000000;
+UPDATE place_rating SET archived_at=archived_at*1000 WHERE archived_at NOT NULL AND archived_at<100000000000;
+UPDATE place_rating_comment SET created_at=created_at*1000 WHERE created_at<100000000000;
+UPDATE place_rating_comment SET archived_at=archived_at*1000 WHERE archived_at NOT NULL AND archived_at<100000000000;
+UPDATE events SET archived=archived*1000 WHERE archived NOT NULL AND archived<100000000000;
@@ -0,0 +1,5 @@
+-- Places with curated tags receive a score boost in search results
+CREATE TABLE curated_tag (
+    tag   TEXT PRIMARY KEY NOT NULL,
+    boost REAL NOT NULL
+);
@@ -0,0 +1,19 @@
+-- Items that have been assigned to scouts for moderation
+CREATE TABLE moderation_assignment (
+    rowid       INTEGER PRIMARY KEY,
+    --
+    item_kind   SMALLINT NOT NULL,
+    item_id     TEXT NOT NULL,
+    assignee    INTEGER NOT NULL,
+    state       SMALLINT NOT NULL,
+    --
+    assigned_at INTEGER NOT NULL,
+    assigned_by INTEGER,
+    changed_at  INTEGER NOT NULL,
+    --
+    FOREIGN KEY (assignee) REFERENCES users(id),
+    FOREIGN KEY (assigned_by) REFERENCES users(id),
+    UNIQUE (item_kind, item_id)
+);
+
+CREATE INDEX moderation_assignment_idx_assignee ON moderation_assignment(assignee);
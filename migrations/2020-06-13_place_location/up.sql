@@ -0,0 +1,17 @@
+-- Further sites of places that operate at multiple locations
+CREATE TABLE place_revision_location (
+    rowid        INTEGER PRIMARY KEY,
+    parent_rowid INTEGER NOT NULL,
+    --
+    lat          FLOAT NOT NULL,
+    lon          FLOAT NOT NULL,
+    street       TEXT,
+    zip          TEXT,
+    city         TEXT,
+    country      TEXT,
+    state        TEXT,
+    --
+    FOREIGN KEY (parent_rowid) REFERENCES place_revision(rowid)
+);
+
+CREATE INDEX place_revision_location_idx_parent ON place_revision_location(parent_rowid);
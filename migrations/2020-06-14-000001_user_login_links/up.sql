@@ -0,0 +1,13 @@
+-- One-time login links that are independent of the
+-- user tokens and might be requested before the user
+-- has been registered
+CREATE TABLE user_login_links (
+    rowid      INTEGER PRIMARY KEY,
+    email      TEXT NOT NULL,
+    nonce      TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL,
+    --
+    UNIQUE (email),
+    UNIQUE (nonce)
+);
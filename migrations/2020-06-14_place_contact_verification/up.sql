@@ -0,0 +1,13 @@
+-- Verifications of the contact e-mail addresses of places
+CREATE TABLE place_contact_verification (
+    rowid        INTEGER PRIMARY KEY,
+    parent_rowid INTEGER NOT NULL,
+    --
+    email        TEXT NOT NULL,
+    nonce        TEXT NOT NULL,
+    expires_at   INTEGER NOT NULL,
+    verified_at  INTEGER,
+    --
+    FOREIGN KEY (parent_rowid) REFERENCES place(rowid),
+    UNIQUE (parent_rowid, email)
+);
@@ -0,0 +1,15 @@
+-- Incomplete submissions of new places
+CREATE TABLE place_draft (
+    rowid      INTEGER PRIMARY KEY,
+    id         TEXT NOT NULL,
+    owner      INTEGER NOT NULL,
+    --
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    data       TEXT NOT NULL,
+    --
+    FOREIGN KEY (owner) REFERENCES users(id),
+    UNIQUE (id)
+);
+
+CREATE INDEX place_draft_idx_owner ON place_draft(owner);
@@ -0,0 +1,12 @@
+-- Permissions that are granted or revoked for individual
+-- users independent of the defaults of their role
+CREATE TABLE user_permission (
+    rowid      INTEGER PRIMARY KEY,
+    user_id    INTEGER NOT NULL,
+    --
+    permission SMALLINT NOT NULL,
+    granted    BOOLEAN NOT NULL,
+    --
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    UNIQUE (user_id, permission)
+);
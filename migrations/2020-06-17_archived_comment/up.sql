@@ -0,0 +1,5 @@
+-- Reasons for archiving ratings, comments and events
+ALTER TABLE place_rating ADD COLUMN archived_comment TEXT;
+ALTER TABLE place_rating_comment ADD COLUMN archived_comment TEXT;
+ALTER TABLE events ADD COLUMN archived_by INTEGER REFERENCES users(id);
+ALTER TABLE events ADD COLUMN archived_comment TEXT;
@@ -0,0 +1 @@
+DROP TABLE scout_report_opt_out;
@@ -0,0 +1,7 @@
+-- Users who don't want to receive the periodic
+-- reports about their region for scouts
+CREATE TABLE scout_report_opt_out (
+    user_id INTEGER PRIMARY KEY,
+    --
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
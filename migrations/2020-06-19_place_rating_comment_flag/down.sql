@@ -0,0 +1 @@
+DROP TABLE place_rating_comment_flag;
@@ -0,0 +1,12 @@
+-- Comments that have been flagged by users for moderation
+CREATE TABLE place_rating_comment_flag (
+    rowid        INTEGER PRIMARY KEY,
+    parent_rowid INTEGER NOT NULL,
+    --
+    created_at   INTEGER NOT NULL,
+    created_by   INTEGER NOT NULL,
+    --
+    FOREIGN KEY (parent_rowid) REFERENCES place_rating_comment(rowid),
+    FOREIGN KEY (created_by) REFERENCES users(id),
+    UNIQUE (parent_rowid, created_by)
+);
@@ -0,0 +1,12 @@
+-- References of places to records in other data sources
+CREATE TABLE place_external_id (
+    rowid        INTEGER PRIMARY KEY,
+    parent_rowid INTEGER NOT NULL,
+    --
+    source       TEXT NOT NULL,
+    external_id  TEXT NOT NULL,
+    url          TEXT,
+    --
+    FOREIGN KEY (parent_rowid) REFERENCES place(rowid),
+    UNIQUE (source, external_id)
+);
@@ -0,0 +1,17 @@
+-- Actions of admins and scouts
+CREATE TABLE audit_log (
+    rowid      INTEGER PRIMARY KEY,
+    --
+    created_at INTEGER NOT NULL,
+    created_by INTEGER,
+    --
+    action     INTEGER NOT NULL,
+    item       TEXT NOT NULL,
+    context    TEXT,
+    comment    TEXT,
+    --
+    FOREIGN KEY (created_by) REFERENCES users(id)
+);
+
+CREATE INDEX audit_log_idx_created_at ON audit_log (created_at);
+CREATE INDEX audit_log_idx_item ON audit_log (item);
@@ -0,0 +1,14 @@
+-- Messages for all visitors, e.g. about maintenance windows
+CREATE TABLE announcement (
+    rowid     INTEGER PRIMARY KEY,
+    id        TEXT NOT NULL,
+    --
+    message   TEXT NOT NULL,
+    severity  SMALLINT NOT NULL,
+    starts_at INTEGER NOT NULL,
+    ends_at   INTEGER,
+    --
+    UNIQUE (id)
+);
+
+CREATE INDEX announcement_idx_starts_at ON announcement (starts_at);
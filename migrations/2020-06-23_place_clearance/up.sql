@@ -0,0 +1,14 @@
+-- Edits of places with tags that are owned by organizations
+-- and that still need to be cleared by these organizations
+CREATE TABLE place_clearance (
+    rowid                 INTEGER PRIMARY KEY,
+    org_id                TEXT NOT NULL,
+    place_rowid           INTEGER NOT NULL,
+    --
+    created_at            INTEGER NOT NULL,
+    last_cleared_revision INTEGER,
+    --
+    FOREIGN KEY (org_id) REFERENCES organizations(id),
+    FOREIGN KEY (place_rowid) REFERENCES place(rowid),
+    UNIQUE (org_id, place_rowid)
+);
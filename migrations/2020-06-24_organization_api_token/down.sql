@@ -0,0 +1,18 @@
+-- The oldest token that grants all scopes and never expires
+-- becomes the default API token again
+ALTER TABLE organizations ADD COLUMN api_token TEXT NOT NULL DEFAULT '';
+
+UPDATE organizations SET api_token = coalesce(
+    (SELECT t.token
+     FROM organization_api_token t
+     WHERE t.org_id = organizations.id
+     AND t.expires_at IS NULL
+     AND (SELECT count(*) FROM organization_api_token_scope s WHERE s.token_rowid = t.rowid) = 4
+     ORDER BY t.created_at
+     LIMIT 1),
+    lower(hex(randomblob(16))));
+
+CREATE UNIQUE INDEX organizations_api_token ON organizations (api_token);
+
+DROP TABLE organization_api_token_scope;
+DROP TABLE organization_api_token;
@@ -0,0 +1,53 @@
+-- Additional API tokens of organizations that are restricted
+-- to some scopes and might expire
+CREATE TABLE organization_api_token (
+    rowid      INTEGER PRIMARY KEY,
+    id         TEXT NOT NULL,
+    org_id     TEXT NOT NULL,
+    token      TEXT NOT NULL,
+    --
+    created_at INTEGER NOT NULL,
+    expires_at INTEGER,
+    --
+    FOREIGN KEY (org_id) REFERENCES organizations(id),
+    UNIQUE (id),
+    UNIQUE (token)
+);
+
+CREATE TABLE organization_api_token_scope (
+    rowid       INTEGER PRIMARY KEY,
+    token_rowid INTEGER NOT NULL,
+    --
+    scope       SMALLINT NOT NULL,
+    --
+    FOREIGN KEY (token_rowid) REFERENCES organization_api_token(rowid),
+    UNIQUE (token_rowid, scope)
+);
+
+-- The default API tokens of organizations become regular
+-- API tokens that grant all scopes and never expire
+INSERT INTO organization_api_token (id, org_id, token, created_at, expires_at)
+SELECT lower(hex(randomblob(16))), id, api_token, strftime('%s', 'now') * 1000, NULL
+FROM organizations;
+
+-- 0 = Read, 1 = WriteEvents, 2 = ClearPlaces, 3 = ImportPlaces
+INSERT INTO organization_api_token_scope (token_rowid, scope)
+SELECT t.rowid, s.scope
+FROM organization_api_token t
+JOIN organizations o ON t.org_id = o.id AND t.token = o.api_token
+CROSS JOIN (SELECT 0 AS scope UNION ALL SELECT 1 UNION ALL SELECT 2 UNION ALL SELECT 3) s;
+
+-- SQLite doesn't support dropping columns
+CREATE TABLE organizations_new (
+    id   TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL
+);
+
+INSERT INTO organizations_new
+SELECT id, name
+FROM organizations;
+
+DROP INDEX organizations_api_token;
+
+DROP TABLE organizations;
+ALTER TABLE organizations_new RENAME TO organizations;
@@ -0,0 +1,15 @@
+-- Personal API tokens of users for authorizing requests
+-- without a session
+CREATE TABLE user_api_tokens (
+    rowid      INTEGER PRIMARY KEY,
+    id         TEXT NOT NULL,
+    user_id    INTEGER NOT NULL,
+    token      TEXT NOT NULL,
+    label      TEXT,
+    --
+    created_at INTEGER NOT NULL,
+    --
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    UNIQUE (id),
+    UNIQUE (token)
+);
@@ -0,0 +1,4 @@
+DROP INDEX webhook_delivery_idx_status_next_attempt_at;
+DROP TABLE webhook_delivery;
+DROP TABLE webhook_event;
+DROP TABLE webhook;
@@ -0,0 +1,44 @@
+-- Callback URLs that are notified about changes of
+-- places, events, and ratings
+CREATE TABLE webhook (
+    rowid      INTEGER PRIMARY KEY,
+    id         TEXT NOT NULL,
+    url        TEXT NOT NULL,
+    secret     TEXT NOT NULL,
+    --
+    created_at INTEGER NOT NULL,
+    --
+    UNIQUE (id)
+);
+
+CREATE TABLE webhook_event (
+    rowid         INTEGER PRIMARY KEY,
+    webhook_rowid INTEGER NOT NULL,
+    --
+    event         SMALLINT NOT NULL,
+    --
+    FOREIGN KEY (webhook_rowid) REFERENCES webhook(rowid),
+    UNIQUE (webhook_rowid, event)
+);
+
+-- The log of all requests to webhooks including
+-- the pending ones that are retried
+CREATE TABLE webhook_delivery (
+    rowid           INTEGER PRIMARY KEY,
+    id              TEXT NOT NULL,
+    webhook_rowid   INTEGER NOT NULL,
+    event           SMALLINT NOT NULL,
+    payload         TEXT NOT NULL,
+    --
+    created_at      INTEGER NOT NULL,
+    status          SMALLINT NOT NULL,
+    attempts        INTEGER NOT NULL,
+    last_attempt_at INTEGER,
+    next_attempt_at INTEGER,
+    response_status INTEGER,
+    --
+    FOREIGN KEY (webhook_rowid) REFERENCES webhook(rowid),
+    UNIQUE (id)
+);
+
+CREATE INDEX webhook_delivery_idx_status_next_attempt_at ON webhook_delivery (status, next_attempt_at);
@@ -0,0 +1,2 @@
+-- SQLite does not support dropping columns
+DROP INDEX events_idx_external_id;
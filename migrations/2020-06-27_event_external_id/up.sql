@@ -0,0 +1,6 @@
+-- References of events to records in the systems of the
+-- organizations that push them, unique per organization
+ALTER TABLE events ADD COLUMN external_org_id TEXT REFERENCES organizations(id);
+ALTER TABLE events ADD COLUMN external_id TEXT;
+
+CREATE UNIQUE INDEX events_idx_external_id ON events(external_org_id, external_id);
@@ -0,0 +1,10 @@
+-- Weekly or monthly repetitions of events
+CREATE TABLE event_recurrences (
+    event_id  INTEGER PRIMARY KEY,
+    --
+    frequency SMALLINT NOT NULL,
+    interval  INTEGER NOT NULL,
+    until     INTEGER,
+    --
+    FOREIGN KEY (event_id) REFERENCES events(id)
+);
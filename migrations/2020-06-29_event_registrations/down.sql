@@ -0,0 +1,2 @@
+DROP TABLE event_registrations;
+-- SQLite does not support dropping columns
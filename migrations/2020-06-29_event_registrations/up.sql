@@ -0,0 +1,17 @@
+-- Maximum number of confirmed registrations
+ALTER TABLE events ADD COLUMN capacity INTEGER;
+
+-- Attendees of events, including those on the waitlist
+CREATE TABLE event_registrations (
+    rowid         INTEGER PRIMARY KEY,
+    event_id      INTEGER NOT NULL,
+    email         TEXT NOT NULL,
+    nonce         TEXT NOT NULL,
+    --
+    status        SMALLINT NOT NULL,
+    registered_at INTEGER NOT NULL,
+    --
+    FOREIGN KEY (event_id) REFERENCES events(id),
+    UNIQUE (event_id, email),
+    UNIQUE (nonce)
+);
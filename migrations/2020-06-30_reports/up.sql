@@ -0,0 +1,22 @@
+-- Places, events, and comments that have been flagged
+-- as spam or offensive by users
+CREATE TABLE reports (
+    rowid        INTEGER PRIMARY KEY,
+    id           TEXT NOT NULL,
+    content_kind SMALLINT NOT NULL,
+    content_id   TEXT NOT NULL,
+    --
+    created_at   INTEGER NOT NULL,
+    created_by   INTEGER,
+    resolved_at  INTEGER,
+    resolved_by  INTEGER,
+    --
+    reason       SMALLINT NOT NULL,
+    comment      TEXT, -- human-written textual comment
+    --
+    FOREIGN KEY (created_by) REFERENCES users(id),
+    FOREIGN KEY (resolved_by) REFERENCES users(id),
+    UNIQUE (id)
+);
+
+CREATE INDEX reports_idx_resolved_at ON reports(resolved_at);
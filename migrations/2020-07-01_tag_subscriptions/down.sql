@@ -0,0 +1,2 @@
+DROP TABLE tag_subscription_tags;
+DROP TABLE tag_subscriptions;
@@ -0,0 +1,23 @@
+-- Subscriptions to places with certain tags that are
+-- optionally restricted to a bounding box
+CREATE TABLE tag_subscriptions (
+    rowid          INTEGER PRIMARY KEY,
+    id             TEXT NOT NULL,
+    user_id        INTEGER NOT NULL,
+    --
+    south_west_lat REAL,
+    south_west_lng REAL,
+    north_east_lat REAL,
+    north_east_lng REAL,
+    --
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    UNIQUE (id)
+);
+
+CREATE TABLE tag_subscription_tags (
+    parent_rowid INTEGER NOT NULL,
+    tag          TEXT NOT NULL,
+    --
+    FOREIGN KEY (parent_rowid) REFERENCES tag_subscriptions(rowid),
+    PRIMARY KEY (parent_rowid, tag)
+);
@@ -0,0 +1,23 @@
+-- Users who receive a periodic summary instead of
+-- an e-mail for every change within their subscriptions
+CREATE TABLE notification_digests (
+    user_id      INTEGER PRIMARY KEY,
+    interval     SMALLINT NOT NULL,
+    last_sent_at INTEGER NOT NULL,
+    --
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
+
+-- Changes that are collected until the next digest is sent
+CREATE TABLE notification_queue (
+    rowid       INTEGER PRIMARY KEY,
+    user_id     INTEGER NOT NULL,
+    created_at  INTEGER NOT NULL,
+    kind        SMALLINT NOT NULL,
+    place_id    TEXT NOT NULL,
+    place_title TEXT NOT NULL,
+    --
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
+
+CREATE INDEX notification_queue_idx_user_id ON notification_queue(user_id);
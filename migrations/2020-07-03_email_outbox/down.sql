@@ -0,0 +1,2 @@
+DROP INDEX email_outbox_idx_status_next_attempt_at;
+DROP TABLE email_outbox;
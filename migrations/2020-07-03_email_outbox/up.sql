@@ -0,0 +1,20 @@
+-- All outgoing e-mails including the pending ones
+-- that are retried
+CREATE TABLE email_outbox (
+    rowid           INTEGER PRIMARY KEY,
+    id              TEXT NOT NULL,
+    recipient       TEXT NOT NULL,
+    subject         TEXT NOT NULL,
+    body            TEXT NOT NULL,
+    --
+    created_at      INTEGER NOT NULL,
+    status          SMALLINT NOT NULL,
+    attempts        INTEGER NOT NULL,
+    last_attempt_at INTEGER,
+    next_attempt_at INTEGER,
+    last_error      TEXT,
+    --
+    UNIQUE (id)
+);
+
+CREATE INDEX email_outbox_idx_status_next_attempt_at ON email_outbox (status, next_attempt_at);
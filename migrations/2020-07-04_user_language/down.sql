@@ -0,0 +1 @@
+-- SQLite doesn't support dropping columns
@@ -0,0 +1,2 @@
+-- The preferred language for e-mails, e.g. 'en'
+ALTER TABLE users ADD COLUMN language TEXT;
@@ -0,0 +1,11 @@
+-- Duplicates that have been merged into a primary place
+CREATE TABLE place_merge (
+    rowid         INTEGER PRIMARY KEY,
+    parent_rowid  INTEGER NOT NULL,
+    primary_rowid INTEGER NOT NULL,
+    merged_at     INTEGER NOT NULL,
+    --
+    FOREIGN KEY (parent_rowid) REFERENCES place(rowid),
+    FOREIGN KEY (primary_rowid) REFERENCES place(rowid),
+    UNIQUE (parent_rowid)
+);
@@ -0,0 +1,7 @@
+-- Places with tags owned by a curated organization receive
+-- a score boost in search results
+CREATE TABLE curated_organization (
+    org_id TEXT PRIMARY KEY NOT NULL,
+    boost  REAL NOT NULL,
+    FOREIGN KEY (org_id) REFERENCES organizations(id)
+);
@@ -0,0 +1,13 @@
+-- Searches that users saved to repeat them later
+CREATE TABLE saved_searches (
+    rowid      INTEGER PRIMARY KEY,
+    id         TEXT NOT NULL,
+    user_id    INTEGER NOT NULL,
+    --
+    created_at INTEGER NOT NULL,
+    title      TEXT NOT NULL,
+    query      TEXT NOT NULL,
+    --
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    UNIQUE (id)
+);
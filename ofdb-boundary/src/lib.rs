@@ -28,6 +28,32 @@ pub struct Entry {
     pub license        : Option<String>,
     pub image_url      : Option<String>,
     pub image_link_url : Option<String>,
+    pub source         : Option<PlaceSource>,
+    #[serde(default)]
+    pub additional_locations: Vec<EntryLocation>,
+    #[serde(default)]
+    pub email_verified: bool,
+}
+
+/// A further site of an entry with multiple locations
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq))]
+pub struct EntryLocation {
+    pub lat: f64,
+    pub lng: f64,
+    pub street: Option<String>,
+    pub zip: Option<String>,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    pub state: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq))]
+pub struct PlaceSource {
+    pub importer: String,
+    pub external_ref: String,
+    pub imported_at: i64,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -69,6 +95,19 @@ pub struct Event {
     pub image_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image_link_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<EventRecurrence>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq))]
+pub struct EventRecurrence {
+    pub frequency: String,
+    pub interval: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<i64>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -84,6 +123,8 @@ pub struct User {
     pub email: String,
     pub email_confirmed: bool,
     pub role: UserRole,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -127,6 +168,15 @@ pub enum RatingContext {
     Solidarity,
 }
 
+/// The average ratings of a place within a single calendar month
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct MonthlyRatings {
+    /// The year and month, e.g. "2020-05"
+    pub month: String,
+    pub ratings: EntrySearchRatings,
+}
+
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
 pub struct EntrySearchRatings {
@@ -137,6 +187,9 @@ pub struct EntrySearchRatings {
     pub renewable: AvgRatingValue,
     pub solidarity: AvgRatingValue,
     pub transparency: AvgRatingValue,
+    // The number of ratings in all contexts
+    #[serde(default)]
+    pub count: u64,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -154,6 +207,73 @@ pub struct Category {
     pub name: String,
 }
 
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq))]
+pub struct CuratedTag {
+    pub tag: String,
+    pub boost: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq))]
+pub struct CuratedOrganization {
+    pub org_id: String,
+    pub boost: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct RenameTag {
+    pub tag: String,
+    pub new_tag: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct MergeTags {
+    pub tags: Vec<String>,
+    pub into: String,
+}
+
+/// Number of places and events that contain(ed) a deleted tag
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq))]
+pub struct DeletedTag {
+    pub places: usize,
+    pub events: usize,
+}
+
+/// An archived entry that has been merged into the primary entry
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct MergedEntry {
+    pub merged_into: String,
+}
+
+/// Merge a duplicate into the primary place
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct MergePlaces {
+    pub primary: String,
+    pub duplicate: String,
+}
+
+/// An organization that owns a tag
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq))]
+pub struct TagOwner {
+    pub id: String,
+    pub name: String,
+}
+
+/// A tag together with all organizations that own it
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq))]
+pub struct OwnedTag {
+    pub tag: String,
+    pub owners: Vec<TagOwner>,
+}
+
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
 pub struct PlaceSearchResult {
@@ -166,6 +286,20 @@ pub struct PlaceSearchResult {
     pub categories: Vec<String>,
     pub tags: Vec<String>,
     pub ratings: EntrySearchRatings,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippets: Option<SearchSnippets>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_locations: Vec<Coordinate>,
+}
+
+/// HTML fragments with the matching terms enclosed in `<b>` tags
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq))]
+pub struct SearchSnippets {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -181,11 +315,59 @@ pub enum ReviewStatus {
     Rejected,
 }
 
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "extra-derive",
+    derive(Debug, Clone, Copy, PartialEq, Eq, Hash)
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportReason {
+    Closed,
+    Fraud,
+    Duplicate,
+    Inappropriate,
+    Inaccurate,
+    Spam,
+    Offensive,
+    Other,
+}
+
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
 pub struct SearchResponse {
     pub visible: Vec<PlaceSearchResult>,
     pub invisible: Vec<PlaceSearchResult>,
+    /// Continuation token for requesting the next page of visible results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// Completions for type-ahead search boxes
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct SearchSuggestions {
+    pub tags: Vec<String>,
+    pub titles: Vec<String>,
+}
+
+/// Multiple places that are rendered as a single marker
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct PlaceCluster {
+    pub lat: f64,
+    pub lng: f64,
+    pub count: usize,
+}
+
+/// A GeoJSON feature collection, see [RFC 7946](https://tools.ietf.org/html/rfc7946)
+/// Results of a global search grouped by their type
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+#[serde(tag = "type", content = "results", rename_all = "snake_case")]
+pub enum GlobalSearchResults {
+    Place(Vec<PlaceSearchResult>),
+    Event(Vec<Event>),
+    Tag(Vec<String>),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -224,6 +406,18 @@ pub struct ResetPassword {
     pub new_password: String,
 }
 
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct RequestLoginLink {
+    pub email: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct LoginWithToken {
+    pub token: String,
+}
+
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
 pub struct TagFrequency(pub String, pub u64);
@@ -240,6 +434,14 @@ pub struct Rating {
     pub source: String,
 }
 
+/// Changes of an existing rating by its author
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct RatingUpdate {
+    pub title: String,
+    pub value: RatingValue,
+}
+
 impl From<e::category::Category> for Category {
     fn from(from: e::category::Category) -> Self {
         let name = from.name();
@@ -250,6 +452,107 @@ impl From<e::category::Category> for Category {
     }
 }
 
+impl From<e::tag::CuratedTag> for CuratedTag {
+    fn from(from: e::tag::CuratedTag) -> Self {
+        let e::tag::CuratedTag { tag, boost } = from;
+        Self { tag, boost }
+    }
+}
+
+impl From<e::organization::CuratedOrganization> for CuratedOrganization {
+    fn from(from: e::organization::CuratedOrganization) -> Self {
+        let e::organization::CuratedOrganization { org_id, boost } = from;
+        Self { org_id, boost }
+    }
+}
+
+impl From<e::rating::AvgRatings> for EntrySearchRatings {
+    fn from(from: e::rating::AvgRatings) -> Self {
+        let total = from.total().into();
+        let e::rating::AvgRatings {
+            diversity,
+            fairness,
+            humanity,
+            renewable,
+            solidarity,
+            transparency,
+            count,
+        } = from;
+        Self {
+            total,
+            diversity: diversity.into(),
+            fairness: fairness.into(),
+            humanity: humanity.into(),
+            renewable: renewable.into(),
+            solidarity: solidarity.into(),
+            transparency: transparency.into(),
+            count,
+        }
+    }
+}
+
+impl From<e::rating::MonthlyAvgRatings> for MonthlyRatings {
+    fn from(from: e::rating::MonthlyAvgRatings) -> Self {
+        let e::rating::MonthlyAvgRatings {
+            year,
+            month,
+            ratings,
+        } = from;
+        Self {
+            month: format!("{:04}-{:02}", year, month),
+            ratings: ratings.into(),
+        }
+    }
+}
+
+impl From<e::organization::TagOwnership> for TagOwner {
+    fn from(from: e::organization::TagOwnership) -> Self {
+        let e::organization::TagOwnership {
+            org_id, org_name, ..
+        } = from;
+        Self {
+            id: org_id,
+            name: org_name,
+        }
+    }
+}
+
+impl From<e::location::Location> for EntryLocation {
+    fn from(from: e::location::Location) -> Self {
+        let e::location::Location { pos, address } = from;
+        let e::address::Address {
+            street,
+            zip,
+            city,
+            country,
+            state,
+        } = address.unwrap_or_default();
+        Self {
+            lat: pos.lat().to_deg(),
+            lng: pos.lng().to_deg(),
+            street,
+            zip,
+            city,
+            country,
+            state,
+        }
+    }
+}
+
+impl From<e::place::PlaceSource> for PlaceSource {
+    fn from(from: e::place::PlaceSource) -> Self {
+        let e::place::PlaceSource {
+            external_id,
+            imported_at,
+        } = from;
+        Self {
+            importer: external_id.source,
+            external_ref: external_id.id,
+            imported_at: imported_at.into_seconds(),
+        }
+    }
+}
+
 impl From<e::review::ReviewStatus> for ReviewStatus {
     fn from(from: e::review::ReviewStatus) -> Self {
         use e::review::ReviewStatus::*;
@@ -274,6 +577,40 @@ impl From<ReviewStatus> for e::review::ReviewStatus {
     }
 }
 
+impl From<e::report::ReportReason> for ReportReason {
+    fn from(from: e::report::ReportReason) -> Self {
+        use e::report::ReportReason as E;
+        use ReportReason as C;
+        match from {
+            E::Closed => C::Closed,
+            E::Fraud => C::Fraud,
+            E::Duplicate => C::Duplicate,
+            E::Inappropriate => C::Inappropriate,
+            E::Inaccurate => C::Inaccurate,
+            E::Spam => C::Spam,
+            E::Offensive => C::Offensive,
+            E::Other => C::Other,
+        }
+    }
+}
+
+impl From<ReportReason> for e::report::ReportReason {
+    fn from(from: ReportReason) -> Self {
+        use e::report::ReportReason as E;
+        use ReportReason as C;
+        match from {
+            C::Closed => E::Closed,
+            C::Fraud => E::Fraud,
+            C::Duplicate => E::Duplicate,
+            C::Inappropriate => E::Inappropriate,
+            C::Inaccurate => E::Inaccurate,
+            C::Spam => E::Spam,
+            C::Offensive => E::Offensive,
+            C::Other => E::Other,
+        }
+    }
+}
+
 impl From<e::user::User> for User {
     fn from(from: e::user::User) -> Self {
         let e::user::User {
@@ -281,11 +618,13 @@ impl From<e::user::User> for User {
             email_confirmed,
             role,
             password: _password,
+            language,
         } = from;
         Self {
             email,
             email_confirmed,
             role: role.into(),
+            language,
         }
     }
 }
@@ -392,6 +731,8 @@ impl From<e::event::Event> for Event {
             organizer,
             image_url,
             image_link_url,
+            recurrence,
+            capacity,
             ..
         } = e;
 
@@ -429,6 +770,16 @@ impl From<e::event::Event> for Event {
             .to_string()
         });
 
+        let recurrence = recurrence.map(|r| EventRecurrence {
+            frequency: match r.frequency {
+                e::event::RecurrenceFrequency::Weekly => "weekly",
+                e::event::RecurrenceFrequency::Monthly => "monthly",
+            }
+            .to_string(),
+            interval: r.interval,
+            until: r.until.map(|until| until.timestamp()),
+        });
+
         let start = start.timestamp();
         let end = end.map(|end| end.timestamp());
 
@@ -453,6 +804,8 @@ impl From<e::event::Event> for Event {
             organizer,
             image_url: image_url.map(Url::into_string),
             image_link_url: image_link_url.map(Url::into_string),
+            recurrence,
+            capacity,
         }
     }
 }
@@ -1,4 +1,7 @@
-use ofdb_entities::{address::*, category::*, email::*, event::*, nonce::*, place::*, user::*};
+use ofdb_entities::{
+    address::*, category::*, email::*, event::*, moderation::*, nonce::*, place::*, report::*,
+    subscription::*, user::*, webhook::*,
+};
 
 pub mod util;
 
@@ -6,6 +9,16 @@ pub trait EmailGateway {
     fn compose_and_send(&self, recipients: &[Email], subject: &str, body: &str);
 }
 
+pub trait EmailTransport {
+    // Sends a single e-mail synchronously and fails if it
+    // could not be handed over to the provider
+    fn send_email(&self, recipient: &Email, subject: &str, body: &str) -> std::io::Result<()>;
+}
+
+/// Informs users and operators about changes.
+///
+/// The lists of e-mail addresses might be empty, because
+/// not every gateway addresses individual users.
 pub trait NotificationGateway {
     fn place_added(&self, email_addresses: &[String], place: &Place, all_categories: Vec<Category>);
     fn place_updated(
@@ -16,12 +29,48 @@ pub trait NotificationGateway {
     );
     fn event_created(&self, email_addresses: &[String], event: &Event);
     fn event_updated(&self, email_addresses: &[String], event: &Event);
+    fn place_archived(&self, email_addresses: &[String], place: &Place, comment: Option<&str>);
+    fn event_archived(&self, email_addresses: &[String], event: &Event, comment: Option<&str>);
     fn user_registered_kvm(&self, user: &User);
     fn user_registered_ofdb(&self, user: &User);
     fn user_registered(&self, user: &User, url: &str);
     fn user_reset_password_requested(&self, email_nonce: &EmailNonce);
+    fn user_login_link_requested(&self, email_nonce: &EmailNonce);
+    fn place_contact_verification_requested(&self, place: &Place, email_nonce: &EmailNonce);
+    fn scout_report_created(&self, email: &str, report: &ScoutReport);
+    fn notification_digest_created(&self, email: &str, digest: &NotificationDigest);
+    fn event_registration_created(&self, event: &Event, registration: &EventRegistration);
+    fn event_registration_promoted(&self, event: &Event, registration: &EventRegistration);
+    fn content_reported(&self, email_addresses: &[String], report: &Report, title: &str);
+}
+
+pub trait ObjectStorageGateway {
+    fn put_object(&self, key: &str, content: Vec<u8>) -> std::io::Result<()>;
+    // The keys of all objects that start with the prefix in ascending order
+    fn list_objects(&self, prefix: &str) -> std::io::Result<Vec<String>>;
+    fn delete_object(&self, key: &str) -> std::io::Result<()>;
+}
+
+pub trait CaptchaGateway {
+    // Verifies the response token of a captcha that has been
+    // solved by the client with the given address
+    fn verify_captcha(
+        &self,
+        response: &str,
+        client_addr: Option<std::net::IpAddr>,
+    ) -> std::io::Result<bool>;
 }
 
 pub trait GeoCodingGateway {
     fn resolve_address_lat_lng(&self, addr: &Address) -> Option<(f64, f64)>;
 }
+
+pub trait WebhookGateway {
+    // Returns the status code of the HTTP response that
+    // might also indicate a failure
+    fn deliver_webhook(
+        &self,
+        webhook: &Webhook,
+        delivery: &WebhookDelivery,
+    ) -> std::io::Result<u16>;
+}
@@ -46,6 +46,10 @@ pub trait InBBox {
 impl InBBox for Place {
     fn in_bbox(&self, bbox: &MapBbox) -> bool {
         bbox.contains_point(self.location.pos)
+            || self
+                .additional_locations
+                .iter()
+                .any(|l| bbox.contains_point(l.pos))
     }
 }
 
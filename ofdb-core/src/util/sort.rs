@@ -33,7 +33,8 @@ pub mod tests {
         Rating {
             id: id.into(),
             place_id: place_id.into(),
-            created_at: Timestamp::now(),
+            created_at: TimestampMs::now(),
+            created_by: None,
             archived_at: None,
             title: "blubb".into(),
             value: value.into(),
@@ -112,7 +113,8 @@ pub mod tests {
             .map(|_| Rating {
                 id: Id::new(),
                 place_id: place_id.into(),
-                created_at: Timestamp::now(),
+                created_at: TimestampMs::now(),
+                created_by: None,
                 archived_at: None,
                 title: "".into(),
                 value: 2.into(),
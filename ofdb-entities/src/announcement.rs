@@ -0,0 +1,44 @@
+use crate::{id::*, time::*};
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::*;
+
+pub type AnnouncementSeverityPrimitive = i16;
+
+/// How prominently an announcement is displayed.
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, FromPrimitive, ToPrimitive)]
+pub enum AnnouncementSeverity {
+    Info     = 0,
+    Warning  = 1,
+    Critical = 2,
+}
+
+impl AnnouncementSeverity {
+    pub fn try_from(from: AnnouncementSeverityPrimitive) -> Option<Self> {
+        Self::from_i16(from)
+    }
+}
+
+impl From<AnnouncementSeverity> for AnnouncementSeverityPrimitive {
+    fn from(from: AnnouncementSeverity) -> Self {
+        from.to_i16().unwrap()
+    }
+}
+
+/// A message for all visitors, e.g. about an upcoming
+/// maintenance window or a campaign.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Announcement {
+    pub id: Id,
+    pub message: String,
+    pub severity: AnnouncementSeverity,
+    pub starts_at: TimestampMs,
+    /// Displayed until deleted if missing
+    pub ends_at: Option<TimestampMs>,
+}
+
+impl Announcement {
+    pub fn is_displayed_at(&self, at: TimestampMs) -> bool {
+        self.starts_at <= at && self.ends_at.map(|ends_at| at < ends_at).unwrap_or(true)
+    }
+}
@@ -0,0 +1,54 @@
+use crate::activity::*;
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::*;
+
+pub type AuditActionPrimitive = i16;
+
+/// An action of an admin or scout that is recorded
+/// in the audit log.
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum AuditAction {
+    ReviewPlace          =  0,
+    RestorePlaceRevision =  1,
+    ArchiveEvent         =  2,
+    ArchiveRating        =  3,
+    ArchiveComment       =  4,
+    ChangeUserRole       =  5,
+    DeactivateUser       =  6,
+    ReactivateUser       =  7,
+    SaveCuratedTag       =  8,
+    DeleteCuratedTag     =  9,
+    RenameTag            = 10,
+    MergeTags            = 11,
+    DeleteTag            = 12,
+    MergePlaces          = 13,
+    SaveCuratedOrg       = 14,
+    DeleteCuratedOrg     = 15,
+}
+
+impl AuditAction {
+    pub fn try_from(from: AuditActionPrimitive) -> Option<Self> {
+        Self::from_i16(from)
+    }
+}
+
+impl From<AuditAction> for AuditActionPrimitive {
+    fn from(from: AuditAction) -> Self {
+        from.to_i16().unwrap()
+    }
+}
+
+/// Who did what and when.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditLogEntry {
+    /// The context contains the outcome of the action,
+    /// e.g. the new review status of a place or the new
+    /// role of a user.
+    pub activity: ActivityLog,
+    pub action: AuditAction,
+    /// The id of the affected place, event, rating, or
+    /// comment, the e-mail address of the affected user,
+    /// the affected tag, or the id of the affected organization
+    pub item: String,
+}
@@ -41,38 +41,71 @@ impl Category {
             tag: Self::TAG_EVENT.into(),
         }
     }
+}
+
+/// The categories of a deployment.
+///
+/// Each category is mapped to a predefined tag that is
+/// stored together with the other tags of a place.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Categories(Vec<Category>);
+
+impl Default for Categories {
+    fn default() -> Self {
+        Self(vec![
+            Category::new_non_profit(),
+            Category::new_commercial(),
+            Category::new_event(),
+        ])
+    }
+}
+
+impl From<Vec<Category>> for Categories {
+    fn from(from: Vec<Category>) -> Self {
+        Self(from)
+    }
+}
+
+impl From<Categories> for Vec<Category> {
+    fn from(from: Categories) -> Self {
+        from.0
+    }
+}
+
+impl Categories {
+    pub fn iter(&self) -> impl Iterator<Item = &Category> {
+        self.0.iter()
+    }
 
-    pub fn split_from_tags(tags: Vec<String>) -> (Vec<String>, Vec<Category>) {
-        let mut categories = Vec::with_capacity(3);
+    pub fn find_by_id(&self, id: &str) -> Option<&Category> {
+        self.0.iter().find(|c| c.id.as_str() == id)
+    }
+
+    pub fn find_by_tag(&self, tag: &str) -> Option<&Category> {
+        self.0.iter().find(|c| c.tag == tag)
+    }
+
+    pub fn split_from_tags(&self, tags: Vec<String>) -> (Vec<String>, Vec<Category>) {
+        let mut categories = Vec::with_capacity(self.0.len());
         let tags = tags
             .into_iter()
-            .filter(|t| match t.as_str() {
-                Self::TAG_NON_PROFIT => {
-                    categories.push(Self::new_non_profit());
-                    false
-                }
-                Self::TAG_COMMERCIAL => {
-                    categories.push(Self::new_commercial());
-                    false
-                }
-                Self::TAG_EVENT => {
-                    categories.push(Self::new_event());
+            .filter(|t| {
+                if let Some(category) = self.find_by_tag(t) {
+                    categories.push(category.clone());
                     false
+                } else {
+                    true
                 }
-                _ => true,
             })
             .collect();
         (tags, categories)
     }
 
-    pub fn merge_ids_into_tags(ids: &[Id], mut tags: Vec<String>) -> Vec<String> {
+    pub fn merge_ids_into_tags(&self, ids: &[Id], mut tags: Vec<String>) -> Vec<String> {
         tags.reserve(ids.len());
         tags = ids.iter().fold(tags, |mut tags, id| {
-            match id.as_ref() {
-                Self::ID_NON_PROFIT => tags.push(Self::TAG_NON_PROFIT.into()),
-                Self::ID_COMMERCIAL => tags.push(Self::TAG_COMMERCIAL.into()),
-                Self::ID_EVENT => tags.push(Self::TAG_EVENT.into()),
-                _ => (),
+            if let Some(category) = self.find_by_id(id.as_ref()) {
+                tags.push(category.tag.clone());
             }
             tags
         });
@@ -81,3 +114,47 @@ impl Category {
         tags
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_and_merge_custom_categories() {
+        let categories = Categories::from(vec![
+            Category {
+                id: "a".into(),
+                tag: "cat-a".into(),
+            },
+            Category {
+                id: "b".into(),
+                tag: "cat-b".into(),
+            },
+        ]);
+        let tags = categories.merge_ids_into_tags(
+            &["b".into(), "unknown".into()],
+            vec!["foo".into(), Category::TAG_NON_PROFIT.into()],
+        );
+        assert_eq!(
+            vec!["cat-b", "foo", Category::TAG_NON_PROFIT],
+            tags.iter().map(String::as_str).collect::<Vec<_>>()
+        );
+        let (tags, found) = categories.split_from_tags(tags);
+        assert_eq!(vec!["foo", Category::TAG_NON_PROFIT], tags);
+        assert_eq!(vec![categories.find_by_id("b").unwrap().clone()], found);
+    }
+
+    #[test]
+    fn default_categories() {
+        let categories = Categories::default();
+        let (tags, found) = categories.split_from_tags(vec![
+            Category::TAG_COMMERCIAL.into(),
+            Category::TAG_EVENT.into(),
+        ]);
+        assert!(tags.is_empty());
+        assert_eq!(
+            vec![Category::new_commercial(), Category::new_event()],
+            found
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{id::*, revision::*, time::*};
+
+/// Edits of a place with tags that are owned by an organization
+/// that have not been cleared by this organization yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingClearanceForPlace {
+    pub place_id: Id,
+    /// The time of the first edit that is still pending
+    pub created_at: TimestampMs,
+    /// Missing if the place has been created by someone else
+    pub last_cleared_revision: Option<Revision>,
+}
+
+/// Clears all pending edits of a place.
+///
+/// If the cleared revision is not the current revision then
+/// its contents are restored, i.e. the pending edits are
+/// overridden.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClearanceForPlace {
+    pub place_id: Id,
+    pub cleared_revision: Revision,
+}
@@ -5,8 +5,7 @@ use crate::{id::*, time::*};
 pub struct Comment {
     pub id          : Id,
     pub rating_id   : Id,
-    // TODO: Convert time stamps from second to millisecond precision?
-    pub created_at  : Timestamp,
-    pub archived_at : Option<Timestamp>,
+    pub created_at  : TimestampMs,
+    pub archived_at : Option<TimestampMs>,
     pub text        : String,
 }
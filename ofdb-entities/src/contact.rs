@@ -1,4 +1,4 @@
-use crate::email::Email;
+use crate::{email::Email, id::Id, nonce::EmailNonce, time::*};
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Contact {
@@ -12,6 +12,17 @@ impl Contact {
     }
 }
 
+/// A pending or completed verification of the
+/// contact e-mail address of a place.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContactVerification {
+    pub place_id: Id,
+    pub email_nonce: EmailNonce,
+    pub expires_at: Timestamp,
+    // Pending until the confirmation link has been followed
+    pub verified_at: Option<TimestampMs>,
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -0,0 +1,13 @@
+use crate::{id::*, time::*};
+
+/// An incomplete submission of a new place that
+/// is saved by its author to be continued later.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaceDraft {
+    pub id: Id,
+    pub owner_email: String,
+    pub created_at: TimestampMs,
+    pub updated_at: TimestampMs,
+    // The unvalidated form data as serialized JSON
+    pub data: String,
+}
@@ -1,3 +1,5 @@
+use crate::{id::*, time::*};
+use num_derive::{FromPrimitive, ToPrimitive};
 use std::{borrow::Borrow, fmt, ops::Deref, str::FromStr};
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -59,3 +61,30 @@ impl fmt::Display for Email {
         f.write_str(self.as_ref())
     }
 }
+
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum OutgoingEmailStatus {
+    Pending = 0,
+    Sent    = 1,
+    /// All attempts failed
+    Failed  = 2,
+}
+
+/// An e-mail to a single recipient in the outbox that is
+/// retried until it has been sent or too many attempts failed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutgoingEmail {
+    pub id: Id,
+    pub recipient: Email,
+    pub subject: String,
+    pub body: String,
+    pub created_at: TimestampMs,
+    pub status: OutgoingEmailStatus,
+    pub attempts: u32,
+    pub last_attempt_at: Option<TimestampMs>,
+    /// Only present while the e-mail is pending
+    pub next_attempt_at: Option<TimestampMs>,
+    /// The reason why the last attempt failed
+    pub last_error: Option<String>,
+}
@@ -1,5 +1,6 @@
-use crate::{contact::*, id::*, location::*, time::*};
-use chrono::prelude::*;
+use crate::{contact::*, id::*, location::*, nonce::*, time::*};
+use chrono::{naive::MAX_DATE, prelude::*, Duration};
+use num_derive::{FromPrimitive, ToPrimitive};
 use std::str::FromStr;
 use url::Url;
 
@@ -25,6 +26,62 @@ impl FromStr for RegistrationType {
     }
 }
 
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum RecurrenceFrequency {
+    Weekly  = 0,
+    Monthly = 1,
+}
+
+/// The repetition of an event, similar to but much simpler
+/// than a recurrence rule (RRULE) in iCalendar.
+///
+/// Monthly events are repeated on the same day of the month
+/// as the first occurrence. Months without this day are
+/// skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    pub frequency: RecurrenceFrequency,
+    /// Repeat every n-th week or month
+    pub interval: u16,
+    /// The last possible start of an occurrence (inclusive)
+    pub until: Option<NaiveDateTime>,
+}
+
+impl Recurrence {
+    /// The starts of all occurrences in chronological order,
+    /// beginning with the first start. Unlimited if no `until`
+    /// has been specified.
+    pub fn starts(&self, first: NaiveDateTime) -> impl Iterator<Item = NaiveDateTime> + '_ {
+        let interval = i64::from(self.interval.max(1));
+        (0..)
+            .map(move |n| match self.frequency {
+                RecurrenceFrequency::Weekly => first
+                    .checked_add_signed(Duration::weeks(n * interval))
+                    .map(Some),
+                RecurrenceFrequency::Monthly => add_months(first, n * interval),
+            })
+            // Stop when exceeding the range of dates
+            .take_while(Option::is_some)
+            .filter_map(Option::flatten)
+            .take_while(move |start| self.until.map(|until| *start <= until).unwrap_or(true))
+    }
+}
+
+// Returns `None` if the date is out of range and `Some(None)`
+// if the month doesn't have the same day.
+fn add_months(first: NaiveDateTime, months: i64) -> Option<Option<NaiveDateTime>> {
+    let month0 = i64::from(first.month0()) + months;
+    let year = i64::from(first.year()) + month0 / 12;
+    if year > i64::from(MAX_DATE.year()) {
+        return None;
+    }
+    Some(
+        NaiveDate::from_ymd_opt(year as i32, (month0 % 12) as u32 + 1, first.day())
+            .map(|date| date.and_time(first.time())),
+    )
+}
+
 #[rustfmt::skip]
 #[derive(Debug, Clone, PartialEq)]
 pub struct Event {
@@ -41,10 +98,12 @@ pub struct Event {
     pub created_by   : Option<String>,
     pub registration : Option<RegistrationType>,
     pub organizer    : Option<String>,
-    // TODO: Switch archived time stamp to millisecond precision?
-    pub archived     : Option<Timestamp>,
+    pub archived     : Option<TimestampMs>,
     pub image_url     : Option<Url>,
     pub image_link_url: Option<Url>,
+    pub recurrence    : Option<Recurrence>,
+    // Maximum number of confirmed registrations
+    pub capacity      : Option<u32>,
 }
 
 impl Event {
@@ -62,6 +121,45 @@ impl Event {
         }
     }
 
+    /// All occurrences that start within the given time window,
+    /// i.e. copies of the event with a different start and end.
+    ///
+    /// The number of occurrences of recurring events is limited.
+    /// Events without a recurrence occur only once.
+    pub fn occurrences(
+        &self,
+        start_min: Option<NaiveDateTime>,
+        start_max: Option<NaiveDateTime>,
+        limit: usize,
+    ) -> Vec<Event> {
+        let in_window = |start: &NaiveDateTime| {
+            start_min.map(|min| *start >= min).unwrap_or(true)
+                && start_max.map(|max| *start <= max).unwrap_or(true)
+        };
+        let recurrence = match self.recurrence {
+            Some(ref recurrence) => recurrence,
+            None => {
+                return if in_window(&self.start) {
+                    vec![self.clone()]
+                } else {
+                    vec![]
+                };
+            }
+        };
+        let duration = self.end.map(|end| end - self.start);
+        recurrence
+            .starts(self.start)
+            .take_while(|start| start_max.map(|max| *start <= max).unwrap_or(true))
+            .filter(in_window)
+            .take(limit)
+            .map(|start| Event {
+                start,
+                end: duration.map(|duration| start + duration),
+                ..self.clone()
+            })
+            .collect()
+    }
+
     pub fn is_owned<'a>(&self, owned_tags: impl IntoIterator<Item = &'a str>) -> bool {
         // Exclusive ownership of events is determined by the associated tags
         owned_tags
@@ -70,9 +168,131 @@ impl Event {
     }
 }
 
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum EventRegistrationStatus {
+    Confirmed  = 0,
+    // Waiting for a confirmed registration to be cancelled
+    Waitlisted = 1,
+    Cancelled  = 2,
+}
+
+/// The registration of an attendee for an event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventRegistration {
+    pub event_id: Id,
+    // The e-mail address of the attendee together with
+    // the secret for cancelling the registration
+    pub email_nonce: EmailNonce,
+    pub status: EventRegistrationStatus,
+    pub registered_at: TimestampMs,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    fn datetime(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn weekly_recurrence_until() {
+        let recurrence = Recurrence {
+            frequency: RecurrenceFrequency::Weekly,
+            interval: 2,
+            until: Some(datetime("2020-07-15 18:00")),
+        };
+        assert_eq!(
+            vec![
+                datetime("2020-06-17 18:00"),
+                datetime("2020-07-01 18:00"),
+                datetime("2020-07-15 18:00"),
+            ],
+            recurrence
+                .starts(datetime("2020-06-17 18:00"))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn monthly_recurrence_skips_months_without_day() {
+        let recurrence = Recurrence {
+            frequency: RecurrenceFrequency::Monthly,
+            interval: 1,
+            until: None,
+        };
+        assert_eq!(
+            vec![
+                datetime("2020-01-31 10:00"),
+                datetime("2020-03-31 10:00"),
+                datetime("2020-05-31 10:00"),
+            ],
+            recurrence
+                .starts(datetime("2020-01-31 10:00"))
+                .take(3)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn occurrences_within_time_window() {
+        let event = Event {
+            id: "foo".into(),
+            title: "Meetup".into(),
+            description: None,
+            start: datetime("2020-06-03 19:00"),
+            end: Some(datetime("2020-06-03 21:00")),
+            location: None,
+            contact: None,
+            tags: vec![],
+            homepage: None,
+            created_by: None,
+            registration: None,
+            organizer: None,
+            archived: None,
+            image_url: None,
+            image_link_url: None,
+            recurrence: Some(Recurrence {
+                frequency: RecurrenceFrequency::Weekly,
+                interval: 1,
+                until: None,
+            }),
+            capacity: None,
+        };
+        let occurrences = event.occurrences(
+            Some(datetime("2020-06-10 00:00")),
+            Some(datetime("2020-06-30 00:00")),
+            100,
+        );
+        assert_eq!(
+            vec![
+                (datetime("2020-06-10 19:00"), datetime("2020-06-10 21:00")),
+                (datetime("2020-06-17 19:00"), datetime("2020-06-17 21:00")),
+                (datetime("2020-06-24 19:00"), datetime("2020-06-24 21:00")),
+            ],
+            occurrences
+                .iter()
+                .map(|e| (e.start, e.end.unwrap()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            1,
+            event
+                .occurrences(Some(datetime("2020-06-10 00:00")), None, 1)
+                .len()
+        );
+
+        let single = Event {
+            recurrence: None,
+            capacity: None,
+            ..event
+        };
+        assert!(single
+            .occurrences(Some(datetime("2020-06-10 00:00")), None, 100)
+            .is_empty());
+        assert_eq!(1, single.occurrences(None, None, 100).len());
+    }
     #[test]
     fn registration_type_from_str() {
         assert_eq!(
@@ -385,6 +385,16 @@ impl MapPoint {
     }
 }
 
+fn wrap_lng_deg(lng_deg: f64) -> f64 {
+    if lng_deg < LngCoord::min().to_deg() {
+        lng_deg + 360.0
+    } else if lng_deg > LngCoord::max().to_deg() {
+        lng_deg - 360.0
+    } else {
+        lng_deg
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct MapBbox {
     sw: MapPoint,
@@ -414,6 +424,58 @@ impl MapBbox {
         self.sw.lat() >= self.ne.lat() || self.sw.lng() == self.ne.lng()
     }
 
+    /// The smallest bounding box that encloses all points
+    /// within the given distance around a center point.
+    /// Reference: http://janmatuschek.de/LatitudeLongitudeBoundingCoordinates
+    pub fn centered_around(center: MapPoint, radius: Distance) -> Self {
+        debug_assert!(center.is_valid());
+        debug_assert!(radius.is_valid());
+        let (lat_rad, lng_rad) = center.to_lat_lng_rad();
+        let angular_radius = radius.to_meters() / MEAN_EARTH_RADIUS.to_meters();
+        let min_lat_rad = LatCoord::min().to_rad();
+        let max_lat_rad = LatCoord::max().to_rad();
+        let sw_lat_rad = (lat_rad - angular_radius).max(min_lat_rad);
+        let ne_lat_rad = (lat_rad + angular_radius).min(max_lat_rad);
+        let (sw_lng_deg, ne_lng_deg) = {
+            let delta_lng_sin = angular_radius.sin() / lat_rad.cos();
+            if sw_lat_rad <= min_lat_rad
+                || ne_lat_rad >= max_lat_rad
+                || angular_radius >= std::f64::consts::FRAC_PI_2
+                || delta_lng_sin >= 1.0
+            {
+                // A pole is included or the circle covers all longitudes
+                (LngCoord::min().to_deg(), LngCoord::max().to_deg())
+            } else {
+                let delta_lng_rad = delta_lng_sin.asin();
+                (
+                    wrap_lng_deg((lng_rad - delta_lng_rad).to_degrees()),
+                    wrap_lng_deg((lng_rad + delta_lng_rad).to_degrees()),
+                )
+            }
+        };
+        // Clamp the latitudes to compensate for rounding errors
+        let sw_lat_deg = sw_lat_rad.to_degrees().max(LatCoord::min().to_deg());
+        let ne_lat_deg = ne_lat_rad.to_degrees().min(LatCoord::max().to_deg());
+        Self::new(
+            MapPoint::from_lat_lng_deg(sw_lat_deg, sw_lng_deg),
+            MapPoint::from_lat_lng_deg(ne_lat_deg, ne_lng_deg),
+        )
+    }
+
+    /// The center of the bounding box, also if it crosses
+    /// the antimeridian.
+    pub fn center(&self) -> MapPoint {
+        debug_assert!(self.is_valid());
+        let lat_deg = (self.sw.lat().to_deg() + self.ne.lat().to_deg()) / 2.0;
+        let sw_lng_deg = self.sw.lng().to_deg();
+        let ne_lng_deg = if self.sw.lng() <= self.ne.lng() {
+            self.ne.lng().to_deg()
+        } else {
+            self.ne.lng().to_deg() + 360.0
+        };
+        MapPoint::from_lat_lng_deg(lat_deg, wrap_lng_deg((sw_lng_deg + ne_lng_deg) / 2.0))
+    }
+
     pub fn contains_point(&self, pt: MapPoint) -> bool {
         debug_assert!(self.is_valid());
         debug_assert!(pt.is_valid());
@@ -673,6 +735,55 @@ mod tests {
         assert!(bbox4.contains_point(MapPoint::from_lat_lng_deg(lat4, lng4)));
     }
 
+    #[test]
+    fn bbox_centered_around_point() {
+        let center = MapPoint::from_lat_lng_deg(48.7755, 9.1827);
+        let radius = Distance::from_meters(10_000.0);
+        let bbox = MapBbox::centered_around(center, radius);
+        assert!(bbox.is_valid());
+        assert!(bbox.contains_point(center));
+        let north = MapPoint::from_lat_lng_deg(48.8645, 9.1827);
+        assert!(MapPoint::distance(center, north).unwrap().to_meters() < radius.to_meters());
+        assert!(bbox.contains_point(north));
+        let east = MapPoint::from_lat_lng_deg(48.7755, 9.3177);
+        assert!(MapPoint::distance(center, east).unwrap().to_meters() < radius.to_meters());
+        assert!(bbox.contains_point(east));
+        assert!(!bbox.contains_point(MapPoint::from_lat_lng_deg(48.9, 9.1827)));
+        assert!(!bbox.contains_point(MapPoint::from_lat_lng_deg(48.7755, 9.35)));
+
+        // Across the antimeridian
+        let bbox = MapBbox::centered_around(MapPoint::from_lat_lng_deg(0.0, 179.99), radius);
+        assert!(bbox.is_valid());
+        assert!(bbox.south_west().lng() > bbox.north_east().lng());
+        assert!(bbox.contains_point(MapPoint::from_lat_lng_deg(0.0, -179.99)));
+
+        // Including a pole
+        let bbox = MapBbox::centered_around(MapPoint::from_lat_lng_deg(89.99, 0.0), radius);
+        assert!(bbox.is_valid());
+        assert_eq!(LatCoord::max(), bbox.north_east().lat());
+        assert!(bbox.contains_point(MapPoint::from_lat_lng_deg(89.99, 180.0)));
+    }
+
+    #[test]
+    fn bbox_center() {
+        let bbox = MapBbox::new(
+            MapPoint::from_lat_lng_deg(48.0, 9.0),
+            MapPoint::from_lat_lng_deg(49.0, 10.0),
+        );
+        let center = MapPoint::from_lat_lng_deg(48.5, 9.5);
+        let distance = MapPoint::distance(center, bbox.center()).unwrap();
+        assert!(distance.to_meters() < 1.0);
+
+        // Across the antimeridian
+        let bbox = MapBbox::new(
+            MapPoint::from_lat_lng_deg(-1.0, 179.0),
+            MapPoint::from_lat_lng_deg(1.0, -177.0),
+        );
+        let center = MapPoint::from_lat_lng_deg(0.0, -179.0);
+        let distance = MapPoint::distance(center, bbox.center()).unwrap();
+        assert!(distance.to_meters() < 1.0);
+    }
+
     // ---- BENCHMARKS ---- //
     //
     // To run the benchmarks you need Rust nightly.
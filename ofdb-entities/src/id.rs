@@ -73,3 +73,102 @@ impl fmt::Display for Id {
         f.write_str(self.as_ref())
     }
 }
+
+// Identifiers of distinct entities that must not be mixed up,
+// e.g. when passing the id of a rating into a place lookup.
+macro_rules! typed_id {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Default, Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+        pub struct $name(Id);
+
+        impl $name {
+            pub fn new() -> Self {
+                Self(Id::new())
+            }
+
+            pub fn is_valid(&self) -> bool {
+                self.0.is_valid()
+            }
+
+            pub fn as_str(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl From<Id> for $name {
+            fn from(from: Id) -> Self {
+                Self(from)
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(from: String) -> Self {
+                Id::from(from).into()
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(from: &str) -> Self {
+                Id::from(from).into()
+            }
+        }
+
+        impl From<$name> for Id {
+            fn from(from: $name) -> Self {
+                from.0
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(from: $name) -> Self {
+                from.0.into()
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ();
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok(s.into())
+            }
+        }
+
+        impl Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+                self.0.fmt(f)
+            }
+        }
+    };
+}
+
+typed_id!(
+    /// Identifier of a place.
+    PlaceId
+);
+
+typed_id!(
+    /// Identifier of an event.
+    EventId
+);
+
+typed_id!(
+    /// Identifier of a rating of a place.
+    RatingId
+);
+
+typed_id!(
+    /// Identifier of a comment on a rating.
+    CommentId
+);
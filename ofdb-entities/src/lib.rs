@@ -1,22 +1,29 @@
 pub mod activity;
 pub mod address;
+pub mod announcement;
+pub mod audit;
 pub mod category;
+pub mod clearance;
 pub mod comment;
 pub mod contact;
+pub mod draft;
 pub mod email;
 pub mod event;
 pub mod geo;
 pub mod id;
 pub mod links;
 pub mod location;
+pub mod moderation;
 pub mod nonce;
 pub mod organization;
 pub mod password;
 pub mod place;
 pub mod rating;
+pub mod report;
 pub mod review;
 pub mod revision;
 pub mod subscription;
 pub mod tag;
 pub mod time;
 pub mod user;
+pub mod webhook;
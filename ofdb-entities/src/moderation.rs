@@ -0,0 +1,106 @@
+use crate::{activity::*, comment::*, email::*, id::*, rating::*, time::*};
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::*;
+
+pub type ModerationItemKindPrimitive = i16;
+
+/// The kind of item that needs to be reviewed by a scout.
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum ModerationItemKind {
+    /// A report about a place
+    Report    = 0,
+    /// A place that has not been reviewed yet
+    Place     = 1,
+    /// A place with edits that have not been cleared yet
+    /// by all organizations that own its tags
+    Clearance = 2,
+}
+
+impl ModerationItemKind {
+    pub fn try_from(from: ModerationItemKindPrimitive) -> Option<Self> {
+        Self::from_i16(from)
+    }
+}
+
+impl From<ModerationItemKind> for ModerationItemKindPrimitive {
+    fn from(from: ModerationItemKind) -> Self {
+        from.to_i16().unwrap()
+    }
+}
+
+pub type AssignmentStatePrimitive = i16;
+
+/// The progress of a scout on an assigned item.
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum AssignmentState {
+    Open       = 0,
+    InProgress = 1,
+    Resolved   = 2,
+}
+
+impl AssignmentState {
+    pub fn try_from(from: AssignmentStatePrimitive) -> Option<Self> {
+        Self::from_i16(from)
+    }
+}
+
+impl From<AssignmentState> for AssignmentStatePrimitive {
+    fn from(from: AssignmentState) -> Self {
+        from.to_i16().unwrap()
+    }
+}
+
+/// An item that has been assigned to a scout.
+///
+/// Each item is assigned to at most one scout at a time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModerationAssignment {
+    pub item_kind: ModerationItemKind,
+    pub item_id: Id,
+    pub assignee: Email,
+    pub state: AssignmentState,
+    pub assigned: Activity,
+    pub changed_at: TimestampMs,
+}
+
+/// A comment together with the context that is needed
+/// for moderating it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModeratedComment {
+    pub comment: Comment,
+    pub rating: Rating,
+    pub place_title: String,
+    /// The number of users who have flagged the comment
+    pub flag_count: u64,
+}
+
+/// A place that is mentioned in a report for scouts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoutReportItem {
+    pub place_id: Id,
+    pub title: String,
+}
+
+/// A periodic summary of the places within the region
+/// of a scout that might need their attention.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoutReport {
+    /// Start of the reported period
+    pub since: TimestampMs,
+    /// Places that have been created during the reported period
+    pub new_places: Vec<ScoutReportItem>,
+    /// Places with open reports from visitors
+    pub reported_places: Vec<ScoutReportItem>,
+    /// Places that have not been changed for a long time
+    pub stale_places: Vec<ScoutReportItem>,
+}
+
+impl ScoutReport {
+    pub fn is_empty(&self) -> bool {
+        self.new_places.is_empty()
+            && self.reported_places.is_empty()
+            && self.stale_places.is_empty()
+    }
+}
@@ -114,7 +114,17 @@ impl EmailNonce {
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct UserToken {
     pub email_nonce: EmailNonce,
-    // TODO: Convert time stamps from second to millisecond precision?
+    pub expires_at: Timestamp,
+}
+
+/// A one-time link for logging in without a password.
+///
+/// Independent of the user tokens for confirming the e-mail
+/// address or resetting the password.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LoginLink {
+    pub email_nonce: EmailNonce,
+    pub created_at: TimestampMs,
     pub expires_at: Timestamp,
 }
 
@@ -1,7 +1,95 @@
+use crate::{id::*, time::*};
+use num_derive::{FromPrimitive, ToPrimitive};
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Organization {
     pub id: String,
     pub name: String,
     pub owned_tags: Vec<String>,
-    pub api_token: String,
+}
+
+/// A tag that is owned by an organization
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagOwnership {
+    pub tag: String,
+    pub org_id: String,
+    pub org_name: String,
+}
+
+/// Places with tags that are owned by a curated organization
+/// are preferred in search results by boosting their score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CuratedOrganization {
+    pub org_id: String,
+    pub boost: f64,
+}
+
+/// What an API token of an organization may be used for
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
+pub enum ApiTokenScope {
+    Read         = 0,
+    WriteEvents  = 1,
+    ClearPlaces  = 2,
+    ImportPlaces = 3,
+}
+
+impl ApiTokenScope {
+    pub const ALL: &'static [ApiTokenScope] = &[
+        ApiTokenScope::Read,
+        ApiTokenScope::WriteEvents,
+        ApiTokenScope::ClearPlaces,
+        ApiTokenScope::ImportPlaces,
+    ];
+}
+
+/// An API token of an organization that might be
+/// restricted to some scopes and might expire.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrganizationApiToken {
+    pub id: Id,
+    pub org_id: String,
+    pub token: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub created_at: TimestampMs,
+    /// Missing if the token never expires
+    pub expires_at: Option<TimestampMs>,
+}
+
+impl OrganizationApiToken {
+    pub fn is_expired_at(&self, at: TimestampMs) -> bool {
+        self.expires_at
+            .map(|expires_at| expires_at <= at)
+            .unwrap_or(false)
+    }
+
+    pub fn has_scope(&self, scope: ApiTokenScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expired_api_token() {
+        let token = OrganizationApiToken {
+            id: Id::new(),
+            org_id: "org".into(),
+            token: "token".into(),
+            scopes: vec![ApiTokenScope::Read],
+            created_at: TimestampMs::from_inner(1000),
+            expires_at: None,
+        };
+        assert!(!token.is_expired_at(TimestampMs::from_inner(2000)));
+        let token = OrganizationApiToken {
+            expires_at: Some(TimestampMs::from_inner(2000)),
+            ..token
+        };
+        assert!(!token.is_expired_at(TimestampMs::from_inner(1999)));
+        assert!(token.is_expired_at(TimestampMs::from_inner(2000)));
+        assert!(token.has_scope(ApiTokenScope::Read));
+        assert!(!token.has_scope(ApiTokenScope::WriteEvents));
+    }
 }
@@ -1,6 +1,9 @@
-use crate::{activity::*, contact::*, id::*, links::*, location::*, review::*, revision::*};
+use crate::{
+    activity::*, contact::*, id::*, links::*, location::*, review::*, revision::*, time::*,
+};
 
 use std::str::FromStr;
+use url::Url;
 
 // Immutable part of a place.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -47,6 +50,26 @@ impl From<OpeningHours> for String {
     }
 }
 
+// Origin of imported place data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaceSource {
+    // The imported record. The source is the id of the
+    // organization that imported the place.
+    pub external_id: ExternalId,
+    pub imported_at: TimestampMs,
+}
+
+// Reference to the same place in another data source,
+// e.g. OpenStreetMap, Wikidata, or a partner database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalId {
+    // Name of the data source, e.g. "osm" or "wikidata"
+    pub source: String,
+    // Id of the corresponding record within the data source
+    pub id: String,
+    pub url: Option<Url>,
+}
+
 // Mutable part of a place.
 #[derive(Debug, Clone, PartialEq)]
 pub struct PlaceRevision {
@@ -55,10 +78,13 @@ pub struct PlaceRevision {
     pub title: String,
     pub description: String,
     pub location: Location,
+    // Further sites of places that operate at multiple locations
+    pub additional_locations: Vec<Location>,
     pub contact: Option<Contact>,
     pub opening_hours: Option<OpeningHours>,
     pub links: Option<Links>,
     pub tags: Vec<String>,
+    pub source: Option<PlaceSource>,
 }
 
 // Convenience type that merges the tuple (PlaceRoot, PlaceRevision)
@@ -72,10 +98,12 @@ pub struct Place {
     pub title: String,
     pub description: String,
     pub location: Location,
+    pub additional_locations: Vec<Location>,
     pub contact: Option<Contact>,
     pub opening_hours: Option<OpeningHours>,
     pub links: Option<Links>,
     pub tags: Vec<String>,
+    pub source: Option<PlaceSource>,
 }
 
 impl Place {
@@ -111,10 +139,12 @@ impl From<(PlaceRoot, PlaceRevision)> for Place {
                 title,
                 description,
                 location,
+                additional_locations,
                 contact,
                 opening_hours,
                 links,
                 tags,
+                source,
             },
         ) = from;
         Self {
@@ -125,10 +155,12 @@ impl From<(PlaceRoot, PlaceRevision)> for Place {
             title,
             description,
             location,
+            additional_locations,
             contact,
             opening_hours,
             links,
             tags,
+            source,
         }
     }
 }
@@ -143,10 +175,12 @@ impl From<Place> for (PlaceRoot, PlaceRevision) {
             title,
             description,
             location,
+            additional_locations,
             contact,
             opening_hours,
             links,
             tags,
+            source,
         } = from;
         (
             PlaceRoot { id, license },
@@ -156,10 +190,12 @@ impl From<Place> for (PlaceRoot, PlaceRevision) {
                 title,
                 description,
                 location,
+                additional_locations,
                 contact,
                 opening_hours,
                 links,
                 tags,
+                source,
             },
         )
     }
@@ -170,3 +206,37 @@ pub struct PlaceHistory {
     pub place: PlaceRoot,
     pub revisions: Vec<(PlaceRevision, Vec<ReviewStatusLog>)>,
 }
+
+/// The values of a field in two revisions of a place.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange<T> {
+    pub from: T,
+    pub to: T,
+}
+
+/// Field-level differences between two revisions of a place.
+///
+/// Fields that have not been changed are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaceRevisionDiff {
+    pub place_id: Id,
+    pub from: Revision,
+    pub to: Revision,
+    pub title: Option<FieldChange<String>>,
+    pub description: Option<FieldChange<String>>,
+    pub location: Option<FieldChange<Location>>,
+    pub contact: Option<FieldChange<Option<Contact>>>,
+    pub added_tags: Vec<String>,
+    pub removed_tags: Vec<String>,
+}
+
+impl PlaceRevisionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.description.is_none()
+            && self.location.is_none()
+            && self.contact.is_none()
+            && self.added_tags.is_empty()
+            && self.removed_tags.is_empty()
+    }
+}
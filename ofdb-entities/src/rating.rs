@@ -142,6 +142,11 @@ impl AvgRatingValueBuilder {
         self.cnt += 1;
     }
 
+    fn add_sum(&mut self, sum: i64, cnt: usize) {
+        self.acc += sum;
+        self.cnt += cnt;
+    }
+
     pub fn build(self) -> AvgRatingValue {
         if self.cnt > 0 {
             AvgRatingValue::from(self.acc as f64 / self.cnt as f64).clamp()
@@ -165,6 +170,8 @@ pub struct AvgRatings {
     pub renewable: AvgRatingValue,
     pub solidarity: AvgRatingValue,
     pub transparency: AvgRatingValue,
+    // The number of ratings in all contexts
+    pub count: u64,
 }
 
 impl AvgRatings {
@@ -203,7 +210,31 @@ impl AvgRatingsBuilder {
         }
     }
 
+    /// Add the sum of multiple rating values at once
+    pub fn add_sum(&mut self, ctx: RatingContext, sum: i64, count: usize) {
+        use RatingContext::*;
+        match ctx {
+            Diversity => self.diversity.add_sum(sum, count),
+            Fairness => self.fairness.add_sum(sum, count),
+            Humanity => self.humanity.add_sum(sum, count),
+            Renewable => self.renewable.add_sum(sum, count),
+            Solidarity => self.solidarity.add_sum(sum, count),
+            Transparency => self.transparency.add_sum(sum, count),
+        }
+    }
+
     pub fn build(self) -> AvgRatings {
+        let count = [
+            &self.diversity,
+            &self.fairness,
+            &self.humanity,
+            &self.renewable,
+            &self.solidarity,
+            &self.transparency,
+        ]
+        .iter()
+        .map(|builder| builder.cnt as u64)
+        .sum();
         AvgRatings {
             diversity: self.diversity.build(),
             fairness: self.fairness.build(),
@@ -211,6 +242,7 @@ impl AvgRatingsBuilder {
             renewable: self.renewable.build(),
             solidarity: self.solidarity.build(),
             transparency: self.transparency.build(),
+            count,
         }
     }
 }
@@ -221,14 +253,24 @@ impl std::ops::AddAssign<(RatingContext, RatingValue)> for AvgRatingsBuilder {
     }
 }
 
+/// The average ratings of all ratings that have been
+/// created within a single calendar month (UTC)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthlyAvgRatings {
+    pub year: i32,
+    // 1 = January, ..., 12 = December
+    pub month: u32,
+    pub ratings: AvgRatings,
+}
+
 #[rustfmt::skip]
 #[derive(Debug, Clone, PartialEq)]
 pub struct Rating {
     pub id          : Id,
     pub place_id    : Id,
-    // TODO: Convert time stamps from second to millisecond precision?
-    pub created_at  : Timestamp,
-    pub archived_at : Option<Timestamp>,
+    pub created_at  : TimestampMs,
+    pub created_by  : Option<String>,
+    pub archived_at : Option<TimestampMs>,
     pub title       : String,
     pub value       : RatingValue,
     pub context     : RatingContext,
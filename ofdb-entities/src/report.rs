@@ -0,0 +1,66 @@
+use crate::{activity::*, id::*};
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::*;
+
+pub type ReportReasonPrimitive = i16;
+
+/// Why content has been reported.
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum ReportReason {
+    Other         = 0,
+    Closed        = 1,
+    Fraud         = 2,
+    Duplicate     = 3,
+    Inappropriate = 4,
+    Inaccurate    = 5,
+    Spam          = 6,
+    Offensive     = 7,
+}
+
+impl ReportReason {
+    pub fn try_from(from: ReportReasonPrimitive) -> Option<Self> {
+        Self::from_i16(from)
+    }
+}
+
+impl From<ReportReason> for ReportReasonPrimitive {
+    fn from(from: ReportReason) -> Self {
+        from.to_i16().unwrap()
+    }
+}
+
+pub type ContentKindPrimitive = i16;
+
+/// The kind of content that can be reported.
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum ContentKind {
+    Place   = 0,
+    Event   = 1,
+    Comment = 2,
+}
+
+impl ContentKind {
+    pub fn try_from(from: ContentKindPrimitive) -> Option<Self> {
+        Self::from_i16(from)
+    }
+}
+
+impl From<ContentKind> for ContentKindPrimitive {
+    fn from(from: ContentKind) -> Self {
+        from.to_i16().unwrap()
+    }
+}
+
+/// A place, an event or a comment that has been reported
+/// and needs to be reviewed by a scout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub id: Id,
+    pub content_kind: ContentKind,
+    pub content_id: Id,
+    pub reason: ReportReason,
+    pub comment: Option<String>,
+    pub created: Activity,
+}
@@ -1,4 +1,6 @@
-use crate::{geo::*, id::*};
+use crate::{geo::*, id::*, time::*};
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::*;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct BboxSubscription {
@@ -6,3 +8,128 @@ pub struct BboxSubscription {
     pub user_email: String,
     pub bbox: MapBbox,
 }
+
+/// Places that contain all of the tags, optionally
+/// restricted to a bounding box.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagSubscription {
+    pub id: Id,
+    pub user_email: String,
+    pub tags: Vec<String>,
+    pub bbox: Option<MapBbox>,
+}
+
+/// A search that a user saved to repeat it later.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedSearch {
+    pub id: Id,
+    pub user_email: String,
+    pub created_at: TimestampMs,
+    pub title: String,
+    /// The query string of the search, e.g. `text=organic&tags=bakery`
+    pub query: String,
+}
+
+pub type DigestIntervalPrimitive = i16;
+
+/// How often subscribers who prefer a single summary
+/// instead of an e-mail for every change are notified.
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum DigestInterval {
+    Daily  = 0,
+    Weekly = 1,
+}
+
+impl DigestInterval {
+    pub fn try_from(from: DigestIntervalPrimitive) -> Option<Self> {
+        Self::from_i16(from)
+    }
+
+    pub fn seconds(self) -> i64 {
+        match self {
+            DigestInterval::Daily => 24 * 60 * 60,
+            DigestInterval::Weekly => 7 * 24 * 60 * 60,
+        }
+    }
+}
+
+impl From<DigestInterval> for DigestIntervalPrimitive {
+    fn from(from: DigestInterval) -> Self {
+        from.to_i16().unwrap()
+    }
+}
+
+/// A user who receives a periodic digest instead
+/// of individual notifications.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestSettings {
+    pub user_email: String,
+    pub interval: DigestInterval,
+    /// End of the period of the last digest
+    pub last_sent_at: TimestampMs,
+}
+
+impl DigestSettings {
+    pub fn is_due(&self, now: TimestampMs) -> bool {
+        now.into_seconds() - self.last_sent_at.into_seconds() >= self.interval.seconds()
+    }
+}
+
+pub type NotificationKindPrimitive = i16;
+
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum NotificationKind {
+    PlaceAdded   = 0,
+    PlaceUpdated = 1,
+}
+
+impl NotificationKind {
+    pub fn try_from(from: NotificationKindPrimitive) -> Option<Self> {
+        Self::from_i16(from)
+    }
+}
+
+impl From<NotificationKind> for NotificationKindPrimitive {
+    fn from(from: NotificationKind) -> Self {
+        from.to_i16().unwrap()
+    }
+}
+
+/// A change within the subscriptions of a user that is
+/// queued until the next digest is sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedNotification {
+    pub user_email: String,
+    pub kind: NotificationKind,
+    pub place_id: Id,
+    pub place_title: String,
+    pub created_at: TimestampMs,
+}
+
+/// A place that is mentioned in a digest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestItem {
+    pub place_id: Id,
+    pub title: String,
+}
+
+/// A summary of all changes within the subscriptions
+/// of a user during a period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationDigest {
+    /// Start of the summarized period
+    pub since: TimestampMs,
+    pub interval: DigestInterval,
+    /// Places that have been created during the period
+    pub new_places: Vec<DigestItem>,
+    /// Places that existed before and have been changed
+    pub updated_places: Vec<DigestItem>,
+}
+
+impl NotificationDigest {
+    pub fn is_empty(&self) -> bool {
+        self.new_places.is_empty() && self.updated_places.is_empty()
+    }
+}
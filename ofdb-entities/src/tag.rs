@@ -7,3 +7,11 @@ pub type TagCount = u64;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct TagFrequency(pub String, pub TagCount);
+
+/// Places with a curated tag are preferred in search results
+/// by boosting their score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CuratedTag {
+    pub tag: String,
+    pub boost: f64,
+}
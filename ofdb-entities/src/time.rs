@@ -130,4 +130,13 @@ mod tests {
         let t2 = TimestampMs::from_inner(i1);
         assert_eq!(t1, t2);
     }
+
+    #[test]
+    fn order_within_same_second_ms() {
+        let t1 = TimestampMs::from_milliseconds(1_000);
+        let t2 = TimestampMs::from_milliseconds(1_999);
+        assert_eq!(t1.into_seconds(), t2.into_seconds());
+        assert!(t1 < t2);
+        assert_eq!(t2, TimestampMs::from_inner(t2.into_inner()));
+    }
 }
@@ -1,4 +1,4 @@
-use crate::password::Password;
+use crate::{activity::*, id::Id, password::Password, time::TimestampMs};
 use num_derive::{FromPrimitive, ToPrimitive};
 
 #[rustfmt::skip]
@@ -8,6 +8,8 @@ pub struct User {
     pub email_confirmed : bool,
     pub password        : Password,
     pub role            : Role,
+    /// The preferred language for e-mails, e.g. `en`
+    pub language        : Option<String>,
 }
 
 #[rustfmt::skip]
@@ -24,3 +26,102 @@ impl Default for Role {
         Role::Guest
     }
 }
+
+impl Role {
+    /// The permissions that are granted to all users
+    /// with this role unless overridden per user.
+    pub fn default_permissions(self) -> &'static [Permission] {
+        use Permission::*;
+        match self {
+            Role::Guest | Role::User => &[],
+            Role::Scout => &[
+                ReviewPlaces,
+                ViewPlaceHistory,
+                ArchiveEvents,
+                ArchiveRatings,
+                ArchiveComments,
+                ModerateReports,
+                ViewTagOwners,
+                ExportData,
+            ],
+            Role::Admin => Permission::ALL,
+        }
+    }
+
+    pub fn has_default_permission(self, permission: Permission) -> bool {
+        self.default_permissions().contains(&permission)
+    }
+}
+
+/// A capability for performing a restricted operation
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
+pub enum Permission {
+    ReviewPlaces        =  0,
+    ViewPlaceHistory    =  1,
+    ArchiveEvents       =  2,
+    ArchiveRatings      =  3,
+    ArchiveComments     =  4,
+    ModerateReports     =  5,
+    ViewTagOwners       =  6,
+    ExportData          =  7,
+    // Export e-mail addresses of all creators and reviewers
+    ExportActivityData  =  8,
+    CurateTags          =  9,
+    ManageUsers         = 10,
+    ManageSubscriptions = 11,
+    ViewAuditLog        = 12,
+    ManageAnnouncements = 13,
+    ManageOrganizations = 14,
+    ManageWebhooks      = 15,
+    MergePlaces         = 16,
+}
+
+impl Permission {
+    pub const ALL: &'static [Permission] = &[
+        Permission::ReviewPlaces,
+        Permission::ViewPlaceHistory,
+        Permission::ArchiveEvents,
+        Permission::ArchiveRatings,
+        Permission::ArchiveComments,
+        Permission::ModerateReports,
+        Permission::ViewTagOwners,
+        Permission::ExportData,
+        Permission::ExportActivityData,
+        Permission::CurateTags,
+        Permission::ManageUsers,
+        Permission::ManageSubscriptions,
+        Permission::ViewAuditLog,
+        Permission::ManageAnnouncements,
+        Permission::ManageOrganizations,
+        Permission::ManageWebhooks,
+        Permission::MergePlaces,
+    ];
+}
+
+/// Grants or revokes a permission for a single user
+/// independent of the default permissions of the role.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionOverride {
+    pub permission: Permission,
+    pub granted: bool,
+}
+
+/// A user account has been deactivated or reactivated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserStatusLog {
+    pub activity: ActivityLog,
+    pub active: bool,
+}
+
+/// A revocable personal API token of a user for authorizing
+/// requests without a session, e.g. in scripts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserApiToken {
+    pub id: Id,
+    pub user_email: String,
+    pub token: String,
+    /// What the token is used for
+    pub label: Option<String>,
+    pub created_at: TimestampMs,
+}
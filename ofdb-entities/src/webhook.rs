@@ -0,0 +1,81 @@
+use crate::{id::*, time::*};
+use num_derive::{FromPrimitive, ToPrimitive};
+use url::Url;
+
+/// A change of an entity that is announced to webhooks
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
+pub enum WebhookEvent {
+    PlaceCreated  = 0,
+    PlaceUpdated  = 1,
+    PlaceArchived = 2,
+    EventCreated  = 3,
+    RatingAdded   = 4,
+}
+
+impl WebhookEvent {
+    pub const ALL: &'static [WebhookEvent] = &[
+        WebhookEvent::PlaceCreated,
+        WebhookEvent::PlaceUpdated,
+        WebhookEvent::PlaceArchived,
+        WebhookEvent::EventCreated,
+        WebhookEvent::RatingAdded,
+    ];
+
+    /// The name of the event in requests to webhooks
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebhookEvent::PlaceCreated => "place.created",
+            WebhookEvent::PlaceUpdated => "place.updated",
+            WebhookEvent::PlaceArchived => "place.archived",
+            WebhookEvent::EventCreated => "event.created",
+            WebhookEvent::RatingAdded => "rating.added",
+        }
+    }
+}
+
+/// A callback URL that receives a signed HTTP POST
+/// request for each of the subscribed events.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Webhook {
+    pub id: Id,
+    pub url: Url,
+    /// Shared with the receiver for verifying the
+    /// signatures of requests
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_at: TimestampMs,
+}
+
+impl Webhook {
+    pub fn is_subscribed_to(&self, event: WebhookEvent) -> bool {
+        self.events.contains(&event)
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum WebhookDeliveryStatus {
+    Pending   = 0,
+    Delivered = 1,
+    /// All attempts failed
+    Failed    = 2,
+}
+
+/// A single request to a webhook that is retried
+/// until it succeeds or too many attempts failed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookDelivery {
+    pub id: Id,
+    pub webhook_id: Id,
+    pub event: WebhookEvent,
+    pub payload: String,
+    pub created_at: TimestampMs,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+    pub last_attempt_at: Option<TimestampMs>,
+    /// Only present while the delivery is pending
+    pub next_attempt_at: Option<TimestampMs>,
+    /// The HTTP status code of the last response
+    pub response_status: Option<u16>,
+}
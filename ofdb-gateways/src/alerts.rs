@@ -0,0 +1,283 @@
+use ofdb_core::NotificationGateway;
+use ofdb_entities::{
+    category::*, event::*, moderation::*, nonce::*, place::*, report::*, subscription::*, user::*,
+};
+use serde::Serialize;
+use std::{
+    io::{Error, ErrorKind, Result},
+    thread,
+    time::Duration,
+};
+use url::Url;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+// Long comments are truncated, the full report is available for moderators
+const MAX_COMMENT_LEN: usize = 200;
+
+/// A channel for short text messages to the admins
+/// and scouts of a deployment, e.g. a group chat.
+pub trait AlertChannel {
+    fn send_alert(&self, event: &str, text: &str) -> Result<()>;
+}
+
+/// Posts alerts into a Slack channel via an incoming webhook.
+#[derive(Debug, Clone)]
+pub struct Slack {
+    pub webhook_url: Url,
+}
+
+/// Posts alerts into a Telegram chat via a bot.
+#[derive(Debug, Clone)]
+pub struct Telegram {
+    pub bot_token: String,
+    pub chat_id: String,
+}
+
+/// Posts alerts as JSON to an arbitrary URL, e.g. for
+/// chat services with Slack compatible webhooks.
+#[derive(Debug, Clone)]
+pub struct AlertWebhook {
+    pub url: Url,
+}
+
+#[derive(Serialize)]
+struct SlackMessage<'a> {
+    text: &'a str,
+}
+
+#[derive(Serialize)]
+struct TelegramMessage<'a> {
+    chat_id: &'a str,
+    text: &'a str,
+    disable_web_page_preview: bool,
+}
+
+#[derive(Serialize)]
+struct WebhookMessage<'a> {
+    event: &'a str,
+    text: &'a str,
+}
+
+fn other_err<E>(err: E) -> Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    Error::new(ErrorKind::Other, err)
+}
+
+fn post_json<T: Serialize>(url: &str, message: &T) -> Result<()> {
+    let res = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(other_err)?
+        .post(url)
+        .json(message)
+        .send()
+        .map_err(other_err)?;
+    if !res.status().is_success() {
+        return Err(other_err(format!(
+            "Unexpected response status {}",
+            res.status()
+        )));
+    }
+    Ok(())
+}
+
+impl AlertChannel for Slack {
+    fn send_alert(&self, _: &str, text: &str) -> Result<()> {
+        post_json(self.webhook_url.as_str(), &SlackMessage { text })
+    }
+}
+
+impl AlertChannel for Telegram {
+    fn send_alert(&self, _: &str, text: &str) -> Result<()> {
+        let url = format!("{}/bot{}/sendMessage", TELEGRAM_API_URL, self.bot_token);
+        post_json(
+            &url,
+            &TelegramMessage {
+                chat_id: &self.chat_id,
+                text,
+                disable_web_page_preview: true,
+            },
+        )
+    }
+}
+
+impl AlertChannel for AlertWebhook {
+    fn send_alert(&self, event: &str, text: &str) -> Result<()> {
+        post_json(self.url.as_str(), &WebhookMessage { event, text })
+    }
+}
+
+fn entry_url(id: &str) -> String {
+    format!("https://kartevonmorgen.org/#/?entry={}", id)
+}
+
+fn place_added_alert(place: &Place) -> String {
+    format!(
+        "New place: {}\n{}",
+        place.title,
+        entry_url(place.id.as_str())
+    )
+}
+
+fn event_created_alert(event: &Event) -> String {
+    format!(
+        "New event: {} ({})\n{}",
+        event.title,
+        event.start.format("%Y-%m-%d %H:%M"),
+        entry_url(event.id.as_str())
+    )
+}
+
+fn content_reported_alert(report: &Report, title: &str) -> String {
+    let kind = match report.content_kind {
+        ContentKind::Place => "place",
+        ContentKind::Event => "event",
+        ContentKind::Comment => "comment",
+    };
+    let reason = match report.reason {
+        ReportReason::Closed => "closed",
+        ReportReason::Fraud => "fraud",
+        ReportReason::Duplicate => "duplicate",
+        ReportReason::Inappropriate => "inappropriate",
+        ReportReason::Inaccurate => "inaccurate",
+        ReportReason::Spam => "spam",
+        ReportReason::Offensive => "offensive content",
+        ReportReason::Other => "other",
+    };
+    let mut text = format!(
+        "Reported {} ({}): {}\nID: {}",
+        kind, reason, title, report.content_id
+    );
+    if let Some(ref comment) = report.comment {
+        let comment: String = comment.chars().take(MAX_COMMENT_LEN).collect();
+        text.push_str(&format!("\nComment: {}", comment));
+    }
+    text
+}
+
+/// Alerts about new entries and reported content.
+///
+/// The alerts are sent in the background and failures
+/// are only logged. All other notifications are addressed
+/// to individual users and are ignored.
+#[derive(Debug, Clone)]
+pub struct Alerts<C>(pub C);
+
+impl<C> Alerts<C>
+where
+    C: AlertChannel + Clone + Send + 'static,
+{
+    fn send(&self, event: &'static str, text: String) {
+        let channel = self.0.clone();
+        thread::spawn(move || {
+            if let Err(err) = channel.send_alert(event, &text) {
+                warn!("Failed to send {} alert: {}", event, err);
+            }
+        });
+    }
+}
+
+impl<C> NotificationGateway for Alerts<C>
+where
+    C: AlertChannel + Clone + Send + 'static,
+{
+    fn place_added(&self, _: &[String], place: &Place, _: Vec<Category>) {
+        self.send("place.created", place_added_alert(place));
+    }
+    fn place_updated(&self, _: &[String], _: &Place, _: Vec<Category>) {}
+    fn event_created(&self, _: &[String], event: &Event) {
+        self.send("event.created", event_created_alert(event));
+    }
+    fn event_updated(&self, _: &[String], _: &Event) {}
+    fn place_archived(&self, _: &[String], _: &Place, _: Option<&str>) {}
+    fn event_archived(&self, _: &[String], _: &Event, _: Option<&str>) {}
+    fn user_registered_kvm(&self, _: &User) {}
+    fn user_registered_ofdb(&self, _: &User) {}
+    fn user_registered(&self, _: &User, _: &str) {}
+    fn user_reset_password_requested(&self, _: &EmailNonce) {}
+    fn user_login_link_requested(&self, _: &EmailNonce) {}
+    fn place_contact_verification_requested(&self, _: &Place, _: &EmailNonce) {}
+    fn scout_report_created(&self, _: &str, _: &ScoutReport) {}
+    fn notification_digest_created(&self, _: &str, _: &NotificationDigest) {}
+    fn event_registration_created(&self, _: &Event, _: &EventRegistration) {}
+    fn event_registration_promoted(&self, _: &Event, _: &EventRegistration) {}
+    fn content_reported(&self, _: &[String], report: &Report, title: &str) {
+        self.send("content.reported", content_reported_alert(report, title));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ofdb_entities::{activity::*, geo::*, location::*, revision::*};
+    use std::sync::mpsc;
+
+    #[derive(Clone)]
+    struct ChannelMock(mpsc::Sender<(String, String)>);
+
+    impl AlertChannel for ChannelMock {
+        fn send_alert(&self, event: &str, text: &str) -> Result<()> {
+            self.0.send((event.into(), text.into())).map_err(other_err)
+        }
+    }
+
+    fn new_place() -> Place {
+        Place {
+            id: "<id>".into(),
+            license: "<license>".into(),
+            revision: Revision::initial(),
+            created: Activity::now(None),
+            title: "<title>".into(),
+            description: "<description>".into(),
+            location: Location {
+                pos: MapPoint::try_from_lat_lng_deg(42.27, -7.97).unwrap(),
+                address: None,
+            },
+            additional_locations: vec![],
+            contact: None,
+            opening_hours: None,
+            links: None,
+            tags: vec![],
+            source: None,
+        }
+    }
+
+    #[test]
+    fn alert_new_places_and_reports() {
+        let (tx, rx) = mpsc::channel();
+        let alerts = Alerts(ChannelMock(tx));
+        let timeout = Duration::from_secs(5);
+
+        alerts.place_added(&[], &new_place(), vec![]);
+        let (event, text) = rx.recv_timeout(timeout).unwrap();
+        assert_eq!("place.created", event);
+        assert_eq!(
+            "New place: <title>\nhttps://kartevonmorgen.org/#/?entry=<id>",
+            text
+        );
+
+        let report = Report {
+            id: "<report-id>".into(),
+            content_kind: ContentKind::Comment,
+            content_id: "<comment-id>".into(),
+            reason: ReportReason::Spam,
+            comment: Some("x".repeat(1000)),
+            created: Activity::now(None),
+        };
+        alerts.content_reported(&[], &report, "<comment>");
+        let (event, text) = rx.recv_timeout(timeout).unwrap();
+        assert_eq!("content.reported", event);
+        assert!(text.starts_with("Reported comment (spam): <comment>\nID: <comment-id>"));
+        assert!(text.contains(&"x".repeat(MAX_COMMENT_LEN)));
+        assert!(!text.contains(&"x".repeat(MAX_COMMENT_LEN + 1)));
+
+        // Notifications for individual users are ignored
+        alerts.place_updated(&[], &new_place(), vec![]);
+        assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+    }
+}
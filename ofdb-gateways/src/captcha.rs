@@ -0,0 +1,69 @@
+use ofdb_core::CaptchaGateway;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{Error, ErrorKind, Result},
+    net::IpAddr,
+    time::Duration,
+};
+use url::Url;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub const HCAPTCHA_VERIFY_URL: &str = "https://hcaptcha.com/siteverify";
+
+/// Verifies captchas with a service that implements the
+/// `siteverify` API of hCaptcha and reCAPTCHA.
+#[derive(Debug, Clone)]
+pub struct SiteVerify {
+    pub verify_url: Url,
+    pub secret: String,
+}
+
+#[derive(Serialize)]
+struct VerifyRequest<'a> {
+    secret: &'a str,
+    response: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remoteip: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct VerifyResponse {
+    success: bool,
+}
+
+fn other_err<E>(err: E) -> Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    Error::new(ErrorKind::Other, err)
+}
+
+impl CaptchaGateway for SiteVerify {
+    fn verify_captcha(&self, response: &str, client_addr: Option<IpAddr>) -> Result<bool> {
+        let request = VerifyRequest {
+            secret: &self.secret,
+            response,
+            remoteip: client_addr.map(|addr| addr.to_string()),
+        };
+        let mut res = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(other_err)?
+            .post(self.verify_url.as_str())
+            .form(&request)
+            .send()
+            .map_err(other_err)?;
+        if !res.status().is_success() {
+            return Err(other_err(format!(
+                "Unexpected response status {}",
+                res.status()
+            )));
+        }
+        let VerifyResponse { success } = res.json().map_err(other_err)?;
+        if !success {
+            debug!("Captcha could not be verified");
+        }
+        Ok(success)
+    }
+}
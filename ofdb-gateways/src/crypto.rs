@@ -0,0 +1,12 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_varkey(key).expect("HMAC accepts keys of any size");
+    mac.input(data);
+    mac.result().code().to_vec()
+}
@@ -1,8 +1,14 @@
 #[macro_use]
 extern crate log;
 
+pub mod alerts;
+pub mod captcha;
+mod crypto;
 pub mod mailgun;
 pub mod notify;
 pub mod opencage;
+pub mod s3;
 pub mod sendmail;
+pub mod smtp;
 pub mod user_communication;
+pub mod webhooks;
@@ -1,8 +1,8 @@
-use ofdb_core::EmailGateway;
+use ofdb_core::EmailTransport;
 use ofdb_entities::email::*;
+use std::io::Result;
 #[cfg(not(test))]
 use std::io::{Error, ErrorKind};
-use std::{io::Result, thread};
 
 /// An email notification manager based on mailgun.net.
 #[derive(Debug, Clone)]
@@ -16,15 +16,6 @@ impl Mailgun {
     fn api_url(&self) -> String {
         format!("https://api.mailgun.net/v3/{}/messages", self.domain)
     }
-    fn send(&self, params: Vec<(&'static str, String)>) {
-        let url = self.api_url();
-        let key = self.api_key.clone();
-        thread::spawn(move || {
-            if let Err(err) = send_raw(&url, &key, params) {
-                warn!("Could not send e-mail: {}", err);
-            }
-        });
-    }
 }
 
 #[cfg(not(test))]
@@ -59,26 +50,15 @@ fn send_raw(_: &str, _: &str, params: Vec<(&'static str, String)>) -> Result<()>
     Ok(())
 }
 
-impl EmailGateway for Mailgun {
-    fn compose_and_send(&self, recipients: &[Email], subject: &str, body: &str) {
-        if recipients.is_empty() {
-            warn!("No valid email adresses specified");
-            return;
-        }
-        debug!("Sending e-mails to: {:?}", recipients);
-        let recipients: String = recipients
-            .into_iter()
-            .map(std::ops::Deref::deref)
-            .map(String::as_str)
-            .collect::<Vec<_>>()
-            .join(",");
-
+impl EmailTransport for Mailgun {
+    fn send_email(&self, recipient: &Email, subject: &str, body: &str) -> Result<()> {
+        debug!("Sending e-mail to: {}", recipient);
         let params = vec![
             ("from", (*self.from_email).clone()),
-            ("bcc", recipients),
+            ("to", (**recipient).clone()),
             ("subject", subject.to_owned()),
             ("text", body.to_owned()),
         ];
-        self.send(params);
+        send_raw(&self.api_url(), &self.api_key, params)
     }
 }
@@ -1,18 +1,65 @@
-use crate::user_communication;
+use crate::user_communication::{self, EmailContent, EmailTemplates};
+use crossbeam_utils::thread;
 use ofdb_core::{EmailGateway, NotificationGateway};
-use ofdb_entities::{category::*, email::*, event::*, nonce::*, place::*, user::*};
+use ofdb_entities::{
+    category::*, email::*, event::*, moderation::*, nonce::*, place::*, report::*, subscription::*,
+    user::*,
+};
+use std::{
+    collections::BTreeMap,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
+};
+
+type LanguageLookup = dyn Fn(&str) -> Option<String> + Send + Sync + 'static;
 
 pub struct Notify {
     email_gw: Box<dyn EmailGateway + Send + Sync + 'static>,
+    templates: Arc<EmailTemplates>,
+    language_of: Box<LanguageLookup>,
 }
 
 impl Notify {
-    pub fn new<G>(gw: G) -> Self
+    /// The preferred language of each recipient is looked up
+    /// by its e-mail address.
+    pub fn new<G, L>(gw: G, templates: Arc<EmailTemplates>, language_of: L) -> Self
     where
         G: EmailGateway + Send + Sync + 'static,
+        L: Fn(&str) -> Option<String> + Send + Sync + 'static,
     {
         Self {
             email_gw: Box::new(gw),
+            templates,
+            language_of: Box::new(language_of),
+        }
+    }
+
+    // Recipients with the same preferred language receive
+    // the same e-mail.
+    fn send_emails<F>(&self, recipients: &[String], compose: F)
+    where
+        F: Fn(&EmailTemplates, Option<&str>) -> tera::Result<EmailContent>,
+    {
+        let mut recipients_by_language: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for recipient in recipients {
+            recipients_by_language
+                .entry((self.language_of)(recipient))
+                .or_default()
+                .push(Email::from(recipient.clone()));
+        }
+        for (language, recipients) in recipients_by_language {
+            match compose(&self.templates, language.as_deref()) {
+                Ok(content) => {
+                    self.email_gw
+                        .compose_and_send(&recipients, &content.subject, &content.body);
+                }
+                Err(err) => {
+                    error!(
+                        "Failed to render e-mail in language {:?}: {}",
+                        language, err
+                    );
+                }
+            }
         }
     }
 }
@@ -25,28 +72,17 @@ impl NotificationGateway for Notify {
         all_categories: Vec<Category>,
     ) {
         let mut place = place.clone();
-        let (tags, categories) = Category::split_from_tags(place.tags);
+        let (tags, categories) = Categories::from(all_categories).split_from_tags(place.tags);
         place.tags = tags;
-        let category_names: Vec<String> = all_categories
-            .into_iter()
-            .filter(|c1| categories.iter().any(|c2| c1.id == c2.id))
-            .map(|c| c.name())
-            .collect();
-        let content = user_communication::place_created_email(&place, &category_names);
-
-        {
-            info!(
-                "Sending e-mails to {} recipients after new place {} added",
-                email_addresses.len(),
-                place.id,
-            );
-            compose_and_send_emails(
-                &*self.email_gw,
-                email_addresses,
-                &content.subject,
-                &content.body,
-            );
-        }
+        let category_names: Vec<String> = categories.into_iter().map(|c| c.name()).collect();
+        info!(
+            "Sending e-mails to {} recipients after new place {} added",
+            email_addresses.len(),
+            place.id,
+        );
+        self.send_emails(email_addresses, |templates, language| {
+            user_communication::place_created_email(templates, language, &place, &category_names)
+        });
     }
     fn place_updated(
         &self,
@@ -55,62 +91,57 @@ impl NotificationGateway for Notify {
         all_categories: Vec<Category>,
     ) {
         let mut place = place.clone();
-        let (tags, categories) = Category::split_from_tags(place.tags);
+        let (tags, categories) = Categories::from(all_categories).split_from_tags(place.tags);
         place.tags = tags;
-        let category_names: Vec<String> = all_categories
-            .into_iter()
-            .filter(|c1| categories.iter().any(|c2| c1.id == c2.id))
-            .map(|c| c.name())
-            .collect();
-        let content = user_communication::place_updated_email(&place, &category_names);
-
-        {
-            info!(
-                "Sending e-mails to {} recipients after place {} updated",
-                email_addresses.len(),
-                place.id
-            );
-            compose_and_send_emails(
-                &*self.email_gw,
-                email_addresses,
-                &content.subject,
-                &content.body,
-            );
-        }
+        let category_names: Vec<String> = categories.into_iter().map(|c| c.name()).collect();
+        info!(
+            "Sending e-mails to {} recipients after place {} updated",
+            email_addresses.len(),
+            place.id
+        );
+        self.send_emails(email_addresses, |templates, language| {
+            user_communication::place_updated_email(templates, language, &place, &category_names)
+        });
     }
     fn event_created(&self, email_addresses: &[String], event: &Event) {
-        let content = user_communication::event_created_email(&event);
-
-        {
-            info!(
-                "Sending e-mails to {} recipients after new event {} created",
-                email_addresses.len(),
-                event.id,
-            );
-            compose_and_send_emails(
-                &*self.email_gw,
-                email_addresses,
-                &content.subject,
-                &content.body,
-            );
-        }
+        info!(
+            "Sending e-mails to {} recipients after new event {} created",
+            email_addresses.len(),
+            event.id,
+        );
+        self.send_emails(email_addresses, |templates, language| {
+            user_communication::event_created_email(templates, language, event)
+        });
     }
     fn event_updated(&self, email_addresses: &[String], event: &Event) {
-        let content = user_communication::event_updated_email(&event);
-
-        {
-            info!(
-                "Sending e-mails to {} recipients after event {} updated",
-                email_addresses.len(),
-                event.id
-            );
-            compose_and_send_emails(
-                &*self.email_gw,
-                email_addresses,
-                &content.subject,
-                &content.body,
-            );
-        }
+        info!(
+            "Sending e-mails to {} recipients after event {} updated",
+            email_addresses.len(),
+            event.id
+        );
+        self.send_emails(email_addresses, |templates, language| {
+            user_communication::event_updated_email(templates, language, event)
+        });
+    }
+    fn place_archived(&self, email_addresses: &[String], place: &Place, comment: Option<&str>) {
+        info!(
+            "Sending e-mails to {} recipients after place {} archived",
+            email_addresses.len(),
+            place.id
+        );
+        self.send_emails(email_addresses, |templates, language| {
+            user_communication::entry_archived_email(templates, language, &place.title, comment)
+        });
+    }
+    fn event_archived(&self, email_addresses: &[String], event: &Event, comment: Option<&str>) {
+        info!(
+            "Sending e-mails to {} recipients after event {} archived",
+            email_addresses.len(),
+            event.id
+        );
+        self.send_emails(email_addresses, |templates, language| {
+            user_communication::entry_archived_email(templates, language, &event.title, comment)
+        });
     }
     fn user_registered_kvm(&self, user: &User) {
         let token = EmailNonce {
@@ -131,47 +162,431 @@ impl NotificationGateway for Notify {
         self.user_registered(user, &url);
     }
     fn user_registered(&self, user: &User, url: &str) {
-        let content = user_communication::user_registration_email(&url);
-
-        {
-            info!("Sending confirmation e-mail to user {}", user.email);
-            compose_and_send_emails(
-                &*self.email_gw,
-                &[user.email.clone()],
-                &content.subject,
-                &content.body,
-            );
-        }
+        info!("Sending confirmation e-mail to user {}", user.email);
+        self.send_emails(&[user.email.clone()], |templates, language| {
+            user_communication::user_registration_email(templates, language, url)
+        });
     }
     fn user_reset_password_requested(&self, email_nonce: &EmailNonce) {
         let url = format!(
             "https://openfairdb.org/reset-password?token={}",
             email_nonce.encode_to_string()
         );
-        let content = user_communication::user_reset_password_email(&url);
+        info!(
+            "Sending e-mail to {} after password reset requested",
+            email_nonce.email
+        );
+        self.send_emails(&[email_nonce.email.to_owned()], |templates, language| {
+            user_communication::user_reset_password_email(templates, language, &url)
+        });
+    }
+    fn user_login_link_requested(&self, email_nonce: &EmailNonce) {
+        let url = format!(
+            "https://openfairdb.org/login/token/{}",
+            email_nonce.encode_to_string()
+        );
+        info!("Sending login link to {}", email_nonce.email);
+        self.send_emails(&[email_nonce.email.to_owned()], |templates, language| {
+            user_communication::user_login_link_email(templates, language, &url)
+        });
+    }
+    fn place_contact_verification_requested(&self, place: &Place, email_nonce: &EmailNonce) {
+        let url = format!(
+            "https://openfairdb.org/verify-contact/{}",
+            email_nonce.encode_to_string()
+        );
+        info!(
+            "Sending e-mail to {} to verify the contact of place {}",
+            email_nonce.email, place.id
+        );
+        self.send_emails(&[email_nonce.email.to_owned()], |templates, language| {
+            user_communication::place_contact_verification_email(
+                templates,
+                language,
+                &place.title,
+                &url,
+            )
+        });
+    }
+    fn scout_report_created(&self, email: &str, report: &ScoutReport) {
+        info!("Sending scout report to {}", email);
+        self.send_emails(&[email.to_owned()], |templates, language| {
+            user_communication::scout_report_email(templates, language, report)
+        });
+    }
+    fn notification_digest_created(&self, email: &str, digest: &NotificationDigest) {
+        info!("Sending notification digest to {}", email);
+        self.send_emails(&[email.to_owned()], |templates, language| {
+            user_communication::notification_digest_email(templates, language, digest)
+        });
+    }
+    fn event_registration_created(&self, event: &Event, registration: &EventRegistration) {
+        let url = cancel_event_registration_url(registration);
+        info!(
+            "Sending e-mail to {} after registration for event {}",
+            registration.email_nonce.email, event.id
+        );
+        self.send_emails(
+            &[registration.email_nonce.email.to_owned()],
+            |templates, language| {
+                user_communication::event_registration_email(
+                    templates,
+                    language,
+                    &event.title,
+                    registration.status,
+                    &url,
+                )
+            },
+        );
+    }
+    fn event_registration_promoted(&self, event: &Event, registration: &EventRegistration) {
+        let url = cancel_event_registration_url(registration);
+        info!(
+            "Sending e-mail to {} after promotion from the waitlist of event {}",
+            registration.email_nonce.email, event.id
+        );
+        self.send_emails(
+            &[registration.email_nonce.email.to_owned()],
+            |templates, language| {
+                user_communication::event_registration_promoted_email(
+                    templates,
+                    language,
+                    &event.title,
+                    &url,
+                )
+            },
+        );
+    }
+    fn content_reported(&self, email_addresses: &[String], report: &Report, title: &str) {
+        info!(
+            "Sending e-mails to {} recipients after {:?} {} reported",
+            email_addresses.len(),
+            report.content_kind,
+            report.content_id
+        );
+        self.send_emails(email_addresses, |templates, language| {
+            user_communication::content_reported_email(templates, language, report, title)
+        });
+    }
+}
 
-        {
-            info!(
-                "Sending e-mail to {} after password reset requested",
-                email_nonce.email
-            );
-            compose_and_send_emails(
-                &*self.email_gw,
-                &[email_nonce.email.to_owned()],
-                &content.subject,
-                &content.body,
-            );
+/// The link in the e-mails to attendees for cancelling their registration
+pub fn cancel_event_registration_url(registration: &EventRegistration) -> String {
+    format!(
+        "https://openfairdb.org/cancel-event-registration/{}",
+        registration.email_nonce.encode_to_string()
+    )
+}
+
+/// Forwards all notifications to multiple gateways in parallel,
+/// e.g. e-mails for the users and chat alerts for the admins.
+#[derive(Default)]
+pub struct NotifyAll {
+    gateways: Vec<Box<dyn NotificationGateway + Send + Sync + 'static>>,
+}
+
+impl NotifyAll {
+    pub fn register<G>(&mut self, gw: G)
+    where
+        G: NotificationGateway + Send + Sync + 'static,
+    {
+        self.gateways.push(Box::new(gw));
+    }
+
+    // Returns after all gateways have been notified
+    fn notify<F>(&self, f: F)
+    where
+        F: Fn(&dyn NotificationGateway) + Sync,
+    {
+        if let [gw] = self.gateways.as_slice() {
+            // No need for spawning a thread
+            f(&**gw);
+            return;
+        }
+        let res = thread::scope(|scope| {
+            for gw in &self.gateways {
+                let f = &f;
+                scope.spawn(move |_| f(&**gw));
+            }
+        });
+        if res.is_err() {
+            error!("Failed to notify all gateways");
+        }
+    }
+}
+
+impl NotificationGateway for NotifyAll {
+    fn place_added(
+        &self,
+        email_addresses: &[String],
+        place: &Place,
+        all_categories: Vec<Category>,
+    ) {
+        self.notify(|gw| gw.place_added(email_addresses, place, all_categories.clone()));
+    }
+    fn place_updated(
+        &self,
+        email_addresses: &[String],
+        place: &Place,
+        all_categories: Vec<Category>,
+    ) {
+        self.notify(|gw| gw.place_updated(email_addresses, place, all_categories.clone()));
+    }
+    fn event_created(&self, email_addresses: &[String], event: &Event) {
+        self.notify(|gw| gw.event_created(email_addresses, event));
+    }
+    fn event_updated(&self, email_addresses: &[String], event: &Event) {
+        self.notify(|gw| gw.event_updated(email_addresses, event));
+    }
+    fn place_archived(&self, email_addresses: &[String], place: &Place, comment: Option<&str>) {
+        self.notify(|gw| gw.place_archived(email_addresses, place, comment));
+    }
+    fn event_archived(&self, email_addresses: &[String], event: &Event, comment: Option<&str>) {
+        self.notify(|gw| gw.event_archived(email_addresses, event, comment));
+    }
+    fn user_registered_kvm(&self, user: &User) {
+        self.notify(|gw| gw.user_registered_kvm(user));
+    }
+    fn user_registered_ofdb(&self, user: &User) {
+        self.notify(|gw| gw.user_registered_ofdb(user));
+    }
+    fn user_registered(&self, user: &User, url: &str) {
+        self.notify(|gw| gw.user_registered(user, url));
+    }
+    fn user_reset_password_requested(&self, email_nonce: &EmailNonce) {
+        self.notify(|gw| gw.user_reset_password_requested(email_nonce));
+    }
+    fn user_login_link_requested(&self, email_nonce: &EmailNonce) {
+        self.notify(|gw| gw.user_login_link_requested(email_nonce));
+    }
+    fn place_contact_verification_requested(&self, place: &Place, email_nonce: &EmailNonce) {
+        self.notify(|gw| gw.place_contact_verification_requested(place, email_nonce));
+    }
+    fn scout_report_created(&self, email: &str, report: &ScoutReport) {
+        self.notify(|gw| gw.scout_report_created(email, report));
+    }
+    fn notification_digest_created(&self, email: &str, digest: &NotificationDigest) {
+        self.notify(|gw| gw.notification_digest_created(email, digest));
+    }
+    fn event_registration_created(&self, event: &Event, registration: &EventRegistration) {
+        self.notify(|gw| gw.event_registration_created(event, registration));
+    }
+    fn event_registration_promoted(&self, event: &Event, registration: &EventRegistration) {
+        self.notify(|gw| gw.event_registration_promoted(event, registration));
+    }
+    fn content_reported(&self, email_addresses: &[String], report: &Report, title: &str) {
+        self.notify(|gw| gw.content_reported(email_addresses, report, title));
+    }
+}
+
+type Job = Box<dyn FnOnce(&dyn NotificationGateway) + Send + 'static>;
+
+/// Forwards all notifications to a gateway that runs on its own
+/// thread, e.g. for putting e-mails into the outbox.
+///
+/// The caller never waits for the gateway and may still hold
+/// resources that the gateway needs, like a database lock.
+#[derive(Clone)]
+pub struct NotifyInBackground {
+    jobs: Arc<Mutex<mpsc::Sender<Job>>>,
+}
+
+impl NotifyInBackground {
+    /// The thread terminates after all clones have been dropped
+    /// and all pending notifications have been processed.
+    pub fn spawn<G>(gw: G) -> Self
+    where
+        G: NotificationGateway + Send + 'static,
+    {
+        let (jobs, pending_jobs) = mpsc::channel::<Job>();
+        std::thread::spawn(move || {
+            for job in pending_jobs {
+                // A single failing notification must not stop all others
+                if panic::catch_unwind(AssertUnwindSafe(|| job(&gw))).is_err() {
+                    error!("Failed to process notification");
+                }
+            }
+        });
+        Self {
+            jobs: Arc::new(Mutex::new(jobs)),
+        }
+    }
+
+    fn notify<F>(&self, job: F)
+    where
+        F: FnOnce(&dyn NotificationGateway) + Send + 'static,
+    {
+        let sent = match self.jobs.lock() {
+            Ok(jobs) => jobs.send(Box::new(job)).is_ok(),
+            Err(_) => false,
+        };
+        if !sent {
+            error!("Failed to pass notification to the background thread");
         }
     }
 }
 
-fn compose_and_send_emails(
-    gw: &dyn EmailGateway,
-    recipients: &[String],
-    subject: &str,
-    body: &str,
-) {
-    // TODO: take &[Email] as argument
-    let rec: Vec<_> = recipients.iter().cloned().map(Email::from).collect();
-    gw.compose_and_send(&rec, subject, body);
+impl NotificationGateway for NotifyInBackground {
+    fn place_added(
+        &self,
+        email_addresses: &[String],
+        place: &Place,
+        all_categories: Vec<Category>,
+    ) {
+        let (email_addresses, place) = (email_addresses.to_vec(), place.clone());
+        self.notify(move |gw| gw.place_added(&email_addresses, &place, all_categories));
+    }
+    fn place_updated(
+        &self,
+        email_addresses: &[String],
+        place: &Place,
+        all_categories: Vec<Category>,
+    ) {
+        let (email_addresses, place) = (email_addresses.to_vec(), place.clone());
+        self.notify(move |gw| gw.place_updated(&email_addresses, &place, all_categories));
+    }
+    fn event_created(&self, email_addresses: &[String], event: &Event) {
+        let (email_addresses, event) = (email_addresses.to_vec(), event.clone());
+        self.notify(move |gw| gw.event_created(&email_addresses, &event));
+    }
+    fn event_updated(&self, email_addresses: &[String], event: &Event) {
+        let (email_addresses, event) = (email_addresses.to_vec(), event.clone());
+        self.notify(move |gw| gw.event_updated(&email_addresses, &event));
+    }
+    fn place_archived(&self, email_addresses: &[String], place: &Place, comment: Option<&str>) {
+        let (email_addresses, place) = (email_addresses.to_vec(), place.clone());
+        let comment = comment.map(ToOwned::to_owned);
+        self.notify(move |gw| gw.place_archived(&email_addresses, &place, comment.as_deref()));
+    }
+    fn event_archived(&self, email_addresses: &[String], event: &Event, comment: Option<&str>) {
+        let (email_addresses, event) = (email_addresses.to_vec(), event.clone());
+        let comment = comment.map(ToOwned::to_owned);
+        self.notify(move |gw| gw.event_archived(&email_addresses, &event, comment.as_deref()));
+    }
+    fn user_registered_kvm(&self, user: &User) {
+        let user = user.clone();
+        self.notify(move |gw| gw.user_registered_kvm(&user));
+    }
+    fn user_registered_ofdb(&self, user: &User) {
+        let user = user.clone();
+        self.notify(move |gw| gw.user_registered_ofdb(&user));
+    }
+    fn user_registered(&self, user: &User, url: &str) {
+        let (user, url) = (user.clone(), url.to_owned());
+        self.notify(move |gw| gw.user_registered(&user, &url));
+    }
+    fn user_reset_password_requested(&self, email_nonce: &EmailNonce) {
+        let email_nonce = email_nonce.clone();
+        self.notify(move |gw| gw.user_reset_password_requested(&email_nonce));
+    }
+    fn user_login_link_requested(&self, email_nonce: &EmailNonce) {
+        let email_nonce = email_nonce.clone();
+        self.notify(move |gw| gw.user_login_link_requested(&email_nonce));
+    }
+    fn place_contact_verification_requested(&self, place: &Place, email_nonce: &EmailNonce) {
+        let (place, email_nonce) = (place.clone(), email_nonce.clone());
+        self.notify(move |gw| gw.place_contact_verification_requested(&place, &email_nonce));
+    }
+    fn scout_report_created(&self, email: &str, report: &ScoutReport) {
+        let (email, report) = (email.to_owned(), report.clone());
+        self.notify(move |gw| gw.scout_report_created(&email, &report));
+    }
+    fn notification_digest_created(&self, email: &str, digest: &NotificationDigest) {
+        let (email, digest) = (email.to_owned(), digest.clone());
+        self.notify(move |gw| gw.notification_digest_created(&email, &digest));
+    }
+    fn event_registration_created(&self, event: &Event, registration: &EventRegistration) {
+        let (event, registration) = (event.clone(), registration.clone());
+        self.notify(move |gw| gw.event_registration_created(&event, &registration));
+    }
+    fn event_registration_promoted(&self, event: &Event, registration: &EventRegistration) {
+        let (event, registration) = (event.clone(), registration.clone());
+        self.notify(move |gw| gw.event_registration_promoted(&event, &registration));
+    }
+    fn content_reported(&self, email_addresses: &[String], report: &Report, title: &str) {
+        let (email_addresses, report) = (email_addresses.to_vec(), report.clone());
+        let title = title.to_owned();
+        self.notify(move |gw| gw.content_reported(&email_addresses, &report, &title));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{thread::sleep, time::Duration};
+
+    #[derive(Clone, Default)]
+    struct ReportsGW(Arc<Mutex<Vec<String>>>);
+
+    impl NotificationGateway for ReportsGW {
+        fn place_added(&self, _: &[String], _: &Place, _: Vec<Category>) {}
+        fn place_updated(&self, _: &[String], _: &Place, _: Vec<Category>) {}
+        fn event_created(&self, _: &[String], _: &Event) {}
+        fn event_updated(&self, _: &[String], _: &Event) {}
+        fn place_archived(&self, _: &[String], _: &Place, _: Option<&str>) {}
+        fn event_archived(&self, _: &[String], _: &Event, _: Option<&str>) {}
+        fn user_registered_kvm(&self, _: &User) {}
+        fn user_registered_ofdb(&self, _: &User) {}
+        fn user_registered(&self, _: &User, _: &str) {}
+        fn user_reset_password_requested(&self, _: &EmailNonce) {}
+        fn user_login_link_requested(&self, _: &EmailNonce) {}
+        fn place_contact_verification_requested(&self, _: &Place, _: &EmailNonce) {}
+        fn scout_report_created(&self, _: &str, _: &ScoutReport) {}
+        fn notification_digest_created(&self, _: &str, _: &NotificationDigest) {}
+        fn event_registration_created(&self, _: &Event, _: &EventRegistration) {}
+        fn event_registration_promoted(&self, _: &Event, _: &EventRegistration) {}
+        fn content_reported(&self, _: &[String], _: &Report, title: &str) {
+            self.0.lock().unwrap().push(title.to_owned());
+        }
+    }
+
+    #[test]
+    fn notify_all_registered_gateways() {
+        let report = Report {
+            id: "<report-id>".into(),
+            content_kind: ContentKind::Place,
+            content_id: "<place-id>".into(),
+            reason: ReportReason::Spam,
+            comment: None,
+            created: ofdb_entities::activity::Activity::now(None),
+        };
+        let (gw1, gw2) = (ReportsGW::default(), ReportsGW::default());
+        let mut notify = NotifyAll::default();
+        notify.content_reported(&[], &report, "<title>");
+
+        notify.register(gw1.clone());
+        notify.content_reported(&[], &report, "<title1>");
+        assert_eq!(vec!["<title1>"], *gw1.0.lock().unwrap());
+
+        notify.register(gw2.clone());
+        notify.content_reported(&[], &report, "<title2>");
+        assert_eq!(vec!["<title1>", "<title2>"], *gw1.0.lock().unwrap());
+        assert_eq!(vec!["<title2>"], *gw2.0.lock().unwrap());
+    }
+
+    #[test]
+    fn notify_in_background_without_blocking_the_caller() {
+        let report = Report {
+            id: "<report-id>".into(),
+            content_kind: ContentKind::Place,
+            content_id: "<place-id>".into(),
+            reason: ReportReason::Spam,
+            comment: None,
+            created: ofdb_entities::activity::Activity::now(None),
+        };
+        let gw = ReportsGW::default();
+        let notify = NotifyInBackground::spawn(gw.clone());
+        {
+            // The gateway needs the lock that is held by the caller
+            let _reports = gw.0.lock().unwrap();
+            notify.content_reported(&[], &report, "<title>");
+        }
+        for _ in 0..100 {
+            if !gw.0.lock().unwrap().is_empty() {
+                break;
+            }
+            sleep(Duration::from_millis(10));
+        }
+        assert_eq!(vec!["<title>"], *gw.0.lock().unwrap());
+    }
 }
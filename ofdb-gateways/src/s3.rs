@@ -0,0 +1,308 @@
+use crate::crypto::{hex, hmac_sha256};
+use chrono::{DateTime, Utc};
+use ofdb_core::ObjectStorageGateway;
+use reqwest::{Method, Response};
+use sha2::{Digest, Sha256};
+use std::io::{Error, ErrorKind, Result};
+use url::Url;
+
+const SIGNING_ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+/// An object storage client for Amazon S3 and compatible services.
+///
+/// Objects are addressed by path-style URLs, i.e. `<endpoint>/<bucket>/<key>`,
+/// that are supported by most providers.
+#[derive(Debug, Clone)]
+pub struct S3 {
+    pub endpoint: Url,
+    pub region: String,
+    pub bucket: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+struct SignedRequest {
+    url: String,
+    amz_date: String,
+    payload_hash: String,
+    authorization: String,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+// Percent-encode all characters except the unreserved ones
+fn uri_encode(input: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn signing_key(secret_access_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(
+        format!("AWS4{}", secret_access_key).as_bytes(),
+        date.as_bytes(),
+    );
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// The contents of all elements with the given name. The responses
+// of the S3 API are simple enough that no XML parser is needed.
+fn xml_elements(xml: &str, name: &str) -> Vec<String> {
+    let start_tag = format!("<{}>", name);
+    let end_tag = format!("</{}>", name);
+    let mut elements = vec![];
+    let mut rest = xml;
+    while let Some(start) = rest.find(&start_tag) {
+        rest = &rest[start + start_tag.len()..];
+        match rest.find(&end_tag) {
+            Some(end) => {
+                elements.push(xml_unescape(&rest[..end]));
+                rest = &rest[end + end_tag.len()..];
+            }
+            None => break,
+        }
+    }
+    elements
+}
+
+impl S3 {
+    fn host(&self) -> String {
+        let host = self.endpoint.host_str().unwrap_or_default();
+        match self.endpoint.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_owned(),
+        }
+    }
+
+    // Sign the request according to AWS Signature Version 4
+    fn sign(
+        &self,
+        method: &Method,
+        key: &str,
+        query: &[(&str, &str)],
+        payload: &[u8],
+        now: DateTime<Utc>,
+    ) -> SignedRequest {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let host = self.host();
+        let path = if key.is_empty() {
+            format!("/{}", self.bucket)
+        } else {
+            format!("/{}/{}", self.bucket, uri_encode(key, false))
+        };
+        let mut query: Vec<_> = query
+            .iter()
+            .map(|(k, v)| format!("{}={}", uri_encode(k, true), uri_encode(v, true)))
+            .collect();
+        query.sort();
+        let query = query.join("&");
+        let payload_hash = sha256_hex(payload);
+        let canonical_request = format!(
+            "{method}\n{path}\n{query}\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}",
+            method = method.as_str(),
+            path = path,
+            query = query,
+            host = host,
+            payload_hash = payload_hash,
+            amz_date = amz_date,
+            signed_headers = SIGNED_HEADERS,
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "{}\n{}\n{}\n{}",
+            SIGNING_ALGORITHM,
+            amz_date,
+            scope,
+            sha256_hex(canonical_request.as_bytes())
+        );
+        let signature = hex(&hmac_sha256(
+            &signing_key(&self.secret_access_key, &date, &self.region, "s3"),
+            string_to_sign.as_bytes(),
+        ));
+        let authorization = format!(
+            "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+            SIGNING_ALGORITHM, self.access_key_id, scope, SIGNED_HEADERS, signature
+        );
+        let mut url = format!("{}://{}{}", self.endpoint.scheme(), host, path);
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query);
+        }
+        SignedRequest {
+            url,
+            amz_date,
+            payload_hash,
+            authorization,
+        }
+    }
+
+    fn send(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, &str)],
+        payload: Vec<u8>,
+    ) -> Result<Response> {
+        let signed = self.sign(&method, key, query, &payload, Utc::now());
+        let res = reqwest::Client::new()
+            .request(method, signed.url.as_str())
+            .header("x-amz-date", signed.amz_date)
+            .header("x-amz-content-sha256", signed.payload_hash)
+            .header("authorization", signed.authorization)
+            .body(payload)
+            .send()
+            .map_err(|err| Error::new(ErrorKind::Other, err))?;
+        if res.status().is_success() {
+            Ok(res)
+        } else {
+            Err(Error::new(
+                ErrorKind::Other,
+                format!(
+                    "Object storage request failed: response status: {:?}",
+                    res.status()
+                ),
+            ))
+        }
+    }
+}
+
+impl ObjectStorageGateway for S3 {
+    fn put_object(&self, key: &str, content: Vec<u8>) -> Result<()> {
+        debug!("Uploading {} bytes to {}", content.len(), key);
+        self.send(Method::PUT, key, &[], content)?;
+        Ok(())
+    }
+
+    fn list_objects(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = vec![];
+        let mut continuation_token: Option<String> = None;
+        loop {
+            let body = {
+                let mut query = vec![("list-type", "2"), ("prefix", prefix)];
+                if let Some(token) = &continuation_token {
+                    query.push(("continuation-token", token.as_str()));
+                }
+                self.send(Method::GET, "", &query, vec![])?
+                    .text()
+                    .map_err(|err| Error::new(ErrorKind::Other, err))?
+            };
+            keys.extend(xml_elements(&body, "Key"));
+            // Only present if the result has been truncated
+            continuation_token = xml_elements(&body, "NextContinuationToken")
+                .into_iter()
+                .next();
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn delete_object(&self, key: &str) -> Result<()> {
+        debug!("Deleting {}", key);
+        self.send(Method::DELETE, key, &[], vec![])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derive_signing_key() {
+        // Example from the AWS General Reference
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20150830",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            "c4afb1cc5771d871763a393e44b703571b55cc28424d1a5e86da6ed3c154a4b9",
+            hex(&key)
+        );
+    }
+
+    #[test]
+    fn encode_uri() {
+        assert_eq!(
+            "backups/db%20file~1.gz",
+            uri_encode("backups/db file~1.gz", false)
+        );
+        assert_eq!("backups%2Fdb%2B1", uri_encode("backups/db+1", true));
+    }
+
+    #[test]
+    fn sign_request_with_path_style_url() {
+        let s3 = S3 {
+            endpoint: "https://s3.example.com:9000".parse().unwrap(),
+            region: "eu-central-1".into(),
+            bucket: "ofdb".into(),
+            access_key_id: "AKIDEXAMPLE".into(),
+            secret_access_key: "secret".into(),
+        };
+        let now = "2020-06-18T12:00:00Z".parse().unwrap();
+        let signed = s3.sign(
+            &Method::GET,
+            "",
+            &[("prefix", "db/"), ("list-type", "2")],
+            &[],
+            now,
+        );
+        assert_eq!(
+            "https://s3.example.com:9000/ofdb?list-type=2&prefix=db%2F",
+            signed.url
+        );
+        assert_eq!("20200618T120000Z", signed.amz_date);
+        assert_eq!(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            signed.payload_hash
+        );
+        assert!(signed.authorization.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20200618/eu-central-1/s3/aws4_request, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature="
+        ));
+    }
+
+    #[test]
+    fn parse_list_objects_response() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Name>ofdb</Name><Prefix>db/</Prefix><KeyCount>2</KeyCount>
+  <Contents><Key>db/b.gz</Key><Size>1</Size></Contents>
+  <Contents><Key>db/a&amp;c.gz</Key><Size>2</Size></Contents>
+  <IsTruncated>true</IsTruncated>
+  <NextContinuationToken>1ueGcxLPRx1Tr/XYExHnhbYLgveDs2J/wm36Hy4vbOwM=</NextContinuationToken>
+</ListBucketResult>"#;
+        assert_eq!(vec!["db/b.gz", "db/a&c.gz"], xml_elements(xml, "Key"));
+        assert_eq!(
+            vec!["1ueGcxLPRx1Tr/XYExHnhbYLgveDs2J/wm36Hy4vbOwM="],
+            xml_elements(xml, "NextContinuationToken")
+        );
+        assert!(xml_elements(xml, "Missing").is_empty());
+    }
+}
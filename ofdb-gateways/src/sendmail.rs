@@ -1,16 +1,13 @@
 use chrono::*;
 use fast_chemail::is_valid_email;
-use ofdb_core::EmailGateway;
+use ofdb_core::EmailTransport;
 use ofdb_entities::email::*;
+use std::io::{Error, ErrorKind, Result};
 #[cfg(not(test))]
 use std::{
     io::prelude::*,
     process::{Command, Stdio},
 };
-use std::{
-    io::{Error, ErrorKind, Result},
-    thread,
-};
 
 #[derive(Debug, Clone)]
 pub struct Sendmail {
@@ -21,13 +18,6 @@ impl Sendmail {
     pub fn new(from: Email) -> Self {
         Self { from }
     }
-    fn send(&self, mail: String) {
-        thread::spawn(move || {
-            if let Err(err) = send_raw(&mail) {
-                warn!("Could not send e-mail: {}", err);
-            }
-        });
-    }
 }
 
 #[cfg(not(test))]
@@ -41,7 +31,13 @@ fn send_raw(mail: &str) -> Result<()> {
         .as_mut()
         .ok_or_else(|| Error::new(ErrorKind::Other, "Could not get stdin"))?
         .write_all(mail.as_bytes())?;
-    child.wait_with_output()?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(Error::new(
+            ErrorKind::Other,
+            format!("sendmail failed: {}", output.status),
+        ));
+    }
     Ok(())
 }
 
@@ -53,19 +49,11 @@ fn send_raw(email: &str) -> Result<()> {
     Ok(())
 }
 
-impl EmailGateway for Sendmail {
-    fn compose_and_send(&self, recipients: &[Email], subject: &str, body: &str) {
-        debug!("Sending e-mails to: {:?}", recipients);
-        for to in recipients {
-            match compose(&self.from, &[to], subject, body) {
-                Ok(email) => {
-                    self.send(email);
-                }
-                Err(err) => {
-                    warn!("Failed to compose e-mail: {}", err);
-                }
-            }
-        }
+impl EmailTransport for Sendmail {
+    fn send_email(&self, recipient: &Email, subject: &str, body: &str) -> Result<()> {
+        debug!("Sending e-mail to: {}", recipient);
+        let email = compose(&self.from, &[recipient], subject, body)?;
+        send_raw(&email)
     }
 }
 
@@ -0,0 +1,161 @@
+use crate::sendmail::compose;
+#[cfg(not(test))]
+use lettre::{
+    smtp::authentication::Credentials, ClientSecurity, ClientTlsParameters, EmailAddress,
+    Envelope, SendableEmail, SmtpClient, Transport,
+};
+#[cfg(not(test))]
+use native_tls::TlsConnector;
+use ofdb_core::EmailTransport;
+use ofdb_entities::email::*;
+#[cfg(not(test))]
+use std::io::{Error, ErrorKind};
+use std::{io::Result, str::FromStr};
+
+/// How the connection to the SMTP server is secured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Plain text, only for local servers
+    None,
+    /// Upgrade the connection with STARTTLS
+    StartTls,
+    /// Connect with TLS from the beginning
+    Tls,
+}
+
+impl SmtpSecurity {
+    pub fn default_port(self) -> u16 {
+        match self {
+            SmtpSecurity::None => 25,
+            SmtpSecurity::StartTls => 587,
+            SmtpSecurity::Tls => 465,
+        }
+    }
+}
+
+impl Default for SmtpSecurity {
+    fn default() -> Self {
+        SmtpSecurity::StartTls
+    }
+}
+
+impl FromStr for SmtpSecurity {
+    type Err = String;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(SmtpSecurity::None),
+            "starttls" => Ok(SmtpSecurity::StartTls),
+            "tls" => Ok(SmtpSecurity::Tls),
+            _ => Err(format!("Invalid SMTP security '{}'", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SmtpCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// An email notification manager that delivers the
+/// e-mails to an SMTP server.
+#[derive(Debug, Clone)]
+pub struct Smtp {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    pub credentials: Option<SmtpCredentials>,
+    pub from_email: Email,
+}
+
+// The envelope only accepts the bare address without
+// a display name, e.g. `"Name" <name@example.com>`
+fn envelope_address(mailbox: &str) -> &str {
+    match (mailbox.rfind('<'), mailbox.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &mailbox[start + 1..end],
+        _ => mailbox.trim(),
+    }
+}
+
+#[cfg(not(test))]
+fn other_err<E>(err: E) -> Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    Error::new(ErrorKind::Other, err)
+}
+
+#[cfg(not(test))]
+fn send_raw(smtp: &Smtp, to: &str, mail: String) -> Result<()> {
+    let security = match smtp.security {
+        SmtpSecurity::None => ClientSecurity::None,
+        SmtpSecurity::StartTls | SmtpSecurity::Tls => {
+            let connector = TlsConnector::new().map_err(other_err)?;
+            let params = ClientTlsParameters::new(smtp.host.clone(), connector);
+            if smtp.security == SmtpSecurity::Tls {
+                ClientSecurity::Wrapper(params)
+            } else {
+                ClientSecurity::Required(params)
+            }
+        }
+    };
+    let mut client =
+        SmtpClient::new((smtp.host.as_str(), smtp.port), security).map_err(other_err)?;
+    if let Some(SmtpCredentials {
+        ref username,
+        ref password,
+    }) = smtp.credentials
+    {
+        client = client.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+    let from =
+        EmailAddress::new(envelope_address(&smtp.from_email).to_owned()).map_err(other_err)?;
+    let to = EmailAddress::new(to.to_owned()).map_err(other_err)?;
+    let envelope = Envelope::new(Some(from), vec![to]).map_err(other_err)?;
+    let email = SendableEmail::new(envelope, String::new(), mail.into_bytes());
+    let response = client.transport().send(email).map_err(other_err)?;
+    debug!("SMTP server response: {:?}", response);
+    Ok(())
+}
+
+/// Don't actually send emails while running the tests or
+/// if the `email` feature is disabled.
+#[cfg(test)]
+fn send_raw(_: &Smtp, to: &str, mail: String) -> Result<()> {
+    debug!("Would send e-mail to {}: {}", to, mail);
+    Ok(())
+}
+
+impl EmailTransport for Smtp {
+    fn send_email(&self, recipient: &Email, subject: &str, body: &str) -> Result<()> {
+        debug!("Sending e-mail to: {}", recipient);
+        let email = compose(&self.from_email, &[recipient], subject, body)?;
+        send_raw(self, recipient, email)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_security() {
+        assert_eq!(Ok(SmtpSecurity::None), "none".parse());
+        assert_eq!(Ok(SmtpSecurity::StartTls), "STARTTLS".parse());
+        assert_eq!(Ok(SmtpSecurity::Tls), "tls".parse());
+        assert!("ssl".parse::<SmtpSecurity>().is_err());
+        assert_eq!(587, SmtpSecurity::default().default_port());
+    }
+
+    #[test]
+    fn bare_envelope_address() {
+        assert_eq!(
+            "no-reply@example.com",
+            envelope_address("\"Karte von morgen\" <no-reply@example.com>")
+        );
+        assert_eq!(
+            "no-reply@example.com",
+            envelope_address(" no-reply@example.com")
+        );
+    }
+}
@@ -1,4 +1,10 @@
-use ofdb_entities::{address::*, contact::*, event::*, place::*};
+use chrono::NaiveDateTime;
+use ofdb_entities::{
+    address::*, contact::*, event::*, moderation::*, place::*, report::*, subscription::*,
+};
+use serde::Serialize;
+use std::{fs, path::Path};
+use tera::{Context, Result, Tera};
 use url::Url;
 
 pub struct EmailContent {
@@ -6,22 +12,144 @@ pub struct EmailContent {
     pub body: String,
 }
 
+/// The language of the e-mails if neither the recipient nor
+/// the operator have chosen another one.
+pub const DEFAULT_LANGUAGE: &str = "de";
+
 const DATE_TIME_FORMAT: &str = "%Y.%m.%d %H:%M:%S";
 
-const INTRO_ENTRY_CREATED: &str = "ein neuer Eintrag auf der Karte von morgen wurde erstellt";
+const DATE_FORMAT: &str = "%Y.%m.%d";
 
-const INTRO_ENTRY_UPDATED: &str = "folgender Eintrag auf der Karte von morgen wurde verändert";
+// The built-in templates are named `<language>/<name>.txt`
+macro_rules! builtin_templates {
+    ($($lang:literal: [$($name:literal),+]),+) => {
+        &[$($(
+            (
+                concat!($lang, "/", $name, ".txt"),
+                include_str!(concat!("../templates/email/", $lang, "/", $name, ".txt")),
+            ),
+        )+)+]
+    };
+}
 
-const OUTRO_HINT: &str = "Weitere Hinweise und Tipps zur Nutzung, z.B. wie du interaktive Karten
-per <iframe> auf deiner Webseite einbettest oder Papierkarten erstellst,
-findest du hier: https://blog.vonmorgen.org";
+const BUILTIN_TEMPLATES: &[(&str, &str)] = builtin_templates!(
+    "de": [
+        "outro", "user_registration", "user_reset_password", "user_login_link",
+        "place_contact_verification", "event_registration", "event_registration_promoted",
+        "entry_archived", "place", "event", "scout_report", "notification_digest",
+        "content_reported"
+    ],
+    "en": [
+        "outro", "user_registration", "user_reset_password", "user_login_link",
+        "place_contact_verification", "event_registration", "event_registration_promoted",
+        "entry_archived", "place", "event", "scout_report", "notification_digest",
+        "content_reported"
+    ]
+);
 
-fn subject_entry_created(entry_title: &str) -> String {
-    format!("Kvm - neuer Eintrag: {}", entry_title)
+/// Localizable templates of all e-mails.
+///
+/// A template is named `<language>/<name>.txt`. The first line
+/// of the rendered template is the subject and the remaining
+/// lines are the body of the e-mail.
+pub struct EmailTemplates {
+    tera: Tera,
+    default_language: String,
 }
 
-fn subject_entry_updated(entry_title: &str) -> String {
-    format!("Kvm - Eintrag verändert: {}", entry_title)
+impl EmailTemplates {
+    /// The built-in German and English templates.
+    pub fn new(default_language: &str) -> Self {
+        let mut tera = Tera::default();
+        tera.add_raw_templates(BUILTIN_TEMPLATES.to_vec())
+            .expect("valid built-in e-mail templates");
+        Self {
+            tera,
+            default_language: default_language.to_owned(),
+        }
+    }
+
+    /// Add templates for other languages or replace the built-in
+    /// templates with all `<dir>/<language>/<name>.txt` files.
+    ///
+    /// Returns the number of templates that have been loaded.
+    pub fn load_dir(&mut self, dir: &Path) -> std::result::Result<usize, String> {
+        let mut templates = vec![];
+        for lang_dir in fs::read_dir(dir).map_err(|err| err.to_string())? {
+            let lang_dir = lang_dir.map_err(|err| err.to_string())?.path();
+            if !lang_dir.is_dir() {
+                continue;
+            }
+            let lang = lang_dir.file_name().unwrap_or_default().to_string_lossy();
+            for file in fs::read_dir(&lang_dir).map_err(|err| err.to_string())? {
+                let file = file.map_err(|err| err.to_string())?.path();
+                if file.extension().map(|ext| ext != "txt").unwrap_or(true) {
+                    continue;
+                }
+                let name = format!(
+                    "{}/{}",
+                    lang,
+                    file.file_name().unwrap_or_default().to_string_lossy()
+                );
+                let content = fs::read_to_string(&file).map_err(|err| err.to_string())?;
+                templates.push((name, content));
+            }
+        }
+        let count = templates.len();
+        self.tera
+            .add_raw_templates(
+                templates
+                    .iter()
+                    .map(|(name, content)| (name.as_str(), content.as_str()))
+                    .collect(),
+            )
+            .map_err(|err| err.to_string())?;
+        Ok(count)
+    }
+
+    // Falls back to the default language and finally to the
+    // built-in language if no template for the requested
+    // language exists.
+    fn template_name(&self, language: Option<&str>, name: &str) -> String {
+        language
+            .into_iter()
+            .chain(vec![self.default_language.as_str(), DEFAULT_LANGUAGE])
+            .map(|lang| format!("{}/{}.txt", lang, name))
+            .find(|template| self.tera.get_template(template).is_ok())
+            .unwrap_or_else(|| format!("{}/{}.txt", DEFAULT_LANGUAGE, name))
+    }
+
+    fn render(
+        &self,
+        language: Option<&str>,
+        name: &str,
+        context: &Context,
+    ) -> Result<EmailContent> {
+        let rendered = self
+            .tera
+            .render(&self.template_name(language, name), context)?;
+        let mut lines = rendered.splitn(2, '\n');
+        let subject = lines.next().unwrap_or_default().trim().to_owned();
+        let body = lines
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches(|c| c == '\n' || c == '\r')
+            .trim_end()
+            .to_owned();
+        Ok(EmailContent { subject, body })
+    }
+}
+
+impl Default for EmailTemplates {
+    fn default() -> Self {
+        Self::new(DEFAULT_LANGUAGE)
+    }
+}
+
+#[derive(Serialize)]
+struct Item<'a> {
+    id: &'a str,
+    title: &'a str,
 }
 
 fn address_line(address: Option<&Address>) -> String {
@@ -45,154 +173,286 @@ fn address_line(address: Option<&Address>) -> String {
     }
 }
 
-pub fn user_registration_email(url: &str) -> EmailContent {
-    let subject = "Karte von morgen: Bitte bestätige deine Email-Adresse".into();
-    let body = format!(
-        "Na du Weltverbesserer*,\n
-wir freuen uns, dass du bei der Karte von morgen mit dabei bist!\n\n
-Bitte bestätige deine Email-Adresse hier:\n
-{url}\n\n
-euphorische Grüße,\n
-das Karte von morgen-Team\n
-{outro_text}",
-        url = url,
-        outro_text = OUTRO_HINT,
-    );
-    EmailContent { subject, body }
+fn url_context(url: &str) -> Context {
+    let mut context = Context::new();
+    context.insert("url", url);
+    context
+}
+
+pub fn user_registration_email(
+    templates: &EmailTemplates,
+    language: Option<&str>,
+    url: &str,
+) -> Result<EmailContent> {
+    templates.render(language, "user_registration", &url_context(url))
+}
+
+pub fn user_reset_password_email(
+    templates: &EmailTemplates,
+    language: Option<&str>,
+    url: &str,
+) -> Result<EmailContent> {
+    templates.render(language, "user_reset_password", &url_context(url))
+}
+
+pub fn user_login_link_email(
+    templates: &EmailTemplates,
+    language: Option<&str>,
+    url: &str,
+) -> Result<EmailContent> {
+    templates.render(language, "user_login_link", &url_context(url))
+}
+
+pub fn place_contact_verification_email(
+    templates: &EmailTemplates,
+    language: Option<&str>,
+    title: &str,
+    url: &str,
+) -> Result<EmailContent> {
+    let mut context = url_context(url);
+    context.insert("title", title);
+    templates.render(language, "place_contact_verification", &context)
 }
 
-pub fn user_reset_password_email(url: &str) -> EmailContent {
-    let subject = "Karte von morgen: Passwort zurücksetzen".into();
-    let body = format!(
-        "Na du Weltverbesserer*,\n
-hast du uns kürzlich gebeten dein Passwort zurücksetzen?\n\n
-Bitte folge zur Eingabe eines neuen Passworts diesem Link:\n
-{url}\n\n
-euphorische Grüße,\n
-das Karte von morgen-Team",
-        url = url,
+pub fn event_registration_email(
+    templates: &EmailTemplates,
+    language: Option<&str>,
+    title: &str,
+    status: EventRegistrationStatus,
+    url: &str,
+) -> Result<EmailContent> {
+    let mut context = url_context(url);
+    context.insert("title", title);
+    context.insert(
+        "waitlisted",
+        &(status == EventRegistrationStatus::Waitlisted),
     );
-    EmailContent { subject, body }
+    templates.render(language, "event_registration", &context)
 }
 
-pub fn place_created_email(place: &Place, category_names: &[String]) -> EmailContent {
-    let subject = subject_entry_created(&place.title);
-    let body = place_email(place, category_names, INTRO_ENTRY_CREATED);
-    EmailContent { subject, body }
+pub fn event_registration_promoted_email(
+    templates: &EmailTemplates,
+    language: Option<&str>,
+    title: &str,
+    url: &str,
+) -> Result<EmailContent> {
+    let mut context = url_context(url);
+    context.insert("title", title);
+    templates.render(language, "event_registration_promoted", &context)
 }
 
-//TODO: calc diff
-pub fn place_updated_email(place: &Place, category_names: &[String]) -> EmailContent {
-    let subject = subject_entry_updated(&place.title);
-    let body = place_email(place, category_names, INTRO_ENTRY_UPDATED);
-    EmailContent { subject, body }
+pub fn entry_archived_email(
+    templates: &EmailTemplates,
+    language: Option<&str>,
+    title: &str,
+    comment: Option<&str>,
+) -> Result<EmailContent> {
+    let mut context = Context::new();
+    context.insert("title", title);
+    context.insert("comment", &comment);
+    templates.render(language, "entry_archived", &context)
 }
 
-fn place_email(place: &Place, category_names: &[String], intro_sentence: &str) -> String {
-    let category = if !category_names.is_empty() {
-        category_names[0].clone()
-    } else {
-        "".to_string()
-    };
+pub fn place_created_email(
+    templates: &EmailTemplates,
+    language: Option<&str>,
+    place: &Place,
+    category_names: &[String],
+) -> Result<EmailContent> {
+    templates.render(
+        language,
+        "place",
+        &place_context(place, category_names, true),
+    )
+}
+
+//TODO: calc diff
+pub fn place_updated_email(
+    templates: &EmailTemplates,
+    language: Option<&str>,
+    place: &Place,
+    category_names: &[String],
+) -> Result<EmailContent> {
+    templates.render(
+        language,
+        "place",
+        &place_context(place, category_names, false),
+    )
+}
 
+fn place_context(place: &Place, category_names: &[String], created: bool) -> Context {
     let Contact { email, phone } = place.contact.clone().unwrap_or_else(|| Contact {
         email: None,
         phone: None,
     });
 
-    format!(
-        "Hallo,\n
-{intro_sentence}:\n
-{title} ({category})
-{description}\n
-    Tags: {tags}
-    Adresse: {address_line}
-    Webseite: {homepage}
-    Email-Adresse: {email}
-    Telefon: {phone}\n
-Eintrag anschauen oder bearbeiten:
-https://kartevonmorgen.org/#/?entry={id}\n
-Du kannst dein Abonnement des Kartenbereichs abbestellen,
-indem du dich auf https://kartevonmorgen.org einloggst.\n
-euphorische Grüße,\n
-das Karte von morgen-Team\n
-{outro_text}",
-        intro_sentence = intro_sentence,
-        outro_text = OUTRO_HINT,
-        id = &place.id,
-        title = &place.title,
-        description = &place.description,
-        address_line = address_line(place.location.address.as_ref()),
-        email = email.map(|e| e.to_string()).unwrap_or_default(),
-        phone = phone.unwrap_or_default(),
-        homepage = place
+    let mut context = Context::new();
+    context.insert("created", &created);
+    context.insert("id", place.id.as_str());
+    context.insert("title", &place.title);
+    context.insert(
+        "category",
+        category_names.first().map(String::as_str).unwrap_or(""),
+    );
+    context.insert("description", &place.description);
+    context.insert("tags", &place.tags.join(", "));
+    context.insert(
+        "address_line",
+        &address_line(place.location.address.as_ref()),
+    );
+    context.insert(
+        "homepage",
+        place
             .links
             .as_ref()
             .and_then(|l| l.homepage.as_ref())
             .map(Url::as_str)
-            .unwrap_or_else(|| ""),
-        category = category,
-        tags = place.tags.join(", ")
-    )
+            .unwrap_or(""),
+    );
+    context.insert("email", &email.map(|e| e.to_string()).unwrap_or_default());
+    context.insert("phone", &phone.unwrap_or_default());
+    context
 }
 
-pub fn event_created_email(event: &Event) -> EmailContent {
-    let subject = subject_entry_created(&event.title);
-    let body = event_email(event, INTRO_ENTRY_CREATED);
-    EmailContent { subject, body }
+fn scout_report_items(items: &[ScoutReportItem]) -> Vec<Item> {
+    items
+        .iter()
+        .map(|item| Item {
+            id: item.place_id.as_str(),
+            title: &item.title,
+        })
+        .collect()
+}
+
+pub fn scout_report_email(
+    templates: &EmailTemplates,
+    language: Option<&str>,
+    report: &ScoutReport,
+) -> Result<EmailContent> {
+    let since = NaiveDateTime::from(report.since).format(DATE_FORMAT);
+    let mut context = Context::new();
+    context.insert("since", &since.to_string());
+    context.insert("new_places", &scout_report_items(&report.new_places));
+    context.insert(
+        "reported_places",
+        &scout_report_items(&report.reported_places),
+    );
+    context.insert("stale_places", &scout_report_items(&report.stale_places));
+    templates.render(language, "scout_report", &context)
+}
+
+fn digest_items(items: &[DigestItem]) -> Vec<Item> {
+    items
+        .iter()
+        .map(|item| Item {
+            id: item.place_id.as_str(),
+            title: &item.title,
+        })
+        .collect()
+}
+
+pub fn notification_digest_email(
+    templates: &EmailTemplates,
+    language: Option<&str>,
+    digest: &NotificationDigest,
+) -> Result<EmailContent> {
+    let since = NaiveDateTime::from(digest.since).format(DATE_FORMAT);
+    let interval = match digest.interval {
+        DigestInterval::Daily => "daily",
+        DigestInterval::Weekly => "weekly",
+    };
+    let mut context = Context::new();
+    context.insert("since", &since.to_string());
+    context.insert("interval", interval);
+    context.insert("new_places", &digest_items(&digest.new_places));
+    context.insert("updated_places", &digest_items(&digest.updated_places));
+    templates.render(language, "notification_digest", &context)
+}
+
+// Comments may be long and are only quoted in the body
+const MAX_SUBJECT_TITLE_LEN: usize = 50;
+
+pub fn content_reported_email(
+    templates: &EmailTemplates,
+    language: Option<&str>,
+    report: &Report,
+    title: &str,
+) -> Result<EmailContent> {
+    let kind = match report.content_kind {
+        ContentKind::Place => "place",
+        ContentKind::Event => "event",
+        ContentKind::Comment => "comment",
+    };
+    let reason = match report.reason {
+        ReportReason::Closed => "closed",
+        ReportReason::Fraud => "fraud",
+        ReportReason::Duplicate => "duplicate",
+        ReportReason::Inappropriate => "inappropriate",
+        ReportReason::Inaccurate => "inaccurate",
+        ReportReason::Spam => "spam",
+        ReportReason::Offensive => "offensive",
+        ReportReason::Other => "other",
+    };
+    let subject_title: String = title.chars().take(MAX_SUBJECT_TITLE_LEN).collect();
+    let mut context = Context::new();
+    context.insert("kind", kind);
+    context.insert("reason", reason);
+    context.insert("title", title);
+    context.insert("subject_title", &subject_title);
+    context.insert("id", report.content_id.as_str());
+    context.insert("comment", &report.comment);
+    templates.render(language, "content_reported", &context)
+}
+
+pub fn event_created_email(
+    templates: &EmailTemplates,
+    language: Option<&str>,
+    event: &Event,
+) -> Result<EmailContent> {
+    templates.render(language, "event", &event_context(event, true))
 }
 
 //TODO: calc diff
-pub fn event_updated_email(event: &Event) -> EmailContent {
-    let subject = subject_entry_updated(&event.title);
-    let body = event_email(event, INTRO_ENTRY_UPDATED);
-    EmailContent { subject, body }
+pub fn event_updated_email(
+    templates: &EmailTemplates,
+    language: Option<&str>,
+    event: &Event,
+) -> Result<EmailContent> {
+    templates.render(language, "event", &event_context(event, false))
 }
 
-fn event_email(event: &Event, intro_sentence: &str) -> String {
+fn event_context(event: &Event, created: bool) -> Context {
     let Contact { email, phone } = event.contact.clone().unwrap_or_else(|| Contact {
         email: None,
         phone: None,
     });
 
-    format!(
-        "Hallo,\n
-{intro_sentence}:\n
-{title} ({category})
-{description}\n
-    Beginn: {start}
-    Ende: {end}
-    Tags: {tags}
-    Veranstalter: {organizer}
-    Adresse: {address_line}
-    Webseite: {homepage}
-    Email-Adresse: {email}
-    Telefon: {phone}\n
-Eintrag anschauen oder bearbeiten:
-https://kartevonmorgen.org/#/?entry={id}\n
-Du kannst dein Abonnement des Kartenbereichs abbestellen,
-indem du dich auf https://kartevonmorgen.org einloggst.\n
-euphorische Grüße,\n
-das Karte von morgen-Team\n
-{outro_text}",
-        intro_sentence = intro_sentence,
-        outro_text = OUTRO_HINT,
-        category = "Event",
-        id = &event.id,
-        title = &event.title,
-        start = event.start.format(DATE_TIME_FORMAT),
-        end = event
+    let mut context = Context::new();
+    context.insert("created", &created);
+    context.insert("id", event.id.as_str());
+    context.insert("title", &event.title);
+    context.insert("start", &event.start.format(DATE_TIME_FORMAT).to_string());
+    context.insert(
+        "end",
+        &event
             .end
             .map(|end| end.format(DATE_TIME_FORMAT).to_string())
             .unwrap_or_default(),
-        description = event.description.as_deref().unwrap_or(""),
-        organizer = event.organizer.as_deref().unwrap_or(""),
-        address_line = address_line(event.location.as_ref().and_then(|l| l.address.as_ref())),
-        email = email.map(|e| e.to_string()).unwrap_or_default(),
-        phone = phone.unwrap_or_default(),
-        homepage = event.homepage.as_ref().map(Url::as_str).unwrap_or(""),
-        tags = event.tags.join(", ")
-    )
+    );
+    context.insert("description", event.description.as_deref().unwrap_or(""));
+    context.insert("organizer", event.organizer.as_deref().unwrap_or(""));
+    context.insert(
+        "address_line",
+        &address_line(event.location.as_ref().and_then(|l| l.address.as_ref())),
+    );
+    context.insert("email", &email.map(|e| e.to_string()).unwrap_or_default());
+    context.insert("phone", &phone.unwrap_or_default());
+    context.insert(
+        "homepage",
+        event.homepage.as_ref().map(Url::as_str).unwrap_or(""),
+    );
+    context.insert("tags", &event.tags.join(", "));
+    context
 }
 
 #[cfg(test)]
@@ -242,6 +502,7 @@ mod tests {
                     state: Some("<state>".into()),
                 }),
             },
+            additional_locations: vec![],
             contact: Some(Contact {
                 email: Some("<email>".into()),
                 phone: Some("<phone>".into()),
@@ -252,6 +513,7 @@ mod tests {
                 ..Default::default()
             }),
             tags: vec!["<tag1>".into(), "<tag2>".into()],
+            source: None,
         }
     }
 
@@ -283,14 +545,21 @@ mod tests {
             homepage: Some("https://kartevonmorgen.org".parse().unwrap()),
             image_url: None,
             image_link_url: None,
+            recurrence: None,
+            capacity: None,
             tags: vec!["<tag1>".into(), "<tag2>".into()],
         }
     }
 
+    const OUTRO_HINT: &str = "https://blog.vonmorgen.org";
+
     #[test]
     fn print_user_registration_email() {
+        let templates = EmailTemplates::default();
         let url = "https://kartevonmorgen.org/confirm-email/";
-        let email = user_registration_email(url);
+        let email = user_registration_email(&templates, None, url).unwrap();
+        assert!(email.subject.contains("bestätige"));
+        assert!(email.body.starts_with("Na du Weltverbesserer*"));
         assert!(email.body.contains(OUTRO_HINT));
         assert!(email.body.contains(url));
         print_email(&email);
@@ -298,28 +567,34 @@ mod tests {
 
     #[test]
     fn print_user_reset_password_email() {
+        let templates = EmailTemplates::default();
         let url = "https://kartevonmorgen.org/reset-password/";
-        let email = user_reset_password_email(url);
+        let email = user_reset_password_email(&templates, None, url).unwrap();
         assert!(email.body.contains(url));
         print_email(&email);
     }
 
     #[test]
     fn print_place_created_email() {
+        let templates = EmailTemplates::default();
         let place = new_place();
-        let email = place_created_email(&place, &["<category>".into()]);
-        assert!(email.body.contains(INTRO_ENTRY_CREATED));
+        let email = place_created_email(&templates, None, &place, &["<category>".into()]).unwrap();
+        assert!(email.subject.contains("neuer Eintrag"));
+        assert!(email.body.contains("wurde erstellt"));
         assert!(email.body.contains(OUTRO_HINT));
         assert!(email.body.contains(place.id.as_str()));
         assert!(email.body.contains(&place.title));
+        assert!(email.body.contains("<tag1>, <tag2>"));
         print_email(&email);
     }
 
     #[test]
     fn print_place_updated_email() {
+        let templates = EmailTemplates::default();
         let place = new_place();
-        let email = place_updated_email(&place, &["<category>".into()]);
-        assert!(email.body.contains(INTRO_ENTRY_UPDATED));
+        let email = place_updated_email(&templates, None, &place, &["<category>".into()]).unwrap();
+        assert!(email.subject.contains("Eintrag verändert"));
+        assert!(email.body.contains("wurde verändert"));
         assert!(email.body.contains(OUTRO_HINT));
         assert!(email.body.contains(place.id.as_str()));
         assert!(email.body.contains(&place.title));
@@ -328,9 +603,10 @@ mod tests {
 
     #[test]
     fn print_event_created_email() {
+        let templates = EmailTemplates::default();
         let event = new_event();
-        let email = event_created_email(&event);
-        assert!(email.body.contains(INTRO_ENTRY_CREATED));
+        let email = event_created_email(&templates, None, &event).unwrap();
+        assert!(email.body.contains("wurde erstellt"));
         assert!(email.body.contains(OUTRO_HINT));
         assert!(email.body.contains(event.id.as_str()));
         assert!(email.body.contains(&event.title));
@@ -339,12 +615,168 @@ mod tests {
 
     #[test]
     fn print_event_updated_email() {
+        let templates = EmailTemplates::default();
         let event = new_event();
-        let email = event_updated_email(&event);
-        assert!(email.body.contains(INTRO_ENTRY_UPDATED));
+        let email = event_updated_email(&templates, None, &event).unwrap();
+        assert!(email.body.contains("wurde verändert"));
         assert!(email.body.contains(OUTRO_HINT));
         assert!(email.body.contains(event.id.as_str()));
         assert!(email.body.contains(&event.title));
         print_email(&email);
     }
+
+    #[test]
+    fn print_entry_archived_email() {
+        let templates = EmailTemplates::default();
+        let email = entry_archived_email(&templates, None, "<title>", Some("<comment>")).unwrap();
+        assert!(email.subject.contains("<title>"));
+        assert!(email.body.contains("<title>"));
+        assert!(email.body.contains("Begründung: <comment>"));
+        assert!(email.body.contains(OUTRO_HINT));
+        print_email(&email);
+        let email = entry_archived_email(&templates, None, "<title>", None).unwrap();
+        assert!(!email.body.contains("Begründung"));
+        print_email(&email);
+    }
+
+    #[test]
+    fn print_event_registration_email() {
+        let templates = EmailTemplates::default();
+        let email = event_registration_email(
+            &templates,
+            None,
+            "<title>",
+            EventRegistrationStatus::Confirmed,
+            "<url>",
+        )
+        .unwrap();
+        assert!(email.subject.contains("<title>"));
+        assert!(email.body.contains("<url>"));
+        assert!(!email.body.contains("Warteliste"));
+        print_email(&email);
+        let email = event_registration_email(
+            &templates,
+            None,
+            "<title>",
+            EventRegistrationStatus::Waitlisted,
+            "<url>",
+        )
+        .unwrap();
+        assert!(email.body.contains("Warteliste"));
+        print_email(&email);
+        let email =
+            event_registration_promoted_email(&templates, None, "<title>", "<url>").unwrap();
+        assert!(email.body.contains("<url>"));
+        print_email(&email);
+    }
+
+    #[test]
+    fn print_content_reported_email() {
+        let templates = EmailTemplates::default();
+        let report = Report {
+            id: "<report-id>".into(),
+            content_kind: ContentKind::Comment,
+            content_id: "<comment-id>".into(),
+            reason: ReportReason::Spam,
+            comment: Some("<comment>".into()),
+            created: Activity::now(None),
+        };
+        let email = content_reported_email(&templates, None, &report, &"x".repeat(100)).unwrap();
+        assert!(email.subject.contains("Kommentar"));
+        assert!(!email.subject.contains(&"x".repeat(51)));
+        assert!(email.body.contains(&"x".repeat(100)));
+        assert!(email.body.contains("<comment-id>"));
+        assert!(email.body.contains("Spam"));
+        assert!(email.body.contains("Kommentar: <comment>"));
+        print_email(&email);
+    }
+
+    #[test]
+    fn print_scout_report_email() {
+        let templates = EmailTemplates::default();
+        let report = ScoutReport {
+            since: TimestampMs::from_seconds(1_592_000_000),
+            new_places: vec![ScoutReportItem {
+                place_id: "<new-id>".into(),
+                title: "<new-title>".into(),
+            }],
+            reported_places: vec![],
+            stale_places: vec![ScoutReportItem {
+                place_id: "<stale-id>".into(),
+                title: "<stale-title>".into(),
+            }],
+        };
+        let email = scout_report_email(&templates, None, &report).unwrap();
+        assert!(email.subject.contains("2020.06.12"));
+        assert!(email.body.contains("<new-id>"));
+        assert!(email.body.contains("<new-title>"));
+        assert!(email.body.contains("<stale-id>"));
+        assert!(!email.body.contains("Gemeldete"));
+        assert!(email.body.contains("abbestellen"));
+        print_email(&email);
+    }
+
+    #[test]
+    fn print_notification_digest_email() {
+        let templates = EmailTemplates::default();
+        let digest = NotificationDigest {
+            since: TimestampMs::from_seconds(1_592_000_000),
+            interval: DigestInterval::Weekly,
+            new_places: vec![],
+            updated_places: vec![DigestItem {
+                place_id: "<updated-id>".into(),
+                title: "<updated-title>".into(),
+            }],
+        };
+        let email = notification_digest_email(&templates, None, &digest).unwrap();
+        assert!(email.subject.contains("Wöchentliche"));
+        assert!(email.subject.contains("2020.06.12"));
+        assert!(email.body.contains("<updated-id>"));
+        assert!(email.body.contains("<updated-title>"));
+        assert!(!email.body.contains("Neue Einträge"));
+        print_email(&email);
+    }
+
+    #[test]
+    fn select_language_of_recipient() {
+        let templates = EmailTemplates::default();
+        let email = user_login_link_email(&templates, Some("en"), "<url>").unwrap();
+        assert_eq!("Karte von morgen: Your login link", email.subject);
+        assert!(email.body.starts_with("Hello world changer"));
+        print_email(&email);
+        // Unknown languages fall back to the default language
+        let email = user_login_link_email(&templates, Some("xx"), "<url>").unwrap();
+        assert_eq!("Karte von morgen: Dein Anmeldelink", email.subject);
+        let templates = EmailTemplates::new("en");
+        let email = user_login_link_email(&templates, None, "<url>").unwrap();
+        assert_eq!("Karte von morgen: Your login link", email.subject);
+    }
+
+    #[test]
+    fn load_custom_templates() {
+        let dir = std::env::temp_dir().join(format!("ofdb-email-templates-{}", std::process::id()));
+        fs::create_dir_all(dir.join("fr")).unwrap();
+        fs::create_dir_all(dir.join("de")).unwrap();
+        fs::write(
+            dir.join("fr").join("user_login_link.txt"),
+            "Votre lien de connexion\n\nBonjour,\n\n{{ url }}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("de").join("user_reset_password.txt"),
+            "Neues Passwort\n\n{{ url }}\n",
+        )
+        .unwrap();
+        let mut templates = EmailTemplates::default();
+        let count = templates.load_dir(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(Ok(2), count);
+
+        let email = user_login_link_email(&templates, Some("fr"), "<url>").unwrap();
+        assert_eq!("Votre lien de connexion", email.subject);
+        assert_eq!("Bonjour,\n\n<url>", email.body);
+        let email = user_reset_password_email(&templates, Some("fr"), "<url>").unwrap();
+        assert_eq!("Neues Passwort", email.subject);
+        assert_eq!("<url>", email.body);
+    }
 }
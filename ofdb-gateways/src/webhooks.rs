@@ -0,0 +1,68 @@
+use crate::crypto::{hex, hmac_sha256};
+use ofdb_core::WebhookGateway;
+use ofdb_entities::webhook::*;
+use std::{
+    io::{Error, ErrorKind, Result},
+    time::Duration,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The header that contains the HMAC-SHA256 of the body
+/// signed with the secret of the webhook.
+pub const SIGNATURE_HEADER: &str = "x-ofdb-signature";
+
+pub const EVENT_HEADER: &str = "x-ofdb-event";
+
+pub const DELIVERY_HEADER: &str = "x-ofdb-delivery";
+
+/// Posts the payload of deliveries as JSON to webhooks.
+#[derive(Debug, Clone, Default)]
+pub struct HttpWebhooks;
+
+fn signature(secret: &str, payload: &str) -> String {
+    format!(
+        "sha256={}",
+        hex(&hmac_sha256(secret.as_bytes(), payload.as_bytes()))
+    )
+}
+
+impl WebhookGateway for HttpWebhooks {
+    fn deliver_webhook(&self, webhook: &Webhook, delivery: &WebhookDelivery) -> Result<u16> {
+        debug!(
+            "Delivering {} to webhook {}",
+            delivery.event.as_str(),
+            webhook.url
+        );
+        let res = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|err| Error::new(ErrorKind::Other, err))?
+            .post(webhook.url.as_str())
+            .header("content-type", "application/json")
+            .header(EVENT_HEADER, delivery.event.as_str())
+            .header(DELIVERY_HEADER, delivery.id.as_str())
+            .header(
+                SIGNATURE_HEADER,
+                signature(&webhook.secret, &delivery.payload),
+            )
+            .body(delivery.payload.clone())
+            .send()
+            .map_err(|err| Error::new(ErrorKind::Other, err))?;
+        Ok(res.status().as_u16())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_payload() {
+        // Verified with `echo -n '{}' | openssl dgst -sha256 -hmac secret`
+        assert_eq!(
+            "sha256=77325902caca812dc259733aacd046b73817372c777b8d95b402647474516e13",
+            signature("secret", "{}")
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::core::{db::RecentlyChangedPlace, entities::*};
+
+use anyhow::Result as Fallible;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::fmt::Write;
+
+pub const MEDIA_TYPE: &str = "application/atom+xml; charset=utf-8";
+
+const FEED_ID: &str = "urn:openfairdb:feed:entries";
+
+const FEED_TITLE: &str = "Recently changed entries";
+
+const ENTRY_URL_PREFIX: &str = "https://kartevonmorgen.org/#/?entry=";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn format_timestamp(at: TimestampMs) -> String {
+    DateTime::<Utc>::from(at).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// An Atom feed with an entry for each change of a place,
+/// newest first.
+pub fn places_feed(changes: Vec<RecentlyChangedPlace>) -> Fallible<String> {
+    let updated = changes
+        .first()
+        .map(|(_, _, log, _)| log.activity.at)
+        .unwrap_or_else(TimestampMs::now);
+    let mut feed = String::from(concat!(
+        r#"<?xml version="1.0" encoding="utf-8"?>"#,
+        "\n",
+        r#"<feed xmlns="http://www.w3.org/2005/Atom">"#,
+        "\n",
+    ));
+    writeln!(feed, "<id>{}</id>", FEED_ID)?;
+    writeln!(feed, "<title>{}</title>", FEED_TITLE)?;
+    writeln!(feed, "<updated>{}</updated>", format_timestamp(updated))?;
+    for (place, _, log, _) in changes {
+        let link = format!("{}{}", ENTRY_URL_PREFIX, place.id);
+        feed.push_str("<entry>\n");
+        // Every revision is a separate entry of the feed
+        writeln!(
+            feed,
+            "<id>urn:openfairdb:entry:{}:{}</id>",
+            escape(place.id.as_str()),
+            u64::from(place.revision)
+        )?;
+        writeln!(feed, "<title>{}</title>", escape(&place.title))?;
+        writeln!(feed, r#"<link href="{}"/>"#, escape(&link))?;
+        writeln!(
+            feed,
+            "<updated>{}</updated>",
+            format_timestamp(log.activity.at)
+        )?;
+        writeln!(feed, "<summary>{}</summary>", escape(&place.description))?;
+        feed.push_str("</entry>\n");
+    }
+    feed.push_str("</feed>\n");
+    Ok(feed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::db::RecentlyChangedEntriesCursor;
+
+    #[test]
+    fn escape_places_in_feed() {
+        let place = Place::build()
+            .id("foo")
+            .title("Bread & butter")
+            .description("<b>fresh</b>")
+            .finish();
+        let log = ActivityLog {
+            activity: Activity {
+                at: TimestampMs::from_seconds(1_593_000_000),
+                by: None,
+            },
+            context: None,
+            comment: None,
+        };
+        let cursor = RecentlyChangedEntriesCursor {
+            created_at: log.activity.at,
+            log_id: 1,
+        };
+        let feed = places_feed(vec![(place, ReviewStatus::Created, log, cursor)]).unwrap();
+        assert!(feed.contains("<updated>2020-06-24T12:00:00Z</updated>"));
+        assert!(feed.contains("<title>Bread &amp; butter</title>"));
+        assert!(feed.contains("<summary>&lt;b&gt;fresh&lt;/b&gt;</summary>"));
+        assert!(feed.contains(r#"<link href="https://kartevonmorgen.org/#/?entry=foo"/>"#));
+        assert!(feed.ends_with("</feed>\n"));
+    }
+}
@@ -1,5 +1,7 @@
-use crate::core::{entities::*, util::time::Timestamp};
+use crate::core::{entities::*, error::ParameterError, util::time::Timestamp};
 
+use anyhow::Result as Fallible;
+use std::convert::TryFrom;
 use url::Url;
 
 #[derive(Debug, Serialize)]
@@ -27,6 +29,8 @@ pub struct CsvRecord {
     pub image_url: Option<String>,
     pub image_link_url: Option<String>,
     pub avg_rating: f64,
+    // Assigned when exporting the record
+    pub dataset_license: Option<String>,
 }
 
 impl From<(Place, Vec<Category>, AvgRatingValue)> for CsvRecord {
@@ -107,6 +111,7 @@ impl From<(Place, Vec<Category>, AvgRatingValue)> for CsvRecord {
             categories,
             tags: tags.join(","),
             avg_rating: avg_rating.into(),
+            dataset_license: None,
         }
     }
 }
@@ -133,6 +138,8 @@ pub struct EventRecord {
     pub image_url: Option<String>,
     pub image_link_url: Option<String>,
     pub tags: String,
+    // Assigned when exporting the record
+    pub dataset_license: Option<String>,
 }
 
 impl From<Event> for EventRecord {
@@ -199,6 +206,230 @@ impl From<Event> for EventRecord {
             image_url: image_url.map(Url::into_string),
             image_link_url: image_link_url.map(Url::into_string),
             tags: tags.join(","),
+            dataset_license: None,
         }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BboxSubscriptionRecord {
+    // A new id is generated if empty
+    #[serde(default)]
+    pub id: String,
+    pub user_email: String,
+    pub south_west_lat: f64,
+    pub south_west_lng: f64,
+    pub north_east_lat: f64,
+    pub north_east_lng: f64,
+}
+
+impl From<BboxSubscription> for BboxSubscriptionRecord {
+    fn from(from: BboxSubscription) -> Self {
+        let BboxSubscription {
+            id,
+            user_email,
+            bbox,
+        } = from;
+        let (south_west_lat, south_west_lng) = bbox.south_west().to_lat_lng_deg();
+        let (north_east_lat, north_east_lng) = bbox.north_east().to_lat_lng_deg();
+        Self {
+            id: id.into(),
+            user_email,
+            south_west_lat,
+            south_west_lng,
+            north_east_lat,
+            north_east_lng,
+        }
+    }
+}
+
+impl TryFrom<BboxSubscriptionRecord> for BboxSubscription {
+    type Error = ParameterError;
+
+    fn try_from(from: BboxSubscriptionRecord) -> Result<Self, Self::Error> {
+        let BboxSubscriptionRecord {
+            id,
+            user_email,
+            south_west_lat,
+            south_west_lng,
+            north_east_lat,
+            north_east_lng,
+        } = from;
+        let south_west = MapPoint::try_from_lat_lng_deg(south_west_lat, south_west_lng)
+            .ok_or(ParameterError::Bbox)?;
+        let north_east = MapPoint::try_from_lat_lng_deg(north_east_lat, north_east_lng)
+            .ok_or(ParameterError::Bbox)?;
+        let id = if id.trim().is_empty() {
+            Id::new()
+        } else {
+            id.trim().into()
+        };
+        Ok(Self {
+            id,
+            user_email: user_email.trim().to_owned(),
+            bbox: MapBbox::new(south_west, north_east),
+        })
+    }
+}
+
+pub fn write_bbox_subscriptions(subscriptions: Vec<BboxSubscription>) -> Fallible<String> {
+    let mut wtr = ::csv::Writer::from_writer(vec![]);
+    for s in subscriptions {
+        wtr.serialize(BboxSubscriptionRecord::from(s))?;
+    }
+    wtr.flush()?;
+    Ok(String::from_utf8(wtr.into_inner()?)?)
+}
+
+/// Parses comma-separated values with a header row
+/// like they are written by `write_bbox_subscriptions`.
+pub fn read_bbox_subscriptions(csv: &str) -> Result<Vec<BboxSubscription>, ParameterError> {
+    ::csv::Reader::from_reader(csv.as_bytes())
+        .deserialize::<BboxSubscriptionRecord>()
+        .map(|record| {
+            let record = record.map_err(|err| {
+                log::warn!("Failed to read subscription: {}", err);
+                ParameterError::InvalidCsv
+            })?;
+            BboxSubscription::try_from(record)
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserRecord {
+    pub email: String,
+    // Regular users if empty
+    #[serde(default)]
+    pub role: String,
+}
+
+fn parse_role(role: &str) -> Result<Role, ParameterError> {
+    match role.trim().to_lowercase().as_str() {
+        "" | "user" => Ok(Role::User),
+        "scout" => Ok(Role::Scout),
+        "admin" => Ok(Role::Admin),
+        _ => Err(ParameterError::InvalidRole),
+    }
+}
+
+/// Parses the e-mail addresses and roles of users from
+/// comma-separated values with the header row `email,role`.
+pub fn read_users(csv: &str) -> Result<Vec<(String, Role)>, ParameterError> {
+    ::csv::Reader::from_reader(csv.as_bytes())
+        .deserialize::<UserRecord>()
+        .map(|record| {
+            let UserRecord { email, role } = record.map_err(|err| {
+                log::warn!("Failed to read user: {}", err);
+                ParameterError::InvalidCsv
+            })?;
+            Ok((email, parse_role(&role)?))
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TagSynonymRecord {
+    pub synonym: String,
+    pub tag: String,
+}
+
+/// Parses pairs of synonyms and the tags they stand for from
+/// comma-separated values with the header row `synonym,tag`.
+pub fn read_tag_synonyms(csv: &str) -> Result<Vec<(String, String)>, ParameterError> {
+    ::csv::Reader::from_reader(csv.as_bytes())
+        .deserialize::<TagSynonymRecord>()
+        .map(|record| {
+            let TagSynonymRecord { synonym, tag } = record.map_err(|err| {
+                log::warn!("Failed to read tag synonym: {}", err);
+                ParameterError::InvalidCsv
+            })?;
+            Ok((synonym, tag))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_and_read_bbox_subscriptions() {
+        let subscriptions = vec![BboxSubscription {
+            id: "s".into(),
+            user_email: "user@example.com".into(),
+            bbox: MapBbox::new(
+                MapPoint::from_lat_lng_deg(1.0, 2.0),
+                MapPoint::from_lat_lng_deg(3.0, 4.0),
+            ),
+        }];
+        let csv = write_bbox_subscriptions(subscriptions.clone()).unwrap();
+        assert!(csv.starts_with(
+            "id,user_email,south_west_lat,south_west_lng,north_east_lat,north_east_lng\n"
+        ));
+        assert_eq!(subscriptions, read_bbox_subscriptions(&csv).unwrap());
+    }
+
+    #[test]
+    fn read_bbox_subscriptions_without_id() {
+        let csv = "user_email,south_west_lat,south_west_lng,north_east_lat,north_east_lng\n\
+                   user@example.com,1,2,3,4\n";
+        let subscriptions = read_bbox_subscriptions(csv).unwrap();
+        assert_eq!(1, subscriptions.len());
+        assert!(!subscriptions[0].id.as_str().is_empty());
+        assert!(matches!(
+            read_bbox_subscriptions("user_email\nuser@example.com\n"),
+            Err(ParameterError::InvalidCsv)
+        ));
+        assert!(matches!(
+            read_bbox_subscriptions(&csv.replace(",1,", ",100,")),
+            Err(ParameterError::Bbox)
+        ));
+    }
+
+    #[test]
+    fn read_users_with_roles() {
+        let csv = "email,role\n\
+                   user@example.com,\n\
+                   scout@example.com,Scout\n\
+                   admin@example.com,admin\n";
+        assert_eq!(
+            vec![
+                ("user@example.com".to_string(), Role::User),
+                ("scout@example.com".to_string(), Role::Scout),
+                ("admin@example.com".to_string(), Role::Admin),
+            ],
+            read_users(csv).unwrap()
+        );
+        assert_eq!(
+            vec![("user@example.com".to_string(), Role::User)],
+            read_users("email\nuser@example.com\n").unwrap()
+        );
+        assert!(matches!(
+            read_users("email,role\nuser@example.com,guest\n"),
+            Err(ParameterError::InvalidRole)
+        ));
+        assert!(matches!(
+            read_users("name\nuser\n"),
+            Err(ParameterError::InvalidCsv)
+        ));
+    }
+
+    #[test]
+    fn read_synonyms_of_tags() {
+        let csv = "synonym,tag\n\
+                   organic,bio\n\
+                   \"fair trade\",fairtrade\n";
+        assert_eq!(
+            vec![
+                ("organic".to_string(), "bio".to_string()),
+                ("fair trade".to_string(), "fairtrade".to_string()),
+            ],
+            read_tag_synonyms(csv).unwrap()
+        );
+        assert!(matches!(
+            read_tag_synonyms("synonym\norganic\n"),
+            Err(ParameterError::InvalidCsv)
+        ));
+    }
+}
@@ -0,0 +1,485 @@
+use super::csv::{CsvRecord, EventRecord};
+use crate::core::entities::*;
+
+use anyhow::Result as Fallible;
+use serde_json::{json, Value};
+use std::fmt::Write;
+
+/// A place with its categories and average total rating
+pub type PlaceExport = (Place, Vec<Category>, AvgRatingValue);
+
+/// A file format for exporting places and events.
+pub trait ExportFormat: Sync {
+    /// The file extension, e.g. `csv`
+    fn extension(&self) -> &'static str;
+
+    fn media_type(&self) -> &'static str;
+
+    /// Export places together with their individual license
+    /// and the license of the whole dataset.
+    fn export_places(
+        &self,
+        places: Vec<PlaceExport>,
+        dataset_license: Option<&str>,
+    ) -> Fallible<String>;
+
+    /// Export events together with the license of the whole dataset.
+    fn export_events(&self, events: Vec<Event>, dataset_license: Option<&str>) -> Fallible<String>;
+}
+
+/// All available export formats.
+///
+/// A format that is registered here becomes available
+/// for all exports.
+pub static EXPORT_FORMATS: &[&dyn ExportFormat] = &[&Csv, &GeoJson, &Kml, &JsonLines];
+
+pub fn export_format_by_extension(extension: &str) -> Option<&'static dyn ExportFormat> {
+    EXPORT_FORMATS
+        .iter()
+        .copied()
+        .find(|format| format.extension().eq_ignore_ascii_case(extension))
+}
+
+// The flat representation of an exported place or event
+trait Record: serde::Serialize {
+    fn id(&self) -> &str;
+    fn title(&self) -> &str;
+    fn description(&self) -> Option<&str>;
+    // (lat, lng) in degrees
+    fn position(&self) -> Option<(f64, f64)>;
+    // The license of this particular record
+    fn license(&self) -> Option<&str>;
+}
+
+impl Record for CsvRecord {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn title(&self) -> &str {
+        &self.title
+    }
+    fn description(&self) -> Option<&str> {
+        Some(&self.description)
+    }
+    fn position(&self) -> Option<(f64, f64)> {
+        Some((self.lat, self.lng))
+    }
+    fn license(&self) -> Option<&str> {
+        Some(&self.license)
+    }
+}
+
+impl Record for EventRecord {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn title(&self) -> &str {
+        &self.title
+    }
+    fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+    fn position(&self) -> Option<(f64, f64)> {
+        match (self.lat, self.lng) {
+            (Some(lat), Some(lng)) => Some((lat, lng)),
+            _ => None,
+        }
+    }
+    fn license(&self) -> Option<&str> {
+        None
+    }
+}
+
+// All formats must use these functions to ensure that every
+// exported record carries the license of the dataset
+fn place_records(places: Vec<PlaceExport>, dataset_license: Option<&str>) -> Vec<CsvRecord> {
+    places
+        .into_iter()
+        .map(|place| CsvRecord {
+            dataset_license: dataset_license.map(ToOwned::to_owned),
+            ..CsvRecord::from(place)
+        })
+        .collect()
+}
+
+fn event_records(events: Vec<Event>, dataset_license: Option<&str>) -> Vec<EventRecord> {
+    events
+        .into_iter()
+        .map(|event| EventRecord {
+            dataset_license: dataset_license.map(ToOwned::to_owned),
+            ..EventRecord::from(event)
+        })
+        .collect()
+}
+
+/// Comma-separated values with a header row
+pub struct Csv;
+
+impl Csv {
+    fn write<R: Record>(records: Vec<R>) -> Fallible<String> {
+        let mut wtr = ::csv::Writer::from_writer(vec![]);
+        for r in records {
+            wtr.serialize(r)?;
+        }
+        wtr.flush()?;
+        Ok(String::from_utf8(wtr.into_inner()?)?)
+    }
+
+    /// Export places in consecutive chunks, e.g. for streaming
+    /// large exports. Only the first chunk starts with the header row.
+    pub fn export_places_chunk(
+        places: Vec<PlaceExport>,
+        dataset_license: Option<&str>,
+        first_chunk: bool,
+    ) -> Fallible<Vec<u8>> {
+        let mut wtr = ::csv::WriterBuilder::new()
+            .has_headers(first_chunk)
+            .from_writer(vec![]);
+        for r in place_records(places, dataset_license) {
+            wtr.serialize(r)?;
+        }
+        wtr.flush()?;
+        Ok(wtr.into_inner()?)
+    }
+}
+
+impl ExportFormat for Csv {
+    fn extension(&self) -> &'static str {
+        "csv"
+    }
+    fn media_type(&self) -> &'static str {
+        "text/csv; charset=utf-8"
+    }
+    fn export_places(
+        &self,
+        places: Vec<PlaceExport>,
+        dataset_license: Option<&str>,
+    ) -> Fallible<String> {
+        Self::write(place_records(places, dataset_license))
+    }
+    fn export_events(&self, events: Vec<Event>, dataset_license: Option<&str>) -> Fallible<String> {
+        Self::write(event_records(events, dataset_license))
+    }
+}
+
+/// A GeoJSON feature collection with point geometries
+pub struct GeoJson;
+
+impl GeoJson {
+    fn feature<R: Record>(record: R) -> Fallible<Value> {
+        let geometry = record
+            .position()
+            .map(|(lat, lng)| json!({ "type": "Point", "coordinates": [lng, lat] }));
+        let id = record.id().to_owned();
+        let mut properties = serde_json::to_value(record)?;
+        if let Some(properties) = properties.as_object_mut() {
+            // Already contained in the geometry
+            properties.remove("lat");
+            properties.remove("lng");
+        }
+        Ok(json!({
+            "type": "Feature",
+            "id": id,
+            "geometry": geometry,
+            "properties": properties,
+        }))
+    }
+
+    fn write<R: Record>(records: Vec<R>, dataset_license: Option<&str>) -> Fallible<String> {
+        let features = records
+            .into_iter()
+            .map(Self::feature)
+            .collect::<Fallible<Vec<_>>>()?;
+        let mut collection = json!({
+            "type": "FeatureCollection",
+            "features": features,
+        });
+        if let Some(license) = dataset_license {
+            // A foreign member of the feature collection
+            collection["license"] = license.into();
+        }
+        Ok(serde_json::to_string(&collection)?)
+    }
+}
+
+impl ExportFormat for GeoJson {
+    fn extension(&self) -> &'static str {
+        "geojson"
+    }
+    fn media_type(&self) -> &'static str {
+        "application/geo+json"
+    }
+    fn export_places(
+        &self,
+        places: Vec<PlaceExport>,
+        dataset_license: Option<&str>,
+    ) -> Fallible<String> {
+        Self::write(place_records(places, dataset_license), dataset_license)
+    }
+    fn export_events(&self, events: Vec<Event>, dataset_license: Option<&str>) -> Fallible<String> {
+        Self::write(event_records(events, dataset_license), dataset_license)
+    }
+}
+
+/// A KML document with a placemark for each record
+pub struct Kml;
+
+impl Kml {
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;")
+    }
+
+    fn write<R: Record>(records: Vec<R>, dataset_license: Option<&str>) -> Fallible<String> {
+        let mut kml = String::from(concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            "\n",
+            r#"<kml xmlns="http://www.opengis.net/kml/2.2">"#,
+            "\n<Document>\n"
+        ));
+        if let Some(license) = dataset_license {
+            writeln!(
+                kml,
+                r#"<ExtendedData><Data name="license"><value>{}</value></Data></ExtendedData>"#,
+                Self::escape(license)
+            )?;
+        }
+        for r in records {
+            write!(kml, "<Placemark><name>{}</name>", Self::escape(r.title()))?;
+            if let Some(description) = r.description() {
+                write!(
+                    kml,
+                    "<description>{}</description>",
+                    Self::escape(description)
+                )?;
+            }
+            write!(
+                kml,
+                r#"<ExtendedData><Data name="id"><value>{}</value></Data>"#,
+                Self::escape(r.id())
+            )?;
+            if let Some(license) = r.license() {
+                write!(
+                    kml,
+                    r#"<Data name="license"><value>{}</value></Data>"#,
+                    Self::escape(license)
+                )?;
+            }
+            kml.push_str("</ExtendedData>");
+            if let Some((lat, lng)) = r.position() {
+                write!(
+                    kml,
+                    "<Point><coordinates>{},{}</coordinates></Point>",
+                    lng, lat
+                )?;
+            }
+            kml.push_str("</Placemark>\n");
+        }
+        kml.push_str("</Document>\n</kml>\n");
+        Ok(kml)
+    }
+}
+
+impl ExportFormat for Kml {
+    fn extension(&self) -> &'static str {
+        "kml"
+    }
+    fn media_type(&self) -> &'static str {
+        "application/vnd.google-earth.kml+xml"
+    }
+    fn export_places(
+        &self,
+        places: Vec<PlaceExport>,
+        dataset_license: Option<&str>,
+    ) -> Fallible<String> {
+        Self::write(place_records(places, dataset_license), dataset_license)
+    }
+    fn export_events(&self, events: Vec<Event>, dataset_license: Option<&str>) -> Fallible<String> {
+        Self::write(event_records(events, dataset_license), dataset_license)
+    }
+}
+
+/// One JSON object per line
+pub struct JsonLines;
+
+impl JsonLines {
+    fn write<R: Record>(records: Vec<R>) -> Fallible<String> {
+        let mut lines = String::new();
+        for r in records {
+            lines.push_str(&serde_json::to_string(&r)?);
+            lines.push('\n');
+        }
+        Ok(lines)
+    }
+}
+
+impl ExportFormat for JsonLines {
+    fn extension(&self) -> &'static str {
+        "jsonl"
+    }
+    fn media_type(&self) -> &'static str {
+        "application/x-ndjson"
+    }
+    fn export_places(
+        &self,
+        places: Vec<PlaceExport>,
+        dataset_license: Option<&str>,
+    ) -> Fallible<String> {
+        Self::write(place_records(places, dataset_license))
+    }
+    fn export_events(&self, events: Vec<Event>, dataset_license: Option<&str>) -> Fallible<String> {
+        Self::write(event_records(events, dataset_license))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_event(title: &str, pos: Option<MapPoint>) -> Event {
+        Event {
+            id: Id::new(),
+            title: title.into(),
+            description: Some("<b>Bring & share</b>".into()),
+            start: chrono::NaiveDateTime::from_timestamp(0, 0),
+            end: None,
+            location: pos.map(|pos| Location { pos, address: None }),
+            contact: None,
+            tags: vec![],
+            homepage: None,
+            created_by: None,
+            registration: None,
+            organizer: None,
+            archived: None,
+            image_url: None,
+            image_link_url: None,
+            recurrence: None,
+            capacity: None,
+        }
+    }
+
+    #[test]
+    fn find_formats_by_extension() {
+        for format in EXPORT_FORMATS {
+            assert_eq!(
+                format.extension(),
+                export_format_by_extension(format.extension())
+                    .unwrap()
+                    .extension()
+            );
+        }
+        assert_eq!(
+            "geojson",
+            export_format_by_extension("GeoJSON").unwrap().extension()
+        );
+        assert!(export_format_by_extension("pdf").is_none());
+    }
+
+    #[test]
+    fn export_events_as_geojson() {
+        let pos = MapPoint::from_lat_lng_deg(1.0, 2.0);
+        let events = vec![new_event("located", Some(pos)), new_event("nowhere", None)];
+        let geojson: Value =
+            serde_json::from_str(&GeoJson.export_events(events, None).unwrap()).unwrap();
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(2, features.len());
+        assert_eq!(
+            json!([pos.lng().to_deg(), pos.lat().to_deg()]),
+            features[0]["geometry"]["coordinates"]
+        );
+        assert_eq!("located", features[0]["properties"]["title"]);
+        assert!(features[0]["properties"].get("lat").is_none());
+        assert!(features[1]["geometry"].is_null());
+    }
+
+    #[test]
+    fn export_events_as_kml() {
+        let pos = MapPoint::from_lat_lng_deg(1.0, 2.0);
+        let events = vec![new_event("located", Some(pos))];
+        let kml = Kml.export_events(events, None).unwrap();
+        assert!(kml.contains("<name>located</name>"));
+        assert!(kml.contains("<description>&lt;b&gt;Bring &amp; share&lt;/b&gt;</description>"));
+        assert!(kml.contains(&format!(
+            "<coordinates>{},{}</coordinates>",
+            pos.lng().to_deg(),
+            pos.lat().to_deg()
+        )));
+    }
+
+    #[test]
+    fn export_events_as_json_lines() {
+        let events = vec![new_event("a", None), new_event("b", None)];
+        let lines = JsonLines.export_events(events, None).unwrap();
+        let titles: Vec<_> = lines
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap()["title"].clone())
+            .collect();
+        assert_eq!(vec![json!("a"), json!("b")], titles);
+    }
+
+    #[test]
+    fn export_license_of_places_and_dataset() {
+        let place = || {
+            let mut place = Place::build().id("foo").title("Foo").finish();
+            place.license = "CC0-1.0".into();
+            (place, vec![], AvgRatingValue::from(0.0))
+        };
+        let dataset_license = Some("ODbL-1.0");
+
+        let csv = Csv.export_places(vec![place()], dataset_license).unwrap();
+        let mut rdr = ::csv::Reader::from_reader(csv.as_bytes());
+        let headers = rdr.headers().unwrap().clone();
+        let record = rdr.records().next().unwrap().unwrap();
+        let column = |name| &record[headers.iter().position(|h| h == name).unwrap()];
+        assert_eq!("CC0-1.0", column("license"));
+        assert_eq!("ODbL-1.0", column("dataset_license"));
+
+        let geojson: Value = serde_json::from_str(
+            &GeoJson
+                .export_places(vec![place()], dataset_license)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!("ODbL-1.0", geojson["license"]);
+        let properties = &geojson["features"][0]["properties"];
+        assert_eq!("CC0-1.0", properties["license"]);
+        assert_eq!("ODbL-1.0", properties["dataset_license"]);
+
+        let kml = Kml.export_places(vec![place()], dataset_license).unwrap();
+        assert!(kml.contains(r#"<Data name="license"><value>ODbL-1.0</value></Data>"#));
+        assert!(kml.contains(r#"<Data name="license"><value>CC0-1.0</value></Data>"#));
+
+        let line: Value = serde_json::from_str(
+            &JsonLines
+                .export_events(vec![new_event("a", None)], dataset_license)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!("ODbL-1.0", line["dataset_license"]);
+
+        // Without a dataset license
+        let geojson: Value =
+            serde_json::from_str(&GeoJson.export_places(vec![place()], None).unwrap()).unwrap();
+        assert!(geojson.get("license").is_none());
+        assert!(geojson["features"][0]["properties"]["dataset_license"].is_null());
+    }
+
+    #[test]
+    fn export_places_as_csv_in_chunks() {
+        let place = |id| {
+            let place = Place::build().id(id).title(id).finish();
+            (place, vec![], AvgRatingValue::from(0.0))
+        };
+        let mut chunks = Csv::export_places_chunk(vec![place("a")], None, true).unwrap();
+        chunks.extend(Csv::export_places_chunk(vec![place("b"), place("c")], None, false).unwrap());
+        assert_eq!(
+            Csv.export_places(vec![place("a"), place("b"), place("c")], None)
+                .unwrap()
+                .as_bytes(),
+            &chunks[..]
+        );
+    }
+}
@@ -1,64 +1,103 @@
-use crate::core::{db::IndexedPlace, entities as e};
+use crate::core::{
+    db::{IndexedPlace, PlaceCluster as IndexedPlaceCluster, TextSnippet},
+    entities as e, usecases,
+    util::quality,
+};
 use ofdb_entities::geo::MapPoint;
 use url::Url;
 
 pub use ofdb_boundary::*;
 
-impl From<IndexedPlace> for PlaceSearchResult {
-    fn from(from: IndexedPlace) -> Self {
-        let IndexedPlace {
-            id,
-            status,
-            title,
-            description,
-            tags,
-            pos,
-            ratings,
-            ..
-        } = from;
-        // The status should never be undefined! It is optional only
-        // for technical reasons.
-        debug_assert!(status.is_some());
-        let status = status.map(Into::into);
-        let (tags, categories) = e::Category::split_from_tags(tags);
-        let categories = categories.into_iter().map(|c| c.id.to_string()).collect();
-        let lat = pos.lat().to_deg();
-        let lng = pos.lng().to_deg();
-        let e::AvgRatings {
-            diversity,
-            fairness,
-            humanity,
-            renewable,
-            solidarity,
-            transparency,
-        } = ratings;
-        let total = ratings.total().into();
-        let ratings = EntrySearchRatings {
-            total,
-            diversity: diversity.into(),
-            fairness: fairness.into(),
-            humanity: humanity.into(),
-            renewable: renewable.into(),
-            solidarity: solidarity.into(),
-            transparency: transparency.into(),
-        };
-        Self {
-            id,
-            status,
-            lat,
-            lng,
-            title,
-            description,
-            categories,
-            tags,
-            ratings,
+pub fn place_search_result_from_indexed_place(
+    from: IndexedPlace,
+    all_categories: &e::Categories,
+) -> PlaceSearchResult {
+    let IndexedPlace {
+        id,
+        status,
+        title,
+        description,
+        tags,
+        pos,
+        additional_positions,
+        ratings,
+        title_snippet,
+        description_snippet,
+        ..
+    } = from;
+    // The status should never be undefined! It is optional only
+    // for technical reasons.
+    debug_assert!(status.is_some());
+    let status = status.map(Into::into);
+    let (tags, categories) = all_categories.split_from_tags(tags);
+    let categories = categories.into_iter().map(|c| c.id.to_string()).collect();
+    let lat = pos.lat().to_deg();
+    let lng = pos.lng().to_deg();
+    let ratings = ratings.into();
+    let snippets = if title_snippet.is_some() || description_snippet.is_some() {
+        Some(SearchSnippets {
+            title: title_snippet.map(snippet_to_html),
+            description: description_snippet.map(snippet_to_html),
+        })
+    } else {
+        None
+    };
+    PlaceSearchResult {
+        id,
+        status,
+        lat,
+        lng,
+        title,
+        description,
+        categories,
+        tags,
+        ratings,
+        snippets,
+        additional_locations: additional_positions
+            .into_iter()
+            .map(|pos| Coordinate {
+                lat: pos.lat().to_deg(),
+                lng: pos.lng().to_deg(),
+            })
+            .collect(),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn snippet_to_html(snippet: TextSnippet) -> String {
+    let TextSnippet {
+        fragment,
+        highlighted,
+    } = snippet;
+    let mut html = String::with_capacity(fragment.len() + 7 * highlighted.len());
+    let mut pos = 0;
+    for range in highlighted {
+        if range.start < pos || range.end > fragment.len() {
+            continue;
         }
+        html.push_str(&escape_html(&fragment[pos..range.start]));
+        html.push_str("<b>");
+        html.push_str(&escape_html(&fragment[range.clone()]));
+        html.push_str("</b>");
+        pos = range.end;
     }
+    html.push_str(&escape_html(&fragment[pos..]));
+    html
 }
 
 // Entity -> JSON
 
-pub fn entry_from_place_with_ratings(place: e::Place, ratings: Vec<e::Rating>) -> Entry {
+pub fn entry_from_place_with_ratings(
+    place: e::Place,
+    ratings: Vec<e::Rating>,
+    all_categories: &e::Categories,
+) -> Entry {
     let e::Place {
         id,
         license,
@@ -67,10 +106,12 @@ pub fn entry_from_place_with_ratings(place: e::Place, ratings: Vec<e::Rating>) -
         title,
         description,
         location,
+        additional_locations,
         contact,
         opening_hours,
         links,
         tags,
+        source,
     } = place;
 
     let e::Location { pos, address } = location;
@@ -95,7 +136,7 @@ pub fn entry_from_place_with_ratings(place: e::Place, ratings: Vec<e::Rating>) -
         (None, None, None)
     };
 
-    let (tags, categories) = e::Category::split_from_tags(tags);
+    let (tags, categories) = all_categories.split_from_tags(tags);
 
     Entry {
         id: id.into(),
@@ -120,6 +161,9 @@ pub fn entry_from_place_with_ratings(place: e::Place, ratings: Vec<e::Rating>) -
         license: Some(license),
         image_url: image_url.map(Url::into_string),
         image_link_url: image_link_url.map(Url::into_string),
+        source: source.map(Into::into),
+        additional_locations: additional_locations.into_iter().map(Into::into).collect(),
+        email_verified: false,
     }
 }
 
@@ -132,6 +176,17 @@ impl From<MapPoint> for LatLonDegrees {
     }
 }
 
+impl From<IndexedPlaceCluster> for PlaceCluster {
+    fn from(from: IndexedPlaceCluster) -> Self {
+        let IndexedPlaceCluster { centroid, count } = from;
+        Self {
+            lat: centroid.lat().to_deg(),
+            lng: centroid.lng().to_deg(),
+            count,
+        }
+    }
+}
+
 #[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Address {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -260,6 +315,30 @@ impl From<e::Links> for Links {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalId {
+    pub source: String,
+
+    pub id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<Url>,
+}
+
+impl From<e::ExternalId> for ExternalId {
+    fn from(from: e::ExternalId) -> Self {
+        let e::ExternalId { source, id, url } = from;
+        Self { source, id, url }
+    }
+}
+
+impl From<ExternalId> for e::ExternalId {
+    fn from(from: ExternalId) -> Self {
+        let ExternalId { source, id, url } = from;
+        Self { source, id, url }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Activity {
     pub at: i64,
@@ -342,6 +421,13 @@ pub struct PlaceRevision {
     #[serde(rename = "loc")]
     pub location: Location,
 
+    #[serde(
+        rename = "locs",
+        skip_serializing_if = "Vec::is_empty",
+        default = "Default::default"
+    )]
+    pub additional_locations: Vec<Location>,
+
     #[serde(
         rename = "cnt",
         skip_serializing_if = "Contact::is_empty",
@@ -365,6 +451,34 @@ pub struct PlaceRevision {
         default = "Default::default"
     )]
     pub tags: Vec<String>,
+
+    #[serde(rename = "src", skip_serializing_if = "Option::is_none")]
+    pub source: Option<PlaceRevisionSource>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlaceRevisionSource {
+    #[serde(rename = "imp")]
+    pub importer: String,
+
+    #[serde(rename = "ref")]
+    pub external_ref: String,
+
+    pub at: i64,
+}
+
+impl From<e::PlaceSource> for PlaceRevisionSource {
+    fn from(from: e::PlaceSource) -> Self {
+        let e::PlaceSource {
+            external_id,
+            imported_at,
+        } = from;
+        Self {
+            importer: external_id.source,
+            external_ref: external_id.id,
+            at: imported_at.into_inner(),
+        }
+    }
 }
 
 impl From<e::PlaceRevision> for PlaceRevision {
@@ -375,10 +489,12 @@ impl From<e::PlaceRevision> for PlaceRevision {
             title,
             description,
             location,
+            additional_locations,
             contact,
             opening_hours,
             links,
             tags,
+            source,
         } = from;
         Self {
             revision: revision.into(),
@@ -386,10 +502,12 @@ impl From<e::PlaceRevision> for PlaceRevision {
             title,
             description,
             location: location.into(),
+            additional_locations: additional_locations.into_iter().map(Into::into).collect(),
             contact: contact.map(Into::into).unwrap_or_default(),
             opening_hours: opening_hours.map(Into::into),
             links: links.map(Into::into).unwrap_or_default(),
             tags,
+            source: source.map(Into::into),
         }
     }
 }
@@ -400,6 +518,41 @@ pub struct Review {
     pub comment: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RestorePlaceRevision {
+    /// The earlier revision that should become the current revision
+    pub revision: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Archive {
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveKind {
+    Place,
+    Event,
+    Rating,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchiveBatch {
+    pub kind: ArchiveKind,
+    pub ids: Vec<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchiveBatchReport {
+    pub archived: Vec<String>,
+    /// Items that don't exist or have already been archived
+    pub not_found: Vec<String>,
+    /// Items that could not be archived due to an error
+    pub failed: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ReviewStatusLog {
     pub rev: u64,
@@ -422,26 +575,1267 @@ impl From<e::ReviewStatusLog> for ReviewStatusLog {
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct UserStatusChange {
+    pub comment: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
-pub struct PlaceHistory {
-    pub place: PlaceRoot,
-    pub revisions: Vec<(PlaceRevision, Vec<ReviewStatusLog>)>,
+pub struct ScoutReportSettings {
+    pub enabled: bool,
 }
 
-impl From<e::PlaceHistory> for PlaceHistory {
-    fn from(from: e::PlaceHistory) -> Self {
-        let e::PlaceHistory { place, revisions } = from;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestInterval {
+    Daily,
+    Weekly,
+}
+
+impl From<e::DigestInterval> for DigestInterval {
+    fn from(from: e::DigestInterval) -> Self {
+        use e::DigestInterval as E;
+        use DigestInterval as C;
+        match from {
+            E::Daily => C::Daily,
+            E::Weekly => C::Weekly,
+        }
+    }
+}
+
+impl From<DigestInterval> for e::DigestInterval {
+    fn from(from: DigestInterval) -> Self {
+        use e::DigestInterval as E;
+        use DigestInterval as C;
+        match from {
+            C::Daily => E::Daily,
+            C::Weekly => E::Weekly,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotificationDigestSettings {
+    /// No digest, i.e. an e-mail for every change if `None`
+    pub interval: Option<DigestInterval>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserLanguage {
+    /// The default language of the e-mails if `None`
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserStatusLog {
+    pub act: ActivityLog,
+    pub active: bool,
+}
+
+impl From<e::UserStatusLog> for UserStatusLog {
+    fn from(from: e::UserStatusLog) -> Self {
+        let e::UserStatusLog { activity, active } = from;
         Self {
-            place: place.into(),
-            revisions: revisions
-                .into_iter()
-                .map(|(place_revision, reviews)| {
-                    (
-                        place_revision.into(),
-                        reviews.into_iter().map(Into::into).collect(),
-                    )
-                })
-                .collect(),
+            act: activity.into(),
+            active,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ReviewPlaces,
+    ViewPlaceHistory,
+    ArchiveEvents,
+    ArchiveRatings,
+    ArchiveComments,
+    ModerateReports,
+    ViewTagOwners,
+    ExportData,
+    ExportActivityData,
+    CurateTags,
+    ManageUsers,
+    ManageSubscriptions,
+    ViewAuditLog,
+    ManageAnnouncements,
+    ManageOrganizations,
+    ManageWebhooks,
+    MergePlaces,
+}
+
+impl From<e::Permission> for Permission {
+    fn from(from: e::Permission) -> Self {
+        use e::Permission::*;
+        match from {
+            ReviewPlaces => Self::ReviewPlaces,
+            ViewPlaceHistory => Self::ViewPlaceHistory,
+            ArchiveEvents => Self::ArchiveEvents,
+            ArchiveRatings => Self::ArchiveRatings,
+            ArchiveComments => Self::ArchiveComments,
+            ModerateReports => Self::ModerateReports,
+            ViewTagOwners => Self::ViewTagOwners,
+            ExportData => Self::ExportData,
+            ExportActivityData => Self::ExportActivityData,
+            CurateTags => Self::CurateTags,
+            ManageUsers => Self::ManageUsers,
+            ManageSubscriptions => Self::ManageSubscriptions,
+            ViewAuditLog => Self::ViewAuditLog,
+            ManageAnnouncements => Self::ManageAnnouncements,
+            ManageOrganizations => Self::ManageOrganizations,
+            ManageWebhooks => Self::ManageWebhooks,
+            MergePlaces => Self::MergePlaces,
+        }
+    }
+}
+
+impl From<Permission> for e::Permission {
+    fn from(from: Permission) -> Self {
+        use e::Permission::*;
+        match from {
+            Permission::ReviewPlaces => ReviewPlaces,
+            Permission::ViewPlaceHistory => ViewPlaceHistory,
+            Permission::ArchiveEvents => ArchiveEvents,
+            Permission::ArchiveRatings => ArchiveRatings,
+            Permission::ArchiveComments => ArchiveComments,
+            Permission::ModerateReports => ModerateReports,
+            Permission::ViewTagOwners => ViewTagOwners,
+            Permission::ExportData => ExportData,
+            Permission::ExportActivityData => ExportActivityData,
+            Permission::CurateTags => CurateTags,
+            Permission::ManageUsers => ManageUsers,
+            Permission::ManageSubscriptions => ManageSubscriptions,
+            Permission::ViewAuditLog => ViewAuditLog,
+            Permission::ManageAnnouncements => ManageAnnouncements,
+            Permission::ManageOrganizations => ManageOrganizations,
+            Permission::ManageWebhooks => ManageWebhooks,
+            Permission::MergePlaces => MergePlaces,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    ReviewPlace,
+    RestorePlaceRevision,
+    ArchiveEvent,
+    ArchiveRating,
+    ArchiveComment,
+    ChangeUserRole,
+    DeactivateUser,
+    ReactivateUser,
+    SaveCuratedTag,
+    DeleteCuratedTag,
+    RenameTag,
+    MergeTags,
+    DeleteTag,
+    MergePlaces,
+    SaveCuratedOrg,
+    DeleteCuratedOrg,
+}
+
+impl From<e::AuditAction> for AuditAction {
+    fn from(from: e::AuditAction) -> Self {
+        use e::AuditAction as E;
+        use AuditAction as C;
+        match from {
+            E::ReviewPlace => C::ReviewPlace,
+            E::RestorePlaceRevision => C::RestorePlaceRevision,
+            E::ArchiveEvent => C::ArchiveEvent,
+            E::ArchiveRating => C::ArchiveRating,
+            E::ArchiveComment => C::ArchiveComment,
+            E::ChangeUserRole => C::ChangeUserRole,
+            E::DeactivateUser => C::DeactivateUser,
+            E::ReactivateUser => C::ReactivateUser,
+            E::SaveCuratedTag => C::SaveCuratedTag,
+            E::DeleteCuratedTag => C::DeleteCuratedTag,
+            E::RenameTag => C::RenameTag,
+            E::MergeTags => C::MergeTags,
+            E::DeleteTag => C::DeleteTag,
+            E::MergePlaces => C::MergePlaces,
+            E::SaveCuratedOrg => C::SaveCuratedOrg,
+            E::DeleteCuratedOrg => C::DeleteCuratedOrg,
+        }
+    }
+}
+
+impl From<AuditAction> for e::AuditAction {
+    fn from(from: AuditAction) -> Self {
+        use e::AuditAction as E;
+        use AuditAction as C;
+        match from {
+            C::ReviewPlace => E::ReviewPlace,
+            C::RestorePlaceRevision => E::RestorePlaceRevision,
+            C::ArchiveEvent => E::ArchiveEvent,
+            C::ArchiveRating => E::ArchiveRating,
+            C::ArchiveComment => E::ArchiveComment,
+            C::ChangeUserRole => E::ChangeUserRole,
+            C::DeactivateUser => E::DeactivateUser,
+            C::ReactivateUser => E::ReactivateUser,
+            C::SaveCuratedTag => E::SaveCuratedTag,
+            C::DeleteCuratedTag => E::DeleteCuratedTag,
+            C::RenameTag => E::RenameTag,
+            C::MergeTags => E::MergeTags,
+            C::DeleteTag => E::DeleteTag,
+            C::MergePlaces => E::MergePlaces,
+            C::SaveCuratedOrg => E::SaveCuratedOrg,
+            C::DeleteCuratedOrg => E::DeleteCuratedOrg,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    pub act: ActivityLog,
+    pub action: AuditAction,
+    pub item: String,
+}
+
+impl From<e::AuditLogEntry> for AuditLogEntry {
+    fn from(from: e::AuditLogEntry) -> Self {
+        let e::AuditLogEntry {
+            activity,
+            action,
+            item,
+        } = from;
+        Self {
+            act: activity.into(),
+            action: action.into(),
+            item,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnouncementSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl From<e::AnnouncementSeverity> for AnnouncementSeverity {
+    fn from(from: e::AnnouncementSeverity) -> Self {
+        use e::AnnouncementSeverity as E;
+        use AnnouncementSeverity as C;
+        match from {
+            E::Info => C::Info,
+            E::Warning => C::Warning,
+            E::Critical => C::Critical,
+        }
+    }
+}
+
+impl From<AnnouncementSeverity> for e::AnnouncementSeverity {
+    fn from(from: AnnouncementSeverity) -> Self {
+        use e::AnnouncementSeverity as E;
+        use AnnouncementSeverity as C;
+        match from {
+            C::Info => E::Info,
+            C::Warning => E::Warning,
+            C::Critical => E::Critical,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Announcement {
+    pub id: String,
+    pub message: String,
+    pub severity: AnnouncementSeverity,
+    /// Time stamp in seconds
+    pub starts_at: i64,
+    /// Time stamp in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ends_at: Option<i64>,
+}
+
+impl From<e::Announcement> for Announcement {
+    fn from(from: e::Announcement) -> Self {
+        let e::Announcement {
+            id,
+            message,
+            severity,
+            starts_at,
+            ends_at,
+        } = from;
+        Self {
+            id: id.into(),
+            message,
+            severity: severity.into(),
+            starts_at: starts_at.into_seconds(),
+            ends_at: ends_at.map(e::TimestampMs::into_seconds),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewAnnouncement {
+    pub message: String,
+    pub severity: AnnouncementSeverity,
+    /// Time stamp in seconds, now if missing
+    pub starts_at: Option<i64>,
+    /// Time stamp in seconds, unlimited if missing
+    pub ends_at: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PermissionChange {
+    pub permission: Permission,
+    /// Reset to the default of the role if missing
+    pub granted: Option<bool>,
+}
+
+/// A user token without the secret nonce
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserTokenInfo {
+    pub email: String,
+    /// Hex-encoded SHA-256 hash of the encoded e-mail nonce
+    pub email_nonce_hash: String,
+    /// Time stamp in seconds
+    pub expires_at: i64,
+}
+
+impl From<e::UserToken> for UserTokenInfo {
+    fn from(from: e::UserToken) -> Self {
+        let e::UserToken {
+            email_nonce,
+            expires_at,
+        } = from;
+        let email_nonce_hash = openssl::sha::sha256(email_nonce.encode_to_string().as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        Self {
+            email: email_nonce.email,
+            email_nonce_hash,
+            expires_at: expires_at.into_inner(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserApiToken {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Time stamp in seconds
+    pub created_at: i64,
+    /// Only returned after creating the token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl From<e::UserApiToken> for UserApiToken {
+    fn from(from: e::UserApiToken) -> Self {
+        let e::UserApiToken {
+            id,
+            user_email: _,
+            token: _,
+            label,
+            created_at,
+        } = from;
+        Self {
+            id: id.into(),
+            label,
+            created_at: created_at.into_seconds(),
+            token: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewUserApiToken {
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityHint {
+    MissingAddress,
+    MissingContact,
+    MissingOpeningHours,
+    MissingImage,
+    TooFewTags,
+    NotRecentlyVerified,
+}
+
+impl From<quality::QualityHint> for QualityHint {
+    fn from(from: quality::QualityHint) -> Self {
+        use quality::QualityHint as Q;
+        match from {
+            Q::MissingAddress => Self::MissingAddress,
+            Q::MissingContact => Self::MissingContact,
+            Q::MissingOpeningHours => Self::MissingOpeningHours,
+            Q::MissingImage => Self::MissingImage,
+            Q::TooFewTags => Self::TooFewTags,
+            Q::NotRecentlyVerified => Self::NotRecentlyVerified,
+        }
+    }
+}
+
+/// Completeness of a place with hints for improving it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlaceQuality {
+    /// Fraction of fulfilled criteria in the range [0.0, 1.0]
+    pub score: f64,
+    pub hints: Vec<QualityHint>,
+}
+
+impl From<quality::PlaceQuality> for PlaceQuality {
+    fn from(from: quality::PlaceQuality) -> Self {
+        let quality::PlaceQuality { score, hints } = from;
+        Self {
+            score,
+            hints: hints.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewPlaceReport {
+    pub reason: ReportReason,
+    pub comment: Option<String>,
+    pub captcha: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentKind {
+    Place,
+    Event,
+    Comment,
+}
+
+impl From<e::ContentKind> for ContentKind {
+    fn from(from: e::ContentKind) -> Self {
+        use e::ContentKind as E;
+        use ContentKind as C;
+        match from {
+            E::Place => C::Place,
+            E::Event => C::Event,
+            E::Comment => C::Comment,
+        }
+    }
+}
+
+impl From<ContentKind> for e::ContentKind {
+    fn from(from: ContentKind) -> Self {
+        use e::ContentKind as E;
+        use ContentKind as C;
+        match from {
+            C::Place => E::Place,
+            C::Event => E::Event,
+            C::Comment => E::Comment,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewContentReport {
+    pub content_kind: ContentKind,
+    pub content_id: String,
+    pub reason: ReportReason,
+    pub comment: Option<String>,
+    pub captcha: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub id: String,
+    pub content_kind: ContentKind,
+    pub content_id: String,
+    pub reason: ReportReason,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    pub created: Activity,
+}
+
+impl From<e::Report> for Report {
+    fn from(from: e::Report) -> Self {
+        let e::Report {
+            id,
+            content_kind,
+            content_id,
+            reason,
+            comment,
+            created,
+        } = from;
+        Self {
+            id: id.into(),
+            content_kind: content_kind.into(),
+            content_id: content_id.into(),
+            reason: reason.into(),
+            comment,
+            created: created.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationItemKind {
+    Report,
+    Place,
+    Clearance,
+}
+
+impl From<e::ModerationItemKind> for ModerationItemKind {
+    fn from(from: e::ModerationItemKind) -> Self {
+        use e::ModerationItemKind as E;
+        use ModerationItemKind as C;
+        match from {
+            E::Report => C::Report,
+            E::Place => C::Place,
+            E::Clearance => C::Clearance,
+        }
+    }
+}
+
+impl From<ModerationItemKind> for e::ModerationItemKind {
+    fn from(from: ModerationItemKind) -> Self {
+        use e::ModerationItemKind as E;
+        use ModerationItemKind as C;
+        match from {
+            C::Report => E::Report,
+            C::Place => E::Place,
+            C::Clearance => E::Clearance,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssignmentState {
+    Open,
+    InProgress,
+    Resolved,
+}
+
+impl From<e::AssignmentState> for AssignmentState {
+    fn from(from: e::AssignmentState) -> Self {
+        use e::AssignmentState as E;
+        use AssignmentState as C;
+        match from {
+            E::Open => C::Open,
+            E::InProgress => C::InProgress,
+            E::Resolved => C::Resolved,
+        }
+    }
+}
+
+impl From<AssignmentState> for e::AssignmentState {
+    fn from(from: AssignmentState) -> Self {
+        use e::AssignmentState as E;
+        use AssignmentState as C;
+        match from {
+            C::Open => E::Open,
+            C::InProgress => E::InProgress,
+            C::Resolved => E::Resolved,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewModerationAssignment {
+    pub item_kind: ModerationItemKind,
+    pub item_id: String,
+    /// The email of the scout
+    pub assignee: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModerationAssignmentStateChange {
+    pub item_kind: ModerationItemKind,
+    pub item_id: String,
+    pub state: AssignmentState,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModerationAssignment {
+    pub item_kind: ModerationItemKind,
+    pub item_id: String,
+    pub assignee: String,
+    pub state: AssignmentState,
+    pub assigned: Activity,
+    pub changed_at: i64,
+}
+
+impl From<e::ModerationAssignment> for ModerationAssignment {
+    fn from(from: e::ModerationAssignment) -> Self {
+        let e::ModerationAssignment {
+            item_kind,
+            item_id,
+            assignee,
+            state,
+            assigned,
+            changed_at,
+        } = from;
+        Self {
+            item_kind: item_kind.into(),
+            item_id: item_id.into(),
+            assignee: assignee.into(),
+            state: state.into(),
+            assigned: assigned.into(),
+            changed_at: changed_at.into_inner(),
+        }
+    }
+}
+
+/// A comment with the context that is needed for moderating it
+#[derive(Serialize, Deserialize)]
+pub struct ModeratedComment {
+    pub id: String,
+    pub created: i64,
+    pub text: String,
+    pub flag_count: u64,
+    pub rating_id: String,
+    pub rating_title: String,
+    pub rating_value: RatingValue,
+    pub rating_context: RatingContext,
+    pub place_id: String,
+    pub place_title: String,
+}
+
+impl From<e::ModeratedComment> for ModeratedComment {
+    fn from(from: e::ModeratedComment) -> Self {
+        let e::ModeratedComment {
+            comment,
+            rating,
+            place_title,
+            flag_count,
+        } = from;
+        Self {
+            id: comment.id.into(),
+            created: comment.created_at.into_seconds(),
+            text: comment.text,
+            flag_count,
+            rating_id: rating.id.into(),
+            rating_title: rating.title,
+            rating_value: rating.value.into(),
+            rating_context: rating.context.into(),
+            place_id: rating.place_id.into(),
+            place_title,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlaceHistory {
+    pub place: PlaceRoot,
+    pub revisions: Vec<(PlaceRevision, Vec<ReviewStatusLog>)>,
+}
+
+impl From<e::PlaceHistory> for PlaceHistory {
+    fn from(from: e::PlaceHistory) -> Self {
+        let e::PlaceHistory { place, revisions } = from;
+        Self {
+            place: place.into(),
+            revisions: revisions
+                .into_iter()
+                .map(|(place_revision, reviews)| {
+                    (
+                        place_revision.into(),
+                        reviews.into_iter().map(Into::into).collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FieldChange<T> {
+    pub from: T,
+    pub to: T,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlaceRevisionDiff {
+    pub place_id: String,
+    pub from: u64,
+    pub to: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<FieldChange<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<FieldChange<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<FieldChange<Location>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact: Option<FieldChange<Contact>>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default = "Default::default")]
+    pub added_tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default = "Default::default")]
+    pub removed_tags: Vec<String>,
+}
+
+impl From<e::PlaceRevisionDiff> for PlaceRevisionDiff {
+    fn from(from: e::PlaceRevisionDiff) -> Self {
+        let e::PlaceRevisionDiff {
+            place_id,
+            from,
+            to,
+            title,
+            description,
+            location,
+            contact,
+            added_tags,
+            removed_tags,
+        } = from;
+        Self {
+            place_id: place_id.into(),
+            from: from.into(),
+            to: to.into(),
+            title: title.map(|c| FieldChange {
+                from: c.from,
+                to: c.to,
+            }),
+            description: description.map(|c| FieldChange {
+                from: c.from,
+                to: c.to,
+            }),
+            location: location.map(|c| FieldChange {
+                from: c.from.into(),
+                to: c.to.into(),
+            }),
+            contact: contact.map(|c| FieldChange {
+                from: c.from.map(Into::into).unwrap_or_default(),
+                to: c.to.map(Into::into).unwrap_or_default(),
+            }),
+            added_tags,
+            removed_tags,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Organization {
+    pub id: String,
+    pub name: String,
+    pub owned_tags: Vec<String>,
+    /// Only returned after creating the organization or
+    /// regenerating its token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_token: Option<String>,
+}
+
+impl From<e::Organization> for Organization {
+    fn from(from: e::Organization) -> Self {
+        let e::Organization {
+            id,
+            name,
+            owned_tags,
+        } = from;
+        Self {
+            id,
+            name,
+            owned_tags,
+            api_token: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewOrganization {
+    pub name: String,
+    #[serde(default)]
+    pub owned_tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiTokenScope {
+    Read,
+    WriteEvents,
+    ClearPlaces,
+    ImportPlaces,
+}
+
+impl From<e::ApiTokenScope> for ApiTokenScope {
+    fn from(from: e::ApiTokenScope) -> Self {
+        use e::ApiTokenScope::*;
+        match from {
+            Read => Self::Read,
+            WriteEvents => Self::WriteEvents,
+            ClearPlaces => Self::ClearPlaces,
+            ImportPlaces => Self::ImportPlaces,
+        }
+    }
+}
+
+impl From<ApiTokenScope> for e::ApiTokenScope {
+    fn from(from: ApiTokenScope) -> Self {
+        use e::ApiTokenScope::*;
+        match from {
+            ApiTokenScope::Read => Read,
+            ApiTokenScope::WriteEvents => WriteEvents,
+            ApiTokenScope::ClearPlaces => ClearPlaces,
+            ApiTokenScope::ImportPlaces => ImportPlaces,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrganizationApiToken {
+    pub id: String,
+    pub scopes: Vec<ApiTokenScope>,
+    /// Time stamp in seconds
+    pub created_at: i64,
+    /// Time stamp in seconds, missing if the token never expires
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    /// Only returned after creating the token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl From<e::OrganizationApiToken> for OrganizationApiToken {
+    fn from(from: e::OrganizationApiToken) -> Self {
+        let e::OrganizationApiToken {
+            id,
+            org_id: _,
+            token: _,
+            scopes,
+            created_at,
+            expires_at,
+        } = from;
+        Self {
+            id: id.into(),
+            scopes: scopes.into_iter().map(Into::into).collect(),
+            created_at: created_at.into_seconds(),
+            expires_at: expires_at.map(e::TimestampMs::into_seconds),
+            token: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewOrganizationApiToken {
+    pub scopes: Vec<ApiTokenScope>,
+    /// Time stamp in seconds
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PendingClearanceForPlace {
+    pub place_id: String,
+    /// Time stamp in seconds
+    pub created_at: i64,
+    /// Missing if the place has been created by someone else
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_cleared_revision: Option<u64>,
+}
+
+impl From<e::PendingClearanceForPlace> for PendingClearanceForPlace {
+    fn from(from: e::PendingClearanceForPlace) -> Self {
+        let e::PendingClearanceForPlace {
+            place_id,
+            created_at,
+            last_cleared_revision,
+        } = from;
+        Self {
+            place_id: place_id.into(),
+            created_at: created_at.into_seconds(),
+            last_cleared_revision: last_cleared_revision.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClearanceForPlace {
+    pub place_id: String,
+    pub cleared_revision: u64,
+}
+
+impl From<ClearanceForPlace> for e::ClearanceForPlace {
+    fn from(from: ClearanceForPlace) -> Self {
+        let ClearanceForPlace {
+            place_id,
+            cleared_revision,
+        } = from;
+        Self {
+            place_id: place_id.into(),
+            cleared_revision: cleared_revision.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebhookEvent {
+    #[serde(rename = "place.created")]
+    PlaceCreated,
+    #[serde(rename = "place.updated")]
+    PlaceUpdated,
+    #[serde(rename = "place.archived")]
+    PlaceArchived,
+    #[serde(rename = "event.created")]
+    EventCreated,
+    #[serde(rename = "rating.added")]
+    RatingAdded,
+}
+
+impl From<e::WebhookEvent> for WebhookEvent {
+    fn from(from: e::WebhookEvent) -> Self {
+        use e::WebhookEvent::*;
+        match from {
+            PlaceCreated => Self::PlaceCreated,
+            PlaceUpdated => Self::PlaceUpdated,
+            PlaceArchived => Self::PlaceArchived,
+            EventCreated => Self::EventCreated,
+            RatingAdded => Self::RatingAdded,
+        }
+    }
+}
+
+impl From<WebhookEvent> for e::WebhookEvent {
+    fn from(from: WebhookEvent) -> Self {
+        use e::WebhookEvent::*;
+        match from {
+            WebhookEvent::PlaceCreated => PlaceCreated,
+            WebhookEvent::PlaceUpdated => PlaceUpdated,
+            WebhookEvent::PlaceArchived => PlaceArchived,
+            WebhookEvent::EventCreated => EventCreated,
+            WebhookEvent::RatingAdded => RatingAdded,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    /// Time stamp in seconds
+    pub created_at: i64,
+    /// Only returned after creating the webhook
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+impl From<e::Webhook> for Webhook {
+    fn from(from: e::Webhook) -> Self {
+        let e::Webhook {
+            id,
+            url,
+            secret: _,
+            events,
+            created_at,
+        } = from;
+        Self {
+            id: id.into(),
+            url: url.into_string(),
+            events: events.into_iter().map(Into::into).collect(),
+            created_at: created_at.into_seconds(),
+            secret: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewWebhook {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl From<e::WebhookDeliveryStatus> for WebhookDeliveryStatus {
+    fn from(from: e::WebhookDeliveryStatus) -> Self {
+        use e::WebhookDeliveryStatus::*;
+        match from {
+            Pending => Self::Pending,
+            Delivered => Self::Delivered,
+            Failed => Self::Failed,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub event: WebhookEvent,
+    /// The signed body of the request
+    pub payload: String,
+    /// Time stamp in seconds
+    pub created_at: i64,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+    /// Time stamp in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_attempt_at: Option<i64>,
+    /// Time stamp in seconds, only present while pending
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<i64>,
+    /// The HTTP status code of the last response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<u16>,
+}
+
+impl From<e::WebhookDelivery> for WebhookDelivery {
+    fn from(from: e::WebhookDelivery) -> Self {
+        let e::WebhookDelivery {
+            id,
+            webhook_id: _,
+            event,
+            payload,
+            created_at,
+            status,
+            attempts,
+            last_attempt_at,
+            next_attempt_at,
+            response_status,
+        } = from;
+        Self {
+            id: id.into(),
+            event: event.into(),
+            payload,
+            created_at: created_at.into_seconds(),
+            status: status.into(),
+            attempts,
+            last_attempt_at: last_attempt_at.map(e::TimestampMs::into_seconds),
+            next_attempt_at: next_attempt_at.map(e::TimestampMs::into_seconds),
+            response_status,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutgoingEmailStatus {
+    Pending,
+    Sent,
+    Failed,
+}
+
+impl From<e::OutgoingEmailStatus> for OutgoingEmailStatus {
+    fn from(from: e::OutgoingEmailStatus) -> Self {
+        use e::OutgoingEmailStatus::*;
+        match from {
+            Pending => Self::Pending,
+            Sent => Self::Sent,
+            Failed => Self::Failed,
+        }
+    }
+}
+
+impl From<OutgoingEmailStatus> for e::OutgoingEmailStatus {
+    fn from(from: OutgoingEmailStatus) -> Self {
+        use OutgoingEmailStatus::*;
+        match from {
+            Pending => Self::Pending,
+            Sent => Self::Sent,
+            Failed => Self::Failed,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutgoingEmail {
+    pub id: String,
+    pub recipient: String,
+    pub subject: String,
+    /// Time stamp in seconds
+    pub created_at: i64,
+    pub status: OutgoingEmailStatus,
+    pub attempts: u32,
+    /// Time stamp in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_attempt_at: Option<i64>,
+    /// Time stamp in seconds, only present while pending
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<i64>,
+    /// The reason why the last attempt failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl From<e::OutgoingEmail> for OutgoingEmail {
+    fn from(from: e::OutgoingEmail) -> Self {
+        let e::OutgoingEmail {
+            id,
+            recipient,
+            subject,
+            created_at,
+            status,
+            attempts,
+            last_attempt_at,
+            next_attempt_at,
+            last_error,
+            ..
+        } = from;
+        Self {
+            id: id.into(),
+            recipient: recipient.into(),
+            subject,
+            created_at: created_at.into_seconds(),
+            status: status.into(),
+            attempts,
+            last_attempt_at: last_attempt_at.map(e::TimestampMs::into_seconds),
+            next_attempt_at: next_attempt_at.map(e::TimestampMs::into_seconds),
+            last_error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventRegistrationStatus {
+    Confirmed,
+    Waitlisted,
+    Cancelled,
+}
+
+impl From<e::EventRegistrationStatus> for EventRegistrationStatus {
+    fn from(from: e::EventRegistrationStatus) -> Self {
+        use e::EventRegistrationStatus::*;
+        match from {
+            Confirmed => Self::Confirmed,
+            Waitlisted => Self::Waitlisted,
+            Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+/// A registration without the secret for cancelling it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventRegistration {
+    pub email: String,
+    pub status: EventRegistrationStatus,
+    /// Time stamp in seconds
+    pub registered_at: i64,
+}
+
+impl From<e::EventRegistration> for EventRegistration {
+    fn from(from: e::EventRegistration) -> Self {
+        let e::EventRegistration {
+            event_id: _,
+            email_nonce,
+            status,
+            registered_at,
+        } = from;
+        Self {
+            email: email_nonce.email,
+            status: status.into(),
+            registered_at: registered_at.into_seconds(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateType {
+    SimilarChars,
+    SimilarWords,
+    SimilarTrigrams,
+}
+
+impl From<usecases::DuplicateType> for DuplicateType {
+    fn from(from: usecases::DuplicateType) -> Self {
+        use usecases::DuplicateType::*;
+        match from {
+            SimilarChars => Self::SimilarChars,
+            SimilarWords => Self::SimilarWords,
+            SimilarTrigrams => Self::SimilarTrigrams,
+        }
+    }
+}
+
+/// A nearby place with a similar title
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlaceDuplicate {
+    pub id: String,
+    pub title: String,
+    pub lat: f64,
+    pub lng: f64,
+    #[serde(rename = "type")]
+    pub duplicate_type: DuplicateType,
+}
+
+impl From<(e::Place, usecases::DuplicateType)> for PlaceDuplicate {
+    fn from(from: (e::Place, usecases::DuplicateType)) -> Self {
+        let (place, duplicate_type) = from;
+        Self {
+            id: place.id.into(),
+            title: place.title,
+            lat: place.location.pos.lat().to_deg(),
+            lng: place.location.pos.lng().to_deg(),
+            duplicate_type: duplicate_type.into(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewTagSubscription {
+    pub tags: Vec<String>,
+    /// The south-west and the north-east corner
+    pub bbox: Option<Vec<Coordinate>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagSubscription {
+    pub id: String,
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bbox: Option<Vec<Coordinate>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewSavedSearch {
+    pub title: String,
+    /// The query string of the search
+    pub query: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SavedSearch {
+    pub id: String,
+    pub created_at: i64,
+    pub title: String,
+    pub query: String,
+}
+
+impl From<e::SavedSearch> for SavedSearch {
+    fn from(from: e::SavedSearch) -> Self {
+        let e::SavedSearch {
+            id,
+            created_at,
+            title,
+            query,
+            ..
+        } = from;
+        Self {
+            id: id.into(),
+            created_at: created_at.into_seconds(),
+            title,
+            query,
+        }
+    }
+}
+
+impl From<e::TagSubscription> for TagSubscription {
+    fn from(from: e::TagSubscription) -> Self {
+        let e::TagSubscription { id, tags, bbox, .. } = from;
+        Self {
+            id: id.into(),
+            tags,
+            bbox: bbox.map(|bbox| {
+                vec![bbox.south_west(), bbox.north_east()]
+                    .into_iter()
+                    .map(|pos| Coordinate {
+                        lat: pos.lat().to_deg(),
+                        lng: pos.lng().to_deg(),
+                    })
+                    .collect()
+            }),
         }
     }
 }
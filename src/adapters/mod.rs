@@ -1,2 +1,4 @@
+pub mod atom;
 pub mod csv;
+pub mod export;
 pub mod json;
@@ -9,6 +9,7 @@ use super::{
 };
 
 use anyhow::Result as Fallible;
+use std::ops::Range;
 
 type Result<T> = std::result::Result<T, RepoError>;
 
@@ -18,30 +19,69 @@ pub struct MostPopularTagsParams {
     pub max_count: Option<u64>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct RecentlyChangedEntriesParams {
     pub since: Option<TimestampMs>,
     pub until: Option<TimestampMs>,
+    // Continue after this position, i.e. with older changes
+    pub after: Option<RecentlyChangedEntriesCursor>,
+}
+
+/// The position of a change in the list of recently changed
+/// entries that is ordered by the time of the change and the
+/// id of the corresponding log entry, both descending.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecentlyChangedEntriesCursor {
+    pub created_at: TimestampMs,
+    pub log_id: i64,
+}
+
+pub type RecentlyChangedPlace = (
+    Place,
+    ReviewStatus,
+    ActivityLog,
+    RecentlyChangedEntriesCursor,
+);
+
+/// The number of items that have been created
+/// within a single calendar month (UTC)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MonthlyCount {
+    pub year: i32,
+    pub month: u32,
+    pub count: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CommentModerationParams {
+    // Only comments that have been flagged at least once
+    pub flagged: bool,
+    pub since: Option<TimestampMs>,
 }
 
 pub trait PlaceRepo {
-    fn get_place(&self, id: &str) -> Result<(Place, ReviewStatus)>;
+    fn get_place(&self, id: &PlaceId) -> Result<(Place, ReviewStatus)>;
     fn get_places(&self, ids: &[&str]) -> Result<Vec<(Place, ReviewStatus)>>;
 
     fn all_places(&self) -> Result<Vec<(Place, ReviewStatus)>>;
+    fn all_place_ids(&self) -> Result<Vec<String>>;
+    // The ids of all places with any of the given tags
+    // in their current revision
+    fn find_place_ids_by_tags(&self, tags: &[&str]) -> Result<Vec<Id>>;
     fn count_places(&self) -> Result<usize>;
+    // The number of new places per calendar month in chronological
+    // order, including places that have been archived since
+    fn count_new_places_per_month(&self) -> Result<Vec<MonthlyCount>>;
 
     fn recently_changed_places(
         &self,
         params: &RecentlyChangedEntriesParams,
         pagination: &Pagination,
-    ) -> Result<Vec<(Place, ReviewStatus, ActivityLog)>>;
+    ) -> Result<Vec<RecentlyChangedPlace>>;
 
-    fn most_popular_place_revision_tags(
-        &self,
-        params: &MostPopularTagsParams,
-        pagination: &Pagination,
-    ) -> Result<Vec<TagFrequency>>;
+    // The current revisions of all places that have been created
+    // but not reviewed yet, starting with the oldest revision
+    fn list_places_pending_review(&self, pagination: &Pagination) -> Result<Vec<Place>>;
 
     fn review_places(
         &self,
@@ -52,15 +92,20 @@ pub trait PlaceRepo {
 
     fn create_or_update_place(&self, place: Place) -> Result<()>;
 
-    fn get_place_history(&self, id: &str) -> Result<PlaceHistory>;
+    fn get_place_history(&self, id: &PlaceId) -> Result<PlaceHistory>;
 }
 
 pub trait EventGateway {
     fn create_event(&self, _: Event) -> Result<()>;
     fn update_event(&self, _: &Event) -> Result<()>;
-    fn archive_events(&self, ids: &[&str], archived: Timestamp) -> Result<usize>;
+    fn archive_events(
+        &self,
+        ids: &[&str],
+        activity: &Activity,
+        comment: Option<&str>,
+    ) -> Result<usize>;
 
-    fn get_event(&self, id: &str) -> Result<Event>;
+    fn get_event(&self, id: &EventId) -> Result<Event>;
     fn get_events_chronologically(&self, ids: &[&str]) -> Result<Vec<Event>>;
 
     fn all_events_chronologically(&self) -> Result<Vec<Event>>;
@@ -70,25 +115,71 @@ pub trait EventGateway {
     // Ok(Some(())) => Found and deleted
     // Ok(None)     => No matching tags
     // TODO: Use explicit result semantics
-    fn delete_event_with_matching_tags(&self, id: &str, tags: &[&str]) -> Result<Option<()>>;
+    fn delete_event_with_matching_tags(&self, id: &EventId, tags: &[&str]) -> Result<Option<()>>;
+
+    // Events that are pushed by organizations from their own systems,
+    // including archived events
+    fn find_event_id_by_external_id(
+        &self,
+        org_id: &str,
+        external_id: &str,
+    ) -> Result<Option<String>>;
+    fn set_event_external_id(
+        &self,
+        event_id: &EventId,
+        org_id: &str,
+        external_id: &str,
+    ) -> Result<()>;
 }
 
 pub trait UserGateway {
     fn create_user(&self, user: &User) -> Result<()>;
     fn update_user(&self, user: &User) -> Result<()>;
     fn delete_user_by_email(&self, email: &str) -> Result<()>;
+    // Delete the account after removing all references to it,
+    // while keeping all contributed content
+    fn anonymize_user_by_email(&self, email: &str) -> Result<()>;
 
     fn all_users(&self) -> Result<Vec<User>>;
     fn count_users(&self) -> Result<usize>;
 
     fn get_user_by_email(&self, email: &str) -> Result<User>;
     fn try_get_user_by_email(&self, email: &str) -> Result<Option<User>>;
+
+    fn log_user_status(&self, email: &str, status_log: &UserStatusLog) -> Result<()>;
+    // Ordered chronologically, i.e. the current status is the last one
+    fn load_user_status_logs(&self, email: &str) -> Result<Vec<UserStatusLog>>;
+
+    fn set_scout_report_opt_out(&self, email: &str, opt_out: bool) -> Result<()>;
+    fn is_scout_report_opted_out(&self, email: &str) -> Result<bool>;
+
+    fn is_user_active(&self, email: &str) -> Result<bool> {
+        // Users without any status changes are active
+        Ok(self
+            .load_user_status_logs(email)?
+            .last()
+            .map(|log| log.active)
+            .unwrap_or(true))
+    }
 }
 
 pub trait OrganizationGateway {
-    fn create_org(&mut self, _: Organization) -> Result<()>;
-    fn get_org_by_api_token(&self, token: &str) -> Result<Organization>;
+    fn create_org(&self, _: Organization) -> Result<()>;
+    fn get_org_by_id(&self, id: &str) -> Result<Organization>;
+    // Ordered by name
+    fn all_orgs(&self) -> Result<Vec<Organization>>;
+    // Replaces the name and all owned tags
+    fn update_org(&self, _: &Organization) -> Result<()>;
+    // Fails with `NotFound` if the organization does not exist
+    fn delete_org(&self, id: &str) -> Result<()>;
     fn get_all_tags_owned_by_orgs(&self) -> Result<Vec<String>>;
+    // Ordered by tag and the name of the organization
+    fn get_all_tag_ownerships(&self) -> Result<Vec<TagOwnership>>;
+
+    fn all_curated_orgs(&self) -> Result<Vec<CuratedOrganization>>;
+    fn save_curated_org(&self, _: &CuratedOrganization) -> Result<()>;
+    // Fails with `NotFound` if the organization is not curated
+    fn delete_curated_org(&self, org_id: &str) -> Result<()>;
 }
 
 //TODO:
@@ -101,6 +192,17 @@ pub struct Pagination {
     pub limit: Option<u64>,
 }
 
+/// All criteria are optional and combined.
+#[derive(Clone, Debug, Default)]
+pub struct AuditLogFilter {
+    pub action: Option<AuditAction>,
+    /// The e-mail address of the admin or scout
+    pub created_by: Option<String>,
+    pub item: Option<String>,
+    pub since: Option<TimestampMs>,
+    pub until: Option<TimestampMs>,
+}
+
 pub trait Db:
     PlaceRepo
     + UserGateway
@@ -108,24 +210,78 @@ pub trait Db:
     + OrganizationGateway
     + CommentRepository
     + RatingRepository
+    + ReportRepository
+    + ModerationAssignmentRepository
     + UserTokenRepo
+    + LoginLinkRepo
+    + ContactVerificationRepo
+    + EventRegistrationRepo
+    + PlaceExternalIdRepo
+    + PlaceMergeRepo
+    + PlaceDraftRepo
+    + UserPermissionRepo
+    + AuditLogRepo
+    + AnnouncementRepo
+    + PlaceClearanceRepo
+    + OrganizationApiTokenRepo
+    + UserApiTokenRepo
+    + WebhookRepo
+    + EmailOutboxRepo
 {
     fn create_tag_if_it_does_not_exist(&self, _: &Tag) -> Result<()>;
 
     fn all_categories(&self) -> Result<Vec<Category>> {
-        Ok(vec![
-            Category::new_non_profit(),
-            Category::new_commercial(),
-            Category::new_event(),
-        ])
+        Ok(Categories::default().into())
     }
     fn all_tags(&self) -> Result<Vec<Tag>>;
     fn count_tags(&self) -> Result<usize>;
+    // Replace a tag in all revisions of places, events, tag
+    // ownerships of organizations and curated tags. The new
+    // tag is created if it doesn't exist yet.
+    fn replace_tag(&self, old_tag: &str, new_tag: &str) -> Result<()>;
+    // Delete a tag together with its tag ownerships and its
+    // curated tag. Places and events are not modified.
+    fn delete_tag(&self, tag: &str) -> Result<()>;
+
+    fn all_curated_tags(&self) -> Result<Vec<CuratedTag>>;
+    fn save_curated_tag(&self, _: &CuratedTag) -> Result<()>;
+    fn delete_curated_tag(&self, tag: &str) -> Result<()>;
+
+    // Only unarchived comments of unarchived ratings, the most
+    // frequently flagged and then the most recent comments first
+    fn load_comments_for_moderation(
+        &self,
+        params: &CommentModerationParams,
+        pagination: &Pagination,
+    ) -> Result<Vec<ModeratedComment>>;
 
     fn create_bbox_subscription(&self, _: &BboxSubscription) -> Result<()>;
     fn all_bbox_subscriptions(&self) -> Result<Vec<BboxSubscription>>;
     fn all_bbox_subscriptions_by_email(&self, user_email: &str) -> Result<Vec<BboxSubscription>>;
     fn delete_bbox_subscriptions_by_email(&self, user_email: &str) -> Result<()>;
+
+    fn create_tag_subscription(&self, _: &TagSubscription) -> Result<()>;
+    fn all_tag_subscriptions(&self) -> Result<Vec<TagSubscription>>;
+    fn all_tag_subscriptions_by_email(&self, user_email: &str) -> Result<Vec<TagSubscription>>;
+    // Only subscriptions of the given user are deleted
+    fn delete_tag_subscription(&self, user_email: &str, id: &str) -> Result<()>;
+
+    fn create_saved_search(&self, _: &SavedSearch) -> Result<()>;
+    // Oldest searches first
+    fn all_saved_searches_by_email(&self, user_email: &str) -> Result<Vec<SavedSearch>>;
+    // Only searches of the given user are deleted
+    fn delete_saved_search(&self, user_email: &str, id: &str) -> Result<()>;
+
+    fn save_digest_settings(&self, _: &DigestSettings) -> Result<()>;
+    fn delete_digest_settings(&self, user_email: &str) -> Result<()>;
+    fn try_get_digest_settings(&self, user_email: &str) -> Result<Option<DigestSettings>>;
+    fn all_digest_settings(&self) -> Result<Vec<DigestSettings>>;
+
+    fn enqueue_notification(&self, _: &QueuedNotification) -> Result<()>;
+    // Oldest notifications first
+    fn load_queued_notifications(&self, user_email: &str) -> Result<Vec<QueuedNotification>>;
+    // Returns the number of deleted notifications
+    fn delete_queued_notifications(&self, user_email: &str, until: TimestampMs) -> Result<usize>;
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -134,6 +290,32 @@ pub enum IndexQueryMode {
     WithoutRating,
 }
 
+/// The order of search results
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IndexSortOrder {
+    /// Highest total rating first
+    Rating,
+    /// Best matching results of a text or tag search first,
+    /// boosted by their rating. Otherwise the same as `Rating`.
+    Relevance,
+    /// Nearest to the center of the query first
+    Distance,
+    /// Most recently changed first
+    RecentlyChanged,
+    /// Most recently created first
+    RecentlyCreated,
+    /// Alphabetical (case-insensitive)
+    Title,
+    /// Most ratings first
+    RatingsCount,
+}
+
+impl Default for IndexSortOrder {
+    fn default() -> Self {
+        Self::Relevance
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct IndexQuery<'a, 'b> {
     // status = None: Don't filter by review status, i.e. return all entries
@@ -145,15 +327,54 @@ pub struct IndexQuery<'a, 'b> {
     pub status: Option<Vec<ReviewStatus>>,
     pub include_bbox: Option<MapBbox>,
     pub exclude_bbox: Option<MapBbox>,
+    // Only entries within the given radius around the center (both required)
+    pub center: Option<MapPoint>,
+    pub radius_meters: Option<f64>,
     pub categories: Vec<&'a str>,
     pub ids: Vec<&'b str>,
     pub hash_tags: Vec<String>,
+    // Only entries with at least one of these tags
+    pub any_hash_tags: Vec<String>,
+    // Only entries with none of these tags
+    pub exclude_hash_tags: Vec<String>,
     pub text_tags: Vec<String>,
     pub text: Option<String>,
+    // Maximum edit distance for matching the words of the text
+    // fuzzily. None or 0: Exact matching
+    pub fuzzy_distance: Option<u8>,
     pub ts_min_lb: Option<Timestamp>, // lower bound (inclusive)
     pub ts_min_ub: Option<Timestamp>, // upper bound (inclusive)
     pub ts_max_lb: Option<Timestamp>, // lower bound (inclusive)
     pub ts_max_ub: Option<Timestamp>, // upper bound (inclusive)
+    // Only places that have been changed within this time range
+    pub updated_since: Option<TimestampMs>, // lower bound (inclusive)
+    pub updated_until: Option<TimestampMs>, // upper bound (exclusive)
+    // Sorting by distance requires either a center or an included bbox
+    pub sort_by: IndexSortOrder,
+    // Prefer more complete places when sorting by relevance
+    pub boost_quality: bool,
+    // Number of results that precede the requested page
+    pub offset: usize,
+    // Continue after the last result of the previous page
+    pub after: Option<IndexCursor>,
+    // Only entries with a title that starts with this prefix (case-insensitive)
+    pub title_prefix: Option<String>,
+    // Only places with a tag that is owned by this organization
+    pub org_id: Option<String>,
+    // Only places with an average total rating of at least this value
+    pub min_total_rating: Option<AvgRatingValue>,
+    // Only places with an average rating of at least the given value
+    // in each of the rating contexts
+    pub min_ratings: Vec<(RatingContext, AvgRatingValue)>,
+}
+
+// Identifies the last result of a page independent of the
+// number of results that have been inserted or removed in
+// front of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexCursor {
+    pub score: f64,
+    pub id: String,
 }
 
 pub trait Indexer {
@@ -178,16 +399,64 @@ pub struct IndexedPlace {
     pub id: String,
     pub status: Option<ReviewStatus>,
     pub pos: MapPoint,
+    pub additional_positions: Vec<MapPoint>,
     pub title: String,
     pub description: String,
     pub tags: Vec<String>,
     pub ratings: AvgRatings,
+    // Ranking according to the requested sort order
+    pub score: f64,
+    // Only available when searching for text
+    pub title_snippet: Option<TextSnippet>,
+    pub description_snippet: Option<TextSnippet>,
+}
+
+impl IndexedPlace {
+    // The primary position followed by all additional positions
+    pub fn positions(&self) -> impl Iterator<Item = MapPoint> + '_ {
+        std::iter::once(self.pos).chain(self.additional_positions.iter().copied())
+    }
+}
+
+/// A fragment of an indexed text with the matching terms
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TextSnippet {
+    pub fragment: String,
+    // Byte ranges within the fragment
+    pub highlighted: Vec<Range<usize>>,
 }
 
 pub trait PlaceIndex {
     fn query_places(&self, query: &IndexQuery, limit: usize) -> Fallible<Vec<IndexedPlace>>;
 }
 
+pub trait TagIndex {
+    // The most frequent tags that start with the given prefix
+    fn suggest_tags(&self, prefix: &str, limit: usize) -> Fallible<Vec<TagFrequency>>;
+
+    // The tags of all visible places ordered by their frequency
+    fn most_popular_tags(
+        &self,
+        params: &MostPopularTagsParams,
+        pagination: &Pagination,
+    ) -> Fallible<Vec<TagFrequency>>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaceCluster {
+    // The average position of all places in the cluster
+    pub centroid: MapPoint,
+    pub count: usize,
+}
+
+pub trait PlaceClusterIndex {
+    // Aggregates all matching places into the cells of a grid
+    // with the given cell size in degrees. Places are assigned
+    // to a cell by their primary position.
+    fn cluster_places(&self, query: &IndexQuery, cell_size_deg: f64)
+        -> Fallible<Vec<PlaceCluster>>;
+}
+
 pub trait PlaceIndexer: IdIndexer + PlaceIndex {
     fn add_or_update_place(
         &self,
@@ -195,6 +464,20 @@ pub trait PlaceIndexer: IdIndexer + PlaceIndex {
         status: ReviewStatus,
         ratings: &AvgRatings,
     ) -> Fallible<()>;
+
+    // Only affects places that are (re-)indexed afterwards
+    fn set_curated_tags(&mut self, curated_tags: &[CuratedTag]);
+
+    // Only affects places that are (re-)indexed afterwards
+    fn set_tag_ownerships(&mut self, tag_ownerships: &[TagOwnership]);
+
+    // Definitions for resolving the categories and for recognizing
+    // category tags in all subsequent queries
+    fn set_categories(&mut self, categories: &[Category]);
+
+    // Commit all pending modifications and merge all segments
+    // of the index to speed up subsequent searches
+    fn optimize_index(&mut self) -> Fallible<()>;
 }
 
 pub trait EventIndexer: IdIndexer {
@@ -1,7 +1,8 @@
 pub use ofdb_entities::{
-    activity::*, address::*, category::*, comment::*, contact::*, email::*, event::*, geo::*,
-    id::*, links::*, location::*, nonce::*, organization::*, password::*, place::*, rating::*,
-    review::*, revision::*, subscription::*, tag::*, time::*, user::*,
+    activity::*, address::*, announcement::*, audit::*, category::*, clearance::*, comment::*,
+    contact::*, draft::*, email::*, event::*, geo::*, id::*, links::*, location::*, moderation::*,
+    nonce::*, organization::*, password::*, place::*, rating::*, report::*, review::*, revision::*,
+    subscription::*, tag::*, time::*, user::*, webhook::*,
 };
 
 #[cfg(test)]
@@ -44,6 +45,13 @@ pub mod place_builder {
             self.place.location.pos = pos;
             self
         }
+        pub fn additional_positions(mut self, positions: Vec<MapPoint>) -> Self {
+            self.place.additional_locations = positions
+                .into_iter()
+                .map(|pos| Location { pos, address: None })
+                .collect();
+            self
+        }
         pub fn tags(mut self, tags: Vec<&str>) -> Self {
             self.place.tags = tags.into_iter().map(|x| x.into()).collect();
             self
@@ -112,10 +120,12 @@ pub mod place_builder {
                         pos: MapPoint::from_lat_lng_deg(0.0, 0.0),
                         address: None,
                     },
+                    additional_locations: vec![],
                     contact: None,
                     opening_hours: None,
                     links: None,
                     tags: vec![],
+                    source: None,
                 },
             }
         }
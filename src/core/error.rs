@@ -1,3 +1,4 @@
+use crate::core::entities::PlaceId;
 use std::io;
 use thiserror::Error;
 
@@ -23,14 +24,28 @@ pub enum ParameterError {
     UserExists,
     #[error("The user does not exist")]
     UserDoesNotExist,
+    #[error("The user account has been deactivated")]
+    UserDeactivated,
     #[error("Invalid password")]
     Password,
     #[error("Empty comment")]
     EmptyComment,
+    #[error("Empty announcement")]
+    EmptyAnnouncement,
+    #[error("Empty name of organization")]
+    EmptyOrganizationName,
+    #[error("API token without scopes")]
+    EmptyApiTokenScopes,
+    #[error("Webhook without events")]
+    EmptyWebhookEvents,
+    #[error("The comment is too long")]
+    CommentTooLong,
     #[error("Rating value out of range")]
     RatingValue,
     #[error("Invalid rating context")]
     RatingContext(String),
+    #[error("Invalid review status")]
+    ReviewStatus(String),
     #[error("Invalid credentials")]
     Credentials,
     #[error("Email not confirmed")]
@@ -39,10 +54,18 @@ pub enum ParameterError {
     Forbidden,
     #[error("This is not allowed without auth")]
     Unauthorized,
+    #[error("Login with a confirmed email address required")]
+    LoginRequired,
     #[error("The date/time is out of range")]
     DateTimeOutOfRange,
     #[error("The end date is before the start")]
     EndDateBeforeStart,
+    #[error("Invalid recurrence")]
+    InvalidRecurrence,
+    #[error("The event does not accept registrations")]
+    RegistrationNotAvailable,
+    #[error("Already registered for the event")]
+    AlreadyRegistered,
     #[error("The tag is owned by an organization")]
     OwnedTag,
     #[error("Missing the email of the creator")]
@@ -51,22 +74,84 @@ pub enum ParameterError {
     InvalidOpeningHours,
     #[error("Invalid position")]
     InvalidPosition,
+    #[error("Invalid radius")]
+    InvalidRadius,
     #[error("Invalid limit")]
     InvalidLimit,
+    #[error("Invalid sort order")]
+    InvalidSortOrder,
+    #[error("Invalid audit action")]
+    InvalidAuditAction,
+    #[error("Invalid e-mail status")]
+    InvalidEmailStatus,
+    #[error("Invalid language")]
+    InvalidLanguage,
+    #[error("Invalid cursor")]
+    InvalidCursor,
+    #[error("Invalid offset")]
+    InvalidOffset,
+    #[error("Invalid zoom level")]
+    InvalidZoom,
+    #[error("Invalid source of imported place")]
+    InvalidPlaceSource,
+    #[error("Another place has already been imported from this source")]
+    DuplicatePlaceSource,
+    #[error("Maximum number of subscriptions exceeded")]
+    SubscriptionQuotaExceeded,
+    #[error("Invalid search query")]
+    InvalidSearchQuery,
+    #[error("Invalid tag")]
+    InvalidTag,
+    #[error("Invalid boost factor")]
+    InvalidBoost,
     #[error("Token invalid")]
     TokenInvalid,
     #[error("Token expired")]
     TokenExpired,
     #[error("Invalid nonce")]
     InvalidNonce,
+    #[error("Too many requests")]
+    TooManyRequests,
+    #[error("Invalid or missing captcha")]
+    InvalidCaptcha,
     #[error("Missing id list")]
     EmptyIdList,
+    #[error("Too many ids")]
+    TooManyIds,
+    #[error("All ratings must refer to the same place with different contexts")]
+    RatingBatch,
+    #[error("Only scouts can be assigned to moderation items")]
+    InvalidAssignee,
+    #[error("Invalid CSV")]
+    InvalidCsv,
+    #[error("The draft is too large")]
+    DraftTooLarge,
+    #[error("The revision is already the current revision")]
+    CurrentRevision,
+    #[error("Invalid external id")]
+    InvalidExternalId,
+    #[error("The external id is already used by another place")]
+    DuplicateExternalId,
+    #[error("Invalid role")]
+    InvalidRole,
+    #[error("The tag already exists")]
+    TagExists,
+    #[error("A place cannot be merged into itself")]
+    MergeIntoItself,
+    #[error("Invalid response format")]
+    InvalidResponseFormat,
+    #[error("The grace period for changing the rating has expired")]
+    GracePeriodExpired,
 }
 
 #[derive(Debug, Error)]
 pub enum RepoError {
     #[error("The requested object could not be found")]
     NotFound,
+    #[error("The requested object has been archived")]
+    Archived,
+    #[error("The requested object has been merged into {0}")]
+    MergedInto(PlaceId),
     #[cfg(test)]
     #[error("The object already exists")]
     AlreadyExists,
@@ -4,7 +4,12 @@
 // by their id and never modified or loaded by another
 // repository.
 
-use super::{entities::*, error::RepoError, util::time::Timestamp};
+use super::{
+    db::{AuditLogFilter, Pagination},
+    entities::*,
+    error::RepoError,
+    util::time::Timestamp,
+};
 
 type Result<T> = std::result::Result<T, RepoError>;
 
@@ -12,9 +17,13 @@ pub trait CommentRepository {
     fn create_comment(&self, _: Comment) -> Result<()>;
 
     // Only unarchived comments
-    fn load_comment(&self, id: &str) -> Result<Comment>;
+    fn load_comment(&self, id: &CommentId) -> Result<Comment>;
     fn load_comments(&self, id: &[&str]) -> Result<Vec<Comment>>;
-    fn load_comments_of_rating(&self, rating_id: &str) -> Result<Vec<Comment>>;
+    fn load_comments_of_rating(&self, rating_id: &RatingId) -> Result<Vec<Comment>>;
+
+    // Each user can flag a comment only once, repeated
+    // flags are ignored
+    fn flag_comment(&self, id: &CommentId, activity: &Activity) -> Result<()>;
 
     // Only unarchived comments (even if the rating has already been archived)
     fn zip_ratings_with_comments(
@@ -24,13 +33,18 @@ pub trait CommentRepository {
         let mut results = Vec::with_capacity(ratings.len());
         for rating in ratings {
             debug_assert!(rating.archived_at.is_none());
-            let comments = self.load_comments_of_rating(rating.id.as_ref())?;
+            let comments = self.load_comments_of_rating(&rating.id.clone().into())?;
             results.push((rating, comments));
         }
         Ok(results)
     }
 
-    fn archive_comments(&self, ids: &[&str], activity: &Activity) -> Result<usize>;
+    fn archive_comments(
+        &self,
+        ids: &[&str],
+        activity: &Activity,
+        comment: Option<&str>,
+    ) -> Result<usize>;
     fn archive_comments_of_ratings(
         &self,
         rating_ids: &[&str],
@@ -41,18 +55,77 @@ pub trait CommentRepository {
 
 pub trait RatingRepository {
     fn create_rating(&self, rating: Rating) -> Result<()>;
+    // Only the title and the value of unarchived ratings
+    fn update_rating(&self, rating: &Rating) -> Result<()>;
 
     // Only unarchived ratings without comments
-    fn load_rating(&self, id: &str) -> Result<Rating>;
+    fn load_rating(&self, id: &RatingId) -> Result<Rating>;
     fn load_ratings(&self, ids: &[&str]) -> Result<Vec<Rating>>;
-    fn load_ratings_of_place(&self, place_id: &str) -> Result<Vec<Rating>>;
+    fn load_ratings_of_place(&self, place_id: &PlaceId) -> Result<Vec<Rating>>;
+    // Only unarchived ratings, grouped by the month of their
+    // creation in chronological order
+    fn load_monthly_avg_ratings_of_place(
+        &self,
+        place_id: &PlaceId,
+    ) -> Result<Vec<MonthlyAvgRatings>>;
 
-    fn archive_ratings(&self, ids: &[&str], activity: &Activity) -> Result<usize>;
+    fn archive_ratings(
+        &self,
+        ids: &[&str],
+        activity: &Activity,
+        comment: Option<&str>,
+    ) -> Result<usize>;
     fn archive_ratings_of_places(&self, place_ids: &[&str], activity: &Activity) -> Result<usize>;
 
+    // Reassigns all ratings including archived ratings together
+    // with their comments
+    fn move_ratings_of_place(
+        &self,
+        from_place_id: &PlaceId,
+        to_place_id: &PlaceId,
+    ) -> Result<usize>;
+
     fn load_place_ids_of_ratings(&self, ids: &[&str]) -> Result<Vec<String>>;
 }
 
+pub trait ReportRepository {
+    fn create_report(&self, report: Report) -> Result<()>;
+
+    // Only unresolved reports in chronological order
+    fn load_open_reports(&self) -> Result<Vec<Report>>;
+
+    fn resolve_reports(&self, ids: &[&str], activity: &Activity) -> Result<usize>;
+}
+
+pub trait ModerationAssignmentRepository {
+    // Replaces any previous assignment of the same item
+    fn assign_moderation_item(&self, assignment: ModerationAssignment) -> Result<()>;
+
+    // Only unresolved assignments in chronological order
+    fn load_open_moderation_assignments(
+        &self,
+        assignee_email: &str,
+    ) -> Result<Vec<ModerationAssignment>>;
+
+    // Fails with `NotFound` if the item is not assigned to this user
+    fn update_moderation_assignment_state(
+        &self,
+        item_kind: ModerationItemKind,
+        item_id: &str,
+        assignee_email: &str,
+        state: AssignmentState,
+        changed_at: TimestampMs,
+    ) -> Result<()>;
+
+    // Resolves the assignments of the given items, regardless of the assignee
+    fn resolve_moderation_assignments(
+        &self,
+        item_kind: ModerationItemKind,
+        item_ids: &[&str],
+        resolved_at: TimestampMs,
+    ) -> Result<usize>;
+}
+
 pub trait UserTokenRepo {
     fn replace_user_token(&self, user_token: UserToken) -> Result<EmailNonce>;
 
@@ -60,5 +133,224 @@ pub trait UserTokenRepo {
 
     fn delete_expired_user_tokens(&self, expired_before: Timestamp) -> Result<usize>;
 
+    // All tokens that are not expired before the given time stamp, ordered by e-mail
+    fn load_valid_user_tokens(&self, expired_before: Timestamp) -> Result<Vec<UserToken>>;
+
     fn get_user_token_by_email(&self, email: &str) -> Result<UserToken>;
 }
+
+pub trait LoginLinkRepo {
+    // Replaces any previous link for the same e-mail address
+    fn replace_login_link(&self, login_link: &LoginLink) -> Result<()>;
+
+    fn get_login_link_by_email(&self, email: &str) -> Result<LoginLink>;
+
+    fn consume_login_link(&self, email_nonce: &EmailNonce) -> Result<LoginLink>;
+
+    fn delete_expired_login_links(&self, expired_before: Timestamp) -> Result<usize>;
+}
+
+pub trait ContactVerificationRepo {
+    // Replaces any previous verification of the same
+    // e-mail address of the place
+    fn replace_contact_verification(&self, verification: &ContactVerification) -> Result<()>;
+
+    fn load_contact_verification(&self, email_nonce: &EmailNonce) -> Result<ContactVerification>;
+
+    // Only completed verifications
+    fn is_contact_verified(&self, place_id: &PlaceId, email: &str) -> Result<bool>;
+}
+
+pub trait EventRegistrationRepo {
+    // Replaces any previous registration of the same
+    // e-mail address for the event
+    fn replace_event_registration(&self, registration: &EventRegistration) -> Result<()>;
+
+    fn load_event_registration(&self, email_nonce: &EmailNonce) -> Result<EventRegistration>;
+
+    // Including cancelled registrations, ordered by the time of registration
+    fn load_event_registrations(&self, event_id: &EventId) -> Result<Vec<EventRegistration>>;
+}
+
+pub trait PlaceExternalIdRepo {
+    // Ordered by source and id
+    fn load_place_external_ids(&self, place_id: &PlaceId) -> Result<Vec<ExternalId>>;
+
+    // Replaces all external ids of the place
+    fn replace_place_external_ids(
+        &self,
+        place_id: &PlaceId,
+        external_ids: &[ExternalId],
+    ) -> Result<()>;
+
+    fn find_place_id_by_external_id(&self, source: &str, id: &str) -> Result<Option<PlaceId>>;
+}
+
+pub trait PlaceMergeRepo {
+    // Replaces a previous merge of the duplicate
+    fn create_place_merge(
+        &self,
+        duplicate_id: &PlaceId,
+        primary_id: &PlaceId,
+        merged_at: TimestampMs,
+    ) -> Result<()>;
+
+    // The primary place into which the place has been merged, if any
+    fn get_place_merged_into(&self, place_id: &PlaceId) -> Result<Option<PlaceId>>;
+}
+
+pub trait PlaceDraftRepo {
+    fn create_place_draft(&self, draft: &PlaceDraft) -> Result<()>;
+
+    // Fails with `NotFound` if the draft does not exist
+    fn update_place_draft(&self, draft: &PlaceDraft) -> Result<()>;
+
+    fn load_place_draft(&self, id: &str) -> Result<PlaceDraft>;
+
+    // Most recently updated drafts first
+    fn load_place_drafts_of_owner(&self, owner_email: &str) -> Result<Vec<PlaceDraft>>;
+
+    fn delete_place_draft(&self, id: &str) -> Result<()>;
+}
+
+pub trait UserPermissionRepo {
+    // Only the permissions that deviate from the defaults of the role
+    fn load_permission_overrides(&self, user_email: &str) -> Result<Vec<PermissionOverride>>;
+
+    fn replace_permission_override(
+        &self,
+        user_email: &str,
+        permission_override: &PermissionOverride,
+    ) -> Result<()>;
+
+    fn delete_permission_override(&self, user_email: &str, permission: Permission) -> Result<()>;
+}
+
+pub trait AuditLogRepo {
+    fn log_audit_entry(&self, entry: &AuditLogEntry) -> Result<()>;
+
+    // Ordered by the time of the action, most recent first
+    fn load_audit_log(
+        &self,
+        filter: &AuditLogFilter,
+        pagination: &Pagination,
+    ) -> Result<Vec<AuditLogEntry>>;
+}
+
+pub trait AnnouncementRepo {
+    fn create_announcement(&self, announcement: &Announcement) -> Result<()>;
+
+    // Fails with `NotFound` if the announcement does not exist
+    fn delete_announcement(&self, id: &str) -> Result<()>;
+
+    // Ordered by start time
+    fn all_announcements(&self) -> Result<Vec<Announcement>>;
+
+    // Only the announcements that are displayed at the given
+    // time, ordered by start time
+    fn load_announcements_displayed_at(&self, at: TimestampMs) -> Result<Vec<Announcement>>;
+}
+
+pub trait PlaceClearanceRepo {
+    // Places that are already pending keep their creation
+    // time and their last cleared revision
+    fn add_pending_clearance_for_place(
+        &self,
+        org_ids: &[&str],
+        pending: &PendingClearanceForPlace,
+    ) -> Result<()>;
+
+    // Ordered by creation time, oldest first
+    fn load_pending_clearances_for_places(
+        &self,
+        org_id: &str,
+    ) -> Result<Vec<PendingClearanceForPlace>>;
+
+    // Fails with `NotFound` if the place is not pending
+    fn delete_pending_clearance_for_place(&self, org_id: &str, place_id: &str) -> Result<()>;
+
+    // The number of organizations that still need to clear the place
+    fn count_pending_clearances_for_place(&self, place_id: &str) -> Result<usize>;
+}
+
+pub trait OrganizationApiTokenRepo {
+    fn create_org_api_token(&self, token: &OrganizationApiToken) -> Result<()>;
+
+    // Including expired tokens, ordered by creation time
+    fn load_org_api_tokens(&self, org_id: &str) -> Result<Vec<OrganizationApiToken>>;
+
+    // Including expired tokens
+    fn get_org_api_token(&self, token: &str) -> Result<OrganizationApiToken>;
+
+    // Fails with `NotFound` if the organization has no token
+    // with this id
+    fn delete_org_api_token(&self, org_id: &str, id: &str) -> Result<()>;
+}
+
+pub trait UserApiTokenRepo {
+    fn create_user_api_token(&self, token: &UserApiToken) -> Result<()>;
+
+    // Ordered by creation time
+    fn load_user_api_tokens(&self, user_email: &str) -> Result<Vec<UserApiToken>>;
+
+    fn get_user_api_token(&self, token: &str) -> Result<UserApiToken>;
+
+    // Fails with `NotFound` if the user has no token with this id
+    fn delete_user_api_token(&self, user_email: &str, id: &str) -> Result<()>;
+}
+
+pub trait WebhookRepo {
+    fn create_webhook(&self, webhook: &Webhook) -> Result<()>;
+
+    // Ordered by creation time
+    fn all_webhooks(&self) -> Result<Vec<Webhook>>;
+
+    fn get_webhook(&self, id: &str) -> Result<Webhook>;
+
+    // Also deletes all deliveries of the webhook
+    fn delete_webhook(&self, id: &str) -> Result<()>;
+
+    fn create_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<()>;
+
+    // Updates the status and the attempts of the delivery
+    fn update_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<()>;
+
+    // Pending deliveries with a next attempt until the given
+    // time, ordered by the time of the next attempt
+    fn load_due_webhook_deliveries(
+        &self,
+        due_at: TimestampMs,
+        limit: u64,
+    ) -> Result<Vec<WebhookDelivery>>;
+
+    // Newest first
+    fn load_webhook_deliveries(
+        &self,
+        webhook_id: &str,
+        pagination: &Pagination,
+    ) -> Result<Vec<WebhookDelivery>>;
+}
+
+pub trait EmailOutboxRepo {
+    fn create_outgoing_email(&self, email: &OutgoingEmail) -> Result<()>;
+
+    // Updates the status and the attempts of the e-mail
+    fn update_outgoing_email(&self, email: &OutgoingEmail) -> Result<()>;
+
+    fn delete_outgoing_email(&self, id: &str) -> Result<()>;
+
+    // Pending e-mails with a next attempt until the given
+    // time, ordered by the time of the next attempt
+    fn load_due_outgoing_emails(
+        &self,
+        due_at: TimestampMs,
+        limit: u64,
+    ) -> Result<Vec<OutgoingEmail>>;
+
+    // Newest first, optionally only those with the given status
+    fn load_outgoing_emails(
+        &self,
+        status: Option<OutgoingEmailStatus>,
+        pagination: &Pagination,
+    ) -> Result<Vec<OutgoingEmail>>;
+}
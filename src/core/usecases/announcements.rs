@@ -0,0 +1,158 @@
+use super::authorize_user_by_email;
+use crate::core::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct NewAnnouncement {
+    pub message: String,
+    pub severity: AnnouncementSeverity,
+    /// Displayed immediately if missing
+    pub starts_at: Option<TimestampMs>,
+    /// Displayed until deleted if missing
+    pub ends_at: Option<TimestampMs>,
+}
+
+pub fn create_announcement<D: Db>(
+    db: &D,
+    account_email: &str,
+    new_announcement: NewAnnouncement,
+) -> Result<Announcement> {
+    authorize_user_by_email(db, account_email, Permission::ManageAnnouncements)?;
+    let NewAnnouncement {
+        message,
+        severity,
+        starts_at,
+        ends_at,
+    } = new_announcement;
+    let message = message.trim();
+    if message.is_empty() {
+        return Err(ParameterError::EmptyAnnouncement.into());
+    }
+    let starts_at = starts_at.unwrap_or_else(TimestampMs::now);
+    if let Some(ends_at) = ends_at {
+        if ends_at <= starts_at {
+            return Err(ParameterError::EndDateBeforeStart.into());
+        }
+    }
+    let announcement = Announcement {
+        id: Id::new(),
+        message: message.to_owned(),
+        severity,
+        starts_at,
+        ends_at,
+    };
+    info!("Creating announcement {}", announcement.id);
+    db.create_announcement(&announcement)?;
+    Ok(announcement)
+}
+
+pub fn delete_announcement<D: Db>(db: &D, account_email: &str, id: &str) -> Result<()> {
+    authorize_user_by_email(db, account_email, Permission::ManageAnnouncements)?;
+    info!("Deleting announcement {}", id);
+    Ok(db.delete_announcement(id)?)
+}
+
+/// All announcements including past and upcoming ones.
+pub fn load_announcements<D: Db>(db: &D, account_email: &str) -> Result<Vec<Announcement>> {
+    authorize_user_by_email(db, account_email, Permission::ManageAnnouncements)?;
+    Ok(db.all_announcements()?)
+}
+
+/// The announcements that should be displayed to all visitors.
+pub fn load_current_announcements<D: Db>(db: &D) -> Result<Vec<Announcement>> {
+    Ok(db.load_announcements_displayed_at(TimestampMs::now())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_user, MockDb};
+    use super::*;
+
+    fn new_announcement(message: &str) -> NewAnnouncement {
+        NewAnnouncement {
+            message: message.into(),
+            severity: AnnouncementSeverity::Warning,
+            starts_at: None,
+            ends_at: None,
+        }
+    }
+
+    #[test]
+    fn manage_announcements_as_admin() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        let now = TimestampMs::now();
+
+        let current =
+            create_announcement(&db, "admin@example.com", new_announcement(" Maintenance "))
+                .unwrap();
+        assert_eq!("Maintenance", current.message);
+        let upcoming = create_announcement(
+            &db,
+            "admin@example.com",
+            NewAnnouncement {
+                starts_at: Some(TimestampMs::from_inner(now.into_inner() + 3_600_000)),
+                ..new_announcement("Campaign")
+            },
+        )
+        .unwrap();
+        create_announcement(
+            &db,
+            "admin@example.com",
+            NewAnnouncement {
+                starts_at: Some(TimestampMs::from_inner(now.into_inner() - 7_200_000)),
+                ends_at: Some(TimestampMs::from_inner(now.into_inner() - 3_600_000)),
+                ..new_announcement("Outdated")
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            3,
+            load_announcements(&db, "admin@example.com").unwrap().len()
+        );
+        assert_eq!(
+            vec![current.clone()],
+            load_current_announcements(&db).unwrap()
+        );
+
+        delete_announcement(&db, "admin@example.com", current.id.as_str()).unwrap();
+        assert!(load_current_announcements(&db).unwrap().is_empty());
+        assert!(matches!(
+            delete_announcement(&db, "admin@example.com", current.id.as_str()),
+            Err(Error::Repo(RepoError::NotFound))
+        ));
+        assert!(load_announcements(&db, "admin@example.com")
+            .unwrap()
+            .contains(&upcoming));
+    }
+
+    #[test]
+    fn reject_invalid_announcements() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_user(&db, "scout@example.com", Role::Scout);
+        let now = TimestampMs::now();
+
+        assert!(matches!(
+            create_announcement(&db, "scout@example.com", new_announcement("foo")),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+        assert!(matches!(
+            create_announcement(&db, "admin@example.com", new_announcement("  ")),
+            Err(Error::Parameter(ParameterError::EmptyAnnouncement))
+        ));
+        assert!(matches!(
+            create_announcement(
+                &db,
+                "admin@example.com",
+                NewAnnouncement {
+                    starts_at: Some(now),
+                    ends_at: Some(now),
+                    ..new_announcement("foo")
+                }
+            ),
+            Err(Error::Parameter(ParameterError::EndDateBeforeStart))
+        ));
+        assert!(db.all_announcements().unwrap().is_empty());
+    }
+}
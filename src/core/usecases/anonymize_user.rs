@@ -0,0 +1,17 @@
+use crate::core::prelude::*;
+
+/// Delete a user account while keeping all contributed content.
+///
+/// The e-mail address of the user is removed from all places,
+/// events, ratings, comments and reports that have been created
+/// or modified by this user. All tokens and subscriptions of the
+/// user are deleted together with the account.
+///
+/// Users may delete their own account, operators may anonymize
+/// any account from the command line.
+pub fn anonymize_user<D: Db>(db: &D, email: &str) -> Result<()> {
+    db.try_get_user_by_email(email)?
+        .ok_or(ParameterError::UserDoesNotExist)?;
+    info!("Anonymizing user {}", email);
+    Ok(db.anonymize_user_by_email(email)?)
+}
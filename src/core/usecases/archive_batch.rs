@@ -0,0 +1,134 @@
+use super::authorize_user_by_email;
+use crate::core::prelude::*;
+use std::collections::HashSet;
+
+/// The maximum number of ids that are archived at once.
+pub const MAX_ARCHIVE_BATCH_SIZE: usize = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Place,
+    Event,
+    Rating,
+}
+
+impl ArchiveKind {
+    fn required_permission(self) -> Permission {
+        match self {
+            ArchiveKind::Place => Permission::ReviewPlaces,
+            ArchiveKind::Event => Permission::ArchiveEvents,
+            ArchiveKind::Rating => Permission::ArchiveRatings,
+        }
+    }
+}
+
+/// The outcome of archiving many items at once.
+///
+/// Each requested id occurs in exactly one of the lists.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArchiveBatchReport {
+    pub archived: Vec<String>,
+    /// Items that don't exist or have already been archived
+    pub not_found: Vec<String>,
+    /// Items that could not be archived due to an error
+    pub failed: Vec<String>,
+}
+
+/// Split the requested ids into those of items that could be
+/// archived and those of items that don't exist or that have
+/// already been archived.
+///
+/// Duplicate ids are ignored and the order of the request is
+/// preserved.
+pub fn prepare_archive_batch<D: Db>(
+    db: &D,
+    account_email: &str,
+    kind: ArchiveKind,
+    ids: &[&str],
+) -> Result<(Vec<String>, Vec<String>)> {
+    authorize_user_by_email(db, account_email, kind.required_permission())?;
+    if ids.is_empty() {
+        return Err(ParameterError::EmptyIdList.into());
+    }
+    if ids.len() > MAX_ARCHIVE_BATCH_SIZE {
+        return Err(ParameterError::TooManyIds.into());
+    }
+    let archivable: HashSet<String> = match kind {
+        ArchiveKind::Place => db
+            .get_places(ids)?
+            .into_iter()
+            .filter(|(_, status)| *status != ReviewStatus::Archived)
+            .map(|(place, _)| place.id.into())
+            .collect(),
+        // Archived events and ratings are never loaded
+        ArchiveKind::Event => db
+            .get_events_chronologically(ids)?
+            .into_iter()
+            .map(|event| event.id.into())
+            .collect(),
+        ArchiveKind::Rating => db
+            .load_ratings(ids)?
+            .into_iter()
+            .map(|rating| rating.id.into())
+            .collect(),
+    };
+    let mut requested = HashSet::with_capacity(ids.len());
+    let (archivable, not_found) = ids
+        .iter()
+        .filter(|id| requested.insert(**id))
+        .map(|id| (*id).to_owned())
+        .partition(|id| archivable.contains(id));
+    Ok((archivable, not_found))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    #[test]
+    fn prepare_archive_batch_of_places() {
+        let db = MockDb::default();
+        db.create_user(&User {
+            email: "scout@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Scout,
+            language: None,
+        })
+        .unwrap();
+        db.entries
+            .borrow_mut()
+            .push((Place::build().id("current").finish(), ReviewStatus::Created));
+        db.entries.borrow_mut().push((
+            Place::build().id("archived").finish(),
+            ReviewStatus::Archived,
+        ));
+
+        let (archivable, not_found) = prepare_archive_batch(
+            &db,
+            "scout@example.com",
+            ArchiveKind::Place,
+            &["archived", "current", "unknown", "current"],
+        )
+        .unwrap();
+        assert_eq!(vec!["current".to_string()], archivable);
+        assert_eq!(
+            vec!["archived".to_string(), "unknown".to_string()],
+            not_found
+        );
+
+        assert!(prepare_archive_batch(&db, "scout@example.com", ArchiveKind::Place, &[]).is_err());
+        assert!(prepare_archive_batch(
+            &db,
+            "scout@example.com",
+            ArchiveKind::Place,
+            &["current"; MAX_ARCHIVE_BATCH_SIZE + 1]
+        )
+        .is_err());
+        assert!(
+            prepare_archive_batch(&db, "guest@example.com", ArchiveKind::Place, &["current"])
+                .is_err()
+        );
+    }
+}
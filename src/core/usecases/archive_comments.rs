@@ -1,14 +1,19 @@
 use crate::core::prelude::*;
 
-pub fn archive_comments<D: Db>(db: &D, user_email: &str, ids: &[&str]) -> Result<usize> {
+pub fn archive_comments<D: Db>(
+    db: &D,
+    user_email: &str,
+    ids: &[&str],
+    comment: Option<&str>,
+) -> Result<usize> {
     info!("Archiving {} comments", ids.len());
     // TODO: Pass an authentication token with user id and role to
     // check if the user is authorized to perform this use case
     let user = db.try_get_user_by_email(user_email)?;
     if let Some(user) = user {
-        if user.role >= Role::Scout {
+        if super::has_permission(db, &user, Permission::ArchiveComments)? {
             let archived = Activity::now(Some(user_email.into()));
-            return Ok(db.archive_comments(ids, &archived)?);
+            return Ok(db.archive_comments(ids, &archived, comment)?);
         }
     }
     Err(ParameterError::Forbidden.into())
@@ -1,7 +1,12 @@
 use crate::core::prelude::*;
 
-pub fn archive_events<D: Db>(db: &D, ids: &[&str]) -> Result<usize> {
+pub fn archive_events<D: Db>(
+    db: &D,
+    ids: &[&str],
+    archived_by_email: &str,
+    comment: Option<&str>,
+) -> Result<usize> {
     debug!("Archiving events {:?}", ids);
-    let archived = Timestamp::now();
-    Ok(db.archive_events(ids, archived)?)
+    let archived = Activity::now(Some(archived_by_email.into()));
+    Ok(db.archive_events(ids, &archived, comment)?)
 }
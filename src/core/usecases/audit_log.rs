@@ -0,0 +1,38 @@
+use super::authorize_user_by_email;
+use crate::core::prelude::*;
+
+/// Record an action of an admin or scout for each
+/// of the affected items.
+pub fn log_audit_action<D: Db>(
+    db: &D,
+    account_email: &str,
+    action: AuditAction,
+    items: &[&str],
+    context: Option<&str>,
+    comment: Option<&str>,
+) -> Result<()> {
+    let activity = ActivityLog {
+        activity: Activity::now(Some(account_email.into())),
+        context: context.map(ToOwned::to_owned),
+        comment: comment.map(ToOwned::to_owned),
+    };
+    for item in items {
+        db.log_audit_entry(&AuditLogEntry {
+            activity: activity.clone(),
+            action,
+            item: (*item).to_owned(),
+        })?;
+    }
+    Ok(())
+}
+
+/// Only admins are allowed to inspect the audit log.
+pub fn load_audit_log<D: Db>(
+    db: &D,
+    account_email: &str,
+    filter: &AuditLogFilter,
+    pagination: &Pagination,
+) -> Result<Vec<AuditLogEntry>> {
+    authorize_user_by_email(db, account_email, Permission::ViewAuditLog)?;
+    Ok(db.load_audit_log(filter, pagination)?)
+}
@@ -1,8 +1,31 @@
 use crate::core::prelude::*;
 
-pub fn authorize_organization_by_token<D: Db>(db: &D, token: &str) -> Result<Organization> {
-    db.get_org_by_api_token(token).map_err(|e| match e {
-        RepoError::NotFound => Error::Parameter(ParameterError::Unauthorized),
-        _ => Error::Repo(e),
-    })
+/// Authorize an organization by one of its API tokens that
+/// must not have expired and must grant the scope.
+pub fn authorize_organization_by_token<D: Db>(
+    db: &D,
+    token: &str,
+    scope: ApiTokenScope,
+) -> Result<Organization> {
+    match db.get_org_api_token(token) {
+        Ok(api_token) => {
+            if api_token.is_expired_at(TimestampMs::now()) {
+                info!(
+                    "API token {} of organization {} has expired",
+                    api_token.id, api_token.org_id
+                );
+                return Err(Error::Parameter(ParameterError::Unauthorized));
+            }
+            if !api_token.has_scope(scope) {
+                info!(
+                    "API token {} of organization {} does not grant {:?}",
+                    api_token.id, api_token.org_id, scope
+                );
+                return Err(Error::Parameter(ParameterError::Forbidden));
+            }
+            Ok(db.get_org_by_id(&api_token.org_id)?)
+        }
+        Err(RepoError::NotFound) => Err(Error::Parameter(ParameterError::Unauthorized)),
+        Err(e) => Err(Error::Repo(e)),
+    }
 }
@@ -0,0 +1,128 @@
+use super::authorize_user_by_email;
+use crate::core::{prelude::*, util::validate};
+
+use std::collections::BTreeSet;
+
+/// All subscriptions of all users, e.g. for migrating
+/// them to another instance.
+pub fn export_bbox_subscriptions<D: Db>(
+    db: &D,
+    account_email: &str,
+) -> Result<Vec<BboxSubscription>> {
+    authorize_user_by_email(db, account_email, Permission::ManageSubscriptions)?;
+    Ok(db.all_bbox_subscriptions()?)
+}
+
+/// Create the given subscriptions in bulk.
+///
+/// Subscriptions that already exist or that belong to unknown
+/// users are skipped. Returns the number of created subscriptions.
+pub fn import_bbox_subscriptions<D: Db>(
+    db: &D,
+    account_email: &str,
+    subscriptions: Vec<BboxSubscription>,
+) -> Result<usize> {
+    authorize_user_by_email(db, account_email, Permission::ManageSubscriptions)?;
+    for s in &subscriptions {
+        validate::bbox(&s.bbox)?;
+    }
+    let mut existing_ids: BTreeSet<_> = db
+        .all_bbox_subscriptions()?
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+    let mut count = 0;
+    for s in subscriptions {
+        if existing_ids.contains(&s.id) {
+            debug!("Skipping existing subscription {}", s.id);
+            continue;
+        }
+        if db.try_get_user_by_email(&s.user_email)?.is_none() {
+            warn!(
+                "Skipping subscription {} of unknown user {}",
+                s.id, s.user_email
+            );
+            continue;
+        }
+        db.create_bbox_subscription(&s)?;
+        existing_ids.insert(s.id);
+        count += 1;
+    }
+    info!("Imported {} bbox subscription(s)", count);
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_user, MockDb};
+    use super::*;
+
+    fn new_subscription(id: &str, user_email: &str) -> BboxSubscription {
+        BboxSubscription {
+            id: id.into(),
+            user_email: user_email.into(),
+            bbox: MapBbox::new(
+                MapPoint::from_lat_lng_deg(1.0, 2.0),
+                MapPoint::from_lat_lng_deg(3.0, 4.0),
+            ),
+        }
+    }
+
+    #[test]
+    fn import_and_export_bbox_subscriptions_as_admin() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_user(&db, "user@example.com", Role::User);
+        db.create_bbox_subscription(&new_subscription("a", "user@example.com"))
+            .unwrap();
+
+        assert!(matches!(
+            import_bbox_subscriptions(&db, "user@example.com", vec![]),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+        assert!(matches!(
+            export_bbox_subscriptions(&db, "user@example.com"),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+
+        let imported = import_bbox_subscriptions(
+            &db,
+            "admin@example.com",
+            vec![
+                new_subscription("a", "user@example.com"),
+                new_subscription("b", "user@example.com"),
+                new_subscription("c", "unknown@example.com"),
+                new_subscription("b", "user@example.com"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(1, imported);
+        let ids: Vec<_> = export_bbox_subscriptions(&db, "admin@example.com")
+            .unwrap()
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(vec![Id::from("a"), Id::from("b")], ids);
+    }
+
+    #[test]
+    fn reject_invalid_bbox_subscriptions() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_user(&db, "user@example.com", Role::User);
+        let mut empty_bbox = new_subscription("a", "user@example.com");
+        empty_bbox.bbox = MapBbox::new(
+            MapPoint::from_lat_lng_deg(1.0, 2.0),
+            MapPoint::from_lat_lng_deg(1.0, 2.0),
+        );
+        assert!(matches!(
+            import_bbox_subscriptions(
+                &db,
+                "admin@example.com",
+                vec![new_subscription("b", "user@example.com"), empty_bbox]
+            ),
+            Err(Error::Parameter(ParameterError::Bbox))
+        ));
+        assert!(db.all_bbox_subscriptions().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+use crate::core::prelude::*;
+
+/// Authors are only allowed to change their ratings
+/// within a limited time after they have been created.
+const RATING_GRACE_PERIOD_SECONDS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone)]
+pub struct RatingUpdate {
+    pub title: String,
+    pub value: RatingValue,
+}
+
+fn authorize_rating_author_change<D: Db>(db: &D, account_email: &str, id: &str) -> Result<Rating> {
+    let rating = db.load_rating(&id.into())?;
+    // Ratings of anonymous visitors cannot be changed at all
+    if rating.created_by.as_deref() != Some(account_email) {
+        return Err(ParameterError::Forbidden.into());
+    }
+    let age_seconds = TimestampMs::now().into_seconds() - rating.created_at.into_seconds();
+    if age_seconds > RATING_GRACE_PERIOD_SECONDS {
+        return Err(ParameterError::GracePeriodExpired.into());
+    }
+    Ok(rating)
+}
+
+/// Change the title and the value of a rating.
+pub fn update_rating<D: Db>(
+    db: &D,
+    account_email: &str,
+    id: &str,
+    update: RatingUpdate,
+) -> Result<Rating> {
+    let mut rating = authorize_rating_author_change(db, account_email, id)?;
+    let RatingUpdate { title, value } = update;
+    if !value.is_valid() {
+        return Err(ParameterError::RatingValue.into());
+    }
+    rating.title = title;
+    rating.value = value;
+    db.update_rating(&rating)?;
+    Ok(rating)
+}
+
+/// Archive a rating together with all of its comments.
+pub fn retract_rating<D: Db>(db: &D, account_email: &str, id: &str) -> Result<Rating> {
+    let rating = authorize_rating_author_change(db, account_email, id)?;
+    let archived = Activity::now(Some(account_email.into()));
+    db.archive_comments_of_ratings(&[id], &archived)?;
+    db.archive_ratings(&[id], &archived, None)?;
+    Ok(rating)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn new_rating(id: &str, created_at: TimestampMs, created_by: Option<&str>) -> Rating {
+        Rating {
+            id: id.into(),
+            place_id: "foo".into(),
+            created_at,
+            created_by: created_by.map(Into::into),
+            archived_at: None,
+            title: "title".into(),
+            value: RatingValue::new(1),
+            context: RatingContext::Fairness,
+            source: None,
+        }
+    }
+
+    fn update(value: i8) -> RatingUpdate {
+        RatingUpdate {
+            title: "changed".into(),
+            value: RatingValue::from(value),
+        }
+    }
+
+    #[test]
+    fn only_authors_can_change_their_ratings() {
+        let db = MockDb::default();
+        db.create_rating(new_rating(
+            "mine",
+            TimestampMs::now(),
+            Some("me@example.com"),
+        ))
+        .unwrap();
+        db.create_rating(new_rating("anonymous", TimestampMs::now(), None))
+            .unwrap();
+
+        assert!(matches!(
+            update_rating(&db, "other@example.com", "mine", update(2)),
+            Err(Error::Parameter(ParameterError::Forbidden))
+        ));
+        assert!(matches!(
+            update_rating(&db, "me@example.com", "anonymous", update(2)),
+            Err(Error::Parameter(ParameterError::Forbidden))
+        ));
+        assert!(matches!(
+            update_rating(&db, "me@example.com", "mine", update(3)),
+            Err(Error::Parameter(ParameterError::RatingValue))
+        ));
+
+        let rating = update_rating(&db, "me@example.com", "mine", update(2)).unwrap();
+        assert_eq!("changed", rating.title);
+        let rating = db.load_rating(&"mine".into()).unwrap();
+        assert_eq!("changed", rating.title);
+        assert_eq!(RatingValue::new(2), rating.value);
+    }
+
+    #[test]
+    fn retract_rating_with_comments() {
+        let db = MockDb::default();
+        db.create_rating(new_rating(
+            "mine",
+            TimestampMs::now(),
+            Some("me@example.com"),
+        ))
+        .unwrap();
+        db.create_comment(Comment {
+            id: "c".into(),
+            rating_id: "mine".into(),
+            created_at: TimestampMs::now(),
+            archived_at: None,
+            text: "text".into(),
+        })
+        .unwrap();
+
+        assert!(retract_rating(&db, "other@example.com", "mine").is_err());
+        retract_rating(&db, "me@example.com", "mine").unwrap();
+        assert!(db.load_rating(&"mine".into()).is_err());
+        assert!(db.comments.borrow()[0].archived_at.is_some());
+        // Retracted ratings are gone
+        assert!(retract_rating(&db, "me@example.com", "mine").is_err());
+    }
+
+    #[test]
+    fn ratings_cannot_be_changed_after_the_grace_period() {
+        let db = MockDb::default();
+        let created_at = TimestampMs::from_seconds(
+            TimestampMs::now().into_seconds() - RATING_GRACE_PERIOD_SECONDS - 1,
+        );
+        db.create_rating(new_rating("old", created_at, Some("me@example.com")))
+            .unwrap();
+        assert!(matches!(
+            update_rating(&db, "me@example.com", "old", update(2)),
+            Err(Error::Parameter(ParameterError::GracePeriodExpired))
+        ));
+        assert!(matches!(
+            retract_rating(&db, "me@example.com", "old"),
+            Err(Error::Parameter(ParameterError::GracePeriodExpired))
+        ));
+    }
+}
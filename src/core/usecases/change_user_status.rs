@@ -0,0 +1,298 @@
+use super::{authorize_user_by_email, restore_place_revision::copy_place_revision};
+use crate::core::prelude::*;
+
+const USER_DEACTIVATED_REVIEW_COMMENT: &str = "User account deactivated";
+
+fn authorize_user_status_change<D: Db>(
+    db: &D,
+    account_email: &str,
+    user_email: &str,
+) -> Result<()> {
+    let account = authorize_user_by_email(db, account_email, Permission::ManageUsers)?;
+    let user = db
+        .try_get_user_by_email(user_email)?
+        .ok_or(ParameterError::UserDoesNotExist)?;
+    if account.role > user.role {
+        Ok(())
+    } else {
+        Err(ParameterError::Forbidden.into())
+    }
+}
+
+/// Places that have been changed while deactivating a user.
+#[derive(Debug, Default)]
+pub struct DeactivatedUserPlaces {
+    /// Pending places that have only been edited by the user
+    pub archived_place_ids: Vec<Id>,
+    /// Pending places that had been edited by others before
+    /// and are reverted to the latest of these revisions
+    pub restored_places: Vec<Place>,
+}
+
+/// Deactivate a user account without deleting any data.
+///
+/// The user is no longer able to log in. Places with pending
+/// (= not yet confirmed) revisions that have been created by
+/// this user are archived if all their revisions have been
+/// created by this user. Otherwise the latest revision of
+/// another author is restored with its previous review status.
+///
+/// Events and ratings of the user are not affected, because
+/// they are visible without any review anyway and can be
+/// archived separately by scouts if needed.
+pub fn deactivate_user<D: Db>(
+    db: &D,
+    account_email: &str,
+    user_email: &str,
+    comment: Option<String>,
+) -> Result<DeactivatedUserPlaces> {
+    authorize_user_status_change(db, account_email, user_email)?;
+    if !db.is_user_active(user_email)? {
+        info!("User {} has already been deactivated", user_email);
+        return Ok(Default::default());
+    }
+    info!("Deactivating user {}", user_email);
+    let activity = Activity::now(Some(account_email.into()));
+    db.log_user_status(
+        user_email,
+        &UserStatusLog {
+            activity: ActivityLog {
+                activity: activity.clone(),
+                context: None,
+                comment,
+            },
+            active: false,
+        },
+    )?;
+    let is_created_by_user =
+        |created: &Activity| created.by.as_ref().map(|by| by.as_str()) == Some(user_email);
+    let mut places = DeactivatedUserPlaces::default();
+    for (place, status) in db.all_places()? {
+        if status != ReviewStatus::Created || !is_created_by_user(&place.created) {
+            continue;
+        }
+        let place_id = PlaceId::from(place.id.as_str());
+        let PlaceHistory { revisions, .. } = db.get_place_history(&place_id)?;
+        let previous_revision = revisions
+            .into_iter()
+            .filter(|(place_revision, _)| !is_created_by_user(&place_revision.created))
+            .max_by_key(|(place_revision, _)| place_revision.revision);
+        let (place_revision, review_logs) = match previous_revision {
+            Some(previous_revision) => previous_revision,
+            None => {
+                places.archived_place_ids.push(place.id);
+                continue;
+            }
+        };
+        let restored_place =
+            copy_place_revision(db, &place_id, place_revision.revision, &activity)?;
+        // The review logs are ordered from newest to oldest
+        let previous_status = review_logs
+            .first()
+            .map(|review_log| review_log.status)
+            .unwrap_or(ReviewStatus::Created);
+        db.review_places(
+            &[restored_place.id.as_str()],
+            previous_status,
+            &ActivityLog {
+                activity: activity.clone(),
+                context: None,
+                comment: Some(format!(
+                    "{}, restored revision {}",
+                    USER_DEACTIVATED_REVIEW_COMMENT,
+                    u64::from(place_revision.revision)
+                )),
+            },
+        )?;
+        places.restored_places.push(restored_place);
+    }
+    if !places.archived_place_ids.is_empty() {
+        let ids: Vec<_> = places.archived_place_ids.iter().map(Id::as_str).collect();
+        let count = db.review_places(
+            &ids,
+            ReviewStatus::Archived,
+            &ActivityLog {
+                activity,
+                context: None,
+                comment: Some(USER_DEACTIVATED_REVIEW_COMMENT.into()),
+            },
+        )?;
+        info!(
+            "Archived {} pending place(s) of deactivated user {}",
+            count, user_email
+        );
+    }
+    if !places.restored_places.is_empty() {
+        info!(
+            "Restored {} place(s) edited by deactivated user {}",
+            places.restored_places.len(),
+            user_email
+        );
+    }
+    Ok(places)
+}
+
+/// Reactivate a previously deactivated user account.
+///
+/// Places that have been archived during the deactivation
+/// are not restored automatically, i.e. the deactivation is
+/// one-way and these places need to be reviewed again by
+/// scouts.
+pub fn reactivate_user<D: Db>(
+    db: &D,
+    account_email: &str,
+    user_email: &str,
+    comment: Option<String>,
+) -> Result<()> {
+    authorize_user_status_change(db, account_email, user_email)?;
+    if db.is_user_active(user_email)? {
+        info!("User {} is already active", user_email);
+        return Ok(());
+    }
+    info!("Reactivating user {}", user_email);
+    db.log_user_status(
+        user_email,
+        &UserStatusLog {
+            activity: ActivityLog {
+                activity: Activity::now(Some(account_email.into())),
+                context: None,
+                comment,
+            },
+            active: true,
+        },
+    )?;
+    Ok(())
+}
+
+pub fn load_user_status_logs<D: Db>(
+    db: &D,
+    account_email: &str,
+    user_email: &str,
+) -> Result<Vec<UserStatusLog>> {
+    authorize_user_by_email(db, account_email, Permission::ManageUsers)?;
+    Ok(db.load_user_status_logs(user_email)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_user, MockDb};
+    use super::super::*;
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn deactivate_and_reactivate_user() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_user(&db, "user@example.com", Role::User);
+
+        assert!(deactivate_user(&db, "admin@example.com", "user@example.com", None).is_ok());
+        assert!(!db.is_user_active("user@example.com").unwrap());
+        assert!(login_with_email(
+            &db,
+            &Credentials {
+                email: "user@example.com",
+                password: "secret",
+            }
+        )
+        .is_err());
+
+        assert!(reactivate_user(&db, "admin@example.com", "user@example.com", None).is_ok());
+        assert!(db.is_user_active("user@example.com").unwrap());
+        assert!(login_with_email(
+            &db,
+            &Credentials {
+                email: "user@example.com",
+                password: "secret",
+            }
+        )
+        .is_ok());
+        assert_eq!(
+            2,
+            load_user_status_logs(&db, "admin@example.com", "user@example.com")
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn only_admins_can_deactivate_users() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_user(&db, "scout@example.com", Role::Scout);
+        create_user(&db, "user@example.com", Role::User);
+
+        assert!(deactivate_user(&db, "scout@example.com", "user@example.com", None).is_err());
+        assert!(deactivate_user(&db, "admin@example.com", "admin@example.com", None).is_err());
+        assert!(db.is_user_active("user@example.com").unwrap());
+        assert!(db.is_user_active("admin@example.com").unwrap());
+    }
+
+    fn review_status(db: &MockDb, place_id: &str) -> ReviewStatus {
+        db.entries
+            .borrow()
+            .iter()
+            .find(|(place, _)| place.id.as_str() == place_id)
+            .map(|(_, status)| *status)
+            .unwrap()
+    }
+
+    #[test]
+    fn deactivation_archives_only_pending_places_for_good() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_user(&db, "user@example.com", Role::User);
+        for (id, status) in &[
+            ("pending", ReviewStatus::Created),
+            ("confirmed", ReviewStatus::Confirmed),
+        ] {
+            let mut place = Place::build().id(id).finish();
+            place.created.by = Some("user@example.com".into());
+            db.entries.borrow_mut().push((place, *status));
+        }
+        db.events.borrow_mut().push(Event {
+            id: "event".into(),
+            title: "event".into(),
+            description: None,
+            start: NaiveDateTime::from_timestamp(0, 0),
+            end: None,
+            location: None,
+            contact: None,
+            tags: vec![],
+            homepage: None,
+            created_by: Some("user@example.com".into()),
+            registration: None,
+            organizer: None,
+            archived: None,
+            image_url: None,
+            image_link_url: None,
+            recurrence: None,
+            capacity: None,
+        });
+        db.create_rating(Rating {
+            id: "rating".into(),
+            place_id: "confirmed".into(),
+            created_at: TimestampMs::now(),
+            created_by: Some("user@example.com".into()),
+            archived_at: None,
+            title: "title".into(),
+            value: RatingValue::new(1),
+            context: RatingContext::Fairness,
+            source: None,
+        })
+        .unwrap();
+
+        let places = deactivate_user(&db, "admin@example.com", "user@example.com", None).unwrap();
+        assert_eq!(vec![Id::from("pending")], places.archived_place_ids);
+        assert!(places.restored_places.is_empty());
+        assert_eq!(ReviewStatus::Archived, review_status(&db, "pending"));
+        assert_eq!(ReviewStatus::Confirmed, review_status(&db, "confirmed"));
+        // Events and ratings are not affected
+        assert!(db.events.borrow()[0].archived.is_none());
+        assert!(db.ratings.borrow()[0].archived_at.is_none());
+
+        // Archived places are not restored
+        reactivate_user(&db, "admin@example.com", "user@example.com", None).unwrap();
+        assert_eq!(ReviewStatus::Archived, review_status(&db, "pending"));
+    }
+}
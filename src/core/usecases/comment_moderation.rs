@@ -0,0 +1,21 @@
+use super::authorize_user_by_email;
+use crate::core::prelude::*;
+
+/// Flag a comment that should be reviewed by scouts.
+pub fn flag_comment<D: Db>(db: &D, user_email: &str, id: &CommentId) -> Result<()> {
+    info!("Flagging comment {}", id);
+    let flagged = Activity::now(Some(user_email.into()));
+    Ok(db.flag_comment(id, &flagged)?)
+}
+
+/// Load comments together with their rating, place, and
+/// the number of flags for moderation.
+pub fn load_comments_for_moderation<D: Db>(
+    db: &D,
+    account_email: &str,
+    params: &CommentModerationParams,
+    pagination: &Pagination,
+) -> Result<Vec<ModeratedComment>> {
+    authorize_user_by_email(db, account_email, Permission::ArchiveComments)?;
+    Ok(db.load_comments_for_moderation(params, pagination)?)
+}
@@ -24,6 +24,33 @@ pub struct NewPlace {
     pub license        : String,
     pub image_url      : Option<String>,
     pub image_link_url : Option<String>,
+    pub source         : Option<NewPlaceSource>,
+    #[serde(default)]
+    pub additional_locations: Vec<NewPlaceLocation>,
+}
+
+/// Origin of an imported place
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewPlaceSource {
+    /// Id of the organization that imports the place. Taken
+    /// from the API token of the request, never from the body.
+    #[serde(skip_deserializing)]
+    pub importer: String,
+    /// Id of the record in the external data source
+    pub external_ref: String,
+}
+
+/// A further site of a place that operates at multiple locations
+#[rustfmt::skip]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewPlaceLocation {
+    pub lat     : f64,
+    pub lng     : f64,
+    pub street  : Option<String>,
+    pub zip     : Option<String>,
+    pub city    : Option<String>,
+    pub country : Option<String>,
+    pub state   : Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -53,6 +80,8 @@ pub fn prepare_new_place<D: Db>(
         opening_hours,
         image_url,
         image_link_url,
+        source,
+        additional_locations,
         ..
     } = e;
     let pos = match MapPoint::try_from_lat_lng_deg(lat, lng) {
@@ -61,7 +90,8 @@ pub fn prepare_new_place<D: Db>(
     };
     let categories: Vec<_> = categories.into_iter().map(Id::from).collect();
     let tags = super::prepare_tag_list(
-        Category::merge_ids_into_tags(&categories, tags)
+        Categories::from(db.all_categories()?)
+            .merge_ids_into_tags(&categories, tags)
             .iter()
             .map(String::as_str),
     );
@@ -79,6 +109,7 @@ pub fn prepare_new_place<D: Db>(
         Some(address)
     };
     let location = Location { pos, address };
+    let additional_locations = super::prepare_additional_locations(additional_locations)?;
 
     let contact = if email.is_some() || telephone.is_some() {
         Some(Contact {
@@ -107,6 +138,9 @@ pub fn prepare_new_place<D: Db>(
     } else {
         None
     };
+    let source = source
+        .map(|s| super::prepare_place_source(db, s, None))
+        .transpose()?;
 
     let place = Place {
         id: Id::new(),
@@ -116,6 +150,7 @@ pub fn prepare_new_place<D: Db>(
         title,
         description,
         location,
+        additional_locations,
         contact,
         opening_hours: opening_hours
             .map(|s| {
@@ -125,11 +160,25 @@ pub fn prepare_new_place<D: Db>(
             .transpose()?,
         links,
         tags,
+        source,
     };
     place.validate()?;
     Ok(Storable(place))
 }
 
+/// Prepare a new place without storing it and find existing
+/// places that might be duplicates, e.g. for warning the author
+/// before actually creating a near-identical place.
+pub fn find_duplicates_of_new_place<D: Db>(
+    db: &D,
+    index: &dyn PlaceIndex,
+    new_place: NewPlace,
+    created_by_email: Option<&str>,
+) -> Result<Vec<(Place, super::DuplicateType)>> {
+    let Storable(place) = prepare_new_place(db, new_place, created_by_email)?;
+    super::find_place_duplicates(db, index, &place)
+}
+
 pub fn store_new_place<D: Db>(db: &D, s: Storable) -> Result<(Place, Vec<Rating>)> {
     let Storable(place) = s;
     debug!("Storing new place revision: {:?}", place);
@@ -137,6 +186,10 @@ pub fn store_new_place<D: Db>(db: &D, s: Storable) -> Result<(Place, Vec<Rating>
         db.create_tag_if_it_does_not_exist(&Tag { id: t.clone() })?;
     }
     db.create_or_update_place(place.clone())?;
+    if let Some(ref source) = place.source {
+        super::store_place_source(db, &place.id.as_str().into(), source)?;
+    }
+    super::add_pending_clearance_for_place(db, &place.tags, &place.id, None, None)?;
     // No initial ratings so far
     let ratings = vec![];
     Ok((place, ratings))
@@ -145,7 +198,7 @@ pub fn store_new_place<D: Db>(db: &D, s: Storable) -> Result<(Place, Vec<Rating>
 #[cfg(test)]
 mod tests {
 
-    use super::super::tests::MockDb;
+    use super::super::{find_place_by_source, tests::MockDb};
     use super::*;
 
     #[test]
@@ -170,6 +223,8 @@ mod tests {
             license     : "CC0-1.0".into(),
             image_url     : None,
             image_link_url: None,
+            source        : None,
+            additional_locations: vec![],
         };
         let mock_db = MockDb::default();
         let now = TimestampMs::now();
@@ -207,6 +262,8 @@ mod tests {
             license     : "CC0-1.0".into(),
             image_url     : None,
             image_link_url: None,
+            source        : None,
+            additional_locations: vec![],
         };
         let mock_db: MockDb = MockDb::default();
         assert!(prepare_new_place(&mock_db, x, None).is_err());
@@ -234,6 +291,8 @@ mod tests {
             license     : "CC0-1.0".into(),
             image_url     : None,
             image_link_url: None,
+            source        : None,
+            additional_locations: vec![],
         };
         let mock_db = MockDb::default();
         let e = prepare_new_place(&mock_db, x, None).unwrap();
@@ -241,4 +300,56 @@ mod tests {
         assert_eq!(mock_db.tags.borrow().len(), 2);
         assert_eq!(mock_db.entries.borrow().len(), 1);
     }
+
+    #[test]
+    fn create_places_from_the_same_source_only_once() {
+        #[rustfmt::skip]
+        let new_place = |external_ref: &str| NewPlace {
+            title       : "foo".into(),
+            description : "bar".into(),
+            lat         : 0.0,
+            lng         : 0.0,
+            street      : None,
+            zip         : None,
+            city        : None,
+            country     : None,
+            state       : None,
+            email       : None,
+            telephone   : None,
+            homepage    : None,
+            opening_hours: None,
+            categories  : vec![],
+            tags        : vec![],
+            license     : "CC0-1.0".into(),
+            image_url     : None,
+            image_link_url: None,
+            source        : Some(NewPlaceSource {
+                importer: " importer ".into(),
+                external_ref: external_ref.into(),
+            }),
+            additional_locations: vec![],
+        };
+        let mock_db = MockDb::default();
+        let e = prepare_new_place(&mock_db, new_place("123"), None).unwrap();
+        let (place, _) = store_new_place(&mock_db, e).unwrap();
+        let source = place.source.unwrap();
+        assert_eq!("importer", source.external_id.source);
+        assert_eq!("123", source.external_id.id);
+        assert_eq!(
+            place.id,
+            find_place_by_source(&mock_db, "importer", "123")
+                .unwrap()
+                .0
+                .id
+        );
+        assert!(matches!(
+            prepare_new_place(&mock_db, new_place("123"), None),
+            Err(Error::Parameter(ParameterError::DuplicatePlaceSource))
+        ));
+        assert!(matches!(
+            prepare_new_place(&mock_db, new_place(" "), None),
+            Err(Error::Parameter(ParameterError::InvalidPlaceSource))
+        ));
+        assert!(prepare_new_place(&mock_db, new_place("456"), None).is_ok());
+    }
 }
@@ -19,6 +19,7 @@ pub fn create_new_user<D: UserGateway>(db: &D, u: NewUser) -> Result<()> {
         email_confirmed: false,
         password,
         role: Role::Guest,
+        language: None,
     };
     debug!("Creating new user: email = {}", new_user.email);
     db.create_user(&new_user)?;
@@ -120,6 +121,7 @@ mod tests {
             email_confirmed: true,
             password: "secret".parse::<Password>().unwrap(),
             role: Role::Guest,
+            language: None,
         });
         let u = NewUser {
             email: "baz@foo.bar".into(),
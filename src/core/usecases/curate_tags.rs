@@ -0,0 +1,272 @@
+use super::{authorize_user_by_email, organizations::place_ids_with_any_tag, prepare_tag_list};
+use crate::core::prelude::*;
+use std::collections::HashMap;
+
+const MAX_CURATED_TAG_BOOST: f64 = 10.0;
+
+pub(super) fn prepare_single_tag(tag: &str) -> Result<String> {
+    let mut tags = prepare_tag_list(Some(tag));
+    if tags.len() != 1 {
+        return Err(ParameterError::InvalidTag.into());
+    }
+    Ok(tags.remove(0))
+}
+
+pub(super) fn place_ids_with_tag<D: Db>(db: &D, tag: &str) -> Result<Vec<Id>> {
+    Ok(db.find_place_ids_by_tags(&[tag])?)
+}
+
+fn check_boost(boost: f64) -> Result<()> {
+    if !boost.is_finite() || boost <= 0.0 || boost > MAX_CURATED_TAG_BOOST {
+        return Err(ParameterError::InvalidBoost.into());
+    }
+    Ok(())
+}
+
+/// The boost factors of all curated tags including the tags
+/// that are owned by curated organizations, ordered by tag.
+///
+/// Tags that are curated more than once receive the maximum
+/// of their boost factors.
+pub fn load_curated_tag_boosts<D: Db>(db: &D) -> Result<Vec<CuratedTag>> {
+    let mut boosts: HashMap<String, f64> = db
+        .all_curated_tags()?
+        .into_iter()
+        .map(|CuratedTag { tag, boost }| (tag, boost))
+        .collect();
+    let curated_orgs = db.all_curated_orgs()?;
+    if !curated_orgs.is_empty() {
+        for ownership in db.get_all_tag_ownerships()? {
+            let org_boost = curated_orgs
+                .iter()
+                .find(|org| org.org_id == ownership.org_id)
+                .map(|org| org.boost);
+            if let Some(org_boost) = org_boost {
+                let boost = boosts.entry(ownership.tag).or_insert(org_boost);
+                *boost = boost.max(org_boost);
+            }
+        }
+    }
+    let mut curated_tags: Vec<_> = boosts
+        .into_iter()
+        .map(|(tag, boost)| CuratedTag { tag, boost })
+        .collect();
+    curated_tags.sort_by(|a, b| a.tag.cmp(&b.tag));
+    Ok(curated_tags)
+}
+
+pub fn load_curated_tags<D: Db>(db: &D, account_email: &str) -> Result<Vec<CuratedTag>> {
+    authorize_user_by_email(db, account_email, Permission::CurateTags)?;
+    Ok(db.all_curated_tags()?)
+}
+
+/// Mark a tag as curated or change its boost factor.
+///
+/// Returns the ids of all places with this tag that need
+/// to be reindexed.
+pub fn save_curated_tag<D: Db>(
+    db: &D,
+    account_email: &str,
+    tag: &str,
+    boost: f64,
+) -> Result<Vec<Id>> {
+    authorize_user_by_email(db, account_email, Permission::CurateTags)?;
+    let tag = prepare_single_tag(tag)?;
+    check_boost(boost)?;
+    info!("Curating tag '{}' with boost {}", tag, boost);
+    db.save_curated_tag(&CuratedTag {
+        tag: tag.clone(),
+        boost,
+    })?;
+    place_ids_with_tag(db, &tag)
+}
+
+/// Returns the ids of all places with this tag that need
+/// to be reindexed.
+pub fn delete_curated_tag<D: Db>(db: &D, account_email: &str, tag: &str) -> Result<Vec<Id>> {
+    authorize_user_by_email(db, account_email, Permission::CurateTags)?;
+    let tag = prepare_single_tag(tag)?;
+    info!("Deleting curated tag '{}'", tag);
+    db.delete_curated_tag(&tag)?;
+    place_ids_with_tag(db, &tag)
+}
+
+pub fn load_curated_orgs<D: Db>(db: &D, account_email: &str) -> Result<Vec<CuratedOrganization>> {
+    authorize_user_by_email(db, account_email, Permission::CurateTags)?;
+    Ok(db.all_curated_orgs()?)
+}
+
+/// Mark an organization as curated or change its boost factor.
+///
+/// Returns the ids of all places with tags owned by this
+/// organization that need to be reindexed.
+pub fn save_curated_org<D: Db>(
+    db: &D,
+    account_email: &str,
+    org_id: &str,
+    boost: f64,
+) -> Result<Vec<Id>> {
+    authorize_user_by_email(db, account_email, Permission::CurateTags)?;
+    check_boost(boost)?;
+    let org = db.get_org_by_id(org_id)?;
+    info!("Curating organization '{}' with boost {}", org.name, boost);
+    db.save_curated_org(&CuratedOrganization {
+        org_id: org.id,
+        boost,
+    })?;
+    place_ids_with_any_tag(db, &org.owned_tags)
+}
+
+/// Returns the ids of all places with tags owned by this
+/// organization that need to be reindexed.
+pub fn delete_curated_org<D: Db>(db: &D, account_email: &str, org_id: &str) -> Result<Vec<Id>> {
+    authorize_user_by_email(db, account_email, Permission::CurateTags)?;
+    info!("Deleting curated organization {}", org_id);
+    db.delete_curated_org(org_id)?;
+    let org = db.get_org_by_id(org_id)?;
+    place_ids_with_any_tag(db, &org.owned_tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_user, MockDb};
+    use super::*;
+
+    #[test]
+    fn curate_tags_as_admin() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        db.entries.borrow_mut().push((
+            Place::build().id("a").tags(vec!["foo", "bar"]).finish(),
+            ReviewStatus::Created,
+        ));
+        db.entries.borrow_mut().push((
+            Place::build().id("b").tags(vec!["bar"]).finish(),
+            ReviewStatus::Created,
+        ));
+
+        let ids = save_curated_tag(&db, "admin@example.com", "#Foo", 2.0).unwrap();
+        assert_eq!(vec![Id::from("a")], ids);
+        assert_eq!(
+            vec![CuratedTag {
+                tag: "foo".into(),
+                boost: 2.0
+            }],
+            load_curated_tags(&db, "admin@example.com").unwrap()
+        );
+
+        let ids = save_curated_tag(&db, "admin@example.com", "foo", 3.0).unwrap();
+        assert_eq!(1, ids.len());
+        let boost = load_curated_tags(&db, "admin@example.com").unwrap()[0].boost;
+        assert!((boost - 3.0).abs() < f64::EPSILON);
+
+        let ids = delete_curated_tag(&db, "admin@example.com", "foo").unwrap();
+        assert_eq!(vec![Id::from("a")], ids);
+        assert!(load_curated_tags(&db, "admin@example.com")
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            delete_curated_tag(&db, "admin@example.com", "foo"),
+            Err(Error::Repo(RepoError::NotFound))
+        ));
+    }
+
+    #[test]
+    fn reject_invalid_curated_tags() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_user(&db, "scout@example.com", Role::Scout);
+
+        assert!(matches!(
+            save_curated_tag(&db, "scout@example.com", "foo", 2.0),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+        assert!(matches!(
+            save_curated_tag(&db, "admin@example.com", "foo bar", 2.0),
+            Err(Error::Parameter(ParameterError::InvalidTag))
+        ));
+        assert!(matches!(
+            save_curated_tag(&db, "admin@example.com", " # ", 2.0),
+            Err(Error::Parameter(ParameterError::InvalidTag))
+        ));
+        for boost in &[0.0, -1.0, 10.5, std::f64::NAN] {
+            assert!(matches!(
+                save_curated_tag(&db, "admin@example.com", "foo", *boost),
+                Err(Error::Parameter(ParameterError::InvalidBoost))
+            ));
+        }
+        assert!(db.all_curated_tags().unwrap().is_empty());
+    }
+
+    #[test]
+    fn curate_orgs_as_admin() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_user(&db, "scout@example.com", Role::Scout);
+        db.orgs.borrow_mut().push(Organization {
+            id: "org".into(),
+            name: "Org".into(),
+            owned_tags: vec!["foo".into(), "bar".into()],
+        });
+        db.entries.borrow_mut().push((
+            Place::build().id("a").tags(vec!["foo"]).finish(),
+            ReviewStatus::Created,
+        ));
+        db.entries.borrow_mut().push((
+            Place::build().id("b").tags(vec!["baz"]).finish(),
+            ReviewStatus::Created,
+        ));
+        save_curated_tag(&db, "admin@example.com", "foo", 4.0).unwrap();
+
+        assert!(matches!(
+            save_curated_org(&db, "scout@example.com", "org", 2.0),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+        assert!(matches!(
+            save_curated_org(&db, "admin@example.com", "org", 11.0),
+            Err(Error::Parameter(ParameterError::InvalidBoost))
+        ));
+        assert!(matches!(
+            save_curated_org(&db, "admin@example.com", "unknown", 2.0),
+            Err(Error::Repo(RepoError::NotFound))
+        ));
+
+        let ids = save_curated_org(&db, "admin@example.com", "org", 2.0).unwrap();
+        assert_eq!(vec![Id::from("a")], ids);
+        assert_eq!(
+            vec![CuratedOrganization {
+                org_id: "org".into(),
+                boost: 2.0
+            }],
+            load_curated_orgs(&db, "admin@example.com").unwrap()
+        );
+        // The maximum boost applies to tags that are curated twice
+        assert_eq!(
+            vec![
+                CuratedTag {
+                    tag: "bar".into(),
+                    boost: 2.0
+                },
+                CuratedTag {
+                    tag: "foo".into(),
+                    boost: 4.0
+                },
+            ],
+            load_curated_tag_boosts(&db).unwrap()
+        );
+
+        let ids = delete_curated_org(&db, "admin@example.com", "org").unwrap();
+        assert_eq!(vec![Id::from("a")], ids);
+        assert_eq!(
+            vec![CuratedTag {
+                tag: "foo".into(),
+                boost: 4.0
+            }],
+            load_curated_tag_boosts(&db).unwrap()
+        );
+        assert!(matches!(
+            delete_curated_org(&db, "admin@example.com", "org"),
+            Err(Error::Repo(RepoError::NotFound))
+        ));
+    }
+}
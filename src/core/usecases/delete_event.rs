@@ -1,10 +1,8 @@
+use super::authorize_organization_by_token;
 use crate::core::prelude::*;
 
-pub fn delete_event<D: Db>(db: &mut D, token: &str, id: &str) -> Result<()> {
-    let org = db.get_org_by_api_token(token).map_err(|e| match e {
-        RepoError::NotFound => Error::Parameter(ParameterError::Unauthorized),
-        _ => Error::Repo(e),
-    })?;
+pub fn delete_event<D: Db>(db: &mut D, token: &str, id: &EventId) -> Result<()> {
+    let org = authorize_organization_by_token(&*db, token, ApiTokenScope::WriteEvents)?;
     let owned_tags: Vec<_> = org.owned_tags.iter().map(|tag| tag.as_str()).collect();
     // FIXME: Only events with at least one tag that is owned by
     // the organization can be deleted. If the organization
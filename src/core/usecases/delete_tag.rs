@@ -0,0 +1,142 @@
+use super::{authorize_user_by_email, curate_tags::prepare_single_tag};
+use crate::core::prelude::*;
+
+/// Places and events that contain a deleted tag.
+#[derive(Debug, Default)]
+pub struct DeletedTag {
+    pub place_ids: Vec<Id>,
+    pub event_ids: Vec<Id>,
+}
+
+/// Delete a tag from all places and events and from the
+/// list of tags.
+///
+/// Places are updated by creating a new revision without the
+/// tag that keeps the review status of the current revision.
+/// In dry-run mode nothing is modified and only the places and
+/// events that would be affected are returned.
+pub fn delete_tag<D: Db>(
+    db: &D,
+    account_email: &str,
+    tag: &str,
+    dry_run: bool,
+) -> Result<DeletedTag> {
+    authorize_user_by_email(db, account_email, Permission::CurateTags)?;
+    let tag = prepare_single_tag(tag)?;
+    if !db.all_tags()?.iter().any(|t| t.id == tag) {
+        return Err(RepoError::NotFound.into());
+    }
+    let places: Vec<_> = db
+        .all_places()?
+        .into_iter()
+        .filter(|(place, _)| place.tags.contains(&tag))
+        .collect();
+    let events: Vec<_> = db
+        .all_events_chronologically()?
+        .into_iter()
+        .filter(|event| event.tags.contains(&tag))
+        .collect();
+    let deleted = DeletedTag {
+        place_ids: places.iter().map(|(place, _)| place.id.clone()).collect(),
+        event_ids: events.iter().map(|event| event.id.clone()).collect(),
+    };
+    if dry_run {
+        return Ok(deleted);
+    }
+    info!(
+        "Deleting tag '{}' from {} place(s) and {} event(s)",
+        tag,
+        deleted.place_ids.len(),
+        deleted.event_ids.len()
+    );
+    let activity = Activity::now(Some(account_email.into()));
+    for (mut place, status) in places {
+        place.revision = place.revision.next();
+        place.created = activity.clone();
+        place.tags.retain(|t| *t != tag);
+        let id = place.id.clone();
+        db.create_or_update_place(place)?;
+        let activity_log = ActivityLog {
+            activity: activity.clone(),
+            context: None,
+            comment: Some(format!("Deleted tag '{}'", tag)),
+        };
+        db.review_places(&[id.as_str()], status, &activity_log)?;
+    }
+    for mut event in events {
+        event.tags.retain(|t| *t != tag);
+        db.update_event(&event)?;
+    }
+    db.delete_tag(&tag)?;
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn delete_tag_from_places_and_events() {
+        let db = MockDb::default();
+        db.create_user(&User {
+            email: "admin@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Admin,
+            language: None,
+        })
+        .unwrap();
+        for tag in &["spam", "bio"] {
+            db.create_tag_if_it_does_not_exist(&Tag {
+                id: (*tag).to_string(),
+            })
+            .unwrap();
+        }
+        db.entries.borrow_mut().push((
+            Place::build().id("a").tags(vec!["spam", "bio"]).finish(),
+            ReviewStatus::Confirmed,
+        ));
+        db.entries.borrow_mut().push((
+            Place::build().id("b").tags(vec!["bio"]).finish(),
+            ReviewStatus::Created,
+        ));
+        db.events.borrow_mut().push(Event {
+            id: "e".into(),
+            title: "event".into(),
+            description: None,
+            start: NaiveDateTime::from_timestamp(0, 0),
+            end: None,
+            location: None,
+            contact: None,
+            tags: vec!["spam".into()],
+            homepage: None,
+            created_by: None,
+            registration: None,
+            organizer: None,
+            archived: None,
+            image_url: None,
+            image_link_url: None,
+            recurrence: None,
+            capacity: None,
+        });
+
+        let deleted = delete_tag(&db, "admin@example.com", "spam", true).unwrap();
+        assert_eq!(vec![Id::from("a")], deleted.place_ids);
+        assert_eq!(vec![Id::from("e")], deleted.event_ids);
+        assert_eq!(2, db.all_tags().unwrap().len());
+
+        delete_tag(&db, "admin@example.com", "#spam", false).unwrap();
+        let (place, status) = db.get_place(&"a".into()).unwrap();
+        assert_eq!(vec!["bio"], place.tags);
+        assert_eq!(Revision::from(1), place.revision);
+        assert_eq!(ReviewStatus::Confirmed, status);
+        assert!(db.events.borrow()[0].tags.is_empty());
+        assert_eq!(vec![Tag { id: "bio".into() }], db.all_tags().unwrap());
+        assert!(matches!(
+            delete_tag(&db, "admin@example.com", "spam", true),
+            Err(Error::Repo(RepoError::NotFound))
+        ));
+    }
+}
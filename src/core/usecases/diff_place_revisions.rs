@@ -0,0 +1,126 @@
+use super::authorize_user_by_email;
+use crate::core::prelude::*;
+
+fn field_change<T: PartialEq>(from: T, to: T) -> Option<FieldChange<T>> {
+    if from == to {
+        None
+    } else {
+        Some(FieldChange { from, to })
+    }
+}
+
+// Empty contact details are equivalent to missing contact details
+fn non_empty_contact(contact: Option<Contact>) -> Option<Contact> {
+    contact.filter(|c| !c.is_empty())
+}
+
+fn diff_revisions(place_id: Id, from: PlaceRevision, to: PlaceRevision) -> PlaceRevisionDiff {
+    let added_tags = to
+        .tags
+        .iter()
+        .filter(|t| !from.tags.contains(t))
+        .cloned()
+        .collect();
+    let removed_tags = from
+        .tags
+        .iter()
+        .filter(|t| !to.tags.contains(t))
+        .cloned()
+        .collect();
+    PlaceRevisionDiff {
+        place_id,
+        from: from.revision,
+        to: to.revision,
+        title: field_change(from.title, to.title),
+        description: field_change(from.description, to.description),
+        location: field_change(from.location, to.location),
+        contact: field_change(
+            non_empty_contact(from.contact),
+            non_empty_contact(to.contact),
+        ),
+        added_tags,
+        removed_tags,
+    }
+}
+
+/// Compare two revisions of a place field by field.
+///
+/// Only scouts and admins are allowed to inspect the
+/// history of a place.
+pub fn diff_place_revisions<D: Db>(
+    db: &D,
+    account_email: &str,
+    id: &PlaceId,
+    from: Revision,
+    to: Revision,
+) -> Result<PlaceRevisionDiff> {
+    authorize_user_by_email(db, account_email, Permission::ViewPlaceHistory)?;
+    let PlaceHistory { place, revisions } = db.get_place_history(id)?;
+    let mut from_revision = None;
+    let mut to_revision = None;
+    for (place_revision, _) in revisions {
+        if place_revision.revision == from {
+            from_revision = Some(place_revision.clone());
+        }
+        if place_revision.revision == to {
+            to_revision = Some(place_revision);
+        }
+    }
+    match (from_revision, to_revision) {
+        (Some(from), Some(to)) => Ok(diff_revisions(place.id, from, to)),
+        _ => Err(RepoError::NotFound.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place_revision(revision: u64) -> PlaceRevision {
+        let (_, place_revision) = Place::build()
+            .id("foo")
+            .revision(revision)
+            .title("Foo")
+            .description("A foo")
+            .pos(MapPoint::from_lat_lng_deg(1.0, 2.0))
+            .tags(vec!["bar", "baz"])
+            .finish()
+            .into();
+        place_revision
+    }
+
+    #[test]
+    fn diff_changed_fields() {
+        let from = place_revision(1);
+        let mut to = place_revision(2);
+        to.title = "Bar".into();
+        to.tags = vec!["baz".into(), "qux".into()];
+        to.contact = Some(Contact {
+            email: None,
+            phone: Some("0123".into()),
+        });
+        let diff = diff_revisions("foo".into(), from, to);
+        assert_eq!(Revision::from(1), diff.from);
+        assert_eq!(Revision::from(2), diff.to);
+        assert_eq!(
+            Some(FieldChange {
+                from: "Foo".to_string(),
+                to: "Bar".to_string()
+            }),
+            diff.title
+        );
+        assert!(diff.description.is_none());
+        assert!(diff.location.is_none());
+        assert_eq!(None, diff.contact.unwrap().from);
+        assert_eq!(vec!["qux"], diff.added_tags);
+        assert_eq!(vec!["bar"], diff.removed_tags);
+    }
+
+    #[test]
+    fn ignore_empty_contact_details() {
+        let from = place_revision(1);
+        let mut to = place_revision(2);
+        to.contact = Some(Contact::default());
+        assert!(diff_revisions("foo".into(), from, to).is_empty());
+    }
+}
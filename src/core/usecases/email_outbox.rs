@@ -0,0 +1,186 @@
+use super::authorize_user_by_email;
+use crate::core::prelude::*;
+
+/// Pending e-mails are abandoned after this many failed attempts.
+pub const MAX_EMAIL_DELIVERY_ATTEMPTS: u32 = 8;
+
+// The delay after the first failed attempt that is doubled
+// after each subsequent failure, i.e. the last retry happens
+// about 2 hours after the first attempt
+const EMAIL_RETRY_DELAY_SECONDS: i64 = 60;
+
+/// Put a separate e-mail for each recipient into the outbox.
+///
+/// Returns the number of new e-mails.
+pub fn enqueue_emails<D: Db>(
+    db: &D,
+    recipients: &[Email],
+    subject: &str,
+    body: &str,
+) -> Result<usize> {
+    let created_at = TimestampMs::now();
+    for recipient in recipients {
+        let email = OutgoingEmail {
+            id: Id::new(),
+            recipient: recipient.clone(),
+            subject: subject.to_owned(),
+            body: body.to_owned(),
+            created_at,
+            status: OutgoingEmailStatus::Pending,
+            attempts: 0,
+            last_attempt_at: None,
+            next_attempt_at: Some(created_at),
+            last_error: None,
+        };
+        db.create_outgoing_email(&email)?;
+    }
+    Ok(recipients.len())
+}
+
+/// Record the outcome of an attempt to send a pending e-mail.
+///
+/// Sent e-mails are removed from the outbox, because they
+/// might contain secrets like links for logging in. Failed
+/// attempts are retried with an exponential backoff until
+/// the maximum number of attempts has been reached.
+pub fn finish_email_delivery_attempt<D: Db>(
+    db: &D,
+    mut email: OutgoingEmail,
+    error: Option<String>,
+    attempted_at: TimestampMs,
+) -> Result<OutgoingEmail> {
+    debug_assert_eq!(OutgoingEmailStatus::Pending, email.status);
+    email.attempts += 1;
+    email.last_attempt_at = Some(attempted_at);
+    if error.is_none() {
+        email.status = OutgoingEmailStatus::Sent;
+        email.next_attempt_at = None;
+    } else if email.attempts >= MAX_EMAIL_DELIVERY_ATTEMPTS {
+        warn!(
+            "Giving up e-mail {} to {} after {} attempts",
+            email.id, email.recipient, email.attempts
+        );
+        email.status = OutgoingEmailStatus::Failed;
+        email.next_attempt_at = None;
+    } else {
+        let delay_seconds = EMAIL_RETRY_DELAY_SECONDS << (email.attempts - 1);
+        email.next_attempt_at = Some(TimestampMs::from_seconds(
+            attempted_at.into_seconds() + delay_seconds,
+        ));
+    }
+    email.last_error = error;
+    if email.status == OutgoingEmailStatus::Sent {
+        db.delete_outgoing_email(email.id.as_str())?;
+    } else {
+        db.update_outgoing_email(&email)?;
+    }
+    Ok(email)
+}
+
+/// The pending and failed e-mails in the outbox, newest first.
+pub fn load_outgoing_emails<D: Db>(
+    db: &D,
+    account_email: &str,
+    status: Option<OutgoingEmailStatus>,
+    pagination: &Pagination,
+) -> Result<Vec<OutgoingEmail>> {
+    authorize_user_by_email(db, account_email, Permission::ManageUsers)?;
+    if status == Some(OutgoingEmailStatus::Sent) {
+        return Err(ParameterError::InvalidEmailStatus.into());
+    }
+    Ok(db.load_outgoing_emails(status, pagination)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    #[test]
+    fn retry_failed_emails_with_backoff() {
+        let db = MockDb::default();
+        let recipients = vec![Email::from("a@example.com"), Email::from("b@example.com")];
+        assert_eq!(
+            2,
+            enqueue_emails(&db, &recipients, "Subject", "Body").unwrap()
+        );
+
+        let now = TimestampMs::now();
+        let mut due = db.load_due_outgoing_emails(now, 10).unwrap();
+        assert_eq!(2, due.len());
+        let sent = finish_email_delivery_attempt(&db, due.remove(0), None, now).unwrap();
+        assert_eq!(OutgoingEmailStatus::Sent, sent.status);
+        assert_eq!(None, sent.next_attempt_at);
+        // Sent e-mails are not kept
+        assert_eq!(1, db.outgoing_emails.borrow().len());
+
+        let mut email = due.remove(0);
+        let mut attempted_at = now;
+        for attempt in 1..MAX_EMAIL_DELIVERY_ATTEMPTS {
+            email = finish_email_delivery_attempt(&db, email, Some("timeout".into()), attempted_at)
+                .unwrap();
+            assert_eq!(OutgoingEmailStatus::Pending, email.status);
+            assert_eq!(attempt, email.attempts);
+            let next_attempt_at = email.next_attempt_at.unwrap();
+            assert_eq!(
+                EMAIL_RETRY_DELAY_SECONDS << (attempt - 1),
+                next_attempt_at.into_seconds() - attempted_at.into_seconds()
+            );
+            // Not due before the next attempt
+            assert!(db
+                .load_due_outgoing_emails(attempted_at, 10)
+                .unwrap()
+                .is_empty());
+            attempted_at = next_attempt_at;
+        }
+        let email = finish_email_delivery_attempt(&db, email, Some("timeout".into()), attempted_at)
+            .unwrap();
+        assert_eq!(OutgoingEmailStatus::Failed, email.status);
+        assert_eq!(Some("timeout"), email.last_error.as_deref());
+        assert!(db
+            .load_due_outgoing_emails(attempted_at, 10)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn only_admins_may_inspect_the_outbox() {
+        let db = MockDb::default();
+        for &(email, role) in &[
+            ("admin@example.com", Role::Admin),
+            ("scout@example.com", Role::Scout),
+        ] {
+            db.create_user(&User {
+                email: email.into(),
+                email_confirmed: true,
+                password: "secret".parse::<Password>().unwrap(),
+                role,
+                language: None,
+            })
+            .unwrap();
+        }
+        enqueue_emails(&db, &[Email::from("a@example.com")], "Subject", "Body").unwrap();
+        assert!(load_outgoing_emails(&db, "scout@example.com", None, &Default::default()).is_err());
+        assert_eq!(
+            1,
+            load_outgoing_emails(&db, "admin@example.com", None, &Default::default())
+                .unwrap()
+                .len()
+        );
+        assert!(load_outgoing_emails(
+            &db,
+            "admin@example.com",
+            Some(OutgoingEmailStatus::Failed),
+            &Default::default()
+        )
+        .unwrap()
+        .is_empty());
+        assert!(load_outgoing_emails(
+            &db,
+            "admin@example.com",
+            Some(OutgoingEmailStatus::Sent),
+            &Default::default()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use super::{
+    authorize_organization_by_token, import_new_event, store_created_event, store_updated_event,
+    NewEvent, NewEventMode,
+};
+use crate::core::prelude::*;
+
+/// An event that has either been created or updated
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventUpsert {
+    Created(Event),
+    Updated(Event),
+}
+
+impl EventUpsert {
+    pub fn event(&self) -> &Event {
+        match self {
+            EventUpsert::Created(event) | EventUpsert::Updated(event) => event,
+        }
+    }
+}
+
+/// Create or update the event that an organization has pushed
+/// from its own system with the given id.
+///
+/// Each external id is only unique per organization. Archived
+/// events are not restored. All changes must be performed
+/// within a single transaction.
+pub fn upsert_event_by_external_id<D: Db>(
+    db: &D,
+    token: &str,
+    external_id: &str,
+    new_event: NewEvent,
+) -> Result<EventUpsert> {
+    let org = authorize_organization_by_token(db, token, ApiTokenScope::WriteEvents)?;
+    let external_id = external_id.trim();
+    if external_id.is_empty() {
+        return Err(ParameterError::InvalidExternalId.into());
+    }
+    match db.find_event_id_by_external_id(org.id.as_str(), external_id)? {
+        Some(id) => {
+            if let Err(RepoError::NotFound) = db.get_event(&id.as_str().into()) {
+                return Err(RepoError::Archived.into());
+            }
+            let storable = import_new_event(db, Some(token), new_event, NewEventMode::Update(&id))?;
+            let event = store_updated_event(db, storable)?;
+            Ok(EventUpsert::Updated(event))
+        }
+        None => {
+            let storable = import_new_event(db, Some(token), new_event, NewEventMode::Create)?;
+            let event = store_created_event(db, storable)?;
+            info!(
+                "Created event {} with external id {} of organization {}",
+                event.id, external_id, org.id
+            );
+            db.set_event_external_id(&event.id.as_str().into(), org.id.as_str(), external_id)?;
+            Ok(EventUpsert::Created(event))
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use super::authorize_organization_by_token;
+use crate::core::prelude::*;
+
+fn count_confirmed(registrations: &[EventRegistration]) -> usize {
+    registrations
+        .iter()
+        .filter(|r| r.status == EventRegistrationStatus::Confirmed)
+        .count()
+}
+
+fn has_free_capacity(event: &Event, confirmed_count: usize) -> bool {
+    event
+        .capacity
+        .map(|capacity| confirmed_count < capacity as usize)
+        .unwrap_or(true)
+}
+
+/// Register an attendee for an event.
+///
+/// The registration is only confirmed if the capacity of the
+/// event has not been reached yet. Otherwise the attendee is
+/// put on the waitlist.
+pub fn register_for_event<D: Db>(
+    db: &D,
+    event_id: &EventId,
+    email: &str,
+) -> Result<(Event, EventRegistration)> {
+    let event = db.get_event(event_id)?;
+    if event.registration.is_none() {
+        return Err(ParameterError::RegistrationNotAvailable.into());
+    }
+    let registrations = db.load_event_registrations(event_id)?;
+    if registrations
+        .iter()
+        .any(|r| r.email_nonce.email == email && r.status != EventRegistrationStatus::Cancelled)
+    {
+        return Err(ParameterError::AlreadyRegistered.into());
+    }
+    let status = if has_free_capacity(&event, count_confirmed(&registrations)) {
+        EventRegistrationStatus::Confirmed
+    } else {
+        EventRegistrationStatus::Waitlisted
+    };
+    let registration = EventRegistration {
+        event_id: event.id.clone(),
+        email_nonce: EmailNonce {
+            email: email.to_owned(),
+            nonce: Nonce::new(),
+        },
+        status,
+        registered_at: TimestampMs::now(),
+    };
+    db.replace_event_registration(&registration)?;
+    Ok((event, registration))
+}
+
+/// The outcome of cancelling a registration.
+#[derive(Debug, Clone)]
+pub struct CancelledEventRegistration {
+    pub event: Event,
+    pub cancelled: EventRegistration,
+    /// The first attendee on the waitlist who took over the place
+    pub promoted: Option<EventRegistration>,
+}
+
+/// Cancel a registration with the token that has been sent
+/// to the attendee.
+///
+/// Cancelling a confirmed registration confirms the oldest
+/// registration on the waitlist.
+pub fn cancel_event_registration<D: Db>(db: &D, token: &str) -> Result<CancelledEventRegistration> {
+    let email_nonce =
+        EmailNonce::decode_from_str(token).map_err(|_| ParameterError::TokenInvalid)?;
+    let mut cancelled = match db.load_event_registration(&email_nonce) {
+        Ok(registration) => registration,
+        Err(RepoError::NotFound) => return Err(ParameterError::TokenInvalid.into()),
+        Err(err) => return Err(err.into()),
+    };
+    let event = db.get_event(&cancelled.event_id.as_str().into())?;
+    let was_confirmed = cancelled.status == EventRegistrationStatus::Confirmed;
+    if cancelled.status != EventRegistrationStatus::Cancelled {
+        cancelled.status = EventRegistrationStatus::Cancelled;
+        db.replace_event_registration(&cancelled)?;
+    }
+    let mut promoted = None;
+    if was_confirmed {
+        let registrations = db.load_event_registrations(&event.id.as_str().into())?;
+        if has_free_capacity(&event, count_confirmed(&registrations)) {
+            promoted = registrations
+                .into_iter()
+                .find(|r| r.status == EventRegistrationStatus::Waitlisted);
+            if let Some(ref mut registration) = promoted {
+                registration.status = EventRegistrationStatus::Confirmed;
+                db.replace_event_registration(registration)?;
+            }
+        }
+    }
+    Ok(CancelledEventRegistration {
+        event,
+        cancelled,
+        promoted,
+    })
+}
+
+/// All registrations for an event, only available for the
+/// organization that owns the event.
+pub fn load_event_registrations<D: Db>(
+    db: &D,
+    token: &str,
+    event_id: &EventId,
+) -> Result<Vec<EventRegistration>> {
+    let org = authorize_organization_by_token(db, token, ApiTokenScope::WriteEvents)?;
+    let event = db.get_event(event_id)?;
+    if !event.is_owned(org.owned_tags.iter().map(String::as_str)) {
+        return Err(ParameterError::Forbidden.into());
+    }
+    Ok(db.load_event_registrations(event_id)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_org_with_api_token, MockDb};
+    use super::*;
+    use chrono::prelude::*;
+
+    fn create_event(db: &MockDb, capacity: Option<u32>) {
+        db.create_event(Event {
+            id: "e".into(),
+            title: "Meetup".into(),
+            description: None,
+            start: Utc::now().naive_utc(),
+            end: None,
+            location: None,
+            contact: None,
+            tags: vec!["org-tag".into()],
+            homepage: None,
+            created_by: None,
+            registration: Some(RegistrationType::Email),
+            organizer: None,
+            archived: None,
+            image_url: None,
+            image_link_url: None,
+            recurrence: None,
+            capacity,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn register_with_waitlist_and_cancel() {
+        let db = MockDb::default();
+        create_event(&db, Some(1));
+
+        let (_, first) = register_for_event(&db, &"e".into(), "first@example.com").unwrap();
+        assert_eq!(EventRegistrationStatus::Confirmed, first.status);
+        let (_, second) = register_for_event(&db, &"e".into(), "second@example.com").unwrap();
+        assert_eq!(EventRegistrationStatus::Waitlisted, second.status);
+        assert!(matches!(
+            register_for_event(&db, &"e".into(), "second@example.com"),
+            Err(Error::Parameter(ParameterError::AlreadyRegistered))
+        ));
+
+        let cancelled =
+            cancel_event_registration(&db, &first.email_nonce.encode_to_string()).unwrap();
+        assert_eq!(
+            EventRegistrationStatus::Cancelled,
+            cancelled.cancelled.status
+        );
+        let promoted = cancelled.promoted.unwrap();
+        assert_eq!("second@example.com", promoted.email_nonce.email);
+        assert_eq!(EventRegistrationStatus::Confirmed, promoted.status);
+
+        // Cancelling twice doesn't promote anyone else
+        let (_, third) = register_for_event(&db, &"e".into(), "third@example.com").unwrap();
+        assert_eq!(EventRegistrationStatus::Waitlisted, third.status);
+        let cancelled =
+            cancel_event_registration(&db, &first.email_nonce.encode_to_string()).unwrap();
+        assert!(cancelled.promoted.is_none());
+
+        // Register again after cancelling
+        let (_, first) = register_for_event(&db, &"e".into(), "first@example.com").unwrap();
+        assert_eq!(EventRegistrationStatus::Waitlisted, first.status);
+    }
+
+    #[test]
+    fn events_without_registration_type_cannot_be_registered_for() {
+        let db = MockDb::default();
+        create_event(&db, None);
+        db.events.borrow_mut()[0].registration = None;
+        assert!(matches!(
+            register_for_event(&db, &"e".into(), "first@example.com"),
+            Err(Error::Parameter(ParameterError::RegistrationNotAvailable))
+        ));
+    }
+
+    #[test]
+    fn only_owners_can_load_registrations() {
+        let db = MockDb::default();
+        create_event(&db, None);
+        register_for_event(&db, &"e".into(), "first@example.com").unwrap();
+        create_org_with_api_token(
+            &db,
+            Organization {
+                id: "owner".into(),
+                name: "owner".into(),
+                owned_tags: vec!["org-tag".into()],
+            },
+            "owner-token",
+        );
+        create_org_with_api_token(
+            &db,
+            Organization {
+                id: "other".into(),
+                name: "other".into(),
+                owned_tags: vec!["other-tag".into()],
+            },
+            "other-token",
+        );
+        let registrations = load_event_registrations(&db, "owner-token", &"e".into()).unwrap();
+        assert_eq!(1, registrations.len());
+        assert!(matches!(
+            load_event_registrations(&db, "other-token", &"e".into()),
+            Err(Error::Parameter(ParameterError::Forbidden))
+        ));
+    }
+}
@@ -1,18 +1,19 @@
 use crate::core::prelude::*;
 
+use std::collections::BTreeSet;
+
 pub fn export_event<'a>(
     event: Event,
-    role: Role,
+    permissions: &BTreeSet<Permission>,
     owned_tags: impl IntoIterator<Item = &'a str>,
 ) -> Event {
-    if role < Role::Admin {
-        let event = super::filter_event(event, owned_tags);
-        if role < Role::Scout {
-            event.strip_contact_details()
-        } else {
-            event
-        }
-    } else {
+    if permissions.contains(&Permission::ExportActivityData) {
+        return event;
+    }
+    let event = super::filter_event(event, owned_tags);
+    if permissions.contains(&Permission::ExportData) {
         event
+    } else {
+        event.strip_contact_details()
     }
 }
@@ -1,18 +1,19 @@
 use crate::core::prelude::*;
 
+use std::collections::BTreeSet;
+
 pub fn export_place<'a>(
     place: Place,
-    role: Role,
+    permissions: &BTreeSet<Permission>,
     owned_tags: impl IntoIterator<Item = &'a str>,
 ) -> Place {
-    if role < Role::Admin {
-        let place = super::filter_place(place, owned_tags);
-        if role < Role::Scout {
-            place.strip_contact_details()
-        } else {
-            place
-        }
-    } else {
+    if permissions.contains(&Permission::ExportActivityData) {
+        return place;
+    }
+    let place = super::filter_place(place, owned_tags);
+    if permissions.contains(&Permission::ExportData) {
         place
+    } else {
+        place.strip_contact_details()
     }
 }
@@ -1,10 +1,11 @@
 use crate::core::prelude::*;
 use std::{cmp::min, collections::HashSet};
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub enum DuplicateType {
     SimilarChars,
     SimilarWords,
+    SimilarTrigrams,
 }
 
 // return vector of places like: (entry1ID, entry2ID, reason)
@@ -27,14 +28,78 @@ pub fn find_duplicates(
     duplicates
 }
 
-const DUPLICATE_MAX_DISTANCE: Distance = Distance::from_meters(100.0);
+/// Only places within this distance are considered as duplicates.
+pub const DUPLICATE_MAX_DISTANCE: Distance = Distance::from_meters(100.0);
+
+// The fraction of common trigrams of both titles, i.e. 1.0 if
+// the titles consist of exactly the same trigrams
+const DUPLICATE_MIN_TRIGRAM_SIMILARITY: f64 = 0.5;
+
+// Limits the number of nearby places that are compared
+const MAX_DUPLICATE_CANDIDATES: usize = 100;
+
+/// Find all places that are located near the given place and have
+/// a similar title.
+///
+/// Archived or rejected places are ignored.
+pub fn find_duplicates_of_place(
+    place: &Place,
+    candidates: Vec<(Place, ReviewStatus)>,
+    max_distance: Distance,
+) -> Vec<(Place, DuplicateType)> {
+    candidates
+        .into_iter()
+        .filter(|(candidate, status)| candidate.id != place.id && status.exists())
+        .filter_map(|(candidate, _)| {
+            is_duplicate_within(place, &candidate, max_distance).map(|t| (candidate, t))
+        })
+        .collect()
+}
+
+/// Find all visible places that might be duplicates of the given
+/// place by searching the index for nearby places.
+pub fn find_place_duplicates<D: Db>(
+    db: &D,
+    index: &dyn PlaceIndex,
+    place: &Place,
+) -> Result<Vec<(Place, DuplicateType)>> {
+    let query = IndexQuery {
+        // Only visible places
+        status: Some(vec![]),
+        center: Some(place.location.pos),
+        radius_meters: Some(DUPLICATE_MAX_DISTANCE.to_meters()),
+        ..Default::default()
+    };
+    let nearby_ids: Vec<_> = index
+        .query_places(&query, MAX_DUPLICATE_CANDIDATES)
+        .map_err(RepoError::Other)?
+        .into_iter()
+        .map(|p| p.id)
+        .collect();
+    let nearby_ids: Vec<_> = nearby_ids.iter().map(String::as_str).collect();
+    let candidates = db.get_places(&nearby_ids)?;
+    Ok(find_duplicates_of_place(
+        place,
+        candidates,
+        DUPLICATE_MAX_DISTANCE,
+    ))
+}
 
 // returns a DuplicateType if the two places have a similar title, returns None otherwise
 fn is_duplicate(e1: &Place, e2: &Place) -> Option<DuplicateType> {
-    if similar_title(e1, e2, 0.3, 0) && in_close_proximity(e1, e2, DUPLICATE_MAX_DISTANCE) {
+    is_duplicate_within(e1, e2, DUPLICATE_MAX_DISTANCE)
+}
+
+fn is_duplicate_within(e1: &Place, e2: &Place, max_dist: Distance) -> Option<DuplicateType> {
+    if !in_close_proximity(e1, e2, max_dist) {
+        return None;
+    }
+    if similar_title(e1, e2, 0.3, 0) {
         Some(DuplicateType::SimilarChars)
-    } else if similar_title(e1, e2, 0.0, 2) && in_close_proximity(e1, e2, DUPLICATE_MAX_DISTANCE) {
+    } else if similar_title(e1, e2, 0.0, 2) {
         Some(DuplicateType::SimilarWords)
+    } else if trigram_similarity(&e1.title, &e2.title) >= DUPLICATE_MIN_TRIGRAM_SIMILARITY {
+        Some(DuplicateType::SimilarTrigrams)
     } else {
         None
     }
@@ -60,6 +125,38 @@ fn similar_title(
         || words_equal_except_k_words(&e1.title, &e2.title, max_words_different)
 }
 
+// The trigrams of all lowercase words, each word padded with two
+// leading and one trailing space like in PostgreSQL's pg_trgm
+fn trigrams(s: &str) -> HashSet<[char; 3]> {
+    let mut trigrams = HashSet::new();
+    for word in s
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        let padded: Vec<_> = "  "
+            .chars()
+            .chain(word.chars().flat_map(char::to_lowercase))
+            .chain(" ".chars())
+            .collect();
+        for w in padded.windows(3) {
+            trigrams.insert([w[0], w[1], w[2]]);
+        }
+    }
+    trigrams
+}
+
+// The number of common trigrams relative to the number of all
+// trigrams in the range [0.0, 1.0]
+fn trigram_similarity(str1: &str, str2: &str) -> f64 {
+    let t1 = trigrams(str1);
+    let t2 = trigrams(str2);
+    let union_count = t1.union(&t2).count();
+    if union_count == 0 {
+        return 0.0;
+    }
+    t1.intersection(&t2).count() as f64 / union_count as f64
+}
+
 // returns true if all but k words are equal in str1 and str2
 // (and one of them has more than one word)
 // (words in str1 and str2 are treated as sets, order & multiplicity of words doesn't matter)
@@ -254,6 +351,50 @@ mod tests {
         assert_eq!(None, is_duplicate(&e4, &e5));
     }
 
+    #[test]
+    fn test_trigram_similarity() {
+        assert!((trigram_similarity("Bioladen", "bioladen") - 1.0).abs() < f64::EPSILON);
+        assert!(
+            trigram_similarity("Weltladen Regenbogen", "Regenbogen-Weltladen e.V. Freiburg") >= 0.5
+        );
+        assert!(trigram_similarity("Weltladen Regenbogen", "Fahrradwerkstatt") < 0.1);
+        assert!(trigram_similarity("", "-").abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_find_duplicates_of_place() {
+        let pos = MapPoint::from_lat_lng_deg(48.23153745093964, 8.003816366195679);
+        let place = new_place("Weltladen Regenbogen".into(), "".into(), pos);
+        let similar = new_place("Regenbogen-Weltladen e.V. Freiburg".into(), "".into(), pos);
+        // Titles with few words differ in more than two words
+        let different = new_place("Fahrradwerkstatt am Hauptbahnhof".into(), "".into(), pos);
+        let far_away = new_place(
+            "Weltladen Regenbogen 2".into(),
+            "".into(),
+            MapPoint::from_lat_lng_deg(48.24153745093964, 8.003816366195679),
+        );
+        let archived = new_place("Weltladen Regenbogen 3".into(), "".into(), pos);
+        let candidates = vec![
+            (place.clone(), ReviewStatus::Created),
+            (similar, ReviewStatus::Created),
+            (different, ReviewStatus::Confirmed),
+            (far_away, ReviewStatus::Created),
+            (archived, ReviewStatus::Archived),
+        ];
+        let duplicates: Vec<_> =
+            find_duplicates_of_place(&place, candidates, DUPLICATE_MAX_DISTANCE)
+                .into_iter()
+                .map(|(p, t)| (p.title, t))
+                .collect();
+        assert_eq!(
+            vec![(
+                "Regenbogen-Weltladen e.V. Freiburg".to_string(),
+                DuplicateType::SimilarTrigrams
+            )],
+            duplicates
+        );
+    }
+
     #[test]
     fn test_min() {
         assert_eq!(1, min3(1, 2, 3));
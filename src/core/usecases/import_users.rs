@@ -0,0 +1,95 @@
+use super::super::util::validate;
+use crate::core::prelude::*;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UserImportReport {
+    pub created: Vec<(String, Role)>,
+    // E-mail addresses of users that already existed
+    pub skipped: Vec<String>,
+}
+
+/// Create accounts with confirmed e-mail addresses, e.g. for
+/// moving an existing community from another platform.
+///
+/// The passwords are generated randomly and never revealed, i.e.
+/// users need to reset their password before they can log in for
+/// the first time. Existing users are skipped and not modified.
+pub fn import_users<D: Db>(db: &D, users: Vec<(String, Role)>) -> Result<UserImportReport> {
+    let mut report = UserImportReport::default();
+    for (email, role) in users {
+        let email = email.trim().to_owned();
+        if let Err(err) = validate::email(&email) {
+            warn!("Failed to import user with e-mail address '{}'", email);
+            return Err(err.into());
+        }
+        if db.try_get_user_by_email(&email)?.is_some() {
+            info!("Skipping import of existing user {}", email);
+            report.skipped.push(email);
+            continue;
+        }
+        let password = Nonce::new().to_string().parse::<Password>()?;
+        debug!("Importing user {} with role {:?}", email, role);
+        db.create_user(&User {
+            email: email.clone(),
+            email_confirmed: true,
+            password,
+            role,
+            language: None,
+        })?;
+        report.created.push((email, role));
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    #[test]
+    fn import_new_users_with_confirmed_emails() {
+        let db = MockDb::default();
+        db.create_user(&User {
+            email: "existing@example.com".into(),
+            email_confirmed: false,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Guest,
+            language: None,
+        })
+        .unwrap();
+
+        let report = import_users(
+            &db,
+            vec![
+                (" scout@example.com ".into(), Role::Scout),
+                ("existing@example.com".into(), Role::Admin),
+                ("scout@example.com".into(), Role::User),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            UserImportReport {
+                created: vec![("scout@example.com".into(), Role::Scout)],
+                skipped: vec!["existing@example.com".into(), "scout@example.com".into()],
+            },
+            report
+        );
+
+        let scout = db.get_user_by_email("scout@example.com").unwrap();
+        assert!(scout.email_confirmed);
+        assert_eq!(Role::Scout, scout.role);
+        let existing = db.get_user_by_email("existing@example.com").unwrap();
+        assert!(!existing.email_confirmed);
+        assert_eq!(Role::Guest, existing.role);
+    }
+
+    #[test]
+    fn reject_invalid_email_addresses() {
+        let db = MockDb::default();
+        assert!(matches!(
+            import_users(&db, vec![("no-email".into(), Role::User)]),
+            Err(Error::Parameter(ParameterError::Email))
+        ));
+        assert!(db.all_users().unwrap().is_empty());
+    }
+}
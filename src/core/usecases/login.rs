@@ -1,4 +1,13 @@
-use crate::core::prelude::*;
+use super::create_user_from_email;
+use crate::core::{prelude::*, util::validate};
+
+use chrono::{Duration, Utc};
+
+// Repeated requests for the same e-mail address within this
+// period are ignored to prevent flooding the recipient's inbox
+const LOGIN_LINK_THROTTLE_PERIOD_MS: i64 = 5 * 60 * 1000;
+
+const LOGIN_LINK_VALIDITY_HOURS: i64 = 1;
 
 //TODO: remove and use Credentials instead
 #[derive(Deserialize, Debug, Clone)]
@@ -18,7 +27,9 @@ pub fn login_with_email<D: Db>(db: &D, login: &Credentials) -> Result<Role> {
         .and_then(|user| {
             if let Some(u) = user {
                 if u.password.verify(&login.password) {
-                    if u.email_confirmed {
+                    if !db.is_user_active(&u.email)? {
+                        Err(Error::Parameter(ParameterError::UserDeactivated))
+                    } else if u.email_confirmed {
                         Ok(u.role)
                     } else {
                         Err(Error::Parameter(ParameterError::EmailNotConfirmed))
@@ -32,7 +43,126 @@ pub fn login_with_email<D: Db>(db: &D, login: &Credentials) -> Result<Role> {
         })
 }
 
+/// Create a one-time link for logging in without a password.
+///
+/// Returns `None` if a link for the same e-mail address has been
+/// created only recently or if the user has been deactivated.
+/// Unknown users are not registered before the link is followed.
+pub fn create_login_link<D: Db>(db: &D, email: &str) -> Result<Option<EmailNonce>> {
+    validate::email(email)?;
+    if !db.is_user_active(email)? {
+        return Ok(None);
+    }
+    let created_at = TimestampMs::now();
+    match db.get_login_link_by_email(email) {
+        Ok(previous) => {
+            if created_at.into_inner() - previous.created_at.into_inner()
+                < LOGIN_LINK_THROTTLE_PERIOD_MS
+            {
+                return Ok(None);
+            }
+        }
+        Err(RepoError::NotFound) => (),
+        Err(err) => return Err(err.into()),
+    }
+    let login_link = LoginLink {
+        email_nonce: EmailNonce {
+            email: email.to_owned(),
+            nonce: Nonce::new(),
+        },
+        created_at,
+        expires_at: Timestamp::from(Utc::now() + Duration::hours(LOGIN_LINK_VALIDITY_HOURS)),
+    };
+    db.replace_login_link(&login_link)?;
+    Ok(Some(login_link.email_nonce))
+}
+
+/// Login with a one-time link that has been sent by e-mail
+/// instead of a password.
+///
+/// The link proves the ownership of the e-mail address that
+/// is confirmed implicitly. Unknown users are registered on
+/// the fly with a generated password that they never need to
+/// know.
+pub fn login_with_login_link<D: Db>(db: &D, email_nonce: &EmailNonce) -> Result<Role> {
+    let login_link = match db.consume_login_link(email_nonce) {
+        Ok(login_link) => login_link,
+        Err(RepoError::NotFound) => return Err(Error::Parameter(ParameterError::TokenInvalid)),
+        Err(err) => return Err(err.into()),
+    };
+    if login_link.expires_at < Timestamp::now() {
+        return Err(Error::Parameter(ParameterError::TokenExpired));
+    }
+    let mut user = create_user_from_email(db, &login_link.email_nonce.email)?;
+    if !db.is_user_active(&user.email)? {
+        return Err(Error::Parameter(ParameterError::UserDeactivated));
+    }
+    if !user.email_confirmed {
+        user.email_confirmed = true;
+        if user.role == Role::Guest {
+            user.role = Role::User;
+        }
+        db.update_user(&user)?;
+    }
+    Ok(user.role)
+}
+
 #[cfg(test)]
 mod tests {
-    //TODO: write tests
+    use super::super::{refresh_user_token, tests::MockDb};
+    use super::*;
+
+    #[test]
+    fn login_with_a_one_time_link() {
+        let db = MockDb::default();
+        db.users.borrow_mut().push(User {
+            email: "user@example.com".into(),
+            email_confirmed: false,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Guest,
+            language: None,
+        });
+        let email_nonce = create_login_link(&db, "user@example.com").unwrap().unwrap();
+        assert_eq!(
+            Role::User,
+            login_with_login_link(&db, &email_nonce).unwrap()
+        );
+        assert!(db.users.borrow()[0].email_confirmed);
+        // The link is consumed
+        assert!(matches!(
+            login_with_login_link(&db, &email_nonce),
+            Err(Error::Parameter(ParameterError::TokenInvalid))
+        ));
+    }
+
+    #[test]
+    fn throttle_login_link_requests() {
+        let db = MockDb::default();
+        assert!(create_login_link(&db, "user@example.com")
+            .unwrap()
+            .is_some());
+        assert!(create_login_link(&db, "user@example.com")
+            .unwrap()
+            .is_none());
+        // Unknown users are not registered before following the link
+        assert!(db.users.borrow().is_empty());
+        // Links for other e-mail addresses are not affected
+        assert!(create_login_link(&db, "other@example.com")
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn login_links_are_independent_of_user_tokens() {
+        let db = MockDb::default();
+        let email_nonce = create_login_link(&db, "user@example.com").unwrap().unwrap();
+        let user = create_user_from_email(&db, "user@example.com").unwrap();
+        let reset_token = refresh_user_token(&db, user.email).unwrap();
+        assert!(matches!(
+            login_with_login_link(&db, &reset_token),
+            Err(Error::Parameter(ParameterError::TokenInvalid))
+        ));
+        assert!(login_with_login_link(&db, &email_nonce).is_ok());
+        assert_eq!(1, db.token.borrow().len());
+    }
 }
@@ -0,0 +1,168 @@
+use super::authorize_user_by_email;
+use crate::core::prelude::*;
+
+/// The places after a duplicate has been merged into
+/// the primary place.
+#[derive(Debug, Clone)]
+pub struct MergedPlaces {
+    /// A new revision if tags have been added
+    pub primary: Place,
+    /// The archived duplicate
+    pub duplicate: Place,
+    pub moved_ratings: usize,
+}
+
+/// Merge a duplicate into the primary place.
+///
+/// All ratings together with their comments are moved and the
+/// tags of the duplicate are added to the primary place. The
+/// duplicate is archived afterwards and remembers the primary
+/// place it has been merged into. All changes must be performed
+/// within a single transaction.
+pub fn merge_places<D: Db>(
+    db: &D,
+    account_email: &str,
+    primary_id: &PlaceId,
+    duplicate_id: &PlaceId,
+) -> Result<MergedPlaces> {
+    authorize_user_by_email(db, account_email, Permission::MergePlaces)?;
+    if primary_id == duplicate_id {
+        return Err(ParameterError::MergeIntoItself.into());
+    }
+    let (mut primary, primary_status) = db.get_place(primary_id)?;
+    let (duplicate, duplicate_status) = db.get_place(duplicate_id)?;
+    if !primary_status.exists() || !duplicate_status.exists() {
+        return Err(RepoError::Archived.into());
+    }
+    let activity = Activity::now(Some(account_email.into()));
+    let merged_at = activity.at;
+
+    let moved_ratings = db.move_ratings_of_place(duplicate_id, primary_id)?;
+
+    let missing_tags: Vec<_> = duplicate
+        .tags
+        .iter()
+        .filter(|tag| !primary.tags.contains(tag))
+        .cloned()
+        .collect();
+    if !missing_tags.is_empty() {
+        for tag in &missing_tags {
+            db.create_tag_if_it_does_not_exist(&Tag { id: tag.clone() })?;
+        }
+        primary.tags.extend(missing_tags);
+        primary.tags.sort_unstable();
+        primary.revision = primary.revision.next();
+        primary.created = activity.clone();
+        db.create_or_update_place(primary.clone())?;
+    }
+
+    let activity_log = ActivityLog {
+        activity,
+        context: None,
+        comment: Some(format!("Merged into {}", primary.id)),
+    };
+    db.review_places(
+        &[duplicate_id.as_str()],
+        ReviewStatus::Archived,
+        &activity_log,
+    )?;
+    db.create_place_merge(duplicate_id, primary_id, merged_at)?;
+
+    info!(
+        "Merged place {} with {} rating(s) into place {}",
+        duplicate.id, moved_ratings, primary.id
+    );
+    Ok(MergedPlaces {
+        primary,
+        duplicate,
+        moved_ratings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_user, MockDb};
+    use super::*;
+
+    fn new_rating(id: &str, place_id: &str) -> Rating {
+        Rating {
+            id: id.into(),
+            place_id: place_id.into(),
+            created_at: TimestampMs::now(),
+            created_by: None,
+            archived_at: None,
+            title: "title".into(),
+            value: RatingValue::new(1),
+            context: RatingContext::Fairness,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn merge_duplicate_into_primary_place() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_user(&db, "scout@example.com", Role::Scout);
+        db.entries.borrow_mut().push((
+            Place::build().id("primary").tags(vec!["a", "b"]).finish(),
+            ReviewStatus::Confirmed,
+        ));
+        db.entries.borrow_mut().push((
+            Place::build().id("duplicate").tags(vec!["b", "c"]).finish(),
+            ReviewStatus::Created,
+        ));
+        db.create_rating(new_rating("r1", "duplicate")).unwrap();
+        db.create_rating(new_rating("r2", "primary")).unwrap();
+
+        assert!(merge_places(
+            &db,
+            "scout@example.com",
+            &"primary".into(),
+            &"duplicate".into()
+        )
+        .is_err());
+        assert!(matches!(
+            merge_places(
+                &db,
+                "admin@example.com",
+                &"primary".into(),
+                &"primary".into()
+            ),
+            Err(Error::Parameter(ParameterError::MergeIntoItself))
+        ));
+
+        let merged = merge_places(
+            &db,
+            "admin@example.com",
+            &"primary".into(),
+            &"duplicate".into(),
+        )
+        .unwrap();
+        assert_eq!(1, merged.moved_ratings);
+        assert_eq!(vec!["a", "b", "c"], merged.primary.tags);
+        assert_eq!(Revision::initial().next(), merged.primary.revision);
+        assert_eq!(
+            2,
+            db.load_ratings_of_place(&"primary".into()).unwrap().len()
+        );
+        assert!(db
+            .entries
+            .borrow()
+            .iter()
+            .any(|(p, s)| p.id.as_str() == "duplicate" && *s == ReviewStatus::Archived));
+        assert_eq!(
+            Some("primary".into()),
+            db.get_place_merged_into(&"duplicate".into()).unwrap()
+        );
+        assert_eq!(None, db.get_place_merged_into(&"primary".into()).unwrap());
+
+        // Archived places cannot be merged again
+        assert!(merge_places(
+            &db,
+            "admin@example.com",
+            &"primary".into(),
+            &"duplicate".into()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,180 @@
+use super::{
+    authorize_user_by_email,
+    curate_tags::{place_ids_with_tag, prepare_single_tag},
+};
+use crate::core::prelude::*;
+
+fn tag_exists(tags: &[Tag], tag: &str) -> bool {
+    tags.iter().any(|t| t.id == tag)
+}
+
+/// Places and events that need to be reindexed after
+/// their tags have been replaced.
+#[derive(Debug, Default)]
+pub struct ReplacedTags {
+    pub place_ids: Vec<Id>,
+    pub event_ids: Vec<Id>,
+}
+
+fn replace_tags<D: Db>(db: &D, old_tags: &[String], new_tag: &str) -> Result<ReplacedTags> {
+    let mut place_ids = vec![];
+    for old_tag in old_tags {
+        place_ids.extend(place_ids_with_tag(db, old_tag)?);
+    }
+    place_ids.sort_unstable();
+    place_ids.dedup();
+    let event_ids = db
+        .all_events_chronologically()?
+        .into_iter()
+        .filter(|event| event.tags.iter().any(|tag| old_tags.contains(tag)))
+        .map(|event| event.id)
+        .collect();
+    for old_tag in old_tags {
+        db.replace_tag(old_tag, new_tag)?;
+    }
+    Ok(ReplacedTags {
+        place_ids,
+        event_ids,
+    })
+}
+
+/// Rename a tag, e.g. to fix a typo like `vegen`.
+///
+/// Existing tags cannot be renamed into each other, they
+/// need to be merged instead.
+pub fn rename_tag<D: Db>(
+    db: &D,
+    account_email: &str,
+    old_tag: &str,
+    new_tag: &str,
+) -> Result<ReplacedTags> {
+    authorize_user_by_email(db, account_email, Permission::CurateTags)?;
+    let old_tag = prepare_single_tag(old_tag)?;
+    let new_tag = prepare_single_tag(new_tag)?;
+    let tags = db.all_tags()?;
+    if !tag_exists(&tags, &old_tag) {
+        return Err(RepoError::NotFound.into());
+    }
+    if tag_exists(&tags, &new_tag) {
+        return Err(ParameterError::TagExists.into());
+    }
+    info!("Renaming tag '{}' into '{}'", old_tag, new_tag);
+    replace_tags(db, &[old_tag], &new_tag)
+}
+
+/// Merge tags into an existing tag, e.g. `vegen` and
+/// `veggie` into `vegan`.
+pub fn merge_tags<D: Db>(
+    db: &D,
+    account_email: &str,
+    old_tags: &[&str],
+    new_tag: &str,
+) -> Result<ReplacedTags> {
+    authorize_user_by_email(db, account_email, Permission::CurateTags)?;
+    let new_tag = prepare_single_tag(new_tag)?;
+    let mut old_tags = old_tags
+        .iter()
+        .map(|tag| prepare_single_tag(tag))
+        .collect::<Result<Vec<_>>>()?;
+    old_tags.sort_unstable();
+    old_tags.dedup();
+    old_tags.retain(|tag| *tag != new_tag);
+    if old_tags.is_empty() {
+        return Err(ParameterError::InvalidTag.into());
+    }
+    let tags = db.all_tags()?;
+    if !old_tags
+        .iter()
+        .chain(Some(&new_tag))
+        .all(|tag| tag_exists(&tags, tag))
+    {
+        return Err(RepoError::NotFound.into());
+    }
+    info!("Merging tags {:?} into '{}'", old_tags, new_tag);
+    replace_tags(db, &old_tags, &new_tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_user, MockDb};
+    use super::*;
+
+    fn create_place(db: &MockDb, id: &str, tags: Vec<&str>) {
+        for tag in &tags {
+            db.create_tag_if_it_does_not_exist(&Tag {
+                id: (*tag).to_string(),
+            })
+            .unwrap();
+        }
+        db.entries.borrow_mut().push((
+            Place::build().id(id).tags(tags).finish(),
+            ReviewStatus::Created,
+        ));
+    }
+
+    fn tags_of_place(db: &MockDb, id: &str) -> Vec<String> {
+        let (place, _) = db.get_place(&id.into()).unwrap();
+        let mut tags = place.tags;
+        tags.sort_unstable();
+        tags
+    }
+
+    #[test]
+    fn rename_tag_with_typo() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_place(&db, "a", vec!["vegen", "bio"]);
+        create_place(&db, "b", vec!["bio"]);
+        db.save_curated_tag(&CuratedTag {
+            tag: "vegen".into(),
+            boost: 2.0,
+        })
+        .unwrap();
+
+        let replaced = rename_tag(&db, "admin@example.com", "vegen", "#Vegan").unwrap();
+        assert_eq!(vec![Id::from("a")], replaced.place_ids);
+        assert_eq!(vec!["bio", "vegan"], tags_of_place(&db, "a"));
+        assert_eq!(vec!["bio"], tags_of_place(&db, "b"));
+        assert_eq!("vegan", db.all_curated_tags().unwrap()[0].tag);
+        assert!(db.all_tags().unwrap().iter().all(|t| t.id != "vegen"));
+
+        assert!(matches!(
+            rename_tag(&db, "admin@example.com", "vegen", "vegan"),
+            Err(Error::Repo(RepoError::NotFound))
+        ));
+        assert!(matches!(
+            rename_tag(&db, "admin@example.com", "bio", "vegan"),
+            Err(Error::Parameter(ParameterError::TagExists))
+        ));
+    }
+
+    #[test]
+    fn merge_tags_into_existing_tag() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_user(&db, "scout@example.com", Role::Scout);
+        create_place(&db, "a", vec!["vegen", "vegan"]);
+        create_place(&db, "b", vec!["veggie"]);
+        create_place(&db, "c", vec!["bio"]);
+
+        assert!(matches!(
+            merge_tags(&db, "scout@example.com", &["vegen"], "vegan"),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+        assert!(matches!(
+            merge_tags(&db, "admin@example.com", &["vegan"], "vegan"),
+            Err(Error::Parameter(ParameterError::InvalidTag))
+        ));
+        assert!(matches!(
+            merge_tags(&db, "admin@example.com", &["vegen", "missing"], "vegan"),
+            Err(Error::Repo(RepoError::NotFound))
+        ));
+        assert_eq!(vec!["vegan", "vegen"], tags_of_place(&db, "a"));
+
+        let replaced = merge_tags(&db, "admin@example.com", &["vegen", "veggie"], "vegan").unwrap();
+        assert_eq!(vec![Id::from("a"), Id::from("b")], replaced.place_ids);
+        assert_eq!(vec!["vegan"], tags_of_place(&db, "a"));
+        assert_eq!(vec!["vegan"], tags_of_place(&db, "b"));
+        assert_eq!(vec!["bio"], tags_of_place(&db, "c"));
+    }
+}
@@ -6,42 +6,85 @@ use crate::core::{
     },
 };
 
+mod announcements;
+mod anonymize_user;
+mod archive_batch;
 mod archive_comments;
 mod archive_events;
 mod archive_ratings;
+mod audit_log;
 mod authorize_organization;
+mod bbox_subscriptions;
+mod change_rating;
 mod change_user_role;
+mod change_user_status;
+mod comment_moderation;
 mod confirm_email;
 mod confirm_email_and_reset_password;
 mod create_new_place;
 mod create_new_user;
+mod curate_tags;
 mod delete_event;
+mod delete_tag;
+mod diff_place_revisions;
+mod email_outbox;
+mod event_external_ids;
+mod event_registrations;
 mod export_event;
 mod export_place;
 mod filter_event;
 mod filter_place;
 mod find_duplicates;
+mod import_users;
 mod indexing;
 mod login;
+mod merge_places;
+mod merge_tags;
+mod moderation_assignments;
+mod notification_digests;
+mod organizations;
+mod permissions;
+mod place_clearance;
+mod place_drafts;
+mod place_external_ids;
+mod places_feed;
 mod query_events;
 mod rate_place;
 mod register;
+mod report_content;
+mod restore_place_revision;
 mod review_places;
+mod saved_searches;
+mod scout_reports;
 mod search;
 mod store_event;
+mod suggest_tags;
+mod tag_owners;
+mod tag_subscriptions;
 mod update_place;
+mod user_api_tokens;
+mod user_language;
 mod user_tokens;
+mod verify_place_contact;
+mod webhooks;
 
 #[cfg(test)]
 pub mod tests;
 
 pub use self::{
-    archive_comments::*, archive_events::*, archive_ratings::*, authorize_organization::*,
-    change_user_role::*, confirm_email::*, confirm_email_and_reset_password::*,
-    create_new_place::*, create_new_user::*, delete_event::*, export_event::*, export_place::*,
-    filter_event::*, filter_place::*, find_duplicates::*, indexing::*, login::*, query_events::*,
-    rate_place::*, register::*, review_places::*, search::*, store_event::*, update_place::*,
-    user_tokens::*,
+    announcements::*, anonymize_user::*, archive_batch::*, archive_comments::*, archive_events::*,
+    archive_ratings::*, audit_log::*, authorize_organization::*, bbox_subscriptions::*,
+    change_rating::*, change_user_role::*, change_user_status::*, comment_moderation::*,
+    confirm_email::*, confirm_email_and_reset_password::*, create_new_place::*, create_new_user::*,
+    curate_tags::*, delete_event::*, delete_tag::*, diff_place_revisions::*, email_outbox::*,
+    event_external_ids::*, event_registrations::*, export_event::*, export_place::*,
+    filter_event::*, filter_place::*, find_duplicates::*, import_users::*, indexing::*, login::*,
+    merge_places::*, merge_tags::*, moderation_assignments::*, notification_digests::*,
+    organizations::*, permissions::*, place_clearance::*, place_drafts::*, place_external_ids::*,
+    places_feed::*, query_events::*, rate_place::*, register::*, report_content::*,
+    restore_place_revision::*, review_places::*, saved_searches::*, scout_reports::*, search::*,
+    store_event::*, suggest_tags::*, tag_owners::*, tag_subscriptions::*, update_place::*,
+    user_api_tokens::*, user_language::*, user_tokens::*, verify_place_contact::*, webhooks::*,
 };
 
 //TODO: move usecases into separate files
@@ -55,6 +98,12 @@ pub fn load_ratings_with_comments<D: Db>(
     Ok(results)
 }
 
+pub fn load_rating_timeline<D: Db>(db: &D, place_id: &PlaceId) -> Result<Vec<MonthlyAvgRatings>> {
+    // Unknown places should not look like places without ratings
+    db.get_place(place_id)?;
+    Ok(db.load_monthly_avg_ratings_of_place(place_id)?)
+}
+
 pub fn get_user<D: Db>(db: &D, logged_in_email: &str, requested_email: &str) -> Result<User> {
     if logged_in_email != requested_email {
         return Err(Error::Parameter(ParameterError::Forbidden));
@@ -62,7 +111,7 @@ pub fn get_user<D: Db>(db: &D, logged_in_email: &str, requested_email: &str) ->
     Ok(db.get_user_by_email(requested_email)?)
 }
 
-pub fn get_event<D: Db>(db: &D, id: &str) -> Result<Event> {
+pub fn get_event<D: Db>(db: &D, id: &EventId) -> Result<Event> {
     Ok(db.get_event(id)?)
 }
 
@@ -106,14 +155,38 @@ pub fn delete_user(db: &dyn Db, login_email: &str, email: &str) -> Result<()> {
     Ok(db.delete_user_by_email(email)?)
 }
 
-pub fn subscribe_to_bbox(db: &dyn Db, user_email: String, bbox: MapBbox) -> Result<()> {
-    validate::bbox(&bbox)?;
+/// Limits the number of subscriptions per user to keep
+/// the fan-out of notifications bounded.
+#[derive(Debug, Clone, Copy)]
+pub struct SubscriptionQuota {
+    /// 0 = Subscriptions are disabled
+    pub max_bbox_subscriptions: usize,
+    /// 0 = Subscriptions are disabled
+    pub max_tag_subscriptions: usize,
+    /// 0 = Saving searches is disabled
+    pub max_saved_searches: usize,
+}
 
-    // TODO: support multiple subscriptions in KVM (frontend)
-    // In the meanwhile we just replace existing subscriptions
-    // with a new one.
-    unsubscribe_all_bboxes(db, &user_email)?;
+impl Default for SubscriptionQuota {
+    fn default() -> Self {
+        Self {
+            max_bbox_subscriptions: 1,
+            max_tag_subscriptions: 10,
+            max_saved_searches: 10,
+        }
+    }
+}
 
+pub fn subscribe_to_bbox(
+    db: &dyn Db,
+    user_email: String,
+    bbox: MapBbox,
+    quota: SubscriptionQuota,
+) -> Result<()> {
+    validate::bbox(&bbox)?;
+    if db.all_bbox_subscriptions_by_email(&user_email)?.len() >= quota.max_bbox_subscriptions {
+        return Err(ParameterError::SubscriptionQuotaExceeded.into());
+    }
     let id = Id::new();
     db.create_bbox_subscription(&BboxSubscription {
         id,
@@ -173,6 +246,90 @@ pub fn prepare_tag_list<'a>(tags: impl IntoIterator<Item = &'a str>) -> Vec<Stri
     tags
 }
 
+// Validates the origin of an imported place. Each record of an
+// external data source must only be associated with a single place.
+pub fn prepare_place_source<D: Db>(
+    db: &D,
+    source: NewPlaceSource,
+    place_id: Option<&Id>,
+) -> Result<PlaceSource> {
+    let NewPlaceSource {
+        importer,
+        external_ref,
+    } = source;
+    let external_id = normalize_external_id(ExternalId {
+        source: importer,
+        id: external_ref,
+        url: None,
+    })
+    .map_err(|_| ParameterError::InvalidPlaceSource)?;
+    if let Some(other_place_id) =
+        db.find_place_id_by_external_id(&external_id.source, &external_id.id)?
+    {
+        if Some(other_place_id.as_str()) != place_id.map(Id::as_str) {
+            return Err(ParameterError::DuplicatePlaceSource.into());
+        }
+    }
+    Ok(PlaceSource {
+        external_id,
+        imported_at: TimestampMs::now(),
+    })
+}
+
+// Subsequent imports find the place by the external id
+// of the imported record
+pub fn store_place_source<D: Db>(db: &D, place_id: &PlaceId, source: &PlaceSource) -> Result<()> {
+    let mut external_ids = db.load_place_external_ids(place_id)?;
+    external_ids.retain(|x| x.source != source.external_id.source);
+    external_ids.push(source.external_id.clone());
+    db.replace_place_external_ids(place_id, &external_ids)?;
+    Ok(())
+}
+
+// Validates the positions of further locations of a place
+pub fn prepare_additional_locations(locations: Vec<NewPlaceLocation>) -> Result<Vec<Location>> {
+    locations
+        .into_iter()
+        .map(|l| {
+            let NewPlaceLocation {
+                lat,
+                lng,
+                street,
+                zip,
+                city,
+                country,
+                state,
+            } = l;
+            let pos =
+                MapPoint::try_from_lat_lng_deg(lat, lng).ok_or(ParameterError::InvalidPosition)?;
+            let address = Address {
+                street,
+                zip,
+                city,
+                country,
+                state,
+            };
+            let address = if address.is_empty() {
+                None
+            } else {
+                Some(address)
+            };
+            Ok(Location { pos, address })
+        })
+        .collect()
+}
+
+pub fn find_place_by_source<D: Db>(
+    db: &D,
+    importer: &str,
+    external_ref: &str,
+) -> Result<(Place, ReviewStatus)> {
+    let place_id = db
+        .find_place_id_by_external_id(&importer.trim().to_lowercase(), external_ref.trim())?
+        .ok_or(RepoError::NotFound)?;
+    Ok(db.get_place(&place_id)?)
+}
+
 // Counts and returns the number of tags owned by this org. If the
 // given list of tags contains tags that are owned by any other org
 // then fails with ParameterError::OwnedTag.
@@ -204,10 +361,10 @@ pub fn check_and_count_owned_tags<D: Db>(
 pub fn authorize_user_by_email(
     db: &dyn Db,
     user_email: &str,
-    min_required_role: Role,
+    required_permission: Permission,
 ) -> Result<User> {
     if let Some(user) = db.try_get_user_by_email(user_email)? {
-        if user.role >= min_required_role {
+        if has_permission(db, &user, required_permission)? && db.is_user_active(user_email)? {
             return Ok(user);
         }
     }
@@ -0,0 +1,262 @@
+use super::authorize_user_by_email;
+use crate::core::prelude::*;
+
+fn check_moderation_item_exists<D: Db>(
+    db: &D,
+    item_kind: ModerationItemKind,
+    item_id: &str,
+) -> Result<()> {
+    let exists = match item_kind {
+        ModerationItemKind::Report => db
+            .load_open_reports()?
+            .iter()
+            .any(|r| r.id.as_str() == item_id),
+        ModerationItemKind::Place => db.get_place(&item_id.into())?.1 == ReviewStatus::Created,
+        ModerationItemKind::Clearance => db.count_pending_clearances_for_place(item_id)? > 0,
+    };
+    if !exists {
+        return Err(RepoError::NotFound.into());
+    }
+    Ok(())
+}
+
+/// Assign an open report, an unreviewed place, or a place
+/// that still needs to be cleared to a scout.
+///
+/// A previous assignment of the same item is replaced.
+pub fn assign_moderation_item<D: Db>(
+    db: &D,
+    account_email: &str,
+    item_kind: ModerationItemKind,
+    item_id: &str,
+    assignee_email: &str,
+) -> Result<ModerationAssignment> {
+    authorize_user_by_email(db, account_email, Permission::ModerateReports)?;
+    authorize_user_by_email(db, assignee_email, Permission::ModerateReports)
+        .map_err(|_| ParameterError::InvalidAssignee)?;
+    check_moderation_item_exists(db, item_kind, item_id)?;
+    let assigned = Activity::now(Some(account_email.into()));
+    let assignment = ModerationAssignment {
+        item_kind,
+        item_id: item_id.into(),
+        assignee: assignee_email.into(),
+        state: AssignmentState::Open,
+        changed_at: assigned.at,
+        assigned,
+    };
+    info!(
+        "Assigning {:?} {} to {}",
+        item_kind, item_id, assignee_email
+    );
+    db.assign_moderation_item(assignment.clone())?;
+    Ok(assignment)
+}
+
+pub fn load_open_moderation_assignments<D: Db>(
+    db: &D,
+    account_email: &str,
+) -> Result<Vec<ModerationAssignment>> {
+    authorize_user_by_email(db, account_email, Permission::ModerateReports)?;
+    Ok(db.load_open_moderation_assignments(account_email)?)
+}
+
+/// Only the assignee is allowed to change the state of an assignment.
+pub fn change_moderation_assignment_state<D: Db>(
+    db: &D,
+    account_email: &str,
+    item_kind: ModerationItemKind,
+    item_id: &str,
+    state: AssignmentState,
+) -> Result<()> {
+    authorize_user_by_email(db, account_email, Permission::ModerateReports)?;
+    Ok(db.update_moderation_assignment_state(
+        item_kind,
+        item_id,
+        account_email,
+        state,
+        TimestampMs::now(),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn add_user(db: &MockDb, email: &str, role: Role) {
+        db.users.borrow_mut().push(User {
+            email: email.into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role,
+            language: None,
+        });
+    }
+
+    fn new_db() -> MockDb {
+        let db = MockDb::default();
+        add_user(&db, "scout@example.com", Role::Scout);
+        add_user(&db, "other@example.com", Role::Scout);
+        add_user(&db, "user@example.com", Role::User);
+        db.entries
+            .borrow_mut()
+            .push((Place::build().id("created").finish(), ReviewStatus::Created));
+        db.entries.borrow_mut().push((
+            Place::build().id("confirmed").finish(),
+            ReviewStatus::Confirmed,
+        ));
+        db
+    }
+
+    #[test]
+    fn assign_unreviewed_places_to_scouts() {
+        let db = new_db();
+        let kind = ModerationItemKind::Place;
+        assert!(assign_moderation_item(
+            &db,
+            "user@example.com",
+            kind,
+            "created",
+            "scout@example.com"
+        )
+        .is_err());
+        assert!(assign_moderation_item(
+            &db,
+            "scout@example.com",
+            kind,
+            "created",
+            "user@example.com"
+        )
+        .is_err());
+        assert!(assign_moderation_item(
+            &db,
+            "scout@example.com",
+            kind,
+            "confirmed",
+            "scout@example.com"
+        )
+        .is_err());
+        assert!(
+            assign_moderation_item(&db, "scout@example.com", kind, "foo", "scout@example.com")
+                .is_err()
+        );
+        assert!(db.moderation_assignments.borrow().is_empty());
+
+        let assignment = assign_moderation_item(
+            &db,
+            "other@example.com",
+            kind,
+            "created",
+            "scout@example.com",
+        )
+        .unwrap();
+        assert_eq!(AssignmentState::Open, assignment.state);
+        assert_eq!(
+            vec![assignment],
+            load_open_moderation_assignments(&db, "scout@example.com").unwrap()
+        );
+        assert!(load_open_moderation_assignments(&db, "other@example.com")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn only_the_assignee_changes_the_state() {
+        let db = new_db();
+        let kind = ModerationItemKind::Place;
+        assign_moderation_item(
+            &db,
+            "scout@example.com",
+            kind,
+            "created",
+            "scout@example.com",
+        )
+        .unwrap();
+        assert!(change_moderation_assignment_state(
+            &db,
+            "other@example.com",
+            kind,
+            "created",
+            AssignmentState::InProgress
+        )
+        .is_err());
+        change_moderation_assignment_state(
+            &db,
+            "scout@example.com",
+            kind,
+            "created",
+            AssignmentState::InProgress,
+        )
+        .unwrap();
+        assert_eq!(
+            AssignmentState::InProgress,
+            load_open_moderation_assignments(&db, "scout@example.com").unwrap()[0].state
+        );
+        change_moderation_assignment_state(
+            &db,
+            "scout@example.com",
+            kind,
+            "created",
+            AssignmentState::Resolved,
+        )
+        .unwrap();
+        assert!(load_open_moderation_assignments(&db, "scout@example.com")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn resolve_clearances_after_all_owners_cleared_the_place() {
+        use super::super::{add_pending_clearance_for_place, clear_places_of_organization};
+
+        let db = new_db();
+        let kind = ModerationItemKind::Clearance;
+        let orgs: Vec<_> = ["a", "b"]
+            .iter()
+            .map(|&id| Organization {
+                id: id.into(),
+                name: id.into(),
+                owned_tags: vec!["foo".into()],
+            })
+            .collect();
+        for org in &orgs {
+            db.create_org(org.clone()).unwrap();
+        }
+        assert!(assign_moderation_item(
+            &db,
+            "scout@example.com",
+            kind,
+            "created",
+            "scout@example.com"
+        )
+        .is_err());
+
+        add_pending_clearance_for_place(&db, &["foo".into()], &"created".into(), None, None)
+            .unwrap();
+        assign_moderation_item(
+            &db,
+            "scout@example.com",
+            kind,
+            "created",
+            "scout@example.com",
+        )
+        .unwrap();
+
+        let (place, _) = db.get_place(&"created".into()).unwrap();
+        let clearances = [ClearanceForPlace {
+            place_id: place.id.clone(),
+            cleared_revision: place.revision,
+        }];
+        clear_places_of_organization(&db, &orgs[0], &clearances).unwrap();
+        assert_eq!(
+            1,
+            load_open_moderation_assignments(&db, "scout@example.com")
+                .unwrap()
+                .len()
+        );
+        clear_places_of_organization(&db, &orgs[1], &clearances).unwrap();
+        assert!(load_open_moderation_assignments(&db, "scout@example.com")
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+use crate::core::prelude::*;
+
+// Keep the e-mails short
+const MAX_ITEMS_PER_SECTION: usize = 50;
+
+/// Receive a periodic digest instead of an e-mail for every
+/// change within the subscriptions of the user or switch back
+/// to individual notifications if no interval is given.
+pub fn set_digest_interval<D: Db>(
+    db: &D,
+    email: &str,
+    interval: Option<DigestInterval>,
+) -> Result<()> {
+    if let Some(interval) = interval {
+        // Changing the interval doesn't restart the current period
+        let last_sent_at = db
+            .try_get_digest_settings(email)?
+            .map(|s| s.last_sent_at)
+            .unwrap_or_else(TimestampMs::now);
+        db.save_digest_settings(&DigestSettings {
+            user_email: email.into(),
+            interval,
+            last_sent_at,
+        })?;
+    } else {
+        db.delete_digest_settings(email)?;
+        db.delete_queued_notifications(email, TimestampMs::now())?;
+    }
+    Ok(())
+}
+
+pub fn get_digest_interval<D: Db>(db: &D, email: &str) -> Result<Option<DigestInterval>> {
+    Ok(db.try_get_digest_settings(email)?.map(|s| s.interval))
+}
+
+/// Queue a change of a place for all recipients who prefer
+/// a digest and return the remaining recipients who need
+/// to be notified immediately.
+pub fn enqueue_digest_notifications<D: Db>(
+    db: &D,
+    email_addresses: Vec<String>,
+    kind: NotificationKind,
+    place: &Place,
+) -> Result<Vec<String>> {
+    let created_at = TimestampMs::now();
+    let mut immediate_recipients = vec![];
+    for email in email_addresses {
+        if db.try_get_digest_settings(&email)?.is_none() {
+            immediate_recipients.push(email);
+            continue;
+        }
+        db.enqueue_notification(&QueuedNotification {
+            user_email: email,
+            kind,
+            place_id: place.id.clone(),
+            place_title: place.title.clone(),
+            created_at,
+        })?;
+    }
+    Ok(immediate_recipients)
+}
+
+/// All users whose current digest period has ended.
+pub fn due_notification_digests<D: Db>(db: &D, now: TimestampMs) -> Result<Vec<DigestSettings>> {
+    Ok(db
+        .all_digest_settings()?
+        .into_iter()
+        .filter(|s| s.is_due(now))
+        .collect())
+}
+
+// Places that have been changed multiple times are only
+// mentioned once with their most recent title
+fn push_item(items: &mut Vec<DigestItem>, place_id: Id, title: String) {
+    if let Some(item) = items.iter_mut().find(|item| item.place_id == place_id) {
+        item.title = title;
+    } else if items.len() < MAX_ITEMS_PER_SECTION {
+        items.push(DigestItem { place_id, title });
+    }
+}
+
+/// Summarize all changes that have been queued for the user
+/// until the given time.
+pub fn create_notification_digest<D: Db>(
+    db: &D,
+    settings: &DigestSettings,
+    until: TimestampMs,
+) -> Result<NotificationDigest> {
+    let mut new_places = vec![];
+    let mut updated_places = vec![];
+    for notification in db.load_queued_notifications(&settings.user_email)? {
+        if notification.created_at > until {
+            continue;
+        }
+        let QueuedNotification {
+            kind,
+            place_id,
+            place_title,
+            ..
+        } = notification;
+        match kind {
+            NotificationKind::PlaceAdded => {
+                push_item(&mut new_places, place_id, place_title);
+            }
+            NotificationKind::PlaceUpdated => {
+                if new_places.iter().any(|item| item.place_id == place_id) {
+                    push_item(&mut new_places, place_id, place_title);
+                } else {
+                    push_item(&mut updated_places, place_id, place_title);
+                }
+            }
+        }
+    }
+    Ok(NotificationDigest {
+        since: settings.last_sent_at,
+        interval: settings.interval,
+        new_places,
+        updated_places,
+    })
+}
+
+/// Discard the summarized changes and start the next period.
+pub fn finish_notification_digest<D: Db>(
+    db: &D,
+    settings: &DigestSettings,
+    until: TimestampMs,
+) -> Result<()> {
+    db.delete_queued_notifications(&settings.user_email, until)?;
+    db.save_digest_settings(&DigestSettings {
+        last_sent_at: until,
+        ..settings.clone()
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn place(id: &str, title: &str) -> Place {
+        Place::build().id(id).title(title).finish()
+    }
+
+    #[test]
+    fn only_queue_changes_for_recipients_of_digests() {
+        let db = MockDb::default();
+        set_digest_interval(&db, "digest@example.com", Some(DigestInterval::Daily)).unwrap();
+        let recipients = vec!["digest@example.com".into(), "instant@example.com".into()];
+        let immediate_recipients = enqueue_digest_notifications(
+            &db,
+            recipients,
+            NotificationKind::PlaceAdded,
+            &place("a", "A"),
+        )
+        .unwrap();
+        assert_eq!(vec!["instant@example.com"], immediate_recipients);
+        assert_eq!(
+            1,
+            db.load_queued_notifications("digest@example.com")
+                .unwrap()
+                .len()
+        );
+
+        // Switching back to individual notifications discards the queue
+        set_digest_interval(&db, "digest@example.com", None).unwrap();
+        assert_eq!(
+            None,
+            get_digest_interval(&db, "digest@example.com").unwrap()
+        );
+        assert!(db
+            .load_queued_notifications("digest@example.com")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn summarize_queued_changes_when_due() {
+        let db = MockDb::default();
+        let email = "digest@example.com";
+        set_digest_interval(&db, email, Some(DigestInterval::Weekly)).unwrap();
+        let now = TimestampMs::now();
+        assert!(due_notification_digests(&db, now).unwrap().is_empty());
+
+        let recipients = || vec![email.to_owned()];
+        for (kind, place) in vec![
+            (NotificationKind::PlaceAdded, place("a", "A")),
+            (NotificationKind::PlaceUpdated, place("a", "A2")),
+            (NotificationKind::PlaceUpdated, place("b", "B")),
+            (NotificationKind::PlaceUpdated, place("b", "B2")),
+        ] {
+            enqueue_digest_notifications(&db, recipients(), kind, &place).unwrap();
+        }
+
+        let next_week =
+            TimestampMs::from_seconds(now.into_seconds() + DigestInterval::Weekly.seconds());
+        let due = due_notification_digests(&db, next_week).unwrap();
+        assert_eq!(1, due.len());
+        let digest = create_notification_digest(&db, &due[0], next_week).unwrap();
+        assert_eq!(due[0].last_sent_at, digest.since);
+        assert_eq!(
+            vec![DigestItem {
+                place_id: "a".into(),
+                title: "A2".into()
+            }],
+            digest.new_places
+        );
+        assert_eq!(
+            vec![DigestItem {
+                place_id: "b".into(),
+                title: "B2".into()
+            }],
+            digest.updated_places
+        );
+
+        finish_notification_digest(&db, &due[0], next_week).unwrap();
+        assert!(db.load_queued_notifications(email).unwrap().is_empty());
+        assert!(due_notification_digests(&db, next_week).unwrap().is_empty());
+        // The interval can be changed without restarting the period
+        set_digest_interval(&db, email, Some(DigestInterval::Daily)).unwrap();
+        assert_eq!(
+            next_week,
+            db.try_get_digest_settings(email)
+                .unwrap()
+                .unwrap()
+                .last_sent_at
+        );
+    }
+}
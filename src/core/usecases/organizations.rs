@@ -0,0 +1,422 @@
+use super::{authorize_user_by_email, prepare_tag_list};
+use crate::core::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct NewOrganization {
+    pub name: String,
+    pub owned_tags: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewOrganizationApiToken {
+    pub scopes: Vec<ApiTokenScope>,
+    pub expires_at: Option<TimestampMs>,
+}
+
+fn prepare_new_org<D: Db>(db: &D, new_org: NewOrganization) -> Result<(String, Vec<String>)> {
+    let NewOrganization { name, owned_tags } = new_org;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ParameterError::EmptyOrganizationName.into());
+    }
+    let owned_tags = prepare_tag_list(owned_tags.iter().map(String::as_str));
+    for tag in &owned_tags {
+        db.create_tag_if_it_does_not_exist(&Tag { id: tag.clone() })?;
+    }
+    Ok((name.to_owned(), owned_tags))
+}
+
+// The ownership of tags is indexed and all places with
+// these tags need to be reindexed after it has changed.
+pub(super) fn place_ids_with_any_tag<D: Db>(db: &D, tags: &[String]) -> Result<Vec<Id>> {
+    let tags: Vec<_> = tags.iter().map(String::as_str).collect();
+    Ok(db.find_place_ids_by_tags(&tags)?)
+}
+
+// An API token that grants all scopes and never expires
+fn create_unrestricted_api_token<D: Db>(db: &D, org_id: &str) -> Result<OrganizationApiToken> {
+    let token = OrganizationApiToken {
+        id: Id::new(),
+        org_id: org_id.to_owned(),
+        token: Nonce::new().to_string(),
+        scopes: ApiTokenScope::ALL.to_vec(),
+        created_at: TimestampMs::now(),
+        expires_at: None,
+    };
+    info!(
+        "Creating unrestricted API token {} of organization {}",
+        token.id, token.org_id
+    );
+    db.create_org_api_token(&token)?;
+    Ok(token)
+}
+
+fn is_unrestricted_api_token(token: &OrganizationApiToken) -> bool {
+    token.expires_at.is_none()
+        && ApiTokenScope::ALL
+            .iter()
+            .all(|scope| token.has_scope(*scope))
+}
+
+/// Create an organization with a new API token that
+/// grants all scopes and never expires.
+///
+/// Returns the organization, its API token and the ids
+/// of all places with tags that are now owned by it.
+pub fn create_organization<D: Db>(
+    db: &D,
+    account_email: &str,
+    new_org: NewOrganization,
+) -> Result<(Organization, OrganizationApiToken, Vec<Id>)> {
+    authorize_user_by_email(db, account_email, Permission::ManageOrganizations)?;
+    let (name, owned_tags) = prepare_new_org(db, new_org)?;
+    let org = Organization {
+        id: Id::new().to_string(),
+        name,
+        owned_tags,
+    };
+    info!("Creating organization {}", org.id);
+    db.create_org(org.clone())?;
+    let api_token = create_unrestricted_api_token(db, &org.id)?;
+    let place_ids = place_ids_with_any_tag(db, &org.owned_tags)?;
+    Ok((org, api_token, place_ids))
+}
+
+/// All organizations ordered by name.
+pub fn load_organizations<D: Db>(db: &D, account_email: &str) -> Result<Vec<Organization>> {
+    authorize_user_by_email(db, account_email, Permission::ManageOrganizations)?;
+    Ok(db.all_orgs()?)
+}
+
+pub fn load_organization<D: Db>(db: &D, account_email: &str, id: &str) -> Result<Organization> {
+    authorize_user_by_email(db, account_email, Permission::ManageOrganizations)?;
+    Ok(db.get_org_by_id(id)?)
+}
+
+/// Replace the name and the owned tags of an organization.
+///
+/// The API tokens are kept. Returns the organization and the
+/// ids of all places with tags that are either no longer or
+/// newly owned by it.
+pub fn update_organization<D: Db>(
+    db: &D,
+    account_email: &str,
+    id: &str,
+    new_org: NewOrganization,
+) -> Result<(Organization, Vec<Id>)> {
+    authorize_user_by_email(db, account_email, Permission::ManageOrganizations)?;
+    let old_org = db.get_org_by_id(id)?;
+    let (name, owned_tags) = prepare_new_org(db, new_org)?;
+    let changed_tags: Vec<_> = old_org
+        .owned_tags
+        .iter()
+        .filter(|tag| !owned_tags.contains(tag))
+        .chain(
+            owned_tags
+                .iter()
+                .filter(|tag| !old_org.owned_tags.contains(tag)),
+        )
+        .cloned()
+        .collect();
+    let org = Organization {
+        name,
+        owned_tags,
+        ..old_org
+    };
+    info!("Updating organization {}", org.id);
+    db.update_org(&org)?;
+    let place_ids = place_ids_with_any_tag(db, &changed_tags)?;
+    Ok((org, place_ids))
+}
+
+/// Delete an organization and release all its owned tags.
+///
+/// Returns the ids of all places with tags that have been
+/// owned by the organization.
+pub fn delete_organization<D: Db>(db: &D, account_email: &str, id: &str) -> Result<Vec<Id>> {
+    authorize_user_by_email(db, account_email, Permission::ManageOrganizations)?;
+    let org = db.get_org_by_id(id)?;
+    info!("Deleting organization {}", org.id);
+    db.delete_org(&org.id)?;
+    place_ids_with_any_tag(db, &org.owned_tags)
+}
+
+/// Replace the API tokens of an organization that grant all
+/// scopes and never expire with a new one.
+///
+/// The old tokens are revoked immediately. Tokens that are
+/// restricted to some scopes or expire are kept.
+pub fn regenerate_organization_api_token<D: Db>(
+    db: &D,
+    account_email: &str,
+    id: &str,
+) -> Result<(Organization, OrganizationApiToken)> {
+    authorize_user_by_email(db, account_email, Permission::ManageOrganizations)?;
+    let org = db.get_org_by_id(id)?;
+    info!("Regenerating the API token of organization {}", org.id);
+    for token in db.load_org_api_tokens(&org.id)? {
+        if is_unrestricted_api_token(&token) {
+            db.delete_org_api_token(&org.id, token.id.as_str())?;
+        }
+    }
+    let api_token = create_unrestricted_api_token(db, &org.id)?;
+    Ok((org, api_token))
+}
+
+/// Create an API token of an organization that only
+/// grants the given scopes.
+pub fn create_organization_api_token<D: Db>(
+    db: &D,
+    account_email: &str,
+    org_id: &str,
+    new_token: NewOrganizationApiToken,
+) -> Result<OrganizationApiToken> {
+    authorize_user_by_email(db, account_email, Permission::ManageOrganizations)?;
+    let org = db.get_org_by_id(org_id)?;
+    let NewOrganizationApiToken {
+        mut scopes,
+        expires_at,
+    } = new_token;
+    scopes.sort_unstable();
+    scopes.dedup();
+    if scopes.is_empty() {
+        return Err(ParameterError::EmptyApiTokenScopes.into());
+    }
+    let token = OrganizationApiToken {
+        id: Id::new(),
+        org_id: org.id,
+        token: Nonce::new().to_string(),
+        scopes,
+        created_at: TimestampMs::now(),
+        expires_at,
+    };
+    info!(
+        "Creating API token {} of organization {}",
+        token.id, token.org_id
+    );
+    db.create_org_api_token(&token)?;
+    Ok(token)
+}
+
+/// The API tokens of an organization including expired
+/// tokens, oldest first.
+pub fn load_organization_api_tokens<D: Db>(
+    db: &D,
+    account_email: &str,
+    org_id: &str,
+) -> Result<Vec<OrganizationApiToken>> {
+    authorize_user_by_email(db, account_email, Permission::ManageOrganizations)?;
+    let org = db.get_org_by_id(org_id)?;
+    Ok(db.load_org_api_tokens(&org.id)?)
+}
+
+/// Revoke an API token of an organization.
+pub fn delete_organization_api_token<D: Db>(
+    db: &D,
+    account_email: &str,
+    org_id: &str,
+    id: &str,
+) -> Result<()> {
+    authorize_user_by_email(db, account_email, Permission::ManageOrganizations)?;
+    info!("Deleting API token {} of organization {}", id, org_id);
+    Ok(db.delete_org_api_token(org_id, id)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        authorize_organization_by_token,
+        tests::{create_user, MockDb},
+    };
+    use super::*;
+
+    fn new_org(name: &str, owned_tags: &[&str]) -> NewOrganization {
+        NewOrganization {
+            name: name.into(),
+            owned_tags: owned_tags.iter().map(|tag| (*tag).to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn create_update_and_delete_organization() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        db.entries.borrow_mut().push((
+            Place::build().id("a").tags(vec!["foo"]).finish(),
+            ReviewStatus::Created,
+        ));
+        db.entries.borrow_mut().push((
+            Place::build().id("b").tags(vec!["bar"]).finish(),
+            ReviewStatus::Created,
+        ));
+
+        let (org, api_token, place_ids) =
+            create_organization(&db, "admin@example.com", new_org(" Org ", &["#Foo", "baz"]))
+                .unwrap();
+        assert_eq!("Org", org.name);
+        assert_eq!(vec!["baz", "foo"], org.owned_tags);
+        assert!(!api_token.token.is_empty());
+        assert_eq!(ApiTokenScope::ALL.to_vec(), api_token.scopes);
+        assert!(api_token.expires_at.is_none());
+        assert_eq!(vec![Id::from("a")], place_ids);
+        assert_eq!(vec!["baz", "foo"], db.get_all_tags_owned_by_orgs().unwrap());
+
+        let (updated, place_ids) = update_organization(
+            &db,
+            "admin@example.com",
+            &org.id,
+            new_org("Renamed", &["baz", "bar"]),
+        )
+        .unwrap();
+        assert_eq!("Renamed", updated.name);
+        assert_eq!(vec![api_token], db.load_org_api_tokens(&org.id).unwrap());
+        assert_eq!(vec![Id::from("a"), Id::from("b")], place_ids);
+        assert_eq!(
+            vec![updated],
+            load_organizations(&db, "admin@example.com").unwrap()
+        );
+
+        let place_ids = delete_organization(&db, "admin@example.com", &org.id).unwrap();
+        assert_eq!(vec![Id::from("b")], place_ids);
+        assert!(db.all_orgs().unwrap().is_empty());
+        assert!(matches!(
+            delete_organization(&db, "admin@example.com", &org.id),
+            Err(Error::Repo(RepoError::NotFound))
+        ));
+    }
+
+    #[test]
+    fn regenerate_api_token() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        let (org, old_token, _) =
+            create_organization(&db, "admin@example.com", new_org("Org", &[])).unwrap();
+        let read_only = create_organization_api_token(
+            &db,
+            "admin@example.com",
+            &org.id,
+            NewOrganizationApiToken {
+                scopes: vec![ApiTokenScope::Read],
+                expires_at: None,
+            },
+        )
+        .unwrap();
+        let (updated, new_token) =
+            regenerate_organization_api_token(&db, "admin@example.com", &org.id).unwrap();
+        assert_eq!(org, updated);
+        assert_ne!(old_token.token, new_token.token);
+        assert_eq!(ApiTokenScope::ALL.to_vec(), new_token.scopes);
+        assert!(matches!(
+            authorize_organization_by_token(&db, &old_token.token, ApiTokenScope::Read),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+        assert_eq!(
+            org,
+            authorize_organization_by_token(&db, &new_token.token, ApiTokenScope::WriteEvents)
+                .unwrap()
+        );
+        // Restricted tokens are kept
+        assert_eq!(
+            org,
+            authorize_organization_by_token(&db, &read_only.token, ApiTokenScope::Read).unwrap()
+        );
+    }
+
+    #[test]
+    fn only_admins_can_manage_organizations() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_user(&db, "scout@example.com", Role::Scout);
+        assert!(matches!(
+            create_organization(&db, "scout@example.com", new_org("Org", &[])),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+        assert!(matches!(
+            create_organization(&db, "admin@example.com", new_org("  ", &[])),
+            Err(Error::Parameter(ParameterError::EmptyOrganizationName))
+        ));
+        assert!(matches!(
+            load_organizations(&db, "scout@example.com"),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+        assert!(db.all_orgs().unwrap().is_empty());
+    }
+
+    #[test]
+    fn scoped_api_tokens() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        let (org, api_token, _) =
+            create_organization(&db, "admin@example.com", new_org("Org", &[])).unwrap();
+        assert!(matches!(
+            create_organization_api_token(
+                &db,
+                "admin@example.com",
+                &org.id,
+                NewOrganizationApiToken {
+                    scopes: vec![],
+                    expires_at: None,
+                },
+            ),
+            Err(Error::Parameter(ParameterError::EmptyApiTokenScopes))
+        ));
+        let read_only = create_organization_api_token(
+            &db,
+            "admin@example.com",
+            &org.id,
+            NewOrganizationApiToken {
+                scopes: vec![ApiTokenScope::Read, ApiTokenScope::Read],
+                expires_at: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(vec![ApiTokenScope::Read], read_only.scopes);
+        let expired = create_organization_api_token(
+            &db,
+            "admin@example.com",
+            &org.id,
+            NewOrganizationApiToken {
+                scopes: ApiTokenScope::ALL.to_vec(),
+                expires_at: Some(TimestampMs::from_inner(0)),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            3,
+            load_organization_api_tokens(&db, "admin@example.com", &org.id)
+                .unwrap()
+                .len()
+        );
+
+        // The initial token grants all scopes
+        for &scope in ApiTokenScope::ALL {
+            assert_eq!(
+                org.id,
+                authorize_organization_by_token(&db, &api_token.token, scope)
+                    .unwrap()
+                    .id
+            );
+        }
+        assert_eq!(
+            org.id,
+            authorize_organization_by_token(&db, &read_only.token, ApiTokenScope::Read)
+                .unwrap()
+                .id
+        );
+        assert!(matches!(
+            authorize_organization_by_token(&db, &read_only.token, ApiTokenScope::WriteEvents),
+            Err(Error::Parameter(ParameterError::Forbidden))
+        ));
+        assert!(matches!(
+            authorize_organization_by_token(&db, &expired.token, ApiTokenScope::Read),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+
+        delete_organization_api_token(&db, "admin@example.com", &org.id, read_only.id.as_str())
+            .unwrap();
+        assert!(matches!(
+            authorize_organization_by_token(&db, &read_only.token, ApiTokenScope::Read),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+    }
+}
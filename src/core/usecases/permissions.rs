@@ -0,0 +1,211 @@
+use super::authorize_user_by_email;
+use crate::core::prelude::*;
+
+use std::collections::BTreeSet;
+
+/// The effective permissions of a user, i.e. the default
+/// permissions of the role with all overrides applied.
+pub fn user_permissions(db: &dyn Db, user: &User) -> Result<BTreeSet<Permission>> {
+    let mut permissions: BTreeSet<_> = user.role.default_permissions().iter().copied().collect();
+    for PermissionOverride {
+        permission,
+        granted,
+    } in db.load_permission_overrides(&user.email)?
+    {
+        if granted {
+            permissions.insert(permission);
+        } else {
+            permissions.remove(&permission);
+        }
+    }
+    Ok(permissions)
+}
+
+/// The effective permissions of an optional account. Anonymous,
+/// unknown and deactivated accounts don't have any permissions.
+pub fn account_permissions(
+    db: &dyn Db,
+    account_email: Option<&str>,
+) -> Result<BTreeSet<Permission>> {
+    if let Some(account_email) = account_email {
+        if let Some(user) = db.try_get_user_by_email(account_email)? {
+            if db.is_user_active(account_email)? {
+                return user_permissions(db, &user);
+            }
+        }
+    }
+    Ok(BTreeSet::new())
+}
+
+pub fn has_permission(db: &dyn Db, user: &User, permission: Permission) -> Result<bool> {
+    Ok(user_permissions(db, user)?.contains(&permission))
+}
+
+fn authorize_user_permission_change<D: Db>(
+    db: &D,
+    account_email: &str,
+    user_email: &str,
+) -> Result<User> {
+    let account = authorize_user_by_email(db, account_email, Permission::ManageUsers)?;
+    let user = db
+        .try_get_user_by_email(user_email)?
+        .ok_or(ParameterError::UserDoesNotExist)?;
+    if account.role > user.role {
+        Ok(user)
+    } else {
+        Err(ParameterError::Forbidden.into())
+    }
+}
+
+pub fn load_user_permissions<D: Db>(
+    db: &D,
+    account_email: &str,
+    user_email: &str,
+) -> Result<BTreeSet<Permission>> {
+    authorize_user_by_email(db, account_email, Permission::ManageUsers)?;
+    let user = db
+        .try_get_user_by_email(user_email)?
+        .ok_or(ParameterError::UserDoesNotExist)?;
+    user_permissions(db, &user)
+}
+
+/// Grant or revoke a single permission of a user.
+///
+/// Without a value the permission is reset to the default
+/// of the user's role.
+pub fn change_user_permission<D: Db>(
+    db: &D,
+    account_email: &str,
+    user_email: &str,
+    permission: Permission,
+    granted: Option<bool>,
+) -> Result<()> {
+    let user = authorize_user_permission_change(db, account_email, user_email)?;
+    info!(
+        "Changing permission {:?} of {} to {:?}",
+        permission, user_email, granted
+    );
+    match granted {
+        Some(granted) if granted != user.role.has_default_permission(permission) => {
+            db.replace_permission_override(
+                user_email,
+                &PermissionOverride {
+                    permission,
+                    granted,
+                },
+            )?;
+        }
+        _ => {
+            // Same as the default of the role
+            db.delete_permission_override(user_email, permission)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_user, MockDb};
+    use super::*;
+
+    #[test]
+    fn grant_and_revoke_permissions_of_single_users() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_user(&db, "scout@example.com", Role::Scout);
+        create_user(&db, "user@example.com", Role::User);
+
+        assert!(
+            authorize_user_by_email(&db, "user@example.com", Permission::ArchiveEvents).is_err()
+        );
+        change_user_permission(
+            &db,
+            "admin@example.com",
+            "user@example.com",
+            Permission::ArchiveEvents,
+            Some(true),
+        )
+        .unwrap();
+        assert!(
+            authorize_user_by_email(&db, "user@example.com", Permission::ArchiveEvents).is_ok()
+        );
+        assert!(
+            authorize_user_by_email(&db, "user@example.com", Permission::ReviewPlaces).is_err()
+        );
+
+        change_user_permission(
+            &db,
+            "admin@example.com",
+            "scout@example.com",
+            Permission::ReviewPlaces,
+            Some(false),
+        )
+        .unwrap();
+        let permissions =
+            load_user_permissions(&db, "admin@example.com", "scout@example.com").unwrap();
+        assert!(!permissions.contains(&Permission::ReviewPlaces));
+        assert!(permissions.contains(&Permission::ArchiveEvents));
+
+        // Reset to the defaults of the roles
+        change_user_permission(
+            &db,
+            "admin@example.com",
+            "scout@example.com",
+            Permission::ReviewPlaces,
+            None,
+        )
+        .unwrap();
+        change_user_permission(
+            &db,
+            "admin@example.com",
+            "user@example.com",
+            Permission::ArchiveEvents,
+            Some(false),
+        )
+        .unwrap();
+        assert!(db.permission_overrides.borrow().is_empty());
+    }
+
+    #[test]
+    fn only_admins_can_change_permissions_of_inferior_users() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_user(&db, "other-admin@example.com", Role::Admin);
+        create_user(&db, "scout@example.com", Role::Scout);
+        create_user(&db, "user@example.com", Role::User);
+        assert!(matches!(
+            change_user_permission(
+                &db,
+                "scout@example.com",
+                "user@example.com",
+                Permission::ArchiveEvents,
+                Some(true),
+            ),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+        assert!(matches!(
+            change_user_permission(
+                &db,
+                "admin@example.com",
+                "other-admin@example.com",
+                Permission::ManageUsers,
+                Some(false),
+            ),
+            Err(Error::Parameter(ParameterError::Forbidden))
+        ));
+        assert!(db.permission_overrides.borrow().is_empty());
+    }
+
+    #[test]
+    fn anonymous_and_unknown_accounts_have_no_permissions() {
+        let db = MockDb::default();
+        create_user(&db, "scout@example.com", Role::Scout);
+        assert!(account_permissions(&db, None).unwrap().is_empty());
+        assert!(account_permissions(&db, Some("unknown@example.com"))
+            .unwrap()
+            .is_empty());
+        assert!(account_permissions(&db, Some("scout@example.com"))
+            .unwrap()
+            .contains(&Permission::ExportData));
+    }
+}
@@ -0,0 +1,211 @@
+use super::restore_place_revision::copy_place_revision;
+use crate::core::prelude::*;
+
+/// Record an edit of a place for all organizations that own
+/// any of the given tags, except for the editing organization.
+///
+/// Both the tags of the new and of the previous revision need
+/// to be passed, otherwise owners would not notice that their
+/// tags have been removed from a place.
+pub fn add_pending_clearance_for_place<D: Db>(
+    db: &D,
+    tags: &[String],
+    place_id: &Id,
+    last_cleared_revision: Option<Revision>,
+    edited_by_org_id: Option<&str>,
+) -> Result<()> {
+    let mut org_ids: Vec<_> = db
+        .get_all_tag_ownerships()?
+        .into_iter()
+        .filter(|ownership| tags.contains(&ownership.tag))
+        .map(|ownership| ownership.org_id)
+        .filter(|org_id| Some(org_id.as_str()) != edited_by_org_id)
+        .collect();
+    if org_ids.is_empty() {
+        return Ok(());
+    }
+    org_ids.sort_unstable();
+    org_ids.dedup();
+    let org_ids: Vec<_> = org_ids.iter().map(String::as_str).collect();
+    debug!(
+        "Edit of place {} needs to be cleared by {:?}",
+        place_id, org_ids
+    );
+    let pending = PendingClearanceForPlace {
+        place_id: place_id.clone(),
+        created_at: TimestampMs::now(),
+        last_cleared_revision,
+    };
+    Ok(db.add_pending_clearance_for_place(&org_ids, &pending)?)
+}
+
+/// All places with edits that the organization has not
+/// cleared yet, oldest first.
+pub fn load_pending_clearances_for_places<D: Db>(
+    db: &D,
+    org: &Organization,
+) -> Result<Vec<PendingClearanceForPlace>> {
+    Ok(db.load_pending_clearances_for_places(&org.id)?)
+}
+
+/// Accept or override the pending edits of places.
+///
+/// Edits are accepted by clearing the current revision. If an
+/// earlier revision is cleared instead, e.g. the last cleared
+/// revision, then its contents are restored in a new revision
+/// that keeps the current review status. Returns the restored
+/// places.
+pub fn clear_places_of_organization<D: Db>(
+    db: &D,
+    org: &Organization,
+    clearances: &[ClearanceForPlace],
+) -> Result<Vec<Place>> {
+    let mut restored_places = vec![];
+    for ClearanceForPlace {
+        place_id,
+        cleared_revision,
+    } in clearances
+    {
+        db.delete_pending_clearance_for_place(&org.id, place_id.as_str())?;
+        let (place, status) = db.get_place(&place_id.as_str().into())?;
+        if place.revision == *cleared_revision {
+            continue;
+        }
+        info!(
+            "Organization {} restores revision {} of place {}",
+            org.id,
+            u64::from(*cleared_revision),
+            place_id
+        );
+        let activity = Activity::now(None);
+        let restored_place =
+            copy_place_revision(db, &place_id.as_str().into(), *cleared_revision, &activity)?;
+        let activity_log = ActivityLog {
+            activity,
+            context: None,
+            comment: Some(format!(
+                "Restored revision {} by organization {}",
+                u64::from(*cleared_revision),
+                org.name
+            )),
+        };
+        db.review_places(&[place_id.as_str()], status, &activity_log)?;
+        add_pending_clearance_for_place(
+            db,
+            &place
+                .tags
+                .iter()
+                .chain(&restored_place.tags)
+                .cloned()
+                .collect::<Vec<_>>(),
+            place_id,
+            Some(place.revision),
+            Some(&org.id),
+        )?;
+        restored_places.push(restored_place);
+    }
+    // Assignments are resolved as soon as all owners have cleared the place
+    let cleared_place_ids = clearances
+        .iter()
+        .map(|c| c.place_id.as_str())
+        .filter(|place_id| {
+            db.count_pending_clearances_for_place(place_id)
+                .map(|count| count == 0)
+                .unwrap_or(false)
+        })
+        .collect::<Vec<_>>();
+    if !cleared_place_ids.is_empty() {
+        db.resolve_moderation_assignments(
+            ModerationItemKind::Clearance,
+            &cleared_place_ids,
+            TimestampMs::now(),
+        )?;
+    }
+    Ok(restored_places)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn create_org(db: &MockDb, id: &str, owned_tags: &[&str]) -> Organization {
+        let org = Organization {
+            id: id.into(),
+            name: id.into(),
+            owned_tags: owned_tags.iter().map(|tag| (*tag).to_string()).collect(),
+        };
+        db.create_org(org.clone()).unwrap();
+        org
+    }
+
+    #[test]
+    fn add_pending_clearances_for_owners_of_tags() {
+        let db = MockDb::default();
+        let a = create_org(&db, "a", &["foo"]);
+        let b = create_org(&db, "b", &["foo", "bar"]);
+        let c = create_org(&db, "c", &["baz"]);
+        let tags = vec!["foo".to_string(), "bar".to_string()];
+
+        add_pending_clearance_for_place(&db, &tags, &"x".into(), None, None).unwrap();
+        add_pending_clearance_for_place(
+            &db,
+            &tags,
+            &"y".into(),
+            Some(Revision::from(1)),
+            Some("a"),
+        )
+        .unwrap();
+        // Already pending places keep their last cleared revision
+        add_pending_clearance_for_place(&db, &tags, &"y".into(), Some(Revision::from(2)), None)
+            .unwrap();
+
+        let pending = load_pending_clearances_for_places(&db, &a).unwrap();
+        assert_eq!(2, pending.len());
+        assert_eq!(None, pending[0].last_cleared_revision);
+        assert_eq!(Some(Revision::from(2)), pending[1].last_cleared_revision);
+        let pending = load_pending_clearances_for_places(&db, &b).unwrap();
+        assert_eq!(2, pending.len());
+        assert_eq!(Id::from("y"), pending[1].place_id);
+        assert_eq!(Some(Revision::from(1)), pending[1].last_cleared_revision);
+        assert!(load_pending_clearances_for_places(&db, &c)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn accept_pending_edits() {
+        let db = MockDb::default();
+        let org = create_org(&db, "org", &["foo"]);
+        db.entries.borrow_mut().push((
+            Place::build()
+                .id("x")
+                .revision(1)
+                .tags(vec!["foo"])
+                .finish(),
+            ReviewStatus::Created,
+        ));
+        add_pending_clearance_for_place(
+            &db,
+            &["foo".to_string()],
+            &"x".into(),
+            Some(Revision::from(0)),
+            None,
+        )
+        .unwrap();
+
+        let clearance = ClearanceForPlace {
+            place_id: "x".into(),
+            cleared_revision: Revision::from(1),
+        };
+        let restored = clear_places_of_organization(&db, &org, &[clearance.clone()]).unwrap();
+        assert!(restored.is_empty());
+        assert!(load_pending_clearances_for_places(&db, &org)
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            clear_places_of_organization(&db, &org, &[clearance]),
+            Err(Error::Repo(RepoError::NotFound))
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+use crate::core::prelude::*;
+
+// Protects the database from arbitrarily large payloads
+const MAX_DRAFT_DATA_LEN: usize = 64 * 1024;
+
+fn check_data(data: &str) -> Result<()> {
+    if data.len() > MAX_DRAFT_DATA_LEN {
+        return Err(ParameterError::DraftTooLarge.into());
+    }
+    Ok(())
+}
+
+/// Load a draft that must belong to the given owner
+pub fn load_place_draft<D: Db>(db: &D, owner_email: &str, id: &str) -> Result<PlaceDraft> {
+    let draft = db.load_place_draft(id)?;
+    if draft.owner_email != owner_email {
+        return Err(ParameterError::Forbidden.into());
+    }
+    Ok(draft)
+}
+
+/// All drafts of a user, the most recently updated first
+pub fn load_place_drafts<D: Db>(db: &D, owner_email: &str) -> Result<Vec<PlaceDraft>> {
+    Ok(db.load_place_drafts_of_owner(owner_email)?)
+}
+
+pub fn create_place_draft<D: Db>(db: &D, owner_email: &str, data: String) -> Result<PlaceDraft> {
+    check_data(&data)?;
+    let now = TimestampMs::now();
+    let draft = PlaceDraft {
+        id: Id::new(),
+        owner_email: owner_email.to_owned(),
+        created_at: now,
+        updated_at: now,
+        data,
+    };
+    db.create_place_draft(&draft)?;
+    Ok(draft)
+}
+
+pub fn update_place_draft<D: Db>(
+    db: &D,
+    owner_email: &str,
+    id: &str,
+    data: String,
+) -> Result<PlaceDraft> {
+    check_data(&data)?;
+    let mut draft = load_place_draft(db, owner_email, id)?;
+    draft.updated_at = TimestampMs::now();
+    draft.data = data;
+    db.update_place_draft(&draft)?;
+    Ok(draft)
+}
+
+/// Delete a draft, e.g. after the place has been created
+pub fn delete_place_draft<D: Db>(db: &D, owner_email: &str, id: &str) -> Result<()> {
+    load_place_draft(db, owner_email, id)?;
+    Ok(db.delete_place_draft(id)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    #[test]
+    fn create_update_and_delete_place_drafts() {
+        let db = MockDb::default();
+        let draft = create_place_draft(&db, "a@example.com", r#"{"title":"foo"}"#.into()).unwrap();
+        create_place_draft(&db, "b@example.com", "{}".into()).unwrap();
+
+        let updated = update_place_draft(
+            &db,
+            "a@example.com",
+            draft.id.as_str(),
+            r#"{"title":"bar"}"#.into(),
+        )
+        .unwrap();
+        assert_eq!(draft.created_at, updated.created_at);
+        let drafts = load_place_drafts(&db, "a@example.com").unwrap();
+        assert_eq!(vec![updated], drafts);
+
+        delete_place_draft(&db, "a@example.com", draft.id.as_str()).unwrap();
+        assert!(load_place_drafts(&db, "a@example.com").unwrap().is_empty());
+        assert_eq!(1, load_place_drafts(&db, "b@example.com").unwrap().len());
+    }
+
+    #[test]
+    fn drafts_of_other_users_are_inaccessible() {
+        let db = MockDb::default();
+        let draft = create_place_draft(&db, "a@example.com", "{}".into()).unwrap();
+        assert!(matches!(
+            load_place_draft(&db, "b@example.com", draft.id.as_str()),
+            Err(Error::Parameter(ParameterError::Forbidden))
+        ));
+        assert!(matches!(
+            update_place_draft(&db, "b@example.com", draft.id.as_str(), "{}".into()),
+            Err(Error::Parameter(ParameterError::Forbidden))
+        ));
+        assert!(matches!(
+            delete_place_draft(&db, "b@example.com", draft.id.as_str()),
+            Err(Error::Parameter(ParameterError::Forbidden))
+        ));
+        assert_eq!(1, load_place_drafts(&db, "a@example.com").unwrap().len());
+    }
+
+    #[test]
+    fn reject_too_large_drafts() {
+        let db = MockDb::default();
+        let data = format!(r#"{{"description":"{}"}}"#, "x".repeat(MAX_DRAFT_DATA_LEN));
+        assert!(matches!(
+            create_place_draft(&db, "a@example.com", data),
+            Err(Error::Parameter(ParameterError::DraftTooLarge))
+        ));
+    }
+}
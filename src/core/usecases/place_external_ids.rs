@@ -0,0 +1,80 @@
+use super::authorize_user_by_email;
+use crate::core::prelude::*;
+
+/// Load the references of a place to records in other data sources.
+pub fn load_place_external_ids<D: Db>(db: &D, place_id: &PlaceId) -> Result<Vec<ExternalId>> {
+    // Fails for unknown places
+    db.get_place(place_id)?;
+    Ok(db.load_place_external_ids(place_id)?)
+}
+
+// The names of sources are case-insensitive
+pub(crate) fn normalize_external_id(external_id: ExternalId) -> Result<ExternalId> {
+    let ExternalId { source, id, url } = external_id;
+    let source = source.trim().to_lowercase();
+    let id = id.trim().to_owned();
+    if source.is_empty() || id.is_empty() {
+        return Err(ParameterError::InvalidExternalId.into());
+    }
+    Ok(ExternalId { source, id, url })
+}
+
+/// Replace all references of a place to records in other data sources.
+///
+/// Each record of a data source can only be referenced by
+/// a single place. Only scouts and admins are allowed to
+/// change the external ids of places.
+pub fn replace_place_external_ids<D: Db>(
+    db: &D,
+    account_email: &str,
+    place_id: &PlaceId,
+    external_ids: Vec<ExternalId>,
+) -> Result<Vec<ExternalId>> {
+    authorize_user_by_email(db, account_email, Permission::ReviewPlaces)?;
+    db.get_place(place_id)?;
+    let mut external_ids = external_ids
+        .into_iter()
+        .map(normalize_external_id)
+        .collect::<Result<Vec<_>>>()?;
+    external_ids.sort_by(|lhs, rhs| (&lhs.source, &lhs.id).cmp(&(&rhs.source, &rhs.id)));
+    external_ids.dedup_by(|lhs, rhs| lhs.source == rhs.source && lhs.id == rhs.id);
+    for external_id in &external_ids {
+        if let Some(other_place_id) =
+            db.find_place_id_by_external_id(&external_id.source, &external_id.id)?
+        {
+            if &other_place_id != place_id {
+                return Err(ParameterError::DuplicateExternalId.into());
+            }
+        }
+    }
+    info!(
+        "Replacing external ids of place {}: {}",
+        place_id,
+        external_ids.len()
+    );
+    db.replace_place_external_ids(place_id, &external_ids)?;
+    Ok(external_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn external_id(source: &str, id: &str) -> ExternalId {
+        ExternalId {
+            source: source.into(),
+            id: id.into(),
+            url: None,
+        }
+    }
+
+    #[test]
+    fn normalize_source_and_id() {
+        assert_eq!(
+            external_id("osm", "node/123"),
+            normalize_external_id(external_id(" OSM ", " node/123")).unwrap()
+        );
+        assert!(normalize_external_id(external_id("", "123")).is_err());
+        assert!(normalize_external_id(external_id("wikidata", " ")).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use crate::core::prelude::*;
+
+// Only look back this far for changes
+const MAX_FEED_AGE_SECONDS: i64 = 100 * 24 * 60 * 60;
+
+const RECENTLY_CHANGED_PLACES_PAGE_SIZE: u64 = 1000;
+
+/// Optional filters for following the changes of places,
+/// e.g. within the region of a community.
+#[derive(Debug, Clone, Default)]
+pub struct PlacesFeedFilter {
+    pub bbox: Option<MapBbox>,
+    /// Places need to be tagged with all of these tags
+    pub tags: Vec<String>,
+}
+
+impl PlacesFeedFilter {
+    fn matches(&self, place: &Place) -> bool {
+        if let Some(bbox) = self.bbox {
+            if !bbox.contains_point(place.location.pos) {
+                return false;
+            }
+        }
+        self.tags.iter().all(|tag| place.tags.contains(tag))
+    }
+}
+
+/// The most recent changes of visible places that match
+/// the filter, newest first.
+pub fn load_places_feed<D: Db>(
+    db: &D,
+    filter: &PlacesFeedFilter,
+    limit: usize,
+) -> Result<Vec<RecentlyChangedPlace>> {
+    let mut params = RecentlyChangedEntriesParams {
+        since: Some(TimestampMs::from_seconds(
+            TimestampMs::now().into_seconds() - MAX_FEED_AGE_SECONDS,
+        )),
+        ..Default::default()
+    };
+    let pagination = Pagination {
+        offset: None,
+        limit: Some(RECENTLY_CHANGED_PLACES_PAGE_SIZE),
+    };
+    let mut feed = vec![];
+    // Continue with older changes until enough changes match the filter
+    loop {
+        let changes = db.recently_changed_places(&params, &pagination)?;
+        let last_page = (changes.len() as u64) < RECENTLY_CHANGED_PLACES_PAGE_SIZE;
+        params.after = changes.last().map(|(_, _, _, cursor)| *cursor);
+        feed.extend(
+            changes
+                .into_iter()
+                .filter(|(place, status, _, _)| status.exists() && filter.matches(place)),
+        );
+        if last_page || feed.len() >= limit {
+            break;
+        }
+    }
+    feed.truncate(limit);
+    Ok(feed)
+}
@@ -3,6 +3,7 @@ use crate::core::{
     prelude::*,
     util::{extract_hash_tags, remove_hash_tags},
 };
+use chrono::NaiveDateTime;
 use ofdb_core::util::filter;
 
 const DEFAULT_RESULT_LIMIT: usize = 100;
@@ -77,7 +78,7 @@ pub fn query_events<D: Db>(db: &D, index: &dyn IdIndex, query: EventQuery) -> Re
             let invisible_events_query = IndexQuery {
                 include_bbox: Some(filter::extend_bbox(&visible_bbox)),
                 exclude_bbox: visible_events_query.include_bbox,
-                ..visible_events_query
+                ..visible_events_query.clone()
             };
             index
                 .query_ids(
@@ -93,11 +94,34 @@ pub fn query_events<D: Db>(db: &D, index: &dyn IdIndex, query: EventQuery) -> Re
         vec![]
     };
 
-    let event_ids: Vec<_> = visible_event_ids
+    // 3rd query: Search for recurring events that started before
+    // the requested time window but might still occur within it
+    let recurring_event_ids = if let Some(start_min) = start_min {
+        let recurring_events_query = IndexQuery {
+            ts_min_lb: None,
+            ts_min_ub: Some(start_min),
+            ts_max_lb: Some(start_min),
+            ..visible_events_query.clone()
+        };
+        index
+            .query_ids(
+                IndexQueryMode::WithoutRating,
+                &recurring_events_query,
+                limit,
+            )
+            .map_err(RepoError::Other)?
+    } else {
+        vec![]
+    };
+
+    let mut event_ids: Vec<_> = visible_event_ids
         .iter()
         .chain(invisible_event_ids.iter())
+        .chain(recurring_event_ids.iter())
         .map(Id::as_str)
         .collect();
+    event_ids.sort_unstable();
+    event_ids.dedup();
     let mut events = db.get_events_chronologically(&event_ids)?;
 
     if let Some(ref email) = created_by {
@@ -111,5 +135,101 @@ pub fn query_events<D: Db>(db: &D, index: &dyn IdIndex, query: EventQuery) -> Re
         }
     }
 
+    if start_min.is_some() || start_max.is_some() {
+        // Replace recurring events by their occurrences within
+        // the requested time window
+        let start_min = start_min.map(NaiveDateTime::from);
+        let start_max = start_max.map(NaiveDateTime::from);
+        events = events
+            .into_iter()
+            .flat_map(|e| e.occurrences(start_min, start_max, limit))
+            .collect();
+        events.sort_by(|a, b| a.start.cmp(&b.start));
+        events.truncate(limit);
+    }
+
     Ok(events)
 }
+
+#[derive(Clone, Debug)]
+pub struct NearbyEventQuery {
+    pub center: MapPoint,
+    pub radius: Distance,
+    /// Only events that have not ended before this point in time
+    pub from: Option<Timestamp>,
+    /// Only events that start before this point in time
+    pub to: Option<Timestamp>,
+
+    pub limit: Option<usize>,
+}
+
+/// Find all events within a circular area that overlap
+/// with the given time window.
+///
+/// The index is queried for events within the bounding box
+/// around the circle that start before the end of the time
+/// window. The results are then filtered by their exact
+/// distance from the center and by their end time, because
+/// events without an end are only indexed with their start.
+/// The index is queried repeatedly with an increasing limit
+/// until enough events remain after filtering.
+pub fn query_events_nearby<D: Db>(
+    db: &D,
+    index: &dyn IdIndex,
+    query: NearbyEventQuery,
+) -> Result<Vec<Event>> {
+    let NearbyEventQuery {
+        center,
+        radius,
+        from,
+        to,
+        limit,
+    } = query;
+    if !center.is_valid() {
+        return Err(ParameterError::InvalidPosition.into());
+    }
+    if !radius.is_valid() || radius.to_meters() <= 0.0 {
+        return Err(ParameterError::InvalidRadius.into());
+    }
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return Err(ParameterError::EndDateBeforeStart.into());
+        }
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_RESULT_LIMIT);
+    let index_query = IndexQuery {
+        include_bbox: Some(MapBbox::centered_around(center, radius)),
+        categories: vec![Category::ID_EVENT],
+        ts_min_ub: to,
+        ..Default::default()
+    };
+    let mut index_limit = limit;
+    loop {
+        let event_ids = index
+            .query_ids(IndexQueryMode::WithoutRating, &index_query, index_limit)
+            .map_err(RepoError::Other)?;
+        let exhausted = event_ids.len() < index_limit;
+        let event_ids: Vec<_> = event_ids.iter().map(Id::as_str).collect();
+        let mut events: Vec<_> = db
+            .get_events_chronologically(&event_ids)?
+            .into_iter()
+            .filter(|e| {
+                from.map(|from| Timestamp::from(e.end.unwrap_or(e.start)) >= from)
+                    .unwrap_or(true)
+            })
+            .filter(|e| {
+                e.location
+                    .as_ref()
+                    .and_then(|l| MapPoint::distance(center, l.pos))
+                    .map(|d| d.to_meters() <= radius.to_meters())
+                    .unwrap_or(false)
+            })
+            .collect();
+        if exhausted || events.len() >= limit {
+            events.truncate(limit);
+            return Ok(events);
+        }
+        index_limit = index_limit.saturating_mul(2);
+    }
+}
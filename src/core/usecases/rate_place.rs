@@ -25,6 +25,22 @@ impl Storable {
     }
 }
 
+/// Check that the author of a new rating is an active, registered user
+/// with a confirmed e-mail address.
+pub fn authorize_rating_author<D: Db>(db: &D, author_email: Option<&str>) -> Result<User> {
+    let email = author_email.ok_or(Error::Parameter(ParameterError::LoginRequired))?;
+    let user = db
+        .try_get_user_by_email(email)?
+        .ok_or(Error::Parameter(ParameterError::LoginRequired))?;
+    if !user.email_confirmed {
+        return Err(Error::Parameter(ParameterError::EmailNotConfirmed));
+    }
+    if !db.is_user_active(email)? {
+        return Err(Error::Parameter(ParameterError::UserDeactivated));
+    }
+    Ok(user)
+}
+
 pub fn prepare_new_rating<D: Db>(db: &D, r: NewPlaceRating) -> Result<Storable> {
     if r.comment.is_empty() {
         return Err(Error::Parameter(ParameterError::EmptyComment));
@@ -33,15 +49,16 @@ pub fn prepare_new_rating<D: Db>(db: &D, r: NewPlaceRating) -> Result<Storable>
     if !r_value.is_valid() {
         return Err(Error::Parameter(ParameterError::RatingValue));
     }
-    let now = Timestamp::now();
+    let now = TimestampMs::now();
     let rating_id = Id::new();
     let comment_id = Id::new();
-    let (place, status) = db.get_place(&r.entry)?;
+    let (place, status) = db.get_place(&r.entry.as_str().into())?;
     debug_assert_eq!(place.id, r.entry.as_str().into());
     let rating = Rating {
         id: rating_id.clone(),
         place_id: r.entry.into(),
         created_at: now,
+        created_by: r.user,
         archived_at: None,
         title: r.title,
         value: r_value,
@@ -58,13 +75,37 @@ pub fn prepare_new_rating<D: Db>(db: &D, r: NewPlaceRating) -> Result<Storable>
     Ok(Storable(place, status, rating, comment))
 }
 
-pub fn store_new_rating<D: Db>(db: &D, s: Storable) -> Result<(Place, ReviewStatus, Vec<Rating>)> {
-    let Storable(place, status, rating, comment) = s;
-    debug_assert_eq!(place.id, rating.place_id);
-    debug_assert_eq!(rating.id, comment.rating_id);
-    db.create_rating(rating)?;
-    db.create_comment(comment)?;
-    let ratings = db.load_ratings_of_place(place.id.as_ref())?;
+/// Prepare multiple ratings of the same place in different
+/// contexts that are validated together.
+pub fn prepare_new_ratings<D: Db>(db: &D, ratings: Vec<NewPlaceRating>) -> Result<Vec<Storable>> {
+    let first = ratings
+        .first()
+        .ok_or(Error::Parameter(ParameterError::RatingBatch))?;
+    for (i, r) in ratings.iter().enumerate() {
+        if r.entry != first.entry || ratings[..i].iter().any(|r2| r2.context == r.context) {
+            return Err(Error::Parameter(ParameterError::RatingBatch));
+        }
+    }
+    ratings
+        .into_iter()
+        .map(|r| prepare_new_rating(db, r))
+        .collect()
+}
+
+pub fn store_new_ratings<D: Db>(
+    db: &D,
+    storables: Vec<Storable>,
+) -> Result<(Place, ReviewStatus, Vec<Rating>)> {
+    let mut place_with_status = None;
+    for Storable(place, status, rating, comment) in storables {
+        debug_assert_eq!(place.id, rating.place_id);
+        debug_assert_eq!(rating.id, comment.rating_id);
+        db.create_rating(rating)?;
+        db.create_comment(comment)?;
+        place_with_status = Some((place, status));
+    }
+    let (place, status) = place_with_status.ok_or(Error::Parameter(ParameterError::RatingBatch))?;
+    let ratings = db.load_ratings_of_place(&place.id.as_str().into())?;
     Ok((place, status, ratings))
 }
 
@@ -164,11 +205,123 @@ mod tests {
             },
         )
         .unwrap();
-        assert!(store_new_rating(&db, c).is_ok());
+        assert!(store_new_ratings(&db, vec![c]).is_ok());
 
         assert_eq!(db.ratings.borrow().len(), 1);
         assert_eq!(db.comments.borrow().len(), 1);
         assert_eq!(db.ratings.borrow()[0].place_id, "foo".into());
         assert_eq!(db.comments.borrow()[0].rating_id, db.ratings.borrow()[0].id);
     }
+
+    #[test]
+    fn authorize_rating_author_requires_login() {
+        let db = MockDb::default();
+        assert!(matches!(
+            authorize_rating_author(&db, None),
+            Err(Error::Parameter(ParameterError::LoginRequired))
+        ));
+        assert!(matches!(
+            authorize_rating_author(&db, Some("unknown@example.com")),
+            Err(Error::Parameter(ParameterError::LoginRequired))
+        ));
+    }
+
+    #[test]
+    fn authorize_rating_author_requires_confirmed_email() {
+        let db = MockDb::default();
+        db.users.borrow_mut().push(User {
+            email: "foo@bar.tld".into(),
+            email_confirmed: false,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::User,
+            language: None,
+        });
+        assert!(matches!(
+            authorize_rating_author(&db, Some("foo@bar.tld")),
+            Err(Error::Parameter(ParameterError::EmailNotConfirmed))
+        ));
+        db.users.borrow_mut()[0].email_confirmed = true;
+        assert!(authorize_rating_author(&db, Some("foo@bar.tld")).is_ok());
+    }
+
+    #[test]
+    fn authorize_rating_author_rejects_deactivated_users() {
+        let db = MockDb::default();
+        db.users.borrow_mut().push(User {
+            email: "foo@bar.tld".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::User,
+            language: None,
+        });
+        db.log_user_status(
+            "foo@bar.tld",
+            &UserStatusLog {
+                activity: ActivityLog {
+                    activity: Activity::now(None),
+                    context: None,
+                    comment: None,
+                },
+                active: false,
+            },
+        )
+        .unwrap();
+        assert!(matches!(
+            authorize_rating_author(&db, Some("foo@bar.tld")),
+            Err(Error::Parameter(ParameterError::UserDeactivated))
+        ));
+    }
+
+    #[test]
+    fn rate_multiple_contexts_of_the_same_place() {
+        let mut db = MockDb::default();
+        let p1 = Place::build().id("foo").finish();
+        let p2 = Place::build().id("bar").finish();
+        db.entries = vec![(p1, ReviewStatus::Created), (p2, ReviewStatus::Created)].into();
+        let new_rating = |entry: &str, context| NewPlaceRating {
+            entry: entry.into(),
+            comment: "comment".into(),
+            title: "title".into(),
+            context,
+            user: None,
+            value: ofdb_boundary::RatingValue::from(1),
+            source: None,
+        };
+        assert!(matches!(
+            prepare_new_ratings(&db, vec![]),
+            Err(Error::Parameter(ParameterError::RatingBatch))
+        ));
+        assert!(matches!(
+            prepare_new_ratings(
+                &db,
+                vec![
+                    new_rating("foo", ofdb_boundary::RatingContext::Fairness),
+                    new_rating("bar", ofdb_boundary::RatingContext::Humanity),
+                ]
+            ),
+            Err(Error::Parameter(ParameterError::RatingBatch))
+        ));
+        assert!(matches!(
+            prepare_new_ratings(
+                &db,
+                vec![
+                    new_rating("foo", ofdb_boundary::RatingContext::Fairness),
+                    new_rating("foo", ofdb_boundary::RatingContext::Fairness),
+                ]
+            ),
+            Err(Error::Parameter(ParameterError::RatingBatch))
+        ));
+        let storables = prepare_new_ratings(
+            &db,
+            vec![
+                new_rating("foo", ofdb_boundary::RatingContext::Fairness),
+                new_rating("foo", ofdb_boundary::RatingContext::Humanity),
+            ],
+        )
+        .unwrap();
+        let (place, _, ratings) = store_new_ratings(&db, storables).unwrap();
+        assert_eq!(place.id, "foo".into());
+        assert_eq!(2, ratings.len());
+        assert_eq!(2, db.comments.borrow().len());
+    }
 }
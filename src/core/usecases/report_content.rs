@@ -0,0 +1,289 @@
+use super::{authorize_user_by_email, email_addresses_by_coordinate, has_permission};
+use crate::core::{prelude::*, util::geo::MapPoint};
+
+const MAX_COMMENT_LEN: usize = 1000;
+
+#[derive(Debug, Clone)]
+pub struct NewReport {
+    pub content_kind: ContentKind,
+    pub content_id: String,
+    pub reason: ReportReason,
+    pub comment: Option<String>,
+    /// The email of the logged in user or `None` for anonymous visitors
+    pub reporter_email: Option<String>,
+}
+
+/// A new report together with a summary of the reported content.
+#[derive(Debug, Clone)]
+pub struct ReportedContent {
+    pub report: Report,
+    /// The title of a place or an event or the text of a comment
+    pub title: String,
+    /// Events without a location cannot be assigned to a region
+    pub pos: Option<MapPoint>,
+}
+
+fn load_reported_content<D: Db>(
+    db: &D,
+    content_kind: ContentKind,
+    content_id: &str,
+) -> Result<(Id, String, Option<MapPoint>)> {
+    match content_kind {
+        ContentKind::Place => {
+            let (place, status) = db.get_place(&content_id.into())?;
+            if !status.exists() {
+                return Err(RepoError::NotFound.into());
+            }
+            Ok((place.id, place.title, Some(place.location.pos)))
+        }
+        ContentKind::Event => {
+            let event = db.get_event(&content_id.into())?;
+            if event.archived.is_some() {
+                return Err(RepoError::NotFound.into());
+            }
+            Ok((event.id, event.title, event.location.map(|l| l.pos)))
+        }
+        ContentKind::Comment => {
+            let comment = db.load_comment(&content_id.into())?;
+            if comment.archived_at.is_some() {
+                return Err(RepoError::NotFound.into());
+            }
+            let rating = db.load_rating(&comment.rating_id.into())?;
+            let (place, _) = db.get_place(&rating.place_id.into())?;
+            Ok((comment.id, comment.text, Some(place.location.pos)))
+        }
+    }
+}
+
+/// Flag a place, an event or a comment for triage by scouts.
+pub fn report_content<D: Db>(db: &D, new_report: NewReport) -> Result<ReportedContent> {
+    let NewReport {
+        content_kind,
+        content_id,
+        reason,
+        comment,
+        reporter_email,
+    } = new_report;
+    let comment = comment
+        .map(|c| c.trim().to_owned())
+        .filter(|c| !c.is_empty());
+    if reason == ReportReason::Other && comment.is_none() {
+        return Err(Error::Parameter(ParameterError::EmptyComment));
+    }
+    if comment
+        .as_ref()
+        .map(|c| c.chars().count() > MAX_COMMENT_LEN)
+        .unwrap_or(false)
+    {
+        return Err(Error::Parameter(ParameterError::CommentTooLong));
+    }
+    let (content_id, title, pos) = load_reported_content(db, content_kind, &content_id)?;
+    let report = Report {
+        id: Id::new(),
+        content_kind,
+        content_id,
+        reason,
+        comment,
+        created: Activity::now(reporter_email.map(Into::into)),
+    };
+    db.create_report(report.clone())?;
+    Ok(ReportedContent { report, title, pos })
+}
+
+/// All users with a confirmed e-mail address who are allowed to
+/// moderate reports and who subscribed to a region that contains
+/// the given position.
+pub fn content_report_recipients<D: Db>(db: &D, pos: MapPoint) -> Result<Vec<String>> {
+    let mut recipients = vec![];
+    for email in email_addresses_by_coordinate(db, pos)? {
+        if recipients.contains(&email) {
+            continue;
+        }
+        let user = match db.try_get_user_by_email(&email)? {
+            Some(user) => user,
+            None => continue,
+        };
+        if user.email_confirmed && has_permission(db, &user, Permission::ModerateReports)? {
+            recipients.push(email);
+        }
+    }
+    Ok(recipients)
+}
+
+pub fn load_open_reports<D: Db>(db: &D, account_email: &str) -> Result<Vec<Report>> {
+    authorize_user_by_email(db, account_email, Permission::ModerateReports)?;
+    Ok(db.load_open_reports()?)
+}
+
+pub fn resolve_reports<D: Db>(db: &D, account_email: &str, ids: &[&str]) -> Result<usize> {
+    authorize_user_by_email(db, account_email, Permission::ModerateReports)?;
+    let activity = Activity::now(Some(account_email.into()));
+    let count = db.resolve_reports(ids, &activity)?;
+    db.resolve_moderation_assignments(ModerationItemKind::Report, ids, activity.at)?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+    use crate::core::util::geo::MapBbox;
+
+    fn new_report(content_kind: ContentKind, content_id: &str, reason: ReportReason) -> NewReport {
+        NewReport {
+            content_kind,
+            content_id: content_id.into(),
+            reason,
+            comment: None,
+            reporter_email: None,
+        }
+    }
+
+    #[test]
+    fn report_places_and_comments() {
+        let db = MockDb::default();
+        db.entries.borrow_mut().push((
+            Place::build()
+                .id("foo")
+                .title("Foo")
+                .pos(MapPoint::from_lat_lng_deg(48.0, 8.0))
+                .finish(),
+            ReviewStatus::Created,
+        ));
+        db.entries
+            .borrow_mut()
+            .push((Place::build().id("bar").finish(), ReviewStatus::Archived));
+        db.create_rating(Rating {
+            id: "r".into(),
+            place_id: "foo".into(),
+            created_at: TimestampMs::now(),
+            created_by: None,
+            archived_at: None,
+            title: "title".into(),
+            value: RatingValue::new(1),
+            context: RatingContext::Fairness,
+            source: None,
+        })
+        .unwrap();
+        db.create_comment(Comment {
+            id: "c".into(),
+            rating_id: "r".into(),
+            created_at: TimestampMs::now(),
+            archived_at: None,
+            text: "Buy cheap watches".into(),
+        })
+        .unwrap();
+
+        let reported = report_content(
+            &db,
+            new_report(ContentKind::Place, "foo", ReportReason::Offensive),
+        )
+        .unwrap();
+        assert_eq!("Foo", reported.title);
+        assert_eq!(Some(MapPoint::from_lat_lng_deg(48.0, 8.0)), reported.pos);
+
+        let reported = report_content(
+            &db,
+            new_report(ContentKind::Comment, "c", ReportReason::Spam),
+        )
+        .unwrap();
+        assert_eq!("Buy cheap watches", reported.title);
+        assert_eq!(Some(MapPoint::from_lat_lng_deg(48.0, 8.0)), reported.pos);
+        assert_eq!(2, db.load_open_reports().unwrap().len());
+
+        assert!(report_content(
+            &db,
+            new_report(ContentKind::Place, "bar", ReportReason::Spam)
+        )
+        .is_err());
+        assert!(report_content(
+            &db,
+            new_report(ContentKind::Event, "foo", ReportReason::Spam)
+        )
+        .is_err());
+        assert!(matches!(
+            report_content(
+                &db,
+                new_report(ContentKind::Place, "foo", ReportReason::Other)
+            ),
+            Err(Error::Parameter(ParameterError::EmptyComment))
+        ));
+        assert_eq!(2, db.load_open_reports().unwrap().len());
+    }
+
+    #[test]
+    fn report_with_invalid_parameters() {
+        let db = MockDb::default();
+        db.entries
+            .borrow_mut()
+            .push((Place::build().id("foo").finish(), ReviewStatus::Created));
+        let report = report_content(
+            &db,
+            NewReport {
+                comment: Some(" ".into()),
+                ..new_report(ContentKind::Place, "foo", ReportReason::Closed)
+            },
+        )
+        .unwrap()
+        .report;
+        assert_eq!(None, report.comment);
+        let long_comment = "x".repeat(MAX_COMMENT_LEN + 1);
+        assert!(matches!(
+            report_content(
+                &db,
+                NewReport {
+                    comment: Some(long_comment),
+                    ..new_report(ContentKind::Place, "foo", ReportReason::Fraud)
+                }
+            ),
+            Err(Error::Parameter(ParameterError::CommentTooLong))
+        ));
+        assert!(report_content(
+            &db,
+            new_report(ContentKind::Place, "baz", ReportReason::Closed)
+        )
+        .is_err());
+        assert_eq!(vec![report], db.load_open_reports().unwrap());
+    }
+
+    #[test]
+    fn only_moderators_in_the_region_receive_reports() {
+        let db = MockDb::default();
+        for (email, role) in &[
+            ("scout@example.com", Role::Scout),
+            ("user@example.com", Role::User),
+            ("far@example.com", Role::Scout),
+        ] {
+            db.create_user(&User {
+                email: (*email).into(),
+                email_confirmed: true,
+                password: "secret".parse::<Password>().unwrap(),
+                role: *role,
+                language: None,
+            })
+            .unwrap();
+        }
+        let bbox = |lat: f64| {
+            MapBbox::new(
+                MapPoint::from_lat_lng_deg(lat - 1.0, 7.0),
+                MapPoint::from_lat_lng_deg(lat + 1.0, 9.0),
+            )
+        };
+        for (email, lat) in &[
+            ("scout@example.com", 48.0),
+            ("user@example.com", 48.0),
+            ("far@example.com", 52.0),
+        ] {
+            db.create_bbox_subscription(&BboxSubscription {
+                id: Id::new(),
+                user_email: (*email).into(),
+                bbox: bbox(*lat),
+            })
+            .unwrap();
+        }
+        assert_eq!(
+            vec!["scout@example.com"],
+            content_report_recipients(&db, MapPoint::from_lat_lng_deg(48.0, 8.0)).unwrap()
+        );
+    }
+}
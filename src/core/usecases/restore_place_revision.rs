@@ -0,0 +1,92 @@
+use super::authorize_user_by_email;
+use crate::core::prelude::*;
+
+/// Restore an earlier revision of a place, e.g. to roll back
+/// vandalism.
+///
+/// The contents of the selected revision are copied into a new
+/// revision that is confirmed by the account immediately. Only
+/// scouts and admins are entitled to restore places.
+pub fn restore_place_revision<D: Db>(
+    db: &D,
+    account_email: &str,
+    id: &PlaceId,
+    revision: Revision,
+) -> Result<Place> {
+    authorize_user_by_email(db, account_email, Permission::ReviewPlaces)?;
+    let activity = Activity::now(Some(account_email.into()));
+    let restored_place = copy_place_revision(db, id, revision, &activity)?;
+    let activity_log = ActivityLog {
+        activity,
+        context: None,
+        comment: Some(format!("Restored revision {}", u64::from(revision))),
+    };
+    db.review_places(
+        &[restored_place.id.as_str()],
+        ReviewStatus::Confirmed,
+        &activity_log,
+    )?;
+    Ok(restored_place)
+}
+
+// Copies the contents of an earlier revision into a new
+// revision without reviewing it.
+pub(super) fn copy_place_revision<D: Db>(
+    db: &D,
+    id: &PlaceId,
+    revision: Revision,
+    activity: &Activity,
+) -> Result<Place> {
+    let PlaceHistory { place, revisions } = db.get_place_history(id)?;
+    let current_revision = revisions
+        .iter()
+        .map(|(place_revision, _)| place_revision.revision)
+        .max()
+        .ok_or(RepoError::NotFound)?;
+    if revision == current_revision {
+        return Err(ParameterError::CurrentRevision.into());
+    }
+    let PlaceRevision {
+        title,
+        description,
+        location,
+        additional_locations,
+        contact,
+        opening_hours,
+        links,
+        tags,
+        source,
+        ..
+    } = revisions
+        .into_iter()
+        .map(|(place_revision, _)| place_revision)
+        .find(|place_revision| place_revision.revision == revision)
+        .ok_or(RepoError::NotFound)?;
+    let PlaceRoot { id, license } = place;
+    let restored_place = Place {
+        id,
+        license,
+        revision: current_revision.next(),
+        created: activity.clone(),
+        title,
+        description,
+        location,
+        additional_locations,
+        contact,
+        opening_hours,
+        links,
+        tags,
+        source,
+    };
+    info!(
+        "Restoring revision {} of place {} as revision {}",
+        u64::from(revision),
+        restored_place.id,
+        u64::from(restored_place.revision)
+    );
+    for t in &restored_place.tags {
+        db.create_tag_if_it_does_not_exist(&Tag { id: t.clone() })?;
+    }
+    db.create_or_update_place(restored_place.clone())?;
+    Ok(restored_place)
+}
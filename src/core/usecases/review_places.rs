@@ -1,3 +1,4 @@
+use super::authorize_user_by_email;
 use crate::core::prelude::*;
 
 pub struct Review {
@@ -27,6 +28,7 @@ pub fn review_places<D: Db>(db: &D, ids: &[&str], review: Review) -> Result<usiz
         comment,
     };
     let place_count = db.review_places(ids, status, &activity_log)?;
+    db.resolve_moderation_assignments(ModerationItemKind::Place, ids, activity_log.activity.at)?;
     info!(
         "Changed review status of {} places to {}",
         place_count,
@@ -34,3 +36,14 @@ pub fn review_places<D: Db>(db: &D, ids: &[&str], review: Review) -> Result<usiz
     );
     Ok(place_count)
 }
+
+/// Load the queue of new places that still need to be
+/// reviewed by scouts, starting with the oldest place.
+pub fn load_places_pending_review<D: Db>(
+    db: &D,
+    account_email: &str,
+    pagination: &Pagination,
+) -> Result<Vec<Place>> {
+    authorize_user_by_email(db, account_email, Permission::ReviewPlaces)?;
+    Ok(db.list_places_pending_review(pagination)?)
+}
@@ -0,0 +1,114 @@
+use super::SubscriptionQuota;
+use crate::core::prelude::*;
+
+const MAX_QUERY_LEN: usize = 2000;
+
+#[derive(Debug, Clone)]
+pub struct NewSavedSearch {
+    pub title: String,
+    pub query: String,
+}
+
+pub fn save_search<D: Db>(
+    db: &D,
+    user_email: &str,
+    new_search: NewSavedSearch,
+    quota: SubscriptionQuota,
+) -> Result<SavedSearch> {
+    let NewSavedSearch { title, query } = new_search;
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(ParameterError::Title.into());
+    }
+    let query = query.trim().trim_start_matches('?');
+    if query.is_empty() || query.len() > MAX_QUERY_LEN {
+        return Err(ParameterError::InvalidSearchQuery.into());
+    }
+    if db.all_saved_searches_by_email(user_email)?.len() >= quota.max_saved_searches {
+        return Err(ParameterError::SubscriptionQuotaExceeded.into());
+    }
+    let search = SavedSearch {
+        id: Id::new(),
+        user_email: user_email.into(),
+        created_at: TimestampMs::now(),
+        title: title.into(),
+        query: query.into(),
+    };
+    db.create_saved_search(&search)?;
+    Ok(search)
+}
+
+pub fn get_saved_searches<D: Db>(db: &D, user_email: &str) -> Result<Vec<SavedSearch>> {
+    Ok(db.all_saved_searches_by_email(user_email)?)
+}
+
+pub fn delete_saved_search<D: Db>(db: &D, user_email: &str, id: &str) -> Result<()> {
+    Ok(db.delete_saved_search(user_email, id)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn new_search(title: &str, query: &str) -> NewSavedSearch {
+        NewSavedSearch {
+            title: title.into(),
+            query: query.into(),
+        }
+    }
+
+    #[test]
+    fn save_searches_within_quota() {
+        let db = MockDb::default();
+        let quota = SubscriptionQuota {
+            max_saved_searches: 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            save_search(&db, "foo@bar.tld", new_search(" ", "text=organic"), quota),
+            Err(Error::Parameter(ParameterError::Title))
+        ));
+        assert!(matches!(
+            save_search(&db, "foo@bar.tld", new_search("Organic", " ? "), quota),
+            Err(Error::Parameter(ParameterError::InvalidSearchQuery))
+        ));
+        let search = save_search(
+            &db,
+            "foo@bar.tld",
+            new_search(" Organic ", "?text=organic"),
+            quota,
+        )
+        .unwrap();
+        assert_eq!("Organic", search.title);
+        assert_eq!("text=organic", search.query);
+        assert!(matches!(
+            save_search(
+                &db,
+                "foo@bar.tld",
+                new_search("Bakery", "tags=bakery"),
+                quota
+            ),
+            Err(Error::Parameter(ParameterError::SubscriptionQuotaExceeded))
+        ));
+        // The quota applies per user
+        save_search(
+            &db,
+            "baz@bar.tld",
+            new_search("Bakery", "tags=bakery"),
+            quota,
+        )
+        .unwrap();
+
+        assert!(delete_saved_search(&db, "baz@bar.tld", search.id.as_str()).is_err());
+        delete_saved_search(&db, "foo@bar.tld", search.id.as_str()).unwrap();
+        assert!(get_saved_searches(&db, "foo@bar.tld").unwrap().is_empty());
+        save_search(
+            &db,
+            "foo@bar.tld",
+            new_search("Bakery", "tags=bakery"),
+            quota,
+        )
+        .unwrap();
+    }
+}
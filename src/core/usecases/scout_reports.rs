@@ -0,0 +1,168 @@
+use super::has_permission;
+use crate::core::prelude::*;
+
+use std::collections::BTreeSet;
+
+// Places that have not been changed for a year are considered stale
+const STALE_PLACE_AGE_SECONDS: i64 = 365 * 24 * 60 * 60;
+
+// Keep the e-mails short
+const MAX_ITEMS_PER_SECTION: usize = 25;
+
+const MAX_RECENTLY_CHANGED_PLACES: u64 = 1000;
+
+/// All scouts that receive periodic reports about their region,
+/// i.e. active users with a confirmed e-mail address who are
+/// allowed to review places and didn't opt out.
+pub fn scout_report_recipients<D: Db>(db: &D) -> Result<Vec<User>> {
+    let mut recipients = vec![];
+    for user in db.all_users()? {
+        if !user.email_confirmed
+            || !has_permission(db, &user, Permission::ReviewPlaces)?
+            || !db.is_user_active(&user.email)?
+            || db.is_scout_report_opted_out(&user.email)?
+        {
+            continue;
+        }
+        recipients.push(user);
+    }
+    Ok(recipients)
+}
+
+/// Set whether the user receives periodic reports about their region.
+pub fn enable_scout_reports<D: Db>(db: &D, email: &str, enabled: bool) -> Result<()> {
+    Ok(db.set_scout_report_opt_out(email, !enabled)?)
+}
+
+pub fn scout_reports_enabled<D: Db>(db: &D, email: &str) -> Result<bool> {
+    Ok(!db.is_scout_report_opted_out(email)?)
+}
+
+fn in_region(region: &[MapBbox], pos: MapPoint) -> bool {
+    region.iter().any(|bbox| bbox.contains_point(pos))
+}
+
+fn push_item(items: &mut Vec<ScoutReportItem>, place_id: Id, title: String) {
+    if items.len() < MAX_ITEMS_PER_SECTION && !items.iter().any(|item| item.place_id == place_id) {
+        items.push(ScoutReportItem { place_id, title });
+    }
+}
+
+/// Summarize all places within the region of a scout, i.e. the
+/// bounding boxes the scout has subscribed to, that have been
+/// created since the given time, that have been reported by
+/// visitors, or that have not been changed for a long time.
+///
+/// Returns `None` for scouts without a region.
+pub fn create_scout_report<D: Db>(
+    db: &D,
+    index: &dyn PlaceIndex,
+    email: &str,
+    since: TimestampMs,
+) -> Result<Option<ScoutReport>> {
+    let region: Vec<_> = db
+        .all_bbox_subscriptions_by_email(email)?
+        .into_iter()
+        .map(|s| s.bbox)
+        .collect();
+    if region.is_empty() {
+        return Ok(None);
+    }
+
+    let mut new_places = vec![];
+    let recently_changed_places = db.recently_changed_places(
+        &RecentlyChangedEntriesParams {
+            since: Some(since),
+            ..Default::default()
+        },
+        &Pagination {
+            offset: None,
+            limit: Some(MAX_RECENTLY_CHANGED_PLACES),
+        },
+    )?;
+    for (place, status, _, _) in recently_changed_places {
+        if place.revision == Revision::initial()
+            && status.exists()
+            && in_region(&region, place.location.pos)
+        {
+            push_item(&mut new_places, place.id, place.title);
+        }
+    }
+
+    let mut reported_places = vec![];
+    let reported_place_ids: BTreeSet<_> = db
+        .load_open_reports()?
+        .into_iter()
+        .filter(|r| r.content_kind == ContentKind::Place)
+        .map(|r| r.content_id)
+        .collect();
+    let reported_place_ids: Vec<_> = reported_place_ids.iter().map(Id::as_str).collect();
+    for (place, _) in db.get_places(&reported_place_ids)? {
+        if in_region(&region, place.location.pos) {
+            push_item(&mut reported_places, place.id, place.title);
+        }
+    }
+
+    let mut stale_places = vec![];
+    let stale_until =
+        TimestampMs::from_seconds(TimestampMs::now().into_seconds() - STALE_PLACE_AGE_SECONDS);
+    for bbox in &region {
+        let query = IndexQuery {
+            include_bbox: Some(*bbox),
+            status: Some(vec![]),
+            updated_until: Some(stale_until),
+            ..Default::default()
+        };
+        let places = index
+            .query_places(&query, MAX_ITEMS_PER_SECTION)
+            .map_err(RepoError::Other)?;
+        for place in places {
+            push_item(&mut stale_places, place.id.into(), place.title);
+        }
+    }
+
+    Ok(Some(ScoutReport {
+        since,
+        new_places,
+        reported_places,
+        stale_places,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn add_user(db: &MockDb, email: &str, role: Role, email_confirmed: bool) {
+        db.users.borrow_mut().push(User {
+            email: email.into(),
+            email_confirmed,
+            password: "secret".parse::<Password>().unwrap(),
+            role,
+            language: None,
+        });
+    }
+
+    #[test]
+    fn only_confirmed_scouts_without_opt_out_receive_reports() {
+        let db = MockDb::default();
+        add_user(&db, "guest@example.com", Role::Guest, true);
+        add_user(&db, "user@example.com", Role::User, true);
+        add_user(&db, "scout@example.com", Role::Scout, true);
+        add_user(&db, "unconfirmed@example.com", Role::Scout, false);
+        add_user(&db, "opt-out@example.com", Role::Scout, true);
+        add_user(&db, "admin@example.com", Role::Admin, true);
+        enable_scout_reports(&db, "opt-out@example.com", false).unwrap();
+        assert!(!scout_reports_enabled(&db, "opt-out@example.com").unwrap());
+        assert!(scout_reports_enabled(&db, "scout@example.com").unwrap());
+        let recipients: Vec<_> = scout_report_recipients(&db)
+            .unwrap()
+            .into_iter()
+            .map(|u| u.email)
+            .collect();
+        assert_eq!(vec!["scout@example.com", "admin@example.com"], recipients);
+        enable_scout_reports(&db, "opt-out@example.com", true).unwrap();
+        assert_eq!(3, scout_report_recipients(&db).unwrap().len());
+    }
+}
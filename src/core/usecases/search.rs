@@ -1,32 +1,78 @@
 use crate::core::{prelude::*, util};
 use ofdb_core::util::filter;
-use ofdb_entities::geo::MapBbox;
+use ofdb_entities::geo::{Distance, MapBbox, MapPoint};
 
 #[rustfmt::skip]
 #[derive(Debug, Clone)]
 pub struct SearchRequest<'a, 'b, 'c, 'd> {
-    pub bbox       : MapBbox,
-    pub ids        : Vec<&'b str>,
-    pub categories : Vec<&'a str>,
-    pub hash_tags  : Vec<&'c str>,
-    pub text       : Option<&'d str>,
-    pub status     : Vec<ReviewStatus>,
+    pub bbox             : MapBbox,
+    // Optional circle that further restricts the results
+    pub center           : Option<MapPoint>,
+    pub radius           : Option<Distance>,
+    pub ids              : Vec<&'b str>,
+    pub categories       : Vec<&'a str>,
+    pub hash_tags        : Vec<&'c str>,
+    // Only places with at least one of these tags
+    pub any_hash_tags    : Vec<&'c str>,
+    // Only places with none of these tags
+    pub exclude_hash_tags: Vec<&'c str>,
+    // Only places with a tag owned by this organization
+    pub org_id           : Option<&'d str>,
+    pub text             : Option<&'d str>,
+    // Tolerate typos in the text up to this edit distance
+    pub fuzzy_distance   : Option<u8>,
+    pub status           : Vec<ReviewStatus>,
+    // Only places that have been changed within this time range
+    pub updated_since    : Option<TimestampMs>,
+    pub updated_until    : Option<TimestampMs>,
+    pub sort_by          : IndexSortOrder,
+    // Prefer more complete places
+    pub boost_quality    : bool,
+    // Hide poorly rated places
+    pub min_total_rating : Option<AvgRatingValue>,
+    pub min_ratings      : Vec<(RatingContext, AvgRatingValue)>,
+    // Pagination of the visible results
+    pub offset           : usize,
+    pub after            : Option<IndexCursor>,
 }
 
-pub fn search(
-    index: &dyn PlaceIndex,
-    req: SearchRequest,
-    limit: usize,
-) -> Result<(Vec<IndexedPlace>, Vec<IndexedPlace>)> {
+// The query for all places within the visible bbox
+fn visible_places_query<'a, 'b>(req: SearchRequest<'a, 'b, '_, '_>) -> IndexQuery<'a, 'b> {
     let SearchRequest {
         bbox: visible_bbox,
+        center,
+        radius,
         ids,
         categories,
         hash_tags: req_hash_tags,
+        any_hash_tags,
+        exclude_hash_tags: req_exclude_hash_tags,
+        org_id,
         text,
+        fuzzy_distance,
         status,
+        updated_since,
+        updated_until,
+        sort_by,
+        boost_quality,
+        min_total_rating,
+        min_ratings,
+        offset,
+        after,
     } = req;
 
+    // Excluded hash tags like `-#tag` need to be extracted
+    // from the text before all other hash tags
+    let mut exclude_hash_tags = text
+        .map(util::extract_excluded_hash_tags)
+        .unwrap_or_default();
+    exclude_hash_tags.reserve(req_exclude_hash_tags.len());
+    for hash_tag in req_exclude_hash_tags {
+        exclude_hash_tags.push(hash_tag.to_owned());
+    }
+    let text = text.map(util::remove_excluded_hash_tags);
+    let text = text.as_deref();
+
     let mut hash_tags = text.map(util::extract_hash_tags).unwrap_or_default();
     hash_tags.reserve(req_hash_tags.len());
     for hash_tag in req_hash_tags {
@@ -46,17 +92,63 @@ pub fn search(
         .map(filter::split_text_to_words)
         .unwrap_or_default();
 
-    let visible_places_query = IndexQuery {
+    // Sort by the distance from the center of the visible
+    // area if no explicit center has been requested
+    let center = match sort_by {
+        IndexSortOrder::Distance => center.or_else(|| Some(visible_bbox.center())),
+        _ => center,
+    };
+
+    IndexQuery {
         include_bbox: Some(visible_bbox),
         exclude_bbox: None,
+        center,
+        radius_meters: radius.map(Distance::to_meters),
         categories,
         ids,
         hash_tags,
+        any_hash_tags: any_hash_tags.into_iter().map(ToOwned::to_owned).collect(),
+        exclude_hash_tags,
+        org_id: org_id.map(ToOwned::to_owned),
         text_tags,
         text,
+        fuzzy_distance,
         status: Some(status),
+        updated_since,
+        updated_until,
+        sort_by,
+        boost_quality,
+        min_total_rating,
+        min_ratings,
+        offset,
+        after,
         ..Default::default()
-    };
+    }
+}
+
+/// Rejected and archived places remain in the search index
+/// together with their review status. Only users who are
+/// allowed to review places may explicitly search for them.
+pub fn authorize_search_by_review_status<D: Db>(
+    db: &D,
+    account_email: Option<&str>,
+    status: &[ReviewStatus],
+) -> Result<()> {
+    if status.iter().all(|s| s.exists()) {
+        return Ok(());
+    }
+    let account_email = account_email.ok_or(ParameterError::Unauthorized)?;
+    super::authorize_user_by_email(db, account_email, Permission::ReviewPlaces)?;
+    Ok(())
+}
+
+pub fn search(
+    index: &dyn PlaceIndex,
+    req: SearchRequest,
+    limit: usize,
+) -> Result<(Vec<IndexedPlace>, Vec<IndexedPlace>)> {
+    let visible_bbox = req.bbox;
+    let visible_places_query = visible_places_query(req);
 
     // 1st query: Search for visible results only
     // This is required to reliably retrieve all available results!
@@ -66,13 +158,17 @@ pub fn search(
         .map_err(RepoError::Other)?;
     debug_assert!(visible_places
         .iter()
-        .all(|e| visible_bbox.contains_point(e.pos)));
+        .all(|e| e.positions().any(|pos| visible_bbox.contains_point(pos))));
 
     // 2nd query: Search for remaining invisible results
     let invisible_places = if visible_places.len() < limit {
         let invisible_places_query = IndexQuery {
             include_bbox: Some(filter::extend_bbox(&visible_bbox)),
             exclude_bbox: visible_places_query.include_bbox,
+            // Only the last page of visible results is supplemented
+            // by invisible results
+            offset: 0,
+            after: None,
             ..visible_places_query
         };
         index
@@ -83,11 +179,35 @@ pub fn search(
     };
     debug_assert!(!invisible_places
         .iter()
-        .any(|e| visible_bbox.contains_point(e.pos)));
+        .any(|e| e.positions().any(|pos| visible_bbox.contains_point(pos))));
 
     Ok((visible_places, invisible_places))
 }
 
+// Each map tile of 256 x 256 pixels is divided into
+// 4 x 4 grid cells, i.e. clusters are 64 pixels apart
+const CLUSTER_CELLS_PER_TILE: f64 = 4.0;
+
+pub const MAX_CLUSTER_ZOOM: u8 = 22;
+
+/// Aggregate all places within the visible bbox into clusters
+/// that depend on the zoom level of the map, e.g. for rendering
+/// thousands of places without loading each of them.
+pub fn search_clusters(
+    index: &dyn PlaceClusterIndex,
+    req: SearchRequest,
+    zoom: u8,
+) -> Result<Vec<PlaceCluster>> {
+    if zoom > MAX_CLUSTER_ZOOM {
+        return Err(ParameterError::InvalidZoom.into());
+    }
+    let tile_size_deg = 360.0 / f64::from(1u32 << zoom);
+    let query = visible_places_query(req);
+    Ok(index
+        .cluster_places(&query, tile_size_deg / CLUSTER_CELLS_PER_TILE)
+        .map_err(RepoError::Other)?)
+}
+
 /// The global search usecase is like the one
 /// of usual internet search engines that exists
 /// of only one single search input.
@@ -96,6 +216,9 @@ pub fn search(
 pub fn global_search(index: &dyn PlaceIndex, txt: &str, limit: usize) -> Result<Vec<IndexedPlace>> {
     let index_query = IndexQuery {
         text: Some(txt.into()),
+        // Only visible places, i.e. neither archived places
+        // nor events that don't have a review status
+        status: Some(vec![]),
         ..Default::default()
     };
 
@@ -105,3 +228,144 @@ pub fn global_search(index: &dyn PlaceIndex, txt: &str, limit: usize) -> Result<
 
     Ok(entries)
 }
+
+#[derive(Debug, Default)]
+pub struct Suggestions {
+    pub tags: Vec<String>,
+    pub titles: Vec<String>,
+}
+
+/// Completions of a prefix for type-ahead search boxes. Tags
+/// are ranked by their frequency and titles by the rating of
+/// the corresponding places.
+pub fn suggest<I>(index: &I, prefix: &str, limit: usize) -> Result<Suggestions>
+where
+    I: PlaceIndex + TagIndex,
+{
+    if prefix.trim().is_empty() {
+        return Ok(Default::default());
+    }
+    let tags = index
+        .suggest_tags(prefix, limit)
+        .map_err(RepoError::Other)?
+        .into_iter()
+        .map(|TagFrequency(tag, _)| tag)
+        .collect();
+    let title_query = IndexQuery {
+        title_prefix: Some(prefix.into()),
+        status: Some(vec![]),
+        sort_by: IndexSortOrder::Rating,
+        ..Default::default()
+    };
+    let places = index
+        .query_places(&title_query, limit)
+        .map_err(RepoError::Other)?;
+    let mut titles: Vec<String> = Vec::with_capacity(places.len());
+    for place in places {
+        // Different places might share the same title
+        if !titles.contains(&place.title) {
+            titles.push(place.title);
+        }
+    }
+    Ok(Suggestions { tags, titles })
+}
+
+/// Find tags of visible places that contain any of the
+/// words of the text. Exact matches are preferred over
+/// shorter and then longer tags.
+pub fn search_tags(index: &dyn TagIndex, txt: &str, limit: usize) -> Result<Vec<String>> {
+    let words: Vec<_> = filter::split_text_to_words(txt)
+        .into_iter()
+        .filter(|word| !word.is_empty())
+        .collect();
+    if words.is_empty() {
+        return Ok(vec![]);
+    }
+    let params = MostPopularTagsParams {
+        min_count: None,
+        max_count: None,
+    };
+    let mut tags: Vec<_> = index
+        .most_popular_tags(&params, &Default::default())
+        .map_err(RepoError::Other)?
+        .into_iter()
+        .map(|TagFrequency(tag, _)| tag)
+        .filter(|tag| words.iter().any(|word| tag.contains(word.as_str())))
+        .collect();
+    tags.sort_by_cached_key(|tag| (!words.contains(tag), tag.len(), tag.clone()));
+    tags.truncate(limit);
+    Ok(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    use anyhow::Result as Fallible;
+
+    struct TagIndexStub(Vec<&'static str>);
+
+    impl TagIndex for TagIndexStub {
+        fn suggest_tags(&self, _prefix: &str, _limit: usize) -> Fallible<Vec<TagFrequency>> {
+            unimplemented!();
+        }
+
+        fn most_popular_tags(
+            &self,
+            _params: &MostPopularTagsParams,
+            _pagination: &Pagination,
+        ) -> Fallible<Vec<TagFrequency>> {
+            Ok(self
+                .0
+                .iter()
+                .map(|tag| TagFrequency((*tag).to_string(), 1))
+                .collect())
+        }
+    }
+
+    #[test]
+    fn search_tags_by_words() {
+        let index = TagIndexStub(vec!["organic", "organic-food", "bio", "food", "fair"]);
+        assert_eq!(
+            vec!["food", "organic", "organic-food"],
+            search_tags(&index, "Organic Food", 10).unwrap()
+        );
+        assert_eq!(
+            vec!["food"],
+            search_tags(&index, "organic food", 1).unwrap()
+        );
+        assert!(search_tags(&index, " ", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn search_invisible_places_only_as_moderator() {
+        let db = MockDb::default();
+        for (email, role) in &[
+            ("user@example.com", Role::User),
+            ("scout@example.com", Role::Scout),
+        ] {
+            db.create_user(&User {
+                email: (*email).into(),
+                email_confirmed: true,
+                password: "secret".parse::<Password>().unwrap(),
+                role: *role,
+                language: None,
+            })
+            .unwrap();
+        }
+        let visible = [ReviewStatus::Created, ReviewStatus::Confirmed];
+        let all = [ReviewStatus::Confirmed, ReviewStatus::Rejected];
+        assert!(authorize_search_by_review_status(&db, None, &[]).is_ok());
+        assert!(authorize_search_by_review_status(&db, None, &visible).is_ok());
+        assert!(matches!(
+            authorize_search_by_review_status(&db, None, &all),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+        assert!(matches!(
+            authorize_search_by_review_status(&db, Some("user@example.com"), &all),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+        assert!(authorize_search_by_review_status(&db, Some("scout@example.com"), &all).is_ok());
+    }
+}
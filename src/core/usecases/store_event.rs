@@ -32,6 +32,36 @@ pub struct NewEvent {
     pub organizer    : Option<String>,
     pub image_url     : Option<String>,
     pub image_link_url: Option<String>,
+    pub recurrence    : Option<NewEventRecurrence>,
+    pub capacity      : Option<u32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NewEventRecurrence {
+    /// Either "weekly" or "monthly"
+    pub frequency: String,
+    /// Defaults to 1, i.e. every week or month
+    pub interval: Option<u16>,
+    /// The last possible start of an occurrence
+    pub until: Option<i64>,
+}
+
+fn import_recurrence(r: NewEventRecurrence) -> Result<Recurrence> {
+    let NewEventRecurrence {
+        frequency,
+        interval,
+        until,
+    } = r;
+    let frequency = match &*frequency.trim().to_lowercase() {
+        "weekly" => RecurrenceFrequency::Weekly,
+        "monthly" => RecurrenceFrequency::Monthly,
+        _ => return Err(ParameterError::InvalidRecurrence.into()),
+    };
+    Ok(Recurrence {
+        frequency,
+        interval: interval.unwrap_or(1),
+        until: until.map(|x| NaiveDateTime::from_timestamp(x, 0)),
+    })
 }
 
 pub enum NewEventMode<'a> {
@@ -69,22 +99,33 @@ pub fn import_new_event<D: Db>(
         homepage,
         image_url,
         image_link_url,
+        recurrence,
+        capacity,
         ..
     } = e;
     let org = token
         .map(|t| {
-            db.get_org_by_api_token(t).map_err(|e| {
+            super::authorize_organization_by_token(db, t, ApiTokenScope::WriteEvents).map_err(|e| {
                 log::warn!("Unknown or invalid API token: {}", t);
-                match e {
-                    RepoError::NotFound => Error::Parameter(ParameterError::Unauthorized),
-                    _ => Error::Repo(e),
-                }
+                e
             })
         })
         .transpose()?;
     let mut tags =
         super::prepare_tag_list(tags.unwrap_or_else(Vec::new).iter().map(String::as_str));
-    if super::check_and_count_owned_tags(db, &tags, org.as_ref())? == 0 {
+    let owned_count = super::check_and_count_owned_tags(db, &tags, org.as_ref())?;
+    let old_tags = match mode {
+        NewEventMode::Create => vec![],
+        NewEventMode::Update(id) => {
+            let old_tags = db.get_event(&id.into())?.tags;
+            // Verify that the org is entitled to update this event according to
+            // the owned tags. This is required independent of the new tags to
+            // prevent that an org captures events that are owned by other orgs.
+            super::check_and_count_owned_tags(db, &old_tags, org.as_ref())?;
+            old_tags
+        }
+    };
+    if owned_count == 0 {
         if let Some(mut org) = org {
             // Implicitly add missing owned tags to prevent events with
             // undefined ownership!
@@ -106,11 +147,12 @@ pub fn import_new_event<D: Db>(
                         tags.append(&mut org.owned_tags);
                     }
                 }
-                NewEventMode::Update(id) => {
+                NewEventMode::Update(_) => {
                     // Keep all existing tags owned by the authorized org
-                    let old_tags = db.get_event(id)?.tags;
-                    // Verify that the org is entitled to update this event according to the owned tags
-                    let owned_count = super::check_and_count_owned_tags(db, &old_tags, Some(&org))?;
+                    let owned_count = old_tags
+                        .iter()
+                        .filter(|t| org.owned_tags.iter().any(|x| x == *t))
+                        .count();
                     if owned_count > 0 {
                         debug_assert!(owned_count <= org.owned_tags.len());
                         tags.reserve(owned_count);
@@ -235,6 +277,7 @@ pub fn import_new_event<D: Db>(
     let image_link_url = image_link_url
         .and_then(|ref url| parse_url_param(url).transpose())
         .transpose()?;
+    let recurrence = recurrence.map(import_recurrence).transpose()?;
 
     let event = Event {
         id,
@@ -252,6 +295,8 @@ pub fn import_new_event<D: Db>(
         archived: None,
         image_url,
         image_link_url,
+        recurrence,
+        capacity,
     };
     let event = event.auto_correct();
     event.validate()?;
@@ -281,7 +326,7 @@ pub fn store_updated_event<D: Db>(db: &D, storable: Storable) -> Result<Event> {
 #[cfg(test)]
 mod tests {
 
-    use super::super::tests::MockDb;
+    use super::super::tests::{create_org_with_api_token, MockDb};
     use super::*;
 
     fn create_new_event<D: Db>(db: &D, token: Option<&str>, e: NewEvent) -> Result<Event> {
@@ -314,6 +359,8 @@ mod tests {
             organizer    : None,
             image_url     : Some("http://somewhere.com/image_url.jpg".to_string()),
             image_link_url: Some("my.url/test.ext".to_string()),
+            recurrence    : None,
+            capacity      : None,
         };
         let mock_db = MockDb::default();
         let id = create_new_event(&mock_db, None, x).unwrap().id;
@@ -361,6 +408,8 @@ mod tests {
             organizer    : None,
             image_url     : None,
             image_link_url: None,
+            recurrence    : None,
+            capacity      : None,
         };
         let mock_db: MockDb = MockDb::default();
         assert!(create_new_event(&mock_db, None, x).is_err());
@@ -390,6 +439,8 @@ mod tests {
             organizer    : None,
             image_url     : None,
             image_link_url: None,
+            recurrence    : None,
+            capacity      : None,
         };
         let mock_db: MockDb = MockDb::default();
         assert!(create_new_event(&mock_db, None, x).is_ok());
@@ -407,6 +458,7 @@ mod tests {
                 email_confirmed: true,
                 password: "secret".parse::<Password>().unwrap(),
                 role: Role::User,
+                language: None,
             })
             .unwrap();
         let users = mock_db.all_users().unwrap();
@@ -433,9 +485,87 @@ mod tests {
             organizer    : None,
             image_url     : None,
             image_link_url: None,
+            recurrence    : None,
+            capacity      : None,
         };
         assert!(create_new_event(&mock_db, None, x).is_ok());
         let users = mock_db.all_users().unwrap();
         assert_eq!(users.len(), 1);
     }
+
+    fn mock_db_with_orgs() -> MockDb {
+        let mock_db = MockDb::default();
+        create_org_with_api_token(
+            &mock_db,
+            Organization {
+                id: "foo".into(),
+                name: "foo".into(),
+                owned_tags: vec!["foo-tag".into()],
+            },
+            "foo",
+        );
+        create_org_with_api_token(
+            &mock_db,
+            Organization {
+                id: "bar".into(),
+                name: "bar".into(),
+                owned_tags: vec!["bar-tag".into()],
+            },
+            "bar",
+        );
+        mock_db
+    }
+
+    fn update_event<D: Db>(db: &D, token: Option<&str>, id: &str, e: NewEvent) -> Result<Event> {
+        let s = import_new_event(db, token, e, NewEventMode::Update(id))?;
+        store_updated_event(db, s)
+    }
+
+    #[test]
+    fn create_event_with_tag_owned_by_other_org() {
+        let mock_db = mock_db_with_orgs();
+        let x = NewEvent {
+            title: "foo".into(),
+            start: Utc::now().naive_utc().timestamp(),
+            tags: Some(vec!["bar-tag".into()]),
+            created_by: Some("fooo@bar.tld".into()),
+            ..Default::default()
+        };
+        assert!(create_new_event(&mock_db, None, x.clone()).is_err());
+        assert!(create_new_event(&mock_db, Some("foo"), x.clone()).is_err());
+        assert!(create_new_event(&mock_db, Some("bar"), x).is_ok());
+    }
+
+    #[test]
+    fn update_event_owned_by_other_org() {
+        let mock_db = mock_db_with_orgs();
+        let x = NewEvent {
+            title: "foo".into(),
+            start: Utc::now().naive_utc().timestamp(),
+            tags: Some(vec!["bar-tag".into(), "other".into()]),
+            created_by: Some("fooo@bar.tld".into()),
+            ..Default::default()
+        };
+        let id = create_new_event(&mock_db, Some("bar"), x.clone())
+            .unwrap()
+            .id;
+
+        // Neither without a token...
+        let mut without_owned_tags = x.clone();
+        without_owned_tags.tags = Some(vec!["other".into()]);
+        assert!(update_event(&mock_db, None, id.as_ref(), without_owned_tags.clone()).is_err());
+
+        // ...nor with the token of another org and its own tags
+        let mut with_other_owned_tags = x.clone();
+        with_other_owned_tags.tags = Some(vec!["foo-tag".into()]);
+        assert!(update_event(&mock_db, Some("foo"), id.as_ref(), with_other_owned_tags).is_err());
+        assert!(update_event(&mock_db, Some("foo"), id.as_ref(), without_owned_tags).is_err());
+        assert_eq!(
+            mock_db.get_event(&id.as_str().into()).unwrap().tags,
+            vec!["bar-tag", "other"]
+        );
+
+        // Only the owning org is entitled to update the event
+        assert!(update_event(&mock_db, Some("bar"), id.as_ref(), x).is_ok());
+    }
 }
@@ -0,0 +1,133 @@
+use crate::core::prelude::*;
+use std::collections::HashMap;
+
+/// Words that stand for existing tags, e.g. `organic`
+/// for the tag `bio`.
+#[derive(Debug, Clone, Default)]
+pub struct TagSynonyms {
+    tags_by_word: HashMap<String, Vec<String>>,
+}
+
+impl TagSynonyms {
+    /// Synonyms with two words like `fair trade` are matched
+    /// by adjacent words in the text.
+    pub fn add(&mut self, synonym: &str, tag: &str) {
+        let word = split_text_to_keywords(synonym).join("-");
+        if word.is_empty() {
+            return;
+        }
+        let tags = self.tags_by_word.entry(word).or_default();
+        let tag = tag.trim().to_lowercase();
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tags_by_word.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags_by_word.is_empty()
+    }
+
+    fn tags(&self, word: &str) -> &[String] {
+        self.tags_by_word
+            .get(word)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+// Tags consist of lowercase words that are joined by hyphens
+fn split_text_to_keywords(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '-'))
+        .map(|word| word.trim_matches('-'))
+        .filter(|word| !word.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+/// Extract existing tags from free text, e.g. the description
+/// of a new place. Tags are matched by single words, pairs of
+/// adjacent words joined by a hyphen and synonyms. Tags that
+/// occur more often come first.
+pub fn suggest_tags_from_text<D: Db>(
+    db: &D,
+    synonyms: &TagSynonyms,
+    text: &str,
+    limit: usize,
+) -> Result<Vec<String>> {
+    let words = split_text_to_keywords(text);
+    if words.is_empty() {
+        return Ok(vec![]);
+    }
+    let vocabulary: Vec<_> = db.all_tags()?.into_iter().map(|tag| tag.id).collect();
+    // Occurrences and position of the first occurrence in the text
+    let mut candidates: Vec<(String, usize, usize)> = vec![];
+    for (pos, word) in words.iter().enumerate() {
+        let pair = words.get(pos + 1).map(|next| format!("{}-{}", word, next));
+        for keyword in pair.iter().chain(Some(word)) {
+            let tags = Some(keyword).into_iter().chain(synonyms.tags(keyword));
+            for tag in tags.filter(|tag| vocabulary.contains(tag)) {
+                if let Some(candidate) = candidates.iter_mut().find(|(t, _, _)| *t == *tag) {
+                    candidate.1 += 1;
+                } else {
+                    candidates.push((tag.clone(), 1, pos));
+                }
+            }
+        }
+    }
+    candidates.sort_by_key(|(_, count, pos)| (std::cmp::Reverse(*count), *pos));
+    Ok(candidates
+        .into_iter()
+        .map(|(tag, _, _)| tag)
+        .take(limit)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn create_tags(db: &MockDb, tags: &[&str]) {
+        for tag in tags {
+            db.create_tag_if_it_does_not_exist(&Tag {
+                id: (*tag).to_string(),
+            })
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn suggest_existing_tags_from_text() {
+        let db = MockDb::default();
+        create_tags(
+            &db,
+            &["bio", "organic-food", "food", "repair-cafe", "fairtrade"],
+        );
+        let mut synonyms = TagSynonyms::default();
+        synonyms.add("Organic", "bio");
+        synonyms.add("unknown", "missing");
+        synonyms.add("Fair Trade", "fairtrade");
+        assert_eq!(
+            vec!["food", "organic-food", "bio", "repair-cafe", "fairtrade"],
+            suggest_tags_from_text(
+                &db,
+                &synonyms,
+                "Organic food and a Repair-Cafe (every week). Delicious fair trade food!",
+                10
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            vec!["food"],
+            suggest_tags_from_text(&db, &synonyms, "Organic food. More food", 1).unwrap()
+        );
+        assert!(suggest_tags_from_text(&db, &synonyms, " ... ", 10)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,67 @@
+use super::authorize_user_by_email;
+use crate::core::prelude::*;
+
+/// All organizations that own the given tag
+pub fn load_tag_owners<D: Db>(db: &D, tag: &str) -> Result<Vec<TagOwnership>> {
+    let tag = tag.trim().to_lowercase();
+    Ok(db
+        .get_all_tag_ownerships()?
+        .into_iter()
+        .filter(|ownership| ownership.tag == tag)
+        .collect())
+}
+
+/// All tags that are owned by organizations
+pub fn load_all_tag_ownerships<D: Db>(db: &D, account_email: &str) -> Result<Vec<TagOwnership>> {
+    authorize_user_by_email(db, account_email, Permission::ViewTagOwners)?;
+    Ok(db.get_all_tag_ownerships()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn new_org(id: &str, owned_tags: &[&str]) -> Organization {
+        Organization {
+            id: id.into(),
+            name: format!("{} name", id),
+            owned_tags: owned_tags.iter().map(|tag| (*tag).to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn load_owners_of_a_single_tag() {
+        let db = MockDb::default();
+        *db.orgs.borrow_mut() = vec![new_org("b", &["foo", "bar"]), new_org("a", &["foo"])];
+        let owners: Vec<_> = load_tag_owners(&db, " Foo ")
+            .unwrap()
+            .into_iter()
+            .map(|ownership| ownership.org_id)
+            .collect();
+        assert_eq!(vec!["a", "b"], owners);
+        assert!(load_tag_owners(&db, "baz").unwrap().is_empty());
+    }
+
+    #[test]
+    fn only_scouts_can_load_all_tag_ownerships() {
+        let db = MockDb::default();
+        for (email, role) in &[
+            ("user@example.com", Role::User),
+            ("scout@example.com", Role::Scout),
+        ] {
+            db.users.borrow_mut().push(User {
+                email: (*email).into(),
+                email_confirmed: true,
+                password: "secret".parse::<Password>().unwrap(),
+                role: *role,
+                language: None,
+            });
+        }
+        assert!(matches!(
+            load_all_tag_ownerships(&db, "user@example.com"),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+        assert!(load_all_tag_ownerships(&db, "scout@example.com").is_ok());
+    }
+}
@@ -0,0 +1,164 @@
+use super::{email_addresses_by_coordinate, prepare_tag_list, SubscriptionQuota};
+use crate::core::{prelude::*, util::validate};
+
+#[derive(Debug, Clone)]
+pub struct NewTagSubscription {
+    pub tags: Vec<String>,
+    pub bbox: Option<MapBbox>,
+}
+
+pub fn subscribe_to_tags<D: Db>(
+    db: &D,
+    user_email: &str,
+    new_subscription: NewTagSubscription,
+    quota: SubscriptionQuota,
+) -> Result<TagSubscription> {
+    let NewTagSubscription { tags, bbox } = new_subscription;
+    let tags = prepare_tag_list(tags.iter().map(String::as_str));
+    if tags.is_empty() {
+        return Err(ParameterError::InvalidTag.into());
+    }
+    if let Some(ref bbox) = bbox {
+        validate::bbox(bbox)?;
+    }
+    if db.all_tag_subscriptions_by_email(user_email)?.len() >= quota.max_tag_subscriptions {
+        return Err(ParameterError::SubscriptionQuotaExceeded.into());
+    }
+    let subscription = TagSubscription {
+        id: Id::new(),
+        user_email: user_email.into(),
+        tags,
+        bbox,
+    };
+    db.create_tag_subscription(&subscription)?;
+    Ok(subscription)
+}
+
+pub fn get_tag_subscriptions<D: Db>(db: &D, user_email: &str) -> Result<Vec<TagSubscription>> {
+    Ok(db.all_tag_subscriptions_by_email(user_email)?)
+}
+
+pub fn unsubscribe_from_tags<D: Db>(db: &D, user_email: &str, id: &str) -> Result<()> {
+    Ok(db.delete_tag_subscription(user_email, id)?)
+}
+
+fn tag_subscription_matches_place(subscription: &TagSubscription, place: &Place) -> bool {
+    subscription.tags.iter().all(|tag| place.tags.contains(tag))
+        && subscription
+            .bbox
+            .map(|bbox| bbox.contains_point(place.location.pos))
+            .unwrap_or(true)
+}
+
+/// The e-mail addresses of all users who subscribed either
+/// to the region or to the tags of the place.
+pub fn email_addresses_by_place<D: Db>(db: &D, place: &Place) -> Result<Vec<String>> {
+    let mut email_addresses = email_addresses_by_coordinate(db, place.location.pos)?;
+    for subscription in db.all_tag_subscriptions()? {
+        if tag_subscription_matches_place(&subscription, place)
+            && !email_addresses.contains(&subscription.user_email)
+        {
+            email_addresses.push(subscription.user_email);
+        }
+    }
+    Ok(email_addresses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn bbox(lat: f64) -> MapBbox {
+        MapBbox::new(
+            MapPoint::from_lat_lng_deg(lat - 1.0, 7.0),
+            MapPoint::from_lat_lng_deg(lat + 1.0, 9.0),
+        )
+    }
+
+    fn new_subscription(tags: &[&str], bbox: Option<MapBbox>) -> NewTagSubscription {
+        NewTagSubscription {
+            tags: tags.iter().map(|t| (*t).to_owned()).collect(),
+            bbox,
+        }
+    }
+
+    #[test]
+    fn subscribe_to_tags_within_quota() {
+        let db = MockDb::default();
+        let quota = SubscriptionQuota {
+            max_tag_subscriptions: 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            subscribe_to_tags(&db, "foo@bar.tld", new_subscription(&[" #"], None), quota),
+            Err(Error::Parameter(ParameterError::InvalidTag))
+        ));
+        let subscription = subscribe_to_tags(
+            &db,
+            "foo@bar.tld",
+            new_subscription(&["#Organic", "bakery"], None),
+            quota,
+        )
+        .unwrap();
+        assert_eq!(vec!["bakery", "organic"], subscription.tags);
+        assert!(matches!(
+            subscribe_to_tags(&db, "foo@bar.tld", new_subscription(&["cafe"], None), quota),
+            Err(Error::Parameter(ParameterError::SubscriptionQuotaExceeded))
+        ));
+
+        unsubscribe_from_tags(&db, "foo@bar.tld", subscription.id.as_str()).unwrap();
+        assert!(get_tag_subscriptions(&db, "foo@bar.tld")
+            .unwrap()
+            .is_empty());
+        assert!(unsubscribe_from_tags(&db, "foo@bar.tld", subscription.id.as_str()).is_err());
+    }
+
+    #[test]
+    fn notify_subscribers_of_all_tags_within_their_bbox() {
+        let db = MockDb::default();
+        let quota = SubscriptionQuota::default();
+        subscribe_to_tags(
+            &db,
+            "everywhere@bar.tld",
+            new_subscription(&["organic"], None),
+            quota,
+        )
+        .unwrap();
+        subscribe_to_tags(
+            &db,
+            "nearby@bar.tld",
+            new_subscription(&["organic", "bakery"], Some(bbox(48.0))),
+            quota,
+        )
+        .unwrap();
+        subscribe_to_tags(
+            &db,
+            "far@bar.tld",
+            new_subscription(&["organic"], Some(bbox(52.0))),
+            quota,
+        )
+        .unwrap();
+        db.create_bbox_subscription(&BboxSubscription {
+            id: Id::new(),
+            user_email: "everywhere@bar.tld".into(),
+            bbox: bbox(48.0),
+        })
+        .unwrap();
+
+        let place = Place::build()
+            .pos(MapPoint::from_lat_lng_deg(48.0, 8.0))
+            .tags(vec!["bakery", "organic"])
+            .finish();
+        assert_eq!(
+            vec!["everywhere@bar.tld", "nearby@bar.tld"],
+            email_addresses_by_place(&db, &place).unwrap()
+        );
+
+        let place = Place::build()
+            .pos(MapPoint::from_lat_lng_deg(52.0, 8.0))
+            .tags(vec!["bakery"])
+            .finish();
+        assert!(email_addresses_by_place(&db, &place).unwrap().is_empty());
+    }
+}
@@ -62,6 +62,12 @@ impl Key for BboxSubscription {
     }
 }
 
+impl Key for TagSubscription {
+    fn key(&self) -> &str {
+        self.id.as_ref()
+    }
+}
+
 impl Key for Organization {
     fn key(&self) -> &str {
         &self.id
@@ -72,13 +78,68 @@ impl Key for Organization {
 pub struct MockDb {
     pub entries: RefCell<Vec<(Place, ReviewStatus)>>,
     pub events: RefCell<Vec<Event>>,
+    // (event id, organization id, external id)
+    pub event_external_ids: RefCell<Vec<(String, String, String)>>,
     pub tags: RefCell<Vec<Tag>>,
     pub users: RefCell<Vec<User>>,
     pub ratings: RefCell<Vec<Rating>>,
     pub comments: RefCell<Vec<Comment>>,
     pub bbox_subscriptions: RefCell<Vec<BboxSubscription>>,
-    pub orgs: Vec<Organization>,
+    pub tag_subscriptions: RefCell<Vec<TagSubscription>>,
+    pub saved_searches: RefCell<Vec<SavedSearch>>,
+    pub digest_settings: RefCell<Vec<DigestSettings>>,
+    pub notification_queue: RefCell<Vec<QueuedNotification>>,
+    pub orgs: RefCell<Vec<Organization>>,
     pub token: RefCell<Vec<UserToken>>,
+    pub login_links: RefCell<Vec<LoginLink>>,
+    // (duplicate id, primary id)
+    pub place_merges: RefCell<Vec<(PlaceId, PlaceId)>>,
+    pub place_external_ids: RefCell<Vec<(PlaceId, ExternalId)>>,
+    pub user_status_logs: RefCell<Vec<(String, UserStatusLog)>>,
+    pub reports: RefCell<Vec<(Report, Option<Activity>)>>,
+    pub curated_tags: RefCell<Vec<CuratedTag>>,
+    pub curated_orgs: RefCell<Vec<CuratedOrganization>>,
+    pub moderation_assignments: RefCell<Vec<ModerationAssignment>>,
+    pub contact_verifications: RefCell<Vec<ContactVerification>>,
+    pub event_registrations: RefCell<Vec<EventRegistration>>,
+    pub place_drafts: RefCell<Vec<PlaceDraft>>,
+    pub permission_overrides: RefCell<Vec<(String, PermissionOverride)>>,
+    pub scout_report_opt_outs: RefCell<Vec<String>>,
+    pub announcements: RefCell<Vec<Announcement>>,
+    pub place_clearances: RefCell<Vec<(String, PendingClearanceForPlace)>>,
+    pub org_api_tokens: RefCell<Vec<OrganizationApiToken>>,
+    pub user_api_tokens: RefCell<Vec<UserApiToken>>,
+    pub webhooks: RefCell<Vec<Webhook>>,
+    pub webhook_deliveries: RefCell<Vec<WebhookDelivery>>,
+    pub outgoing_emails: RefCell<Vec<OutgoingEmail>>,
+}
+
+/// Add a user with a confirmed email address.
+pub fn create_user(db: &MockDb, email: &str, role: Role) {
+    db.create_user(&User {
+        email: email.into(),
+        email_confirmed: true,
+        password: "secret".parse::<Password>().unwrap(),
+        role,
+        language: None,
+    })
+    .unwrap();
+}
+
+/// Add an organization with an API token that grants all
+/// scopes and never expires.
+pub fn create_org_with_api_token<D: Db>(db: &D, org: Organization, token: &str) {
+    let org_id = org.id.clone();
+    db.create_org(org).unwrap();
+    db.create_org_api_token(&OrganizationApiToken {
+        id: Id::new(),
+        org_id,
+        token: token.into(),
+        scopes: ApiTokenScope::ALL.to_vec(),
+        created_at: TimestampMs::now(),
+        expires_at: None,
+    })
+    .unwrap();
 }
 
 impl UserTokenRepo for MockDb {
@@ -94,14 +155,11 @@ impl UserTokenRepo for MockDb {
     }
 
     fn consume_user_token(&self, email_nonce: &EmailNonce) -> RepoResult<UserToken> {
-        if let Some(index) = self.token.borrow().iter().enumerate().find_map(|(i, x)| {
-            if x.email_nonce.email == email_nonce.email && x.email_nonce.nonce == email_nonce.nonce
-            {
-                Some(i)
-            } else {
-                None
-            }
-        }) {
+        // The shared borrow must end before removing the token
+        let index = self.token.borrow().iter().position(|x| {
+            x.email_nonce.email == email_nonce.email && x.email_nonce.nonce == email_nonce.nonce
+        });
+        if let Some(index) = index {
             Ok(self.token.borrow_mut().swap_remove(index))
         } else {
             Err(RepoError::NotFound)
@@ -118,11 +176,591 @@ impl UserTokenRepo for MockDb {
         Ok(len_before - len_after)
     }
 
+    fn load_valid_user_tokens(&self, expired_before: Timestamp) -> RepoResult<Vec<UserToken>> {
+        let mut tokens: Vec<_> = self
+            .token
+            .borrow()
+            .iter()
+            .filter(|x| x.expires_at >= expired_before)
+            .cloned()
+            .collect();
+        tokens.sort_by(|a, b| a.email_nonce.email.cmp(&b.email_nonce.email));
+        Ok(tokens)
+    }
+
     fn get_user_token_by_email(&self, _email: &str) -> RepoResult<UserToken> {
         unimplemented!()
     }
 }
 
+impl LoginLinkRepo for MockDb {
+    fn replace_login_link(&self, login_link: &LoginLink) -> RepoResult<()> {
+        let mut login_links = self.login_links.borrow_mut();
+        login_links.retain(|x| x.email_nonce.email != login_link.email_nonce.email);
+        login_links.push(login_link.clone());
+        Ok(())
+    }
+
+    fn get_login_link_by_email(&self, email: &str) -> RepoResult<LoginLink> {
+        self.login_links
+            .borrow()
+            .iter()
+            .find(|x| x.email_nonce.email == email)
+            .cloned()
+            .ok_or(RepoError::NotFound)
+    }
+
+    fn consume_login_link(&self, email_nonce: &EmailNonce) -> RepoResult<LoginLink> {
+        // The shared borrow must end before removing the link
+        let index = self
+            .login_links
+            .borrow()
+            .iter()
+            .position(|x| &x.email_nonce == email_nonce);
+        if let Some(index) = index {
+            Ok(self.login_links.borrow_mut().swap_remove(index))
+        } else {
+            Err(RepoError::NotFound)
+        }
+    }
+
+    fn delete_expired_login_links(&self, expired_before: Timestamp) -> RepoResult<usize> {
+        let len_before = self.login_links.borrow().len();
+        self.login_links
+            .borrow_mut()
+            .retain(|x| x.expires_at >= expired_before);
+        Ok(len_before - self.login_links.borrow().len())
+    }
+}
+
+impl ContactVerificationRepo for MockDb {
+    fn replace_contact_verification(&self, verification: &ContactVerification) -> RepoResult<()> {
+        let mut verifications = self.contact_verifications.borrow_mut();
+        verifications.retain(|x| {
+            x.place_id != verification.place_id
+                || x.email_nonce.email != verification.email_nonce.email
+        });
+        verifications.push(verification.clone());
+        Ok(())
+    }
+
+    fn load_contact_verification(
+        &self,
+        email_nonce: &EmailNonce,
+    ) -> RepoResult<ContactVerification> {
+        self.contact_verifications
+            .borrow()
+            .iter()
+            .find(|x| &x.email_nonce == email_nonce)
+            .cloned()
+            .ok_or(RepoError::NotFound)
+    }
+
+    fn is_contact_verified(&self, place_id: &PlaceId, email: &str) -> RepoResult<bool> {
+        Ok(self.contact_verifications.borrow().iter().any(|x| {
+            x.place_id.as_str() == place_id.as_str()
+                && x.email_nonce.email == email
+                && x.verified_at.is_some()
+        }))
+    }
+}
+
+impl EventRegistrationRepo for MockDb {
+    fn replace_event_registration(&self, registration: &EventRegistration) -> RepoResult<()> {
+        let mut registrations = self.event_registrations.borrow_mut();
+        registrations.retain(|x| {
+            x.event_id != registration.event_id
+                || x.email_nonce.email != registration.email_nonce.email
+        });
+        registrations.push(registration.clone());
+        Ok(())
+    }
+
+    fn load_event_registration(&self, email_nonce: &EmailNonce) -> RepoResult<EventRegistration> {
+        self.event_registrations
+            .borrow()
+            .iter()
+            .find(|x| &x.email_nonce == email_nonce)
+            .cloned()
+            .ok_or(RepoError::NotFound)
+    }
+
+    fn load_event_registrations(&self, event_id: &EventId) -> RepoResult<Vec<EventRegistration>> {
+        let mut registrations: Vec<_> = self
+            .event_registrations
+            .borrow()
+            .iter()
+            .filter(|x| x.event_id.as_str() == event_id.as_str())
+            .cloned()
+            .collect();
+        registrations.sort_by_key(|x| x.registered_at);
+        Ok(registrations)
+    }
+}
+
+impl PlaceExternalIdRepo for MockDb {
+    fn load_place_external_ids(&self, place_id: &PlaceId) -> RepoResult<Vec<ExternalId>> {
+        Ok(self
+            .place_external_ids
+            .borrow()
+            .iter()
+            .filter(|(id, _)| id == place_id)
+            .map(|(_, external_id)| external_id.clone())
+            .collect())
+    }
+
+    fn replace_place_external_ids(
+        &self,
+        place_id: &PlaceId,
+        external_ids: &[ExternalId],
+    ) -> RepoResult<()> {
+        let mut place_external_ids = self.place_external_ids.borrow_mut();
+        place_external_ids.retain(|(id, _)| id != place_id);
+        place_external_ids.extend(
+            external_ids
+                .iter()
+                .map(|external_id| (place_id.clone(), external_id.clone())),
+        );
+        Ok(())
+    }
+
+    fn find_place_id_by_external_id(&self, source: &str, id: &str) -> RepoResult<Option<PlaceId>> {
+        Ok(self
+            .place_external_ids
+            .borrow()
+            .iter()
+            .find(|(_, x)| x.source == source && x.id == id)
+            .map(|(place_id, _)| place_id.clone()))
+    }
+}
+
+impl PlaceMergeRepo for MockDb {
+    fn create_place_merge(
+        &self,
+        duplicate_id: &PlaceId,
+        primary_id: &PlaceId,
+        _merged_at: TimestampMs,
+    ) -> RepoResult<()> {
+        let mut place_merges = self.place_merges.borrow_mut();
+        place_merges.retain(|(x, _)| x != duplicate_id);
+        place_merges.push((duplicate_id.clone(), primary_id.clone()));
+        Ok(())
+    }
+
+    fn get_place_merged_into(&self, place_id: &PlaceId) -> RepoResult<Option<PlaceId>> {
+        Ok(self
+            .place_merges
+            .borrow()
+            .iter()
+            .find(|(x, _)| x == place_id)
+            .map(|(_, primary_id)| primary_id.clone()))
+    }
+}
+
+impl AuditLogRepo for MockDb {
+    fn log_audit_entry(&self, _entry: &AuditLogEntry) -> RepoResult<()> {
+        unimplemented!();
+    }
+
+    fn load_audit_log(
+        &self,
+        _filter: &AuditLogFilter,
+        _pagination: &Pagination,
+    ) -> RepoResult<Vec<AuditLogEntry>> {
+        unimplemented!();
+    }
+}
+
+impl AnnouncementRepo for MockDb {
+    fn create_announcement(&self, announcement: &Announcement) -> RepoResult<()> {
+        self.announcements.borrow_mut().push(announcement.clone());
+        Ok(())
+    }
+
+    fn delete_announcement(&self, id: &str) -> RepoResult<()> {
+        let mut announcements = self.announcements.borrow_mut();
+        let count = announcements.len();
+        announcements.retain(|a| a.id.as_str() != id);
+        if announcements.len() == count {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn all_announcements(&self) -> RepoResult<Vec<Announcement>> {
+        let mut announcements = self.announcements.borrow().clone();
+        announcements.sort_by_key(|a| a.starts_at);
+        Ok(announcements)
+    }
+
+    fn load_announcements_displayed_at(&self, at: TimestampMs) -> RepoResult<Vec<Announcement>> {
+        Ok(self
+            .all_announcements()?
+            .into_iter()
+            .filter(|a| a.is_displayed_at(at))
+            .collect())
+    }
+}
+
+impl PlaceClearanceRepo for MockDb {
+    fn add_pending_clearance_for_place(
+        &self,
+        org_ids: &[&str],
+        pending: &PendingClearanceForPlace,
+    ) -> RepoResult<()> {
+        let mut place_clearances = self.place_clearances.borrow_mut();
+        for &org_id in org_ids {
+            if !place_clearances
+                .iter()
+                .any(|(id, p)| id == org_id && p.place_id == pending.place_id)
+            {
+                place_clearances.push((org_id.to_owned(), pending.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    fn load_pending_clearances_for_places(
+        &self,
+        org_id: &str,
+    ) -> RepoResult<Vec<PendingClearanceForPlace>> {
+        let mut pending: Vec<_> = self
+            .place_clearances
+            .borrow()
+            .iter()
+            .filter(|(id, _)| id == org_id)
+            .map(|(_, p)| p.clone())
+            .collect();
+        pending.sort_by_key(|p| p.created_at);
+        Ok(pending)
+    }
+
+    fn delete_pending_clearance_for_place(&self, org_id: &str, place_id: &str) -> RepoResult<()> {
+        let mut place_clearances = self.place_clearances.borrow_mut();
+        let count = place_clearances.len();
+        place_clearances.retain(|(id, p)| !(id == org_id && p.place_id.as_str() == place_id));
+        if place_clearances.len() == count {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn count_pending_clearances_for_place(&self, place_id: &str) -> RepoResult<usize> {
+        Ok(self
+            .place_clearances
+            .borrow()
+            .iter()
+            .filter(|(_, p)| p.place_id.as_str() == place_id)
+            .count())
+    }
+}
+
+impl OrganizationApiTokenRepo for MockDb {
+    fn create_org_api_token(&self, token: &OrganizationApiToken) -> RepoResult<()> {
+        self.org_api_tokens.borrow_mut().push(token.clone());
+        Ok(())
+    }
+
+    fn load_org_api_tokens(&self, org_id: &str) -> RepoResult<Vec<OrganizationApiToken>> {
+        let mut tokens: Vec<_> = self
+            .org_api_tokens
+            .borrow()
+            .iter()
+            .filter(|t| t.org_id == org_id)
+            .cloned()
+            .collect();
+        tokens.sort_by_key(|t| t.created_at);
+        Ok(tokens)
+    }
+
+    fn get_org_api_token(&self, token: &str) -> RepoResult<OrganizationApiToken> {
+        self.org_api_tokens
+            .borrow()
+            .iter()
+            .find(|t| t.token == token)
+            .cloned()
+            .ok_or(RepoError::NotFound)
+    }
+
+    fn delete_org_api_token(&self, org_id: &str, id: &str) -> RepoResult<()> {
+        let mut tokens = self.org_api_tokens.borrow_mut();
+        let count = tokens.len();
+        tokens.retain(|t| !(t.org_id == org_id && t.id.as_str() == id));
+        if tokens.len() == count {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+impl UserApiTokenRepo for MockDb {
+    fn create_user_api_token(&self, token: &UserApiToken) -> RepoResult<()> {
+        self.user_api_tokens.borrow_mut().push(token.clone());
+        Ok(())
+    }
+
+    fn load_user_api_tokens(&self, user_email: &str) -> RepoResult<Vec<UserApiToken>> {
+        let mut tokens: Vec<_> = self
+            .user_api_tokens
+            .borrow()
+            .iter()
+            .filter(|t| t.user_email == user_email)
+            .cloned()
+            .collect();
+        tokens.sort_by_key(|t| t.created_at);
+        Ok(tokens)
+    }
+
+    fn get_user_api_token(&self, token: &str) -> RepoResult<UserApiToken> {
+        self.user_api_tokens
+            .borrow()
+            .iter()
+            .find(|t| t.token == token)
+            .cloned()
+            .ok_or(RepoError::NotFound)
+    }
+
+    fn delete_user_api_token(&self, user_email: &str, id: &str) -> RepoResult<()> {
+        let mut tokens = self.user_api_tokens.borrow_mut();
+        let count = tokens.len();
+        tokens.retain(|t| !(t.user_email == user_email && t.id.as_str() == id));
+        if tokens.len() == count {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+impl WebhookRepo for MockDb {
+    fn create_webhook(&self, webhook: &Webhook) -> RepoResult<()> {
+        self.webhooks.borrow_mut().push(webhook.clone());
+        Ok(())
+    }
+
+    fn all_webhooks(&self) -> RepoResult<Vec<Webhook>> {
+        let mut webhooks = self.webhooks.borrow().clone();
+        webhooks.sort_by_key(|w| w.created_at);
+        Ok(webhooks)
+    }
+
+    fn get_webhook(&self, id: &str) -> RepoResult<Webhook> {
+        self.webhooks
+            .borrow()
+            .iter()
+            .find(|w| w.id.as_str() == id)
+            .cloned()
+            .ok_or(RepoError::NotFound)
+    }
+
+    fn delete_webhook(&self, id: &str) -> RepoResult<()> {
+        let mut webhooks = self.webhooks.borrow_mut();
+        let count = webhooks.len();
+        webhooks.retain(|w| w.id.as_str() != id);
+        if webhooks.len() == count {
+            return Err(RepoError::NotFound);
+        }
+        self.webhook_deliveries
+            .borrow_mut()
+            .retain(|d| d.webhook_id.as_str() != id);
+        Ok(())
+    }
+
+    fn create_webhook_delivery(&self, delivery: &WebhookDelivery) -> RepoResult<()> {
+        self.webhook_deliveries.borrow_mut().push(delivery.clone());
+        Ok(())
+    }
+
+    fn update_webhook_delivery(&self, delivery: &WebhookDelivery) -> RepoResult<()> {
+        let mut deliveries = self.webhook_deliveries.borrow_mut();
+        let existing = deliveries
+            .iter_mut()
+            .find(|d| d.id == delivery.id)
+            .ok_or(RepoError::NotFound)?;
+        *existing = delivery.clone();
+        Ok(())
+    }
+
+    fn load_due_webhook_deliveries(
+        &self,
+        due_at: TimestampMs,
+        limit: u64,
+    ) -> RepoResult<Vec<WebhookDelivery>> {
+        let mut deliveries: Vec<_> = self
+            .webhook_deliveries
+            .borrow()
+            .iter()
+            .filter(|d| d.status == WebhookDeliveryStatus::Pending)
+            .filter(|d| d.next_attempt_at.map(|at| at <= due_at).unwrap_or(false))
+            .cloned()
+            .collect();
+        deliveries.sort_by_key(|d| d.next_attempt_at);
+        deliveries.truncate(limit as usize);
+        Ok(deliveries)
+    }
+
+    fn load_webhook_deliveries(
+        &self,
+        webhook_id: &str,
+        pagination: &Pagination,
+    ) -> RepoResult<Vec<WebhookDelivery>> {
+        let deliveries = self
+            .webhook_deliveries
+            .borrow()
+            .iter()
+            .rev()
+            .filter(|d| d.webhook_id.as_str() == webhook_id)
+            .skip(pagination.offset.unwrap_or(0) as usize)
+            .take(pagination.limit.unwrap_or(u64::MAX) as usize)
+            .cloned()
+            .collect();
+        Ok(deliveries)
+    }
+}
+
+impl EmailOutboxRepo for MockDb {
+    fn create_outgoing_email(&self, email: &OutgoingEmail) -> RepoResult<()> {
+        self.outgoing_emails.borrow_mut().push(email.clone());
+        Ok(())
+    }
+
+    fn update_outgoing_email(&self, email: &OutgoingEmail) -> RepoResult<()> {
+        let mut emails = self.outgoing_emails.borrow_mut();
+        let existing = emails
+            .iter_mut()
+            .find(|e| e.id == email.id)
+            .ok_or(RepoError::NotFound)?;
+        *existing = email.clone();
+        Ok(())
+    }
+
+    fn delete_outgoing_email(&self, id: &str) -> RepoResult<()> {
+        let mut emails = self.outgoing_emails.borrow_mut();
+        let len = emails.len();
+        emails.retain(|e| e.id.as_str() != id);
+        if emails.len() == len {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn load_due_outgoing_emails(
+        &self,
+        due_at: TimestampMs,
+        limit: u64,
+    ) -> RepoResult<Vec<OutgoingEmail>> {
+        let mut emails: Vec<_> = self
+            .outgoing_emails
+            .borrow()
+            .iter()
+            .filter(|e| e.status == OutgoingEmailStatus::Pending)
+            .filter(|e| e.next_attempt_at.map(|at| at <= due_at).unwrap_or(false))
+            .cloned()
+            .collect();
+        emails.sort_by_key(|e| e.next_attempt_at);
+        emails.truncate(limit as usize);
+        Ok(emails)
+    }
+
+    fn load_outgoing_emails(
+        &self,
+        status: Option<OutgoingEmailStatus>,
+        pagination: &Pagination,
+    ) -> RepoResult<Vec<OutgoingEmail>> {
+        let emails = self
+            .outgoing_emails
+            .borrow()
+            .iter()
+            .rev()
+            .filter(|e| status.map(|status| e.status == status).unwrap_or(true))
+            .skip(pagination.offset.unwrap_or(0) as usize)
+            .take(pagination.limit.unwrap_or(u64::MAX) as usize)
+            .cloned()
+            .collect();
+        Ok(emails)
+    }
+}
+
+impl PlaceDraftRepo for MockDb {
+    fn create_place_draft(&self, draft: &PlaceDraft) -> RepoResult<()> {
+        self.place_drafts.borrow_mut().push(draft.clone());
+        Ok(())
+    }
+
+    fn update_place_draft(&self, draft: &PlaceDraft) -> RepoResult<()> {
+        let mut drafts = self.place_drafts.borrow_mut();
+        let existing = drafts
+            .iter_mut()
+            .find(|x| x.id == draft.id)
+            .ok_or(RepoError::NotFound)?;
+        existing.updated_at = draft.updated_at;
+        existing.data = draft.data.clone();
+        Ok(())
+    }
+
+    fn load_place_draft(&self, id: &str) -> RepoResult<PlaceDraft> {
+        self.place_drafts
+            .borrow()
+            .iter()
+            .find(|x| x.id.as_str() == id)
+            .cloned()
+            .ok_or(RepoError::NotFound)
+    }
+
+    fn load_place_drafts_of_owner(&self, owner_email: &str) -> RepoResult<Vec<PlaceDraft>> {
+        let mut drafts: Vec<_> = self
+            .place_drafts
+            .borrow()
+            .iter()
+            .filter(|x| x.owner_email == owner_email)
+            .cloned()
+            .collect();
+        drafts.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        Ok(drafts)
+    }
+
+    fn delete_place_draft(&self, id: &str) -> RepoResult<()> {
+        self.place_drafts
+            .borrow_mut()
+            .retain(|x| x.id.as_str() != id);
+        Ok(())
+    }
+}
+
+impl UserPermissionRepo for MockDb {
+    fn load_permission_overrides(&self, user_email: &str) -> RepoResult<Vec<PermissionOverride>> {
+        Ok(self
+            .permission_overrides
+            .borrow()
+            .iter()
+            .filter(|(email, _)| email == user_email)
+            .map(|(_, permission_override)| permission_override.clone())
+            .collect())
+    }
+
+    fn replace_permission_override(
+        &self,
+        user_email: &str,
+        permission_override: &PermissionOverride,
+    ) -> RepoResult<()> {
+        self.delete_permission_override(user_email, permission_override.permission)?;
+        self.permission_overrides
+            .borrow_mut()
+            .push((user_email.to_owned(), permission_override.clone()));
+        Ok(())
+    }
+
+    fn delete_permission_override(
+        &self,
+        user_email: &str,
+        permission: Permission,
+    ) -> RepoResult<()> {
+        self.permission_overrides
+            .borrow_mut()
+            .retain(|(email, x)| email != user_email || x.permission != permission);
+        Ok(())
+    }
+}
+
 pub struct DummySearchEngine;
 
 impl Indexer for DummySearchEngine {
@@ -163,6 +801,14 @@ impl PlaceIndexer for DummySearchEngine {
     ) -> Fallible<()> {
         Ok(())
     }
+
+    fn set_curated_tags(&mut self, _curated_tags: &[CuratedTag]) {}
+    fn set_tag_ownerships(&mut self, _tag_ownerships: &[TagOwnership]) {}
+    fn set_categories(&mut self, _categories: &[Category]) {}
+
+    fn optimize_index(&mut self) -> Fallible<()> {
+        Ok(())
+    }
 }
 
 impl EventIndexer for DummySearchEngine {
@@ -216,8 +862,8 @@ impl PlaceRepo for MockDb {
             (place, ReviewStatus::Created),
         )
     }
-    fn get_place(&self, id: &str) -> RepoResult<(Place, ReviewStatus)> {
-        get(&self.entries.borrow(), id).and_then(|(p, s)| {
+    fn get_place(&self, id: &PlaceId) -> RepoResult<(Place, ReviewStatus)> {
+        get(&self.entries.borrow(), id.as_str()).and_then(|(p, s)| {
             if s != ReviewStatus::Archived {
                 Ok((p, s))
             } else {
@@ -245,35 +891,81 @@ impl PlaceRepo for MockDb {
             .cloned()
             .collect())
     }
+    fn all_place_ids(&self) -> RepoResult<Vec<String>> {
+        Ok(self
+            .entries
+            .borrow()
+            .iter()
+            .map(|(p, _)| p.id.to_string())
+            .collect())
+    }
+    fn find_place_ids_by_tags(&self, tags: &[&str]) -> RepoResult<Vec<Id>> {
+        Ok(self
+            .entries
+            .borrow()
+            .iter()
+            .filter(|(p, _)| p.tags.iter().any(|t| tags.contains(&t.as_str())))
+            .map(|(p, _)| p.id.clone())
+            .collect())
+    }
     fn recently_changed_places(
         &self,
         _params: &RecentlyChangedEntriesParams,
         _pagination: &Pagination,
-    ) -> RepoResult<Vec<(Place, ReviewStatus, ActivityLog)>> {
-        unimplemented!();
-    }
-    fn most_popular_place_revision_tags(
-        &self,
-        _params: &MostPopularTagsParams,
-        _pagination: &Pagination,
-    ) -> RepoResult<Vec<TagFrequency>> {
+    ) -> RepoResult<Vec<RecentlyChangedPlace>> {
         unimplemented!();
     }
     fn count_places(&self) -> RepoResult<usize> {
         self.all_places().map(|v| v.len())
     }
 
+    fn count_new_places_per_month(&self) -> RepoResult<Vec<MonthlyCount>> {
+        unimplemented!();
+    }
+
+    fn list_places_pending_review(&self, _pagination: &Pagination) -> RepoResult<Vec<Place>> {
+        unimplemented!();
+    }
+
     fn review_places(
         &self,
-        _ids: &[&str],
-        _status: ReviewStatus,
+        ids: &[&str],
+        status: ReviewStatus,
         _activity: &ActivityLog,
     ) -> RepoResult<usize> {
-        unimplemented!();
+        let mut count = 0;
+        for (place, s) in self.entries.borrow_mut().iter_mut() {
+            if ids.contains(&place.id.as_str()) {
+                *s = status;
+                count += 1;
+            }
+        }
+        Ok(count)
     }
 
-    fn get_place_history(&self, _id: &str) -> RepoResult<PlaceHistory> {
-        unimplemented!();
+    // Only the current revision is stored
+    fn get_place_history(&self, id: &PlaceId) -> RepoResult<PlaceHistory> {
+        let (place, status) = self
+            .entries
+            .borrow()
+            .iter()
+            .find(|(place, _)| place.id.as_str() == id.as_str())
+            .cloned()
+            .ok_or(RepoError::NotFound)?;
+        let review_log = ReviewStatusLog {
+            revision: Revision::initial(),
+            activity: ActivityLog {
+                activity: place.created.clone(),
+                context: None,
+                comment: None,
+            },
+            status,
+        };
+        let (place, place_revision) = place.into();
+        Ok(PlaceHistory {
+            place,
+            revisions: vec![(place_revision, vec![review_log])],
+        })
     }
 }
 
@@ -282,8 +974,8 @@ impl EventGateway for MockDb {
         create(&mut self.events.borrow_mut(), e)
     }
 
-    fn get_event(&self, id: &str) -> RepoResult<Event> {
-        get(&self.events.borrow(), id).and_then(|e| {
+    fn get_event(&self, id: &EventId) -> RepoResult<Event> {
+        get(&self.events.borrow(), id.as_str()).and_then(|e| {
             if e.archived.is_none() {
                 Ok(e)
             } else {
@@ -325,13 +1017,47 @@ impl EventGateway for MockDb {
         update(&mut self.events.borrow_mut(), e)
     }
 
-    fn archive_events(&self, _ids: &[&str], _archived: Timestamp) -> RepoResult<usize> {
+    fn archive_events(
+        &self,
+        _ids: &[&str],
+        _activity: &Activity,
+        _comment: Option<&str>,
+    ) -> RepoResult<usize> {
         unimplemented!();
     }
 
-    fn delete_event_with_matching_tags(&self, _id: &str, _tags: &[&str]) -> RepoResult<Option<()>> {
+    fn delete_event_with_matching_tags(
+        &self,
+        _id: &EventId,
+        _tags: &[&str],
+    ) -> RepoResult<Option<()>> {
         unimplemented!();
     }
+
+    fn find_event_id_by_external_id(
+        &self,
+        org_id: &str,
+        external_id: &str,
+    ) -> RepoResult<Option<String>> {
+        Ok(self
+            .event_external_ids
+            .borrow()
+            .iter()
+            .find(|(_, o, x)| o == org_id && x == external_id)
+            .map(|(id, _, _)| id.clone()))
+    }
+
+    fn set_event_external_id(
+        &self,
+        event_id: &EventId,
+        org_id: &str,
+        external_id: &str,
+    ) -> RepoResult<()> {
+        let mut external_ids = self.event_external_ids.borrow_mut();
+        external_ids.retain(|(id, _, _)| id != event_id.as_str());
+        external_ids.push((event_id.to_string(), org_id.into(), external_id.into()));
+        Ok(())
+    }
 }
 
 impl UserGateway for MockDb {
@@ -366,9 +1092,47 @@ impl UserGateway for MockDb {
         Ok(())
     }
 
+    fn anonymize_user_by_email(&self, _email: &str) -> RepoResult<()> {
+        unimplemented!();
+    }
+
     fn update_user(&self, u: &User) -> RepoResult<()> {
         update(&mut self.users.borrow_mut(), u)
     }
+
+    fn log_user_status(&self, email: &str, status_log: &UserStatusLog) -> RepoResult<()> {
+        self.user_status_logs
+            .borrow_mut()
+            .push((email.to_owned(), status_log.clone()));
+        Ok(())
+    }
+
+    fn load_user_status_logs(&self, email: &str) -> RepoResult<Vec<UserStatusLog>> {
+        Ok(self
+            .user_status_logs
+            .borrow()
+            .iter()
+            .filter(|(e, _)| e == email)
+            .map(|(_, log)| log.clone())
+            .collect())
+    }
+
+    fn set_scout_report_opt_out(&self, email: &str, opt_out: bool) -> RepoResult<()> {
+        let mut opt_outs = self.scout_report_opt_outs.borrow_mut();
+        opt_outs.retain(|e| e != email);
+        if opt_out {
+            opt_outs.push(email.to_owned());
+        }
+        Ok(())
+    }
+
+    fn is_scout_report_opted_out(&self, email: &str) -> RepoResult<bool> {
+        Ok(self
+            .scout_report_opt_outs
+            .borrow()
+            .iter()
+            .any(|e| e == email))
+    }
 }
 
 impl CommentRepository for MockDb {
@@ -376,8 +1140,8 @@ impl CommentRepository for MockDb {
         create(&mut self.comments.borrow_mut(), c)
     }
 
-    fn load_comment(&self, id: &str) -> RepoResult<Comment> {
-        get(&self.comments.borrow(), id).and_then(|c| {
+    fn load_comment(&self, id: &CommentId) -> RepoResult<Comment> {
+        get(&self.comments.borrow(), id.as_str()).and_then(|c| {
             if c.archived_at.is_none() {
                 Ok(c)
             } else {
@@ -396,26 +1160,42 @@ impl CommentRepository for MockDb {
             .collect())
     }
 
-    fn load_comments_of_rating(&self, rating_id: &str) -> RepoResult<Vec<Comment>> {
+    fn load_comments_of_rating(&self, rating_id: &RatingId) -> RepoResult<Vec<Comment>> {
         Ok(self
             .comments
             .borrow()
             .iter()
-            .filter(|c| c.rating_id.as_str() == rating_id && c.archived_at.is_none())
+            .filter(|c| c.rating_id.as_str() == rating_id.as_str() && c.archived_at.is_none())
             .cloned()
             .collect())
     }
 
-    fn archive_comments(&self, _ids: &[&str], _activity: &Activity) -> RepoResult<usize> {
+    fn flag_comment(&self, _id: &CommentId, _activity: &Activity) -> RepoResult<()> {
         unimplemented!();
     }
-    fn archive_comments_of_ratings(
+
+    fn archive_comments(
         &self,
-        _rating_ids: &[&str],
+        _ids: &[&str],
         _activity: &Activity,
+        _comment: Option<&str>,
     ) -> RepoResult<usize> {
         unimplemented!();
     }
+    fn archive_comments_of_ratings(
+        &self,
+        rating_ids: &[&str],
+        activity: &Activity,
+    ) -> RepoResult<usize> {
+        let mut count = 0;
+        for c in self.comments.borrow_mut().iter_mut() {
+            if c.archived_at.is_none() && rating_ids.iter().any(|id| c.rating_id.as_str() == *id) {
+                c.archived_at = Some(activity.at);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
     fn archive_comments_of_places(
         &self,
         _place_ids: &[&str],
@@ -426,24 +1206,72 @@ impl CommentRepository for MockDb {
 }
 
 impl OrganizationGateway for MockDb {
-    fn create_org(&mut self, o: Organization) -> RepoResult<()> {
-        create(&mut self.orgs, o)
-    }
-    fn get_org_by_api_token(&self, token: &str) -> RepoResult<Organization> {
-        let o = self
-            .orgs
-            .iter()
-            .find(|o| o.api_token == token)
-            .ok_or(RepoError::NotFound)?;
-        Ok(o.clone())
+    fn create_org(&self, o: Organization) -> RepoResult<()> {
+        create(&mut self.orgs.borrow_mut(), o)
+    }
+    fn get_org_by_id(&self, id: &str) -> RepoResult<Organization> {
+        get(&self.orgs.borrow(), id)
+    }
+    fn all_orgs(&self) -> RepoResult<Vec<Organization>> {
+        let mut orgs = self.orgs.borrow().clone();
+        orgs.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(orgs)
+    }
+    fn update_org(&self, o: &Organization) -> RepoResult<()> {
+        update(&mut self.orgs.borrow_mut(), o)
+    }
+    fn delete_org(&self, id: &str) -> RepoResult<()> {
+        let mut orgs = self.orgs.borrow_mut();
+        let len_before = orgs.len();
+        orgs.retain(|o| o.id != id);
+        if orgs.len() == len_before {
+            return Err(RepoError::NotFound);
+        }
+        self.curated_orgs.borrow_mut().retain(|o| o.org_id != id);
+        Ok(())
     }
     fn get_all_tags_owned_by_orgs(&self) -> RepoResult<Vec<String>> {
         Ok(self
             .orgs
+            .borrow()
             .iter()
             .flat_map(|o| o.owned_tags.clone())
             .collect())
     }
+    fn get_all_tag_ownerships(&self) -> RepoResult<Vec<TagOwnership>> {
+        let mut ownerships: Vec<_> = self
+            .orgs
+            .borrow()
+            .iter()
+            .flat_map(|o| {
+                o.owned_tags.iter().map(move |tag| TagOwnership {
+                    tag: tag.clone(),
+                    org_id: o.id.clone(),
+                    org_name: o.name.clone(),
+                })
+            })
+            .collect();
+        ownerships.sort_by(|a, b| (&a.tag, &a.org_name).cmp(&(&b.tag, &b.org_name)));
+        Ok(ownerships)
+    }
+    fn all_curated_orgs(&self) -> RepoResult<Vec<CuratedOrganization>> {
+        Ok(self.curated_orgs.borrow().clone())
+    }
+    fn save_curated_org(&self, curated_org: &CuratedOrganization) -> RepoResult<()> {
+        let mut curated_orgs = self.curated_orgs.borrow_mut();
+        curated_orgs.retain(|o| o.org_id != curated_org.org_id);
+        curated_orgs.push(curated_org.clone());
+        Ok(())
+    }
+    fn delete_curated_org(&self, org_id: &str) -> RepoResult<()> {
+        let mut curated_orgs = self.curated_orgs.borrow_mut();
+        let len_before = curated_orgs.len();
+        curated_orgs.retain(|o| o.org_id != org_id);
+        if curated_orgs.len() == len_before {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
 }
 
 impl RatingRepository for MockDb {
@@ -451,8 +1279,12 @@ impl RatingRepository for MockDb {
         create(&mut self.ratings.borrow_mut(), r)
     }
 
-    fn load_rating(&self, id: &str) -> RepoResult<Rating> {
-        get(&self.ratings.borrow(), id).and_then(|r| {
+    fn update_rating(&self, r: &Rating) -> RepoResult<()> {
+        update(&mut self.ratings.borrow_mut(), r)
+    }
+
+    fn load_rating(&self, id: &RatingId) -> RepoResult<Rating> {
+        get(&self.ratings.borrow(), id.as_str()).and_then(|r| {
             if r.archived_at.is_none() {
                 Ok(r)
             } else {
@@ -471,22 +1303,41 @@ impl RatingRepository for MockDb {
             .collect())
     }
 
-    fn load_ratings_of_place(&self, place_id: &str) -> RepoResult<Vec<Rating>> {
+    fn load_ratings_of_place(&self, place_id: &PlaceId) -> RepoResult<Vec<Rating>> {
         Ok(self
             .ratings
             .borrow()
             .iter()
-            .filter(|r| r.archived_at.is_none() && r.place_id.as_str() == place_id)
+            .filter(|r| r.archived_at.is_none() && r.place_id.as_str() == place_id.as_str())
             .cloned()
             .collect())
     }
 
-    fn load_place_ids_of_ratings(&self, _ids: &[&str]) -> RepoResult<Vec<String>> {
+    fn load_monthly_avg_ratings_of_place(
+        &self,
+        _place_id: &PlaceId,
+    ) -> RepoResult<Vec<MonthlyAvgRatings>> {
         unimplemented!();
     }
-    fn archive_ratings(&self, _ids: &[&str], _activity: &Activity) -> RepoResult<usize> {
+
+    fn load_place_ids_of_ratings(&self, _ids: &[&str]) -> RepoResult<Vec<String>> {
         unimplemented!();
     }
+    fn archive_ratings(
+        &self,
+        ids: &[&str],
+        activity: &Activity,
+        _comment: Option<&str>,
+    ) -> RepoResult<usize> {
+        let mut count = 0;
+        for r in self.ratings.borrow_mut().iter_mut() {
+            if r.archived_at.is_none() && ids.iter().any(|id| r.id.as_str() == *id) {
+                r.archived_at = Some(activity.at);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
     fn archive_ratings_of_places(
         &self,
         _place_ids: &[&str],
@@ -494,6 +1345,115 @@ impl RatingRepository for MockDb {
     ) -> RepoResult<usize> {
         unimplemented!();
     }
+    fn move_ratings_of_place(
+        &self,
+        from_place_id: &PlaceId,
+        to_place_id: &PlaceId,
+    ) -> RepoResult<usize> {
+        let mut count = 0;
+        for r in self.ratings.borrow_mut().iter_mut() {
+            if r.place_id.as_str() == from_place_id.as_str() {
+                r.place_id = to_place_id.clone().into();
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+impl ReportRepository for MockDb {
+    fn create_report(&self, report: Report) -> RepoResult<()> {
+        self.reports.borrow_mut().push((report, None));
+        Ok(())
+    }
+
+    fn load_open_reports(&self) -> RepoResult<Vec<Report>> {
+        Ok(self
+            .reports
+            .borrow()
+            .iter()
+            .filter(|(_, resolved)| resolved.is_none())
+            .map(|(r, _)| r.clone())
+            .collect())
+    }
+
+    fn resolve_reports(&self, ids: &[&str], activity: &Activity) -> RepoResult<usize> {
+        let mut count = 0;
+        for (r, resolved) in self.reports.borrow_mut().iter_mut() {
+            if resolved.is_none() && ids.iter().any(|id| r.id.as_str() == *id) {
+                *resolved = Some(activity.clone());
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+impl ModerationAssignmentRepository for MockDb {
+    fn assign_moderation_item(&self, assignment: ModerationAssignment) -> RepoResult<()> {
+        let mut assignments = self.moderation_assignments.borrow_mut();
+        assignments
+            .retain(|a| a.item_kind != assignment.item_kind || a.item_id != assignment.item_id);
+        assignments.push(assignment);
+        Ok(())
+    }
+
+    fn load_open_moderation_assignments(
+        &self,
+        assignee_email: &str,
+    ) -> RepoResult<Vec<ModerationAssignment>> {
+        Ok(self
+            .moderation_assignments
+            .borrow()
+            .iter()
+            .filter(|a| {
+                a.assignee.as_str() == assignee_email && a.state != AssignmentState::Resolved
+            })
+            .cloned()
+            .collect())
+    }
+
+    fn update_moderation_assignment_state(
+        &self,
+        item_kind: ModerationItemKind,
+        item_id: &str,
+        assignee_email: &str,
+        state: AssignmentState,
+        changed_at: TimestampMs,
+    ) -> RepoResult<()> {
+        let mut assignments = self.moderation_assignments.borrow_mut();
+        let assignment = assignments
+            .iter_mut()
+            .find(|a| {
+                a.item_kind == item_kind
+                    && a.item_id.as_str() == item_id
+                    && a.assignee.as_str() == assignee_email
+            })
+            .ok_or(RepoError::NotFound)?;
+        assignment.state = state;
+        assignment.changed_at = changed_at;
+        Ok(())
+    }
+
+    fn resolve_moderation_assignments(
+        &self,
+        item_kind: ModerationItemKind,
+        item_ids: &[&str],
+        resolved_at: TimestampMs,
+    ) -> RepoResult<usize> {
+        let mut count = 0;
+        for a in self.moderation_assignments.borrow_mut().iter_mut() {
+            if a.item_kind == item_kind
+                && a.state != AssignmentState::Resolved
+                && item_ids.iter().any(|id| a.item_id.as_str() == *id)
+            {
+                a.state = AssignmentState::Resolved;
+                a.changed_at = resolved_at;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
 }
 
 impl Db for MockDb {
@@ -509,6 +1469,14 @@ impl Db for MockDb {
         Ok(())
     }
 
+    fn load_comments_for_moderation(
+        &self,
+        _params: &CommentModerationParams,
+        _pagination: &Pagination,
+    ) -> RepoResult<Vec<ModeratedComment>> {
+        unimplemented!();
+    }
+
     fn create_bbox_subscription(&self, s: &BboxSubscription) -> RepoResult<()> {
         create(&mut self.bbox_subscriptions.borrow_mut(), s.clone())
     }
@@ -519,6 +1487,55 @@ impl Db for MockDb {
     fn count_tags(&self) -> RepoResult<usize> {
         self.all_tags().map(|v| v.len())
     }
+    fn replace_tag(&self, old_tag: &str, new_tag: &str) -> RepoResult<()> {
+        fn replace(tags: &mut Vec<String>, old_tag: &str, new_tag: &str) {
+            if tags.iter().any(|t| t == old_tag) {
+                tags.retain(|t| t != old_tag && t != new_tag);
+                tags.push(new_tag.to_owned());
+            }
+        }
+        for (place, _) in self.entries.borrow_mut().iter_mut() {
+            replace(&mut place.tags, old_tag, new_tag);
+        }
+        for event in self.events.borrow_mut().iter_mut() {
+            replace(&mut event.tags, old_tag, new_tag);
+        }
+        let mut curated_tags = self.curated_tags.borrow_mut();
+        if !curated_tags.iter().any(|t| t.tag == new_tag) {
+            for curated_tag in curated_tags.iter_mut().filter(|t| t.tag == old_tag) {
+                curated_tag.tag = new_tag.to_owned();
+            }
+        }
+        curated_tags.retain(|t| t.tag != old_tag);
+        self.tags.borrow_mut().retain(|t| t.id != old_tag);
+        self.create_tag_if_it_does_not_exist(&Tag {
+            id: new_tag.to_owned(),
+        })
+    }
+    fn delete_tag(&self, tag: &str) -> RepoResult<()> {
+        self.curated_tags.borrow_mut().retain(|t| t.tag != tag);
+        self.tags.borrow_mut().retain(|t| t.id != tag);
+        Ok(())
+    }
+
+    fn all_curated_tags(&self) -> RepoResult<Vec<CuratedTag>> {
+        Ok(self.curated_tags.borrow().clone())
+    }
+    fn save_curated_tag(&self, curated_tag: &CuratedTag) -> RepoResult<()> {
+        let mut curated_tags = self.curated_tags.borrow_mut();
+        curated_tags.retain(|t| t.tag != curated_tag.tag);
+        curated_tags.push(curated_tag.clone());
+        Ok(())
+    }
+    fn delete_curated_tag(&self, tag: &str) -> RepoResult<()> {
+        let mut curated_tags = self.curated_tags.borrow_mut();
+        let len_before = curated_tags.len();
+        curated_tags.retain(|t| t.tag != tag);
+        if curated_tags.len() == len_before {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
 
     fn all_bbox_subscriptions(&self) -> RepoResult<Vec<BboxSubscription>> {
         Ok(self.bbox_subscriptions.borrow().clone())
@@ -543,6 +1560,114 @@ impl Db for MockDb {
             .retain(|s| s.user_email != user_email);
         Ok(())
     }
+
+    fn create_tag_subscription(&self, s: &TagSubscription) -> RepoResult<()> {
+        create(&mut self.tag_subscriptions.borrow_mut(), s.clone())
+    }
+
+    fn all_tag_subscriptions(&self) -> RepoResult<Vec<TagSubscription>> {
+        Ok(self.tag_subscriptions.borrow().clone())
+    }
+
+    fn all_tag_subscriptions_by_email(&self, user_email: &str) -> RepoResult<Vec<TagSubscription>> {
+        Ok(self
+            .tag_subscriptions
+            .borrow()
+            .iter()
+            .filter(|s| s.user_email == user_email)
+            .cloned()
+            .collect())
+    }
+
+    fn delete_tag_subscription(&self, user_email: &str, id: &str) -> RepoResult<()> {
+        let mut subscriptions = self.tag_subscriptions.borrow_mut();
+        let pos = subscriptions
+            .iter()
+            .position(|s| s.user_email == user_email && s.id.as_str() == id)
+            .ok_or(RepoError::NotFound)?;
+        subscriptions.remove(pos);
+        Ok(())
+    }
+
+    fn create_saved_search(&self, s: &SavedSearch) -> RepoResult<()> {
+        self.saved_searches.borrow_mut().push(s.clone());
+        Ok(())
+    }
+
+    fn all_saved_searches_by_email(&self, user_email: &str) -> RepoResult<Vec<SavedSearch>> {
+        Ok(self
+            .saved_searches
+            .borrow()
+            .iter()
+            .filter(|s| s.user_email == user_email)
+            .cloned()
+            .collect())
+    }
+
+    fn delete_saved_search(&self, user_email: &str, id: &str) -> RepoResult<()> {
+        let mut searches = self.saved_searches.borrow_mut();
+        let pos = searches
+            .iter()
+            .position(|s| s.user_email == user_email && s.id.as_str() == id)
+            .ok_or(RepoError::NotFound)?;
+        searches.remove(pos);
+        Ok(())
+    }
+
+    fn save_digest_settings(&self, settings: &DigestSettings) -> RepoResult<()> {
+        let mut all_settings = self.digest_settings.borrow_mut();
+        all_settings.retain(|s| s.user_email != settings.user_email);
+        all_settings.push(settings.clone());
+        Ok(())
+    }
+
+    fn delete_digest_settings(&self, user_email: &str) -> RepoResult<()> {
+        self.digest_settings
+            .borrow_mut()
+            .retain(|s| s.user_email != user_email);
+        Ok(())
+    }
+
+    fn try_get_digest_settings(&self, user_email: &str) -> RepoResult<Option<DigestSettings>> {
+        Ok(self
+            .digest_settings
+            .borrow()
+            .iter()
+            .find(|s| s.user_email == user_email)
+            .cloned())
+    }
+
+    fn all_digest_settings(&self) -> RepoResult<Vec<DigestSettings>> {
+        Ok(self.digest_settings.borrow().clone())
+    }
+
+    fn enqueue_notification(&self, notification: &QueuedNotification) -> RepoResult<()> {
+        self.notification_queue
+            .borrow_mut()
+            .push(notification.clone());
+        Ok(())
+    }
+
+    fn load_queued_notifications(&self, user_email: &str) -> RepoResult<Vec<QueuedNotification>> {
+        Ok(self
+            .notification_queue
+            .borrow()
+            .iter()
+            .filter(|n| n.user_email == user_email)
+            .cloned()
+            .collect())
+    }
+
+    fn delete_queued_notifications(
+        &self,
+        user_email: &str,
+        until: TimestampMs,
+    ) -> RepoResult<usize> {
+        let mut queue = self.notification_queue.borrow_mut();
+        let count = queue.len();
+        queue.retain(|n| n.user_email != user_email || n.created_at > until);
+        Ok(count - queue.len())
+    }
 }
 
 #[test]
@@ -553,12 +1678,14 @@ fn receive_different_user() {
         email_confirmed: true,
         password: "secret".parse::<Password>().unwrap(),
         role: Role::Guest,
+        language: None,
     });
     db.users.borrow_mut().push(User {
         email: "b@foo.bar".into(),
         email_confirmed: true,
         password: "secret".parse::<Password>().unwrap(),
         role: Role::Guest,
+        language: None,
     });
     assert!(get_user(&db, "a@foo.bar", "b@foo.bar").is_err());
     assert!(get_user(&db, "a@foo.bar", "a@foo.bar").is_ok());
@@ -578,9 +1705,12 @@ fn create_bbox_subscription() {
             email_confirmed: true,
             password: "secret".parse::<Password>().unwrap(),
             role: Role::Guest,
+            language: None,
         })
         .is_ok());
-    assert!(usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox_new).is_ok());
+    assert!(
+        usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox_new, Default::default()).is_ok()
+    );
 
     let bbox_subscription = db.all_bbox_subscriptions().unwrap()[0].clone();
     assert_eq!(
@@ -589,6 +1719,38 @@ fn create_bbox_subscription() {
     );
 }
 
+#[test]
+fn reject_bbox_subscriptions_exceeding_quota() {
+    let db = MockDb::default();
+    let bbox = geo::MapBbox::new(
+        MapPoint::from_lat_lng_deg(0.0, 0.0),
+        MapPoint::from_lat_lng_deg(10.0, 10.0),
+    );
+    let quota = usecases::SubscriptionQuota {
+        max_bbox_subscriptions: 0,
+        ..Default::default()
+    };
+    match usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox, quota) {
+        Err(Error::Parameter(ParameterError::SubscriptionQuotaExceeded)) => {}
+        res => panic!("Unexpected result: {:?}", res),
+    }
+    assert!(db.all_bbox_subscriptions().unwrap().is_empty());
+
+    // The quota applies to the number of existing subscriptions
+    let quota = usecases::SubscriptionQuota {
+        max_bbox_subscriptions: 2,
+        ..Default::default()
+    };
+    usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox, quota).unwrap();
+    usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox, quota).unwrap();
+    usecases::subscribe_to_bbox(&db, "xyz@abc.de".into(), bbox, quota).unwrap();
+    match usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox, quota) {
+        Err(Error::Parameter(ParameterError::SubscriptionQuotaExceeded)) => {}
+        res => panic!("Unexpected result: {:?}", res),
+    }
+    assert_eq!(3, db.all_bbox_subscriptions().unwrap().len());
+}
+
 #[test]
 fn modify_bbox_subscription() {
     let db = MockDb::default();
@@ -609,6 +1771,7 @@ fn modify_bbox_subscription() {
             email_confirmed: true,
             password: "secret".parse::<Password>().unwrap(),
             role: Role::Guest,
+            language: None,
         })
         .is_ok());
 
@@ -619,7 +1782,13 @@ fn modify_bbox_subscription() {
     };
     db.create_bbox_subscription(&bbox_subscription).unwrap();
 
-    usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox_new).unwrap();
+    // Existing subscriptions are not replaced implicitly
+    assert!(matches!(
+        usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox_new, Default::default()),
+        Err(Error::Parameter(ParameterError::SubscriptionQuotaExceeded))
+    ));
+    usecases::unsubscribe_all_bboxes(&db, "abc@abc.de").unwrap();
+    usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox_new, Default::default()).unwrap();
 
     let bbox_subscriptions: Vec<_> = db
         .all_bbox_subscriptions()
@@ -655,6 +1824,7 @@ fn get_bbox_subscriptions() {
             email_confirmed: true,
             password: "secret1".parse::<Password>().unwrap(),
             role: Role::Guest,
+            language: None,
         })
         .is_ok());
     let bbox_subscription = BboxSubscription {
@@ -670,6 +1840,7 @@ fn get_bbox_subscriptions() {
             email_confirmed: true,
             password: "secret2".parse::<Password>().unwrap(),
             role: Role::Guest,
+            language: None,
         })
         .is_ok());
     let bbox_subscription2 = BboxSubscription {
@@ -696,10 +1867,11 @@ fn email_addresses_by_coordinate() {
         email_confirmed: true,
         password: "secret".parse::<Password>().unwrap(),
         role: Role::Guest,
+        language: None,
     })
     .unwrap();
 
-    usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox_new).unwrap();
+    usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox_new, Default::default()).unwrap();
 
     let email_addresses =
         usecases::email_addresses_by_coordinate(&db, MapPoint::from_lat_lng_deg(5.0, 5.0)).unwrap();
@@ -721,6 +1893,7 @@ fn delete_user() {
             email_confirmed: true,
             password: "secret".parse::<Password>().unwrap(),
             role: Role::Guest,
+            language: None,
         })
         .is_ok());
     assert!(db
@@ -729,6 +1902,7 @@ fn delete_user() {
             email_confirmed: true,
             password: "secret".parse::<Password>().unwrap(),
             role: Role::Guest,
+            language: None,
         })
         .is_ok());
     assert_eq!(db.count_users().unwrap(), 2);
@@ -745,6 +1919,7 @@ fn receive_event_with_creators_email() {
         email_confirmed: true,
         password: "secret".parse::<Password>().unwrap(),
         role: Role::Guest,
+        language: None,
     })
     .unwrap();
     db.create_event(Event {
@@ -763,9 +1938,11 @@ fn receive_event_with_creators_email() {
         archived: None,
         image_url: None,
         image_link_url: None,
+        recurrence: None,
+        capacity: None,
     })
     .unwrap();
-    let e = usecases::get_event(&db, "x").unwrap();
+    let e = usecases::get_event(&db, &"x".into()).unwrap();
     assert_eq!(e.created_by.unwrap(), "abc@abc.de");
 }
 
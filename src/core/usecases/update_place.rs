@@ -1,3 +1,4 @@
+use super::{NewPlaceLocation, NewPlaceSource};
 use crate::core::{
     prelude::*,
     util::{parse::parse_url_param, validate::Validate},
@@ -24,6 +25,8 @@ pub struct UpdatePlace {
     pub tags           : Vec<String>,
     pub image_url      : Option<String>,
     pub image_link_url : Option<String>,
+    pub source         : Option<NewPlaceSource>,
+    pub additional_locations: Option<Vec<NewPlaceLocation>>,
 }
 
 pub struct Storable(Place);
@@ -53,6 +56,8 @@ pub fn prepare_updated_place<D: Db>(
         homepage,
         image_url,
         image_link_url,
+        source,
+        additional_locations,
         ..
     } = e;
     let pos = match MapPoint::try_from_lat_lng_deg(lat, lng) {
@@ -61,17 +66,11 @@ pub fn prepare_updated_place<D: Db>(
     };
     let categories: Vec<_> = categories.into_iter().map(Id::from).collect();
     let tags = super::prepare_tag_list(
-        Category::merge_ids_into_tags(&categories, tags)
+        Categories::from(db.all_categories()?)
+            .merge_ids_into_tags(&categories, tags)
             .iter()
             .map(String::as_str),
     );
-    super::check_and_count_owned_tags(db, &tags, None)?;
-    // TODO: Ensure that no reserved tags are removed without authorization.
-    // All existing reserved tags from other organizations must be preserved
-    // when editing places. Reserved tags that already exist should not be
-    // considers during the check, because they must be preserved independent
-    // of who is editing the place_rev.
-    // GitHub issue: https://github.com/slowtec/openfairdb/issues/203
     let address = Address {
         street,
         zip,
@@ -84,8 +83,17 @@ pub fn prepare_updated_place<D: Db>(
     } else {
         Some(address)
     };
-    let (revision, license) = {
-        let (old_place, _) = db.get_place(place_id.as_str())?;
+    let (revision, license, old_source, old_additional_locations) = {
+        let (old_place, _) = db.get_place(&place_id.as_str().into())?;
+        // Owned tags that have already been assigned to the place
+        // may be kept or removed, but not added. These edits need
+        // to be cleared by the owners afterwards.
+        let added_tags: Vec<_> = tags
+            .iter()
+            .filter(|tag| !old_place.tags.contains(tag))
+            .cloned()
+            .collect();
+        super::check_and_count_owned_tags(db, &added_tags, None)?;
         // Check for revision conflict (optimistic locking)
         let revision = Revision::from(version);
         if old_place.revision.next() != revision {
@@ -93,7 +101,25 @@ pub fn prepare_updated_place<D: Db>(
         }
         // The license is immutable
         let license = old_place.license;
-        (revision, license)
+        (
+            revision,
+            license,
+            old_place.source,
+            old_place.additional_locations,
+        )
+    };
+    // The origin of imported places is preserved unless
+    // the place is updated by a subsequent import
+    let source = if let Some(source) = source {
+        Some(super::prepare_place_source(db, source, Some(&place_id))?)
+    } else {
+        old_source
+    };
+    // Additional locations are preserved if omitted
+    let additional_locations = if let Some(additional_locations) = additional_locations {
+        super::prepare_additional_locations(additional_locations)?
+    } else {
+        old_additional_locations
     };
 
     let homepage = homepage
@@ -123,6 +149,7 @@ pub fn prepare_updated_place<D: Db>(
         title,
         description,
         location: Location { pos, address },
+        additional_locations,
         contact: Some(Contact {
             email: email.map(Into::into),
             phone,
@@ -135,6 +162,7 @@ pub fn prepare_updated_place<D: Db>(
             .transpose()?,
         links,
         tags,
+        source,
     };
     place.validate()?;
     Ok(Storable(place))
@@ -146,8 +174,20 @@ pub fn store_updated_place<D: Db>(db: &D, s: Storable) -> Result<(Place, Vec<Rat
     for t in &place.tags {
         db.create_tag_if_it_does_not_exist(&Tag { id: t.clone() })?;
     }
+    let (old_place, _) = db.get_place(&place.id.as_str().into())?;
     db.create_or_update_place(place.clone())?;
-    let ratings = db.load_ratings_of_place(place.id.as_ref())?;
+    if let Some(ref source) = place.source {
+        if old_place.source.as_ref().map(|s| &s.external_id) != Some(&source.external_id) {
+            super::store_place_source(db, &place.id.as_str().into(), source)?;
+        }
+    }
+    let tags: Vec<_> = old_place
+        .tags
+        .into_iter()
+        .chain(place.tags.clone())
+        .collect();
+    super::add_pending_clearance_for_place(db, &tags, &place.id, Some(old_place.revision), None)?;
+    let ratings = db.load_ratings_of_place(&place.id.as_str().into())?;
     Ok((place, ratings))
 }
 
@@ -192,6 +232,8 @@ mod tests {
             tags        : vec![],
             image_url     : Some("img2".into()),
             image_link_url: old.links.as_ref().and_then(|l| l.image_href.as_ref()).map(|url| url.as_str().to_string()),
+            source        : None,
+            additional_locations: None,
         };
         let mut mock_db = MockDb::default();
         mock_db.entries = vec![(old, ReviewStatus::Created)].into();
@@ -264,6 +306,8 @@ mod tests {
             tags        : vec![],
             image_url     : None,
             image_link_url: None,
+            source        : None,
+            additional_locations: None,
         };
         let mut mock_db = MockDb::default();
         mock_db.entries = vec![(old, ReviewStatus::Created)].into();
@@ -309,6 +353,8 @@ mod tests {
             tags        : vec![],
             image_url     : None,
             image_link_url: None,
+            source        : None,
+            additional_locations: None,
         };
         let mut mock_db = MockDb::default();
         mock_db.entries = vec![].into();
@@ -357,14 +403,66 @@ mod tests {
             tags        : vec!["vegan".into()],
             image_url     : None,
             image_link_url: None,
+            source        : None,
+            additional_locations: None,
         };
         let mut mock_db = MockDb::default();
         mock_db.entries = vec![(old, ReviewStatus::Created)].into();
         mock_db.tags = vec![Tag { id: "bio".into() }, Tag { id: "fair".into() }].into();
         let storable = prepare_updated_place(&mock_db, id.clone(), new, None).unwrap();
         assert!(store_updated_place(&mock_db, storable).is_ok());
-        let (e, _) = mock_db.get_place(id.as_ref()).unwrap();
+        let (e, _) = mock_db.get_place(&id.as_str().into()).unwrap();
         assert_eq!(e.tags, vec!["vegan"]);
         assert_eq!(mock_db.tags.borrow().len(), 3);
     }
+
+    #[test]
+    fn keep_but_do_not_add_owned_tags() {
+        let id = Id::new();
+        let old = Place::build()
+            .id(id.as_ref())
+            .revision(1)
+            .tags(vec!["bio", "owned"])
+            .license("CC0-1.0")
+            .finish();
+        #[rustfmt::skip]
+        let new = |tags: Vec<&str>| UpdatePlace {
+            version     : 2,
+            title       : "foo".into(),
+            description : "bar".into(),
+            lat         : 0.0,
+            lng         : 0.0,
+            street      : None,
+            zip         : None,
+            city        : None,
+            country     : None,
+            state       : None,
+            email       : None,
+            telephone   : None,
+            homepage    : None,
+            opening_hours: None,
+            categories  : vec![],
+            tags        : tags.into_iter().map(Into::into).collect(),
+            image_url     : None,
+            image_link_url: None,
+            source        : None,
+            additional_locations: None,
+        };
+        let mut mock_db = MockDb::default();
+        mock_db.entries = vec![(old, ReviewStatus::Created)].into();
+        *mock_db.orgs.borrow_mut() = vec![Organization {
+            id: "org".into(),
+            name: "org".into(),
+            owned_tags: vec!["owned".into(), "other".into()],
+        }];
+        assert!(matches!(
+            prepare_updated_place(&mock_db, id.clone(), new(vec!["owned", "other"]), None),
+            Err(Error::Parameter(ParameterError::OwnedTag))
+        ));
+        let storable = prepare_updated_place(&mock_db, id, new(vec!["owned"]), None).unwrap();
+        store_updated_place(&mock_db, storable).unwrap();
+        let pending = mock_db.load_pending_clearances_for_places("org").unwrap();
+        assert_eq!(1, pending.len());
+        assert_eq!(Some(Revision::from(1)), pending[0].last_cleared_revision);
+    }
 }
@@ -0,0 +1,99 @@
+use crate::core::prelude::*;
+
+/// Create a personal API token for authorizing requests of
+/// a user without a session.
+pub fn create_user_api_token<D: Db>(
+    db: &D,
+    user_email: &str,
+    label: Option<String>,
+) -> Result<UserApiToken> {
+    let user = db.get_user_by_email(user_email)?;
+    let label = label
+        .map(|label| label.trim().to_owned())
+        .filter(|label| !label.is_empty());
+    let token = UserApiToken {
+        id: Id::new(),
+        user_email: user.email,
+        token: Nonce::new().to_string(),
+        label,
+        created_at: TimestampMs::now(),
+    };
+    info!(
+        "Creating API token {} of user {}",
+        token.id, token.user_email
+    );
+    db.create_user_api_token(&token)?;
+    Ok(token)
+}
+
+/// All personal API tokens of a user, oldest first.
+pub fn load_user_api_tokens<D: Db>(db: &D, user_email: &str) -> Result<Vec<UserApiToken>> {
+    Ok(db.load_user_api_tokens(user_email)?)
+}
+
+/// Revoke a personal API token of a user.
+pub fn delete_user_api_token<D: Db>(db: &D, user_email: &str, id: &str) -> Result<()> {
+    info!("Deleting API token {} of user {}", id, user_email);
+    Ok(db.delete_user_api_token(user_email, id)?)
+}
+
+/// Resolve a personal API token to its user.
+///
+/// Deactivated users are rejected as if they tried to log in.
+pub fn authorize_user_by_api_token<D: Db>(db: &D, token: &str) -> Result<User> {
+    let api_token = db.get_user_api_token(token).map_err(|e| match e {
+        RepoError::NotFound => Error::Parameter(ParameterError::Unauthorized),
+        _ => Error::Repo(e),
+    })?;
+    if !db.is_user_active(&api_token.user_email)? {
+        return Err(Error::Parameter(ParameterError::UserDeactivated));
+    }
+    Ok(db.get_user_by_email(&api_token.user_email)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    #[test]
+    fn create_and_revoke_api_tokens() {
+        let db = MockDb::default();
+        db.users.borrow_mut().push(User {
+            email: "user@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::User,
+            language: None,
+        });
+        assert!(create_user_api_token(&db, "unknown@example.com", None).is_err());
+
+        let token =
+            create_user_api_token(&db, "user@example.com", Some(" script ".into())).unwrap();
+        assert_eq!(Some("script"), token.label.as_deref());
+        let other = create_user_api_token(&db, "user@example.com", Some("".into())).unwrap();
+        assert_eq!(None, other.label);
+        assert_ne!(token.token, other.token);
+        assert_eq!(
+            2,
+            load_user_api_tokens(&db, "user@example.com").unwrap().len()
+        );
+        assert_eq!(
+            "user@example.com",
+            authorize_user_by_api_token(&db, &token.token)
+                .unwrap()
+                .email
+        );
+
+        delete_user_api_token(&db, "user@example.com", token.id.as_str()).unwrap();
+        assert!(matches!(
+            authorize_user_by_api_token(&db, &token.token),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+        assert!(matches!(
+            delete_user_api_token(&db, "user@example.com", token.id.as_str()),
+            Err(Error::Repo(RepoError::NotFound))
+        ));
+        assert!(authorize_user_by_api_token(&db, &other.token).is_ok());
+    }
+}
@@ -0,0 +1,59 @@
+use crate::core::prelude::*;
+
+// ISO 639-1 language codes, e.g. `de` or `en`
+fn is_valid_language(language: &str) -> bool {
+    language.len() == 2 && language.chars().all(|c| c.is_ascii_lowercase())
+}
+
+/// Choose the language of the e-mails that are sent to the
+/// user or fall back to the default language if none is given.
+pub fn set_user_language<D: Db>(db: &D, email: &str, language: Option<&str>) -> Result<()> {
+    let language = language
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| !l.is_empty());
+    if let Some(ref language) = language {
+        if !is_valid_language(language) {
+            return Err(ParameterError::InvalidLanguage.into());
+        }
+    }
+    let mut user = db.get_user_by_email(email)?;
+    user.language = language;
+    db.update_user(&user)?;
+    Ok(())
+}
+
+pub fn get_user_language<D: Db>(db: &D, email: &str) -> Result<Option<String>> {
+    Ok(db.get_user_by_email(email)?.language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_user, MockDb};
+    use super::*;
+
+    #[test]
+    fn change_the_language_of_emails() {
+        let db = MockDb::default();
+        create_user(&db, "user@example.com", Role::User);
+        assert_eq!(None, get_user_language(&db, "user@example.com").unwrap());
+
+        set_user_language(&db, "user@example.com", Some(" EN ")).unwrap();
+        assert_eq!(
+            Some("en".to_string()),
+            get_user_language(&db, "user@example.com").unwrap()
+        );
+
+        set_user_language(&db, "user@example.com", Some("")).unwrap();
+        assert_eq!(None, get_user_language(&db, "user@example.com").unwrap());
+    }
+
+    #[test]
+    fn reject_invalid_languages() {
+        let db = MockDb::default();
+        create_user(&db, "user@example.com", Role::User);
+        for language in &["english", "e", "e1", "de-DE"] {
+            assert!(set_user_language(&db, "user@example.com", Some(language)).is_err());
+        }
+        assert!(set_user_language(&db, "unknown@example.com", Some("en")).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use super::authorize_user_by_email;
 use crate::core::prelude::*;
 
 use chrono::{Duration, Utc};
@@ -23,7 +24,17 @@ pub fn consume_user_token<D: Db>(db: &D, email_nonce: &EmailNonce) -> Result<Use
     Ok(token)
 }
 
+/// Delete all expired user tokens and login links.
 pub fn delete_expired_user_tokens<D: Db>(db: &D) -> Result<usize> {
     let expired_before = Timestamp::now();
-    Ok(db.delete_expired_user_tokens(expired_before)?)
+    let count = db.delete_expired_user_tokens(expired_before)?;
+    Ok(count + db.delete_expired_login_links(expired_before)?)
+}
+
+/// All user tokens that have not expired yet.
+///
+/// Only admins are entitled to inspect the tokens of other users.
+pub fn load_valid_user_tokens<D: Db>(db: &D, account_email: &str) -> Result<Vec<UserToken>> {
+    authorize_user_by_email(db, account_email, Permission::ManageUsers)?;
+    Ok(db.load_valid_user_tokens(Timestamp::now())?)
 }
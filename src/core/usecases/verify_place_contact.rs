@@ -0,0 +1,147 @@
+use crate::core::prelude::*;
+
+use chrono::{Duration, Utc};
+
+fn contact_email(place: &Place) -> Option<&str> {
+    place
+        .contact
+        .as_ref()
+        .and_then(|contact| contact.email.as_ref())
+        .map(AsRef::as_ref)
+}
+
+/// Start the verification of the contact e-mail address of a place.
+///
+/// Returns the place together with the token that needs to be
+/// sent to the contact or `None` if the address has already
+/// been verified.
+pub fn request_contact_verification<D: Db>(
+    db: &D,
+    place_id: &PlaceId,
+) -> Result<Option<(Place, EmailNonce)>> {
+    let (place, _) = db.get_place(place_id)?;
+    let email = contact_email(&place).ok_or(ParameterError::Email)?;
+    if db.is_contact_verified(&place.id.as_str().into(), email)? {
+        return Ok(None);
+    }
+    let email_nonce = EmailNonce {
+        email: email.to_owned(),
+        nonce: Nonce::new(),
+    };
+    db.replace_contact_verification(&ContactVerification {
+        place_id: place.id.clone(),
+        email_nonce: email_nonce.clone(),
+        expires_at: Timestamp::from(Utc::now() + Duration::days(1)),
+        verified_at: None,
+    })?;
+    Ok(Some((place, email_nonce)))
+}
+
+/// Complete the verification of a contact e-mail address
+/// and return the id of the corresponding place.
+pub fn confirm_contact_verification<D: Db>(db: &D, token: &str) -> Result<Id> {
+    let email_nonce =
+        EmailNonce::decode_from_str(token).map_err(|_| ParameterError::TokenInvalid)?;
+    let mut verification = match db.load_contact_verification(&email_nonce) {
+        Ok(verification) => verification,
+        Err(RepoError::NotFound) => return Err(ParameterError::TokenInvalid.into()),
+        Err(err) => return Err(err.into()),
+    };
+    if verification.verified_at.is_some() {
+        return Ok(verification.place_id);
+    }
+    if verification.expires_at < Timestamp::now() {
+        return Err(ParameterError::TokenExpired.into());
+    }
+    // The contact might have been edited in the meantime
+    let (place, _) = db.get_place(&verification.place_id.as_str().into())?;
+    if contact_email(&place) != Some(email_nonce.email.as_str()) {
+        return Err(ParameterError::TokenInvalid.into());
+    }
+    verification.verified_at = Some(TimestampMs::now());
+    db.replace_contact_verification(&verification)?;
+    Ok(verification.place_id)
+}
+
+/// Check if the current contact e-mail address of a place has been verified
+pub fn is_contact_email_verified<D: Db>(db: &D, place: &Place) -> Result<bool> {
+    Ok(match contact_email(place) {
+        Some(email) => db.is_contact_verified(&place.id.as_str().into(), email)?,
+        None => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn new_place_with_contact(db: &MockDb, email: &str) -> Place {
+        let mut place = Place::build().id("p").finish();
+        place.contact = Some(Contact {
+            email: Some(email.into()),
+            phone: None,
+        });
+        db.entries
+            .borrow_mut()
+            .push((place.clone(), ReviewStatus::Created));
+        place
+    }
+
+    #[test]
+    fn verify_the_contact_email_of_a_place() {
+        let db = MockDb::default();
+        let place = new_place_with_contact(&db, "contact@example.com");
+        assert!(!is_contact_email_verified(&db, &place).unwrap());
+
+        let (_, email_nonce) = request_contact_verification(&db, &"p".into())
+            .unwrap()
+            .unwrap();
+        assert_eq!("contact@example.com", email_nonce.email);
+        assert!(!is_contact_email_verified(&db, &place).unwrap());
+
+        let place_id = confirm_contact_verification(&db, &email_nonce.encode_to_string()).unwrap();
+        assert_eq!("p", place_id.as_str());
+        assert!(is_contact_email_verified(&db, &place).unwrap());
+        assert!(request_contact_verification(&db, &"p".into())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn reject_tokens_of_outdated_contacts() {
+        let db = MockDb::default();
+        new_place_with_contact(&db, "old@example.com");
+        let (_, email_nonce) = request_contact_verification(&db, &"p".into())
+            .unwrap()
+            .unwrap();
+        db.entries.borrow_mut()[0].0.contact = Some(Contact {
+            email: Some("new@example.com".into()),
+            phone: None,
+        });
+        assert!(matches!(
+            confirm_contact_verification(&db, &email_nonce.encode_to_string()),
+            Err(Error::Parameter(ParameterError::TokenInvalid))
+        ));
+        let unknown = EmailNonce {
+            email: "new@example.com".into(),
+            nonce: Nonce::new(),
+        };
+        assert!(matches!(
+            confirm_contact_verification(&db, &unknown.encode_to_string()),
+            Err(Error::Parameter(ParameterError::TokenInvalid))
+        ));
+    }
+
+    #[test]
+    fn places_without_contact_email_cannot_be_verified() {
+        let db = MockDb::default();
+        db.entries
+            .borrow_mut()
+            .push((Place::build().id("p").finish(), ReviewStatus::Created));
+        assert!(matches!(
+            request_contact_verification(&db, &"p".into()),
+            Err(Error::Parameter(ParameterError::Email))
+        ));
+    }
+}
@@ -0,0 +1,337 @@
+use super::authorize_user_by_email;
+use crate::core::prelude::*;
+use serde_json::json;
+use url::Url;
+
+/// Pending deliveries are abandoned after this many failed attempts.
+pub const MAX_WEBHOOK_DELIVERY_ATTEMPTS: u32 = 8;
+
+// The delay after the first failed attempt that is doubled
+// after each subsequent failure, i.e. the last retry happens
+// about 2 hours after the first attempt
+const WEBHOOK_RETRY_DELAY_SECONDS: i64 = 60;
+
+#[derive(Debug, Clone)]
+pub struct NewWebhook {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+/// Register a callback URL for the given events.
+///
+/// The secret for verifying the signatures of requests is
+/// generated and only revealed to the admin through the
+/// returned webhook.
+pub fn create_webhook<D: Db>(
+    db: &D,
+    account_email: &str,
+    new_webhook: NewWebhook,
+) -> Result<Webhook> {
+    authorize_user_by_email(db, account_email, Permission::ManageWebhooks)?;
+    let NewWebhook { url, mut events } = new_webhook;
+    let url: Url = url
+        .trim()
+        .parse()
+        .map_err(|_| Error::Parameter(ParameterError::Url))?;
+    if url.scheme() != "https" && url.scheme() != "http" {
+        return Err(ParameterError::Url.into());
+    }
+    events.sort_unstable();
+    events.dedup();
+    if events.is_empty() {
+        return Err(ParameterError::EmptyWebhookEvents.into());
+    }
+    let webhook = Webhook {
+        id: Id::new(),
+        url,
+        secret: Nonce::new().to_string(),
+        events,
+        created_at: TimestampMs::now(),
+    };
+    info!("Creating webhook {} for {}", webhook.id, webhook.url);
+    db.create_webhook(&webhook)?;
+    Ok(webhook)
+}
+
+pub fn load_webhooks<D: Db>(db: &D, account_email: &str) -> Result<Vec<Webhook>> {
+    authorize_user_by_email(db, account_email, Permission::ManageWebhooks)?;
+    Ok(db.all_webhooks()?)
+}
+
+/// Delete a webhook together with its delivery log.
+pub fn delete_webhook<D: Db>(db: &D, account_email: &str, id: &str) -> Result<()> {
+    authorize_user_by_email(db, account_email, Permission::ManageWebhooks)?;
+    info!("Deleting webhook {}", id);
+    Ok(db.delete_webhook(id)?)
+}
+
+/// The delivery log of a webhook, newest first.
+pub fn load_webhook_deliveries<D: Db>(
+    db: &D,
+    account_email: &str,
+    webhook_id: &str,
+    pagination: &Pagination,
+) -> Result<Vec<WebhookDelivery>> {
+    authorize_user_by_email(db, account_email, Permission::ManageWebhooks)?;
+    // Unknown webhooks should not look like webhooks without deliveries
+    db.get_webhook(webhook_id)?;
+    Ok(db.load_webhook_deliveries(webhook_id, pagination)?)
+}
+
+/// The data of the payload only contains identifiers. Receivers
+/// need to load the changed items through the API that hides
+/// details like contact data depending on their permissions.
+pub fn place_webhook_data(place: &Place) -> serde_json::Value {
+    json!({
+        "id": place.id.as_str(),
+        "revision": u64::from(place.revision),
+    })
+}
+
+pub fn event_webhook_data(event: &Event) -> serde_json::Value {
+    json!({ "id": event.id.as_str() })
+}
+
+pub fn rating_webhook_data(rating: &Rating) -> serde_json::Value {
+    json!({
+        "id": rating.id.as_str(),
+        "place_id": rating.place_id.as_str(),
+    })
+}
+
+/// Create a pending delivery for each webhook that is
+/// subscribed to the event.
+///
+/// Returns the number of new deliveries.
+pub fn enqueue_webhook_deliveries<D: Db>(
+    db: &D,
+    event: WebhookEvent,
+    data: serde_json::Value,
+) -> Result<usize> {
+    let created_at = TimestampMs::now();
+    let payload = json!({
+        "event": event.as_str(),
+        "created_at": created_at.into_inner(),
+        "data": data,
+    })
+    .to_string();
+    let mut count = 0;
+    for webhook in db.all_webhooks()? {
+        if !webhook.is_subscribed_to(event) {
+            continue;
+        }
+        let delivery = WebhookDelivery {
+            id: Id::new(),
+            webhook_id: webhook.id,
+            event,
+            payload: payload.clone(),
+            created_at,
+            status: WebhookDeliveryStatus::Pending,
+            attempts: 0,
+            last_attempt_at: None,
+            next_attempt_at: Some(created_at),
+            response_status: None,
+        };
+        db.create_webhook_delivery(&delivery)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Record the outcome of an attempt to deliver a pending request.
+///
+/// The `response_status` is missing if no response has been
+/// received at all. Failed deliveries are retried with an
+/// exponential backoff until the maximum number of attempts
+/// has been reached.
+pub fn finish_webhook_delivery_attempt<D: Db>(
+    db: &D,
+    mut delivery: WebhookDelivery,
+    response_status: Option<u16>,
+    attempted_at: TimestampMs,
+) -> Result<WebhookDelivery> {
+    debug_assert_eq!(WebhookDeliveryStatus::Pending, delivery.status);
+    delivery.attempts += 1;
+    delivery.last_attempt_at = Some(attempted_at);
+    delivery.response_status = response_status;
+    let succeeded = response_status
+        .map(|status| (200..300).contains(&status))
+        .unwrap_or(false);
+    if succeeded {
+        delivery.status = WebhookDeliveryStatus::Delivered;
+        delivery.next_attempt_at = None;
+    } else if delivery.attempts >= MAX_WEBHOOK_DELIVERY_ATTEMPTS {
+        warn!(
+            "Giving up delivery {} to webhook {} after {} attempts",
+            delivery.id, delivery.webhook_id, delivery.attempts
+        );
+        delivery.status = WebhookDeliveryStatus::Failed;
+        delivery.next_attempt_at = None;
+    } else {
+        let delay_seconds = WEBHOOK_RETRY_DELAY_SECONDS << (delivery.attempts - 1);
+        delivery.next_attempt_at = Some(TimestampMs::from_seconds(
+            attempted_at.into_seconds() + delay_seconds,
+        ));
+    }
+    db.update_webhook_delivery(&delivery)?;
+    Ok(delivery)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_user, MockDb};
+    use super::*;
+
+    #[test]
+    fn manage_webhooks_as_admin() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_user(&db, "scout@example.com", Role::Scout);
+        let new_webhook = NewWebhook {
+            url: "https://example.com/hook".into(),
+            events: vec![WebhookEvent::PlaceUpdated, WebhookEvent::PlaceCreated],
+        };
+        assert!(create_webhook(&db, "scout@example.com", new_webhook.clone()).is_err());
+        assert!(create_webhook(
+            &db,
+            "admin@example.com",
+            NewWebhook {
+                url: "ftp://example.com".into(),
+                ..new_webhook.clone()
+            }
+        )
+        .is_err());
+        assert!(create_webhook(
+            &db,
+            "admin@example.com",
+            NewWebhook {
+                events: vec![],
+                ..new_webhook.clone()
+            }
+        )
+        .is_err());
+
+        let webhook = create_webhook(&db, "admin@example.com", new_webhook).unwrap();
+        assert_eq!(
+            vec![WebhookEvent::PlaceCreated, WebhookEvent::PlaceUpdated],
+            webhook.events
+        );
+        assert!(!webhook.secret.is_empty());
+        assert_eq!(
+            vec![webhook.clone()],
+            load_webhooks(&db, "admin@example.com").unwrap()
+        );
+        assert!(load_webhooks(&db, "scout@example.com").is_err());
+
+        delete_webhook(&db, "admin@example.com", webhook.id.as_str()).unwrap();
+        assert!(load_webhooks(&db, "admin@example.com").unwrap().is_empty());
+    }
+
+    #[test]
+    fn enqueue_deliveries_for_subscribed_webhooks() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        let places = create_webhook(
+            &db,
+            "admin@example.com",
+            NewWebhook {
+                url: "https://example.com/places".into(),
+                events: vec![WebhookEvent::PlaceCreated],
+            },
+        )
+        .unwrap();
+        create_webhook(
+            &db,
+            "admin@example.com",
+            NewWebhook {
+                url: "https://example.com/events".into(),
+                events: vec![WebhookEvent::EventCreated],
+            },
+        )
+        .unwrap();
+        let place = Place::build().id("foo").finish();
+
+        let count =
+            enqueue_webhook_deliveries(&db, WebhookEvent::PlaceCreated, place_webhook_data(&place))
+                .unwrap();
+        assert_eq!(1, count);
+        let deliveries = load_webhook_deliveries(
+            &db,
+            "admin@example.com",
+            places.id.as_str(),
+            &Default::default(),
+        )
+        .unwrap();
+        assert_eq!(1, deliveries.len());
+        let delivery = &deliveries[0];
+        assert_eq!(WebhookDeliveryStatus::Pending, delivery.status);
+        let payload: serde_json::Value = serde_json::from_str(&delivery.payload).unwrap();
+        assert_eq!("place.created", payload["event"]);
+        assert_eq!("foo", payload["data"]["id"]);
+        assert!(
+            load_webhook_deliveries(&db, "admin@example.com", "unknown", &Default::default())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn retry_failed_deliveries_with_backoff() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_webhook(
+            &db,
+            "admin@example.com",
+            NewWebhook {
+                url: "https://example.com/ratings".into(),
+                events: vec![WebhookEvent::RatingAdded],
+            },
+        )
+        .unwrap();
+        enqueue_webhook_deliveries(&db, WebhookEvent::RatingAdded, json!({})).unwrap();
+        let now = TimestampMs::now();
+        let mut delivery = db.load_due_webhook_deliveries(now, 10).unwrap().remove(0);
+
+        delivery = finish_webhook_delivery_attempt(&db, delivery, Some(500), now).unwrap();
+        assert_eq!(WebhookDeliveryStatus::Pending, delivery.status);
+        assert_eq!(Some(500), delivery.response_status);
+        let first_retry_at = TimestampMs::from_seconds(now.into_seconds() + 60);
+        assert_eq!(Some(first_retry_at), delivery.next_attempt_at);
+        assert!(db.load_due_webhook_deliveries(now, 10).unwrap().is_empty());
+
+        delivery = finish_webhook_delivery_attempt(&db, delivery, None, first_retry_at).unwrap();
+        assert_eq!(
+            Some(TimestampMs::from_seconds(
+                first_retry_at.into_seconds() + 120
+            )),
+            delivery.next_attempt_at
+        );
+
+        delivery = finish_webhook_delivery_attempt(&db, delivery, Some(204), now).unwrap();
+        assert_eq!(WebhookDeliveryStatus::Delivered, delivery.status);
+        assert_eq!(3, delivery.attempts);
+        assert_eq!(None, delivery.next_attempt_at);
+    }
+
+    #[test]
+    fn give_up_after_max_attempts() {
+        let db = MockDb::default();
+        create_user(&db, "admin@example.com", Role::Admin);
+        create_webhook(
+            &db,
+            "admin@example.com",
+            NewWebhook {
+                url: "https://example.com/events".into(),
+                events: vec![WebhookEvent::EventCreated],
+            },
+        )
+        .unwrap();
+        enqueue_webhook_deliveries(&db, WebhookEvent::EventCreated, json!({})).unwrap();
+        let now = TimestampMs::now();
+        let mut delivery = db.load_due_webhook_deliveries(now, 10).unwrap().remove(0);
+        for _ in 0..MAX_WEBHOOK_DELIVERY_ATTEMPTS {
+            delivery = finish_webhook_delivery_attempt(&db, delivery, Some(404), now).unwrap();
+        }
+        assert_eq!(WebhookDeliveryStatus::Failed, delivery.status);
+        assert_eq!(None, delivery.next_attempt_at);
+    }
+}
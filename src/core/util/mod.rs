@@ -1,4 +1,5 @@
 pub mod parse;
+pub mod quality;
 pub mod validate;
 
 use regex::Regex;
@@ -16,6 +17,8 @@ pub fn split_ids(ids: &str) -> Vec<&str> {
 
 lazy_static! {
     static ref HASH_TAG_REGEX: Regex = Regex::new(r"#(?P<tag>\w+((-\w+)*)?)").unwrap();
+    static ref EXCLUDED_HASH_TAG_REGEX: Regex =
+        Regex::new(r"(^|\s)-#(?P<tag>\w+((-\w+)*)?)").unwrap();
 }
 
 pub fn extract_hash_tags(text: &str) -> Vec<String> {
@@ -26,6 +29,22 @@ pub fn extract_hash_tags(text: &str) -> Vec<String> {
     res
 }
 
+// Hash tags with a leading minus sign, e.g. `-#tag`
+pub fn extract_excluded_hash_tags(text: &str) -> Vec<String> {
+    EXCLUDED_HASH_TAG_REGEX
+        .captures_iter(text)
+        .map(|cap| cap["tag"].into())
+        .collect()
+}
+
+pub fn remove_excluded_hash_tags(text: &str) -> String {
+    // The preceding whitespace is removed together with the tag
+    EXCLUDED_HASH_TAG_REGEX
+        .replace_all(text, "")
+        .trim()
+        .to_owned()
+}
+
 pub fn remove_hash_tags(text: &str) -> String {
     HASH_TAG_REGEX
         .replace_all(text, "")
@@ -79,4 +98,18 @@ mod tests {
         assert_eq!(remove_hash_tags("#tag"), "");
         assert_eq!(remove_hash_tags("some #text with #tags"), "some with");
     }
+
+    #[test]
+    fn excluded_hash_tag_extractor_test() {
+        assert_eq!(extract_excluded_hash_tags("#bar").len(), 0);
+        assert_eq!(extract_excluded_hash_tags("foo-#bar").len(), 0);
+        assert_eq!(
+            extract_excluded_hash_tags("-#bar foo -#a-long-tag"),
+            vec!["bar".to_string(), "a-long-tag".to_string()]
+        );
+        assert_eq!(
+            remove_hash_tags(&remove_excluded_hash_tags("some -#bar #baz text")),
+            "some text"
+        );
+    }
 }
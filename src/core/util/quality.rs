@@ -0,0 +1,176 @@
+use crate::core::entities::*;
+
+// Places with fewer tags are hard to find
+pub const MIN_TAG_COUNT: usize = 3;
+
+// Places that have not been edited within this period
+// are considered as outdated
+pub const MAX_VERIFICATION_AGE_MILLIS: i64 = 365 * 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualityHint {
+    MissingAddress,
+    MissingContact,
+    MissingOpeningHours,
+    MissingImage,
+    TooFewTags,
+    NotRecentlyVerified,
+}
+
+impl QualityHint {
+    pub const fn total_count() -> u8 {
+        6
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaceQuality {
+    // Fraction of fulfilled criteria in the range [0.0, 1.0]
+    pub score: f64,
+    // Suggestions for improving the score
+    pub hints: Vec<QualityHint>,
+}
+
+fn score(hint_count: usize) -> f64 {
+    let total_count = f64::from(QualityHint::total_count());
+    let score = (total_count - hint_count as f64) / total_count;
+    debug_assert!(score >= 0.0 && score <= 1.0);
+    score
+}
+
+/// Places are verified by editing them, i.e. the time stamp
+/// of the current revision.
+pub fn verified_at(place: &Place) -> TimestampMs {
+    place.created.at
+}
+
+pub fn is_recently_verified(verified_at: TimestampMs, now: TimestampMs) -> bool {
+    now.into_milliseconds() - verified_at.into_milliseconds() <= MAX_VERIFICATION_AGE_MILLIS
+}
+
+// All hints that don't change over time
+fn completeness_hints(place: &Place) -> Vec<QualityHint> {
+    let mut hints = Vec::with_capacity(QualityHint::total_count().into());
+    if place
+        .location
+        .address
+        .as_ref()
+        .map_or(true, |a| a.is_empty())
+    {
+        hints.push(QualityHint::MissingAddress);
+    }
+    if place.contact.as_ref().map_or(true, |c| c.is_empty()) {
+        hints.push(QualityHint::MissingContact);
+    }
+    if place.opening_hours.is_none() {
+        hints.push(QualityHint::MissingOpeningHours);
+    }
+    if place.links.as_ref().map_or(true, |l| l.image.is_none()) {
+        hints.push(QualityHint::MissingImage);
+    }
+    if place.tags.len() < MIN_TAG_COUNT {
+        hints.push(QualityHint::TooFewTags);
+    }
+    hints
+}
+
+pub fn place_quality(place: &Place, now: TimestampMs) -> PlaceQuality {
+    let mut hints = completeness_hints(place);
+    if !is_recently_verified(verified_at(place), now) {
+        hints.push(QualityHint::NotRecentlyVerified);
+    }
+    PlaceQuality {
+        score: score(hints.len()),
+        hints,
+    }
+}
+
+/// The score of a place as if it has been verified recently.
+///
+/// Only this part of the score is stored in the search index,
+/// because it doesn't change over time. The final score is
+/// calculated when querying the index from the time stamp of
+/// the last verification, see `quality_score_at()`.
+pub fn completeness_score(place: &Place) -> f64 {
+    score(completeness_hints(place).len())
+}
+
+pub fn quality_score_at(
+    completeness_score: f64,
+    verified_at: TimestampMs,
+    now: TimestampMs,
+) -> f64 {
+    if is_recently_verified(verified_at, now) {
+        completeness_score
+    } else {
+        (completeness_score - 1.0 / f64::from(QualityHint::total_count())).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn incomplete_place() {
+        let now = TimestampMs::now();
+        let place = Place::build().id("a").finish();
+        let quality = place_quality(&place, now);
+        assert_eq!(
+            vec![
+                QualityHint::MissingAddress,
+                QualityHint::MissingContact,
+                QualityHint::MissingOpeningHours,
+                QualityHint::MissingImage,
+                QualityHint::TooFewTags,
+            ],
+            quality.hints
+        );
+        assert!(quality.score > 0.0);
+        assert!(quality.score < 0.5);
+    }
+
+    #[test]
+    fn complete_place() {
+        let mut place = Place::build()
+            .id("a")
+            .tags(vec!["a", "b", "c"])
+            .image_url(Some("https://img.example.com/a.png"))
+            .finish();
+        place.location.address = Some(Address {
+            city: Some("Stuttgart".into()),
+            ..Default::default()
+        });
+        place.contact = Some(Contact {
+            email: None,
+            phone: Some("0123".into()),
+        });
+        place.opening_hours = Some("Mo-Fr 08:00-18:00".parse().unwrap());
+        let quality = place_quality(&place, place.created.at);
+        assert!(quality.hints.is_empty());
+        assert!((quality.score - 1.0).abs() < f64::EPSILON);
+
+        let later = TimestampMs::from_milliseconds(
+            place.created.at.into_milliseconds() + MAX_VERIFICATION_AGE_MILLIS + 1,
+        );
+        let quality = place_quality(&place, later);
+        assert_eq!(vec![QualityHint::NotRecentlyVerified], quality.hints);
+        assert!(quality.score < 1.0);
+    }
+
+    #[test]
+    fn quality_score_at_query_time() {
+        let place = Place::build().id("a").tags(vec!["a", "b", "c"]).finish();
+        let completeness_score = completeness_score(&place);
+        let verified_at = verified_at(&place);
+        let score = quality_score_at(completeness_score, verified_at, verified_at);
+        assert!((place_quality(&place, verified_at).score - score).abs() < f64::EPSILON);
+
+        let later = TimestampMs::from_milliseconds(
+            verified_at.into_milliseconds() + MAX_VERIFICATION_AGE_MILLIS + 1,
+        );
+        let score = quality_score_at(completeness_score, verified_at, later);
+        assert!(score < completeness_score);
+        assert!((place_quality(&place, later).score - score).abs() < f64::EPSILON);
+    }
+}
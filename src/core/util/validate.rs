@@ -114,6 +114,16 @@ impl Validate for Event {
                 return Err(ParameterError::EndDateBeforeStart);
             }
         }
+        if let Some(ref recurrence) = self.recurrence {
+            if recurrence.interval == 0 {
+                return Err(ParameterError::InvalidRecurrence);
+            }
+            if let Some(until) = recurrence.until {
+                if until < self.start || until > max_until {
+                    return Err(ParameterError::InvalidRecurrence);
+                }
+            }
+        }
         Ok(())
     }
 }
@@ -199,6 +209,8 @@ mod tests {
             archived: None,
             image_url: None,
             image_link_url: None,
+            recurrence: None,
+            capacity: None,
         };
 
         let mut x = e.clone();
@@ -302,6 +314,8 @@ mod tests {
             archived: None,
             image_url: None,
             image_link_url: None,
+            recurrence: None,
+            capacity: None,
         };
         assert!(e.validate().is_ok());
         assert!(Event {
@@ -324,7 +338,7 @@ mod tests {
         .is_err());
         assert!(Event {
             start: min_valid_event_date_time(now) + Duration::seconds(10),
-            ..e.clone()
+            ..e
         }
         .validate()
         .is_ok());
@@ -348,10 +362,71 @@ mod tests {
             archived: None,
             image_url: None,
             image_link_url: None,
+            recurrence: None,
+            capacity: None,
         };
         assert!(e.validate().is_err());
     }
 
+    #[test]
+    fn event_with_invalid_recurrence_test() {
+        let now = Utc::now().naive_utc();
+        let e = Event {
+            id: "x".into(),
+            title: "foo".into(),
+            description: None,
+            start: now,
+            end: None,
+            location: None,
+            contact: None,
+            tags: vec![],
+            homepage: None,
+            created_by: None,
+            registration: None,
+            organizer: None,
+            archived: None,
+            image_url: None,
+            image_link_url: None,
+            recurrence: Some(Recurrence {
+                frequency: RecurrenceFrequency::Weekly,
+                interval: 1,
+                until: None,
+            }),
+            capacity: None,
+        };
+        assert!(e.validate().is_ok());
+        assert!(Event {
+            recurrence: Some(Recurrence {
+                frequency: RecurrenceFrequency::Monthly,
+                interval: 0,
+                until: None,
+            }),
+            ..e.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(Event {
+            recurrence: Some(Recurrence {
+                frequency: RecurrenceFrequency::Weekly,
+                interval: 2,
+                until: Some(now - Duration::days(1)),
+            }),
+            ..e.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(Event {
+            recurrence: Some(Recurrence {
+                frequency: RecurrenceFrequency::Weekly,
+                interval: 2,
+                until: Some(now + Duration::days(30)),
+            }),
+            ..e
+        }
+        .validate()
+        .is_ok());
+    }
+
     #[test]
     fn bbox_test() {
         let p1 = MapPoint::from_lat_lng_deg(48.123, 5.123);
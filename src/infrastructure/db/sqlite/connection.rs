@@ -7,16 +7,93 @@ use diesel::{
     prelude::{Connection as DieselConnection, *},
     result::{DatabaseErrorKind, Error as DieselError},
 };
-use std::result;
+use std::{collections::HashMap, result};
 use url::Url;
 
 type Result<T> = result::Result<T, RepoError>;
 
+// SQLite limits the number of host parameters per statement,
+// i.e. the number of ids that can be passed to `eq_any()`
+const MAX_IDS_PER_QUERY: usize = 500;
+
+// Replaces the address of a deleted user in pending e-mails and the audit log
+const DELETED_EMAIL_PLACEHOLDER: &str = "[deleted]";
+
+// Split the loading of many items into multiple queries
+// with a limited number of ids
+fn load_in_chunks<T>(
+    ids: &[&str],
+    mut load_chunk: impl FnMut(&[&str]) -> Result<Vec<T>>,
+) -> Result<Vec<T>> {
+    if ids.len() > MAX_IDS_PER_QUERY {
+        info!(
+            "Loading multiple ({}) items in chunks of {}",
+            ids.len(),
+            MAX_IDS_PER_QUERY
+        );
+    }
+    let mut results = Vec::with_capacity(ids.len());
+    for chunk in ids.chunks(MAX_IDS_PER_QUERY) {
+        results.extend(load_chunk(chunk)?);
+    }
+    Ok(results)
+}
+
 fn load_review_status(status: ReviewStatusPrimitive) -> Result<ReviewStatus> {
     ReviewStatus::try_from(status)
         .ok_or_else(|| RepoError::Other(anyhow!("Invalid review status: {}", status)))
 }
 
+fn load_place_source(
+    importer: Option<String>,
+    external_ref: Option<String>,
+    imported_at: Option<i64>,
+) -> Option<PlaceSource> {
+    match (importer, external_ref, imported_at) {
+        (Some(importer), Some(external_ref), Some(imported_at)) => Some(PlaceSource {
+            external_id: ExternalId {
+                source: importer,
+                id: external_ref,
+                url: None,
+            },
+            imported_at: TimestampMs::from_inner(imported_at),
+        }),
+        _ => None,
+    }
+}
+
+fn load_additional_locations(conn: &SqliteConnection, parent_rowid: i64) -> Result<Vec<Location>> {
+    use schema::place_revision_location::dsl;
+    Ok(schema::place_revision_location::table
+        .filter(dsl::parent_rowid.eq(parent_rowid))
+        .order_by(dsl::rowid)
+        .load::<models::PlaceRevisionLocation>(conn)?
+        .into_iter()
+        .map(|l| {
+            let models::PlaceRevisionLocation {
+                lat,
+                lon,
+                street,
+                zip,
+                city,
+                country,
+                state,
+                ..
+            } = l;
+            Location {
+                pos: MapPoint::try_from_lat_lng_deg(lat, lon).unwrap_or_default(),
+                address: Some(Address {
+                    street,
+                    zip,
+                    city,
+                    country,
+                    state,
+                }),
+            }
+        })
+        .collect())
+}
+
 fn load_place(
     conn: &SqliteConnection,
     place: models::JoinedPlaceRevision,
@@ -44,6 +121,9 @@ fn load_place(
         opening_hours,
         image_url,
         image_link_url,
+        source_importer,
+        source_external_ref,
+        source_imported_at,
         ..
     } = place;
 
@@ -65,6 +145,7 @@ fn load_place(
         .into_iter()
         .map(|r| r.tag)
         .collect();
+    let additional_locations = load_additional_locations(conn, id)?;
 
     let created_by = if let Some(user_id) = created_by_id {
         use schema::users::dsl;
@@ -89,6 +170,7 @@ fn load_place(
         title,
         description,
         location,
+        additional_locations,
         contact: Some(Contact {
             email: email.map(Into::into),
             phone,
@@ -100,6 +182,7 @@ fn load_place(
         }),
         opening_hours: opening_hours.map(Into::into),
         tags,
+        source: load_place_source(source_importer, source_external_ref, source_imported_at),
     };
 
     Ok((place, load_review_status(current_status)?))
@@ -129,6 +212,9 @@ fn load_place_with_status_review(
         opening_hours,
         image_url,
         image_link_url,
+        source_importer,
+        source_external_ref,
+        source_imported_at,
         place_id,
         place_license: license,
         review_created_at,
@@ -157,6 +243,7 @@ fn load_place_with_status_review(
         .into_iter()
         .map(|r| r.tag)
         .collect();
+    let additional_locations = load_additional_locations(conn, id)?;
 
     let created_by = if let Some(user_id) = created_by_id {
         use schema::users::dsl;
@@ -206,10 +293,12 @@ fn load_place_with_status_review(
         title,
         description,
         location,
+        additional_locations,
         contact: Some(contact),
         opening_hours: opening_hours.map(Into::into),
         links: Some(links),
         tags,
+        source: load_place_source(source_importer, source_external_ref, source_imported_at),
     };
 
     let activity_log = ActivityLog {
@@ -225,14 +314,77 @@ fn load_place_with_status_review(
 }
 
 #[derive(QueryableByName)]
-struct TagCountRow {
+struct MonthlyCountRow {
+    #[sql_type = "diesel::sql_types::Integer"]
+    year: i32,
+
+    #[sql_type = "diesel::sql_types::Integer"]
+    month: i32,
+
+    #[sql_type = "diesel::sql_types::BigInt"]
+    count: i64,
+}
+
+#[derive(QueryableByName)]
+struct MonthlyRatingSumRow {
+    #[sql_type = "diesel::sql_types::Integer"]
+    year: i32,
+
+    #[sql_type = "diesel::sql_types::Integer"]
+    month: i32,
+
     #[sql_type = "diesel::sql_types::Text"]
-    tag: String,
+    context: String,
+
+    #[sql_type = "diesel::sql_types::BigInt"]
+    sum: i64,
 
     #[sql_type = "diesel::sql_types::BigInt"]
     count: i64,
 }
 
+#[derive(QueryableByName)]
+struct ModeratedCommentRow {
+    #[sql_type = "diesel::sql_types::Text"]
+    comment_id: String,
+
+    #[sql_type = "diesel::sql_types::BigInt"]
+    comment_created_at: i64,
+
+    #[sql_type = "diesel::sql_types::Text"]
+    comment_text: String,
+
+    #[sql_type = "diesel::sql_types::Text"]
+    rating_id: String,
+
+    #[sql_type = "diesel::sql_types::BigInt"]
+    rating_created_at: i64,
+
+    #[sql_type = "diesel::sql_types::Nullable<diesel::sql_types::Text>"]
+    rating_created_by: Option<String>,
+
+    #[sql_type = "diesel::sql_types::Text"]
+    rating_title: String,
+
+    #[sql_type = "diesel::sql_types::SmallInt"]
+    rating_value: i16,
+
+    #[sql_type = "diesel::sql_types::Text"]
+    rating_context: String,
+
+    #[sql_type = "diesel::sql_types::Nullable<diesel::sql_types::Text>"]
+    rating_source: Option<String>,
+
+    #[sql_type = "diesel::sql_types::Text"]
+    place_id: String,
+
+    #[sql_type = "diesel::sql_types::Text"]
+    place_title: String,
+
+    #[sql_type = "diesel::sql_types::BigInt"]
+    flag_count: i64,
+}
+
 fn resolve_place_rowid(conn: &SqliteConnection, id: &Id) -> Result<(i64, Revision)> {
     use schema::place::dsl;
     Ok(schema::place::table
@@ -258,10 +410,21 @@ fn resolve_rating_rowid(conn: &SqliteConnection, id: &str) -> Result<i64> {
         })?)
 }
 
+type NewPlaceRevisionRecords = (
+    Id,
+    Option<Revision>,
+    models::NewPlaceRevision,
+    Vec<String>,
+    Vec<Location>,
+);
+
+// Returns the current revision of an existing place that is
+// replaced by the new revision or None for a new place. The
+// head pointer of an existing place is NOT updated here!
 fn into_new_place_revision(
     conn: &SqliteConnection,
     place: Place,
-) -> Result<(Id, models::NewPlaceRevision, Vec<String>)> {
+) -> Result<NewPlaceRevisionRecords> {
     let Place {
         id: place_id,
         license,
@@ -270,12 +433,14 @@ fn into_new_place_revision(
         title,
         description,
         location: Location { pos, address },
+        additional_locations,
         contact,
         opening_hours,
         tags,
         links,
+        source,
     } = place;
-    let parent_rowid = if new_revision.is_initial() {
+    let (parent_rowid, replaced_revision) = if new_revision.is_initial() {
         // Create a new place
         let new_place = models::NewPlace {
             id: place_id.as_ref(),
@@ -287,7 +452,7 @@ fn into_new_place_revision(
             .execute(conn)?;
         let (rowid, _revision) = resolve_place_rowid(conn, &place_id)?;
         debug_assert_eq!(new_revision, _revision);
-        rowid
+        (rowid, None)
     } else {
         // Update the existing place with a new revision
         let (rowid, revision) = resolve_place_rowid(conn, &place_id)?;
@@ -295,16 +460,7 @@ fn into_new_place_revision(
         if revision.next() != new_revision {
             return Err(RepoError::InvalidVersion);
         }
-        use schema::place::dsl;
-        let _count = diesel::update(
-            schema::place::table
-                .filter(dsl::rowid.eq(rowid))
-                .filter(dsl::current_rev.eq(u64::from(revision) as i64)),
-        )
-        .set(dsl::current_rev.eq(u64::from(new_revision) as i64))
-        .execute(conn)?;
-        debug_assert_eq!(1, _count);
-        rowid
+        (rowid, Some(revision))
     };
     let created_by = if let Some(ref email) = created.by {
         Some(resolve_user_created_by_email(conn, email.as_ref())?)
@@ -325,6 +481,15 @@ fn into_new_place_revision(
         image: image_url,
         image_href: image_link_url,
     } = links.unwrap_or_default();
+    let (source_importer, source_external_ref, source_imported_at) = source
+        .map(|s| {
+            (
+                Some(s.external_id.source),
+                Some(s.external_id.id),
+                Some(s.imported_at.into_inner()),
+            )
+        })
+        .unwrap_or_default();
     let new_place = models::NewPlaceRevision {
         parent_rowid,
         rev: u64::from(new_revision) as i64,
@@ -346,60 +511,136 @@ fn into_new_place_revision(
         opening_hours: opening_hours.map(Into::into),
         image_url: image_url.map(Url::into_string),
         image_link_url: image_link_url.map(Url::into_string),
+        source_importer,
+        source_external_ref,
+        source_imported_at,
     };
-    Ok((place_id, new_place, tags))
+    Ok((
+        place_id,
+        replaced_revision,
+        new_place,
+        tags,
+        additional_locations,
+    ))
 }
 
 impl PlaceRepo for SqliteConnection {
     fn create_or_update_place(&self, place: Place) -> Result<()> {
-        let (_place_id, new_place, tags) = into_new_place_revision(self, place)?;
-        diesel::insert_into(schema::place_revision::table)
-            .values(&new_place)
-            .execute(self)?;
+        // All statements are executed within a single transaction. The
+        // head pointer of an existing place is only moved after the new
+        // revision has been stored completely.
+        self.transaction::<_, RepoError, _>(|| {
+            let (place_id, replaced_revision, new_place, tags, additional_locations) =
+                into_new_place_revision(self, place)?;
+            diesel::insert_into(schema::place_revision::table)
+                .values(&new_place)
+                .execute(self)?;
 
-        use schema::place_revision::dsl;
-        let parent_rowid = schema::place_revision::table
-            .select(dsl::rowid)
-            .filter(dsl::parent_rowid.eq(new_place.parent_rowid))
-            .filter(dsl::rev.eq(new_place.rev))
-            .first::<i64>(self)
-            .map_err(|e| {
-                log::warn!(
-                    "Newly inserted place {} revision {} not found: {}",
-                    new_place.parent_rowid,
-                    new_place.rev,
+            use schema::place_revision::dsl;
+            let parent_rowid = schema::place_revision::table
+                .select(dsl::rowid)
+                .filter(dsl::parent_rowid.eq(new_place.parent_rowid))
+                .filter(dsl::rev.eq(new_place.rev))
+                .first::<i64>(self)
+                .map_err(|e| {
+                    log::warn!(
+                        "Newly inserted place {} revision {} not found: {}",
+                        new_place.parent_rowid,
+                        new_place.rev,
+                        e
+                    );
                     e
-                );
-                e
-            })?;
-
-        // Insert into place_revision_review
-        let new_review = models::NewPlaceRevisionReview {
-            parent_rowid,
-            rev: u64::from(Revision::initial()) as i64,
-            created_at: new_place.created_at,
-            created_by: new_place.created_by,
-            status: new_place.current_status,
-            context: None,
-            comment: Some("created"),
-        };
-        diesel::insert_into(schema::place_revision_review::table)
-            .values(new_review)
-            .execute(self)?;
+                })?;
 
-        // Insert into place_revision_tag
-        let tags: Vec<_> = tags
-            .iter()
-            .map(|tag| models::NewPlaceRevisionTag {
+            // Insert into place_revision_review
+            let new_review = models::NewPlaceRevisionReview {
                 parent_rowid,
-                tag: tag.as_str(),
-            })
-            .collect();
-        diesel::insert_into(schema::place_revision_tag::table)
-            .values(&tags)
-            .execute(self)?;
+                rev: u64::from(Revision::initial()) as i64,
+                created_at: new_place.created_at,
+                created_by: new_place.created_by,
+                status: new_place.current_status,
+                context: None,
+                comment: Some("created"),
+            };
+            diesel::insert_into(schema::place_revision_review::table)
+                .values(new_review)
+                .execute(self)?;
 
-        Ok(())
+            // Insert into place_revision_tag
+            let tags: Vec<_> = tags
+                .iter()
+                .map(|tag| models::NewPlaceRevisionTag {
+                    parent_rowid,
+                    tag: tag.as_str(),
+                })
+                .collect();
+            diesel::insert_into(schema::place_revision_tag::table)
+                .values(&tags)
+                .execute(self)?;
+
+            // Insert into place_revision_location
+            let additional_locations: Vec<_> = additional_locations
+                .into_iter()
+                .map(|Location { pos, address }| {
+                    debug_assert!(pos.is_valid());
+                    let Address {
+                        street,
+                        zip,
+                        city,
+                        country,
+                        state,
+                    } = address.unwrap_or_default();
+                    models::NewPlaceRevisionLocation {
+                        parent_rowid,
+                        lat: pos.lat().to_deg(),
+                        lon: pos.lng().to_deg(),
+                        street,
+                        zip,
+                        city,
+                        country,
+                        state,
+                    }
+                })
+                .collect();
+            diesel::insert_into(schema::place_revision_location::table)
+                .values(&additional_locations)
+                .execute(self)?;
+
+            if let Some(replaced_revision) = replaced_revision {
+                // Move the head pointer to the new revision (optimistic locking)
+                use schema::place::dsl as place_dsl;
+                let count = diesel::update(
+                    schema::place::table
+                        .filter(place_dsl::rowid.eq(new_place.parent_rowid))
+                        .filter(place_dsl::current_rev.eq(u64::from(replaced_revision) as i64)),
+                )
+                .set(place_dsl::current_rev.eq(new_place.rev))
+                .execute(self)?;
+                if count != 1 {
+                    log::warn!(
+                        "Failed to update current revision of place {}: {} -> {}",
+                        place_id,
+                        u64::from(replaced_revision),
+                        new_place.rev
+                    );
+                    return Err(RepoError::InvalidVersion);
+                }
+            }
+
+            // The head pointer must refer to the newly inserted revision
+            let (_, current_rev) = resolve_place_rowid(self, &place_id)?;
+            if u64::from(current_rev) as i64 != new_place.rev {
+                log::warn!(
+                    "Current revision {} of place {} does not match the new revision {}",
+                    u64::from(current_rev),
+                    place_id,
+                    new_place.rev
+                );
+                return Err(RepoError::InvalidVersion);
+            }
+
+            Ok(())
+        })
     }
 
     fn review_places(
@@ -475,55 +716,61 @@ impl PlaceRepo for SqliteConnection {
         use schema::place::dsl;
         use schema::place_revision::dsl as rev_dsl;
 
-        let mut query = schema::place_revision::table
-            .inner_join(
-                schema::place::table.on(rev_dsl::parent_rowid
-                    .eq(dsl::rowid)
-                    .and(rev_dsl::rev.eq(dsl::current_rev))),
-            )
-            .select((
-                rev_dsl::rowid,
-                rev_dsl::rev,
-                rev_dsl::created_at,
-                rev_dsl::created_by,
-                rev_dsl::current_status,
-                rev_dsl::title,
-                rev_dsl::description,
-                rev_dsl::lat,
-                rev_dsl::lon,
-                rev_dsl::street,
-                rev_dsl::zip,
-                rev_dsl::city,
-                rev_dsl::country,
-                rev_dsl::state,
-                rev_dsl::email,
-                rev_dsl::phone,
-                rev_dsl::homepage,
-                rev_dsl::opening_hours,
-                rev_dsl::image_url,
-                rev_dsl::image_link_url,
-                dsl::id,
-                dsl::license,
-            ))
-            .into_boxed();
+        let load_places = |place_ids: &[&str]| -> Result<Vec<_>> {
+            let mut query = schema::place_revision::table
+                .inner_join(
+                    schema::place::table.on(rev_dsl::parent_rowid
+                        .eq(dsl::rowid)
+                        .and(rev_dsl::rev.eq(dsl::current_rev))),
+                )
+                .select((
+                    rev_dsl::rowid,
+                    rev_dsl::rev,
+                    rev_dsl::created_at,
+                    rev_dsl::created_by,
+                    rev_dsl::current_status,
+                    rev_dsl::title,
+                    rev_dsl::description,
+                    rev_dsl::lat,
+                    rev_dsl::lon,
+                    rev_dsl::street,
+                    rev_dsl::zip,
+                    rev_dsl::city,
+                    rev_dsl::country,
+                    rev_dsl::state,
+                    rev_dsl::email,
+                    rev_dsl::phone,
+                    rev_dsl::homepage,
+                    rev_dsl::opening_hours,
+                    rev_dsl::image_url,
+                    rev_dsl::image_link_url,
+                    rev_dsl::source_importer,
+                    rev_dsl::source_external_ref,
+                    rev_dsl::source_imported_at,
+                    dsl::id,
+                    dsl::license,
+                ))
+                .into_boxed();
+            if !place_ids.is_empty() {
+                query = query.filter(dsl::id.eq_any(place_ids));
+            }
+            let rows = query.load::<models::JoinedPlaceRevision>(self)?;
+            let mut results = Vec::with_capacity(rows.len());
+            for row in rows {
+                results.push(load_place(self, row)?);
+            }
+            Ok(results)
+        };
+
         if place_ids.is_empty() {
             warn!("Loading all entries at once");
-        } else {
-            // TODO: Split loading into chunks of fixed size
-            info!("Loading multiple ({}) entries at once", place_ids.len());
-            query = query.filter(dsl::id.eq_any(place_ids));
+            return load_places(place_ids);
         }
-
-        let rows = query.load::<models::JoinedPlaceRevision>(self)?;
-        let mut results = Vec::with_capacity(rows.len());
-        for row in rows {
-            results.push(load_place(self, row)?);
-        }
-        Ok(results)
+        load_in_chunks(place_ids, load_places)
     }
 
-    fn get_place(&self, place_id: &str) -> Result<(Place, ReviewStatus)> {
-        let places = self.get_places(&[place_id])?;
+    fn get_place(&self, place_id: &PlaceId) -> Result<(Place, ReviewStatus)> {
+        let places = self.get_places(&[place_id.as_str()])?;
         debug_assert!(places.len() <= 1);
         places.into_iter().next().ok_or(RepoError::NotFound)
     }
@@ -532,11 +779,41 @@ impl PlaceRepo for SqliteConnection {
         self.get_places(&[])
     }
 
+    fn all_place_ids(&self) -> Result<Vec<String>> {
+        use schema::place::dsl;
+        Ok(schema::place::table.select(dsl::id).load(self)?)
+    }
+
+    fn find_place_ids_by_tags(&self, tags: &[&str]) -> Result<Vec<Id>> {
+        use schema::place::dsl;
+        use schema::place_revision::dsl as rev_dsl;
+        use schema::place_revision_tag::dsl as tag_dsl;
+
+        if tags.is_empty() {
+            return Ok(vec![]);
+        }
+        Ok(schema::place_revision_tag::table
+            .inner_join(schema::place_revision::table)
+            .inner_join(
+                schema::place::table.on(rev_dsl::parent_rowid
+                    .eq(dsl::rowid)
+                    .and(rev_dsl::rev.eq(dsl::current_rev))),
+            )
+            .select(dsl::id)
+            .filter(tag_dsl::tag.eq_any(tags))
+            .distinct()
+            .order_by(dsl::id)
+            .load::<String>(self)?
+            .into_iter()
+            .map(Id::from)
+            .collect())
+    }
+
     fn recently_changed_places(
         &self,
         params: &RecentlyChangedEntriesParams,
         pagination: &Pagination,
-    ) -> Result<Vec<(Place, ReviewStatus, ActivityLog)>> {
+    ) -> Result<Vec<RecentlyChangedPlace>> {
         use schema::place::dsl;
         use schema::place_revision::dsl as rev_dsl;
         use schema::place_revision_review::dsl as review_dsl;
@@ -571,6 +848,9 @@ impl PlaceRepo for SqliteConnection {
                 rev_dsl::opening_hours,
                 rev_dsl::image_url,
                 rev_dsl::image_link_url,
+                rev_dsl::source_importer,
+                rev_dsl::source_external_ref,
+                rev_dsl::source_imported_at,
                 dsl::id,
                 dsl::license,
                 review_dsl::rev,
@@ -579,9 +859,13 @@ impl PlaceRepo for SqliteConnection {
                 review_dsl::status,
                 review_dsl::context,
                 review_dsl::comment,
+                review_dsl::rowid,
             ))
             .order_by(review_dsl::created_at.desc())
-            .then_order_by(review_dsl::rev.desc()) // disambiguation of equal time stamps
+            // The rowid of the log entry is unique and disambiguates
+            // equal time stamps. The order of existing rows is stable
+            // when new rows are inserted.
+            .then_order_by(review_dsl::rowid.desc())
             .into_boxed();
 
         // Since (inclusive)
@@ -594,6 +878,18 @@ impl PlaceRepo for SqliteConnection {
             query = query.filter(review_dsl::created_at.lt(until.into_inner()));
         }
 
+        // Keyset pagination (exclusive)
+        if let Some(after) = params.after {
+            let created_at = after.created_at.into_inner();
+            query = query.filter(
+                review_dsl::created_at
+                    .lt(created_at)
+                    .or(review_dsl::created_at
+                        .eq(created_at)
+                        .and(review_dsl::rowid.lt(after.log_id))),
+            );
+        }
+
         // Pagination
         let offset = pagination.offset.unwrap_or(0);
         if offset > 0 {
@@ -606,49 +902,74 @@ impl PlaceRepo for SqliteConnection {
         let rows = query.load::<models::JoinedPlaceRevisionWithStatusReview>(self)?;
         let mut results = Vec::with_capacity(rows.len());
         for row in rows {
-            results.push(load_place_with_status_review(self, row)?);
+            let cursor = RecentlyChangedEntriesCursor {
+                created_at: TimestampMs::from_inner(row.review_created_at),
+                log_id: row.review_rowid,
+            };
+            let (place, status, activity_log) = load_place_with_status_review(self, row)?;
+            results.push((place, status, activity_log, cursor));
         }
         Ok(results)
     }
 
-    fn most_popular_place_revision_tags(
-        &self,
-        params: &MostPopularTagsParams,
-        pagination: &Pagination,
-    ) -> Result<Vec<TagFrequency>> {
-        // TODO: Diesel 1.4.x does not support the HAVING clause
-        // that is required to filter the aggregated column.
-        let mut sql = "SELECT tag, COUNT(*) as count \
-                       FROM place_revision_tag \
-                       WHERE parent_rowid IN \
-                       (SELECT rowid FROM place_revision WHERE (parent_rowid, rev) IN (SELECT rowid, current_rev FROM place) AND current_status > 0) \
-                       GROUP BY tag"
-            .to_string();
-        if params.min_count.is_some() || params.max_count.is_some() {
-            if let Some(min_count) = params.min_count {
-                sql.push_str(&format!(" HAVING count>={}", min_count));
-                if let Some(max_count) = params.max_count {
-                    sql.push_str(&format!(" AND count<={}", max_count));
-                }
-            } else if let Some(max_count) = params.max_count {
-                sql.push_str(&format!(" HAVING count<={}", max_count));
-            }
+    fn list_places_pending_review(&self, pagination: &Pagination) -> Result<Vec<Place>> {
+        use schema::place::dsl;
+        use schema::place_revision::dsl as rev_dsl;
+
+        let mut query = schema::place_revision::table
+            .inner_join(
+                schema::place::table.on(rev_dsl::parent_rowid
+                    .eq(dsl::rowid)
+                    .and(rev_dsl::rev.eq(dsl::current_rev))),
+            )
+            .select((
+                rev_dsl::rowid,
+                rev_dsl::rev,
+                rev_dsl::created_at,
+                rev_dsl::created_by,
+                rev_dsl::current_status,
+                rev_dsl::title,
+                rev_dsl::description,
+                rev_dsl::lat,
+                rev_dsl::lon,
+                rev_dsl::street,
+                rev_dsl::zip,
+                rev_dsl::city,
+                rev_dsl::country,
+                rev_dsl::state,
+                rev_dsl::email,
+                rev_dsl::phone,
+                rev_dsl::homepage,
+                rev_dsl::opening_hours,
+                rev_dsl::image_url,
+                rev_dsl::image_link_url,
+                rev_dsl::source_importer,
+                rev_dsl::source_external_ref,
+                rev_dsl::source_imported_at,
+                dsl::id,
+                dsl::license,
+            ))
+            .filter(rev_dsl::current_status.eq(ReviewStatusPrimitive::from(ReviewStatus::Created)))
+            .order_by(rev_dsl::created_at)
+            .then_order_by(rev_dsl::rowid) // disambiguation of equal time stamps
+            .into_boxed();
+
+        // Pagination
+        let offset = pagination.offset.unwrap_or(0);
+        if offset > 0 {
+            query = query.offset(offset as i64);
         }
-        sql.push_str(" ORDER BY count DESC, tag");
         if let Some(limit) = pagination.limit {
-            sql.push_str(&format!(" LIMIT {}", limit));
-            // LIMIT must precede OFFSET, i.e. OFFSET without LIMIT
-            // is not supported!
-            let offset = pagination.offset.unwrap_or(0);
-            if offset > 0 {
-                sql.push_str(&format!(" OFFSET {}", offset));
-            }
+            query = query.limit(limit as i64);
         }
-        let rows = diesel::dsl::sql_query(sql).load::<TagCountRow>(self)?;
-        Ok(rows
-            .into_iter()
-            .map(|row| TagFrequency(row.tag, row.count as TagCount))
-            .collect())
+
+        let rows = query.load::<models::JoinedPlaceRevision>(self)?;
+        let mut results = Vec::with_capacity(rows.len());
+        for row in rows {
+            let (place, _) = load_place(self, row)?;
+            results.push(place);
+        }
+        Ok(results)
     }
 
     fn count_places(&self) -> Result<usize> {
@@ -665,7 +986,29 @@ impl PlaceRepo for SqliteConnection {
             .first::<i64>(self)? as usize)
     }
 
-    fn get_place_history(&self, id: &str) -> Result<PlaceHistory> {
+    fn count_new_places_per_month(&self) -> Result<Vec<MonthlyCount>> {
+        // TODO: Diesel 1.4.x does not support grouping by
+        // expressions that are not columns.
+        let sql = "SELECT \
+                   CAST(strftime('%Y', created_at / 1000, 'unixepoch') AS INTEGER) AS year, \
+                   CAST(strftime('%m', created_at / 1000, 'unixepoch') AS INTEGER) AS month, \
+                   COUNT(*) AS count \
+                   FROM place_revision \
+                   WHERE rev=0 \
+                   GROUP BY year, month \
+                   ORDER BY year, month";
+        Ok(diesel::dsl::sql_query(sql)
+            .load::<MonthlyCountRow>(self)?
+            .into_iter()
+            .map(|row| MonthlyCount {
+                year: row.year,
+                month: row.month as u32,
+                count: row.count as usize,
+            })
+            .collect())
+    }
+
+    fn get_place_history(&self, id: &PlaceId) -> Result<PlaceHistory> {
         use schema::place::dsl;
         use schema::place_revision::dsl as rev_dsl;
 
@@ -692,10 +1035,13 @@ impl PlaceRepo for SqliteConnection {
                 rev_dsl::opening_hours,
                 rev_dsl::image_url,
                 rev_dsl::image_link_url,
+                rev_dsl::source_importer,
+                rev_dsl::source_external_ref,
+                rev_dsl::source_imported_at,
                 dsl::id,
                 dsl::license,
             ))
-            .filter(dsl::id.eq(id))
+            .filter(dsl::id.eq(id.as_str()))
             .order_by(rev_dsl::rev.desc())
             .load::<models::JoinedPlaceRevision>(self)?;
         let mut place_history = None;
@@ -774,6 +1120,7 @@ fn into_new_event_with_tags(
         image_url,
         image_link_url,
         tags,
+        capacity,
         ..
     } = event;
 
@@ -836,9 +1183,10 @@ fn into_new_event_with_tags(
             created_by,
             registration,
             organizer,
-            archived: archived.map(Timestamp::into_inner),
+            archived: archived.map(TimestampMs::into_inner),
             image_url: image_url.map(Url::into_string),
             image_link_url: image_link_url.map(Url::into_string),
+            capacity: capacity.map(i64::from),
         },
         tags,
     ))
@@ -852,8 +1200,25 @@ fn resolve_event_id(conn: &SqliteConnection, uid: &str) -> Result<i64> {
         .first(conn)?)
 }
 
+// Replace the recurrence of an event
+fn store_event_recurrence(
+    conn: &SqliteConnection,
+    id: i64,
+    recurrence: Option<&Recurrence>,
+) -> result::Result<(), DieselError> {
+    use schema::event_recurrences::dsl;
+    diesel::delete(dsl::event_recurrences.filter(dsl::event_id.eq(id))).execute(conn)?;
+    if let Some(recurrence) = recurrence {
+        diesel::insert_into(dsl::event_recurrences)
+            .values(&util::event_recurrence_from_recurrence(id, recurrence))
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
 impl EventGateway for SqliteConnection {
     fn create_event(&self, e: Event) -> Result<()> {
+        let recurrence = e.recurrence.clone();
         let (new_event, tags) = into_new_event_with_tags(self, e)?;
         self.transaction::<_, diesel::result::Error, _>(|| {
             // Insert event
@@ -878,6 +1243,7 @@ impl EventGateway for SqliteConnection {
             diesel::insert_or_ignore_into(schema::event_tags::table)
                 .values(&tags)
                 .execute(self)?;
+            store_event_recurrence(self, id, recurrence.as_ref())?;
             Ok(())
         })?;
         Ok(())
@@ -920,13 +1286,17 @@ impl EventGateway for SqliteConnection {
                     .values(&new_tags)
                     .execute(self)?;
             }
+            store_event_recurrence(self, id, event.recurrence.as_ref())?;
             Ok(())
         })?;
         Ok(())
     }
 
     fn get_events_chronologically(&self, ids: &[&str]) -> Result<Vec<Event>> {
-        use schema::{event_tags::dsl as et_dsl, events::dsl as e_dsl, users::dsl as u_dsl};
+        use schema::{
+            event_recurrences::dsl as er_dsl, event_tags::dsl as et_dsl, events::dsl as e_dsl,
+            users::dsl as u_dsl,
+        };
 
         let rows = e_dsl::events
             .left_outer_join(u_dsl::users)
@@ -953,6 +1323,7 @@ impl EventGateway for SqliteConnection {
                 e_dsl::archived,
                 e_dsl::image_url,
                 e_dsl::image_link_url,
+                e_dsl::capacity,
                 u_dsl::email.nullable(),
             ))
             .filter(e_dsl::uid.eq_any(ids))
@@ -984,6 +1355,7 @@ impl EventGateway for SqliteConnection {
                 archived,
                 image_url,
                 image_link_url,
+                capacity,
                 created_by_email,
                 ..
             } = row;
@@ -993,6 +1365,13 @@ impl EventGateway for SqliteConnection {
                 .filter(et_dsl::event_id.eq(id))
                 .load::<String>(self)?;
 
+            let recurrence = er_dsl::event_recurrences
+                .filter(er_dsl::event_id.eq(id))
+                .first::<models::EventRecurrence>(self)
+                .optional()?
+                .as_ref()
+                .and_then(util::recurrence_from_event_recurrence);
+
             let address = Address {
                 street,
                 zip,
@@ -1044,9 +1423,11 @@ impl EventGateway for SqliteConnection {
                 created_by: created_by_email,
                 registration,
                 organizer,
-                archived: archived.map(Timestamp::from_inner),
+                archived: archived.map(TimestampMs::from_inner),
                 image_url: image_url.and_then(load_url),
                 image_link_url: image_link_url.and_then(load_url),
+                recurrence,
+                capacity: capacity.map(|x| x as u32),
             };
             events.push(event);
         }
@@ -1054,14 +1435,17 @@ impl EventGateway for SqliteConnection {
         Ok(events)
     }
 
-    fn get_event(&self, id: &str) -> Result<Event> {
-        let events = self.get_events_chronologically(&[id])?;
+    fn get_event(&self, id: &EventId) -> Result<Event> {
+        let events = self.get_events_chronologically(&[id.as_str()])?;
         debug_assert!(events.len() <= 1);
         events.into_iter().next().ok_or(RepoError::NotFound)
     }
 
     fn all_events_chronologically(&self) -> Result<Vec<Event>> {
-        use schema::{event_tags::dsl as et_dsl, events::dsl as e_dsl, users::dsl as u_dsl};
+        use schema::{
+            event_recurrences::dsl as er_dsl, event_tags::dsl as et_dsl, events::dsl as e_dsl,
+            users::dsl as u_dsl,
+        };
         let events: Vec<_> = e_dsl::events
             .left_outer_join(u_dsl::users)
             .select((
@@ -1087,15 +1471,17 @@ impl EventGateway for SqliteConnection {
                 e_dsl::archived,
                 e_dsl::image_url,
                 e_dsl::image_link_url,
+                e_dsl::capacity,
                 u_dsl::email.nullable(),
             ))
             .filter(e_dsl::archived.is_null())
             .order_by(e_dsl::start)
             .load::<models::EventEntity>(self)?;
         let tag_rels = et_dsl::event_tags.load(self)?;
+        let recurrences = er_dsl::event_recurrences.load(self)?;
         Ok(events
             .into_iter()
-            .map(|e| util::event_from_event_entity_and_tags(e, &tag_rels))
+            .map(|e| util::event_from_event_entity_and_tags(e, &tag_rels, &recurrences))
             .collect())
     }
 
@@ -1107,22 +1493,39 @@ impl EventGateway for SqliteConnection {
             .first::<i64>(self)? as usize)
     }
 
-    fn archive_events(&self, ids: &[&str], archived: Timestamp) -> Result<usize> {
+    fn archive_events(
+        &self,
+        ids: &[&str],
+        activity: &Activity,
+        comment: Option<&str>,
+    ) -> Result<usize> {
         use schema::events::dsl;
+        let archived_by = if let Some(ref email) = activity.by {
+            Some(resolve_user_created_by_email(self, email.as_ref())?)
+        } else {
+            None
+        };
         let count = diesel::update(
             dsl::events
                 .filter(dsl::uid.eq_any(ids))
                 .filter(dsl::archived.is_null()),
         )
-        .set(dsl::archived.eq(Some(archived.into_inner())))
+        .set((
+            dsl::archived.eq(Some(activity.at.into_inner())),
+            dsl::archived_by.eq(archived_by),
+            dsl::archived_comment.eq(comment),
+        ))
         .execute(self)?;
         debug_assert!(count <= ids.len());
         Ok(count)
     }
 
-    fn delete_event_with_matching_tags(&self, id: &str, tags: &[&str]) -> Result<Option<()>> {
-        use schema::{event_tags::dsl as et_dsl, events::dsl as e_dsl};
-        let id = resolve_event_id(self, id)?;
+    fn delete_event_with_matching_tags(&self, id: &EventId, tags: &[&str]) -> Result<Option<()>> {
+        use schema::{
+            event_recurrences::dsl as er_dsl, event_registrations::dsl as r_dsl,
+            event_tags::dsl as et_dsl, events::dsl as e_dsl,
+        };
+        let id = resolve_event_id(self, id.as_str())?;
         if !tags.is_empty() {
             let ids: Vec<_> = et_dsl::event_tags
                 .select(et_dsl::event_id)
@@ -1137,9 +1540,44 @@ impl EventGateway for SqliteConnection {
             debug_assert_eq!(id, *ids.first().unwrap());
         }
         diesel::delete(et_dsl::event_tags.filter(et_dsl::event_id.eq(id))).execute(self)?;
+        diesel::delete(er_dsl::event_recurrences.filter(er_dsl::event_id.eq(id))).execute(self)?;
+        diesel::delete(r_dsl::event_registrations.filter(r_dsl::event_id.eq(id))).execute(self)?;
         diesel::delete(e_dsl::events.filter(e_dsl::id.eq(id))).execute(self)?;
         Ok(Some(()))
     }
+
+    fn find_event_id_by_external_id(
+        &self,
+        org_id: &str,
+        external_id: &str,
+    ) -> Result<Option<String>> {
+        use schema::events::dsl;
+        Ok(dsl::events
+            .select(dsl::uid)
+            .filter(dsl::external_org_id.eq(org_id))
+            .filter(dsl::external_id.eq(external_id))
+            .first::<String>(self)
+            .optional()?)
+    }
+
+    fn set_event_external_id(
+        &self,
+        event_id: &EventId,
+        org_id: &str,
+        external_id: &str,
+    ) -> Result<()> {
+        use schema::events::dsl;
+        let count = diesel::update(dsl::events.filter(dsl::uid.eq(event_id.as_str())))
+            .set((
+                dsl::external_org_id.eq(org_id),
+                dsl::external_id.eq(external_id),
+            ))
+            .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
 }
 
 fn resolve_user_created_by_email(conn: &SqliteConnection, email: &str) -> Result<i64> {
@@ -1173,9 +1611,177 @@ impl UserGateway for SqliteConnection {
     }
 
     fn delete_user_by_email(&self, email: &str) -> Result<()> {
+        use schema::audit_log::dsl as audit_dsl;
+        use schema::email_outbox::dsl as outbox_dsl;
+        use schema::event_registrations::dsl as registration_dsl;
+        use schema::notification_digests::dsl as digest_dsl;
+        use schema::notification_queue::dsl as queue_dsl;
+        use schema::place_draft::dsl as draft_dsl;
+        use schema::place_rating_comment_flag::dsl as flag_dsl;
+        use schema::scout_report_opt_out::dsl as opt_out_dsl;
+        use schema::user_api_tokens::dsl as api_token_dsl;
+        use schema::user_login_links::dsl as login_link_dsl;
+        use schema::user_permission::dsl as perm_dsl;
+        use schema::user_status_log::dsl as log_dsl;
         use schema::users::dsl;
-        diesel::delete(dsl::users.filter(dsl::email.eq(email))).execute(self)?;
-        Ok(())
+        self.transaction::<_, RepoError, _>(|| {
+            diesel::delete(perm_dsl::user_permission.filter(
+                perm_dsl::user_id.eq_any(dsl::users.select(dsl::id).filter(dsl::email.eq(email))),
+            ))
+            .execute(self)?;
+            diesel::delete(draft_dsl::place_draft.filter(
+                draft_dsl::owner.eq_any(dsl::users.select(dsl::id).filter(dsl::email.eq(email))),
+            ))
+            .execute(self)?;
+            diesel::delete(digest_dsl::notification_digests.filter(
+                digest_dsl::user_id.eq_any(dsl::users.select(dsl::id).filter(dsl::email.eq(email))),
+            ))
+            .execute(self)?;
+            diesel::delete(queue_dsl::notification_queue.filter(
+                queue_dsl::user_id.eq_any(dsl::users.select(dsl::id).filter(dsl::email.eq(email))),
+            ))
+            .execute(self)?;
+            diesel::delete(
+                opt_out_dsl::scout_report_opt_out.filter(
+                    opt_out_dsl::user_id
+                        .eq_any(dsl::users.select(dsl::id).filter(dsl::email.eq(email))),
+                ),
+            )
+            .execute(self)?;
+            diesel::delete(
+                flag_dsl::place_rating_comment_flag.filter(
+                    flag_dsl::created_by
+                        .eq_any(dsl::users.select(dsl::id).filter(dsl::email.eq(email))),
+                ),
+            )
+            .execute(self)?;
+            diesel::delete(log_dsl::user_status_log.filter(
+                log_dsl::user_id.eq_any(dsl::users.select(dsl::id).filter(dsl::email.eq(email))),
+            ))
+            .execute(self)?;
+            diesel::delete(
+                api_token_dsl::user_api_tokens.filter(
+                    api_token_dsl::user_id
+                        .eq_any(dsl::users.select(dsl::id).filter(dsl::email.eq(email))),
+                ),
+            )
+            .execute(self)?;
+            diesel::delete(
+                login_link_dsl::user_login_links.filter(login_link_dsl::email.eq(email)),
+            )
+            .execute(self)?;
+            diesel::delete(
+                registration_dsl::event_registrations.filter(registration_dsl::email.eq(email)),
+            )
+            .execute(self)?;
+            diesel::delete(outbox_dsl::email_outbox.filter(outbox_dsl::recipient.eq(email)))
+                .execute(self)?;
+            // E-mails to other recipients might mention the address
+            diesel::dsl::sql_query(
+                "UPDATE email_outbox SET subject=replace(subject, ?, ?), body=replace(body, ?, ?) \
+                 WHERE instr(subject, ?) > 0 OR instr(body, ?) > 0",
+            )
+            .bind::<diesel::sql_types::Text, _>(email)
+            .bind::<diesel::sql_types::Text, _>(DELETED_EMAIL_PLACEHOLDER)
+            .bind::<diesel::sql_types::Text, _>(email)
+            .bind::<diesel::sql_types::Text, _>(DELETED_EMAIL_PLACEHOLDER)
+            .bind::<diesel::sql_types::Text, _>(email)
+            .bind::<diesel::sql_types::Text, _>(email)
+            .execute(self)?;
+            // Audit log entries of user actions refer to the address
+            diesel::update(audit_dsl::audit_log.filter(audit_dsl::item.eq(email)))
+                .set(audit_dsl::item.eq(DELETED_EMAIL_PLACEHOLDER))
+                .execute(self)?;
+            diesel::delete(dsl::users.filter(dsl::email.eq(email))).execute(self)?;
+            Ok(())
+        })
+    }
+
+    fn anonymize_user_by_email(&self, email: &str) -> Result<()> {
+        use schema::audit_log::dsl as audit_dsl;
+        use schema::bbox_subscriptions::dsl as sub_dsl;
+        use schema::events::dsl as e_dsl;
+        use schema::moderation_assignment::dsl as assign_dsl;
+        use schema::place_rating::dsl as rating_dsl;
+        use schema::place_rating_comment::dsl as comment_dsl;
+        use schema::place_revision::dsl as rev_dsl;
+        use schema::place_revision_review::dsl as review_dsl;
+        use schema::reports::dsl as report_dsl;
+        use schema::saved_searches::dsl as search_dsl;
+        use schema::tag_subscription_tags::dsl as tag_sub_tag_dsl;
+        use schema::tag_subscriptions::dsl as tag_sub_dsl;
+        use schema::user_status_log::dsl as log_dsl;
+        use schema::user_tokens::dsl as token_dsl;
+        let user_id = resolve_user_created_by_email(self, email)?;
+        let none = None::<i64>;
+        diesel::update(rev_dsl::place_revision.filter(rev_dsl::created_by.eq(user_id)))
+            .set(rev_dsl::created_by.eq(none))
+            .execute(self)?;
+        diesel::update(
+            review_dsl::place_revision_review.filter(review_dsl::created_by.eq(user_id)),
+        )
+        .set(review_dsl::created_by.eq(none))
+        .execute(self)?;
+        diesel::update(rating_dsl::place_rating.filter(rating_dsl::created_by.eq(user_id)))
+            .set(rating_dsl::created_by.eq(none))
+            .execute(self)?;
+        diesel::update(rating_dsl::place_rating.filter(rating_dsl::archived_by.eq(user_id)))
+            .set(rating_dsl::archived_by.eq(none))
+            .execute(self)?;
+        diesel::update(
+            comment_dsl::place_rating_comment.filter(comment_dsl::created_by.eq(user_id)),
+        )
+        .set(comment_dsl::created_by.eq(none))
+        .execute(self)?;
+        diesel::update(
+            comment_dsl::place_rating_comment.filter(comment_dsl::archived_by.eq(user_id)),
+        )
+        .set(comment_dsl::archived_by.eq(none))
+        .execute(self)?;
+        diesel::update(report_dsl::reports.filter(report_dsl::created_by.eq(user_id)))
+            .set(report_dsl::created_by.eq(none))
+            .execute(self)?;
+        diesel::update(report_dsl::reports.filter(report_dsl::resolved_by.eq(user_id)))
+            .set(report_dsl::resolved_by.eq(none))
+            .execute(self)?;
+        diesel::update(e_dsl::events.filter(e_dsl::created_by.eq(user_id)))
+            .set(e_dsl::created_by.eq(none))
+            .execute(self)?;
+        diesel::update(e_dsl::events.filter(e_dsl::archived_by.eq(user_id)))
+            .set(e_dsl::archived_by.eq(none))
+            .execute(self)?;
+        diesel::update(audit_dsl::audit_log.filter(audit_dsl::created_by.eq(user_id)))
+            .set(audit_dsl::created_by.eq(none))
+            .execute(self)?;
+        diesel::update(log_dsl::user_status_log.filter(log_dsl::created_by.eq(user_id)))
+            .set(log_dsl::created_by.eq(none))
+            .execute(self)?;
+        diesel::update(
+            assign_dsl::moderation_assignment.filter(assign_dsl::assigned_by.eq(user_id)),
+        )
+        .set(assign_dsl::assigned_by.eq(none))
+        .execute(self)?;
+        diesel::delete(assign_dsl::moderation_assignment.filter(assign_dsl::assignee.eq(user_id)))
+            .execute(self)?;
+        diesel::delete(sub_dsl::bbox_subscriptions.filter(sub_dsl::user_id.eq(user_id)))
+            .execute(self)?;
+        diesel::delete(
+            tag_sub_tag_dsl::tag_subscription_tags.filter(
+                tag_sub_tag_dsl::parent_rowid.eq_any(
+                    tag_sub_dsl::tag_subscriptions
+                        .select(tag_sub_dsl::rowid)
+                        .filter(tag_sub_dsl::user_id.eq(user_id)),
+                ),
+            ),
+        )
+        .execute(self)?;
+        diesel::delete(tag_sub_dsl::tag_subscriptions.filter(tag_sub_dsl::user_id.eq(user_id)))
+            .execute(self)?;
+        diesel::delete(search_dsl::saved_searches.filter(search_dsl::user_id.eq(user_id)))
+            .execute(self)?;
+        diesel::delete(token_dsl::user_tokens.filter(token_dsl::user_id.eq(user_id)))
+            .execute(self)?;
+        self.delete_user_by_email(email)
     }
 
     fn get_user_by_email(&self, email: &str) -> Result<User> {
@@ -1210,6 +1816,109 @@ impl UserGateway for SqliteConnection {
             .select(diesel::dsl::count(dsl::id))
             .first::<i64>(self)? as usize)
     }
+
+    fn log_user_status(&self, email: &str, status_log: &UserStatusLog) -> Result<()> {
+        let UserStatusLog {
+            activity:
+                ActivityLog {
+                    activity,
+                    context,
+                    comment,
+                },
+            active,
+        } = status_log;
+        let user_id = resolve_user_created_by_email(self, email)?;
+        let created_by = if let Some(ref email) = activity.by {
+            Some(resolve_user_created_by_email(self, email.as_ref())?)
+        } else {
+            None
+        };
+        let new_status_log = models::NewUserStatusLog {
+            user_id,
+            created_at: activity.at.into_inner(),
+            created_by,
+            active: *active,
+            context: context.as_deref(),
+            comment: comment.as_deref(),
+        };
+        diesel::insert_into(schema::user_status_log::table)
+            .values(new_status_log)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_user_status_logs(&self, email: &str) -> Result<Vec<UserStatusLog>> {
+        use schema::user_status_log::dsl as log_dsl;
+        use schema::users::dsl as user_dsl;
+        let user_id = resolve_user_created_by_email(self, email)?;
+        let rows = schema::user_status_log::table
+            .left_outer_join(
+                schema::users::table.on(log_dsl::created_by.eq(user_dsl::id.nullable())),
+            )
+            .select((
+                log_dsl::created_at,
+                user_dsl::email.nullable(),
+                log_dsl::active,
+                log_dsl::context,
+                log_dsl::comment,
+            ))
+            .filter(log_dsl::user_id.eq(user_id))
+            .order_by(log_dsl::rowid)
+            .load::<models::UserStatusLog>(self)?;
+        Ok(rows
+            .into_iter()
+            .map(|row| UserStatusLog {
+                activity: ActivityLog {
+                    activity: Activity {
+                        at: TimestampMs::from_inner(row.created_at),
+                        by: row.created_by_email.map(Into::into),
+                    },
+                    context: row.context,
+                    comment: row.comment,
+                },
+                active: row.active,
+            })
+            .collect())
+    }
+
+    fn set_scout_report_opt_out(&self, email: &str, opt_out: bool) -> Result<()> {
+        use schema::scout_report_opt_out::dsl;
+        let user_id = resolve_user_created_by_email(self, email)?;
+        if opt_out {
+            diesel::replace_into(schema::scout_report_opt_out::table)
+                .values(dsl::user_id.eq(user_id))
+                .execute(self)?;
+        } else {
+            diesel::delete(dsl::scout_report_opt_out.filter(dsl::user_id.eq(user_id)))
+                .execute(self)?;
+        }
+        Ok(())
+    }
+
+    fn is_scout_report_opted_out(&self, email: &str) -> Result<bool> {
+        use schema::scout_report_opt_out::dsl;
+        let user_id = resolve_user_created_by_email(self, email)?;
+        Ok(dsl::scout_report_opt_out
+            .select(dsl::user_id)
+            .filter(dsl::user_id.eq(user_id))
+            .first::<i64>(self)
+            .optional()?
+            .is_some())
+    }
+
+    fn is_user_active(&self, email: &str) -> Result<bool> {
+        use schema::user_status_log::dsl as log_dsl;
+        use schema::users::dsl as user_dsl;
+        Ok(schema::user_status_log::table
+            .inner_join(schema::users::table.on(log_dsl::user_id.eq(user_dsl::id)))
+            .select(log_dsl::active)
+            .filter(user_dsl::email.eq(email))
+            .order_by(log_dsl::rowid.desc())
+            .first::<bool>(self)
+            .optional()?
+            // Users without any status changes are active
+            .unwrap_or(true))
+    }
 }
 
 impl RatingRepository for SqliteConnection {
@@ -1218,6 +1927,7 @@ impl RatingRepository for SqliteConnection {
             id,
             place_id,
             created_at,
+            created_by,
             archived_at,
             title,
             value,
@@ -1225,12 +1935,17 @@ impl RatingRepository for SqliteConnection {
             source,
         } = rating;
         let (parent_rowid, _) = resolve_place_rowid(self, &place_id)?;
+        let created_by = if let Some(ref email) = created_by {
+            Some(resolve_user_created_by_email(self, email)?)
+        } else {
+            None
+        };
         let new_place_rating = models::NewPlaceRating {
             id: id.into(),
             parent_rowid,
             created_at: created_at.into_inner(),
-            created_by: None,
-            archived_at: archived_at.map(Timestamp::into_inner),
+            created_by,
+            archived_at: archived_at.map(TimestampMs::into_inner),
             archived_by: None,
             title,
             value: i8::from(value).into(),
@@ -1244,47 +1959,76 @@ impl RatingRepository for SqliteConnection {
         Ok(())
     }
 
+    fn update_rating(&self, rating: &Rating) -> Result<()> {
+        use schema::place_rating::dsl;
+        let count = diesel::update(
+            schema::place_rating::table
+                .filter(dsl::id.eq(rating.id.as_str()))
+                .filter(dsl::archived_at.is_null()),
+        )
+        .set((
+            dsl::title.eq(&rating.title),
+            dsl::value.eq(i16::from(i8::from(rating.value))),
+        ))
+        .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        debug_assert_eq!(1, count);
+        Ok(())
+    }
+
     fn load_ratings(&self, ids: &[&str]) -> Result<Vec<Rating>> {
         use schema::place::dsl;
         use schema::place_rating::dsl as rating_dsl;
-        Ok(schema::place_rating::table
-            .inner_join(schema::place::table)
-            .select((
-                rating_dsl::rowid,
-                rating_dsl::created_at,
-                rating_dsl::created_by,
-                rating_dsl::archived_at,
-                rating_dsl::archived_by,
-                rating_dsl::id,
-                rating_dsl::title,
-                rating_dsl::value,
-                rating_dsl::context,
-                rating_dsl::source,
-                dsl::id,
-            ))
-            .filter(rating_dsl::id.eq_any(ids))
-            .filter(rating_dsl::archived_at.is_null())
-            .load::<models::PlaceRating>(self)?
-            .into_iter()
-            .map(Into::into)
-            .collect())
+        use schema::users::dsl as user_dsl;
+        load_in_chunks(ids, |ids| {
+            Ok(schema::place_rating::table
+                .inner_join(schema::place::table)
+                .left_outer_join(
+                    schema::users::table.on(rating_dsl::created_by.eq(user_dsl::id.nullable())),
+                )
+                .select((
+                    rating_dsl::rowid,
+                    rating_dsl::created_at,
+                    user_dsl::email.nullable(),
+                    rating_dsl::archived_at,
+                    rating_dsl::archived_by,
+                    rating_dsl::id,
+                    rating_dsl::title,
+                    rating_dsl::value,
+                    rating_dsl::context,
+                    rating_dsl::source,
+                    dsl::id,
+                ))
+                .filter(rating_dsl::id.eq_any(ids))
+                .filter(rating_dsl::archived_at.is_null())
+                .load::<models::PlaceRating>(self)?
+                .into_iter()
+                .map(Into::into)
+                .collect())
+        })
     }
 
-    fn load_rating(&self, id: &str) -> Result<Rating> {
-        let ratings = self.load_ratings(&[id])?;
+    fn load_rating(&self, id: &RatingId) -> Result<Rating> {
+        let ratings = self.load_ratings(&[id.as_str()])?;
         debug_assert!(ratings.len() <= 1);
         ratings.into_iter().next().ok_or(RepoError::NotFound)
     }
 
-    fn load_ratings_of_place(&self, place_id: &str) -> Result<Vec<Rating>> {
+    fn load_ratings_of_place(&self, place_id: &PlaceId) -> Result<Vec<Rating>> {
         use schema::place::dsl;
         use schema::place_rating::dsl as rating_dsl;
+        use schema::users::dsl as user_dsl;
         Ok(schema::place_rating::table
             .inner_join(schema::place::table)
+            .left_outer_join(
+                schema::users::table.on(rating_dsl::created_by.eq(user_dsl::id.nullable())),
+            )
             .select((
                 rating_dsl::rowid,
                 rating_dsl::created_at,
-                rating_dsl::created_by,
+                user_dsl::email.nullable(),
                 rating_dsl::archived_at,
                 rating_dsl::archived_by,
                 rating_dsl::id,
@@ -1294,7 +2038,7 @@ impl RatingRepository for SqliteConnection {
                 rating_dsl::source,
                 dsl::id,
             ))
-            .filter(dsl::id.eq(place_id))
+            .filter(dsl::id.eq(place_id.as_str()))
             .filter(rating_dsl::archived_at.is_null())
             .load::<models::PlaceRating>(self)?
             .into_iter()
@@ -1302,6 +2046,50 @@ impl RatingRepository for SqliteConnection {
             .collect())
     }
 
+    fn load_monthly_avg_ratings_of_place(
+        &self,
+        place_id: &PlaceId,
+    ) -> Result<Vec<MonthlyAvgRatings>> {
+        // TODO: Diesel 1.4.x does not support grouping by
+        // expressions that are not columns.
+        let sql = "SELECT \
+                   CAST(strftime('%Y', created_at / 1000, 'unixepoch') AS INTEGER) AS year, \
+                   CAST(strftime('%m', created_at / 1000, 'unixepoch') AS INTEGER) AS month, \
+                   context, SUM(value) AS sum, COUNT(*) AS count \
+                   FROM place_rating \
+                   WHERE parent_rowid IN (SELECT rowid FROM place WHERE id=?) \
+                   AND archived_at IS NULL \
+                   GROUP BY year, month, context \
+                   ORDER BY year, month";
+        let rows = diesel::dsl::sql_query(sql)
+            .bind::<diesel::sql_types::Text, _>(place_id.as_str())
+            .load::<MonthlyRatingSumRow>(self)?;
+        let mut results: Vec<(i32, i32, AvgRatingsBuilder)> = Vec::new();
+        for row in rows {
+            let context = util::rating_context_from_str(&row.context).map_err(|_| {
+                RepoError::Other(anyhow!("Invalid rating context: {}", row.context))
+            })?;
+            match results.last_mut() {
+                Some((year, month, builder)) if (*year, *month) == (row.year, row.month) => {
+                    builder.add_sum(context, row.sum, row.count as usize);
+                }
+                _ => {
+                    let mut builder = AvgRatingsBuilder::default();
+                    builder.add_sum(context, row.sum, row.count as usize);
+                    results.push((row.year, row.month, builder));
+                }
+            }
+        }
+        Ok(results
+            .into_iter()
+            .map(|(year, month, builder)| MonthlyAvgRatings {
+                year,
+                month: month as u32,
+                ratings: builder.build(),
+            })
+            .collect())
+    }
+
     fn load_place_ids_of_ratings(&self, ids: &[&str]) -> Result<Vec<String>> {
         use schema::place::dsl;
         use schema::place_rating::dsl as rating_dsl;
@@ -1312,7 +2100,12 @@ impl RatingRepository for SqliteConnection {
             .load::<String>(self)?)
     }
 
-    fn archive_ratings(&self, ids: &[&str], activity: &Activity) -> Result<usize> {
+    fn archive_ratings(
+        &self,
+        ids: &[&str],
+        activity: &Activity,
+        comment: Option<&str>,
+    ) -> Result<usize> {
         use schema::place_rating::dsl;
         let archived_at = Some(activity.at.into_inner());
         let archived_by = if let Some(ref email) = activity.by {
@@ -1328,6 +2121,7 @@ impl RatingRepository for SqliteConnection {
         .set((
             dsl::archived_at.eq(archived_at),
             dsl::archived_by.eq(archived_by),
+            dsl::archived_comment.eq(comment),
         ))
         .execute(self)?;
         debug_assert!(count <= ids.len());
@@ -1360,82 +2154,352 @@ impl RatingRepository for SqliteConnection {
         ))
         .execute(self)?)
     }
+
+    fn move_ratings_of_place(
+        &self,
+        from_place_id: &PlaceId,
+        to_place_id: &PlaceId,
+    ) -> Result<usize> {
+        use schema::place::dsl;
+        use schema::place_rating::dsl as rating_dsl;
+        let (to_rowid, _) = resolve_place_rowid(self, &to_place_id.as_str().into())?;
+        Ok(diesel::update(
+            schema::place_rating::table.filter(
+                rating_dsl::parent_rowid.eq_any(
+                    schema::place::table
+                        .select(dsl::rowid)
+                        .filter(dsl::id.eq(from_place_id.as_str())),
+                ),
+            ),
+        )
+        .set(rating_dsl::parent_rowid.eq(to_rowid))
+        .execute(self)?)
+    }
 }
 
-impl CommentRepository for SqliteConnection {
-    fn create_comment(&self, comment: Comment) -> Result<()> {
-        let Comment {
+impl ReportRepository for SqliteConnection {
+    fn create_report(&self, report: Report) -> Result<()> {
+        let Report {
             id,
-            rating_id,
-            created_at,
-            archived_at,
-            text,
-            ..
-        } = comment;
-        let parent_rowid = resolve_rating_rowid(self, rating_id.as_ref())?;
-        let new_place_rating_comment = models::NewPlaceRatingComment {
+            content_kind,
+            content_id,
+            reason,
+            comment,
+            created,
+        } = report;
+        let created_by = if let Some(ref email) = created.by {
+            Some(resolve_user_created_by_email(self, email.as_ref())?)
+        } else {
+            None
+        };
+        let new_report = models::NewReport {
             id: id.into(),
-            parent_rowid,
-            created_at: created_at.into_inner(),
-            created_by: None,
-            archived_at: archived_at.map(Timestamp::into_inner),
-            archived_by: None,
-            text,
+            content_kind: content_kind.into(),
+            content_id: content_id.into(),
+            created_at: created.at.into_inner(),
+            created_by,
+            reason: reason.into(),
+            comment,
         };
-        let _count = diesel::insert_into(schema::place_rating_comment::table)
-            .values(&new_place_rating_comment)
+        let _count = diesel::insert_into(schema::reports::table)
+            .values(&new_report)
             .execute(self)?;
         debug_assert_eq!(1, _count);
         Ok(())
     }
 
-    fn load_comments(&self, ids: &[&str]) -> Result<Vec<Comment>> {
-        use schema::place_rating::dsl as rating_dsl;
-        use schema::place_rating_comment::dsl as comment_dsl;
-        // TODO: Split loading into chunks of fixed size
-        info!("Loading multiple ({}) comments at once", ids.len());
-        Ok(schema::place_rating_comment::table
-            .inner_join(schema::place_rating::table)
+    fn load_open_reports(&self) -> Result<Vec<Report>> {
+        use schema::reports::dsl as report_dsl;
+        use schema::users::dsl as user_dsl;
+        schema::reports::table
+            .left_outer_join(
+                schema::users::table.on(report_dsl::created_by.eq(user_dsl::id.nullable())),
+            )
             .select((
-                comment_dsl::rowid,
-                comment_dsl::created_at,
-                comment_dsl::created_by,
-                comment_dsl::archived_at,
-                comment_dsl::archived_by,
-                comment_dsl::id,
-                comment_dsl::text,
-                rating_dsl::id,
+                report_dsl::id,
+                report_dsl::content_kind,
+                report_dsl::content_id,
+                report_dsl::created_at,
+                user_dsl::email.nullable(),
+                report_dsl::reason,
+                report_dsl::comment,
             ))
-            .filter(comment_dsl::id.eq_any(ids))
-            .filter(comment_dsl::archived_at.is_null())
-            .load::<models::PlaceRatingComment>(self)?
+            .filter(report_dsl::resolved_at.is_null())
+            .order_by((report_dsl::created_at, report_dsl::rowid))
+            .load::<models::Report>(self)?
             .into_iter()
-            .map(Into::into)
-            .collect())
+            .map(|row| -> Result<Report> {
+                let content_kind = ContentKind::try_from(row.content_kind).ok_or_else(|| {
+                    RepoError::Other(anyhow!("Invalid content kind: {}", row.content_kind))
+                })?;
+                let reason = ReportReason::try_from(row.reason).ok_or_else(|| {
+                    RepoError::Other(anyhow!("Invalid report reason: {}", row.reason))
+                })?;
+                Ok(Report {
+                    id: row.id.into(),
+                    content_kind,
+                    content_id: row.content_id.into(),
+                    reason,
+                    comment: row.comment,
+                    created: Activity {
+                        at: TimestampMs::from_inner(row.created_at),
+                        by: row.created_by_email.map(Into::into),
+                    },
+                })
+            })
+            .collect()
     }
 
-    fn load_comment(&self, id: &str) -> Result<Comment> {
-        let comments = self.load_comments(&[id])?;
-        debug_assert!(comments.len() <= 1);
-        comments.into_iter().next().ok_or(RepoError::NotFound)
+    fn resolve_reports(&self, ids: &[&str], activity: &Activity) -> Result<usize> {
+        use schema::reports::dsl;
+        let resolved_at = Some(activity.at.into_inner());
+        let resolved_by = if let Some(ref email) = activity.by {
+            Some(resolve_user_created_by_email(self, email.as_ref())?)
+        } else {
+            None
+        };
+        let count = diesel::update(
+            schema::reports::table
+                .filter(dsl::id.eq_any(ids))
+                .filter(dsl::resolved_at.is_null()),
+        )
+        .set((
+            dsl::resolved_at.eq(resolved_at),
+            dsl::resolved_by.eq(resolved_by),
+        ))
+        .execute(self)?;
+        debug_assert!(count <= ids.len());
+        Ok(count)
     }
+}
 
-    fn load_comments_of_rating(&self, rating_id: &str) -> Result<Vec<Comment>> {
-        use schema::place_rating::dsl as rating_dsl;
-        use schema::place_rating_comment::dsl as comment_dsl;
-        Ok(schema::place_rating_comment::table
-            .inner_join(schema::place_rating::table)
-            .select((
-                comment_dsl::rowid,
-                comment_dsl::created_at,
-                comment_dsl::created_by,
+impl ModerationAssignmentRepository for SqliteConnection {
+    fn assign_moderation_item(&self, assignment: ModerationAssignment) -> Result<()> {
+        let ModerationAssignment {
+            item_kind,
+            item_id,
+            assignee,
+            state,
+            assigned,
+            changed_at,
+        } = assignment;
+        let assignee = resolve_user_created_by_email(self, assignee.as_ref())?;
+        let assigned_by = if let Some(ref email) = assigned.by {
+            Some(resolve_user_created_by_email(self, email.as_ref())?)
+        } else {
+            None
+        };
+        let new_assignment = models::NewModerationAssignment {
+            item_kind: item_kind.into(),
+            item_id: item_id.into(),
+            assignee,
+            state: state.into(),
+            assigned_at: assigned.at.into_inner(),
+            assigned_by,
+            changed_at: changed_at.into_inner(),
+        };
+        diesel::replace_into(schema::moderation_assignment::table)
+            .values(&new_assignment)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_open_moderation_assignments(
+        &self,
+        assignee_email: &str,
+    ) -> Result<Vec<ModerationAssignment>> {
+        use schema::moderation_assignment::dsl;
+        use schema::users::dsl as user_dsl;
+        let assignee = resolve_user_created_by_email(self, assignee_email)?;
+        schema::moderation_assignment::table
+            .left_outer_join(schema::users::table.on(dsl::assigned_by.eq(user_dsl::id.nullable())))
+            .select((
+                dsl::item_kind,
+                dsl::item_id,
+                dsl::state,
+                dsl::assigned_at,
+                user_dsl::email.nullable(),
+                dsl::changed_at,
+            ))
+            .filter(dsl::assignee.eq(assignee))
+            .filter(dsl::state.ne(AssignmentStatePrimitive::from(AssignmentState::Resolved)))
+            .order_by(dsl::assigned_at)
+            .load::<models::ModerationAssignment>(self)?
+            .into_iter()
+            .map(|row| -> Result<ModerationAssignment> {
+                let item_kind = ModerationItemKind::try_from(row.item_kind).ok_or_else(|| {
+                    RepoError::Other(anyhow!("Invalid moderation item kind: {}", row.item_kind))
+                })?;
+                let state = AssignmentState::try_from(row.state).ok_or_else(|| {
+                    RepoError::Other(anyhow!("Invalid assignment state: {}", row.state))
+                })?;
+                Ok(ModerationAssignment {
+                    item_kind,
+                    item_id: row.item_id.into(),
+                    assignee: assignee_email.into(),
+                    state,
+                    assigned: Activity {
+                        at: TimestampMs::from_inner(row.assigned_at),
+                        by: row.assigned_by_email.map(Into::into),
+                    },
+                    changed_at: TimestampMs::from_inner(row.changed_at),
+                })
+            })
+            .collect()
+    }
+
+    fn update_moderation_assignment_state(
+        &self,
+        item_kind: ModerationItemKind,
+        item_id: &str,
+        assignee_email: &str,
+        state: AssignmentState,
+        changed_at: TimestampMs,
+    ) -> Result<()> {
+        use schema::moderation_assignment::dsl;
+        let assignee = resolve_user_created_by_email(self, assignee_email)?;
+        let count = diesel::update(
+            schema::moderation_assignment::table
+                .filter(dsl::item_kind.eq(ModerationItemKindPrimitive::from(item_kind)))
+                .filter(dsl::item_id.eq(item_id))
+                .filter(dsl::assignee.eq(assignee)),
+        )
+        .set((
+            dsl::state.eq(AssignmentStatePrimitive::from(state)),
+            dsl::changed_at.eq(changed_at.into_inner()),
+        ))
+        .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        debug_assert_eq!(1, count);
+        Ok(())
+    }
+
+    fn resolve_moderation_assignments(
+        &self,
+        item_kind: ModerationItemKind,
+        item_ids: &[&str],
+        resolved_at: TimestampMs,
+    ) -> Result<usize> {
+        use schema::moderation_assignment::dsl;
+        let resolved = AssignmentStatePrimitive::from(AssignmentState::Resolved);
+        let count = diesel::update(
+            schema::moderation_assignment::table
+                .filter(dsl::item_kind.eq(ModerationItemKindPrimitive::from(item_kind)))
+                .filter(dsl::item_id.eq_any(item_ids))
+                .filter(dsl::state.ne(resolved)),
+        )
+        .set((
+            dsl::state.eq(resolved),
+            dsl::changed_at.eq(resolved_at.into_inner()),
+        ))
+        .execute(self)?;
+        debug_assert!(count <= item_ids.len());
+        Ok(count)
+    }
+}
+
+impl CommentRepository for SqliteConnection {
+    fn create_comment(&self, comment: Comment) -> Result<()> {
+        let Comment {
+            id,
+            rating_id,
+            created_at,
+            archived_at,
+            text,
+            ..
+        } = comment;
+        let parent_rowid = resolve_rating_rowid(self, rating_id.as_ref())?;
+        let new_place_rating_comment = models::NewPlaceRatingComment {
+            id: id.into(),
+            parent_rowid,
+            created_at: created_at.into_inner(),
+            created_by: None,
+            archived_at: archived_at.map(TimestampMs::into_inner),
+            archived_by: None,
+            text,
+        };
+        let _count = diesel::insert_into(schema::place_rating_comment::table)
+            .values(&new_place_rating_comment)
+            .execute(self)?;
+        debug_assert_eq!(1, _count);
+        Ok(())
+    }
+
+    fn flag_comment(&self, id: &CommentId, activity: &Activity) -> Result<()> {
+        use schema::place_rating_comment::dsl;
+        let parent_rowid = schema::place_rating_comment::table
+            .select(dsl::rowid)
+            .filter(dsl::id.eq(id.as_str()))
+            .filter(dsl::archived_at.is_null())
+            .first::<i64>(self)?;
+        let created_by = match activity.by {
+            Some(ref email) => resolve_user_created_by_email(self, email.as_ref())?,
+            None => {
+                return Err(RepoError::Other(anyhow!(
+                    "Comments can only be flagged by registered users"
+                )));
+            }
+        };
+        let new_flag = models::NewPlaceRatingCommentFlag {
+            parent_rowid,
+            created_at: activity.at.into_inner(),
+            created_by,
+        };
+        diesel::insert_or_ignore_into(schema::place_rating_comment_flag::table)
+            .values(&new_flag)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_comments(&self, ids: &[&str]) -> Result<Vec<Comment>> {
+        use schema::place_rating::dsl as rating_dsl;
+        use schema::place_rating_comment::dsl as comment_dsl;
+        load_in_chunks(ids, |ids| {
+            Ok(schema::place_rating_comment::table
+                .inner_join(schema::place_rating::table)
+                .select((
+                    comment_dsl::rowid,
+                    comment_dsl::created_at,
+                    comment_dsl::created_by,
+                    comment_dsl::archived_at,
+                    comment_dsl::archived_by,
+                    comment_dsl::id,
+                    comment_dsl::text,
+                    rating_dsl::id,
+                ))
+                .filter(comment_dsl::id.eq_any(ids))
+                .filter(comment_dsl::archived_at.is_null())
+                .load::<models::PlaceRatingComment>(self)?
+                .into_iter()
+                .map(Into::into)
+                .collect())
+        })
+    }
+
+    fn load_comment(&self, id: &CommentId) -> Result<Comment> {
+        let comments = self.load_comments(&[id.as_str()])?;
+        debug_assert!(comments.len() <= 1);
+        comments.into_iter().next().ok_or(RepoError::NotFound)
+    }
+
+    fn load_comments_of_rating(&self, rating_id: &RatingId) -> Result<Vec<Comment>> {
+        use schema::place_rating::dsl as rating_dsl;
+        use schema::place_rating_comment::dsl as comment_dsl;
+        Ok(schema::place_rating_comment::table
+            .inner_join(schema::place_rating::table)
+            .select((
+                comment_dsl::rowid,
+                comment_dsl::created_at,
+                comment_dsl::created_by,
                 comment_dsl::archived_at,
                 comment_dsl::archived_by,
                 comment_dsl::id,
                 comment_dsl::text,
                 rating_dsl::id,
             ))
-            .filter(rating_dsl::id.eq(rating_id))
+            .filter(rating_dsl::id.eq(rating_id.as_str()))
             .filter(comment_dsl::archived_at.is_null())
             .load::<models::PlaceRatingComment>(self)?
             .into_iter()
@@ -1443,7 +2507,12 @@ impl CommentRepository for SqliteConnection {
             .collect())
     }
 
-    fn archive_comments(&self, ids: &[&str], activity: &Activity) -> Result<usize> {
+    fn archive_comments(
+        &self,
+        ids: &[&str],
+        activity: &Activity,
+        comment: Option<&str>,
+    ) -> Result<usize> {
         use schema::place_rating_comment::dsl;
         let archived_at = Some(activity.at.into_inner());
         let archived_by = if let Some(ref email) = activity.by {
@@ -1459,6 +2528,7 @@ impl CommentRepository for SqliteConnection {
         .set((
             dsl::archived_at.eq(archived_at),
             dsl::archived_by.eq(archived_by),
+            dsl::archived_comment.eq(comment),
         ))
         .execute(self)?;
         debug_assert!(count <= ids.len());
@@ -1558,6 +2628,79 @@ impl Db for SqliteConnection {
         Ok(())
     }
 
+    fn load_comments_for_moderation(
+        &self,
+        params: &CommentModerationParams,
+        pagination: &Pagination,
+    ) -> Result<Vec<ModeratedComment>> {
+        // TODO: Diesel 1.4.x does not support counting
+        // the rows of a joined table per group.
+        let sql = "SELECT * FROM (\
+                   SELECT \
+                   c.id AS comment_id, c.created_at AS comment_created_at, \
+                   c.text AS comment_text, \
+                   r.id AS rating_id, r.created_at AS rating_created_at, \
+                   u.email AS rating_created_by, \
+                   r.title AS rating_title, r.value AS rating_value, \
+                   r.context AS rating_context, r.source AS rating_source, \
+                   p.id AS place_id, pr.title AS place_title, \
+                   (SELECT COUNT(*) FROM place_rating_comment_flag f \
+                   WHERE f.parent_rowid=c.rowid) AS flag_count \
+                   FROM place_rating_comment c \
+                   JOIN place_rating r ON r.rowid=c.parent_rowid \
+                   JOIN place p ON p.rowid=r.parent_rowid \
+                   JOIN place_revision pr ON pr.parent_rowid=p.rowid AND pr.rev=p.current_rev \
+                   LEFT JOIN users u ON u.id=r.created_by \
+                   WHERE c.archived_at IS NULL AND r.archived_at IS NULL \
+                   AND c.created_at>=?) \
+                   WHERE flag_count>=? \
+                   ORDER BY flag_count DESC, comment_created_at DESC \
+                   LIMIT ? OFFSET ?";
+        let since = params
+            .since
+            .map(TimestampMs::into_inner)
+            .unwrap_or(i64::MIN);
+        let min_flag_count = if params.flagged { 1 } else { 0 };
+        // A negative limit means no limit in SQLite
+        let limit = pagination.limit.map(|l| l as i64).unwrap_or(-1);
+        let offset = pagination.offset.unwrap_or(0) as i64;
+        let rows = diesel::dsl::sql_query(sql)
+            .bind::<diesel::sql_types::BigInt, _>(since)
+            .bind::<diesel::sql_types::BigInt, _>(min_flag_count)
+            .bind::<diesel::sql_types::BigInt, _>(limit)
+            .bind::<diesel::sql_types::BigInt, _>(offset)
+            .load::<ModeratedCommentRow>(self)?;
+        rows.into_iter()
+            .map(|row| {
+                let context = util::rating_context_from_str(&row.rating_context).map_err(|_| {
+                    RepoError::Other(anyhow!("Invalid rating context: {}", row.rating_context))
+                })?;
+                Ok(ModeratedComment {
+                    comment: Comment {
+                        id: row.comment_id.into(),
+                        rating_id: row.rating_id.clone().into(),
+                        created_at: TimestampMs::from_inner(row.comment_created_at),
+                        archived_at: None,
+                        text: row.comment_text,
+                    },
+                    rating: Rating {
+                        id: row.rating_id.into(),
+                        place_id: row.place_id.into(),
+                        created_at: TimestampMs::from_inner(row.rating_created_at),
+                        created_by: row.rating_created_by,
+                        archived_at: None,
+                        title: row.rating_title,
+                        value: (row.rating_value as i8).into(),
+                        context,
+                        source: row.rating_source,
+                    },
+                    place_title: row.place_title,
+                    flag_count: row.flag_count as u64,
+                })
+            })
+            .collect()
+    }
+
     fn create_bbox_subscription(&self, new: &BboxSubscription) -> Result<()> {
         let user_id = resolve_user_created_by_email(self, &new.user_email)?;
         let (south_west_lat, south_west_lng) = new.bbox.south_west().to_lat_lng_deg();
@@ -1627,6 +2770,230 @@ impl Db for SqliteConnection {
             .execute(self)?;
         Ok(())
     }
+    fn create_tag_subscription(&self, new: &TagSubscription) -> Result<()> {
+        use schema::tag_subscriptions::dsl;
+        let user_id = resolve_user_created_by_email(self, &new.user_email)?;
+        let south_west = new.bbox.map(|bbox| bbox.south_west().to_lat_lng_deg());
+        let north_east = new.bbox.map(|bbox| bbox.north_east().to_lat_lng_deg());
+        let insertable = models::NewTagSubscription {
+            id: new.id.as_ref(),
+            user_id,
+            south_west_lat: south_west.map(|(lat, _)| lat),
+            south_west_lng: south_west.map(|(_, lng)| lng),
+            north_east_lat: north_east.map(|(lat, _)| lat),
+            north_east_lng: north_east.map(|(_, lng)| lng),
+        };
+        diesel::insert_into(schema::tag_subscriptions::table)
+            .values(&insertable)
+            .execute(self)?;
+        let parent_rowid = dsl::tag_subscriptions
+            .select(dsl::rowid)
+            .filter(dsl::id.eq(new.id.as_str()))
+            .first::<i64>(self)?;
+        let tags: Vec<_> = new
+            .tags
+            .iter()
+            .map(|tag| models::TagSubscriptionTag {
+                parent_rowid,
+                tag: tag.clone(),
+            })
+            .collect();
+        diesel::insert_into(schema::tag_subscription_tags::table)
+            .values(&tags)
+            .execute(self)?;
+        Ok(())
+    }
+    fn all_tag_subscriptions(&self) -> Result<Vec<TagSubscription>> {
+        load_tag_subscriptions(self, None)
+    }
+    fn all_tag_subscriptions_by_email(&self, email: &str) -> Result<Vec<TagSubscription>> {
+        load_tag_subscriptions(self, Some(email))
+    }
+    fn delete_tag_subscription(&self, email: &str, id: &str) -> Result<()> {
+        use schema::tag_subscription_tags::dsl as t_dsl;
+        use schema::tag_subscriptions::dsl as s_dsl;
+        use schema::users::dsl as u_dsl;
+        let users_id = u_dsl::users
+            .select(u_dsl::id)
+            .filter(u_dsl::email.eq(email));
+        let rowid = s_dsl::tag_subscriptions
+            .select(s_dsl::rowid)
+            .filter(s_dsl::id.eq(id))
+            .filter(s_dsl::user_id.eq_any(users_id))
+            .first::<i64>(self)?;
+        diesel::delete(t_dsl::tag_subscription_tags.filter(t_dsl::parent_rowid.eq(rowid)))
+            .execute(self)?;
+        diesel::delete(s_dsl::tag_subscriptions.filter(s_dsl::rowid.eq(rowid))).execute(self)?;
+        Ok(())
+    }
+    fn create_saved_search(&self, new: &SavedSearch) -> Result<()> {
+        let user_id = resolve_user_created_by_email(self, &new.user_email)?;
+        let insertable = models::NewSavedSearch {
+            id: new.id.as_ref(),
+            user_id,
+            created_at: new.created_at.into_inner(),
+            title: &new.title,
+            query: &new.query,
+        };
+        diesel::insert_into(schema::saved_searches::table)
+            .values(&insertable)
+            .execute(self)?;
+        Ok(())
+    }
+    fn all_saved_searches_by_email(&self, email: &str) -> Result<Vec<SavedSearch>> {
+        use schema::saved_searches::dsl as s_dsl;
+        use schema::users::dsl as u_dsl;
+        Ok(s_dsl::saved_searches
+            .inner_join(u_dsl::users)
+            .select((
+                s_dsl::id,
+                s_dsl::created_at,
+                s_dsl::title,
+                s_dsl::query,
+                u_dsl::email,
+            ))
+            .filter(u_dsl::email.eq(email))
+            .order_by(s_dsl::rowid)
+            .load::<models::SavedSearch>(self)?
+            .into_iter()
+            .map(|s| SavedSearch {
+                id: s.id.into(),
+                user_email: s.user_email,
+                created_at: TimestampMs::from_inner(s.created_at),
+                title: s.title,
+                query: s.query,
+            })
+            .collect())
+    }
+    fn delete_saved_search(&self, email: &str, id: &str) -> Result<()> {
+        use schema::saved_searches::dsl as s_dsl;
+        use schema::users::dsl as u_dsl;
+        let users_id = u_dsl::users
+            .select(u_dsl::id)
+            .filter(u_dsl::email.eq(email));
+        let count = diesel::delete(
+            s_dsl::saved_searches
+                .filter(s_dsl::id.eq(id))
+                .filter(s_dsl::user_id.eq_any(users_id)),
+        )
+        .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+    fn save_digest_settings(&self, settings: &DigestSettings) -> Result<()> {
+        let user_id = resolve_user_created_by_email(self, &settings.user_email)?;
+        let insertable = models::NewNotificationDigest {
+            user_id,
+            interval: settings.interval.into(),
+            last_sent_at: settings.last_sent_at.into_inner(),
+        };
+        diesel::replace_into(schema::notification_digests::table)
+            .values(&insertable)
+            .execute(self)?;
+        Ok(())
+    }
+    fn delete_digest_settings(&self, user_email: &str) -> Result<()> {
+        use schema::notification_digests::dsl;
+        let user_id = resolve_user_created_by_email(self, user_email)?;
+        diesel::delete(dsl::notification_digests.filter(dsl::user_id.eq(user_id))).execute(self)?;
+        Ok(())
+    }
+    fn try_get_digest_settings(&self, user_email: &str) -> Result<Option<DigestSettings>> {
+        Ok(load_digest_settings(self, Some(user_email))?
+            .into_iter()
+            .next())
+    }
+    fn all_digest_settings(&self) -> Result<Vec<DigestSettings>> {
+        load_digest_settings(self, None)
+    }
+    fn enqueue_notification(&self, notification: &QueuedNotification) -> Result<()> {
+        let user_id = resolve_user_created_by_email(self, &notification.user_email)?;
+        let insertable = models::NewQueuedNotification {
+            user_id,
+            created_at: notification.created_at.into_inner(),
+            kind: notification.kind.into(),
+            place_id: notification.place_id.as_ref(),
+            place_title: &notification.place_title,
+        };
+        diesel::insert_into(schema::notification_queue::table)
+            .values(&insertable)
+            .execute(self)?;
+        Ok(())
+    }
+    fn load_queued_notifications(&self, user_email: &str) -> Result<Vec<QueuedNotification>> {
+        use schema::notification_queue::dsl as q_dsl;
+        use schema::users::dsl as u_dsl;
+        q_dsl::notification_queue
+            .inner_join(u_dsl::users)
+            .select((
+                q_dsl::created_at,
+                q_dsl::kind,
+                q_dsl::place_id,
+                q_dsl::place_title,
+                u_dsl::email,
+            ))
+            .filter(u_dsl::email.eq(user_email))
+            .order_by(q_dsl::rowid)
+            .load::<models::QueuedNotification>(self)?
+            .into_iter()
+            .map(|row| -> Result<QueuedNotification> {
+                let kind = NotificationKind::try_from(row.kind).ok_or_else(|| {
+                    RepoError::Other(anyhow!("Invalid notification kind: {}", row.kind))
+                })?;
+                Ok(QueuedNotification {
+                    user_email: row.user_email,
+                    kind,
+                    place_id: row.place_id.into(),
+                    place_title: row.place_title,
+                    created_at: TimestampMs::from_inner(row.created_at),
+                })
+            })
+            .collect()
+    }
+    fn delete_queued_notifications(&self, user_email: &str, until: TimestampMs) -> Result<usize> {
+        use schema::notification_queue::dsl;
+        let user_id = resolve_user_created_by_email(self, user_email)?;
+        Ok(diesel::delete(
+            dsl::notification_queue
+                .filter(dsl::user_id.eq(user_id))
+                .filter(dsl::created_at.le(until.into_inner())),
+        )
+        .execute(self)?)
+    }
+    fn all_categories(&self) -> Result<Vec<Category>> {
+        use schema::category::dsl;
+        Ok(dsl::category
+            .order_by(dsl::rowid)
+            .load::<models::Category>(self)?
+            .into_iter()
+            .map(Category::from)
+            .collect())
+    }
+    fn all_curated_tags(&self) -> Result<Vec<CuratedTag>> {
+        use schema::curated_tag::dsl;
+        Ok(dsl::curated_tag
+            .order_by(dsl::tag)
+            .load::<models::CuratedTag>(self)?
+            .into_iter()
+            .map(CuratedTag::from)
+            .collect())
+    }
+    fn save_curated_tag(&self, curated_tag: &CuratedTag) -> Result<()> {
+        diesel::replace_into(schema::curated_tag::table)
+            .values(&models::CuratedTag::from(curated_tag.clone()))
+            .execute(self)?;
+        Ok(())
+    }
+    fn delete_curated_tag(&self, tag: &str) -> Result<()> {
+        use schema::curated_tag::dsl;
+        let count = diesel::delete(dsl::curated_tag.filter(dsl::tag.eq(tag))).execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
     fn all_tags(&self) -> Result<Vec<Tag>> {
         use schema::tags::dsl::*;
         Ok(tags
@@ -1639,55 +3006,250 @@ impl Db for SqliteConnection {
         use schema::tags::dsl::*;
         Ok(tags.select(diesel::dsl::count(id)).first::<i64>(self)? as usize)
     }
+    fn replace_tag(&self, old_tag: &str, new_tag: &str) -> Result<()> {
+        self.create_tag_if_it_does_not_exist(&Tag {
+            id: new_tag.to_owned(),
+        })?;
+        // TODO: Diesel 1.4.x does not support UPDATE OR IGNORE.
+        // Rows that already contain the new tag are not updated
+        // and deleted afterwards.
+        for (table, column) in &[
+            ("place_revision_tag", "tag"),
+            ("event_tags", "tag"),
+            ("org_tag_relations", "tag_id"),
+            ("curated_tag", "tag"),
+            ("tag_subscription_tags", "tag"),
+        ] {
+            diesel::dsl::sql_query(format!(
+                "UPDATE OR IGNORE {} SET {}=? WHERE {}=?",
+                table, column, column
+            ))
+            .bind::<diesel::sql_types::Text, _>(new_tag)
+            .bind::<diesel::sql_types::Text, _>(old_tag)
+            .execute(self)?;
+            diesel::dsl::sql_query(format!("DELETE FROM {} WHERE {}=?", table, column))
+                .bind::<diesel::sql_types::Text, _>(old_tag)
+                .execute(self)?;
+        }
+        use schema::tags::dsl;
+        diesel::delete(dsl::tags.filter(dsl::id.eq(old_tag))).execute(self)?;
+        Ok(())
+    }
+
+    fn delete_tag(&self, tag: &str) -> Result<()> {
+        use schema::{curated_tag::dsl as c_dsl, org_tag_relations::dsl as o_dsl, tags::dsl};
+        diesel::delete(o_dsl::org_tag_relations.filter(o_dsl::tag_id.eq(tag))).execute(self)?;
+        diesel::delete(c_dsl::curated_tag.filter(c_dsl::tag.eq(tag))).execute(self)?;
+        diesel::delete(dsl::tags.filter(dsl::id.eq(tag))).execute(self)?;
+        Ok(())
+    }
+}
+
+fn load_tag_subscriptions(
+    conn: &SqliteConnection,
+    user_email: Option<&str>,
+) -> Result<Vec<TagSubscription>> {
+    use schema::tag_subscription_tags::dsl as t_dsl;
+    use schema::tag_subscriptions::dsl as s_dsl;
+    use schema::users::dsl as u_dsl;
+    let mut subscriptions_query = s_dsl::tag_subscriptions
+        .inner_join(u_dsl::users)
+        .select((
+            s_dsl::rowid,
+            s_dsl::id,
+            s_dsl::south_west_lat,
+            s_dsl::south_west_lng,
+            s_dsl::north_east_lat,
+            s_dsl::north_east_lng,
+            u_dsl::email,
+        ))
+        .order_by(s_dsl::rowid)
+        .into_boxed();
+    let mut tags_query = t_dsl::tag_subscription_tags
+        .order_by((t_dsl::parent_rowid, t_dsl::tag))
+        .into_boxed();
+    if let Some(email) = user_email {
+        subscriptions_query = subscriptions_query.filter(u_dsl::email.eq(email));
+        let users_id = u_dsl::users
+            .select(u_dsl::id)
+            .filter(u_dsl::email.eq(email));
+        tags_query = tags_query.filter(
+            t_dsl::parent_rowid.eq_any(
+                s_dsl::tag_subscriptions
+                    .select(s_dsl::rowid)
+                    .filter(s_dsl::user_id.eq_any(users_id)),
+            ),
+        );
+    }
+    let subscriptions = subscriptions_query.load::<models::TagSubscription>(conn)?;
+    let mut tags_by_rowid: HashMap<i64, Vec<String>> = HashMap::new();
+    for t in tags_query.load::<models::TagSubscriptionTag>(conn)? {
+        tags_by_rowid.entry(t.parent_rowid).or_default().push(t.tag);
+    }
+    Ok(subscriptions
+        .into_iter()
+        .map(|s| {
+            let bbox = match (
+                s.south_west_lat,
+                s.south_west_lng,
+                s.north_east_lat,
+                s.north_east_lng,
+            ) {
+                (Some(sw_lat), Some(sw_lng), Some(ne_lat), Some(ne_lng)) => Some(MapBbox::new(
+                    MapPoint::try_from_lat_lng_deg(sw_lat, sw_lng).unwrap_or_default(),
+                    MapPoint::try_from_lat_lng_deg(ne_lat, ne_lng).unwrap_or_default(),
+                )),
+                _ => None,
+            };
+            TagSubscription {
+                id: s.id.into(),
+                user_email: s.user_email,
+                tags: tags_by_rowid.remove(&s.rowid).unwrap_or_default(),
+                bbox,
+            }
+        })
+        .collect())
+}
+
+fn load_digest_settings(
+    conn: &SqliteConnection,
+    user_email: Option<&str>,
+) -> Result<Vec<DigestSettings>> {
+    use schema::notification_digests::dsl as d_dsl;
+    use schema::users::dsl as u_dsl;
+    let mut query = d_dsl::notification_digests
+        .inner_join(u_dsl::users)
+        .select((d_dsl::interval, d_dsl::last_sent_at, u_dsl::email))
+        .into_boxed();
+    if let Some(user_email) = user_email {
+        query = query.filter(u_dsl::email.eq(user_email));
+    }
+    query
+        .load::<models::NotificationDigest>(conn)?
+        .into_iter()
+        .map(|row| -> Result<DigestSettings> {
+            let interval = DigestInterval::try_from(row.interval).ok_or_else(|| {
+                RepoError::Other(anyhow!("Invalid digest interval: {}", row.interval))
+            })?;
+            Ok(DigestSettings {
+                user_email: row.user_email,
+                interval,
+                last_sent_at: TimestampMs::from_inner(row.last_sent_at),
+            })
+        })
+        .collect()
+}
+
+fn load_org_owned_tags(conn: &SqliteConnection, org_id: &str) -> Result<Vec<String>> {
+    use schema::org_tag_relations::dsl;
+    Ok(dsl::org_tag_relations
+        .filter(dsl::org_id.eq(org_id))
+        .load::<models::OrgTagRelation>(conn)?
+        .into_iter()
+        .map(|r| r.tag_id)
+        .collect())
+}
+
+fn insert_org_owned_tags(
+    conn: &SqliteConnection,
+    org_id: &str,
+    owned_tags: &[String],
+) -> Result<()> {
+    let tag_rels: Vec<_> = owned_tags
+        .iter()
+        .map(|tag_id| models::StoreableOrgTagRelation {
+            org_id,
+            tag_id: &tag_id,
+        })
+        .collect();
+    diesel::insert_into(schema::org_tag_relations::table)
+        //WHERE NOT EXISTS
+        .values(&tag_rels)
+        .execute(conn)?;
+    Ok(())
+}
+
+fn load_org(conn: &SqliteConnection, org: models::Organization) -> Result<Organization> {
+    let models::Organization { id, name } = org;
+    let owned_tags = load_org_owned_tags(conn, &id)?;
+    Ok(Organization {
+        id,
+        name,
+        owned_tags,
+    })
 }
 
 impl OrganizationGateway for SqliteConnection {
-    fn create_org(&mut self, mut o: Organization) -> Result<()> {
-        let org_id = o.id.clone();
+    fn create_org(&self, mut o: Organization) -> Result<()> {
         let owned_tags = std::mem::replace(&mut o.owned_tags, vec![]);
-        let tag_rels: Vec<_> = owned_tags
-            .iter()
-            .map(|tag_id| models::StoreableOrgTagRelation {
-                org_id: &org_id,
-                tag_id: &tag_id,
-            })
-            .collect();
         let new_org = models::Organization::from(o);
-        self.transaction::<_, diesel::result::Error, _>(|| {
+        self.transaction::<_, RepoError, _>(|| {
             diesel::insert_into(schema::organizations::table)
                 .values(&new_org)
                 .execute(self)?;
-            diesel::insert_into(schema::org_tag_relations::table)
-                //WHERE NOT EXISTS
-                .values(&tag_rels)
-                .execute(self)?;
-            Ok(())
-        })?;
-        Ok(())
+            insert_org_owned_tags(self, &new_org.id, &owned_tags)
+        })
     }
-    fn get_org_by_api_token(&self, token: &str) -> Result<Organization> {
-        use schema::{org_tag_relations::dsl as o_t_dsl, organizations::dsl as o_dsl};
 
-        let models::Organization {
-            id,
-            name,
-            api_token,
-        } = o_dsl::organizations
-            .filter(o_dsl::api_token.eq(token))
-            .first(self)?;
+    fn get_org_by_id(&self, id: &str) -> Result<Organization> {
+        use schema::organizations::dsl;
+        let org = dsl::organizations.filter(dsl::id.eq(id)).first(self)?;
+        load_org(self, org)
+    }
 
-        let owned_tags = o_t_dsl::org_tag_relations
-            .filter(o_t_dsl::org_id.eq(&id))
-            .load::<models::OrgTagRelation>(self)?
+    fn all_orgs(&self) -> Result<Vec<Organization>> {
+        use schema::organizations::dsl;
+        dsl::organizations
+            .order_by(dsl::name)
+            .load::<models::Organization>(self)?
             .into_iter()
-            .map(|r| r.tag_id)
-            .collect();
+            .map(|org| load_org(self, org))
+            .collect()
+    }
 
-        Ok(Organization {
-            id,
-            name,
-            api_token,
-            owned_tags,
+    fn update_org(&self, org: &Organization) -> Result<()> {
+        use schema::{org_tag_relations::dsl as o_t_dsl, organizations::dsl as o_dsl};
+        self.transaction::<_, RepoError, _>(|| {
+            let count = diesel::update(o_dsl::organizations.filter(o_dsl::id.eq(&org.id)))
+                .set(o_dsl::name.eq(&org.name))
+                .execute(self)?;
+            if count == 0 {
+                return Err(RepoError::NotFound);
+            }
+            diesel::delete(o_t_dsl::org_tag_relations.filter(o_t_dsl::org_id.eq(&org.id)))
+                .execute(self)?;
+            insert_org_owned_tags(self, &org.id, &org.owned_tags)
+        })
+    }
+
+    fn delete_org(&self, id: &str) -> Result<()> {
+        use schema::{
+            curated_organization::dsl as c_o_dsl, events::dsl as e_dsl,
+            org_tag_relations::dsl as o_t_dsl, organizations::dsl as o_dsl,
+            place_clearance::dsl as c_dsl,
+        };
+        self.transaction::<_, RepoError, _>(|| {
+            diesel::delete(o_t_dsl::org_tag_relations.filter(o_t_dsl::org_id.eq(id)))
+                .execute(self)?;
+            diesel::delete(c_o_dsl::curated_organization.filter(c_o_dsl::org_id.eq(id)))
+                .execute(self)?;
+            diesel::delete(c_dsl::place_clearance.filter(c_dsl::org_id.eq(id))).execute(self)?;
+            // The events remain without their references
+            diesel::update(e_dsl::events.filter(e_dsl::external_org_id.eq(id)))
+                .set((
+                    e_dsl::external_org_id.eq(None::<String>),
+                    e_dsl::external_id.eq(None::<String>),
+                ))
+                .execute(self)?;
+            for token in self.load_org_api_tokens(id)? {
+                self.delete_org_api_token(id, token.id.as_str())?;
+            }
+            let count =
+                diesel::delete(o_dsl::organizations.filter(o_dsl::id.eq(id))).execute(self)?;
+            if count == 0 {
+                return Err(RepoError::NotFound);
+            }
+            Ok(())
         })
     }
 
@@ -1701,6 +3263,49 @@ impl OrganizationGateway for SqliteConnection {
         tags.dedup();
         Ok(tags)
     }
+
+    fn get_all_tag_ownerships(&self) -> Result<Vec<TagOwnership>> {
+        use schema::{org_tag_relations::dsl as o_t_dsl, organizations::dsl as o_dsl};
+        Ok(o_t_dsl::org_tag_relations
+            .inner_join(o_dsl::organizations)
+            .select((o_t_dsl::tag_id, o_dsl::id, o_dsl::name))
+            .order_by((o_t_dsl::tag_id, o_dsl::name))
+            .load::<(String, String, String)>(self)?
+            .into_iter()
+            .map(|(tag, org_id, org_name)| TagOwnership {
+                tag,
+                org_id,
+                org_name,
+            })
+            .collect())
+    }
+
+    fn all_curated_orgs(&self) -> Result<Vec<CuratedOrganization>> {
+        use schema::curated_organization::dsl;
+        Ok(dsl::curated_organization
+            .order_by(dsl::org_id)
+            .load::<models::CuratedOrganization>(self)?
+            .into_iter()
+            .map(CuratedOrganization::from)
+            .collect())
+    }
+
+    fn save_curated_org(&self, curated_org: &CuratedOrganization) -> Result<()> {
+        diesel::replace_into(schema::curated_organization::table)
+            .values(&models::CuratedOrganization::from(curated_org.clone()))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn delete_curated_org(&self, org_id: &str) -> Result<()> {
+        use schema::curated_organization::dsl;
+        let count = diesel::delete(dsl::curated_organization.filter(dsl::org_id.eq(org_id)))
+            .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
 }
 
 impl UserTokenRepo for SqliteConnection {
@@ -1755,6 +3360,20 @@ impl UserTokenRepo for SqliteConnection {
         )
     }
 
+    fn load_valid_user_tokens(&self, expired_before: Timestamp) -> Result<Vec<UserToken>> {
+        use schema::user_tokens::dsl as t_dsl;
+        use schema::users::dsl as u_dsl;
+        Ok(t_dsl::user_tokens
+            .inner_join(u_dsl::users)
+            .select((u_dsl::id, t_dsl::nonce, t_dsl::expires_at, u_dsl::email))
+            .filter(t_dsl::expires_at.ge(expired_before.into_inner()))
+            .order_by(u_dsl::email)
+            .load::<models::UserTokenEntity>(self)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
     fn get_user_token_by_email(&self, email: &str) -> Result<UserToken> {
         use schema::user_tokens::dsl as t_dsl;
         use schema::users::dsl as u_dsl;
@@ -1766,3 +3385,1403 @@ impl UserTokenRepo for SqliteConnection {
             .into())
     }
 }
+
+impl LoginLinkRepo for SqliteConnection {
+    fn replace_login_link(&self, login_link: &LoginLink) -> Result<()> {
+        let model = models::NewUserLoginLink {
+            email: &login_link.email_nonce.email,
+            nonce: login_link.email_nonce.nonce.to_string(),
+            created_at: login_link.created_at.into_inner(),
+            expires_at: login_link.expires_at.into_inner(),
+        };
+        diesel::replace_into(schema::user_login_links::table)
+            .values(&model)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn get_login_link_by_email(&self, email: &str) -> Result<LoginLink> {
+        use schema::user_login_links::dsl;
+        Ok(dsl::user_login_links
+            .select((dsl::email, dsl::nonce, dsl::created_at, dsl::expires_at))
+            .filter(dsl::email.eq(email))
+            .first::<models::UserLoginLink>(self)?
+            .into())
+    }
+
+    fn consume_login_link(&self, email_nonce: &EmailNonce) -> Result<LoginLink> {
+        use schema::user_login_links::dsl;
+        let login_link = self.get_login_link_by_email(&email_nonce.email)?;
+        let target = dsl::user_login_links
+            .filter(dsl::email.eq(&email_nonce.email))
+            .filter(dsl::nonce.eq(email_nonce.nonce.to_string()));
+        if diesel::delete(target).execute(self)? == 0 {
+            return Err(RepoError::NotFound);
+        }
+        debug_assert_eq!(email_nonce, &login_link.email_nonce);
+        Ok(login_link)
+    }
+
+    fn delete_expired_login_links(&self, expired_before: Timestamp) -> Result<usize> {
+        use schema::user_login_links::dsl;
+        Ok(diesel::delete(
+            dsl::user_login_links.filter(dsl::expires_at.lt(expired_before.into_inner())),
+        )
+        .execute(self)?)
+    }
+}
+
+impl ContactVerificationRepo for SqliteConnection {
+    fn replace_contact_verification(&self, verification: &ContactVerification) -> Result<()> {
+        let (parent_rowid, _) = resolve_place_rowid(self, &verification.place_id)?;
+        let model = models::NewPlaceContactVerification {
+            parent_rowid,
+            email: verification.email_nonce.email.clone(),
+            nonce: verification.email_nonce.nonce.to_string(),
+            expires_at: verification.expires_at.into_inner(),
+            verified_at: verification.verified_at.map(TimestampMs::into_inner),
+        };
+        diesel::replace_into(schema::place_contact_verification::table)
+            .values(&model)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_contact_verification(&self, email_nonce: &EmailNonce) -> Result<ContactVerification> {
+        use schema::place::dsl as p_dsl;
+        use schema::place_contact_verification::dsl as v_dsl;
+        let models::PlaceContactVerification {
+            email,
+            nonce,
+            expires_at,
+            verified_at,
+            place_id,
+        } = v_dsl::place_contact_verification
+            .inner_join(p_dsl::place)
+            .select((
+                v_dsl::email,
+                v_dsl::nonce,
+                v_dsl::expires_at,
+                v_dsl::verified_at,
+                p_dsl::id,
+            ))
+            .filter(v_dsl::email.eq(&email_nonce.email))
+            .filter(v_dsl::nonce.eq(email_nonce.nonce.to_string()))
+            .first::<models::PlaceContactVerification>(self)?;
+        Ok(ContactVerification {
+            place_id: place_id.into(),
+            email_nonce: EmailNonce {
+                email,
+                nonce: nonce.parse::<Nonce>().unwrap_or_default(),
+            },
+            expires_at: Timestamp::from_inner(expires_at),
+            verified_at: verified_at.map(TimestampMs::from_inner),
+        })
+    }
+
+    fn is_contact_verified(&self, place_id: &PlaceId, email: &str) -> Result<bool> {
+        use schema::place::dsl as p_dsl;
+        use schema::place_contact_verification::dsl as v_dsl;
+        let count = v_dsl::place_contact_verification
+            .inner_join(p_dsl::place)
+            .filter(p_dsl::id.eq(place_id.as_str()))
+            .filter(v_dsl::email.eq(email))
+            .filter(v_dsl::verified_at.is_not_null())
+            .count()
+            .get_result::<i64>(self)?;
+        Ok(count > 0)
+    }
+}
+
+fn load_event_registration(registration: models::EventRegistration) -> Result<EventRegistration> {
+    use num_traits::FromPrimitive;
+    let models::EventRegistration {
+        email,
+        nonce,
+        status,
+        registered_at,
+        event_id,
+    } = registration;
+    let status = EventRegistrationStatus::from_i16(status).ok_or_else(|| {
+        anyhow!(
+            "Unknown status {} of registration for event {}",
+            status,
+            event_id
+        )
+    })?;
+    Ok(EventRegistration {
+        event_id: event_id.into(),
+        email_nonce: EmailNonce {
+            email,
+            nonce: nonce.parse::<Nonce>().unwrap_or_default(),
+        },
+        status,
+        registered_at: TimestampMs::from_inner(registered_at),
+    })
+}
+
+impl EventRegistrationRepo for SqliteConnection {
+    fn replace_event_registration(&self, registration: &EventRegistration) -> Result<()> {
+        use num_traits::ToPrimitive;
+        let event_id = resolve_event_id(self, registration.event_id.as_ref())?;
+        let model = models::NewEventRegistration {
+            event_id,
+            email: registration.email_nonce.email.clone(),
+            nonce: registration.email_nonce.nonce.to_string(),
+            status: registration.status.to_i16().unwrap(),
+            registered_at: registration.registered_at.into_inner(),
+        };
+        diesel::replace_into(schema::event_registrations::table)
+            .values(&model)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_event_registration(&self, email_nonce: &EmailNonce) -> Result<EventRegistration> {
+        use schema::event_registrations::dsl as r_dsl;
+        use schema::events::dsl as e_dsl;
+        let registration = r_dsl::event_registrations
+            .inner_join(e_dsl::events)
+            .select((
+                r_dsl::email,
+                r_dsl::nonce,
+                r_dsl::status,
+                r_dsl::registered_at,
+                e_dsl::uid,
+            ))
+            .filter(r_dsl::email.eq(&email_nonce.email))
+            .filter(r_dsl::nonce.eq(email_nonce.nonce.to_string()))
+            .first::<models::EventRegistration>(self)?;
+        load_event_registration(registration)
+    }
+
+    fn load_event_registrations(&self, event_id: &EventId) -> Result<Vec<EventRegistration>> {
+        use schema::event_registrations::dsl as r_dsl;
+        use schema::events::dsl as e_dsl;
+        r_dsl::event_registrations
+            .inner_join(e_dsl::events)
+            .select((
+                r_dsl::email,
+                r_dsl::nonce,
+                r_dsl::status,
+                r_dsl::registered_at,
+                e_dsl::uid,
+            ))
+            .filter(e_dsl::uid.eq(event_id.as_str()))
+            .order_by((r_dsl::registered_at, r_dsl::rowid))
+            .load::<models::EventRegistration>(self)?
+            .into_iter()
+            .map(load_event_registration)
+            .collect()
+    }
+}
+
+impl PlaceExternalIdRepo for SqliteConnection {
+    fn load_place_external_ids(&self, place_id: &PlaceId) -> Result<Vec<ExternalId>> {
+        use schema::place::dsl as p_dsl;
+        use schema::place_external_id::dsl;
+        Ok(dsl::place_external_id
+            .inner_join(p_dsl::place)
+            .select((dsl::source, dsl::external_id, dsl::url))
+            .filter(p_dsl::id.eq(place_id.as_str()))
+            .order_by(dsl::source)
+            .then_order_by(dsl::external_id)
+            .load::<models::PlaceExternalId>(self)?
+            .into_iter()
+            .map(
+                |models::PlaceExternalId {
+                     source,
+                     external_id,
+                     url,
+                 }| ExternalId {
+                    source,
+                    id: external_id,
+                    url: url.and_then(load_url),
+                },
+            )
+            .collect())
+    }
+
+    fn replace_place_external_ids(
+        &self,
+        place_id: &PlaceId,
+        external_ids: &[ExternalId],
+    ) -> Result<()> {
+        use schema::place_external_id::dsl;
+        let (parent_rowid, _) = resolve_place_rowid(self, &place_id.as_str().into())?;
+        // All previous ids are only replaced if all new ids
+        // have been stored successfully
+        self.transaction::<_, RepoError, _>(|| {
+            diesel::delete(dsl::place_external_id.filter(dsl::parent_rowid.eq(parent_rowid)))
+                .execute(self)?;
+            for external_id in external_ids {
+                let model = models::NewPlaceExternalId {
+                    parent_rowid,
+                    source: &external_id.source,
+                    external_id: &external_id.id,
+                    url: external_id.url.as_ref().map(Url::to_string),
+                };
+                diesel::insert_into(schema::place_external_id::table)
+                    .values(&model)
+                    .execute(self)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn find_place_id_by_external_id(&self, source: &str, id: &str) -> Result<Option<PlaceId>> {
+        use schema::place::dsl as p_dsl;
+        use schema::place_external_id::dsl;
+        Ok(dsl::place_external_id
+            .inner_join(p_dsl::place)
+            .select(p_dsl::id)
+            .filter(dsl::source.eq(source))
+            .filter(dsl::external_id.eq(id))
+            .first::<String>(self)
+            .optional()?
+            .map(Into::into))
+    }
+}
+
+impl PlaceMergeRepo for SqliteConnection {
+    fn create_place_merge(
+        &self,
+        duplicate_id: &PlaceId,
+        primary_id: &PlaceId,
+        merged_at: TimestampMs,
+    ) -> Result<()> {
+        let (parent_rowid, _) = resolve_place_rowid(self, &duplicate_id.as_str().into())?;
+        let (primary_rowid, _) = resolve_place_rowid(self, &primary_id.as_str().into())?;
+        let model = models::NewPlaceMerge {
+            parent_rowid,
+            primary_rowid,
+            merged_at: merged_at.into_inner(),
+        };
+        diesel::replace_into(schema::place_merge::table)
+            .values(&model)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn get_place_merged_into(&self, place_id: &PlaceId) -> Result<Option<PlaceId>> {
+        use schema::place::dsl as p_dsl;
+        use schema::place_merge::dsl;
+        let primary_rowid = dsl::place_merge
+            .inner_join(p_dsl::place)
+            .select(dsl::primary_rowid)
+            .filter(p_dsl::id.eq(place_id.as_str()))
+            .first::<i64>(self)
+            .optional()?;
+        if let Some(primary_rowid) = primary_rowid {
+            let primary_id = p_dsl::place
+                .select(p_dsl::id)
+                .filter(p_dsl::rowid.eq(primary_rowid))
+                .first::<String>(self)?;
+            Ok(Some(primary_id.into()))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl PlaceDraftRepo for SqliteConnection {
+    fn create_place_draft(&self, draft: &PlaceDraft) -> Result<()> {
+        let owner = resolve_user_created_by_email(self, &draft.owner_email)?;
+        let model = models::NewPlaceDraft {
+            id: draft.id.as_str(),
+            owner,
+            created_at: draft.created_at.into_inner(),
+            updated_at: draft.updated_at.into_inner(),
+            data: &draft.data,
+        };
+        diesel::insert_into(schema::place_draft::table)
+            .values(&model)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn update_place_draft(&self, draft: &PlaceDraft) -> Result<()> {
+        use schema::place_draft::dsl;
+        let count = diesel::update(dsl::place_draft.filter(dsl::id.eq(draft.id.as_str())))
+            .set((
+                dsl::updated_at.eq(draft.updated_at.into_inner()),
+                dsl::data.eq(&draft.data),
+            ))
+            .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn load_place_draft(&self, id: &str) -> Result<PlaceDraft> {
+        use schema::place_draft::dsl;
+        use schema::users::dsl as u_dsl;
+        Ok(dsl::place_draft
+            .inner_join(u_dsl::users)
+            .select((
+                dsl::id,
+                dsl::created_at,
+                dsl::updated_at,
+                dsl::data,
+                u_dsl::email,
+            ))
+            .filter(dsl::id.eq(id))
+            .first::<models::PlaceDraft>(self)?
+            .into())
+    }
+
+    fn load_place_drafts_of_owner(&self, owner_email: &str) -> Result<Vec<PlaceDraft>> {
+        use schema::place_draft::dsl;
+        use schema::users::dsl as u_dsl;
+        Ok(dsl::place_draft
+            .inner_join(u_dsl::users)
+            .select((
+                dsl::id,
+                dsl::created_at,
+                dsl::updated_at,
+                dsl::data,
+                u_dsl::email,
+            ))
+            .filter(u_dsl::email.eq(owner_email))
+            .order_by(dsl::updated_at.desc())
+            .load::<models::PlaceDraft>(self)?
+            .into_iter()
+            .map(PlaceDraft::from)
+            .collect())
+    }
+
+    fn delete_place_draft(&self, id: &str) -> Result<()> {
+        use schema::place_draft::dsl;
+        diesel::delete(dsl::place_draft.filter(dsl::id.eq(id))).execute(self)?;
+        Ok(())
+    }
+}
+
+impl UserPermissionRepo for SqliteConnection {
+    fn load_permission_overrides(&self, user_email: &str) -> Result<Vec<PermissionOverride>> {
+        use num_traits::FromPrimitive;
+        use schema::user_permission::dsl;
+        use schema::users::dsl as u_dsl;
+        let rows = dsl::user_permission
+            .inner_join(u_dsl::users)
+            .select((dsl::permission, dsl::granted))
+            .filter(u_dsl::email.eq(user_email))
+            .order_by(dsl::permission)
+            .load::<(i16, bool)>(self)?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|(permission, granted)| {
+                let permission = Permission::from_i16(permission);
+                if permission.is_none() {
+                    warn!("Ignoring unknown permission of user {}", user_email);
+                }
+                permission.map(|permission| PermissionOverride {
+                    permission,
+                    granted,
+                })
+            })
+            .collect())
+    }
+
+    fn replace_permission_override(
+        &self,
+        user_email: &str,
+        permission_override: &PermissionOverride,
+    ) -> Result<()> {
+        use num_traits::ToPrimitive;
+        let model = models::NewUserPermission {
+            user_id: resolve_user_created_by_email(self, user_email)?,
+            permission: permission_override.permission.to_i16().unwrap(),
+            granted: permission_override.granted,
+        };
+        diesel::replace_into(schema::user_permission::table)
+            .values(&model)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn delete_permission_override(&self, user_email: &str, permission: Permission) -> Result<()> {
+        use num_traits::ToPrimitive;
+        use schema::user_permission::dsl;
+        use schema::users::dsl as u_dsl;
+        diesel::delete(
+            dsl::user_permission
+                .filter(
+                    dsl::user_id.eq_any(
+                        u_dsl::users
+                            .select(u_dsl::id)
+                            .filter(u_dsl::email.eq(user_email)),
+                    ),
+                )
+                .filter(dsl::permission.eq(permission.to_i16().unwrap())),
+        )
+        .execute(self)?;
+        Ok(())
+    }
+}
+
+impl AuditLogRepo for SqliteConnection {
+    fn log_audit_entry(&self, entry: &AuditLogEntry) -> Result<()> {
+        let AuditLogEntry {
+            activity:
+                ActivityLog {
+                    activity,
+                    context,
+                    comment,
+                },
+            action,
+            item,
+        } = entry;
+        let created_by = if let Some(ref email) = activity.by {
+            Some(resolve_user_created_by_email(self, email.as_ref())?)
+        } else {
+            None
+        };
+        let model = models::NewAuditLogEntry {
+            created_at: activity.at.into_inner(),
+            created_by,
+            action: AuditActionPrimitive::from(*action),
+            item,
+            context: context.as_deref(),
+            comment: comment.as_deref(),
+        };
+        diesel::insert_into(schema::audit_log::table)
+            .values(&model)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_audit_log(
+        &self,
+        filter: &AuditLogFilter,
+        pagination: &Pagination,
+    ) -> Result<Vec<AuditLogEntry>> {
+        use schema::audit_log::dsl;
+        use schema::users::dsl as u_dsl;
+        let mut query = schema::audit_log::table
+            .left_outer_join(schema::users::table.on(dsl::created_by.eq(u_dsl::id.nullable())))
+            .select((
+                dsl::created_at,
+                u_dsl::email.nullable(),
+                dsl::action,
+                dsl::item,
+                dsl::context,
+                dsl::comment,
+            ))
+            .order_by(dsl::created_at.desc())
+            .then_order_by(dsl::rowid.desc()) // disambiguation of equal time stamps
+            .into_boxed();
+        if let Some(action) = filter.action {
+            query = query.filter(dsl::action.eq(AuditActionPrimitive::from(action)));
+        }
+        if let Some(ref email) = filter.created_by {
+            query = query.filter(u_dsl::email.eq(email));
+        }
+        if let Some(ref item) = filter.item {
+            query = query.filter(dsl::item.eq(item));
+        }
+        if let Some(since) = filter.since {
+            query = query.filter(dsl::created_at.ge(since.into_inner()));
+        }
+        if let Some(until) = filter.until {
+            query = query.filter(dsl::created_at.lt(until.into_inner()));
+        }
+
+        // Pagination
+        let offset = pagination.offset.unwrap_or(0);
+        if offset > 0 {
+            query = query.offset(offset as i64);
+        }
+        if let Some(limit) = pagination.limit {
+            query = query.limit(limit as i64);
+        }
+
+        let rows = query.load::<models::AuditLogEntry>(self)?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let action = AuditAction::try_from(row.action);
+                if action.is_none() {
+                    warn!("Ignoring unknown audit log action {}", row.action);
+                }
+                action.map(|action| AuditLogEntry {
+                    activity: ActivityLog {
+                        activity: Activity {
+                            at: TimestampMs::from_inner(row.created_at),
+                            by: row.created_by_email.map(Into::into),
+                        },
+                        context: row.context,
+                        comment: row.comment,
+                    },
+                    action,
+                    item: row.item,
+                })
+            })
+            .collect())
+    }
+}
+
+fn announcements_from_rows(rows: Vec<models::Announcement>) -> Vec<Announcement> {
+    rows.into_iter()
+        .filter_map(|row| {
+            let severity = AnnouncementSeverity::try_from(row.severity);
+            if severity.is_none() {
+                warn!("Ignoring unknown announcement severity {}", row.severity);
+            }
+            severity.map(|severity| Announcement {
+                id: row.id.into(),
+                message: row.message,
+                severity,
+                starts_at: TimestampMs::from_inner(row.starts_at),
+                ends_at: row.ends_at.map(TimestampMs::from_inner),
+            })
+        })
+        .collect()
+}
+
+impl AnnouncementRepo for SqliteConnection {
+    fn create_announcement(&self, announcement: &Announcement) -> Result<()> {
+        let model = models::NewAnnouncement {
+            id: announcement.id.as_str(),
+            message: &announcement.message,
+            severity: AnnouncementSeverityPrimitive::from(announcement.severity),
+            starts_at: announcement.starts_at.into_inner(),
+            ends_at: announcement.ends_at.map(TimestampMs::into_inner),
+        };
+        diesel::insert_into(schema::announcement::table)
+            .values(&model)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn delete_announcement(&self, id: &str) -> Result<()> {
+        use schema::announcement::dsl;
+        let count = diesel::delete(dsl::announcement.filter(dsl::id.eq(id))).execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn all_announcements(&self) -> Result<Vec<Announcement>> {
+        use schema::announcement::dsl;
+        let rows = dsl::announcement
+            .order_by(dsl::starts_at)
+            .then_order_by(dsl::rowid)
+            .load::<models::Announcement>(self)?;
+        Ok(announcements_from_rows(rows))
+    }
+
+    fn load_announcements_displayed_at(&self, at: TimestampMs) -> Result<Vec<Announcement>> {
+        use schema::announcement::dsl;
+        let at = at.into_inner();
+        let rows = dsl::announcement
+            .filter(dsl::starts_at.le(at))
+            .filter(dsl::ends_at.is_null().or(dsl::ends_at.gt(at)))
+            .order_by(dsl::starts_at)
+            .then_order_by(dsl::rowid)
+            .load::<models::Announcement>(self)?;
+        Ok(announcements_from_rows(rows))
+    }
+}
+
+impl PlaceClearanceRepo for SqliteConnection {
+    fn add_pending_clearance_for_place(
+        &self,
+        org_ids: &[&str],
+        pending: &PendingClearanceForPlace,
+    ) -> Result<()> {
+        use schema::place_clearance::dsl;
+        let (place_rowid, _) = resolve_place_rowid(self, &pending.place_id)?;
+        for &org_id in org_ids {
+            let is_pending = dsl::place_clearance
+                .select(dsl::rowid)
+                .filter(dsl::org_id.eq(org_id))
+                .filter(dsl::place_rowid.eq(place_rowid))
+                .first::<i64>(self)
+                .optional()?
+                .is_some();
+            if is_pending {
+                continue;
+            }
+            let model = models::NewPlaceClearance {
+                org_id,
+                place_rowid,
+                created_at: pending.created_at.into_inner(),
+                last_cleared_revision: pending
+                    .last_cleared_revision
+                    .map(|rev| u64::from(rev) as i64),
+            };
+            diesel::insert_into(schema::place_clearance::table)
+                .values(&model)
+                .execute(self)?;
+        }
+        Ok(())
+    }
+
+    fn load_pending_clearances_for_places(
+        &self,
+        org_id: &str,
+    ) -> Result<Vec<PendingClearanceForPlace>> {
+        use schema::place::dsl as p_dsl;
+        use schema::place_clearance::dsl;
+        Ok(dsl::place_clearance
+            .inner_join(p_dsl::place)
+            .select((p_dsl::id, dsl::created_at, dsl::last_cleared_revision))
+            .filter(dsl::org_id.eq(org_id))
+            .order_by(dsl::created_at)
+            .then_order_by(dsl::rowid)
+            .load::<models::PendingPlaceClearance>(self)?
+            .into_iter()
+            .map(
+                |models::PendingPlaceClearance {
+                     place_id,
+                     created_at,
+                     last_cleared_revision,
+                 }| PendingClearanceForPlace {
+                    place_id: place_id.into(),
+                    created_at: TimestampMs::from_inner(created_at),
+                    last_cleared_revision: last_cleared_revision
+                        .map(|rev| Revision::from(rev as u64)),
+                },
+            )
+            .collect())
+    }
+
+    fn delete_pending_clearance_for_place(&self, org_id: &str, place_id: &str) -> Result<()> {
+        use schema::place_clearance::dsl;
+        let (place_rowid, _) = resolve_place_rowid(self, &Id::from(place_id))?;
+        let count = diesel::delete(
+            dsl::place_clearance
+                .filter(dsl::org_id.eq(org_id))
+                .filter(dsl::place_rowid.eq(place_rowid)),
+        )
+        .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn count_pending_clearances_for_place(&self, place_id: &str) -> Result<usize> {
+        use schema::place::dsl as p_dsl;
+        use schema::place_clearance::dsl;
+        Ok(dsl::place_clearance
+            .inner_join(p_dsl::place)
+            .select(diesel::dsl::count(dsl::rowid))
+            .filter(p_dsl::id.eq(place_id))
+            .first::<i64>(self)? as usize)
+    }
+}
+
+fn load_org_api_token(
+    conn: &SqliteConnection,
+    token: models::OrganizationApiToken,
+) -> Result<OrganizationApiToken> {
+    use num_traits::FromPrimitive;
+    use schema::organization_api_token_scope::dsl;
+    let models::OrganizationApiToken {
+        rowid,
+        id,
+        org_id,
+        token,
+        created_at,
+        expires_at,
+    } = token;
+    let scopes = dsl::organization_api_token_scope
+        .select(dsl::scope)
+        .filter(dsl::token_rowid.eq(rowid))
+        .order_by(dsl::scope)
+        .load::<i16>(conn)?
+        .into_iter()
+        .filter_map(|scope| {
+            let scope = ApiTokenScope::from_i16(scope);
+            if scope.is_none() {
+                warn!("Ignoring unknown scope of API token {}", id);
+            }
+            scope
+        })
+        .collect();
+    Ok(OrganizationApiToken {
+        id: id.into(),
+        org_id,
+        token,
+        scopes,
+        created_at: TimestampMs::from_inner(created_at),
+        expires_at: expires_at.map(TimestampMs::from_inner),
+    })
+}
+
+impl UserApiTokenRepo for SqliteConnection {
+    fn create_user_api_token(&self, token: &UserApiToken) -> Result<()> {
+        let model = models::NewUserApiToken {
+            id: token.id.as_str(),
+            user_id: resolve_user_created_by_email(self, &token.user_email)?,
+            token: &token.token,
+            label: token.label.as_deref(),
+            created_at: token.created_at.into_inner(),
+        };
+        diesel::insert_into(schema::user_api_tokens::table)
+            .values(&model)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_user_api_tokens(&self, user_email: &str) -> Result<Vec<UserApiToken>> {
+        use schema::user_api_tokens::dsl;
+        use schema::users::dsl as u_dsl;
+        Ok(dsl::user_api_tokens
+            .inner_join(u_dsl::users)
+            .select((
+                dsl::id,
+                dsl::token,
+                dsl::label,
+                dsl::created_at,
+                u_dsl::email,
+            ))
+            .filter(u_dsl::email.eq(user_email))
+            .order_by(dsl::created_at)
+            .then_order_by(dsl::rowid)
+            .load::<models::UserApiToken>(self)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    fn get_user_api_token(&self, token: &str) -> Result<UserApiToken> {
+        use schema::user_api_tokens::dsl;
+        use schema::users::dsl as u_dsl;
+        Ok(dsl::user_api_tokens
+            .inner_join(u_dsl::users)
+            .select((
+                dsl::id,
+                dsl::token,
+                dsl::label,
+                dsl::created_at,
+                u_dsl::email,
+            ))
+            .filter(dsl::token.eq(token))
+            .first::<models::UserApiToken>(self)?
+            .into())
+    }
+
+    fn delete_user_api_token(&self, user_email: &str, id: &str) -> Result<()> {
+        use schema::user_api_tokens::dsl;
+        let user_id = resolve_user_created_by_email(self, user_email)?;
+        let count = diesel::delete(
+            dsl::user_api_tokens
+                .filter(dsl::user_id.eq(user_id))
+                .filter(dsl::id.eq(id)),
+        )
+        .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+impl OrganizationApiTokenRepo for SqliteConnection {
+    fn create_org_api_token(&self, token: &OrganizationApiToken) -> Result<()> {
+        use num_traits::ToPrimitive;
+        use schema::organization_api_token::dsl;
+        let model = models::NewOrganizationApiToken {
+            id: token.id.as_str(),
+            org_id: &token.org_id,
+            token: &token.token,
+            created_at: token.created_at.into_inner(),
+            expires_at: token.expires_at.map(TimestampMs::into_inner),
+        };
+        self.transaction::<_, RepoError, _>(|| {
+            diesel::insert_into(schema::organization_api_token::table)
+                .values(&model)
+                .execute(self)?;
+            let token_rowid = dsl::organization_api_token
+                .select(dsl::rowid)
+                .filter(dsl::id.eq(model.id))
+                .first::<i64>(self)?;
+            let scopes: Vec<_> = token
+                .scopes
+                .iter()
+                .map(|scope| models::NewOrganizationApiTokenScope {
+                    token_rowid,
+                    scope: scope.to_i16().unwrap(),
+                })
+                .collect();
+            diesel::insert_into(schema::organization_api_token_scope::table)
+                .values(&scopes)
+                .execute(self)?;
+            Ok(())
+        })
+    }
+
+    fn load_org_api_tokens(&self, org_id: &str) -> Result<Vec<OrganizationApiToken>> {
+        use schema::organization_api_token::dsl;
+        dsl::organization_api_token
+            .filter(dsl::org_id.eq(org_id))
+            .order_by(dsl::created_at)
+            .then_order_by(dsl::rowid)
+            .load::<models::OrganizationApiToken>(self)?
+            .into_iter()
+            .map(|token| load_org_api_token(self, token))
+            .collect()
+    }
+
+    fn get_org_api_token(&self, token: &str) -> Result<OrganizationApiToken> {
+        use schema::organization_api_token::dsl;
+        let token = dsl::organization_api_token
+            .filter(dsl::token.eq(token))
+            .first(self)?;
+        load_org_api_token(self, token)
+    }
+
+    fn delete_org_api_token(&self, org_id: &str, id: &str) -> Result<()> {
+        use schema::{organization_api_token::dsl, organization_api_token_scope::dsl as s_dsl};
+        self.transaction::<_, RepoError, _>(|| {
+            let token_rowid = dsl::organization_api_token
+                .select(dsl::rowid)
+                .filter(dsl::org_id.eq(org_id))
+                .filter(dsl::id.eq(id))
+                .first::<i64>(self)?;
+            diesel::delete(
+                s_dsl::organization_api_token_scope.filter(s_dsl::token_rowid.eq(token_rowid)),
+            )
+            .execute(self)?;
+            diesel::delete(dsl::organization_api_token.filter(dsl::rowid.eq(token_rowid)))
+                .execute(self)?;
+            Ok(())
+        })
+    }
+}
+
+fn load_webhook(conn: &SqliteConnection, webhook: models::Webhook) -> Result<Webhook> {
+    use num_traits::FromPrimitive;
+    use schema::webhook_event::dsl;
+    let models::Webhook {
+        rowid,
+        id,
+        url,
+        secret,
+        created_at,
+    } = webhook;
+    let events = dsl::webhook_event
+        .select(dsl::event)
+        .filter(dsl::webhook_rowid.eq(rowid))
+        .order_by(dsl::event)
+        .load::<i16>(conn)?
+        .into_iter()
+        .filter_map(|event| {
+            let event = WebhookEvent::from_i16(event);
+            if event.is_none() {
+                warn!("Ignoring unknown event of webhook {}", id);
+            }
+            event
+        })
+        .collect();
+    let url = load_url(url).ok_or_else(|| anyhow!("Invalid URL of webhook {}", id))?;
+    Ok(Webhook {
+        id: id.into(),
+        url,
+        secret,
+        events,
+        created_at: TimestampMs::from_inner(created_at),
+    })
+}
+
+fn load_webhook_delivery(delivery: models::WebhookDelivery) -> Result<WebhookDelivery> {
+    use num_traits::FromPrimitive;
+    let models::WebhookDelivery {
+        id,
+        event,
+        payload,
+        created_at,
+        status,
+        attempts,
+        last_attempt_at,
+        next_attempt_at,
+        response_status,
+        webhook_id,
+    } = delivery;
+    let event = WebhookEvent::from_i16(event)
+        .ok_or_else(|| anyhow!("Unknown event {} of webhook delivery {}", event, id))?;
+    let status = WebhookDeliveryStatus::from_i16(status)
+        .ok_or_else(|| anyhow!("Unknown status {} of webhook delivery {}", status, id))?;
+    Ok(WebhookDelivery {
+        id: id.into(),
+        webhook_id: webhook_id.into(),
+        event,
+        payload,
+        created_at: TimestampMs::from_inner(created_at),
+        status,
+        attempts: attempts as u32,
+        last_attempt_at: last_attempt_at.map(TimestampMs::from_inner),
+        next_attempt_at: next_attempt_at.map(TimestampMs::from_inner),
+        response_status: response_status.map(|status| status as u16),
+    })
+}
+
+impl WebhookRepo for SqliteConnection {
+    fn create_webhook(&self, webhook: &Webhook) -> Result<()> {
+        use num_traits::ToPrimitive;
+        use schema::webhook::dsl;
+        let model = models::NewWebhook {
+            id: webhook.id.as_str(),
+            url: webhook.url.as_str(),
+            secret: &webhook.secret,
+            created_at: webhook.created_at.into_inner(),
+        };
+        self.transaction::<_, RepoError, _>(|| {
+            diesel::insert_into(schema::webhook::table)
+                .values(&model)
+                .execute(self)?;
+            let webhook_rowid = dsl::webhook
+                .select(dsl::rowid)
+                .filter(dsl::id.eq(model.id))
+                .first::<i64>(self)?;
+            let events: Vec<_> = webhook
+                .events
+                .iter()
+                .map(|event| models::NewWebhookEvent {
+                    webhook_rowid,
+                    event: event.to_i16().unwrap(),
+                })
+                .collect();
+            diesel::insert_into(schema::webhook_event::table)
+                .values(&events)
+                .execute(self)?;
+            Ok(())
+        })
+    }
+
+    fn all_webhooks(&self) -> Result<Vec<Webhook>> {
+        use schema::webhook::dsl;
+        dsl::webhook
+            .order_by(dsl::created_at)
+            .then_order_by(dsl::rowid)
+            .load::<models::Webhook>(self)?
+            .into_iter()
+            .map(|webhook| load_webhook(self, webhook))
+            .collect()
+    }
+
+    fn get_webhook(&self, id: &str) -> Result<Webhook> {
+        use schema::webhook::dsl;
+        let webhook = dsl::webhook.filter(dsl::id.eq(id)).first(self)?;
+        load_webhook(self, webhook)
+    }
+
+    fn delete_webhook(&self, id: &str) -> Result<()> {
+        use schema::{webhook::dsl, webhook_delivery::dsl as d_dsl, webhook_event::dsl as e_dsl};
+        self.transaction::<_, RepoError, _>(|| {
+            let webhook_rowid = dsl::webhook
+                .select(dsl::rowid)
+                .filter(dsl::id.eq(id))
+                .first::<i64>(self)?;
+            diesel::delete(d_dsl::webhook_delivery.filter(d_dsl::webhook_rowid.eq(webhook_rowid)))
+                .execute(self)?;
+            diesel::delete(e_dsl::webhook_event.filter(e_dsl::webhook_rowid.eq(webhook_rowid)))
+                .execute(self)?;
+            diesel::delete(dsl::webhook.filter(dsl::rowid.eq(webhook_rowid))).execute(self)?;
+            Ok(())
+        })
+    }
+
+    fn create_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<()> {
+        use num_traits::ToPrimitive;
+        use schema::webhook::dsl;
+        let webhook_rowid = dsl::webhook
+            .select(dsl::rowid)
+            .filter(dsl::id.eq(delivery.webhook_id.as_str()))
+            .first::<i64>(self)?;
+        let model = models::NewWebhookDelivery {
+            id: delivery.id.as_str(),
+            webhook_rowid,
+            event: delivery.event.to_i16().unwrap(),
+            payload: &delivery.payload,
+            created_at: delivery.created_at.into_inner(),
+            status: delivery.status.to_i16().unwrap(),
+            attempts: delivery.attempts as i32,
+            last_attempt_at: delivery.last_attempt_at.map(TimestampMs::into_inner),
+            next_attempt_at: delivery.next_attempt_at.map(TimestampMs::into_inner),
+            response_status: delivery.response_status.map(i32::from),
+        };
+        diesel::insert_into(schema::webhook_delivery::table)
+            .values(&model)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn update_webhook_delivery(&self, delivery: &WebhookDelivery) -> Result<()> {
+        use num_traits::ToPrimitive;
+        use schema::webhook_delivery::dsl;
+        let count = diesel::update(dsl::webhook_delivery.filter(dsl::id.eq(delivery.id.as_str())))
+            .set((
+                dsl::status.eq(delivery.status.to_i16().unwrap()),
+                dsl::attempts.eq(delivery.attempts as i32),
+                dsl::last_attempt_at.eq(delivery.last_attempt_at.map(TimestampMs::into_inner)),
+                dsl::next_attempt_at.eq(delivery.next_attempt_at.map(TimestampMs::into_inner)),
+                dsl::response_status.eq(delivery.response_status.map(i32::from)),
+            ))
+            .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn load_due_webhook_deliveries(
+        &self,
+        due_at: TimestampMs,
+        limit: u64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        use num_traits::ToPrimitive;
+        use schema::{webhook::dsl as w_dsl, webhook_delivery::dsl};
+        dsl::webhook_delivery
+            .inner_join(w_dsl::webhook)
+            .select((
+                dsl::id,
+                dsl::event,
+                dsl::payload,
+                dsl::created_at,
+                dsl::status,
+                dsl::attempts,
+                dsl::last_attempt_at,
+                dsl::next_attempt_at,
+                dsl::response_status,
+                w_dsl::id,
+            ))
+            .filter(dsl::status.eq(WebhookDeliveryStatus::Pending.to_i16().unwrap()))
+            .filter(dsl::next_attempt_at.le(due_at.into_inner()))
+            .order_by(dsl::next_attempt_at)
+            .then_order_by(dsl::rowid)
+            .limit(limit as i64)
+            .load::<models::WebhookDelivery>(self)?
+            .into_iter()
+            .map(load_webhook_delivery)
+            .collect()
+    }
+
+    fn load_webhook_deliveries(
+        &self,
+        webhook_id: &str,
+        pagination: &Pagination,
+    ) -> Result<Vec<WebhookDelivery>> {
+        use schema::{webhook::dsl as w_dsl, webhook_delivery::dsl};
+        let mut query = dsl::webhook_delivery
+            .inner_join(w_dsl::webhook)
+            .select((
+                dsl::id,
+                dsl::event,
+                dsl::payload,
+                dsl::created_at,
+                dsl::status,
+                dsl::attempts,
+                dsl::last_attempt_at,
+                dsl::next_attempt_at,
+                dsl::response_status,
+                w_dsl::id,
+            ))
+            .filter(w_dsl::id.eq(webhook_id))
+            .order_by(dsl::created_at.desc())
+            .then_order_by(dsl::rowid.desc()) // disambiguation of equal time stamps
+            .into_boxed();
+
+        // Pagination
+        let offset = pagination.offset.unwrap_or(0);
+        if offset > 0 {
+            query = query.offset(offset as i64);
+        }
+        if let Some(limit) = pagination.limit {
+            query = query.limit(limit as i64);
+        }
+
+        query
+            .load::<models::WebhookDelivery>(self)?
+            .into_iter()
+            .map(load_webhook_delivery)
+            .collect()
+    }
+}
+
+fn load_outgoing_email(email: models::OutgoingEmail) -> Result<OutgoingEmail> {
+    use num_traits::FromPrimitive;
+    let models::OutgoingEmail {
+        id,
+        recipient,
+        subject,
+        body,
+        created_at,
+        status,
+        attempts,
+        last_attempt_at,
+        next_attempt_at,
+        last_error,
+        ..
+    } = email;
+    let status = OutgoingEmailStatus::from_i16(status)
+        .ok_or_else(|| anyhow!("Unknown status {} of outgoing e-mail {}", status, id))?;
+    Ok(OutgoingEmail {
+        id: id.into(),
+        recipient: recipient.into(),
+        subject,
+        body,
+        created_at: TimestampMs::from_inner(created_at),
+        status,
+        attempts: attempts as u32,
+        last_attempt_at: last_attempt_at.map(TimestampMs::from_inner),
+        next_attempt_at: next_attempt_at.map(TimestampMs::from_inner),
+        last_error,
+    })
+}
+
+impl EmailOutboxRepo for SqliteConnection {
+    fn create_outgoing_email(&self, email: &OutgoingEmail) -> Result<()> {
+        use num_traits::ToPrimitive;
+        let model = models::NewOutgoingEmail {
+            id: email.id.as_str(),
+            recipient: email.recipient.as_str(),
+            subject: &email.subject,
+            body: &email.body,
+            created_at: email.created_at.into_inner(),
+            status: email.status.to_i16().unwrap(),
+            attempts: email.attempts as i32,
+            last_attempt_at: email.last_attempt_at.map(TimestampMs::into_inner),
+            next_attempt_at: email.next_attempt_at.map(TimestampMs::into_inner),
+            last_error: email.last_error.as_deref(),
+        };
+        diesel::insert_into(schema::email_outbox::table)
+            .values(&model)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn update_outgoing_email(&self, email: &OutgoingEmail) -> Result<()> {
+        use num_traits::ToPrimitive;
+        use schema::email_outbox::dsl;
+        let count = diesel::update(dsl::email_outbox.filter(dsl::id.eq(email.id.as_str())))
+            .set((
+                dsl::status.eq(email.status.to_i16().unwrap()),
+                dsl::attempts.eq(email.attempts as i32),
+                dsl::last_attempt_at.eq(email.last_attempt_at.map(TimestampMs::into_inner)),
+                dsl::next_attempt_at.eq(email.next_attempt_at.map(TimestampMs::into_inner)),
+                dsl::last_error.eq(email.last_error.as_deref()),
+            ))
+            .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn delete_outgoing_email(&self, id: &str) -> Result<()> {
+        use schema::email_outbox::dsl;
+        let count = diesel::delete(dsl::email_outbox.filter(dsl::id.eq(id))).execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn load_due_outgoing_emails(
+        &self,
+        due_at: TimestampMs,
+        limit: u64,
+    ) -> Result<Vec<OutgoingEmail>> {
+        use num_traits::ToPrimitive;
+        use schema::email_outbox::dsl;
+        dsl::email_outbox
+            .filter(dsl::status.eq(OutgoingEmailStatus::Pending.to_i16().unwrap()))
+            .filter(dsl::next_attempt_at.le(due_at.into_inner()))
+            .order_by(dsl::next_attempt_at)
+            .then_order_by(dsl::rowid)
+            .limit(limit as i64)
+            .load::<models::OutgoingEmail>(self)?
+            .into_iter()
+            .map(load_outgoing_email)
+            .collect()
+    }
+
+    fn load_outgoing_emails(
+        &self,
+        status: Option<OutgoingEmailStatus>,
+        pagination: &Pagination,
+    ) -> Result<Vec<OutgoingEmail>> {
+        use num_traits::ToPrimitive;
+        use schema::email_outbox::dsl;
+        let mut query = dsl::email_outbox
+            .order_by(dsl::created_at.desc())
+            .then_order_by(dsl::rowid.desc()) // disambiguation of equal time stamps
+            .into_boxed();
+        if let Some(status) = status {
+            query = query.filter(dsl::status.eq(status.to_i16().unwrap()));
+        }
+
+        // Pagination
+        let offset = pagination.offset.unwrap_or(0);
+        if offset > 0 {
+            query = query.offset(offset as i64);
+        }
+        if let Some(limit) = pagination.limit {
+            query = query.limit(limit as i64);
+        }
+
+        query
+            .load::<models::OutgoingEmail>(self)?
+            .into_iter()
+            .map(load_outgoing_email)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_many_ids_in_chunks() {
+        let ids: Vec<_> = (0..MAX_IDS_PER_QUERY * 2 + 1)
+            .map(|i| i.to_string())
+            .collect();
+        let ids: Vec<_> = ids.iter().map(String::as_str).collect();
+        let mut chunk_sizes = vec![];
+        let results = load_in_chunks(&ids, |chunk| {
+            chunk_sizes.push(chunk.len());
+            Ok(chunk
+                .iter()
+                .map(|id| id.parse::<usize>().unwrap())
+                .collect())
+        })
+        .unwrap();
+        assert_eq!(vec![MAX_IDS_PER_QUERY, MAX_IDS_PER_QUERY, 1], chunk_sizes);
+        assert_eq!((0..ids.len()).collect::<Vec<_>>(), results);
+    }
+
+    #[test]
+    fn abort_loading_chunks_on_error() {
+        let ids = vec!["a"; MAX_IDS_PER_QUERY + 1];
+        let mut chunk_count = 0;
+        let results: Result<Vec<()>> = load_in_chunks(&ids, |_| {
+            chunk_count += 1;
+            Err(RepoError::NotFound)
+        });
+        assert!(results.is_err());
+        assert_eq!(1, chunk_count);
+    }
+
+    #[test]
+    fn migrate_time_stamps_from_seconds_to_milliseconds() {
+        use diesel::{connection::SimpleConnection, migration::Migration};
+        let conn = SqliteConnection::establish(":memory:").unwrap();
+        let migrations_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations");
+        let (before, after): (Vec<_>, Vec<_>) =
+            diesel_migrations::mark_migrations_in_directory(&conn, &migrations_dir)
+                .unwrap()
+                .into_iter()
+                .map(|(migration, _)| migration)
+                .partition(|migration| migration.version() < "20200610");
+        diesel_migrations::run_migrations(&conn, before, &mut std::io::sink()).unwrap();
+        // 2020-06-10 00:00:00 UTC in seconds
+        conn.batch_execute(
+            r#"
+            INSERT INTO place (rowid, current_rev, id, license)
+            VALUES (1, 0, 'place', 'CC0-1.0');
+            INSERT INTO place_rating (rowid, parent_rowid, created_at, archived_at, id, title, value, context)
+            VALUES (1, 1, 1591747200, 1591747201, 'seconds', 'title', 1, 'fairness'),
+                   (2, 1, 1591747200000, 1591747200001, 'milliseconds', 'title', 1, 'fairness');
+            INSERT INTO place_rating_comment (parent_rowid, created_at, archived_at, id, text)
+            VALUES (1, 1591747200, 1591747201, 'comment', 'text');
+            INSERT INTO events (uid, title, start, archived)
+            VALUES ('event', 'title', 1591747200, 1591747201);
+            "#,
+        )
+        .unwrap();
+        diesel_migrations::run_migrations(&conn, after, &mut std::io::sink()).unwrap();
+
+        let rating = |id: &str| {
+            use schema::place_rating::dsl;
+            dsl::place_rating
+                .select((dsl::created_at, dsl::archived_at))
+                .filter(dsl::id.eq(id))
+                .first::<(i64, Option<i64>)>(&conn)
+                .unwrap()
+        };
+        assert_eq!(
+            (1_591_747_200_000, Some(1_591_747_201_000)),
+            rating("seconds")
+        );
+        // Time stamps that have already been stored
+        // in milliseconds are not converted again
+        assert_eq!(
+            (1_591_747_200_000, Some(1_591_747_200_001)),
+            rating("milliseconds")
+        );
+        let comment = {
+            use schema::place_rating_comment::dsl;
+            dsl::place_rating_comment
+                .select((dsl::created_at, dsl::archived_at))
+                .filter(dsl::id.eq("comment"))
+                .first::<(i64, Option<i64>)>(&conn)
+                .unwrap()
+        };
+        assert_eq!((1_591_747_200_000, Some(1_591_747_201_000)), comment);
+        let event = {
+            use schema::events::dsl;
+            dsl::events
+                .select((dsl::start, dsl::archived))
+                .filter(dsl::uid.eq("event"))
+                .first::<(i64, Option<i64>)>(&conn)
+                .unwrap()
+        };
+        // The start of events is still stored in seconds
+        assert_eq!((1_591_747_200, Some(1_591_747_201_000)), event);
+    }
+
+    #[test]
+    fn migrate_default_api_tokens_of_organizations() {
+        use diesel::{connection::SimpleConnection, migration::Migration};
+        let conn = SqliteConnection::establish(":memory:").unwrap();
+        let migrations_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations");
+        let (before, after): (Vec<_>, Vec<_>) =
+            diesel_migrations::mark_migrations_in_directory(&conn, &migrations_dir)
+                .unwrap()
+                .into_iter()
+                .map(|(migration, _)| migration)
+                .partition(|migration| migration.version() < "20200624");
+        diesel_migrations::run_migrations(&conn, before, &mut std::io::sink()).unwrap();
+        conn.batch_execute(
+            r#"
+            INSERT INTO organizations (id, name, api_token)
+            VALUES ('org', 'name', 'legacy');
+            "#,
+        )
+        .unwrap();
+        diesel_migrations::run_migrations(&conn, after, &mut std::io::sink()).unwrap();
+
+        let legacy_token = conn.get_org_api_token("legacy").unwrap();
+        assert!(legacy_token.expires_at.is_none());
+        for scope in ApiTokenScope::ALL {
+            assert!(legacy_token.has_scope(*scope));
+        }
+
+        conn.create_user(&User {
+            email: "admin@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Admin,
+            language: None,
+        })
+        .unwrap();
+        let (_, new_token) = crate::core::usecases::regenerate_organization_api_token(
+            &conn,
+            "admin@example.com",
+            "org",
+        )
+        .unwrap();
+        assert!(conn.get_org_api_token(&new_token.token).is_ok());
+        assert!(matches!(
+            conn.get_org_api_token("legacy"),
+            Err(RepoError::NotFound)
+        ));
+    }
+}
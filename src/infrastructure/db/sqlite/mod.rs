@@ -3,11 +3,13 @@ mod models;
 mod schema;
 mod util;
 
+use crate::infrastructure::{live_changes::LiveChanges, place_changes::PlaceChanges};
 use anyhow::Result as Fallible;
-use diesel::{r2d2, sqlite::SqliteConnection};
+use diesel::{r2d2, sql_types::Text, sqlite::SqliteConnection, RunQueryDsl};
 use owning_ref::{RwLockReadGuardRef, RwLockWriteGuardRefMut};
 use std::{
     ops::{Deref, DerefMut},
+    path::Path,
     sync::{Arc, RwLock},
 };
 
@@ -19,6 +21,17 @@ pub type PooledConnection = r2d2::PooledConnection<ConnectionManager>;
 
 pub type SharedConnectionPool = Arc<RwLock<ConnectionPool>>;
 
+/// Write a consistent snapshot of the database into a new file
+/// while other connections are still reading.
+///
+/// Requires SQLite 3.27.0 or newer.
+pub fn vacuum_into(conn: &Connection, path: &Path) -> Fallible<()> {
+    diesel::sql_query("VACUUM INTO ?")
+        .bind::<Text, _>(path.to_string_lossy().into_owned())
+        .execute(conn)?;
+    Ok(())
+}
+
 pub struct DbReadOnly<'a> {
     _locked_pool: RwLockReadGuardRef<'a, ConnectionPool>,
     conn: PooledConnection,
@@ -94,6 +107,10 @@ pub struct Connections {
     // ("database is locked") errors that are causing internal
     // server errors and failed requests.
     pool: SharedConnectionPool,
+    // Committed changes are published by the flows for
+    // clients that follow them continuously.
+    live_changes: LiveChanges,
+    place_changes: PlaceChanges,
 }
 
 impl Connections {
@@ -108,9 +125,14 @@ impl Connections {
     pub fn new(pool: ConnectionPool) -> Self {
         Self {
             pool: Arc::new(RwLock::new(pool)),
+            live_changes: Default::default(),
+            place_changes: Default::default(),
         }
     }
 
+    /// Read-only requests are served concurrently from the
+    /// same database file. Routing them to read replicas requires
+    /// a database server and is not supported for SQLite.
     pub fn shared<'a>(&'a self) -> Fallible<DbReadOnly<'a>> {
         DbReadOnly::try_new(&self.pool)
     }
@@ -118,4 +140,15 @@ impl Connections {
     pub fn exclusive<'a>(&'a self) -> Fallible<DbReadWrite<'a>> {
         DbReadWrite::try_new(&self.pool)
     }
+
+    /// Shared by all clones, i.e. all changes of the database
+    /// are published in the same sequence.
+    pub fn live_changes(&self) -> &LiveChanges {
+        &self.live_changes
+    }
+
+    /// Shared by all clones like the live changes.
+    pub fn place_changes(&self) -> &PlaceChanges {
+        &self.place_changes
+    }
 }
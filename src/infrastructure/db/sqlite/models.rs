@@ -39,6 +39,9 @@ pub struct NewPlaceRevision {
     pub opening_hours: Option<String>,
     pub image_url: Option<String>,
     pub image_link_url: Option<String>,
+    pub source_importer: Option<String>,
+    pub source_external_ref: Option<String>,
+    pub source_imported_at: Option<i64>,
 }
 
 #[derive(Queryable)]
@@ -63,6 +66,9 @@ pub struct JoinedPlaceRevision {
     pub opening_hours: Option<String>,
     pub image_url: Option<String>,
     pub image_link_url: Option<String>,
+    pub source_importer: Option<String>,
+    pub source_external_ref: Option<String>,
+    pub source_imported_at: Option<i64>,
     // Joined columns
     pub place_id: String,
     pub place_license: String,
@@ -89,6 +95,9 @@ pub struct JoinedPlaceRevisionWithStatusReview {
     pub opening_hours: Option<String>,
     pub image_url: Option<String>,
     pub image_link_url: Option<String>,
+    pub source_importer: Option<String>,
+    pub source_external_ref: Option<String>,
+    pub source_imported_at: Option<i64>,
     // Joined columns
     pub place_id: String,
     pub place_license: String,
@@ -98,6 +107,7 @@ pub struct JoinedPlaceRevisionWithStatusReview {
     pub review_status: i16,
     pub review_context: Option<String>,
     pub review_comment: Option<String>,
+    pub review_rowid: i64,
 }
 
 #[derive(Insertable)]
@@ -136,6 +146,32 @@ pub struct NewPlaceRevisionTag<'a> {
     pub tag: &'a str,
 }
 
+#[derive(Queryable)]
+pub struct PlaceRevisionLocation {
+    pub rowid: i64,
+    pub parent_rowid: i64,
+    pub lat: f64,
+    pub lon: f64,
+    pub street: Option<String>,
+    pub zip: Option<String>,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    pub state: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "place_revision_location"]
+pub struct NewPlaceRevisionLocation {
+    pub parent_rowid: i64,
+    pub lat: f64,
+    pub lon: f64,
+    pub street: Option<String>,
+    pub zip: Option<String>,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    pub state: Option<String>,
+}
+
 #[derive(Insertable)]
 #[table_name = "place_rating"]
 pub struct NewPlaceRating {
@@ -155,7 +191,7 @@ pub struct NewPlaceRating {
 pub struct PlaceRating {
     pub rowid: i64,
     pub created_at: i64,
-    pub created_by: Option<i64>,
+    pub created_by: Option<String>,
     pub archived_at: Option<i64>,
     pub archived_by: Option<i64>,
     pub id: String,
@@ -167,6 +203,131 @@ pub struct PlaceRating {
     pub place_id: String,
 }
 
+#[derive(Insertable)]
+#[table_name = "reports"]
+pub struct NewReport {
+    pub id: String,
+    pub content_kind: i16,
+    pub content_id: String,
+    pub created_at: i64,
+    pub created_by: Option<i64>,
+    pub reason: i16,
+    pub comment: Option<String>,
+}
+
+#[derive(Queryable)]
+pub struct Report {
+    pub id: String,
+    pub content_kind: i16,
+    pub content_id: String,
+    pub created_at: i64,
+    pub created_by_email: Option<String>,
+    pub reason: i16,
+    pub comment: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "place_contact_verification"]
+pub struct NewPlaceContactVerification {
+    pub parent_rowid: i64,
+    pub email: String,
+    pub nonce: String,
+    pub expires_at: i64,
+    pub verified_at: Option<i64>,
+}
+
+#[derive(Queryable)]
+pub struct PlaceContactVerification {
+    pub email: String,
+    pub nonce: String,
+    pub expires_at: i64,
+    pub verified_at: Option<i64>,
+    // Joined columns
+    pub place_id: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "place_external_id"]
+pub struct NewPlaceExternalId<'a> {
+    pub parent_rowid: i64,
+    pub source: &'a str,
+    pub external_id: &'a str,
+    pub url: Option<String>,
+}
+
+#[derive(Queryable)]
+pub struct PlaceExternalId {
+    pub source: String,
+    pub external_id: String,
+    pub url: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "place_merge"]
+pub struct NewPlaceMerge {
+    pub parent_rowid: i64,
+    pub primary_rowid: i64,
+    pub merged_at: i64,
+}
+
+#[derive(Insertable)]
+#[table_name = "place_clearance"]
+pub struct NewPlaceClearance<'a> {
+    pub org_id: &'a str,
+    pub place_rowid: i64,
+    pub created_at: i64,
+    pub last_cleared_revision: Option<i64>,
+}
+
+#[derive(Queryable)]
+pub struct PendingPlaceClearance {
+    pub place_id: String,
+    pub created_at: i64,
+    pub last_cleared_revision: Option<i64>,
+}
+
+#[derive(Insertable)]
+#[table_name = "place_draft"]
+pub struct NewPlaceDraft<'a> {
+    pub id: &'a str,
+    pub owner: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub data: &'a str,
+}
+
+#[derive(Queryable)]
+pub struct PlaceDraft {
+    pub id: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub data: String,
+    // Joined columns
+    pub owner_email: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "moderation_assignment"]
+pub struct NewModerationAssignment {
+    pub item_kind: i16,
+    pub item_id: String,
+    pub assignee: i64,
+    pub state: i16,
+    pub assigned_at: i64,
+    pub assigned_by: Option<i64>,
+    pub changed_at: i64,
+}
+
+#[derive(Queryable)]
+pub struct ModerationAssignment {
+    pub item_kind: i16,
+    pub item_id: String,
+    pub state: i16,
+    pub assigned_at: i64,
+    pub assigned_by_email: Option<String>,
+    pub changed_at: i64,
+}
+
 #[derive(Insertable)]
 #[table_name = "place_rating_comment"]
 pub struct NewPlaceRatingComment {
@@ -179,6 +340,14 @@ pub struct NewPlaceRatingComment {
     pub text: String,
 }
 
+#[derive(Insertable)]
+#[table_name = "place_rating_comment_flag"]
+pub struct NewPlaceRatingCommentFlag {
+    pub parent_rowid: i64,
+    pub created_at: i64,
+    pub created_by: i64,
+}
+
 #[derive(Queryable)]
 pub struct PlaceRatingComment {
     pub rowid: i64,
@@ -216,6 +385,7 @@ pub struct NewEvent {
     pub archived: Option<i64>,
     pub image_url: Option<String>,
     pub image_link_url: Option<String>,
+    pub capacity: Option<i64>,
 }
 
 #[derive(Queryable)]
@@ -242,6 +412,7 @@ pub struct EventEntity {
     pub archived: Option<i64>,
     pub image_url: Option<String>,
     pub image_link_url: Option<String>,
+    pub capacity: Option<i64>,
     // Joined columns
     pub created_by_email: Option<String>,
 }
@@ -251,7 +422,33 @@ pub struct EventEntity {
 pub struct Organization {
     pub id: String,
     pub name: String,
-    pub api_token: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "organization_api_token"]
+pub struct NewOrganizationApiToken<'a> {
+    pub id: &'a str,
+    pub org_id: &'a str,
+    pub token: &'a str,
+    pub created_at: i64,
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Queryable)]
+pub struct OrganizationApiToken {
+    pub rowid: i64,
+    pub id: String,
+    pub org_id: String,
+    pub token: String,
+    pub created_at: i64,
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Insertable)]
+#[table_name = "organization_api_token_scope"]
+pub struct NewOrganizationApiTokenScope {
+    pub token_rowid: i64,
+    pub scope: i16,
 }
 
 #[derive(Queryable)]
@@ -267,12 +464,48 @@ pub struct NewEventTag<'a> {
     pub tag: &'a str,
 }
 
+#[derive(Queryable, Insertable)]
+#[table_name = "event_recurrences"]
+pub struct EventRecurrence {
+    pub event_id: i64,
+    pub frequency: i16,
+    pub interval: i32,
+    pub until: Option<i64>,
+}
+
+#[derive(Insertable)]
+#[table_name = "event_registrations"]
+pub struct NewEventRegistration {
+    pub event_id: i64,
+    pub email: String,
+    pub nonce: String,
+    pub status: i16,
+    pub registered_at: i64,
+}
+
+#[derive(Queryable)]
+pub struct EventRegistration {
+    pub email: String,
+    pub nonce: String,
+    pub status: i16,
+    pub registered_at: i64,
+    // Joined columns
+    pub event_id: String,
+}
+
 #[derive(Queryable)]
 pub struct OrgTagRelation {
     pub org_id: String,
     pub tag_id: String,
 }
 
+#[derive(Queryable, Insertable)]
+#[table_name = "curated_organization"]
+pub struct CuratedOrganization {
+    pub org_id: String,
+    pub boost: f64,
+}
+
 #[derive(Insertable)]
 #[table_name = "org_tag_relations"]
 pub struct StoreableOrgTagRelation<'a, 'b> {
@@ -286,13 +519,29 @@ pub struct Tag {
     pub id: String,
 }
 
+#[derive(Queryable)]
+pub struct Category {
+    pub rowid: i64,
+    pub id: String,
+    pub tag: String,
+}
+
+#[derive(Queryable, Insertable)]
+#[table_name = "curated_tag"]
+pub struct CuratedTag {
+    pub tag: String,
+    pub boost: f64,
+}
+
 #[derive(Insertable, AsChangeset)]
 #[table_name = "users"]
+#[changeset_options(treat_none_as_null = "true")]
 pub struct NewUser<'a> {
     pub email: &'a str,
     pub email_confirmed: bool,
     pub password: String,
     pub role: i16,
+    pub language: Option<&'a str>,
 }
 
 #[derive(Queryable)]
@@ -302,6 +551,96 @@ pub struct UserEntity {
     pub email_confirmed: bool,
     pub password: String,
     pub role: i16,
+    pub language: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "user_status_log"]
+pub struct NewUserStatusLog<'a, 'b> {
+    pub user_id: i64,
+    pub created_at: i64,
+    pub created_by: Option<i64>,
+    pub active: bool,
+    pub context: Option<&'a str>,
+    pub comment: Option<&'b str>,
+}
+
+#[derive(Queryable)]
+pub struct UserStatusLog {
+    pub created_at: i64,
+    pub created_by_email: Option<String>,
+    pub active: bool,
+    pub context: Option<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "audit_log"]
+pub struct NewAuditLogEntry<'a, 'b, 'c> {
+    pub created_at: i64,
+    pub created_by: Option<i64>,
+    pub action: i16,
+    pub item: &'a str,
+    pub context: Option<&'b str>,
+    pub comment: Option<&'c str>,
+}
+
+#[derive(Queryable)]
+pub struct AuditLogEntry {
+    pub created_at: i64,
+    pub created_by_email: Option<String>,
+    pub action: i16,
+    pub item: String,
+    pub context: Option<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "announcement"]
+pub struct NewAnnouncement<'a, 'b> {
+    pub id: &'a str,
+    pub message: &'b str,
+    pub severity: i16,
+    pub starts_at: i64,
+    pub ends_at: Option<i64>,
+}
+
+#[derive(Queryable)]
+pub struct Announcement {
+    pub rowid: i64,
+    pub id: String,
+    pub message: String,
+    pub severity: i16,
+    pub starts_at: i64,
+    pub ends_at: Option<i64>,
+}
+
+#[derive(Insertable)]
+#[table_name = "user_api_tokens"]
+pub struct NewUserApiToken<'a> {
+    pub id: &'a str,
+    pub user_id: i64,
+    pub token: &'a str,
+    pub label: Option<&'a str>,
+    pub created_at: i64,
+}
+
+#[derive(Queryable)]
+pub struct UserApiToken {
+    pub id: String,
+    pub token: String,
+    pub label: Option<String>,
+    pub created_at: i64,
+    // Joined columns
+    pub user_email: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "user_permission"]
+pub struct NewUserPermission {
+    pub user_id: i64,
+    pub permission: i16,
+    pub granted: bool,
 }
 
 #[derive(Insertable)]
@@ -328,6 +667,92 @@ pub struct BboxSubscriptionEntity {
     pub user_email: String,
 }
 
+#[derive(Insertable)]
+#[table_name = "tag_subscriptions"]
+pub struct NewTagSubscription<'a> {
+    pub id: &'a str,
+    pub user_id: i64,
+    pub south_west_lat: Option<f64>,
+    pub south_west_lng: Option<f64>,
+    pub north_east_lat: Option<f64>,
+    pub north_east_lng: Option<f64>,
+}
+
+#[derive(Queryable)]
+pub struct TagSubscription {
+    pub rowid: i64,
+    pub id: String,
+    pub south_west_lat: Option<f64>,
+    pub south_west_lng: Option<f64>,
+    pub north_east_lat: Option<f64>,
+    pub north_east_lng: Option<f64>,
+    // Joined columns
+    pub user_email: String,
+}
+
+#[derive(Insertable, Queryable)]
+#[table_name = "tag_subscription_tags"]
+pub struct TagSubscriptionTag {
+    pub parent_rowid: i64,
+    pub tag: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "saved_searches"]
+pub struct NewSavedSearch<'a> {
+    pub id: &'a str,
+    pub user_id: i64,
+    pub created_at: i64,
+    pub title: &'a str,
+    pub query: &'a str,
+}
+
+#[derive(Queryable)]
+pub struct SavedSearch {
+    pub id: String,
+    pub created_at: i64,
+    pub title: String,
+    pub query: String,
+    // Joined columns
+    pub user_email: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "notification_digests"]
+pub struct NewNotificationDigest {
+    pub user_id: i64,
+    pub interval: i16,
+    pub last_sent_at: i64,
+}
+
+#[derive(Queryable)]
+pub struct NotificationDigest {
+    pub interval: i16,
+    pub last_sent_at: i64,
+    // Joined columns
+    pub user_email: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "notification_queue"]
+pub struct NewQueuedNotification<'a> {
+    pub user_id: i64,
+    pub created_at: i64,
+    pub kind: i16,
+    pub place_id: &'a str,
+    pub place_title: &'a str,
+}
+
+#[derive(Queryable)]
+pub struct QueuedNotification {
+    pub created_at: i64,
+    pub kind: i16,
+    pub place_id: String,
+    pub place_title: String,
+    // Joined columns
+    pub user_email: String,
+}
+
 #[derive(Insertable, AsChangeset)]
 #[table_name = "user_tokens"]
 pub struct NewUserToken {
@@ -344,3 +769,105 @@ pub struct UserTokenEntity {
     // Joined columns
     pub user_email: String,
 }
+
+#[derive(Insertable)]
+#[table_name = "user_login_links"]
+pub struct NewUserLoginLink<'a> {
+    pub email: &'a str,
+    pub nonce: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
+
+#[derive(Queryable)]
+pub struct UserLoginLink {
+    pub email: String,
+    pub nonce: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
+
+#[derive(Insertable)]
+#[table_name = "webhook"]
+pub struct NewWebhook<'a> {
+    pub id: &'a str,
+    pub url: &'a str,
+    pub secret: &'a str,
+    pub created_at: i64,
+}
+
+#[derive(Queryable)]
+pub struct Webhook {
+    pub rowid: i64,
+    pub id: String,
+    pub url: String,
+    pub secret: String,
+    pub created_at: i64,
+}
+
+#[derive(Insertable)]
+#[table_name = "webhook_event"]
+pub struct NewWebhookEvent {
+    pub webhook_rowid: i64,
+    pub event: i16,
+}
+
+#[derive(Insertable)]
+#[table_name = "webhook_delivery"]
+pub struct NewWebhookDelivery<'a> {
+    pub id: &'a str,
+    pub webhook_rowid: i64,
+    pub event: i16,
+    pub payload: &'a str,
+    pub created_at: i64,
+    pub status: i16,
+    pub attempts: i32,
+    pub last_attempt_at: Option<i64>,
+    pub next_attempt_at: Option<i64>,
+    pub response_status: Option<i32>,
+}
+
+#[derive(Queryable)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub event: i16,
+    pub payload: String,
+    pub created_at: i64,
+    pub status: i16,
+    pub attempts: i32,
+    pub last_attempt_at: Option<i64>,
+    pub next_attempt_at: Option<i64>,
+    pub response_status: Option<i32>,
+    // Joined columns
+    pub webhook_id: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "email_outbox"]
+pub struct NewOutgoingEmail<'a> {
+    pub id: &'a str,
+    pub recipient: &'a str,
+    pub subject: &'a str,
+    pub body: &'a str,
+    pub created_at: i64,
+    pub status: i16,
+    pub attempts: i32,
+    pub last_attempt_at: Option<i64>,
+    pub next_attempt_at: Option<i64>,
+    pub last_error: Option<&'a str>,
+}
+
+#[derive(Queryable)]
+pub struct OutgoingEmail {
+    pub rowid: i64,
+    pub id: String,
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+    pub created_at: i64,
+    pub status: i16,
+    pub attempts: i32,
+    pub last_attempt_at: Option<i64>,
+    pub next_attempt_at: Option<i64>,
+    pub last_error: Option<String>,
+}
@@ -8,6 +8,21 @@ table! {
     }
 }
 
+table! {
+    category (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        tag -> Text,
+    }
+}
+
+table! {
+    curated_tag (tag) {
+        tag -> Text,
+        boost -> Double,
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////
 // Organizations
 ///////////////////////////////////////////////////////////////////////
@@ -16,7 +31,6 @@ table! {
     organizations (id) {
         id -> Text,
         name -> Text,
-        api_token -> Text,
     }
 }
 
@@ -30,6 +44,38 @@ table! {
 joinable!(org_tag_relations -> organizations (org_id));
 joinable!(org_tag_relations -> tags (tag_id));
 
+table! {
+    curated_organization (org_id) {
+        org_id -> Text,
+        boost -> Double,
+    }
+}
+
+joinable!(curated_organization -> organizations (org_id));
+
+table! {
+    organization_api_token (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        org_id -> Text,
+        token -> Text,
+        created_at -> BigInt,
+        expires_at -> Nullable<BigInt>,
+    }
+}
+
+joinable!(organization_api_token -> organizations (org_id));
+
+table! {
+    organization_api_token_scope (rowid) {
+        rowid -> BigInt,
+        token_rowid -> BigInt,
+        scope -> SmallInt,
+    }
+}
+
+joinable!(organization_api_token_scope -> organization_api_token (token_rowid));
+
 ///////////////////////////////////////////////////////////////////////
 // Users
 ///////////////////////////////////////////////////////////////////////
@@ -41,6 +87,7 @@ table! {
         email_confirmed -> Bool,
         password -> Text,
         role -> SmallInt,
+        language -> Nullable<Text>,
     }
 }
 
@@ -55,6 +102,60 @@ table! {
 
 joinable!(user_tokens -> users (user_id));
 
+table! {
+    user_login_links (rowid) {
+        rowid -> BigInt,
+        email -> Text,
+        nonce -> Text,
+        created_at -> BigInt,
+        expires_at -> BigInt,
+    }
+}
+
+table! {
+    user_api_tokens (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        user_id -> BigInt,
+        token -> Text,
+        label -> Nullable<Text>,
+        created_at -> BigInt,
+    }
+}
+
+joinable!(user_api_tokens -> users (user_id));
+
+table! {
+    user_status_log (rowid) {
+        rowid -> BigInt,
+        user_id -> BigInt,
+        created_at -> BigInt,
+        created_by -> Nullable<BigInt>,
+        active -> Bool,
+        context -> Nullable<Text>,
+        comment -> Nullable<Text>,
+    }
+}
+
+table! {
+    user_permission (rowid) {
+        rowid -> BigInt,
+        user_id -> BigInt,
+        permission -> SmallInt,
+        granted -> Bool,
+    }
+}
+
+joinable!(user_permission -> users (user_id));
+
+table! {
+    scout_report_opt_out (user_id) {
+        user_id -> BigInt,
+    }
+}
+
+joinable!(scout_report_opt_out -> users (user_id));
+
 ///////////////////////////////////////////////////////////////////////
 // Places
 ///////////////////////////////////////////////////////////////////////
@@ -91,6 +192,9 @@ table! {
         opening_hours -> Nullable<Text>,
         image_url -> Nullable<Text>,
         image_link_url -> Nullable<Text>,
+        source_importer -> Nullable<Text>,
+        source_external_ref -> Nullable<Text>,
+        source_imported_at -> Nullable<BigInt>,
     }
 }
 
@@ -105,6 +209,22 @@ table! {
 
 joinable!(place_revision_tag -> place_revision (parent_rowid));
 
+table! {
+    place_revision_location (rowid) {
+        rowid -> BigInt,
+        parent_rowid -> BigInt,
+        lat -> Double,
+        lon -> Double,
+        street -> Nullable<Text>,
+        zip -> Nullable<Text>,
+        city -> Nullable<Text>,
+        country -> Nullable<Text>,
+        state -> Nullable<Text>,
+    }
+}
+
+joinable!(place_revision_location -> place_revision (parent_rowid));
+
 table! {
     place_revision_review (rowid) {
         rowid -> BigInt,
@@ -133,6 +253,7 @@ table! {
         value -> SmallInt,
         context -> Text,
         source -> Nullable<Text>,
+        archived_comment -> Nullable<Text>,
     }
 }
 
@@ -148,11 +269,113 @@ table! {
         archived_by -> Nullable<BigInt>,
         id -> Text,
         text -> Text,
+        archived_comment -> Nullable<Text>,
     }
 }
 
 joinable!(place_rating_comment -> place_rating (parent_rowid));
 
+table! {
+    place_rating_comment_flag (rowid) {
+        rowid -> BigInt,
+        parent_rowid -> BigInt,
+        created_at -> BigInt,
+        created_by -> BigInt,
+    }
+}
+
+joinable!(place_rating_comment_flag -> place_rating_comment (parent_rowid));
+
+table! {
+    reports (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        content_kind -> SmallInt,
+        content_id -> Text,
+        created_at -> BigInt,
+        created_by -> Nullable<BigInt>,
+        resolved_at -> Nullable<BigInt>,
+        resolved_by -> Nullable<BigInt>,
+        reason -> SmallInt,
+        comment -> Nullable<Text>,
+    }
+}
+
+table! {
+    place_contact_verification (rowid) {
+        rowid -> BigInt,
+        parent_rowid -> BigInt,
+        email -> Text,
+        nonce -> Text,
+        expires_at -> BigInt,
+        verified_at -> Nullable<BigInt>,
+    }
+}
+
+joinable!(place_contact_verification -> place (parent_rowid));
+
+table! {
+    place_external_id (rowid) {
+        rowid -> BigInt,
+        parent_rowid -> BigInt,
+        source -> Text,
+        external_id -> Text,
+        url -> Nullable<Text>,
+    }
+}
+
+joinable!(place_external_id -> place (parent_rowid));
+
+table! {
+    place_merge (rowid) {
+        rowid -> BigInt,
+        parent_rowid -> BigInt,
+        primary_rowid -> BigInt,
+        merged_at -> BigInt,
+    }
+}
+
+joinable!(place_merge -> place (parent_rowid));
+
+table! {
+    place_clearance (rowid) {
+        rowid -> BigInt,
+        org_id -> Text,
+        place_rowid -> BigInt,
+        created_at -> BigInt,
+        last_cleared_revision -> Nullable<BigInt>,
+    }
+}
+
+joinable!(place_clearance -> organizations (org_id));
+joinable!(place_clearance -> place (place_rowid));
+
+table! {
+    moderation_assignment (rowid) {
+        rowid -> BigInt,
+        item_kind -> SmallInt,
+        item_id -> Text,
+        assignee -> BigInt,
+        state -> SmallInt,
+        assigned_at -> BigInt,
+        assigned_by -> Nullable<BigInt>,
+        changed_at -> BigInt,
+    }
+}
+
+table! {
+    place_draft (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        owner -> BigInt,
+        created_at -> BigInt,
+        updated_at -> BigInt,
+        data -> Text,
+    }
+}
+
+joinable!(place_draft -> users (owner));
+
 ///////////////////////////////////////////////////////////////////////
 // Events
 ///////////////////////////////////////////////////////////////////////
@@ -183,6 +406,11 @@ table! {
         archived -> Nullable<BigInt>,
         image_url -> Nullable<Text>,
         image_link_url -> Nullable<Text>,
+        archived_by -> Nullable<BigInt>,
+        archived_comment -> Nullable<Text>,
+        external_org_id -> Nullable<Text>,
+        external_id -> Nullable<Text>,
+        capacity -> Nullable<BigInt>,
     }
 }
 
@@ -197,6 +425,30 @@ table! {
 
 joinable!(event_tags -> events (event_id));
 
+table! {
+    event_recurrences (event_id) {
+        event_id -> BigInt,
+        frequency -> SmallInt,
+        interval -> Integer,
+        until -> Nullable<BigInt>,
+    }
+}
+
+joinable!(event_recurrences -> events (event_id));
+
+table! {
+    event_registrations (rowid) {
+        rowid -> BigInt,
+        event_id -> BigInt,
+        email -> Text,
+        nonce -> Text,
+        status -> SmallInt,
+        registered_at -> BigInt,
+    }
+}
+
+joinable!(event_registrations -> events (event_id));
+
 ///////////////////////////////////////////////////////////////////////
 // Subscriptions
 ///////////////////////////////////////////////////////////////////////
@@ -218,21 +470,197 @@ table! {
 
 joinable!(bbox_subscriptions -> users (user_id));
 
+table! {
+    tag_subscriptions (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        user_id -> BigInt,
+        south_west_lat -> Nullable<Double>,
+        south_west_lng -> Nullable<Double>,
+        north_east_lat -> Nullable<Double>,
+        north_east_lng -> Nullable<Double>,
+    }
+}
+
+joinable!(tag_subscriptions -> users (user_id));
+
+table! {
+    tag_subscription_tags (parent_rowid, tag) {
+        parent_rowid -> BigInt,
+        tag -> Text,
+    }
+}
+
+joinable!(tag_subscription_tags -> tag_subscriptions (parent_rowid));
+
+table! {
+    saved_searches (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        user_id -> BigInt,
+        created_at -> BigInt,
+        title -> Text,
+        query -> Text,
+    }
+}
+
+joinable!(saved_searches -> users (user_id));
+
+table! {
+    notification_digests (user_id) {
+        user_id -> BigInt,
+        interval -> SmallInt,
+        last_sent_at -> BigInt,
+    }
+}
+
+joinable!(notification_digests -> users (user_id));
+
+table! {
+    notification_queue (rowid) {
+        rowid -> BigInt,
+        user_id -> BigInt,
+        created_at -> BigInt,
+        kind -> SmallInt,
+        place_id -> Text,
+        place_title -> Text,
+    }
+}
+
+joinable!(notification_queue -> users (user_id));
+
+///////////////////////////////////////////////////////////////////////
+// Audit log
+///////////////////////////////////////////////////////////////////////
+
+table! {
+    audit_log (rowid) {
+        rowid -> BigInt,
+        created_at -> BigInt,
+        created_by -> Nullable<BigInt>,
+        action -> SmallInt,
+        item -> Text,
+        context -> Nullable<Text>,
+        comment -> Nullable<Text>,
+    }
+}
+
+///////////////////////////////////////////////////////////////////////
+
+table! {
+    announcement (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        message -> Text,
+        severity -> SmallInt,
+        starts_at -> BigInt,
+        ends_at -> Nullable<BigInt>,
+    }
+}
+
+///////////////////////////////////////////////////////////////////////
+
+table! {
+    webhook (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        url -> Text,
+        secret -> Text,
+        created_at -> BigInt,
+    }
+}
+
+table! {
+    webhook_event (rowid) {
+        rowid -> BigInt,
+        webhook_rowid -> BigInt,
+        event -> SmallInt,
+    }
+}
+
+joinable!(webhook_event -> webhook (webhook_rowid));
+
+table! {
+    webhook_delivery (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        webhook_rowid -> BigInt,
+        event -> SmallInt,
+        payload -> Text,
+        created_at -> BigInt,
+        status -> SmallInt,
+        attempts -> Integer,
+        last_attempt_at -> Nullable<BigInt>,
+        next_attempt_at -> Nullable<BigInt>,
+        response_status -> Nullable<Integer>,
+    }
+}
+
+joinable!(webhook_delivery -> webhook (webhook_rowid));
+
+table! {
+    email_outbox (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        recipient -> Text,
+        subject -> Text,
+        body -> Text,
+        created_at -> BigInt,
+        status -> SmallInt,
+        attempts -> Integer,
+        last_attempt_at -> Nullable<BigInt>,
+        next_attempt_at -> Nullable<BigInt>,
+        last_error -> Nullable<Text>,
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////
 
 allow_tables_to_appear_in_same_query!(
+    announcement,
+    audit_log,
     bbox_subscriptions,
+    category,
+    curated_organization,
+    curated_tag,
+    email_outbox,
     events,
+    event_recurrences,
+    event_registrations,
     event_tags,
+    moderation_assignment,
+    notification_digests,
+    notification_queue,
     place,
     place_rating,
     place_rating_comment,
+    place_rating_comment_flag,
+    place_clearance,
+    place_contact_verification,
+    place_draft,
+    place_external_id,
+    place_merge,
     place_revision,
+    place_revision_location,
     place_revision_review,
     place_revision_tag,
     org_tag_relations,
+    organization_api_token,
+    organization_api_token_scope,
     organizations,
+    reports,
+    saved_searches,
+    scout_report_opt_out,
     tags,
+    tag_subscriptions,
+    tag_subscription_tags,
     users,
+    user_api_tokens,
+    user_login_links,
+    user_permission,
+    user_status_log,
     user_tokens,
+    webhook,
+    webhook_delivery,
+    webhook_event,
 );
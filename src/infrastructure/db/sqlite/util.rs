@@ -5,7 +5,7 @@ use crate::core::{
     util::{
         geo::{MapBbox, MapPoint},
         nonce::Nonce,
-        time::Timestamp,
+        time::{Timestamp, TimestampMs},
     },
 };
 use chrono::prelude::*;
@@ -74,7 +74,11 @@ mod tests {
     }
 }
 
-pub(crate) fn event_from_event_entity_and_tags(e: EventEntity, tag_rels: &[EventTag]) -> e::Event {
+pub(crate) fn event_from_event_entity_and_tags(
+    e: EventEntity,
+    tag_rels: &[EventTag],
+    recurrences: &[EventRecurrence],
+) -> e::Event {
     let EventEntity {
         id,
         uid,
@@ -97,6 +101,7 @@ pub(crate) fn event_from_event_entity_and_tags(e: EventEntity, tag_rels: &[Event
         archived,
         image_url,
         image_link_url,
+        capacity,
         created_by_email,
         ..
     } = e;
@@ -106,6 +111,10 @@ pub(crate) fn event_from_event_entity_and_tags(e: EventEntity, tag_rels: &[Event
         .map(|r| &r.tag)
         .cloned()
         .collect();
+    let recurrence = recurrences
+        .iter()
+        .find(|r| r.event_id == id)
+        .and_then(recurrence_from_event_recurrence);
     let address = if street.is_some()
         || zip.is_some()
         || city.is_some()
@@ -159,9 +168,42 @@ pub(crate) fn event_from_event_entity_and_tags(e: EventEntity, tag_rels: &[Event
         created_by: created_by_email,
         registration,
         organizer,
-        archived: archived.map(Timestamp::from_inner),
+        archived: archived.map(TimestampMs::from_inner),
         image_url: image_url.and_then(load_url),
         image_link_url: image_link_url.and_then(load_url),
+        recurrence,
+        capacity: capacity.map(|x| x as u32),
+    }
+}
+
+pub(crate) fn recurrence_from_event_recurrence(r: &EventRecurrence) -> Option<e::Recurrence> {
+    use num_traits::FromPrimitive;
+    let frequency = e::RecurrenceFrequency::from_i16(r.frequency);
+    if frequency.is_none() {
+        // The database should only contain valid frequencies
+        log::error!(
+            "Failed to load recurrence of event {} with frequency {}",
+            r.event_id,
+            r.frequency
+        );
+    }
+    Some(e::Recurrence {
+        frequency: frequency?,
+        interval: r.interval as u16,
+        until: r.until.map(|x| NaiveDateTime::from_timestamp(x, 0)),
+    })
+}
+
+pub(crate) fn event_recurrence_from_recurrence(
+    event_id: i64,
+    r: &e::Recurrence,
+) -> EventRecurrence {
+    use num_traits::ToPrimitive;
+    EventRecurrence {
+        event_id,
+        frequency: r.frequency.to_i16().unwrap(),
+        interval: i32::from(r.interval),
+        until: r.until.map(|x| x.timestamp()),
     }
 }
 
@@ -177,6 +219,43 @@ impl From<e::Tag> for Tag {
     }
 }
 
+impl From<Category> for e::Category {
+    fn from(c: Category) -> e::Category {
+        e::Category {
+            id: c.id.into(),
+            tag: c.tag,
+        }
+    }
+}
+
+impl From<CuratedTag> for e::CuratedTag {
+    fn from(t: CuratedTag) -> e::CuratedTag {
+        let CuratedTag { tag, boost } = t;
+        e::CuratedTag { tag, boost }
+    }
+}
+
+impl From<e::CuratedTag> for CuratedTag {
+    fn from(t: e::CuratedTag) -> CuratedTag {
+        let e::CuratedTag { tag, boost } = t;
+        CuratedTag { tag, boost }
+    }
+}
+
+impl From<CuratedOrganization> for e::CuratedOrganization {
+    fn from(o: CuratedOrganization) -> e::CuratedOrganization {
+        let CuratedOrganization { org_id, boost } = o;
+        e::CuratedOrganization { org_id, boost }
+    }
+}
+
+impl From<e::CuratedOrganization> for CuratedOrganization {
+    fn from(o: e::CuratedOrganization) -> CuratedOrganization {
+        let e::CuratedOrganization { org_id, boost } = o;
+        CuratedOrganization { org_id, boost }
+    }
+}
+
 impl<'a> From<&'a e::User> for NewUser<'a> {
     fn from(u: &'a e::User) -> NewUser<'a> {
         use num_traits::ToPrimitive;
@@ -188,6 +267,26 @@ impl<'a> From<&'a e::User> for NewUser<'a> {
                 warn!("Could not convert role {:?} to i16. Use 0 instead.", u.role);
                 0
             }),
+            language: u.language.as_deref(),
+        }
+    }
+}
+
+impl From<UserApiToken> for e::UserApiToken {
+    fn from(from: UserApiToken) -> Self {
+        let UserApiToken {
+            id,
+            token,
+            label,
+            created_at,
+            user_email,
+        } = from;
+        Self {
+            id: id.into(),
+            user_email,
+            token,
+            label,
+            created_at: TimestampMs::from_inner(created_at),
         }
     }
 }
@@ -200,6 +299,7 @@ impl From<UserEntity> for e::User {
             email_confirmed,
             password,
             role,
+            language,
             ..
         } = u;
         Self {
@@ -214,6 +314,7 @@ impl From<UserEntity> for e::User {
                 );
                 e::Role::default()
             }),
+            language,
         }
     }
 }
@@ -231,8 +332,8 @@ impl From<PlaceRatingComment> for e::Comment {
         Self {
             id: id.into(),
             rating_id: rating_id.into(),
-            created_at: Timestamp::from_inner(created_at),
-            archived_at: archived_at.map(Timestamp::from_inner),
+            created_at: TimestampMs::from_inner(created_at),
+            archived_at: archived_at.map(TimestampMs::from_inner),
             text,
         }
     }
@@ -244,6 +345,7 @@ impl From<PlaceRating> for e::Rating {
             id,
             place_id,
             created_at,
+            created_by,
             archived_at,
             title,
             context,
@@ -254,8 +356,9 @@ impl From<PlaceRating> for e::Rating {
         Self {
             id: id.into(),
             place_id: place_id.into(),
-            created_at: Timestamp::from_inner(created_at),
-            archived_at: archived_at.map(Timestamp::from_inner),
+            created_at: TimestampMs::from_inner(created_at),
+            created_by,
+            archived_at: archived_at.map(TimestampMs::from_inner),
             title,
             value: (value as i8).into(),
             context: rating_context_from_str(&context).unwrap(),
@@ -288,6 +391,25 @@ impl From<BboxSubscriptionEntity> for e::BboxSubscription {
     }
 }
 
+impl From<PlaceDraft> for e::PlaceDraft {
+    fn from(from: PlaceDraft) -> Self {
+        let PlaceDraft {
+            id,
+            created_at,
+            updated_at,
+            data,
+            owner_email,
+        } = from;
+        Self {
+            id: id.into(),
+            owner_email,
+            created_at: TimestampMs::from_inner(created_at),
+            updated_at: TimestampMs::from_inner(updated_at),
+            data,
+        }
+    }
+}
+
 impl From<UserTokenEntity> for e::UserToken {
     fn from(from: UserTokenEntity) -> Self {
         Self {
@@ -300,6 +422,19 @@ impl From<UserTokenEntity> for e::UserToken {
     }
 }
 
+impl From<UserLoginLink> for e::LoginLink {
+    fn from(from: UserLoginLink) -> Self {
+        Self {
+            email_nonce: e::EmailNonce {
+                email: from.email,
+                nonce: from.nonce.parse::<Nonce>().unwrap_or_default(),
+            },
+            created_at: TimestampMs::from_inner(from.created_at),
+            expires_at: Timestamp::from_inner(from.expires_at),
+        }
+    }
+}
+
 pub(crate) fn rating_context_to_string(context: e::RatingContext) -> String {
     match context {
         e::RatingContext::Diversity => "diversity",
@@ -312,7 +447,7 @@ pub(crate) fn rating_context_to_string(context: e::RatingContext) -> String {
     .into()
 }
 
-fn rating_context_from_str(context: &str) -> Result<e::RatingContext> {
+pub(crate) fn rating_context_from_str(context: &str) -> Result<e::RatingContext> {
     Ok(match context {
         "diversity" => e::RatingContext::Diversity,
         "renewable" => e::RatingContext::Renewable,
@@ -328,17 +463,8 @@ fn rating_context_from_str(context: &str) -> Result<e::RatingContext> {
 
 impl From<e::Organization> for Organization {
     fn from(o: e::Organization) -> Self {
-        let e::Organization {
-            id,
-            name,
-            api_token,
-            ..
-        } = o;
-        Organization {
-            id,
-            name,
-            api_token,
-        }
+        let e::Organization { id, name, .. } = o;
+        Organization { id, name }
     }
 }
 
@@ -0,0 +1,280 @@
+use crate::core::db::{IndexQuery, IndexedPlace};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+const DEFAULT_CAPACITY: usize = 1_000;
+
+struct CachedPlaces {
+    places: Vec<IndexedPlace>,
+    expires_at: Instant,
+    last_used: Instant,
+}
+
+/// Short-lived cache for the results of frequent, identical
+/// place queries.
+///
+/// All entries are discarded when the index is flushed. Results
+/// of queries that have been started before the last flush are
+/// never cached.
+pub struct PlaceQueryCache {
+    entries: HashMap<String, CachedPlaces>,
+    ttl: Duration,
+    capacity: usize,
+    generation: u64,
+}
+
+impl Default for PlaceQueryCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL, DEFAULT_CAPACITY)
+    }
+}
+
+impl PlaceQueryCache {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            ttl,
+            capacity,
+            generation: 0,
+        }
+    }
+
+    /// The generation is incremented whenever the cache is cleared.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn get(&mut self, key: &str) -> Option<Vec<IndexedPlace>> {
+        let now = Instant::now();
+        match self.entries.get_mut(key) {
+            Some(entry) if entry.expires_at > now => {
+                entry.last_used = now;
+                Some(entry.places.clone())
+            }
+            Some(_) => {
+                self.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&mut self, key: String, places: Vec<IndexedPlace>, generation: u64) {
+        if generation != self.generation || self.capacity == 0 {
+            // The index has been modified in the meantime
+            return;
+        }
+        let now = Instant::now();
+        self.entries.retain(|_, entry| entry.expires_at > now);
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            let lru_key = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(key) = lru_key {
+                self.entries.remove(&key);
+            }
+        }
+        self.entries.insert(
+            key,
+            CachedPlaces {
+                places,
+                expires_at: now + self.ttl,
+                last_used: now,
+            },
+        );
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
+
+/// Normalized cache key for a place query. The order of
+/// filter values and the case of the search text don't
+/// affect the results.
+pub fn place_query_cache_key(query: &IndexQuery, limit: usize) -> String {
+    let IndexQuery {
+        status,
+        include_bbox,
+        exclude_bbox,
+        center,
+        radius_meters,
+        categories,
+        ids,
+        hash_tags,
+        any_hash_tags,
+        exclude_hash_tags,
+        text_tags,
+        text,
+        fuzzy_distance,
+        ts_min_lb,
+        ts_min_ub,
+        ts_max_lb,
+        ts_max_ub,
+        updated_since,
+        updated_until,
+        sort_by,
+        boost_quality,
+        offset,
+        after,
+        title_prefix,
+        org_id,
+        min_total_rating,
+        min_ratings,
+    } = query;
+    let status = status.as_ref().map(|status| {
+        let mut status: Vec<_> = status.iter().map(|s| format!("{:?}", s)).collect();
+        status.sort_unstable();
+        status.dedup();
+        status
+    });
+    let text = text
+        .as_ref()
+        .map(|text| text.trim().to_lowercase())
+        .filter(|text| !text.is_empty());
+    let title_prefix = title_prefix
+        .as_ref()
+        .map(|prefix| prefix.trim().to_lowercase())
+        .filter(|prefix| !prefix.is_empty());
+    format!(
+        "{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{}|{}|{:?}|{:?}|{:?}|{:?}|{:?}|{}",
+        status,
+        include_bbox,
+        exclude_bbox,
+        center,
+        radius_meters,
+        sorted(categories),
+        sorted(ids),
+        sorted(hash_tags),
+        sorted(any_hash_tags),
+        sorted(exclude_hash_tags),
+        sorted(text_tags),
+        text,
+        fuzzy_distance,
+        ts_min_lb,
+        ts_min_ub,
+        ts_max_lb,
+        ts_max_ub,
+        updated_since,
+        updated_until,
+        sort_by,
+        boost_quality,
+        offset,
+        after,
+        title_prefix,
+        org_id,
+        min_total_rating,
+        min_ratings,
+        limit,
+    )
+}
+
+fn sorted<T: Ord + Clone>(values: &[T]) -> Vec<T> {
+    let mut values = values.to_vec();
+    values.sort_unstable();
+    values.dedup();
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::db::IndexSortOrder;
+
+    fn places(ids: &[&str]) -> Vec<IndexedPlace> {
+        ids.iter()
+            .map(|id| IndexedPlace {
+                id: (*id).into(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn normalize_query_keys() {
+        let q1 = IndexQuery {
+            hash_tags: vec!["b".into(), "a".into()],
+            text: Some(" Foo ".into()),
+            ..Default::default()
+        };
+        let q2 = IndexQuery {
+            hash_tags: vec!["a".into(), "b".into(), "a".into()],
+            text: Some("foo".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            place_query_cache_key(&q1, 10),
+            place_query_cache_key(&q2, 10)
+        );
+        assert_ne!(
+            place_query_cache_key(&q1, 10),
+            place_query_cache_key(&q1, 11)
+        );
+        let q3 = IndexQuery {
+            hash_tags: vec!["a".into()],
+            ..Default::default()
+        };
+        assert_ne!(
+            place_query_cache_key(&q1, 10),
+            place_query_cache_key(&q3, 10)
+        );
+        let q4 = IndexQuery {
+            sort_by: IndexSortOrder::Title,
+            ..q1.clone()
+        };
+        assert_ne!(
+            place_query_cache_key(&q1, 10),
+            place_query_cache_key(&q4, 10)
+        );
+        let q5 = IndexQuery {
+            offset: 10,
+            ..q1.clone()
+        };
+        assert_ne!(
+            place_query_cache_key(&q1, 10),
+            place_query_cache_key(&q5, 10)
+        );
+    }
+
+    #[test]
+    fn invalidate_on_clear() {
+        let mut cache = PlaceQueryCache::default();
+        let generation = cache.generation();
+        cache.insert("q".into(), places(&["a"]), generation);
+        assert_eq!(1, cache.get("q").unwrap().len());
+
+        cache.clear();
+        assert!(cache.get("q").is_none());
+
+        // Results of queries that have been started before
+        // clearing the cache are discarded
+        cache.insert("q".into(), places(&["a"]), generation);
+        assert!(cache.get("q").is_none());
+    }
+
+    #[test]
+    fn expire_entries() {
+        let mut cache = PlaceQueryCache::new(Duration::from_millis(0), 10);
+        cache.insert("q".into(), places(&["a"]), cache.generation());
+        assert!(cache.get("q").is_none());
+    }
+
+    #[test]
+    fn evict_least_recently_used() {
+        let mut cache = PlaceQueryCache::new(DEFAULT_TTL, 2);
+        cache.insert("q1".into(), places(&["a"]), cache.generation());
+        cache.insert("q2".into(), places(&["b"]), cache.generation());
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(cache.get("q1").is_some());
+        cache.insert("q3".into(), places(&["c"]), cache.generation());
+        assert!(cache.get("q1").is_some());
+        assert!(cache.get("q2").is_none());
+        assert!(cache.get("q3").is_some());
+    }
+}
@@ -1,35 +1,59 @@
 use crate::core::{
     db::{
         EventAndPlaceIndexer, EventIndexer, IdIndex, IdIndexer, IndexQuery, IndexQueryMode,
-        IndexedPlace, Indexer, PlaceIndex, PlaceIndexer,
+        IndexSortOrder, IndexedPlace, Indexer, MostPopularTagsParams, Pagination, PlaceCluster,
+        PlaceClusterIndex, PlaceIndex, PlaceIndexer, TagIndex, TextSnippet,
     },
     entities::{
-        Address, AvgRatingValue, AvgRatings, Category, Event, Id, Place, RatingContext,
-        ReviewStatus, ReviewStatusPrimitive,
+        Address, AvgRatingValue, AvgRatings, Categories, Category, CuratedTag, Event, Id, Place,
+        RatingContext, ReviewStatus, ReviewStatusPrimitive, TagFrequency, TagOwnership,
     },
     util::{
-        geo::{LatCoord, LngCoord, MapPoint},
-        time::Timestamp,
+        geo::{Distance, LatCoord, LngCoord, MapBbox, MapPoint},
+        quality,
+        time::{Timestamp, TimestampMs},
     },
 };
 
+use self::{
+    cache::{place_query_cache_key, PlaceQueryCache},
+    queue::{IndexCommand, IndexingQueue},
+};
 use anyhow::{bail, Result as Fallible};
 use failure::Fail;
+use futures::executor::block_on;
 use num_traits::ToPrimitive;
 use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, HashMap},
+    fs,
     ops::Bound,
-    path::Path,
-    sync::{Arc, Mutex},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
 };
 use strum::IntoEnumIterator;
 use tantivy::{
-    collector::TopDocs,
-    query::{BooleanQuery, Occur, Query, QueryParser, RangeQuery, TermQuery},
+    collector::{Collector, SegmentCollector, TopDocs},
+    directory::{Directory, DirectoryLock, MmapDirectory, INDEX_WRITER_LOCK},
+    fastfield::FastFieldReader,
+    query::{
+        AllQuery, BooleanQuery, FuzzyTermQuery, Occur, Query, QueryParser, RangeQuery, TermQuery,
+    },
     schema::*,
-    tokenizer::{LowerCaser, RawTokenizer, RemoveLongFilter, SimpleTokenizer, TextAnalyzer},
-    DocAddress, DocId, Document, Index, IndexReader, IndexWriter, ReloadPolicy, Score,
-    SegmentReader,
+    store::StoreReader,
+    tokenizer::{
+        Language, LowerCaser, RawTokenizer, RemoveLongFilter, SimpleTokenizer, Stemmer,
+        TextAnalyzer,
+    },
+    DocAddress, DocId, DocSet, Document, Index, IndexReader, IndexWriter, ReloadPolicy, Score,
+    Searcher, SegmentId, SegmentLocalId, SegmentReader, Snippet, SnippetGenerator, TantivyError,
 };
+use thiserror::Error;
+
+mod cache;
+mod queue;
 
 const OVERALL_INDEX_HEAP_SIZE_IN_BYTES: usize = 50_000_000;
 
@@ -52,6 +76,9 @@ struct IndexedFields {
     status: Field,
     lat: Field,
     lng: Field,
+    // Positions of all additional locations of a place in the same order
+    additional_lat: Field,
+    additional_lng: Field,
     ts_min: Field, // minimum time stamp with second precision, e.g. event start
     ts_max: Field, // maximum time stamp with second precision, e.g. event end
     title: Field,
@@ -63,6 +90,7 @@ struct IndexedFields {
     address_state: Field,
     organizer: Field,
     tag: Field,
+    org: Field, // ids of all organizations that own any of the tags
     ratings_diversity: Field,
     ratings_fairness: Field,
     ratings_humanity: Field,
@@ -70,10 +98,17 @@ struct IndexedFields {
     ratings_solidarity: Field,
     ratings_transparency: Field,
     total_rating: Field,
+    ratings_count: Field,
+    curated_boost: Field,
+    created_at: Field, // time stamp of the first revision with millisecond precision
+    updated_at: Field, // time stamp of the last change with millisecond precision
+    title_key: Field,  // case-insensitive sort key derived from the title
+    quality: Field,    // completeness score of a place regardless of its last verification
+    title_prefix: Field, // the whole title as a single, lowercase term
 }
 
 impl IndexedFields {
-    fn build_schema() -> (Self, Schema) {
+    fn build_schema(text_tokenizer: &str) -> (Self, Schema) {
         let id_options = TextOptions::default()
             .set_indexing_options(
                 TextFieldIndexing::default()
@@ -88,10 +123,15 @@ impl IndexedFields {
                     .set_index_option(IndexRecordOption::WithFreqs),
             )
             .set_stored();
+        let title_prefix_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer(TITLE_PREFIX_TOKENIZER)
+                .set_index_option(IndexRecordOption::Basic),
+        );
         let address_options = TextOptions::default()
             .set_indexing_options(
                 TextFieldIndexing::default()
-                    .set_tokenizer(TEXT_TOKENIZER)
+                    .set_tokenizer(text_tokenizer)
                     .set_index_option(IndexRecordOption::WithFreqs),
             )
             // Address fields currently are currently not store stored
@@ -101,7 +141,7 @@ impl IndexedFields {
         let text_options = TextOptions::default()
             .set_indexing_options(
                 TextFieldIndexing::default()
-                    .set_tokenizer(TEXT_TOKENIZER)
+                    .set_tokenizer(text_tokenizer)
                     // IndexRecordOption::WithFreqsAndPositions: Positions are not
                     // needed as long as no PhraseQuery is used.
                     .set_index_option(IndexRecordOption::WithFreqs),
@@ -110,10 +150,12 @@ impl IndexedFields {
         let mut schema_builder = SchemaBuilder::default();
         let fields = Self {
             kind: schema_builder.add_i64_field("kind", INDEXED),
-            id: schema_builder.add_text_field("id", id_options),
+            id: schema_builder.add_text_field("id", id_options.clone()),
             status: schema_builder.add_i64_field("status", INDEXED | STORED),
-            lat: schema_builder.add_f64_field("lat", INDEXED | STORED),
-            lng: schema_builder.add_f64_field("lon", INDEXED | STORED),
+            lat: schema_builder.add_f64_field("lat", INDEXED | STORED | FAST),
+            lng: schema_builder.add_f64_field("lon", INDEXED | STORED | FAST),
+            additional_lat: schema_builder.add_f64_field("add_lat", INDEXED | STORED),
+            additional_lng: schema_builder.add_f64_field("add_lon", INDEXED | STORED),
             ts_min: schema_builder.add_i64_field("ts_min", INDEXED | STORED),
             ts_max: schema_builder.add_i64_field("ts_max", INDEXED | STORED),
             title: schema_builder.add_text_field("tit", text_options.clone()),
@@ -125,20 +167,81 @@ impl IndexedFields {
             address_country: schema_builder.add_text_field("adr_country", address_options.clone()),
             address_state: schema_builder.add_text_field("adr_state", address_options),
             tag: schema_builder.add_text_field("tag", tag_options),
-            ratings_diversity: schema_builder.add_f64_field("rat_diversity", STORED),
-            ratings_fairness: schema_builder.add_f64_field("rat_fairness", STORED),
-            ratings_humanity: schema_builder.add_f64_field("rat_humanity", STORED),
-            ratings_renewable: schema_builder.add_f64_field("rat_renewable", STORED),
-            ratings_solidarity: schema_builder.add_f64_field("rat_solidarity", STORED),
-            ratings_transparency: schema_builder.add_f64_field("rat_transparency", STORED),
-            total_rating: schema_builder.add_u64_field("rat_total", STORED | FAST),
+            org: schema_builder.add_text_field("org_id", id_options),
+            ratings_diversity: schema_builder.add_f64_field("rat_diversity", INDEXED | STORED),
+            ratings_fairness: schema_builder.add_f64_field("rat_fairness", INDEXED | STORED),
+            ratings_humanity: schema_builder.add_f64_field("rat_humanity", INDEXED | STORED),
+            ratings_renewable: schema_builder.add_f64_field("rat_renewable", INDEXED | STORED),
+            ratings_solidarity: schema_builder.add_f64_field("rat_solidarity", INDEXED | STORED),
+            ratings_transparency: schema_builder
+                .add_f64_field("rat_transparency", INDEXED | STORED),
+            total_rating: schema_builder.add_u64_field("rat_total", INDEXED | STORED | FAST),
+            ratings_count: schema_builder.add_u64_field("rat_count", INDEXED | STORED | FAST),
+            curated_boost: schema_builder.add_f64_field("boost", FAST),
+            created_at: schema_builder.add_i64_field("ts_created", INDEXED | FAST),
+            updated_at: schema_builder.add_i64_field("ts_updated", INDEXED | FAST),
+            title_key: schema_builder.add_u64_field("tit_key", FAST),
+            quality: schema_builder.add_f64_field("quality", FAST),
+            title_prefix: schema_builder.add_text_field("tit_prefix", title_prefix_options),
         };
         (fields, schema_builder.build())
     }
 
+    fn rating_context_field(&self, context: RatingContext) -> Field {
+        match context {
+            RatingContext::Diversity => self.ratings_diversity,
+            RatingContext::Fairness => self.ratings_fairness,
+            RatingContext::Humanity => self.ratings_humanity,
+            RatingContext::Renewable => self.ratings_renewable,
+            RatingContext::Solidarity => self.ratings_solidarity,
+            RatingContext::Transparency => self.ratings_transparency,
+        }
+    }
+
+    // All fields that are searched for the query text
+    fn text_fields(&self) -> Vec<Field> {
+        vec![
+            self.title,
+            self.description,
+            self.address_street,
+            self.address_city,
+            self.address_zip,
+            self.address_country,
+            self.address_state,
+            self.organizer,
+        ]
+    }
+
+    fn add_address(&self, doc: &mut Document, address: &Address) {
+        let Address {
+            street,
+            city,
+            zip,
+            country,
+            state,
+        } = address;
+        if let Some(street) = street {
+            doc.add_text(self.address_street, street);
+        }
+        if let Some(city) = city {
+            doc.add_text(self.address_city, city);
+        }
+        if let Some(zip) = zip {
+            doc.add_text(self.address_zip, zip);
+        }
+        if let Some(country) = country {
+            doc.add_text(self.address_country, country);
+        }
+        if let Some(state) = state {
+            doc.add_text(self.address_state, state);
+        }
+    }
+
     fn read_indexed_place(&self, doc: &Document) -> IndexedPlace {
         let mut lat: Option<LatCoord> = Default::default();
         let mut lng: Option<LngCoord> = Default::default();
+        let mut additional_lats = Vec::new();
+        let mut additional_lngs = Vec::new();
         let mut place = IndexedPlace::default();
         place.tags.reserve(32);
         for field_value in doc.field_values() {
@@ -155,6 +258,12 @@ impl IndexedFields {
                     debug_assert!(lng.is_none());
                     lng = Some(LngCoord::from_deg(fv.value().f64_value()));
                 }
+                fv if fv.field() == self.additional_lat => {
+                    additional_lats.push(fv.value().f64_value());
+                }
+                fv if fv.field() == self.additional_lng => {
+                    additional_lngs.push(fv.value().f64_value());
+                }
                 fv if fv.field() == self.id => {
                     debug_assert!(place.id.is_empty());
                     if let Some(id) = fv.value().text() {
@@ -211,6 +320,9 @@ impl IndexedFields {
                     place.ratings.transparency = fv.value().f64_value().into();
                 }
                 fv if fv.field() == self.total_rating => (),
+                fv if fv.field() == self.ratings_count => {
+                    place.ratings.count = fv.value().u64_value();
+                }
                 // Address fields are currently not stored
                 //fv if fv.field() == self.address_street => (),
                 //fv if fv.field() == self.address_city => (),
@@ -227,23 +339,137 @@ impl IndexedFields {
         } else {
             error!("Invalid position: lat = {:?}, lng = {:?}", lat, lng);
         }
+        debug_assert_eq!(additional_lats.len(), additional_lngs.len());
+        place.additional_positions = additional_lats
+            .into_iter()
+            .zip(additional_lngs)
+            .filter_map(|(lat, lng)| MapPoint::try_from_lat_lng_deg(lat, lng))
+            .collect();
         place
     }
 }
 
-pub(crate) struct TantivyIndex {
+// The read-only part of the index that is shared
+// by concurrent searches without any locking
+pub(crate) struct TantivyIndexReader {
     fields: IndexedFields,
     index_reader: IndexReader,
-    index_writer: IndexWriter,
     text_query_parser: QueryParser,
+    text_tokenizer: TextAnalyzer,
+    // Definitions of the categories that are mapped to tags
+    categories: RwLock<Categories>,
+}
+
+pub(crate) struct TantivyIndex {
+    reader: Arc<TantivyIndexReader>,
+    index_writer: IndexWriter,
+    // Score boost factors of curated tags
+    curated_tags: HashMap<String, f64>,
+    // Ids of the organizations that own a tag
+    tag_owners: HashMap<String, Vec<String>>,
 }
 
 const ID_TOKENIZER: &str = "raw";
 const TAG_TOKENIZER: &str = "tag";
+const TITLE_PREFIX_TOKENIZER: &str = "title_prefix";
 const TEXT_TOKENIZER: &str = "default";
 
+// Must be incremented whenever the schema changes. The index is
+// stored in a separate subdirectory for each version and language.
+const INDEX_SCHEMA_VERSION: u32 = 8;
+
+// References all segments of a committed index
+const INDEX_META_FILE: &str = "meta.json";
+
+// Reading the files of an index that is modified concurrently
+// is retried at most this number of times
+const MAX_SNAPSHOT_ATTEMPTS: usize = 3;
+
+/// The language of all full-text fields for reducing
+/// words to their stem, e.g. "Gärten" to "Garten".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextLanguage {
+    English,
+    French,
+    German,
+}
+
+impl TextLanguage {
+    fn tokenizer_name(self) -> &'static str {
+        match self {
+            TextLanguage::English => "text_en",
+            TextLanguage::French => "text_fr",
+            TextLanguage::German => "text_de",
+        }
+    }
+
+    fn stemmer_language(self) -> Language {
+        match self {
+            TextLanguage::English => Language::English,
+            TextLanguage::French => Language::French,
+            TextLanguage::German => Language::German,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Unsupported text language: {0}")]
+pub struct TextLanguageParseError(String);
+
+impl FromStr for TextLanguage {
+    type Err = TextLanguageParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "en" | "english" => Ok(TextLanguage::English),
+            "fr" | "french" => Ok(TextLanguage::French),
+            "de" | "german" => Ok(TextLanguage::German),
+            _ => Err(TextLanguageParseError(s.into())),
+        }
+    }
+}
+
+fn text_tokenizer_name(language: Option<TextLanguage>) -> &'static str {
+    language
+        .map(TextLanguage::tokenizer_name)
+        .unwrap_or(TEXT_TOKENIZER)
+}
+
+// The subdirectory of the current schema version and language
+fn index_dir(path: &Path, language: Option<TextLanguage>) -> PathBuf {
+    path.join(format!(
+        "v{}_{}",
+        INDEX_SCHEMA_VERSION,
+        text_tokenizer_name(language)
+    ))
+}
+
+// The writer lock of an index in a directory prevents that the
+// index is replaced or removed while it is still in use, e.g.
+// by a running server.
+fn lock_index_dir(dir: &Path) -> Fallible<DirectoryLock> {
+    let directory = MmapDirectory::open(dir).map_err(Fail::compat)?;
+    directory.acquire_lock(&INDEX_WRITER_LOCK).map_err(|err| {
+        anyhow::anyhow!(
+            "Full-text search index in directory {} is in use: {}",
+            dir.to_string_lossy(),
+            err
+        )
+    })
+}
+
 const MAX_TOKEN_LEN: usize = 40;
 
+// Tantivy supports fuzzy matching only up to this edit distance
+const MAX_FUZZY_DISTANCE: u8 = 2;
+
+// Short words are always matched exactly, because they would
+// otherwise match too many unrelated words
+const MIN_FUZZY_WORD_LEN: usize = 4;
+
+// Limits the number of alternatives when matching a prefix
+const MAX_PREFIX_TERMS: usize = 100;
+
 fn register_tokenizers(index: &Index) {
     // Predefined tokenizers
     debug_assert!(index.tokenizers().get(ID_TOKENIZER).is_some());
@@ -254,10 +480,28 @@ fn register_tokenizers(index: &Index) {
         .filter(LowerCaser)
         .filter(RemoveLongFilter::limit(MAX_TOKEN_LEN));
     index.tokenizers().register(TAG_TOKENIZER, tag_tokenizer);
+    debug_assert!(index.tokenizers().get(TITLE_PREFIX_TOKENIZER).is_none());
+    let title_prefix_tokenizer = TextAnalyzer::from(RawTokenizer).filter(LowerCaser);
+    index
+        .tokenizers()
+        .register(TITLE_PREFIX_TOKENIZER, title_prefix_tokenizer);
     let text_tokenizer = TextAnalyzer::from(SimpleTokenizer)
         .filter(LowerCaser)
         .filter(RemoveLongFilter::limit(MAX_TOKEN_LEN));
     index.tokenizers().register(TEXT_TOKENIZER, text_tokenizer);
+    for language in &[
+        TextLanguage::English,
+        TextLanguage::French,
+        TextLanguage::German,
+    ] {
+        let stemming_tokenizer = TextAnalyzer::from(SimpleTokenizer)
+            .filter(LowerCaser)
+            .filter(RemoveLongFilter::limit(MAX_TOKEN_LEN))
+            .filter(Stemmer::new(language.stemmer_language()));
+        index
+            .tokenizers()
+            .register(language.tokenizer_name(), stemming_tokenizer);
+    }
 }
 
 fn f64_to_u64(val: f64, min: f64, max: f64) -> u64 {
@@ -303,34 +547,112 @@ fn u64_to_avg_rating(val: u64) -> AvgRatingValue {
     .into()
 }
 
+// Order preserving sort key of the first 8 bytes of the lowercase title.
+// Titles that share a common prefix are finally sorted after loading
+// the matching documents.
+fn title_sort_key(title: &str) -> u64 {
+    let mut key = [0u8; 8];
+    for (k, b) in key.iter_mut().zip(title.trim().to_lowercase().bytes()) {
+        *k = b;
+    }
+    u64::from_be_bytes(key)
+}
+
 #[derive(Copy, Clone, Debug)]
 enum TopDocsMode {
     Score,
     Rating,
     ScoreBoostedByRating,
+    Distance(MapPoint),
+    RecentlyChanged,
+    RecentlyCreated,
+    Title,
+    RatingsCount,
 }
 
 impl TantivyIndex {
     pub fn create_in_ram() -> Fallible<Self> {
         let no_path: Option<&Path> = None;
-        Self::create(no_path)
+        Self::create(no_path, None)
     }
 
-    pub fn create<P: AsRef<Path>>(path: Option<P>) -> Fallible<Self> {
-        let (fields, schema) = IndexedFields::build_schema();
+    pub fn create<P: AsRef<Path>>(
+        path: Option<P>,
+        language: Option<TextLanguage>,
+    ) -> Fallible<Self> {
+        // Indexes with an outdated schema are never opened and
+        // need to be recreated.
+        let dir = path.map(|path| index_dir(path.as_ref(), language));
+        Self::create_in_dir(dir.as_deref(), language)
+    }
 
-        // TODO: Open index from existing directory
-        let index = if let Some(path) = path {
+    // Replaces any existing index in the given directory
+    // unless it is still in use
+    fn create_in_dir(dir: Option<&Path>, language: Option<TextLanguage>) -> Fallible<Self> {
+        let text_tokenizer_name = text_tokenizer_name(language);
+        let (fields, schema) = IndexedFields::build_schema(text_tokenizer_name);
+
+        let index = if let Some(path) = dir {
+            if path.exists() {
+                let _lock = lock_index_dir(path)?;
+                info!(
+                    "Removing previous full-text search index in directory: {}",
+                    path.to_string_lossy()
+                );
+                fs::remove_dir_all(&path)?;
+            }
+            fs::create_dir_all(&path)?;
             info!(
                 "Creating full-text search index in directory: {}",
-                path.as_ref().to_string_lossy()
+                path.to_string_lossy()
             );
             Index::create_in_dir(path, schema).map_err(Fail::compat)?
         } else {
             warn!("Creating full-text search index in RAM");
             Index::create_in_ram(schema)
         };
+        Self::with_index(index, fields, text_tokenizer_name)
+    }
+
+    /// Open an existing index in the given directory without
+    /// modifying its contents.
+    ///
+    /// Fails if the directory doesn't contain an index with the
+    /// current schema or if the index is locked by another writer,
+    /// e.g. a running server.
+    pub fn open<P: AsRef<Path>>(path: P, language: Option<TextLanguage>) -> Fallible<Self> {
+        let dir = index_dir(path.as_ref(), language);
+        Self::open_in_dir(&dir, language)
+    }
+
+    fn open_in_dir(dir: &Path, language: Option<TextLanguage>) -> Fallible<Self> {
+        let text_tokenizer_name = text_tokenizer_name(language);
+        let (fields, schema) = IndexedFields::build_schema(text_tokenizer_name);
+        if !dir.is_dir() {
+            bail!(
+                "No full-text search index in directory: {}",
+                dir.to_string_lossy()
+            );
+        }
+        info!(
+            "Opening full-text search index in directory: {}",
+            dir.to_string_lossy()
+        );
+        let index = Index::open_in_dir(dir).map_err(Fail::compat)?;
+        if index.schema() != schema {
+            bail!(
+                "Outdated schema of full-text search index in directory: {}",
+                dir.to_string_lossy()
+            );
+        }
+        Self::with_index(index, fields, text_tokenizer_name)
+    }
 
+    fn with_index(
+        index: Index,
+        fields: IndexedFields,
+        text_tokenizer_name: &str,
+    ) -> Fallible<Self> {
         register_tokenizers(&index);
 
         // Prefer to manually reload the index reader during `flush()`
@@ -345,27 +667,72 @@ impl TantivyIndex {
         let index_writer = index
             .writer(OVERALL_INDEX_HEAP_SIZE_IN_BYTES)
             .map_err(Fail::compat)?;
-        let text_query_parser = QueryParser::for_index(
-            &index,
-            vec![
-                fields.title,
-                fields.description,
-                fields.address_street,
-                fields.address_city,
-                fields.address_zip,
-                fields.address_country,
-                fields.address_state,
-                fields.organizer,
-            ],
-        );
-        Ok(Self {
+        let text_query_parser = QueryParser::for_index(&index, fields.text_fields());
+        let text_tokenizer = index
+            .tokenizers()
+            .get(text_tokenizer_name)
+            .expect("text tokenizer");
+        let reader = TantivyIndexReader {
             fields,
             index_reader,
-            index_writer,
             text_query_parser,
+            text_tokenizer,
+            categories: RwLock::new(Categories::default()),
+        };
+        Ok(Self {
+            reader: Arc::new(reader),
+            index_writer,
+            curated_tags: HashMap::new(),
+            tag_owners: HashMap::new(),
         })
     }
 
+    // The maximum boost of all curated tags or 1.0 if none of the tags is curated
+    fn curated_boost(&self, tags: &[String]) -> f64 {
+        tags.iter()
+            .filter_map(|tag| self.curated_tags.get(tag))
+            .fold(None, |max: Option<f64>, boost| {
+                Some(max.map_or(*boost, |max| max.max(*boost)))
+            })
+            .unwrap_or(1.0)
+    }
+
+    // The ids of all organizations that own any of the tags
+    fn owner_org_ids<'a>(&'a self, tags: &[String]) -> BTreeSet<&'a str> {
+        tags.iter()
+            .filter_map(|tag| self.tag_owners.get(tag))
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+impl TantivyIndexReader {
+    fn read_categories(&self) -> std::sync::RwLockReadGuard<Categories> {
+        match self.categories.read() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn set_categories(&self, categories: &[Category]) {
+        let categories = Categories::from(categories.to_vec());
+        match self.categories.write() {
+            Ok(mut guard) => *guard = categories,
+            Err(poisoned) => *poisoned.into_inner() = categories,
+        }
+    }
+
+    // The ids of all committed segments that are visible for searches
+    fn segment_ids(&self) -> Vec<SegmentId> {
+        self.index_reader
+            .searcher()
+            .segment_readers()
+            .iter()
+            .map(SegmentReader::segment_id)
+            .collect()
+    }
+
     fn build_query(
         &self,
         query_mode: IndexQueryMode,
@@ -395,6 +762,51 @@ impl TantivyIndex {
             sub_queries.push((Occur::Must, ids_query));
         }
 
+        // Title prefix
+        if let Some(prefix) = query
+            .title_prefix
+            .as_ref()
+            .map(|prefix| prefix.trim().to_lowercase())
+            .filter(|prefix| !prefix.is_empty())
+        {
+            debug!("Query title prefix: {}", prefix);
+            let prefix_terms: Vec<_> = self
+                .prefix_terms(self.fields.title_prefix, &prefix, MAX_PREFIX_TERMS)
+                .into_iter()
+                .map(|(term, _)| Term::from_field_text(self.fields.title_prefix, &term))
+                .collect();
+            // Matches nothing if no title starts with the prefix
+            sub_queries.push((
+                Occur::Must,
+                Box::new(BooleanQuery::new_multiterms_query(prefix_terms)),
+            ));
+        }
+
+        // Minimum ratings
+        if let Some(min_total_rating) = query.min_total_rating {
+            debug!("Query minimum total rating: {:?}", min_total_rating);
+            let min_total_rating = avg_rating_to_u64(min_total_rating.clamp());
+            sub_queries.push((
+                Occur::Must,
+                Box::new(RangeQuery::new_u64_bounds(
+                    self.fields.total_rating,
+                    Bound::Included(min_total_rating),
+                    Bound::Unbounded,
+                )),
+            ));
+        }
+        for (context, min_rating) in &query.min_ratings {
+            debug!("Query minimum {:?} rating: {:?}", context, min_rating);
+            sub_queries.push((
+                Occur::Must,
+                Box::new(RangeQuery::new_f64_bounds(
+                    self.fields.rating_context_field(*context),
+                    Bound::Included(min_rating.clamp().into()),
+                    Bound::Unbounded,
+                )),
+            ));
+        }
+
         // Status
         if let Some(ref status) = query.status {
             // NOTE(2019-12-17, Tantivy v0.11.1): A boolean query that contains
@@ -463,36 +875,44 @@ impl TantivyIndex {
             sub_queries.push((Occur::Must, Box::new(BooleanQuery::from(status_queries))));
         }
 
-        // Bbox (include)
+        // Circle (include): The enclosing bbox is used for narrowing the
+        // results that are then filtered by their exact distance when
+        // collecting the documents.
+        let circle_bbox =
+            query_circle(query).map(|(center, radius)| MapBbox::centered_around(center, radius));
+
+        // Bbox (include): Either the primary or any additional
+        // location of a place must be inside
         if let Some(ref bbox) = query.include_bbox {
             debug!("Query bbox (include): {}", bbox);
             debug_assert!(bbox.is_valid());
             debug_assert!(!bbox.is_empty());
-            let lat_query = RangeQuery::new_f64_bounds(
-                self.fields.lat,
-                Bound::Included(bbox.south_west().lat().to_deg()),
-                Bound::Included(bbox.north_east().lat().to_deg()),
-            );
-            // Latitude query: Always inclusive
-            sub_queries.push((Occur::Must, Box::new(lat_query)));
-            // Longitude query: Either inclusive or exclusive (wrap around)
-            if bbox.south_west().lng() <= bbox.north_east().lng() {
-                // regular (inclusive)
-                let lng_query = RangeQuery::new_f64_bounds(
-                    self.fields.lng,
-                    Bound::Included(bbox.south_west().lng().to_deg()),
-                    Bound::Included(bbox.north_east().lng().to_deg()),
-                );
-                sub_queries.push((Occur::Must, Box::new(lng_query)));
-            } else {
-                // inverse (exclusive)
-                let lng_query = RangeQuery::new_f64_bounds(
-                    self.fields.lng,
-                    Bound::Excluded(bbox.north_east().lng().to_deg()),
-                    Bound::Excluded(bbox.south_west().lng().to_deg()),
-                );
-                sub_queries.push((Occur::MustNot, Box::new(lng_query)));
-            }
+            let location_queries: Vec<(Occur, Box<dyn Query>)> = vec![
+                (
+                    Occur::Should,
+                    Box::new(bbox_query(self.fields.lat, self.fields.lng, bbox)),
+                ),
+                (
+                    Occur::Should,
+                    Box::new(bbox_query(
+                        self.fields.additional_lat,
+                        self.fields.additional_lng,
+                        bbox,
+                    )),
+                ),
+            ];
+            sub_queries.push((Occur::Must, Box::new(BooleanQuery::from(location_queries))));
+        }
+
+        // Circle (include): Only the primary location is considered
+        if let Some(ref bbox) = circle_bbox {
+            debug!("Query bbox (circle): {}", bbox);
+            debug_assert!(bbox.is_valid());
+            debug_assert!(!bbox.is_empty());
+            sub_queries.push((
+                Occur::Must,
+                Box::new(bbox_query(self.fields.lat, self.fields.lng, bbox)),
+            ));
         }
 
         // Inverse Bbox (exclude)
@@ -525,17 +945,35 @@ impl TantivyIndex {
                 );
                 sub_queries.push((Occur::Must, Box::new(lng_query)));
             }
+            // Additional locations (exclusive)
+            let lat_query = RangeQuery::new_f64_bounds(
+                self.fields.additional_lat,
+                Bound::Included(bbox.south_west().lat().to_deg()),
+                Bound::Included(bbox.north_east().lat().to_deg()),
+            );
+            sub_queries.push((Occur::MustNot, Box::new(lat_query)));
+            for (min_lng, max_lng) in lng_ranges(bbox) {
+                let lng_query = RangeQuery::new_f64_bounds(
+                    self.fields.additional_lng,
+                    Bound::Included(min_lng),
+                    Bound::Included(max_lng),
+                );
+                sub_queries.push((Occur::MustNot, Box::new(lng_query)));
+            }
         }
 
-        let merged_tags = Category::merge_ids_into_tags(
-            &query
-                .categories
-                .iter()
-                .map(|c| Id::from(*c))
-                .collect::<Vec<_>>(),
-            query.hash_tags.clone(),
-        );
-        let (tags, categories) = Category::split_from_tags(merged_tags);
+        let (tags, categories) = {
+            let all_categories = self.read_categories();
+            let merged_tags = all_categories.merge_ids_into_tags(
+                &query
+                    .categories
+                    .iter()
+                    .map(|c| Id::from(*c))
+                    .collect::<Vec<_>>(),
+                query.hash_tags.clone(),
+            );
+            all_categories.split_from_tags(merged_tags)
+        };
 
         // Categories (= mapped to predefined tags + separate sub-query + kind)
         let mut kinds_mask = 0i64;
@@ -603,18 +1041,63 @@ impl TantivyIndex {
             sub_queries.push((Occur::Must, Box::new(tag_query)));
         }
 
+        // Hash tags (at least one)
+        // NOTE(Tantivy v0.12): A boolean query with only Should
+        // occurences matches if any of its sub-queries matches
+        if !query.any_hash_tags.is_empty() {
+            debug!("Query any of the hash tags: {:?}", query.any_hash_tags);
+            let any_tag_queries: Vec<(Occur, Box<dyn Query>)> = query
+                .any_hash_tags
+                .iter()
+                .map(|tag| {
+                    debug_assert!(!tag.trim().is_empty());
+                    let tag_term = Term::from_field_text(self.fields.tag, &tag.to_lowercase());
+                    let tag_query: Box<dyn Query> =
+                        Box::new(TermQuery::new(tag_term, IndexRecordOption::Basic));
+                    (Occur::Should, tag_query)
+                })
+                .collect();
+            sub_queries.push((Occur::Must, Box::new(BooleanQuery::from(any_tag_queries))));
+        }
+
+        // Organization that owns any of the tags
+        if let Some(org_id) = &query.org_id {
+            debug!("Query organization: {}", org_id);
+            let org_term = Term::from_field_text(self.fields.org, org_id);
+            let org_query = TermQuery::new(org_term, IndexRecordOption::Basic);
+            sub_queries.push((Occur::Must, Box::new(org_query)));
+        }
+
+        // Hash tags (excluded)
+        for tag in &query.exclude_hash_tags {
+            debug!("Query hash tag (excluded): {}", tag);
+            debug_assert!(!tag.trim().is_empty());
+            let tag_term = Term::from_field_text(self.fields.tag, &tag.to_lowercase());
+            let tag_query = TermQuery::new(tag_term, IndexRecordOption::Basic);
+            sub_queries.push((Occur::MustNot, Box::new(tag_query)));
+        }
+
         let mut text_and_tags_queries: Vec<(Occur, Box<dyn Query>)> =
             Vec::with_capacity(1 + query.text_tags.len());
 
+        // Text queries without any tags are required and not added
+        // to the optional text and tags queries
+        let mut required_text_query = false;
+
         // Text
         if let Some(text) = &query.text {
             debug!("Query text: {}", text);
             debug_assert!(!text.trim().is_empty());
             let text = text.to_lowercase();
-            match self.text_query_parser.parse_query(&text) {
+            let text_query = match query.fuzzy_distance {
+                Some(distance) if distance > 0 => Ok(self.fuzzy_text_query(&text, distance)),
+                _ => self.text_query_parser.parse_query(&text),
+            };
+            match text_query {
                 Ok(text_query) => {
                     if query.hash_tags.is_empty() && query.text_tags.is_empty() {
                         sub_queries.push((Occur::Must, Box::new(text_query)));
+                        required_text_query = true;
                     } else {
                         text_and_tags_queries.push((Occur::Should, Box::new(text_query)));
                     }
@@ -662,26 +1145,199 @@ impl TantivyIndex {
             sub_queries.push((Occur::Must, Box::new(ts_max_query)));
         }
 
+        // Time of the last change
+        let updated_since = query
+            .updated_since
+            .map(|x| Bound::Included(x.into_inner()))
+            .unwrap_or(Bound::Unbounded);
+        let updated_until = query
+            .updated_until
+            .map(|x| Bound::Excluded(x.into_inner()))
+            .unwrap_or(Bound::Unbounded);
+        if (updated_since, updated_until) != (Bound::Unbounded, Bound::Unbounded) {
+            let updated_query =
+                RangeQuery::new_i64_bounds(self.fields.updated_at, updated_since, updated_until);
+            sub_queries.push((Occur::Must, Box::new(updated_query)));
+        }
+
         // Boosting the score by the rating does only make sense if the
         // query actually contains search terms or tags. Otherwise the
         // results are sorted only by their rating, e.g. if the query
         // contains just the bounding box or ids.
-        if text_and_tags_queries.is_empty() {
-            let mode = match query_mode {
+        let relevance_mode = if text_and_tags_queries.is_empty() {
+            match query_mode {
+                IndexQueryMode::WithRating if required_text_query => {
+                    TopDocsMode::ScoreBoostedByRating
+                }
                 IndexQueryMode::WithRating => TopDocsMode::Rating,
                 IndexQueryMode::WithoutRating => TopDocsMode::Score,
-            };
-            (sub_queries.into(), mode)
+            }
         } else {
             sub_queries.push((
                 Occur::Must,
                 Box::new(BooleanQuery::from(text_and_tags_queries)),
             ));
-            let mode = match query_mode {
+            match query_mode {
                 IndexQueryMode::WithRating => TopDocsMode::ScoreBoostedByRating,
                 IndexQueryMode::WithoutRating => TopDocsMode::Score,
-            };
-            (sub_queries.into(), mode)
+            }
+        };
+        let mode = match query.sort_by {
+            IndexSortOrder::Relevance => relevance_mode,
+            IndexSortOrder::Rating => TopDocsMode::Rating,
+            IndexSortOrder::Distance => {
+                if let Some(center) = query
+                    .center
+                    .or_else(|| query.include_bbox.map(|bbox| bbox.center()))
+                {
+                    TopDocsMode::Distance(center)
+                } else {
+                    warn!("Cannot sort by distance without a center or bounding box");
+                    relevance_mode
+                }
+            }
+            IndexSortOrder::RecentlyChanged => TopDocsMode::RecentlyChanged,
+            IndexSortOrder::RecentlyCreated => TopDocsMode::RecentlyCreated,
+            IndexSortOrder::Title => TopDocsMode::Title,
+            IndexSortOrder::RatingsCount => TopDocsMode::RatingsCount,
+        };
+        // A boolean query without any sub-queries doesn't match
+        // any documents
+        if sub_queries.is_empty() {
+            sub_queries.push((Occur::Must, Box::new(AllQuery)));
+        }
+        (sub_queries.into(), mode)
+    }
+
+    // The creation time stamp of a previously indexed and
+    // committed document
+    fn indexed_created_at(&self, id: &str) -> Option<i64> {
+        let searcher = self.index_reader.searcher();
+        let id_term = Term::from_field_text(self.fields.id, id);
+        let id_query = TermQuery::new(id_term, IndexRecordOption::Basic);
+        let top_docs = searcher
+            .search(&id_query, &TopDocs::with_limit(1))
+            .map_err(|err| warn!("Failed to lookup indexed document {}: {}", id, err))
+            .ok()?;
+        let (_, doc_addr) = top_docs.into_iter().next()?;
+        let created_at_reader = searcher
+            .segment_reader(doc_addr.segment_ord())
+            .fast_fields()
+            .i64(self.fields.created_at)?;
+        Some(created_at_reader.get(doc_addr.doc()))
+    }
+
+    // The most frequent terms of the field that start with the
+    // prefix. The frequencies include deleted documents until
+    // the affected segments are merged.
+    fn prefix_terms(&self, field: Field, prefix: &str, max_count: usize) -> Vec<(String, u64)> {
+        let searcher = self.index_reader.searcher();
+        let mut term_freqs: HashMap<String, u64> = HashMap::new();
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(field);
+            let mut terms = inverted_index
+                .terms()
+                .range()
+                .ge(prefix.as_bytes())
+                .into_stream();
+            while terms.advance() {
+                if !terms.key().starts_with(prefix.as_bytes()) {
+                    break;
+                }
+                if let Ok(term) = std::str::from_utf8(terms.key()) {
+                    *term_freqs.entry(term.to_owned()).or_default() +=
+                        u64::from(terms.value().doc_freq);
+                }
+            }
+        }
+        let mut term_freqs: Vec<_> = term_freqs.into_iter().collect();
+        term_freqs.sort_unstable_by(|(lhs_term, lhs_freq), (rhs_term, rhs_freq)| {
+            rhs_freq.cmp(lhs_freq).then_with(|| lhs_term.cmp(rhs_term))
+        });
+        term_freqs.truncate(max_count);
+        term_freqs
+    }
+
+    // The number of matching documents for each term of the field.
+    // Unlike the document frequencies of the terms this excludes
+    // both deleted and non-matching documents.
+    fn matching_term_counts(
+        &self,
+        field: Field,
+        query: &dyn Query,
+    ) -> Fallible<HashMap<String, u64>> {
+        let searcher = self.index_reader.searcher();
+        let matching_docs = searcher
+            .search(query, &SegmentDocIdsCollector)
+            .map_err(Fail::compat)?;
+        let mut term_counts: HashMap<String, u64> = HashMap::new();
+        for (segment_local_id, doc_ids) in matching_docs {
+            if doc_ids.is_empty() {
+                continue;
+            }
+            let segment_reader = searcher.segment_reader(segment_local_id);
+            let mut matching = vec![false; segment_reader.max_doc() as usize];
+            for doc in doc_ids {
+                matching[doc as usize] = true;
+            }
+            let inverted_index = segment_reader.inverted_index(field);
+            let mut terms = inverted_index.terms().range().into_stream();
+            while terms.advance() {
+                let mut postings = inverted_index
+                    .read_postings_from_terminfo(terms.value(), IndexRecordOption::Basic);
+                let mut count = 0;
+                while postings.advance() {
+                    if matching[postings.doc() as usize] {
+                        count += 1;
+                    }
+                }
+                if count == 0 {
+                    continue;
+                }
+                if let Ok(term) = std::str::from_utf8(terms.key()) {
+                    *term_counts.entry(term.to_owned()).or_default() += count;
+                }
+            }
+        }
+        Ok(term_counts)
+    }
+
+    // Matches any of the words in any of the text fields while
+    // tolerating typos up to the given edit distance
+    fn fuzzy_text_query(&self, text: &str, distance: u8) -> Box<dyn Query> {
+        let distance = distance.min(MAX_FUZZY_DISTANCE);
+        let text_fields = self.fields.text_fields();
+        let mut word_queries: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        self.text_tokenizer
+            .token_stream(text)
+            .process(&mut |token| {
+                let distance = if token.text.chars().count() < MIN_FUZZY_WORD_LEN {
+                    0
+                } else {
+                    distance
+                };
+                for field in &text_fields {
+                    let term = Term::from_field_text(*field, &token.text);
+                    let word_query = FuzzyTermQuery::new(term, distance, true);
+                    word_queries.push((Occur::Should, Box::new(word_query)));
+                }
+            });
+        Box::new(BooleanQuery::from(word_queries))
+    }
+
+    fn location_filter<C: Collector>(
+        &self,
+        query: &IndexQuery,
+        collector: C,
+    ) -> LocationFilterCollector<C> {
+        LocationFilterCollector {
+            lat_field: self.fields.lat,
+            lng_field: self.fields.lng,
+            additional_lat_field: self.fields.additional_lat,
+            additional_lng_field: self.fields.additional_lng,
+            circle: query_circle(query),
+            bbox: query.include_bbox,
+            collector,
         }
     }
 
@@ -701,55 +1357,57 @@ impl TantivyIndex {
         }
 
         let (search_query, top_docs_mode) = self.build_query(query_mode, query);
+        // Results of previous pages are skipped after loading them.
+        // Continuing after a cursor requires to fetch additional
+        // results that might have been inserted in front of it.
+        let page_size = limit;
+        let limit = query.offset
+            + if query.after.is_some() {
+                2 * page_size
+            } else {
+                page_size
+            };
         let searcher = self.index_reader.searcher();
-        // TODO: Try to combine redundant code from different search strategies
-        match top_docs_mode {
+        doc_collector.prepare(&searcher, &search_query)?;
+        let doc_addrs = match top_docs_mode {
             TopDocsMode::Score => {
-                let collector = TopDocs::with_limit(limit);
+                let collector = self.location_filter(query, TopDocs::with_limit(limit));
                 let top_docs = searcher
                     .search(&search_query, &collector)
                     .map_err(Fail::compat)?;
-                for (_, doc_addr) in top_docs {
-                    match searcher.doc(doc_addr) {
-                        Ok(doc) => {
-                            doc_collector.collect_document(doc_addr, doc);
-                        }
-                        Err(err) => {
-                            warn!("Failed to load document {:?}: {}", doc_addr, err);
-                        }
-                    }
-                }
-                Ok(doc_collector)
+                top_doc_addrs(top_docs)
             }
             TopDocsMode::Rating => {
-                let collector =
-                    TopDocs::with_limit(limit).order_by_u64_field(self.fields.total_rating);
-                searcher
-                    .search(&search_query, &collector)
-                    .map_err(Fail::compat)?;
+                let collector = self.location_filter(
+                    query,
+                    TopDocs::with_limit(limit).order_by_u64_field(self.fields.total_rating),
+                );
                 let top_docs = searcher
                     .search(&search_query, &collector)
                     .map_err(Fail::compat)?;
-                for (_, doc_addr) in top_docs {
-                    match searcher.doc(doc_addr) {
-                        Ok(doc) => {
-                            doc_collector.collect_document(doc_addr, doc);
-                        }
-                        Err(err) => {
-                            warn!("Failed to load document {:?}: {}", doc_addr, err);
-                        }
-                    }
-                }
-                Ok(doc_collector)
+                top_doc_addrs(top_docs)
             }
             TopDocsMode::ScoreBoostedByRating => {
-                let collector = {
+                let collector = self.location_filter(query, {
                     let total_rating_field = self.fields.total_rating;
+                    let curated_boost_field = self.fields.curated_boost;
+                    let quality_field = self.fields.quality;
+                    let updated_at_field = self.fields.updated_at;
+                    let boost_quality = query.boost_quality;
+                    let now = TimestampMs::now();
                     TopDocs::with_limit(limit).tweak_score(move |segment_reader: &SegmentReader| {
                         let total_rating_reader = segment_reader
                             .fast_fields()
                             .u64(total_rating_field)
                             .unwrap();
+                        let curated_boost_reader = segment_reader
+                            .fast_fields()
+                            .f64(curated_boost_field)
+                            .unwrap();
+                        let quality_reader =
+                            segment_reader.fast_fields().f64(quality_field).unwrap();
+                        let updated_at_reader =
+                            segment_reader.fast_fields().i64(updated_at_field).unwrap();
 
                         move |doc: DocId, original_score: Score| {
                             let total_rating =
@@ -773,55 +1431,614 @@ impl TantivyIndex {
                             // rated entries over entries that received a much higher score.
                             debug_assert!(original_score >= 0.0);
                             let unboosted_score = (1.0 + original_score).log2();
-                            unboosted_score * (boost_factor as f32)
+                            // Places with curated tags are preferred independent of their rating
+                            let curated_boost = curated_boost_reader.get(doc);
+                            let curated_boost = if curated_boost > 0.0 {
+                                curated_boost
+                            } else {
+                                1.0
+                            };
+                            // Complete places are optionally preferred over incomplete places.
+                            // The last verification is checked now, because it expires over time.
+                            let quality_boost = if boost_quality {
+                                1.0 + quality::quality_score_at(
+                                    quality_reader.get(doc),
+                                    TimestampMs::from_inner(updated_at_reader.get(doc)),
+                                    now,
+                                )
+                            } else {
+                                1.0
+                            };
+                            unboosted_score
+                                * ((boost_factor * curated_boost * quality_boost) as f32)
                         }
                     })
-                };
+                });
                 let top_docs = searcher
                     .search(&search_query, &collector)
                     .map_err(Fail::compat)?;
-                for (_, doc_addr) in top_docs {
-                    match searcher.doc(doc_addr) {
-                        Ok(doc) => {
-                            doc_collector.collect_document(doc_addr, doc);
-                        }
-                        Err(err) => {
-                            warn!("Failed to load document {:?}: {}", doc_addr, err);
-                        }
-                    }
-                }
-                Ok(doc_collector)
+                top_doc_addrs(top_docs)
             }
-        }
-    }
-}
-
-trait DocumentCollector {
-    fn collect_document(&mut self, doc_addr: DocAddress, doc: Document);
-}
-
-struct IdCollector {
-    id_field: Field,
-    collected_ids: Vec<Id>,
-}
-
-impl IdCollector {
-    fn with_capacity(id_field: Field, capacity: usize) -> Self {
-        Self {
-            id_field,
-            collected_ids: Vec::with_capacity(capacity),
-        }
-    }
-}
-
-impl From<IdCollector> for Vec<Id> {
-    fn from(from: IdCollector) -> Self {
-        from.collected_ids
-    }
+            TopDocsMode::Distance(center) => {
+                let collector = self.location_filter(query, {
+                    let lat_field = self.fields.lat;
+                    let lng_field = self.fields.lng;
+                    TopDocs::with_limit(limit).custom_score(
+                        move |segment_reader: &SegmentReader| {
+                            let lat_reader = segment_reader.fast_fields().f64(lat_field).unwrap();
+                            let lng_reader = segment_reader.fast_fields().f64(lng_field).unwrap();
+
+                            move |doc: DocId| {
+                                // The nearest documents receive the highest score
+                                MapPoint::try_from_lat_lng_deg(
+                                    lat_reader.get(doc),
+                                    lng_reader.get(doc),
+                                )
+                                .and_then(|pos| MapPoint::distance(center, pos))
+                                .map(|distance| -distance.to_meters())
+                                .unwrap_or(f64::NEG_INFINITY)
+                            }
+                        },
+                    )
+                });
+                let top_docs = searcher
+                    .search(&search_query, &collector)
+                    .map_err(Fail::compat)?;
+                top_doc_addrs(top_docs)
+            }
+            TopDocsMode::RecentlyChanged => {
+                let collector = self.location_filter(query, {
+                    let updated_at_field = self.fields.updated_at;
+                    TopDocs::with_limit(limit).custom_score(
+                        move |segment_reader: &SegmentReader| {
+                            let updated_at_reader =
+                                segment_reader.fast_fields().i64(updated_at_field).unwrap();
+                            move |doc: DocId| updated_at_reader.get(doc)
+                        },
+                    )
+                });
+                let top_docs = searcher
+                    .search(&search_query, &collector)
+                    .map_err(Fail::compat)?;
+                top_doc_addrs(top_docs)
+            }
+            TopDocsMode::RecentlyCreated => {
+                let collector = self.location_filter(query, {
+                    let created_at_field = self.fields.created_at;
+                    TopDocs::with_limit(limit).custom_score(
+                        move |segment_reader: &SegmentReader| {
+                            let created_at_reader =
+                                segment_reader.fast_fields().i64(created_at_field).unwrap();
+                            move |doc: DocId| created_at_reader.get(doc)
+                        },
+                    )
+                });
+                let top_docs = searcher
+                    .search(&search_query, &collector)
+                    .map_err(Fail::compat)?;
+                top_doc_addrs(top_docs)
+            }
+            TopDocsMode::Title => {
+                let collector = self.location_filter(query, {
+                    let title_key_field = self.fields.title_key;
+                    TopDocs::with_limit(limit).custom_score(
+                        move |segment_reader: &SegmentReader| {
+                            let title_key_reader =
+                                segment_reader.fast_fields().u64(title_key_field).unwrap();
+                            // Ascending order
+                            move |doc: DocId| Reverse(title_key_reader.get(doc))
+                        },
+                    )
+                });
+                let top_docs = searcher
+                    .search(&search_query, &collector)
+                    .map_err(Fail::compat)?;
+                top_doc_addrs(top_docs)
+            }
+            TopDocsMode::RatingsCount => {
+                let collector = self.location_filter(
+                    query,
+                    TopDocs::with_limit(limit).order_by_u64_field(self.fields.ratings_count),
+                );
+                let top_docs = searcher
+                    .search(&search_query, &collector)
+                    .map_err(Fail::compat)?;
+                top_doc_addrs(top_docs)
+            }
+        };
+        let mut docs = Vec::with_capacity(doc_addrs.len());
+        for (score, doc_addr) in doc_addrs {
+            match searcher.doc(doc_addr) {
+                Ok(doc) => {
+                    docs.push((score, doc_addr, doc));
+                }
+                Err(err) => {
+                    warn!("Failed to load document {:?}: {}", doc_addr, err);
+                }
+            }
+        }
+        if let TopDocsMode::Title = top_docs_mode {
+            // The sort key only covers a prefix of the title. Places
+            // with equal titles are ordered by their id to keep the
+            // order stable across pages.
+            let title_field = self.fields.title;
+            let id_field = self.fields.id;
+            docs.sort_by_cached_key(|(_, _, doc)| {
+                let title = doc
+                    .get_first(title_field)
+                    .and_then(Value::text)
+                    .map(|title| title.trim().to_lowercase())
+                    .unwrap_or_default();
+                let id = doc
+                    .get_first(id_field)
+                    .and_then(Value::text)
+                    .map(ToOwned::to_owned)
+                    .unwrap_or_default();
+                (title, id)
+            });
+        }
+        let skip_count = if let Some(cursor) = &query.after {
+            let id_field = self.fields.id;
+            docs.iter()
+                .position(|(_, _, doc)| {
+                    doc.get_first(id_field).and_then(Value::text) == Some(cursor.id.as_str())
+                })
+                .map(|pos| pos + 1)
+                // The last result of the previous page has been removed
+                // or changed: Continue with the next lower score instead
+                .or_else(|| docs.iter().position(|(score, _, _)| *score < cursor.score))
+                .unwrap_or_else(|| docs.len())
+        } else {
+            query.offset
+        };
+        for (score, doc_addr, doc) in docs.into_iter().skip(skip_count).take(page_size) {
+            doc_collector.collect_document(doc_addr, score, doc);
+        }
+        Ok(doc_collector)
+    }
+}
+
+// Converts the different kinds of sort keys into a common
+// score, i.e. higher scores precede lower scores
+trait IntoSortScore {
+    fn into_sort_score(self) -> f64;
+}
+
+impl IntoSortScore for Score {
+    fn into_sort_score(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+impl IntoSortScore for f64 {
+    fn into_sort_score(self) -> f64 {
+        self
+    }
+}
+
+impl IntoSortScore for u64 {
+    fn into_sort_score(self) -> f64 {
+        self as f64
+    }
+}
+
+impl IntoSortScore for i64 {
+    fn into_sort_score(self) -> f64 {
+        self as f64
+    }
+}
+
+impl IntoSortScore for Reverse<u64> {
+    fn into_sort_score(self) -> f64 {
+        -(self.0 as f64)
+    }
+}
+
+fn top_doc_addrs<S: IntoSortScore>(top_docs: Vec<(S, DocAddress)>) -> Vec<(f64, DocAddress)> {
+    top_docs
+        .into_iter()
+        .map(|(score, doc_addr)| (score.into_sort_score(), doc_addr))
+        .collect()
+}
+
+// The inclusive longitude ranges of a bbox in degrees that
+// wraps around at the antimeridian if west of east
+fn lng_ranges(bbox: &MapBbox) -> Vec<(f64, f64)> {
+    let west = bbox.south_west().lng().to_deg();
+    let east = bbox.north_east().lng().to_deg();
+    if west <= east {
+        vec![(west, east)]
+    } else {
+        vec![
+            (west, LngCoord::max().to_deg()),
+            (LngCoord::min().to_deg(), east),
+        ]
+    }
+}
+
+// Matches if some value of the latitude field and some value of the
+// longitude field are inside the bbox. For multi-valued fields these
+// values don't need to belong to the same position!
+fn bbox_query(lat_field: Field, lng_field: Field, bbox: &MapBbox) -> BooleanQuery {
+    let lat_query = RangeQuery::new_f64_bounds(
+        lat_field,
+        Bound::Included(bbox.south_west().lat().to_deg()),
+        Bound::Included(bbox.north_east().lat().to_deg()),
+    );
+    let lng_queries: Vec<(Occur, Box<dyn Query>)> = lng_ranges(bbox)
+        .into_iter()
+        .map(|(min_lng, max_lng)| {
+            let lng_query = RangeQuery::new_f64_bounds(
+                lng_field,
+                Bound::Included(min_lng),
+                Bound::Included(max_lng),
+            );
+            (Occur::Should, Box::new(lng_query) as Box<dyn Query>)
+        })
+        .collect();
+    BooleanQuery::from(vec![
+        (Occur::Must, Box::new(lat_query) as Box<dyn Query>),
+        (Occur::Must, Box::new(BooleanQuery::from(lng_queries))),
+    ])
+}
+
+// Uses the same bounds as bbox_query()
+fn bbox_contains_deg(bbox: &MapBbox, lat: f64, lng: f64) -> bool {
+    lat >= bbox.south_west().lat().to_deg()
+        && lat <= bbox.north_east().lat().to_deg()
+        && lng_ranges(bbox)
+            .into_iter()
+            .any(|(min_lng, max_lng)| lng >= min_lng && lng <= max_lng)
+}
+
+fn query_circle(query: &IndexQuery) -> Option<(MapPoint, Distance)> {
+    match (query.center, query.radius_meters) {
+        (Some(center), Some(radius_meters)) => {
+            debug_assert!(center.is_valid());
+            debug_assert!(radius_meters >= 0.0);
+            Some((center, Distance::from_meters(radius_meters)))
+        }
+        _ => None,
+    }
+}
+
+// Only collects documents within the given circle (if any) and
+// with at least one location within the given bbox (if any)
+struct LocationFilterCollector<C> {
+    lat_field: Field,
+    lng_field: Field,
+    additional_lat_field: Field,
+    additional_lng_field: Field,
+    circle: Option<(MapPoint, Distance)>,
+    bbox: Option<MapBbox>,
+    collector: C,
+}
+
+impl<C: Collector> Collector for LocationFilterCollector<C> {
+    type Fruit = C::Fruit;
+    type Child = LocationFilterSegmentCollector<C::Child>;
+
+    fn for_segment(
+        &self,
+        segment_local_id: SegmentLocalId,
+        segment_reader: &SegmentReader,
+    ) -> tantivy::Result<Self::Child> {
+        let fast_fields = segment_reader.fast_fields();
+        let lat_reader = || {
+            fast_fields
+                .f64(self.lat_field)
+                .ok_or_else(|| TantivyError::SchemaError("Missing fast field for latitude".into()))
+        };
+        let lng_reader = || {
+            fast_fields
+                .f64(self.lng_field)
+                .ok_or_else(|| TantivyError::SchemaError("Missing fast field for longitude".into()))
+        };
+        let distance_filter = if let Some((center, radius)) = self.circle {
+            Some(DistanceFilter {
+                lat_reader: lat_reader()?,
+                lng_reader: lng_reader()?,
+                center,
+                radius,
+            })
+        } else {
+            None
+        };
+        let bbox_filter = if let Some(bbox) = self.bbox {
+            Some(BboxFilter {
+                lat_reader: lat_reader()?,
+                lng_reader: lng_reader()?,
+                store_reader: segment_reader.get_store_reader(),
+                additional_lat_field: self.additional_lat_field,
+                additional_lng_field: self.additional_lng_field,
+                bbox,
+            })
+        } else {
+            None
+        };
+        let collector = self
+            .collector
+            .for_segment(segment_local_id, segment_reader)?;
+        Ok(LocationFilterSegmentCollector {
+            distance_filter,
+            bbox_filter,
+            collector,
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        self.collector.requires_scoring()
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<<Self::Child as SegmentCollector>::Fruit>,
+    ) -> tantivy::Result<Self::Fruit> {
+        self.collector.merge_fruits(segment_fruits)
+    }
+}
+
+struct DistanceFilter {
+    lat_reader: FastFieldReader<f64>,
+    lng_reader: FastFieldReader<f64>,
+    center: MapPoint,
+    radius: Distance,
+}
+
+impl DistanceFilter {
+    fn contains(&self, doc: DocId) -> bool {
+        MapPoint::try_from_lat_lng_deg(self.lat_reader.get(doc), self.lng_reader.get(doc))
+            .and_then(|pos| MapPoint::distance(self.center, pos))
+            .map(|distance| distance <= self.radius)
+            .unwrap_or(false)
+    }
+}
+
+struct BboxFilter {
+    lat_reader: FastFieldReader<f64>,
+    lng_reader: FastFieldReader<f64>,
+    store_reader: StoreReader,
+    additional_lat_field: Field,
+    additional_lng_field: Field,
+    bbox: MapBbox,
+}
+
+impl BboxFilter {
+    fn contains(&self, doc: DocId) -> bool {
+        if bbox_contains_deg(
+            &self.bbox,
+            self.lat_reader.get(doc),
+            self.lng_reader.get(doc),
+        ) {
+            return true;
+        }
+        // Otherwise the document has been matched by its additional
+        // locations. Latitudes and longitudes are indexed separately
+        // and need to be verified pairwise.
+        let doc = match self.store_reader.get(doc) {
+            Ok(doc) => doc,
+            Err(err) => {
+                warn!("Failed to load document {}: {}", doc, err);
+                return false;
+            }
+        };
+        let mut lats = Vec::new();
+        let mut lngs = Vec::new();
+        for field_value in doc.field_values() {
+            if field_value.field() == self.additional_lat_field {
+                lats.push(field_value.value().f64_value());
+            } else if field_value.field() == self.additional_lng_field {
+                lngs.push(field_value.value().f64_value());
+            }
+        }
+        lats.into_iter()
+            .zip(lngs)
+            .any(|(lat, lng)| bbox_contains_deg(&self.bbox, lat, lng))
+    }
+}
+
+struct LocationFilterSegmentCollector<C> {
+    distance_filter: Option<DistanceFilter>,
+    bbox_filter: Option<BboxFilter>,
+    collector: C,
+}
+
+impl<C: SegmentCollector> SegmentCollector for LocationFilterSegmentCollector<C> {
+    type Fruit = C::Fruit;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        if let Some(ref filter) = self.distance_filter {
+            if !filter.contains(doc) {
+                return;
+            }
+        }
+        if let Some(ref filter) = self.bbox_filter {
+            if !filter.contains(doc) {
+                return;
+            }
+        }
+        self.collector.collect(doc, score);
+    }
+
+    fn harvest(self) -> C::Fruit {
+        self.collector.harvest()
+    }
+}
+
+#[derive(Debug, Default)]
+struct GridCell {
+    count: usize,
+    lat_sum: f64,
+    lng_sum: f64,
+}
+
+impl GridCell {
+    fn merge(&mut self, other: GridCell) {
+        self.count += other.count;
+        self.lat_sum += other.lat_sum;
+        self.lng_sum += other.lng_sum;
+    }
+}
+
+// Grid cells indexed by (row, column)
+type GridCells = HashMap<(i64, i64), GridCell>;
+
+// Aggregates documents into the cells of a grid by their
+// position that is read from the fast fields, i.e. without
+// loading the stored documents
+struct GridClusterCollector {
+    lat_field: Field,
+    lng_field: Field,
+    cell_size_deg: f64,
+}
+
+impl Collector for GridClusterCollector {
+    type Fruit = GridCells;
+    type Child = GridClusterSegmentCollector;
+
+    fn for_segment(
+        &self,
+        _segment_local_id: SegmentLocalId,
+        segment_reader: &SegmentReader,
+    ) -> tantivy::Result<Self::Child> {
+        let fast_fields = segment_reader.fast_fields();
+        let lat_reader = fast_fields
+            .f64(self.lat_field)
+            .ok_or_else(|| TantivyError::SchemaError("Missing fast field for latitude".into()))?;
+        let lng_reader = fast_fields
+            .f64(self.lng_field)
+            .ok_or_else(|| TantivyError::SchemaError("Missing fast field for longitude".into()))?;
+        Ok(GridClusterSegmentCollector {
+            lat_reader,
+            lng_reader,
+            cell_size_deg: self.cell_size_deg,
+            cells: Default::default(),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(&self, segment_fruits: Vec<GridCells>) -> tantivy::Result<GridCells> {
+        let mut cells = GridCells::new();
+        for segment_cells in segment_fruits {
+            for (key, cell) in segment_cells {
+                cells.entry(key).or_default().merge(cell);
+            }
+        }
+        Ok(cells)
+    }
+}
+
+struct GridClusterSegmentCollector {
+    lat_reader: FastFieldReader<f64>,
+    lng_reader: FastFieldReader<f64>,
+    cell_size_deg: f64,
+    cells: GridCells,
+}
+
+impl SegmentCollector for GridClusterSegmentCollector {
+    type Fruit = GridCells;
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        let lat = self.lat_reader.get(doc);
+        let lng = self.lng_reader.get(doc);
+        let key = (
+            (lat / self.cell_size_deg).floor() as i64,
+            (lng / self.cell_size_deg).floor() as i64,
+        );
+        self.cells.entry(key).or_default().merge(GridCell {
+            count: 1,
+            lat_sum: lat,
+            lng_sum: lng,
+        });
+    }
+
+    fn harvest(self) -> GridCells {
+        self.cells
+    }
+}
+
+// Collects the ids of all matching documents per segment
+struct SegmentDocIdsCollector;
+
+impl Collector for SegmentDocIdsCollector {
+    type Fruit = Vec<(SegmentLocalId, Vec<DocId>)>;
+    type Child = SegmentDocIdsSegmentCollector;
+
+    fn for_segment(
+        &self,
+        segment_local_id: SegmentLocalId,
+        _segment_reader: &SegmentReader,
+    ) -> tantivy::Result<Self::Child> {
+        Ok(SegmentDocIdsSegmentCollector {
+            segment_local_id,
+            doc_ids: Vec::new(),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<Vec<(SegmentLocalId, Vec<DocId>)>>,
+    ) -> tantivy::Result<Self::Fruit> {
+        Ok(segment_fruits.into_iter().flatten().collect())
+    }
+}
+
+struct SegmentDocIdsSegmentCollector {
+    segment_local_id: SegmentLocalId,
+    doc_ids: Vec<DocId>,
+}
+
+impl SegmentCollector for SegmentDocIdsSegmentCollector {
+    // The fruits of all segments are concatenated
+    type Fruit = Vec<(SegmentLocalId, Vec<DocId>)>;
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        self.doc_ids.push(doc);
+    }
+
+    fn harvest(self) -> Self::Fruit {
+        vec![(self.segment_local_id, self.doc_ids)]
+    }
+}
+
+trait DocumentCollector {
+    // Invoked once before collecting any documents
+    fn prepare(&mut self, _searcher: &Searcher, _query: &dyn Query) -> Fallible<()> {
+        Ok(())
+    }
+
+    fn collect_document(&mut self, doc_addr: DocAddress, score: f64, doc: Document);
+}
+
+struct IdCollector {
+    id_field: Field,
+    collected_ids: Vec<Id>,
+}
+
+impl IdCollector {
+    fn with_capacity(id_field: Field, capacity: usize) -> Self {
+        Self {
+            id_field,
+            collected_ids: Vec::with_capacity(capacity),
+        }
+    }
+}
+
+impl From<IdCollector> for Vec<Id> {
+    fn from(from: IdCollector) -> Self {
+        from.collected_ids
+    }
 }
 
 impl DocumentCollector for IdCollector {
-    fn collect_document(&mut self, doc_addr: DocAddress, doc: Document) {
+    fn collect_document(&mut self, doc_addr: DocAddress, _score: f64, doc: Document) {
         if let Some(id) = doc.get_first(self.id_field).and_then(Value::text) {
             self.collected_ids.push(Id::from(id));
         } else {
@@ -836,6 +2053,9 @@ impl DocumentCollector for IdCollector {
 struct IndexedPlaceCollector<'a> {
     fields: &'a IndexedFields,
     collected_places: Vec<IndexedPlace>,
+    with_snippets: bool,
+    // Snippet generators for (title, description)
+    snippet_generators: Option<(SnippetGenerator, SnippetGenerator)>,
 }
 
 impl<'a> IndexedPlaceCollector<'a> {
@@ -843,8 +2063,43 @@ impl<'a> IndexedPlaceCollector<'a> {
         Self {
             fields,
             collected_places: Vec::with_capacity(capacity),
+            with_snippets: false,
+            snippet_generators: None,
         }
     }
+
+    fn with_snippets(mut self) -> Self {
+        self.with_snippets = true;
+        self
+    }
+}
+
+// The maximum length of a snippet fragment
+const SNIPPET_MAX_NUM_CHARS: usize = 150;
+
+fn text_snippet(snippet: Snippet, text: &str) -> Option<TextSnippet> {
+    if snippet.highlighted().is_empty() {
+        return None;
+    }
+    // Fragments start and end with a token. Texts that fit into a
+    // single fragment are returned completely, including leading
+    // or trailing punctuation.
+    let (fragment, offset) = match text.find(snippet.fragments()) {
+        Some(offset) if text.len() <= SNIPPET_MAX_NUM_CHARS => (text, offset),
+        _ => (snippet.fragments(), 0),
+    };
+    let highlighted = snippet
+        .highlighted()
+        .iter()
+        .map(|section| {
+            let (start, end) = section.bounds();
+            offset + start..offset + end
+        })
+        .collect();
+    Some(TextSnippet {
+        fragment: fragment.to_owned(),
+        highlighted,
+    })
 }
 
 impl<'a> From<IndexedPlaceCollector<'a>> for Vec<IndexedPlace> {
@@ -854,13 +2109,33 @@ impl<'a> From<IndexedPlaceCollector<'a>> for Vec<IndexedPlace> {
 }
 
 impl<'a> DocumentCollector for IndexedPlaceCollector<'a> {
-    fn collect_document(&mut self, _doc_addr: DocAddress, doc: Document) {
-        self.collected_places
-            .push(self.fields.read_indexed_place(&doc));
+    fn prepare(&mut self, searcher: &Searcher, query: &dyn Query) -> Fallible<()> {
+        if self.with_snippets {
+            let mut title = SnippetGenerator::create(searcher, query, self.fields.title)
+                .map_err(Fail::compat)?;
+            title.set_max_num_chars(SNIPPET_MAX_NUM_CHARS);
+            let mut description =
+                SnippetGenerator::create(searcher, query, self.fields.description)
+                    .map_err(Fail::compat)?;
+            description.set_max_num_chars(SNIPPET_MAX_NUM_CHARS);
+            self.snippet_generators = Some((title, description));
+        }
+        Ok(())
+    }
+
+    fn collect_document(&mut self, _doc_addr: DocAddress, score: f64, doc: Document) {
+        let mut place = self.fields.read_indexed_place(&doc);
+        place.score = score;
+        if let Some((title, description)) = &self.snippet_generators {
+            place.title_snippet = text_snippet(title.snippet_from_doc(&doc), &place.title);
+            place.description_snippet =
+                text_snippet(description.snippet_from_doc(&doc), &place.description);
+        }
+        self.collected_places.push(place);
     }
 }
 
-impl IdIndex for TantivyIndex {
+impl IdIndex for TantivyIndexReader {
     fn query_ids(
         &self,
         query_mode: IndexQueryMode,
@@ -873,19 +2148,30 @@ impl IdIndex for TantivyIndex {
     }
 }
 
+impl IdIndex for TantivyIndex {
+    fn query_ids(
+        &self,
+        query_mode: IndexQueryMode,
+        query: &IndexQuery,
+        limit: usize,
+    ) -> Fallible<Vec<Id>> {
+        self.reader.query_ids(query_mode, query, limit)
+    }
+}
+
 impl Indexer for TantivyIndex {
     fn flush_index(&mut self) -> Fallible<()> {
         self.index_writer.commit().map_err(Fail::compat)?;
         // Manually reload the reader to ensure that all committed changes
         // become visible immediately.
-        self.index_reader.reload().map_err(Fail::compat)?;
+        self.reader.index_reader.reload().map_err(Fail::compat)?;
         Ok(())
     }
 }
 
 impl IdIndexer for TantivyIndex {
     fn remove_by_id(&self, id: &Id) -> Fallible<()> {
-        let id_term = Term::from_field_text(self.fields.id, id.as_str());
+        let id_term = Term::from_field_text(self.reader.fields.id, id.as_str());
         self.index_writer.delete_term(id_term);
         Ok(())
     }
@@ -898,150 +2184,489 @@ impl PlaceIndexer for TantivyIndex {
         status: ReviewStatus,
         ratings: &AvgRatings,
     ) -> Fallible<()> {
-        let id_term = Term::from_field_text(self.fields.id, place.id.as_ref());
+        let fields = &self.reader.fields;
+        let updated_at = place.created.at.into_inner();
+        // The creation time of the initial revision is preserved
+        // when updating a place
+        let created_at = self
+            .reader
+            .indexed_created_at(place.id.as_ref())
+            .unwrap_or(updated_at);
+        let id_term = Term::from_field_text(fields.id, place.id.as_ref());
         self.index_writer.delete_term(id_term);
         let mut doc = Document::default();
-        doc.add_i64(self.fields.kind, PLACE_KIND_FLAG);
+        doc.add_i64(fields.kind, PLACE_KIND_FLAG);
         if let Some(status) = status.to_i64() {
-            doc.add_i64(self.fields.status, status);
+            doc.add_i64(fields.status, status);
         }
-        doc.add_text(self.fields.id, place.id.as_ref());
-        doc.add_f64(self.fields.lat, place.location.pos.lat().to_deg());
-        doc.add_f64(self.fields.lng, place.location.pos.lng().to_deg());
-        doc.add_text(self.fields.title, &place.title);
-        doc.add_text(self.fields.description, &place.description);
+        doc.add_text(fields.id, place.id.as_ref());
+        doc.add_f64(fields.lat, place.location.pos.lat().to_deg());
+        doc.add_f64(fields.lng, place.location.pos.lng().to_deg());
+        doc.add_text(fields.title, &place.title);
+        doc.add_text(fields.title_prefix, &place.title);
+        doc.add_u64(fields.title_key, title_sort_key(&place.title));
+        doc.add_i64(fields.created_at, created_at);
+        doc.add_i64(fields.updated_at, updated_at);
+        doc.add_text(fields.description, &place.description);
         if let Some(address) = &place.location.address {
-            let Address {
-                street,
-                city,
-                zip,
-                country,
-                state,
-            } = address;
-            if let Some(street) = street {
-                doc.add_text(self.fields.address_street, street);
-            }
-            if let Some(city) = city {
-                doc.add_text(self.fields.address_city, city);
-            }
-            if let Some(zip) = zip {
-                doc.add_text(self.fields.address_zip, zip);
-            }
-            if let Some(country) = country {
-                doc.add_text(self.fields.address_country, country);
-            }
-            if let Some(state) = state {
-                doc.add_text(self.fields.address_country, state);
+            fields.add_address(&mut doc, address);
+        }
+        for location in &place.additional_locations {
+            doc.add_f64(fields.additional_lat, location.pos.lat().to_deg());
+            doc.add_f64(fields.additional_lng, location.pos.lng().to_deg());
+            if let Some(address) = &location.address {
+                fields.add_address(&mut doc, address);
             }
         }
         for tag in &place.tags {
-            doc.add_text(self.fields.tag, tag);
-        }
-        doc.add_u64(self.fields.total_rating, avg_rating_to_u64(ratings.total()));
-        doc.add_f64(self.fields.ratings_diversity, ratings.diversity.into());
-        doc.add_f64(self.fields.ratings_fairness, ratings.fairness.into());
-        doc.add_f64(self.fields.ratings_humanity, ratings.humanity.into());
-        doc.add_f64(self.fields.ratings_renewable, ratings.renewable.into());
-        doc.add_f64(self.fields.ratings_solidarity, ratings.solidarity.into());
-        doc.add_f64(
-            self.fields.ratings_transparency,
-            ratings.transparency.into(),
-        );
+            doc.add_text(fields.tag, tag);
+        }
+        for org_id in self.owner_org_ids(&place.tags) {
+            doc.add_text(fields.org, org_id);
+        }
+        doc.add_f64(fields.curated_boost, self.curated_boost(&place.tags));
+        doc.add_f64(fields.quality, quality::completeness_score(place));
+        doc.add_u64(fields.total_rating, avg_rating_to_u64(ratings.total()));
+        doc.add_u64(fields.ratings_count, ratings.count);
+        doc.add_f64(fields.ratings_diversity, ratings.diversity.into());
+        doc.add_f64(fields.ratings_fairness, ratings.fairness.into());
+        doc.add_f64(fields.ratings_humanity, ratings.humanity.into());
+        doc.add_f64(fields.ratings_renewable, ratings.renewable.into());
+        doc.add_f64(fields.ratings_solidarity, ratings.solidarity.into());
+        doc.add_f64(fields.ratings_transparency, ratings.transparency.into());
         self.index_writer.add_document(doc);
         Ok(())
     }
+
+    fn set_curated_tags(&mut self, curated_tags: &[CuratedTag]) {
+        self.curated_tags = curated_tags
+            .iter()
+            .map(|t| (t.tag.clone(), t.boost))
+            .collect();
+    }
+
+    fn set_tag_ownerships(&mut self, tag_ownerships: &[TagOwnership]) {
+        let mut tag_owners: HashMap<String, Vec<String>> = HashMap::new();
+        for t in tag_ownerships {
+            tag_owners
+                .entry(t.tag.clone())
+                .or_default()
+                .push(t.org_id.clone());
+        }
+        self.tag_owners = tag_owners;
+    }
+
+    fn set_categories(&mut self, categories: &[Category]) {
+        self.reader.set_categories(categories);
+    }
+
+    fn optimize_index(&mut self) -> Fallible<()> {
+        self.flush_index()?;
+        let segment_ids = self.reader.segment_ids();
+        if segment_ids.len() < 2 {
+            return Ok(());
+        }
+        info!("Merging {} segments of the search index", segment_ids.len());
+        let merged = self.index_writer.merge(&segment_ids);
+        block_on(merged).map_err(Fail::compat)?;
+        self.reader.index_reader.reload().map_err(Fail::compat)?;
+        Ok(())
+    }
 }
 
 impl EventIndexer for TantivyIndex {
     fn add_or_update_event(&self, event: &Event) -> Fallible<()> {
-        let id_term = Term::from_field_text(self.fields.id, event.id.as_ref());
+        let fields = &self.reader.fields;
+        let id_term = Term::from_field_text(fields.id, event.id.as_ref());
         self.index_writer.delete_term(id_term);
         let mut doc = Document::default();
-        doc.add_i64(self.fields.kind, EVENT_KIND_FLAG);
-        doc.add_text(self.fields.id, event.id.as_ref());
+        doc.add_i64(fields.kind, EVENT_KIND_FLAG);
+        doc.add_text(fields.id, event.id.as_ref());
         if let Some(ref location) = event.location {
-            doc.add_f64(self.fields.lat, location.pos.lat().to_deg());
-            doc.add_f64(self.fields.lng, location.pos.lng().to_deg());
+            doc.add_f64(fields.lat, location.pos.lat().to_deg());
+            doc.add_f64(fields.lng, location.pos.lng().to_deg());
             if let Some(address) = &location.address {
-                let Address {
-                    street,
-                    city,
-                    zip,
-                    country,
-                    state,
-                } = address;
-                if let Some(street) = street {
-                    doc.add_text(self.fields.address_street, street);
-                }
-                if let Some(city) = city {
-                    doc.add_text(self.fields.address_city, city);
-                }
-                if let Some(zip) = zip {
-                    doc.add_text(self.fields.address_zip, zip);
-                }
-                if let Some(country) = country {
-                    doc.add_text(self.fields.address_country, country);
-                }
-                if let Some(state) = state {
-                    doc.add_text(self.fields.address_country, state);
-                }
+                fields.add_address(&mut doc, address);
             }
         }
-        doc.add_i64(
-            self.fields.ts_min,
-            Timestamp::from(event.start).into_inner(),
-        );
-        if let Some(end) = event.end {
+        doc.add_i64(fields.ts_min, Timestamp::from(event.start).into_inner());
+        if let Some(ref recurrence) = event.recurrence {
+            // The end of the last occurrence
+            let ts_max = if recurrence.until.is_some() {
+                let last_start = recurrence.starts(event.start).last().unwrap_or(event.start);
+                let last_end = event.end.map(|end| last_start + (end - event.start));
+                Timestamp::from(last_end.unwrap_or(last_start)).into_inner()
+            } else {
+                i64::MAX
+            };
+            doc.add_i64(fields.ts_max, ts_max);
+        } else if let Some(end) = event.end {
             debug_assert!(event.start <= end);
-            doc.add_i64(self.fields.ts_max, Timestamp::from(end).into_inner());
+            doc.add_i64(fields.ts_max, Timestamp::from(end).into_inner());
         }
-        doc.add_text(self.fields.title, &event.title);
+        doc.add_text(fields.title, &event.title);
+        doc.add_u64(fields.title_key, title_sort_key(&event.title));
         if let Some(ref description) = event.description {
-            doc.add_text(self.fields.description, description);
+            doc.add_text(fields.description, description);
         }
         if let Some(ref organizer) = event.organizer {
-            doc.add_text(self.fields.organizer, organizer);
+            doc.add_text(fields.organizer, organizer);
         }
         for tag in &event.tags {
-            doc.add_text(self.fields.tag, tag);
+            doc.add_text(fields.tag, tag);
         }
         self.index_writer.add_document(doc);
         Ok(())
     }
 }
 
-impl PlaceIndex for TantivyIndex {
+impl PlaceIndex for TantivyIndexReader {
     fn query_places(&self, query: &IndexQuery, limit: usize) -> Fallible<Vec<IndexedPlace>> {
-        let collector = IndexedPlaceCollector::with_capacity(&self.fields, limit);
+        let mut collector = IndexedPlaceCollector::with_capacity(&self.fields, limit);
+        if query.text.is_some() {
+            collector = collector.with_snippets();
+        }
         self.query_documents(IndexQueryMode::WithRating, query, limit, collector)
             .map(Into::into)
     }
 }
 
+impl TagIndex for TantivyIndexReader {
+    fn suggest_tags(&self, prefix: &str, limit: usize) -> Fallible<Vec<TagFrequency>> {
+        let prefix = prefix.trim().to_lowercase();
+        if prefix.is_empty() {
+            return Ok(vec![]);
+        }
+        Ok(self
+            .prefix_terms(self.fields.tag, &prefix, limit)
+            .into_iter()
+            .map(|(tag, count)| TagFrequency(tag, count))
+            .collect())
+    }
+
+    fn most_popular_tags(
+        &self,
+        params: &MostPopularTagsParams,
+        pagination: &Pagination,
+    ) -> Fallible<Vec<TagFrequency>> {
+        // Only visible places, i.e. neither archived places
+        // nor events that don't have a review status
+        let query = IndexQuery {
+            status: Some(vec![]),
+            ..Default::default()
+        };
+        let (search_query, _) = self.build_query(IndexQueryMode::WithRating, &query);
+        let mut tag_counts: Vec<_> = self
+            .matching_term_counts(self.fields.tag, &search_query)?
+            .into_iter()
+            .filter(|(_, count)| params.min_count.map(|min| *count >= min).unwrap_or(true))
+            .filter(|(_, count)| params.max_count.map(|max| *count <= max).unwrap_or(true))
+            .collect();
+        tag_counts.sort_unstable_by(|(lhs_tag, lhs_count), (rhs_tag, rhs_count)| {
+            rhs_count.cmp(lhs_count).then_with(|| lhs_tag.cmp(rhs_tag))
+        });
+        let offset = pagination.offset.unwrap_or(0) as usize;
+        let limit = pagination.limit.map(|limit| limit as usize);
+        Ok(tag_counts
+            .into_iter()
+            .skip(offset)
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|(tag, count)| TagFrequency(tag, count))
+            .collect())
+    }
+}
+
+impl PlaceClusterIndex for TantivyIndexReader {
+    fn cluster_places(
+        &self,
+        query: &IndexQuery,
+        cell_size_deg: f64,
+    ) -> Fallible<Vec<PlaceCluster>> {
+        if !cell_size_deg.is_finite() || cell_size_deg <= 0.0 {
+            bail!("Invalid cell size: {}", cell_size_deg);
+        }
+        let (search_query, _) = self.build_query(IndexQueryMode::WithRating, query);
+        let collector = self.location_filter(
+            query,
+            GridClusterCollector {
+                lat_field: self.fields.lat,
+                lng_field: self.fields.lng,
+                cell_size_deg,
+            },
+        );
+        let cells = self
+            .index_reader
+            .searcher()
+            .search(&search_query, &collector)
+            .map_err(Fail::compat)?;
+        let mut cells: Vec<_> = cells.into_iter().collect();
+        // Largest clusters first in a deterministic order
+        cells.sort_unstable_by(|(lhs_key, lhs_cell), (rhs_key, rhs_cell)| {
+            rhs_cell
+                .count
+                .cmp(&lhs_cell.count)
+                .then_with(|| lhs_key.cmp(rhs_key))
+        });
+        Ok(cells
+            .into_iter()
+            .map(|(_, cell)| {
+                let count = cell.count as f64;
+                PlaceCluster {
+                    centroid: MapPoint::from_lat_lng_deg(
+                        cell.lat_sum / count,
+                        cell.lng_sum / count,
+                    ),
+                    count: cell.count,
+                }
+            })
+            .collect())
+    }
+}
+
+impl PlaceIndex for TantivyIndex {
+    fn query_places(&self, query: &IndexQuery, limit: usize) -> Fallible<Vec<IndexedPlace>> {
+        self.reader.query_places(query, limit)
+    }
+}
+
+impl PlaceClusterIndex for TantivyIndex {
+    fn cluster_places(
+        &self,
+        query: &IndexQuery,
+        cell_size_deg: f64,
+    ) -> Fallible<Vec<PlaceCluster>> {
+        self.reader.cluster_places(query, cell_size_deg)
+    }
+}
+
+impl TagIndex for TantivyIndex {
+    fn suggest_tags(&self, prefix: &str, limit: usize) -> Fallible<Vec<TagFrequency>> {
+        self.reader.suggest_tags(prefix, limit)
+    }
+
+    fn most_popular_tags(
+        &self,
+        params: &MostPopularTagsParams,
+        pagination: &Pagination,
+    ) -> Fallible<Vec<TagFrequency>> {
+        self.reader.most_popular_tags(params, pagination)
+    }
+}
+
 impl EventAndPlaceIndexer for TantivyIndex {}
 
+fn lock_index(writer: &Mutex<TantivyIndex>) -> std::sync::MutexGuard<TantivyIndex> {
+    match writer.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn clear_cache(cache: &Mutex<PlaceQueryCache>) {
+    match cache.lock() {
+        Ok(mut guard) => guard.clear(),
+        Err(poisoned) => poisoned.into_inner().clear(),
+    }
+}
+
+fn commit_index(writer: &Mutex<TantivyIndex>, cache: &Mutex<PlaceQueryCache>) -> Fallible<()> {
+    let res = lock_index(writer).flush_index();
+    // Cached results might be outdated now
+    clear_cache(cache);
+    res
+}
+
+fn optimize_index(writer: &Mutex<TantivyIndex>, cache: &Mutex<PlaceQueryCache>) -> Fallible<()> {
+    let res = lock_index(writer).optimize_index();
+    // Pending modifications have been committed
+    clear_cache(cache);
+    res
+}
+
+/// Shareable access to the search index.
+///
+/// Searches are executed concurrently on the reader while all
+/// modifications are serialized by the single writer.
 #[derive(Clone)]
-pub struct SearchEngine(Arc<Mutex<Box<dyn EventAndPlaceIndexer + Send>>>);
+pub struct SearchEngine {
+    reader: Arc<TantivyIndexReader>,
+    writer: Arc<Mutex<TantivyIndex>>,
+    cache: Arc<Mutex<PlaceQueryCache>>,
+    // Only used for background indexing
+    queue: Option<IndexingQueue>,
+}
 
 impl SearchEngine {
     pub fn init_in_ram() -> Fallible<SearchEngine> {
         let index = TantivyIndex::create_in_ram()?;
-        Ok(SearchEngine(Arc::new(Mutex::new(Box::new(index)))))
+        Ok(SearchEngine::new(index))
     }
 
-    pub fn init_with_path<P: AsRef<Path>>(path: Option<P>) -> Fallible<SearchEngine> {
-        let index = TantivyIndex::create(path)?;
-        Ok(SearchEngine(Arc::new(Mutex::new(Box::new(index)))))
+    pub fn init_with_path<P: AsRef<Path>>(
+        path: Option<P>,
+        language: Option<TextLanguage>,
+    ) -> Fallible<SearchEngine> {
+        let index = TantivyIndex::create(path, language)?;
+        Ok(SearchEngine::new(index))
+    }
+
+    /// Open an existing index without recreating it.
+    pub fn open_with_path<P: AsRef<Path>>(
+        path: P,
+        language: Option<TextLanguage>,
+    ) -> Fallible<SearchEngine> {
+        let index = TantivyIndex::open(path, language)?;
+        Ok(SearchEngine::new(index))
+    }
+
+    fn new(index: TantivyIndex) -> Self {
+        SearchEngine {
+            reader: Arc::clone(&index.reader),
+            writer: Arc::new(Mutex::new(index)),
+            cache: Arc::new(Mutex::new(PlaceQueryCache::default())),
+            queue: None,
+        }
+    }
+
+    /// Rebuild the index in a temporary directory and replace
+    /// the current index in the directory afterwards.
+    ///
+    /// The current index remains untouched if populating the new
+    /// index fails. Fails if the current index is still in use,
+    /// e.g. by a running server.
+    pub fn rebuild_in_dir<P, F>(
+        path: P,
+        language: Option<TextLanguage>,
+        populate: F,
+    ) -> Fallible<()>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(&mut SearchEngine) -> Fallible<()>,
+    {
+        let index_dir = index_dir(path.as_ref(), language);
+        let tmp_dir = index_dir.with_extension("rebuild");
+        let old_dir = index_dir.with_extension("old");
+        {
+            let index = TantivyIndex::create_in_dir(Some(&tmp_dir), language)?;
+            let mut search_engine = SearchEngine::new(index);
+            populate(&mut search_engine)?;
+            search_engine.flush_now()?;
+            // The index writer is released when dropped
+        }
+        if old_dir.exists() {
+            fs::remove_dir_all(&old_dir)?;
+        }
+        if index_dir.exists() {
+            let lock = lock_index_dir(&index_dir).map_err(|err| {
+                // Discard the new index
+                if let Err(err) = fs::remove_dir_all(&tmp_dir) {
+                    warn!("Failed to remove the rebuilt index: {}", err);
+                }
+                err
+            })?;
+            fs::rename(&index_dir, &old_dir)?;
+            drop(lock);
+        }
+        info!(
+            "Replacing full-text search index in directory: {}",
+            index_dir.to_string_lossy()
+        );
+        fs::rename(&tmp_dir, &index_dir)?;
+        if old_dir.exists() {
+            fs::remove_dir_all(&old_dir)?;
+        }
+        Ok(())
+    }
+
+    /// Read all files of the index in the directory, even while
+    /// the index is in use, e.g. by a running server.
+    ///
+    /// Segment files are never modified. The files are consistent
+    /// if the metadata that references the segments has not been
+    /// replaced while reading them. Returns the paths of the files
+    /// relative to the directory with the metadata file last.
+    pub fn read_snapshot_in_dir<P: AsRef<Path>>(
+        path: P,
+        language: Option<TextLanguage>,
+    ) -> Fallible<Vec<(String, Vec<u8>)>> {
+        let index_dir = index_dir(path.as_ref(), language);
+        let dir_name = index_dir
+            .file_name()
+            .expect("subdirectory")
+            .to_string_lossy()
+            .into_owned();
+        for _ in 0..MAX_SNAPSHOT_ATTEMPTS {
+            let meta = fs::read(index_dir.join(INDEX_META_FILE))?;
+            let mut files = vec![];
+            for entry in fs::read_dir(&index_dir)? {
+                let entry = entry?;
+                let file_name = entry.file_name().to_string_lossy().into_owned();
+                if file_name == INDEX_META_FILE || file_name.ends_with(".lock") {
+                    continue;
+                }
+                match fs::read(entry.path()) {
+                    Ok(content) => files.push((format!("{}/{}", dir_name, file_name), content)),
+                    // Deleted files are not referenced by the metadata
+                    // unless it has been replaced in the meantime
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            if fs::read(index_dir.join(INDEX_META_FILE))? == meta {
+                files.push((format!("{}/{}", dir_name, INDEX_META_FILE), meta));
+                return Ok(files);
+            }
+            debug!("Full-text search index has been modified while reading its files");
+        }
+        bail!(
+            "Full-text search index in directory {} is modified continuously",
+            index_dir.to_string_lossy()
+        );
+    }
+
+    /// Apply all subsequent modifications in a background thread.
+    ///
+    /// The modifications are committed in batches and become
+    /// visible after the commit interval has elapsed.
+    pub fn with_background_indexing(mut self, commit_interval: Duration) -> Self {
+        debug_assert!(self.queue.is_none());
+        self.queue = Some(IndexingQueue::spawn(
+            Arc::clone(&self.writer),
+            Arc::clone(&self.cache),
+            commit_interval,
+        ));
+        self
+    }
+
+    /// Commit all pending modifications immediately and wait
+    /// until they have become visible.
+    pub fn flush_now(&self) -> Fallible<()> {
+        if let Some(queue) = &self.queue {
+            queue.flush_now()
+        } else {
+            commit_index(&self.writer, &self.cache)
+        }
+    }
+
+    fn lock_writer(&self) -> std::sync::MutexGuard<TantivyIndex> {
+        lock_index(&self.writer)
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<PlaceQueryCache> {
+        match self.cache.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
     }
 }
 
 impl Indexer for SearchEngine {
     fn flush_index(&mut self) -> Fallible<()> {
-        let mut inner = match self.0.lock() {
-            Ok(guard) => guard,
-            Err(poisoned) => poisoned.into_inner(),
-        };
-        inner.flush_index()
+        if self.queue.is_some() {
+            // Committed periodically by the background thread
+            return Ok(());
+        }
+        commit_index(&self.writer, &self.cache)
     }
 }
 
@@ -1052,31 +2677,56 @@ impl IdIndex for SearchEngine {
         query: &IndexQuery,
         limit: usize,
     ) -> Fallible<Vec<Id>> {
-        let inner = match self.0.lock() {
-            Ok(guard) => guard,
-            Err(poisoned) => poisoned.into_inner(),
-        };
-        inner.query_ids(mode, query, limit)
+        self.reader.query_ids(mode, query, limit)
     }
 }
 
 impl IdIndexer for SearchEngine {
     fn remove_by_id(&self, id: &Id) -> Fallible<()> {
-        let inner = match self.0.lock() {
-            Ok(guard) => guard,
-            Err(poisoned) => poisoned.into_inner(),
-        };
-        inner.remove_by_id(id)
+        if let Some(queue) = &self.queue {
+            return queue.send(IndexCommand::RemoveById(id.clone()));
+        }
+        self.lock_writer().remove_by_id(id)
     }
 }
 
 impl PlaceIndex for SearchEngine {
     fn query_places(&self, query: &IndexQuery, limit: usize) -> Fallible<Vec<IndexedPlace>> {
-        let inner = match self.0.lock() {
-            Ok(guard) => guard,
-            Err(poisoned) => poisoned.into_inner(),
+        let key = place_query_cache_key(query, limit);
+        let generation = {
+            let mut cache = self.lock_cache();
+            if let Some(places) = cache.get(&key) {
+                return Ok(places);
+            }
+            cache.generation()
         };
-        inner.query_places(query, limit)
+        let places = self.reader.query_places(query, limit)?;
+        self.lock_cache().insert(key, places.clone(), generation);
+        Ok(places)
+    }
+}
+
+impl TagIndex for SearchEngine {
+    fn suggest_tags(&self, prefix: &str, limit: usize) -> Fallible<Vec<TagFrequency>> {
+        self.reader.suggest_tags(prefix, limit)
+    }
+
+    fn most_popular_tags(
+        &self,
+        params: &MostPopularTagsParams,
+        pagination: &Pagination,
+    ) -> Fallible<Vec<TagFrequency>> {
+        self.reader.most_popular_tags(params, pagination)
+    }
+}
+
+impl PlaceClusterIndex for SearchEngine {
+    fn cluster_places(
+        &self,
+        query: &IndexQuery,
+        cell_size_deg: f64,
+    ) -> Fallible<Vec<PlaceCluster>> {
+        self.reader.cluster_places(query, cell_size_deg)
     }
 }
 
@@ -1087,22 +2737,566 @@ impl PlaceIndexer for SearchEngine {
         status: ReviewStatus,
         ratings: &AvgRatings,
     ) -> Fallible<()> {
-        let inner = match self.0.lock() {
-            Ok(guard) => guard,
-            Err(poisoned) => poisoned.into_inner(),
-        };
-        inner.add_or_update_place(place, status, ratings)
+        if let Some(queue) = &self.queue {
+            let args = (place.clone(), status, ratings.clone());
+            return queue.send(IndexCommand::AddOrUpdatePlace(Box::new(args)));
+        }
+        self.lock_writer()
+            .add_or_update_place(place, status, ratings)
+    }
+
+    fn set_curated_tags(&mut self, curated_tags: &[CuratedTag]) {
+        if let Some(queue) = &self.queue {
+            // Must be applied in order with all other modifications
+            let cmd = IndexCommand::SetCuratedTags(curated_tags.to_vec());
+            if let Err(err) = queue.send(cmd) {
+                error!("Failed to update curated tags: {}", err);
+            }
+            return;
+        }
+        self.lock_writer().set_curated_tags(curated_tags);
+        // The ranking of cached results might have changed
+        clear_cache(&self.cache);
+    }
+
+    fn set_tag_ownerships(&mut self, tag_ownerships: &[TagOwnership]) {
+        if let Some(queue) = &self.queue {
+            // Must be applied in order with all other modifications
+            let cmd = IndexCommand::SetTagOwnerships(tag_ownerships.to_vec());
+            if let Err(err) = queue.send(cmd) {
+                error!("Failed to update tag ownerships: {}", err);
+            }
+            return;
+        }
+        self.lock_writer().set_tag_ownerships(tag_ownerships)
+    }
+
+    fn set_categories(&mut self, categories: &[Category]) {
+        // Applied immediately, independent of pending modifications
+        self.reader.set_categories(categories);
+        // Cached results might have been resolved with outdated categories
+        clear_cache(&self.cache);
+    }
+
+    fn optimize_index(&mut self) -> Fallible<()> {
+        if let Some(queue) = &self.queue {
+            return queue.optimize_now();
+        }
+        optimize_index(&self.writer, &self.cache)
     }
 }
 
 impl EventIndexer for SearchEngine {
     fn add_or_update_event(&self, event: &Event) -> Fallible<()> {
-        let inner = match self.0.lock() {
-            Ok(guard) => guard,
-            Err(poisoned) => poisoned.into_inner(),
-        };
-        inner.add_or_update_event(event)
+        if let Some(queue) = &self.queue {
+            return queue.send(IndexCommand::AddOrUpdateEvent(Box::new(event.clone())));
+        }
+        self.lock_writer().add_or_update_event(event)
     }
 }
 
 impl EventAndPlaceIndexer for SearchEngine {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::entities::Builder;
+    use std::{env, process};
+
+    fn query_place_ids(index: &TantivyIndex, text: &str) -> Vec<String> {
+        let query = IndexQuery {
+            text: Some(text.into()),
+            ..Default::default()
+        };
+        index
+            .query_places(&query, 10)
+            .unwrap()
+            .into_iter()
+            .map(|place| place.id)
+            .collect()
+    }
+
+    fn create_index_with_place(language: Option<TextLanguage>) -> TantivyIndex {
+        let no_path: Option<&Path> = None;
+        let mut index = TantivyIndex::create(no_path, language).unwrap();
+        let place = Place::build()
+            .id("a")
+            .title("Gemeinschaftsgarten")
+            .description("Offene Gärten für alle")
+            .finish();
+        index
+            .add_or_update_place(&place, ReviewStatus::Created, &Default::default())
+            .unwrap();
+        index.flush_index().unwrap();
+        index
+    }
+
+    #[test]
+    fn stem_words_of_configured_language() {
+        let index = create_index_with_place(Some(TextLanguage::German));
+        assert_eq!(vec!["a".to_string()], query_place_ids(&index, "garten"));
+        assert_eq!(vec!["a".to_string()], query_place_ids(&index, "Gärten"));
+
+        let index = create_index_with_place(None);
+        assert!(query_place_ids(&index, "garten").is_empty());
+        assert_eq!(vec!["a".to_string()], query_place_ids(&index, "Gärten"));
+    }
+
+    #[test]
+    fn setting_curated_tags_clears_cached_results() {
+        let mut search_engine = SearchEngine::init_in_ram().unwrap();
+        let query = IndexQuery::default();
+        search_engine.query_places(&query, 10).unwrap();
+        let generation = search_engine.lock_cache().generation();
+        search_engine.set_curated_tags(&[CuratedTag {
+            tag: "bio".into(),
+            boost: 2.0,
+        }]);
+        assert_ne!(generation, search_engine.lock_cache().generation());
+        let key = place_query_cache_key(&query, 10);
+        assert!(search_engine.lock_cache().get(&key).is_none());
+    }
+
+    #[test]
+    fn sort_places_by_ratings_count() {
+        let mut index = TantivyIndex::create_in_ram().unwrap();
+        for (id, count) in &[("a", 0), ("b", 5), ("c", 2)] {
+            let place = Place::build().id(id).finish();
+            let ratings = AvgRatings {
+                count: *count,
+                ..Default::default()
+            };
+            index
+                .add_or_update_place(&place, ReviewStatus::Created, &ratings)
+                .unwrap();
+        }
+        index.flush_index().unwrap();
+        let query = IndexQuery {
+            sort_by: IndexSortOrder::RatingsCount,
+            ..Default::default()
+        };
+        let places = index.query_places(&query, 10).unwrap();
+        assert_eq!(
+            vec![("b", 5), ("c", 2), ("a", 0)],
+            places
+                .iter()
+                .map(|place| (place.id.as_str(), place.ratings.count))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn sort_places_with_equal_titles_by_id() {
+        let mut index = TantivyIndex::create_in_ram().unwrap();
+        for (id, title) in &[("c", "Shop"), ("b", "Bakery"), ("a", "shop"), ("d", "Shop")] {
+            let place = Place::build().id(id).title(title).finish();
+            index
+                .add_or_update_place(&place, ReviewStatus::Created, &Default::default())
+                .unwrap();
+        }
+        index.flush_index().unwrap();
+        let query = IndexQuery {
+            sort_by: IndexSortOrder::Title,
+            ..Default::default()
+        };
+        let places = index.query_places(&query, 10).unwrap();
+        assert_eq!(
+            vec!["b", "a", "c", "d"],
+            places
+                .iter()
+                .map(|place| place.id.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn find_places_by_additional_locations() {
+        let no_path: Option<&Path> = None;
+        let mut index = TantivyIndex::create(no_path, None).unwrap();
+        let place = Place::build()
+            .id("a")
+            .pos(MapPoint::from_lat_lng_deg(0.0, 0.0))
+            .additional_positions(vec![
+                MapPoint::from_lat_lng_deg(10.0, 20.0),
+                MapPoint::from_lat_lng_deg(30.0, 40.0),
+            ])
+            .finish();
+        index
+            .add_or_update_place(&place, ReviewStatus::Created, &Default::default())
+            .unwrap();
+        index.flush_index().unwrap();
+
+        let query_bbox = |south_west: (f64, f64), north_east: (f64, f64)| {
+            let query = IndexQuery {
+                include_bbox: Some(MapBbox::new(
+                    MapPoint::from_lat_lng_deg(south_west.0, south_west.1),
+                    MapPoint::from_lat_lng_deg(north_east.0, north_east.1),
+                )),
+                ..Default::default()
+            };
+            index.query_places(&query, 10).unwrap()
+        };
+        let places = query_bbox((9.0, 19.0), (11.0, 21.0));
+        assert_eq!(1, places.len());
+        assert_eq!(MapPoint::from_lat_lng_deg(0.0, 0.0), places[0].pos);
+        assert_eq!(
+            vec![
+                MapPoint::from_lat_lng_deg(10.0, 20.0),
+                MapPoint::from_lat_lng_deg(30.0, 40.0),
+            ],
+            places[0].additional_positions
+        );
+        assert_eq!(1, query_bbox((29.0, 39.0), (31.0, 41.0)).len());
+        assert_eq!(1, query_bbox((-1.0, -1.0), (1.0, 1.0)).len());
+        // Latitude and longitude of different locations
+        assert!(query_bbox((9.0, 39.0), (11.0, 41.0)).is_empty());
+        assert!(query_bbox((50.0, 50.0), (51.0, 51.0)).is_empty());
+    }
+
+    #[test]
+    fn cluster_places_on_a_grid() {
+        let mut index = TantivyIndex::create_in_ram().unwrap();
+        for (id, lat, lng) in &[
+            ("a", 1.2, 1.2),
+            ("b", 1.4, 1.8),
+            ("c", 1.6, 1.4),
+            ("d", 5.5, 5.5),
+            ("e", 50.0, 50.0),
+        ] {
+            let place = Place::build()
+                .id(id)
+                .pos(MapPoint::from_lat_lng_deg(*lat, *lng))
+                .finish();
+            index
+                .add_or_update_place(&place, ReviewStatus::Created, &Default::default())
+                .unwrap();
+        }
+        index.flush_index().unwrap();
+        let query = IndexQuery {
+            include_bbox: Some(MapBbox::new(
+                MapPoint::from_lat_lng_deg(0.0, 0.0),
+                MapPoint::from_lat_lng_deg(10.0, 10.0),
+            )),
+            ..Default::default()
+        };
+        let clusters = index.cluster_places(&query, 1.0).unwrap();
+        assert_eq!(2, clusters.len());
+        assert_eq!(3, clusters[0].count);
+        assert!((clusters[0].centroid.lat().to_deg() - 1.4).abs() < 0.000_01);
+        assert!((clusters[0].centroid.lng().to_deg() - 1.466_667).abs() < 0.000_01);
+        assert_eq!(
+            PlaceCluster {
+                centroid: MapPoint::from_lat_lng_deg(5.5, 5.5),
+                count: 1,
+            },
+            clusters[1]
+        );
+        // All places within a single cell
+        let clusters = index.cluster_places(&query, 10.0).unwrap();
+        assert_eq!(1, clusters.len());
+        assert_eq!(4, clusters[0].count);
+    }
+
+    #[test]
+    fn parse_text_language() {
+        assert_eq!(TextLanguage::German, "de".parse().unwrap());
+        assert_eq!(TextLanguage::English, " English ".parse().unwrap());
+        assert!("xx".parse::<TextLanguage>().is_err());
+    }
+
+    #[test]
+    fn search_while_indexing() {
+        let mut search_engine = SearchEngine::init_in_ram().unwrap();
+        let place = Place::build().id("a").title("Garden").finish();
+        search_engine
+            .add_or_update_place(&place, ReviewStatus::Created, &Default::default())
+            .unwrap();
+        search_engine.flush_index().unwrap();
+
+        let _writer = search_engine.lock_writer();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let reader = search_engine.clone();
+        std::thread::spawn(move || {
+            let query = IndexQuery {
+                text: Some("garden".into()),
+                ..Default::default()
+            };
+            tx.send(reader.query_places(&query, 10).unwrap()).unwrap();
+        });
+        let places = rx
+            .recv_timeout(Duration::from_secs(10))
+            .expect("search is blocked by the writer");
+        assert_eq!(1, places.len());
+    }
+
+    #[test]
+    fn commit_in_background() {
+        let search_engine = SearchEngine::init_in_ram()
+            .unwrap()
+            .with_background_indexing(Duration::from_secs(60));
+        let query = IndexQuery {
+            text: Some("garden".into()),
+            ..Default::default()
+        };
+        let place = Place::build().id("a").title("Garden").finish();
+        search_engine
+            .add_or_update_place(&place, ReviewStatus::Created, &Default::default())
+            .unwrap();
+        search_engine.remove_by_id(&"b".into()).unwrap();
+        // Not committed yet
+        assert!(search_engine.query_places(&query, 10).unwrap().is_empty());
+
+        search_engine.flush_now().unwrap();
+        assert_eq!(1, search_engine.query_places(&query, 10).unwrap().len());
+    }
+
+    #[test]
+    fn optimize_in_background() {
+        let mut search_engine = SearchEngine::init_in_ram()
+            .unwrap()
+            .with_background_indexing(Duration::from_secs(60));
+        let query = IndexQuery {
+            text: Some("garden".into()),
+            ..Default::default()
+        };
+        for id in &["a", "b", "c"] {
+            let place = Place::build().id(id).title("Garden").finish();
+            search_engine
+                .add_or_update_place(&place, ReviewStatus::Created, &Default::default())
+                .unwrap();
+            search_engine.flush_now().unwrap();
+        }
+        let segment_count = |search_engine: &SearchEngine| search_engine.reader.segment_ids().len();
+        assert!(segment_count(&search_engine) > 1);
+
+        let place = Place::build().id("d").title("Garden").finish();
+        search_engine
+            .add_or_update_place(&place, ReviewStatus::Created, &Default::default())
+            .unwrap();
+        search_engine.optimize_index().unwrap();
+        assert_eq!(1, segment_count(&search_engine));
+        assert_eq!(4, search_engine.query_places(&query, 10).unwrap().len());
+    }
+
+    #[test]
+    fn optimize_existing_index_in_dir() {
+        let path = env::temp_dir().join(format!("openfairdb-test-index-{}", process::id()));
+        let query = IndexQuery {
+            text: Some("garden".into()),
+            ..Default::default()
+        };
+        {
+            let search_engine = SearchEngine::init_with_path(Some(&path), None).unwrap();
+            for id in &["a", "b", "c"] {
+                let place = Place::build().id(id).title("Garden").finish();
+                search_engine
+                    .add_or_update_place(&place, ReviewStatus::Created, &Default::default())
+                    .unwrap();
+                search_engine.flush_now().unwrap();
+            }
+        }
+        {
+            let mut search_engine = SearchEngine::open_with_path(&path, None).unwrap();
+            assert_eq!(3, search_engine.query_places(&query, 10).unwrap().len());
+            search_engine.optimize_index().unwrap();
+        }
+        let search_engine = SearchEngine::open_with_path(&path, None).unwrap();
+        assert_eq!(3, search_engine.query_places(&query, 10).unwrap().len());
+        drop(search_engine);
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn keep_index_in_dir_while_in_use() {
+        let path = env::temp_dir().join(format!("openfairdb-test-in-use-{}", process::id()));
+        let query = IndexQuery {
+            text: Some("garden".into()),
+            ..Default::default()
+        };
+        let search_engine = SearchEngine::init_with_path(Some(&path), None).unwrap();
+        let place = Place::build().id("a").title("Garden").finish();
+        search_engine
+            .add_or_update_place(&place, ReviewStatus::Created, &Default::default())
+            .unwrap();
+        search_engine.flush_now().unwrap();
+
+        assert!(SearchEngine::init_with_path(Some(&path), None).is_err());
+        assert!(SearchEngine::rebuild_in_dir(&path, None, |_| Ok(())).is_err());
+        assert_eq!(1, search_engine.query_places(&query, 10).unwrap().len());
+
+        // The index can be replaced after it has been released
+        drop(search_engine);
+        SearchEngine::rebuild_in_dir(&path, None, |_| Ok(())).unwrap();
+        let search_engine = SearchEngine::open_with_path(&path, None).unwrap();
+        assert!(search_engine.query_places(&query, 10).unwrap().is_empty());
+        drop(search_engine);
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn read_snapshot_of_index_in_use() {
+        let path = env::temp_dir().join(format!("openfairdb-test-snapshot-{}", process::id()));
+        let restored_path = path.with_extension("restored");
+        let query = IndexQuery {
+            text: Some("garden".into()),
+            ..Default::default()
+        };
+        let search_engine = SearchEngine::init_with_path(Some(&path), None).unwrap();
+        let place = Place::build().id("a").title("Garden").finish();
+        search_engine
+            .add_or_update_place(&place, ReviewStatus::Created, &Default::default())
+            .unwrap();
+        search_engine.flush_now().unwrap();
+
+        let files = SearchEngine::read_snapshot_in_dir(&path, None).unwrap();
+        assert!(files.last().unwrap().0.ends_with("/meta.json"));
+        assert!(files.iter().all(|(name, _)| !name.ends_with(".lock")));
+        for (name, content) in files {
+            let file_path = restored_path.join(name);
+            fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+            fs::write(file_path, content).unwrap();
+        }
+        let restored = SearchEngine::open_with_path(&restored_path, None).unwrap();
+        assert_eq!(1, restored.query_places(&query, 10).unwrap().len());
+        drop(restored);
+        drop(search_engine);
+        fs::remove_dir_all(&path).unwrap();
+        fs::remove_dir_all(&restored_path).unwrap();
+    }
+
+    #[test]
+    fn open_missing_index_in_dir() {
+        let path = env::temp_dir().join(format!("openfairdb-test-missing-{}", process::id()));
+        assert!(SearchEngine::open_with_path(&path, None).is_err());
+        assert!(!path.exists());
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::core::entities::Builder;
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        thread,
+    };
+    use test::Bencher;
+
+    const PLACE_COUNT: usize = 1_000;
+    const SEARCH_THREADS: usize = 4;
+    const SEARCHES_PER_THREAD: usize = 25;
+
+    fn new_place(i: usize) -> Place {
+        Place::build()
+            .id(&i.to_string())
+            .title(&format!("Garden {}", i))
+            .description("Vegetables and fruits for everyone")
+            .finish()
+    }
+
+    fn garden_query(i: usize) -> IndexQuery<'static, 'static> {
+        IndexQuery {
+            text: Some(format!("garden {}", i % PLACE_COUNT)),
+            ..Default::default()
+        }
+    }
+
+    // Continuously modifies the index until the returned flag is set
+    fn spawn_indexing(
+        mut index: impl FnMut(&Place) -> Fallible<()> + Send + 'static,
+    ) -> (Arc<AtomicBool>, thread::JoinHandle<()>) {
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || {
+                let mut i = 0;
+                while !stop.load(Ordering::Relaxed) {
+                    index(&new_place(i % PLACE_COUNT)).unwrap();
+                    i += 1;
+                }
+            })
+        };
+        (stop, handle)
+    }
+
+    fn search_concurrently<S>(search: S)
+    where
+        S: Fn(&IndexQuery) -> Fallible<Vec<Id>> + Clone + Send + 'static,
+    {
+        let handles: Vec<_> = (0..SEARCH_THREADS)
+            .map(|t| {
+                let search = search.clone();
+                thread::spawn(move || {
+                    for i in 0..SEARCHES_PER_THREAD {
+                        search(&garden_query(t * SEARCHES_PER_THREAD + i)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[bench]
+    fn search_while_indexing(b: &mut Bencher) {
+        let mut search_engine = SearchEngine::init_in_ram().unwrap();
+        for i in 0..PLACE_COUNT {
+            search_engine
+                .add_or_update_place(&new_place(i), ReviewStatus::Created, &Default::default())
+                .unwrap();
+        }
+        search_engine.flush_index().unwrap();
+
+        let (stop, indexing) = {
+            let mut search_engine = search_engine.clone();
+            spawn_indexing(move |place| {
+                search_engine.add_or_update_place(
+                    place,
+                    ReviewStatus::Created,
+                    &Default::default(),
+                )?;
+                search_engine.flush_index()
+            })
+        };
+        b.iter(|| {
+            let search_engine = search_engine.clone();
+            // Bypasses the query cache
+            search_concurrently(move |query| {
+                search_engine.query_ids(IndexQueryMode::WithoutRating, query, 10)
+            })
+        });
+        stop.store(true, Ordering::Relaxed);
+        indexing.join().unwrap();
+    }
+
+    // Baseline: All searches and modifications are serialized
+    #[bench]
+    fn search_while_indexing_with_global_lock(b: &mut Bencher) {
+        let mut index = TantivyIndex::create_in_ram().unwrap();
+        for i in 0..PLACE_COUNT {
+            index
+                .add_or_update_place(&new_place(i), ReviewStatus::Created, &Default::default())
+                .unwrap();
+        }
+        index.flush_index().unwrap();
+        let index = Arc::new(Mutex::new(index));
+
+        let (stop, indexing) = {
+            let index = Arc::clone(&index);
+            spawn_indexing(move |place| {
+                let mut index = lock_index(&index);
+                index.add_or_update_place(place, ReviewStatus::Created, &Default::default())?;
+                index.flush_index()
+            })
+        };
+        b.iter(|| {
+            let index = Arc::clone(&index);
+            search_concurrently(move |query| {
+                lock_index(&index).query_ids(IndexQueryMode::WithoutRating, query, 10)
+            })
+        });
+        stop.store(true, Ordering::Relaxed);
+        indexing.join().unwrap();
+    }
+}
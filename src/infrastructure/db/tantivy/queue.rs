@@ -0,0 +1,142 @@
+use super::*;
+
+use anyhow::anyhow;
+use std::{
+    sync::mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Requests are delayed when the indexing thread
+// cannot keep up with all modifications
+const MAX_PENDING_COMMANDS: usize = 10_000;
+
+pub(super) enum IndexCommand {
+    AddOrUpdatePlace(Box<(Place, ReviewStatus, AvgRatings)>),
+    AddOrUpdateEvent(Box<Event>),
+    RemoveById(Id),
+    SetCuratedTags(Vec<CuratedTag>),
+    SetTagOwnerships(Vec<TagOwnership>),
+    // Commit all pending modifications immediately
+    FlushNow(SyncSender<Fallible<()>>),
+    // Commit all pending modifications and merge all segments
+    OptimizeNow(SyncSender<Fallible<()>>),
+}
+
+/// Modifications of the index that are applied by
+/// a background thread and committed periodically.
+#[derive(Clone)]
+pub(super) struct IndexingQueue {
+    tx: SyncSender<IndexCommand>,
+}
+
+impl IndexingQueue {
+    pub fn spawn(
+        writer: Arc<Mutex<TantivyIndex>>,
+        cache: Arc<Mutex<PlaceQueryCache>>,
+        commit_interval: Duration,
+    ) -> Self {
+        let (tx, rx) = sync_channel(MAX_PENDING_COMMANDS);
+        thread::spawn(move || process_commands(&writer, &cache, &rx, commit_interval));
+        Self { tx }
+    }
+
+    pub fn send(&self, cmd: IndexCommand) -> Fallible<()> {
+        self.tx
+            .send(cmd)
+            .map_err(|_| anyhow!("The indexing thread has terminated"))
+    }
+
+    pub fn flush_now(&self) -> Fallible<()> {
+        let (done_tx, done_rx) = sync_channel(1);
+        self.send(IndexCommand::FlushNow(done_tx))?;
+        done_rx
+            .recv()
+            .map_err(|_| anyhow!("The indexing thread has terminated"))?
+    }
+
+    pub fn optimize_now(&self) -> Fallible<()> {
+        let (done_tx, done_rx) = sync_channel(1);
+        self.send(IndexCommand::OptimizeNow(done_tx))?;
+        done_rx
+            .recv()
+            .map_err(|_| anyhow!("The indexing thread has terminated"))?
+    }
+}
+
+fn apply_command(index: &mut TantivyIndex, cmd: IndexCommand) -> Fallible<()> {
+    match cmd {
+        IndexCommand::AddOrUpdatePlace(args) => {
+            let (place, status, ratings) = *args;
+            index.add_or_update_place(&place, status, &ratings)
+        }
+        IndexCommand::AddOrUpdateEvent(event) => index.add_or_update_event(&event),
+        IndexCommand::RemoveById(id) => index.remove_by_id(&id),
+        IndexCommand::SetTagOwnerships(tag_ownerships) => {
+            index.set_tag_ownerships(&tag_ownerships);
+            Ok(())
+        }
+        IndexCommand::SetCuratedTags(_)
+        | IndexCommand::FlushNow(_)
+        | IndexCommand::OptimizeNow(_) => unreachable!(),
+    }
+}
+
+fn process_commands(
+    writer: &Mutex<TantivyIndex>,
+    cache: &Mutex<PlaceQueryCache>,
+    rx: &Receiver<IndexCommand>,
+    commit_interval: Duration,
+) {
+    // The next commit is due at this point in time
+    // if there are any uncommitted modifications
+    let mut commit_deadline: Option<Instant> = None;
+    loop {
+        let cmd = if let Some(deadline) = commit_deadline {
+            match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(cmd) => Some(cmd),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        } else {
+            match rx.recv() {
+                Ok(cmd) => Some(cmd),
+                Err(_) => break,
+            }
+        };
+        match cmd {
+            Some(IndexCommand::FlushNow(done)) => {
+                commit_deadline = None;
+                let _ = done.send(commit_index(writer, cache));
+            }
+            Some(IndexCommand::OptimizeNow(done)) => {
+                commit_deadline = None;
+                let _ = done.send(optimize_index(writer, cache));
+            }
+            Some(IndexCommand::SetCuratedTags(curated_tags)) => {
+                lock_index(writer).set_curated_tags(&curated_tags);
+                // The ranking of cached results might have changed
+                clear_cache(cache);
+            }
+            Some(cmd) => {
+                if let Err(err) = apply_command(&mut *lock_index(writer), cmd) {
+                    error!("Failed to update the search index: {}", err);
+                }
+                if commit_deadline.is_none() {
+                    commit_deadline = Some(Instant::now() + commit_interval);
+                }
+            }
+            None => {
+                commit_deadline = None;
+                if let Err(err) = commit_index(writer, cache) {
+                    error!("Failed to commit the search index: {}", err);
+                }
+            }
+        }
+    }
+    if commit_deadline.is_some() {
+        if let Err(err) = commit_index(writer, cache) {
+            error!("Failed to commit the search index: {}", err);
+        }
+    }
+}
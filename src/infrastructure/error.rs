@@ -41,7 +41,7 @@ pub enum AppError {
     #[error(transparent)]
     R2d2(#[from] r2d2::PoolError),
     #[error(transparent)]
-    CsvIntoInner(#[from] ::csv::IntoInnerError<::csv::Writer<::std::vec::Vec<u8>>>),
+    CsvIntoInner(Box<::csv::IntoInnerError<::csv::Writer<::std::vec::Vec<u8>>>>),
     #[error(transparent)]
     String(#[from] ::std::string::FromUtf8Error),
     #[error(transparent)]
@@ -50,6 +50,13 @@ pub enum AppError {
     Csv(#[from] ::csv::Error),
 }
 
+// The writer of the error is boxed, because it is large
+impl From<::csv::IntoInnerError<::csv::Writer<::std::vec::Vec<u8>>>> for AppError {
+    fn from(err: ::csv::IntoInnerError<::csv::Writer<::std::vec::Vec<u8>>>) -> Self {
+        AppError::CsvIntoInner(Box::new(err))
+    }
+}
+
 impl From<ofdb_entities::password::ParseError> for AppError {
     fn from(err: ofdb_entities::password::ParseError) -> Self {
         BError::from(err).into()
@@ -0,0 +1,198 @@
+use super::*;
+use diesel::connection::Connection;
+
+pub fn anonymize_user(connections: &sqlite::Connections, email: &str) -> Result<()> {
+    let mut repo_err = None;
+    let connection = connections.exclusive()?;
+    Ok(connection
+        .transaction::<_, diesel::result::Error, _>(|| {
+            usecases::anonymize_user(&*connection, email).map_err(|err| {
+                warn!("Failed to anonymize user {}: {}", email, err);
+                repo_err = Some(err);
+                diesel::result::Error::RollbackTransaction
+            })
+        })
+        .map_err(|err| {
+            if let Some(repo_err) = repo_err {
+                repo_err
+            } else {
+                RepoError::from(err).into()
+            }
+        })?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use diesel::{sql_query, sql_types::Text, RunQueryDsl};
+
+    #[derive(QueryableByName)]
+    struct NameRow {
+        #[sql_type = "Text"]
+        name: String,
+    }
+
+    #[derive(QueryableByName)]
+    struct CountRow {
+        #[sql_type = "diesel::sql_types::BigInt"]
+        count: i64,
+    }
+
+    // Returns all "table.column" pairs that contain the given text
+    fn find_text_in_database(fixture: &EnvFixture, text: &str) -> Vec<String> {
+        let conn = fixture.db_connections.shared().unwrap();
+        let tables: Vec<NameRow> = sql_query(
+            "SELECT name FROM sqlite_master WHERE type='table' \
+             AND name NOT LIKE 'sqlite_%' AND name != '__diesel_schema_migrations'",
+        )
+        .load(&*conn)
+        .unwrap();
+        let mut matches = vec![];
+        for table in tables {
+            let columns: Vec<NameRow> = sql_query(format!("PRAGMA table_info(\"{}\")", table.name))
+                .load(&*conn)
+                .unwrap();
+            for column in columns {
+                let rows: Vec<CountRow> = sql_query(format!(
+                    "SELECT COUNT(*) AS count FROM \"{}\" WHERE instr(CAST(\"{}\" AS TEXT), ?) > 0",
+                    table.name, column.name
+                ))
+                .bind::<Text, _>(text)
+                .load(&*conn)
+                .unwrap();
+                if rows[0].count > 0 {
+                    matches.push(format!("{}.{}", table.name, column.name));
+                }
+            }
+        }
+        matches
+    }
+
+    #[test]
+    fn anonymize_user_and_keep_content() {
+        let fixture = EnvFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "user@example.com".into(),
+                password: "test123".into(),
+            },
+            None,
+        );
+        let place_id = fixture.create_place(0.into(), Some("user@example.com"));
+        let (rating_id, comment_id) = flows::create_rating(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            new_entry_rating(0, &place_id, RatingContext::Fairness, RatingValue::new(1)),
+            Some("user@example.com"),
+            false,
+        )
+        .unwrap();
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .replace_user_token(UserToken {
+                email_nonce: EmailNonce {
+                    email: "user@example.com".into(),
+                    nonce: Nonce::new(),
+                },
+                expires_at: Timestamp::now(),
+            })
+            .unwrap();
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .replace_login_link(&LoginLink {
+                email_nonce: EmailNonce {
+                    email: "user@example.com".into(),
+                    nonce: Nonce::new(),
+                },
+                created_at: TimestampMs::now(),
+                expires_at: Timestamp::now(),
+            })
+            .unwrap();
+        let event_id = flows::create_event(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            None,
+            usecases::NewEvent {
+                title: "Meetup".into(),
+                start: chrono::Utc::now().naive_utc().timestamp(),
+                email: Some("organizer@example.com".into()),
+                created_by: Some("organizer@example.com".into()),
+                registration: Some("email".into()),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .id;
+        flows::register_for_event(
+            &fixture.db_connections,
+            &fixture.notify,
+            &event_id.as_str().into(),
+            "user@example.com",
+        )
+        .unwrap();
+        flows::enqueue_emails(
+            &fixture.db_connections,
+            &[Email::from("user@example.com")],
+            "Hello",
+            "World",
+        );
+        flows::enqueue_emails(
+            &fixture.db_connections,
+            &[Email::from("admin@example.com")],
+            "New user user@example.com",
+            "Please review user@example.com",
+        );
+        fixture.create_user(
+            usecases::NewUser {
+                email: "admin@example.com".into(),
+                password: "test123".into(),
+            },
+            Some(Role::Admin),
+        );
+        flows::change_user_role(
+            &fixture.db_connections,
+            "admin@example.com",
+            "user@example.com",
+            Role::Scout,
+        )
+        .unwrap();
+        assert!(!find_text_in_database(&fixture, "user@example.com").is_empty());
+
+        flows::anonymize_user(&fixture.db_connections, "user@example.com").unwrap();
+
+        assert!(fixture.try_get_user("user@example.com").is_none());
+        let (place, _) = fixture.try_get_place(&place_id).unwrap();
+        assert!(place.created.by.is_none());
+        assert_eq!("title_0", place.title);
+        assert!(fixture
+            .try_get_rating(&rating_id)
+            .unwrap()
+            .created_by
+            .is_none());
+        assert!(fixture.comment_exists(&comment_id));
+        assert!(fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .get_user_token_by_email("user@example.com")
+            .is_err());
+        assert!(find_text_in_database(&fixture, "user@example.com").is_empty());
+        let outbox = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_outgoing_emails(None, &Default::default())
+            .unwrap();
+        assert_eq!(1, outbox.len());
+        assert_eq!("admin@example.com", outbox[0].recipient.as_str());
+        assert_eq!("New user [deleted]", outbox[0].subject);
+
+        // The account has already been deleted
+        assert!(flows::anonymize_user(&fixture.db_connections, "user@example.com").is_err());
+    }
+}
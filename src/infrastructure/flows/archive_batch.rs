@@ -0,0 +1,138 @@
+use super::{archive_events::*, archive_ratings::*, review_places::*, *};
+
+use ofdb_core::NotificationGateway;
+
+// Items are archived in smaller chunks to limit the number
+// of ids per statement and the impact of a single failure
+const ARCHIVE_BATCH_CHUNK_SIZE: usize = 100;
+
+fn archive_chunk<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    notify: &dyn NotificationGateway,
+    account_email: &str,
+    kind: usecases::ArchiveKind,
+    ids: &[&str],
+    comment: Option<&str>,
+) -> Result<usize> {
+    match kind {
+        usecases::ArchiveKind::Place => {
+            let review = usecases::Review {
+                context: None,
+                reviewer_email: account_email.into(),
+                status: ReviewStatus::Archived,
+                comment: comment.map(ToOwned::to_owned),
+            };
+            review_places(connections, indexer, notify, ids, review)
+        }
+        usecases::ArchiveKind::Event => {
+            archive_events(connections, indexer, notify, ids, account_email, comment)
+        }
+        usecases::ArchiveKind::Rating => {
+            archive_ratings(connections, indexer, account_email, ids, comment)
+        }
+    }
+}
+
+/// Archive many places, events, or ratings at once.
+///
+/// Instead of failing the whole batch the ids of items that
+/// don't exist or that could not be archived are reported
+/// separately.
+pub fn archive_batch<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    notify: &dyn NotificationGateway,
+    account_email: &str,
+    kind: usecases::ArchiveKind,
+    ids: &[&str],
+    comment: Option<&str>,
+) -> Result<usecases::ArchiveBatchReport> {
+    let (archivable, not_found) =
+        usecases::prepare_archive_batch(&*connections.shared()?, account_email, kind, ids)?;
+    let mut report = usecases::ArchiveBatchReport {
+        not_found,
+        ..Default::default()
+    };
+    for chunk in archivable.chunks(ARCHIVE_BATCH_CHUNK_SIZE) {
+        let chunk_ids: Vec<_> = chunk.iter().map(String::as_str).collect();
+        match archive_chunk(
+            connections,
+            indexer,
+            notify,
+            account_email,
+            kind,
+            &chunk_ids,
+            comment,
+        ) {
+            Ok(_) => report.archived.extend_from_slice(chunk),
+            Err(err) => {
+                warn!("Failed to archive {} items: {}", chunk.len(), err);
+                report.failed.extend_from_slice(chunk);
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    fn archive_ratings(fixture: &EnvFixture, ids: &[&str]) -> usecases::ArchiveBatchReport {
+        flows::archive_batch(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            "scout@foo.tld",
+            usecases::ArchiveKind::Rating,
+            ids,
+            Some("spam"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn archive_batch_of_ratings_with_unknown_ids() {
+        let fixture = EnvFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "scout@foo.tld".into(),
+                password: "123456".into(),
+            },
+            Some(Role::Scout),
+        );
+        let place_id = fixture.create_place(0.into(), None);
+        let rating_ids = vec![
+            fixture
+                .create_rating(new_entry_rating(
+                    0,
+                    &place_id,
+                    RatingContext::Diversity,
+                    RatingValue::new(-1),
+                ))
+                .0,
+            fixture
+                .create_rating(new_entry_rating(
+                    1,
+                    &place_id,
+                    RatingContext::Fairness,
+                    RatingValue::new(1),
+                ))
+                .0,
+        ];
+
+        let report = archive_ratings(&fixture, &[&rating_ids[0], "unknown", &rating_ids[1]]);
+        assert_eq!(rating_ids, report.archived);
+        assert_eq!(vec!["unknown".to_string()], report.not_found);
+        assert!(report.failed.is_empty());
+        assert!(!fixture.rating_exists(&rating_ids[0]));
+        assert!(!fixture.rating_exists(&rating_ids[1]));
+        assert!(fixture.place_exists(&place_id));
+
+        // Already archived
+        let report = archive_ratings(&fixture, &[&rating_ids[0]]);
+        assert!(report.archived.is_empty());
+        assert_eq!(vec![rating_ids[0].clone()], report.not_found);
+    }
+}
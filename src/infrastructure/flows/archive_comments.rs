@@ -5,24 +5,66 @@ pub fn archive_comments(
     connections: &sqlite::Connections,
     account_email: &str,
     ids: &[&str],
+    comment: Option<&str>,
 ) -> Result<usize> {
-    let mut repo_err = None;
-    let connection = connections.exclusive()?;
-    Ok(connection
-        .transaction::<_, diesel::result::Error, _>(|| {
-            usecases::archive_comments(&*connection, account_email, ids).map_err(|err| {
-                warn!("Failed to archive {} comments: {}", ids.len(), err);
-                repo_err = Some(err);
-                diesel::result::Error::RollbackTransaction
+    let (count, rating_ids) = {
+        let mut repo_err = None;
+        let connection = connections.exclusive()?;
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                // Only comments that have not been archived yet are affected
+                let rating_ids: Vec<_> = connection
+                    .load_comments(ids)
+                    .map_err(|err| {
+                        warn!(
+                            "Failed to load {} comments for archiving: {}",
+                            ids.len(),
+                            err
+                        );
+                        repo_err = Some(err.into());
+                        diesel::result::Error::RollbackTransaction
+                    })?
+                    .into_iter()
+                    .map(|comment| comment.rating_id)
+                    .collect();
+                let count = usecases::archive_comments(&*connection, account_email, ids, comment)
+                    .map_err(|err| {
+                    warn!("Failed to archive {} comments: {}", ids.len(), err);
+                    repo_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })?;
+                usecases::log_audit_action(
+                    &*connection,
+                    account_email,
+                    AuditAction::ArchiveComment,
+                    ids,
+                    None,
+                    comment,
+                )
+                .map_err(|err| {
+                    warn!("Failed to log archiving of {} comments: {}", ids.len(), err);
+                    repo_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })?;
+                Ok((count, rating_ids))
             })
-        })
-        .map_err(|err| {
-            if let Some(repo_err) = repo_err {
-                repo_err
-            } else {
-                RepoError::from(err).into()
-            }
-        })?)
+            .map_err(|err| {
+                if let Some(repo_err) = repo_err {
+                    repo_err
+                } else {
+                    RepoError::from(err).into()
+                }
+            })
+    }?;
+    // The comments of the ratings have changed
+    for rating_id in &rating_ids {
+        connections.live_changes().publish(
+            ChangedEntity::Rating,
+            rating_id.as_str(),
+            ChangeAction::Updated,
+        );
+    }
+    Ok(count)
 }
 
 #[cfg(test)]
@@ -34,7 +76,7 @@ mod tests {
         account_email: &str,
         ids: &[&str],
     ) -> super::Result<usize> {
-        super::archive_comments(&fixture.db_connections, account_email, ids)
+        super::archive_comments(&fixture.db_connections, account_email, ids, None)
     }
 
     #[test]
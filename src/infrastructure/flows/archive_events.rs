@@ -1,21 +1,45 @@
 use super::*;
 
 use diesel::connection::Connection;
+use ofdb_core::NotificationGateway;
 
 fn exec_archive_events(
     connections: &sqlite::Connections,
     ids: &[&str],
-    _archived_by_email: &str,
-) -> Result<usize> {
+    archived_by_email: &str,
+    comment: Option<&str>,
+) -> Result<(usize, Vec<Event>)> {
     let mut repo_err = None;
     let connection = connections.exclusive()?;
     Ok(connection
         .transaction::<_, diesel::result::Error, _>(|| {
-            usecases::archive_events(&*connection, ids).map_err(|err| {
-                warn!("Failed to archive {} events: {}", ids.len(), err);
+            // Only events that have not been archived yet are affected
+            let events = connection.get_events_chronologically(ids).map_err(|err| {
+                warn!("Failed to load {} events for archiving: {}", ids.len(), err);
+                repo_err = Some(err.into());
+                diesel::result::Error::RollbackTransaction
+            })?;
+            let count = usecases::archive_events(&*connection, ids, archived_by_email, comment)
+                .map_err(|err| {
+                    warn!("Failed to archive {} events: {}", ids.len(), err);
+                    repo_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })?;
+            let archived_ids: Vec<_> = events.iter().map(|e| e.id.as_str()).collect();
+            usecases::log_audit_action(
+                &*connection,
+                archived_by_email,
+                AuditAction::ArchiveEvent,
+                &archived_ids,
+                None,
+                comment,
+            )
+            .map_err(|err| {
+                warn!("Failed to log archiving of {} events: {}", ids.len(), err);
                 repo_err = Some(err);
                 diesel::result::Error::RollbackTransaction
-            })
+            })?;
+            Ok((count, events))
         })
         .map_err(|err| {
             if let Some(repo_err) = repo_err {
@@ -48,11 +72,35 @@ fn post_archive_events(indexer: &mut dyn EventIndexer, ids: &[&str]) -> Result<(
 pub fn archive_events(
     connections: &sqlite::Connections,
     indexer: &mut dyn EventIndexer,
+    notify: &dyn NotificationGateway,
     ids: &[&str],
     archived_by_email: &str,
+    comment: Option<&str>,
 ) -> Result<usize> {
-    let count = exec_archive_events(connections, ids, archived_by_email)?;
+    let (count, archived_events) =
+        exec_archive_events(connections, ids, archived_by_email, comment)?;
     // TODO: Move post processing to a separate task/thread that doesn't delay this request
     post_archive_events(indexer, ids)?;
+    notify_events_archived(notify, &archived_events, comment);
+    for event in &archived_events {
+        connections.live_changes().publish(
+            ChangedEntity::Event,
+            event.id.as_ref(),
+            ChangeAction::Archived,
+        );
+    }
     Ok(count)
 }
+
+fn notify_events_archived(
+    notify: &dyn NotificationGateway,
+    events: &[Event],
+    comment: Option<&str>,
+) {
+    for event in events {
+        // Inform the author instead of removing the event silently
+        if let Some(ref email) = event.created_by {
+            notify.event_archived(&[email.clone()], event, comment);
+        }
+    }
+}
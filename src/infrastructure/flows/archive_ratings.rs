@@ -1,4 +1,4 @@
-use super::*;
+use super::{reindex::*, *};
 
 use diesel::connection::Connection;
 
@@ -6,17 +6,44 @@ pub fn exec_archive_ratings(
     connections: &sqlite::Connections,
     account_email: &str,
     ids: &[&str],
+    comment: Option<&str>,
+    delta: &mut PlaceReindexDelta,
 ) -> Result<usize> {
     //TODO: check if user is allowed to archive the ratings
     let mut repo_err = None;
     let connection = connections.exclusive()?;
     Ok(connection
         .transaction::<_, diesel::result::Error, _>(|| {
-            usecases::archive_ratings(&*connection, account_email, ids).map_err(|err| {
-                warn!("Failed to archive {} ratings: {}", ids.len(), err);
+            let count = usecases::archive_ratings(&*connection, account_email, ids, comment)
+                .map_err(|err| {
+                    warn!("Failed to archive {} ratings: {}", ids.len(), err);
+                    repo_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })?;
+            usecases::log_audit_action(
+                &*connection,
+                account_email,
+                AuditAction::ArchiveRating,
+                ids,
+                None,
+                comment,
+            )
+            .map_err(|err| {
+                warn!("Failed to log archiving of {} ratings: {}", ids.len(), err);
                 repo_err = Some(err);
                 diesel::result::Error::RollbackTransaction
-            })
+            })?;
+            let place_ids = connection.load_place_ids_of_ratings(ids).map_err(|err| {
+                warn!(
+                    "Failed to load places of {} archived ratings: {}",
+                    ids.len(),
+                    err
+                );
+                repo_err = Some(err.into());
+                diesel::result::Error::RollbackTransaction
+            })?;
+            delta.extend_place_ids(place_ids);
+            Ok(count)
         })
         .map_err(|err| {
             if let Some(repo_err) = repo_err {
@@ -27,60 +54,21 @@ pub fn exec_archive_ratings(
         })?)
 }
 
-pub fn post_archive_ratings(
-    connections: &sqlite::Connections,
-    indexer: &mut dyn PlaceIndexer,
-    ids: &[&str],
-) -> Result<()> {
-    let connection = connections.shared()?;
-    let place_ids = connection.load_place_ids_of_ratings(ids)?;
-    for place_id in place_ids {
-        let (place, status) = match connection.get_place(&place_id) {
-            Ok(place) => place,
-            Err(err) => {
-                error!(
-                    "Failed to load place {} for reindexing after archiving ratings: {}",
-                    place_id, err
-                );
-                // Skip place
-                continue;
-            }
-        };
-        let ratings = match connection.load_ratings_of_place(place.id.as_ref()) {
-            Ok(ratings) => ratings,
-            Err(err) => {
-                error!(
-                    "Failed to load ratings for place {} for reindexing after archiving ratings: {}",
-                    place.id, err
-                );
-                // Skip place
-                continue;
-            }
-        };
-        if let Err(err) = usecases::reindex_place(indexer, &place, status, &ratings) {
-            error!(
-                "Failed to reindex place {} after archiving ratings: {}",
-                place.id, err
-            );
-        }
-    }
-    if let Err(err) = indexer.flush_index() {
-        error!(
-            "Failed to finish updating the search index after archiving ratings: {}",
-            err
-        );
-    }
-    Ok(())
-}
-
 pub fn archive_ratings(
     connections: &sqlite::Connections,
     indexer: &mut dyn PlaceIndexer,
     account_email: &str,
     ids: &[&str],
+    comment: Option<&str>,
 ) -> Result<usize> {
-    let count = exec_archive_ratings(connections, account_email, ids)?;
-    post_archive_ratings(connections, indexer, ids)?;
+    let mut delta = PlaceReindexDelta::default();
+    let count = exec_archive_ratings(connections, account_email, ids, comment, &mut delta)?;
+    reindex_places(connections, indexer, delta)?;
+    for id in ids {
+        connections
+            .live_changes()
+            .publish(ChangedEntity::Rating, id, ChangeAction::Archived);
+    }
     Ok(count)
 }
 
@@ -94,6 +82,7 @@ mod tests {
             &mut *fixture.search_engine.borrow_mut(),
             "scout@foo.tld",
             ids,
+            None,
         )
     }
 
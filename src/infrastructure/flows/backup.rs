@@ -0,0 +1,217 @@
+use super::*;
+use crate::infrastructure::db::tantivy;
+use chrono::Utc;
+use flate2::{write::GzEncoder, Compression};
+use ofdb_core::ObjectStorageGateway;
+use std::{collections::BTreeSet, env, fs, io::Write, path::Path};
+
+pub const DB_BACKUP_KEY_PREFIX: &str = "db/openfairdb-";
+
+const DB_BACKUP_KEY_SUFFIX: &str = ".sqlite.gz";
+
+pub const INDEX_SNAPSHOT_KEY_PREFIX: &str = "index/";
+
+// The keys of backups are ordered chronologically
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// Upload a compressed snapshot of the database to the object
+/// storage and delete all but the `retention` most recent backups.
+///
+/// Returns the key of the new backup.
+pub fn backup_database(
+    connections: &sqlite::Connections,
+    storage: &dyn ObjectStorageGateway,
+    retention: usize,
+) -> Result<String> {
+    debug_assert!(retention > 0);
+    let timestamp = Utc::now().format(BACKUP_TIMESTAMP_FORMAT).to_string();
+    let snapshot_path = env::temp_dir().join(format!("openfairdb-{}.sqlite", timestamp));
+    sqlite::vacuum_into(&*connections.shared()?, &snapshot_path)?;
+    let snapshot = fs::read(&snapshot_path);
+    fs::remove_file(&snapshot_path)?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&snapshot?)?;
+    let key = format!(
+        "{}{}{}",
+        DB_BACKUP_KEY_PREFIX, timestamp, DB_BACKUP_KEY_SUFFIX
+    );
+    storage.put_object(&key, encoder.finish()?)?;
+    info!("Uploaded database backup {}", key);
+    let deleted = delete_outdated_backups(storage, DB_BACKUP_KEY_PREFIX, retention)?;
+    if deleted > 0 {
+        info!("Deleted {} outdated database backup(s)", deleted);
+    }
+    Ok(key)
+}
+
+/// Upload a snapshot of the full-text search index to the object
+/// storage and delete all but the `retention` most recent snapshots.
+///
+/// The files of a snapshot are stored as separate objects with
+/// a common key prefix that is returned. The index might be in
+/// use by a running server.
+pub fn backup_index(
+    idx_path: &Path,
+    idx_language: Option<tantivy::TextLanguage>,
+    storage: &dyn ObjectStorageGateway,
+    retention: usize,
+) -> Result<String> {
+    debug_assert!(retention > 0);
+    let timestamp = Utc::now().format(BACKUP_TIMESTAMP_FORMAT).to_string();
+    let files = tantivy::SearchEngine::read_snapshot_in_dir(idx_path, idx_language)?;
+    let key_prefix = format!("{}{}/", INDEX_SNAPSHOT_KEY_PREFIX, timestamp);
+    // The metadata is uploaded last to complete the snapshot
+    for (path, content) in files {
+        storage.put_object(&format!("{}{}", key_prefix, path), content)?;
+    }
+    info!("Uploaded index snapshot {}", key_prefix);
+    let deleted = delete_outdated_backups(storage, INDEX_SNAPSHOT_KEY_PREFIX, retention)?;
+    if deleted > 0 {
+        info!("Deleted {} outdated index snapshot(s)", deleted);
+    }
+    Ok(key_prefix)
+}
+
+// A backup consists of all objects with the same key
+// up to the first slash after the prefix
+fn backup_name<'a>(key: &'a str, prefix: &str) -> &'a str {
+    let name = &key[prefix.len()..];
+    name.find('/').map(|end| &name[..end]).unwrap_or(name)
+}
+
+fn delete_outdated_backups(
+    storage: &dyn ObjectStorageGateway,
+    prefix: &str,
+    retention: usize,
+) -> Result<usize> {
+    let keys = storage.list_objects(prefix)?;
+    let names: BTreeSet<_> = keys.iter().map(|key| backup_name(key, prefix)).collect();
+    let outdated: BTreeSet<_> = names
+        .iter()
+        .take(names.len().saturating_sub(retention))
+        .copied()
+        .collect();
+    for key in &keys {
+        if outdated.contains(&backup_name(key, prefix)) {
+            storage.delete_object(key)?;
+        }
+    }
+    Ok(outdated.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::{cell::RefCell, collections::BTreeMap, io, io::Read};
+
+    #[derive(Default)]
+    struct InMemoryStorage {
+        objects: RefCell<BTreeMap<String, Vec<u8>>>,
+    }
+
+    impl ObjectStorageGateway for InMemoryStorage {
+        fn put_object(&self, key: &str, content: Vec<u8>) -> io::Result<()> {
+            self.objects.borrow_mut().insert(key.into(), content);
+            Ok(())
+        }
+        fn list_objects(&self, prefix: &str) -> io::Result<Vec<String>> {
+            Ok(self
+                .objects
+                .borrow()
+                .keys()
+                .filter(|key| key.starts_with(prefix))
+                .cloned()
+                .collect())
+        }
+        fn delete_object(&self, key: &str) -> io::Result<()> {
+            self.objects.borrow_mut().remove(key);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn upload_compressed_database_snapshot() {
+        let fixture = EnvFixture::new();
+        let storage = InMemoryStorage::default();
+        let key = backup_database(&fixture.db_connections, &storage, 1).unwrap();
+        assert!(key.starts_with(DB_BACKUP_KEY_PREFIX));
+        let objects = storage.objects.borrow();
+        let mut snapshot = vec![];
+        GzDecoder::new(&objects[&key][..])
+            .read_to_end(&mut snapshot)
+            .unwrap();
+        assert!(snapshot.starts_with(b"SQLite format 3\0"));
+    }
+
+    #[test]
+    fn keep_only_the_most_recent_backups() {
+        let storage = InMemoryStorage::default();
+        for key in &[
+            "db/openfairdb-20200616T120000.000Z.sqlite.gz",
+            "db/openfairdb-20200618T120000.000Z.sqlite.gz",
+            "db/openfairdb-20200617T120000.000Z.sqlite.gz",
+            "index/20200601T120000.000Z",
+        ] {
+            storage.put_object(key, vec![]).unwrap();
+        }
+        assert_eq!(
+            1,
+            delete_outdated_backups(&storage, DB_BACKUP_KEY_PREFIX, 2).unwrap()
+        );
+        assert_eq!(
+            vec![
+                "db/openfairdb-20200617T120000.000Z.sqlite.gz",
+                "db/openfairdb-20200618T120000.000Z.sqlite.gz",
+                "index/20200601T120000.000Z",
+            ],
+            storage.list_objects("").unwrap()
+        );
+        assert_eq!(
+            0,
+            delete_outdated_backups(&storage, DB_BACKUP_KEY_PREFIX, 2).unwrap()
+        );
+    }
+
+    #[test]
+    fn keep_only_the_most_recent_index_snapshots() {
+        let storage = InMemoryStorage::default();
+        for key in &[
+            "db/openfairdb-20200616T120000.000Z.sqlite.gz",
+            "index/20200616T120000.000Z/v8_default/a.idx",
+            "index/20200616T120000.000Z/v8_default/meta.json",
+            "index/20200617T120000.000Z/v8_default/b.idx",
+            "index/20200617T120000.000Z/v8_default/meta.json",
+        ] {
+            storage.put_object(key, vec![]).unwrap();
+        }
+        assert_eq!(
+            1,
+            delete_outdated_backups(&storage, INDEX_SNAPSHOT_KEY_PREFIX, 1).unwrap()
+        );
+        assert_eq!(
+            vec![
+                "db/openfairdb-20200616T120000.000Z.sqlite.gz",
+                "index/20200617T120000.000Z/v8_default/b.idx",
+                "index/20200617T120000.000Z/v8_default/meta.json",
+            ],
+            storage.list_objects("").unwrap()
+        );
+    }
+
+    #[test]
+    fn upload_index_snapshot() {
+        use crate::infrastructure::db::tantivy::SearchEngine;
+        let path = env::temp_dir().join(format!("openfairdb-test-backup-{}", std::process::id()));
+        let search_engine = SearchEngine::init_with_path(Some(&path), None).unwrap();
+        search_engine.flush_now().unwrap();
+        let storage = InMemoryStorage::default();
+        let key_prefix = backup_index(&path, None, &storage, 1).unwrap();
+        assert!(key_prefix.starts_with(INDEX_SNAPSHOT_KEY_PREFIX));
+        let keys = storage.list_objects(&key_prefix).unwrap();
+        assert!(keys.iter().any(|key| key.ends_with("/meta.json")));
+        drop(search_engine);
+        fs::remove_dir_all(&path).unwrap();
+    }
+}
@@ -0,0 +1,147 @@
+use super::{reindex::*, *};
+
+use diesel::connection::Connection;
+
+fn exec_change_rating<F>(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    rating_id: &str,
+    change: F,
+) -> Result<Rating>
+where
+    F: FnOnce(&sqlite::Connection) -> std::result::Result<Rating, Error>,
+{
+    let rating = {
+        let connection = connections.exclusive()?;
+        let mut prepare_err = None;
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                change(&*connection).map_err(|err| {
+                    warn!("Failed to change rating {}: {}", rating_id, err);
+                    prepare_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })
+            })
+            .map_err(|err| {
+                if let Some(err) = prepare_err {
+                    err
+                } else {
+                    RepoError::from(err).into()
+                }
+            })
+    }?;
+
+    // The average ratings of the place have changed
+    let mut delta = PlaceReindexDelta::default();
+    delta.extend_place_ids(vec![rating.place_id.as_str()]);
+    reindex_places(connections, indexer, delta)?;
+
+    Ok(rating)
+}
+
+/// Change a rating by its author and reindex the rated place.
+pub fn update_rating(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    account_email: &str,
+    rating_id: &str,
+    update: usecases::RatingUpdate,
+) -> Result<Rating> {
+    let rating = exec_change_rating(connections, indexer, rating_id, |connection| {
+        usecases::update_rating(connection, account_email, rating_id, update)
+    })?;
+    connections
+        .live_changes()
+        .publish(ChangedEntity::Rating, rating_id, ChangeAction::Updated);
+    Ok(rating)
+}
+
+/// Retract a rating by its author and reindex the rated place.
+pub fn retract_rating(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    account_email: &str,
+    rating_id: &str,
+) -> Result<Rating> {
+    let rating = exec_change_rating(connections, indexer, rating_id, |connection| {
+        usecases::retract_rating(connection, account_email, rating_id)
+    })?;
+    connections
+        .live_changes()
+        .publish(ChangedEntity::Rating, rating_id, ChangeAction::Archived);
+    Ok(rating)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn update_and_retract_own_rating() {
+        let fixture = EnvFixture::new();
+        for email in &["author@example.com", "other@example.com"] {
+            fixture.create_user(
+                usecases::NewUser {
+                    email: (*email).into(),
+                    password: "secret".into(),
+                },
+                None,
+            );
+        }
+        let place_id = fixture.create_place(0.into(), None);
+        let (rating_id, comment_id) = flows::create_rating(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            new_entry_rating(0, &place_id, RatingContext::Fairness, RatingValue::new(-1)),
+            Some("author@example.com"),
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            Some("author@example.com"),
+            fixture
+                .try_get_rating(&rating_id)
+                .unwrap()
+                .created_by
+                .as_deref()
+        );
+
+        let update = || usecases::RatingUpdate {
+            title: "Changed".into(),
+            value: RatingValue::new(2),
+        };
+        assert!(flows::update_rating(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "other@example.com",
+            &rating_id,
+            update(),
+        )
+        .is_err());
+        flows::update_rating(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "author@example.com",
+            &rating_id,
+            update(),
+        )
+        .unwrap();
+        let rating = fixture.try_get_rating(&rating_id).unwrap();
+        assert_eq!("Changed", rating.title);
+        assert_eq!(RatingValue::new(2), rating.value);
+        let fairness = fixture.query_places_by_tag("tag_0")[0].ratings.fairness;
+        assert!((f64::from(fairness) - 2.0).abs() < f64::EPSILON);
+
+        flows::retract_rating(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "author@example.com",
+            &rating_id,
+        )
+        .unwrap();
+        assert!(!fixture.rating_exists(&rating_id));
+        assert!(!fixture.comment_exists(&comment_id));
+        let fairness = fixture.query_places_by_tag("tag_0")[0].ratings.fairness;
+        assert!(f64::from(fairness).abs() < f64::EPSILON);
+    }
+}
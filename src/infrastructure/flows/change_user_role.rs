@@ -17,7 +17,20 @@ pub fn change_user_role(
                     repo_err = Some(err);
                     diesel::result::Error::RollbackTransaction
                 },
+            )?;
+            usecases::log_audit_action(
+                &*connection,
+                account_email,
+                AuditAction::ChangeUserRole,
+                &[user_email],
+                Some(format!("{:?}", role).to_lowercase().as_str()),
+                None,
             )
+            .map_err(|err| {
+                warn!("Failed to log role change of {}: {}", user_email, err);
+                repo_err = Some(err);
+                diesel::result::Error::RollbackTransaction
+            })
         })
         .map_err(|err| {
             if let Some(repo_err) = repo_err {
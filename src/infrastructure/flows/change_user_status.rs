@@ -0,0 +1,250 @@
+use super::{reindex::*, *};
+use diesel::connection::Connection;
+
+pub fn deactivate_user(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    account_email: &str,
+    user_email: &str,
+    comment: Option<String>,
+) -> Result<()> {
+    let mut delta = PlaceReindexDelta::default();
+    let usecases::DeactivatedUserPlaces {
+        archived_place_ids,
+        restored_places,
+    } = {
+        let mut repo_err = None;
+        let connection = connections.exclusive()?;
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let places = usecases::deactivate_user(
+                    &*connection,
+                    account_email,
+                    user_email,
+                    comment.clone(),
+                )
+                .map_err(|err| {
+                    warn!("Failed to deactivate user {}: {}", user_email, err);
+                    repo_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })?;
+                usecases::log_audit_action(
+                    &*connection,
+                    account_email,
+                    AuditAction::DeactivateUser,
+                    &[user_email],
+                    None,
+                    comment.as_deref(),
+                )
+                .map_err(|err| {
+                    warn!("Failed to log deactivation of user {}: {}", user_email, err);
+                    repo_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })?;
+                Ok(places)
+            })
+            .map_err(|err| {
+                if let Some(repo_err) = repo_err {
+                    repo_err
+                } else {
+                    RepoError::from(err).into()
+                }
+            })?
+    };
+    for id in &archived_place_ids {
+        connections.live_changes().publish(
+            ChangedEntity::Place,
+            id.as_str(),
+            ChangeAction::Archived,
+        );
+        connections
+            .place_changes()
+            .publish(id.as_str(), PlaceChange::Status(ReviewStatus::Archived));
+    }
+    for place in &restored_places {
+        connections.live_changes().publish(
+            ChangedEntity::Place,
+            place.id.as_ref(),
+            ChangeAction::Updated,
+        );
+        connections.place_changes().publish(
+            place.id.as_ref(),
+            PlaceChange::Revision(place.revision.into()),
+        );
+    }
+    delta.extend_place_ids(archived_place_ids);
+    delta.extend_place_ids(restored_places.iter().map(|place| place.id.as_str()));
+    reindex_places(connections, indexer, delta)?;
+    Ok(())
+}
+
+pub fn reactivate_user(
+    connections: &sqlite::Connections,
+    account_email: &str,
+    user_email: &str,
+    comment: Option<String>,
+) -> Result<()> {
+    let mut repo_err = None;
+    let connection = connections.exclusive()?;
+    Ok(connection
+        .transaction::<_, diesel::result::Error, _>(|| {
+            usecases::reactivate_user(&*connection, account_email, user_email, comment.clone())
+                .map_err(|err| {
+                    warn!("Failed to reactivate user {}: {}", user_email, err);
+                    repo_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })?;
+            usecases::log_audit_action(
+                &*connection,
+                account_email,
+                AuditAction::ReactivateUser,
+                &[user_email],
+                None,
+                comment.as_deref(),
+            )
+            .map_err(|err| {
+                warn!("Failed to log reactivation of user {}: {}", user_email, err);
+                repo_err = Some(err);
+                diesel::result::Error::RollbackTransaction
+            })
+        })
+        .map_err(|err| {
+            if let Some(repo_err) = repo_err {
+                repo_err
+            } else {
+                RepoError::from(err).into()
+            }
+        })?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    fn deactivate_user(
+        fixture: &EnvFixture,
+        account_email: &str,
+        user_email: &str,
+    ) -> super::Result<()> {
+        super::deactivate_user(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            account_email,
+            user_email,
+            None,
+        )
+    }
+
+    fn reactivate_user(
+        fixture: &EnvFixture,
+        account_email: &str,
+        user_email: &str,
+    ) -> super::Result<()> {
+        super::reactivate_user(&fixture.db_connections, account_email, user_email, None)
+    }
+
+    #[test]
+    fn should_archive_pending_places_of_deactivated_users() {
+        let fixture = EnvFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "user@bar.tld".into(),
+                password: "123456".into(),
+            },
+            Some(Role::User),
+        );
+        fixture.create_user(
+            usecases::NewUser {
+                email: "admin@foo.tld".into(),
+                password: "123456".into(),
+            },
+            Some(Role::Admin),
+        );
+        let pending_place_id = fixture.create_place(0.into(), Some("user@bar.tld"));
+        let anonymous_place_id = fixture.create_place(1.into(), None);
+        assert!(fixture.place_exists(&pending_place_id));
+
+        assert!(deactivate_user(&fixture, "admin@foo.tld", "user@bar.tld").is_ok());
+        assert!(!fixture.place_exists(&pending_place_id));
+        assert!(fixture.place_exists(&anonymous_place_id));
+        assert!(!fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .is_user_active("user@bar.tld")
+            .unwrap());
+
+        assert!(reactivate_user(&fixture, "admin@foo.tld", "user@bar.tld").is_ok());
+        assert!(fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .is_user_active("user@bar.tld")
+            .unwrap());
+        // Archived places are not restored automatically
+        assert!(!fixture.place_exists(&pending_place_id));
+    }
+
+    #[test]
+    fn should_restore_places_that_have_been_edited_by_deactivated_users() {
+        let fixture = EnvFixture::new();
+        for (email, role) in &[
+            ("a@bar.tld", Role::User),
+            ("b@bar.tld", Role::User),
+            ("admin@foo.tld", Role::Admin),
+        ] {
+            fixture.create_user(
+                usecases::NewUser {
+                    email: (*email).into(),
+                    password: "123456".into(),
+                },
+                Some(*role),
+            );
+        }
+        let place_id = fixture.create_place(0.into(), Some("a@bar.tld"));
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .review_places(
+                &[place_id.as_str()],
+                ReviewStatus::Confirmed,
+                &ActivityLog {
+                    activity: Activity::now(Some("admin@foo.tld".into())),
+                    context: None,
+                    comment: None,
+                },
+            )
+            .unwrap();
+        let (original_place, _) = fixture.try_get_place(&place_id).unwrap();
+
+        let mut edited_place = original_place.clone();
+        edited_place.revision = original_place.revision.next();
+        edited_place.title = "edited".into();
+        edited_place.created = Activity::now(Some("b@bar.tld".into()));
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .create_or_update_place(edited_place)
+            .unwrap();
+        assert_eq!(
+            ReviewStatus::Created,
+            fixture.try_get_place(&place_id).unwrap().1
+        );
+
+        assert!(deactivate_user(&fixture, "admin@foo.tld", "b@bar.tld").is_ok());
+        assert!(fixture.place_exists(&place_id));
+        let (current_place, status) = fixture.try_get_place(&place_id).unwrap();
+        assert_eq!(ReviewStatus::Confirmed, status);
+        assert_eq!(Revision::from(2), current_place.revision);
+        assert_eq!(original_place.title, current_place.title);
+        assert_eq!(original_place.tags, current_place.tags);
+
+        let query = IndexQuery {
+            text: Some(original_place.title),
+            ..Default::default()
+        };
+        assert_eq!(1, fixture.query_places(&query).len());
+    }
+}
@@ -1,4 +1,4 @@
-use super::*;
+use super::{webhooks::*, *};
 use crate::core::error::RepoError;
 use diesel::Connection;
 use ofdb_core::NotificationGateway;
@@ -47,7 +47,6 @@ pub fn create_event(
     }?;
 
     // Index newly added event
-    // TODO: Move to a separate task/thread that doesn't delay this request
     if let Err(err) = usecases::index_event(indexer, &event).and_then(|_| indexer.flush_index()) {
         error!("Failed to index newly added event {}: {}", event.id, err);
     }
@@ -61,20 +60,31 @@ pub fn create_event(
         );
     }
 
+    notify_webhooks(
+        connections,
+        WebhookEvent::EventCreated,
+        usecases::event_webhook_data(&event),
+    );
+    connections.live_changes().publish(
+        ChangedEntity::Event,
+        event.id.as_ref(),
+        ChangeAction::Created,
+    );
+
     Ok(event)
 }
 
-fn notify_event_created(
+pub(super) fn notify_event_created(
     connections: &sqlite::Connections,
     notify: &dyn NotificationGateway,
     event: &Event,
 ) -> Result<()> {
-    if let Some(ref location) = event.location {
-        let email_addresses = {
-            let conn = connections.shared()?;
-            usecases::email_addresses_by_coordinate(&*conn, location.pos)?
-        };
-        notify.event_created(&email_addresses, event);
-    }
+    let email_addresses = if let Some(ref location) = event.location {
+        let conn = connections.shared()?;
+        usecases::email_addresses_by_coordinate(&*conn, location.pos)?
+    } else {
+        vec![]
+    };
+    notify.event_created(&email_addresses, event);
     Ok(())
 }
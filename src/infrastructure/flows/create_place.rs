@@ -1,4 +1,4 @@
-use super::*;
+use super::{webhooks::*, *};
 use crate::core::error::RepoError;
 use diesel::Connection;
 use ofdb_core::NotificationGateway;
@@ -41,7 +41,6 @@ pub fn create_place(
     }?;
 
     // Index newly added place
-    // TODO: Move to a separate task/thread that doesn't delay this request
     if let Err(err) = usecases::reindex_place(indexer, &place, ReviewStatus::Created, &ratings)
         .and_then(|_| indexer.flush_index())
     {
@@ -57,6 +56,17 @@ pub fn create_place(
         );
     }
 
+    notify_webhooks(
+        connections,
+        WebhookEvent::PlaceCreated,
+        usecases::place_webhook_data(&place),
+    );
+    connections.live_changes().publish(
+        ChangedEntity::Place,
+        place.id.as_ref(),
+        ChangeAction::Created,
+    );
+
     Ok(place)
 }
 
@@ -66,9 +76,15 @@ fn notify_place_added(
     place: &Place,
 ) -> Result<()> {
     let (email_addresses, all_categories) = {
-        let connection = connections.shared()?;
-        let email_addresses =
-            usecases::email_addresses_by_coordinate(&*connection, place.location.pos)?;
+        let connection = connections.exclusive()?;
+        let email_addresses = usecases::email_addresses_by_place(&*connection, place)?;
+        // Subscribers who prefer a digest are notified later
+        let email_addresses = usecases::enqueue_digest_notifications(
+            &*connection,
+            email_addresses,
+            NotificationKind::PlaceAdded,
+            place,
+        )?;
         let all_categories = connection.all_categories()?;
         (email_addresses, all_categories)
     };
@@ -1,4 +1,4 @@
-use super::*;
+use super::{webhooks::*, *};
 
 use diesel::connection::Connection;
 
@@ -6,23 +6,62 @@ pub fn create_rating(
     connections: &sqlite::Connections,
     indexer: &mut dyn PlaceIndexer,
     rate_entry: usecases::NewPlaceRating,
+    author_email: Option<&str>,
+    login_required: bool,
 ) -> Result<(String, String)> {
-    // Add new rating to existing entry
-    let (rating_id, comment_id, place, status, ratings) = {
+    let mut ids = create_ratings(
+        connections,
+        indexer,
+        vec![rate_entry],
+        author_email,
+        login_required,
+    )?;
+    debug_assert_eq!(1, ids.len());
+    Ok(ids.remove(0))
+}
+
+/// Add multiple ratings of the same place at once.
+///
+/// Returns the ids of the new ratings and their comments.
+pub fn create_ratings(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    mut rate_entries: Vec<usecases::NewPlaceRating>,
+    author_email: Option<&str>,
+    login_required: bool,
+) -> Result<Vec<(String, String)>> {
+    // Ratings are only attributed to the logged in user and
+    // never to a user that has been submitted by the client
+    for rate_entry in &mut rate_entries {
+        rate_entry.user = author_email.map(ToOwned::to_owned);
+    }
+    // Add new ratings to existing entry
+    let (ids, place, status, ratings) = {
         let connection = connections.exclusive()?;
         let mut prepare_err = None;
         connection
             .transaction::<_, diesel::result::Error, _>(|| {
-                match usecases::prepare_new_rating(&*connection, rate_entry) {
-                    Ok(storable) => {
-                        let rating_id = storable.rating_id().to_owned();
-                        let comment_id = storable.comment_id().to_owned();
+                if login_required {
+                    if let Err(err) = usecases::authorize_rating_author(&*connection, author_email)
+                    {
+                        prepare_err = Some(err);
+                        return Err(diesel::result::Error::RollbackTransaction);
+                    }
+                }
+                match usecases::prepare_new_ratings(&*connection, rate_entries) {
+                    Ok(storables) => {
+                        let ids: Vec<(String, String)> = storables
+                            .iter()
+                            .map(|s| (s.rating_id().to_owned(), s.comment_id().to_owned()))
+                            .collect();
                         let (place, status, ratings) =
-                            usecases::store_new_rating(&*connection, storable).map_err(|err| {
-                                warn!("Failed to store new rating for entry: {}", err);
-                                diesel::result::Error::RollbackTransaction
-                            })?;
-                        Ok((rating_id, comment_id, place, status, ratings))
+                            usecases::store_new_ratings(&*connection, storables).map_err(
+                                |err| {
+                                    warn!("Failed to store new ratings for entry: {}", err);
+                                    diesel::result::Error::RollbackTransaction
+                                },
+                            )?;
+                        Ok((ids, place, status, ratings))
                     }
                     Err(err) => {
                         prepare_err = Some(err);
@@ -39,16 +78,44 @@ pub fn create_rating(
             })
     }?;
 
-    // Reindex entry after adding the new rating
-    // TODO: Move to a separate task/thread that doesn't delay this request
+    // Reindex entry after adding the new ratings
     if let Err(err) = usecases::reindex_place(indexer, &place, status, &ratings)
         .and_then(|_| indexer.flush_index())
     {
         error!(
-            "Failed to reindex place {} after adding a new rating: {}",
+            "Failed to reindex place {} after adding new ratings: {}",
             place.id, err
         );
     }
 
-    Ok((rating_id, comment_id))
+    for rating in ratings
+        .iter()
+        .filter(|rating| ids.iter().any(|(id, _)| rating.id.as_str() == id))
+    {
+        notify_webhooks(
+            connections,
+            WebhookEvent::RatingAdded,
+            usecases::rating_webhook_data(rating),
+        );
+    }
+    for (rating_id, comment_id) in &ids {
+        connections
+            .live_changes()
+            .publish(ChangedEntity::Rating, rating_id, ChangeAction::Created);
+        connections.place_changes().publish(
+            place.id.as_ref(),
+            PlaceChange::Rating {
+                id: rating_id.clone(),
+            },
+        );
+        connections.place_changes().publish(
+            place.id.as_ref(),
+            PlaceChange::Comment {
+                id: comment_id.clone(),
+                rating_id: rating_id.clone(),
+            },
+        );
+    }
+
+    Ok(ids)
 }
@@ -0,0 +1,209 @@
+use super::{reindex::*, *};
+
+pub fn save_curated_tag(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    account_email: &str,
+    tag: &str,
+    boost: f64,
+) -> Result<()> {
+    let place_ids = {
+        let connection = connections.exclusive()?;
+        let place_ids = usecases::save_curated_tag(&*connection, account_email, tag, boost)?;
+        usecases::log_audit_action(
+            &*connection,
+            account_email,
+            AuditAction::SaveCuratedTag,
+            &[tag],
+            Some(boost.to_string().as_str()),
+            None,
+        )?;
+        place_ids
+    };
+    reindex_curated_places(connections, indexer, place_ids)
+}
+
+pub fn delete_curated_tag(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    account_email: &str,
+    tag: &str,
+) -> Result<()> {
+    let place_ids = {
+        let connection = connections.exclusive()?;
+        let place_ids = usecases::delete_curated_tag(&*connection, account_email, tag)?;
+        usecases::log_audit_action(
+            &*connection,
+            account_email,
+            AuditAction::DeleteCuratedTag,
+            &[tag],
+            None,
+            None,
+        )?;
+        place_ids
+    };
+    reindex_curated_places(connections, indexer, place_ids)
+}
+
+pub fn save_curated_org(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    account_email: &str,
+    org_id: &str,
+    boost: f64,
+) -> Result<()> {
+    let place_ids = {
+        let connection = connections.exclusive()?;
+        let place_ids = usecases::save_curated_org(&*connection, account_email, org_id, boost)?;
+        usecases::log_audit_action(
+            &*connection,
+            account_email,
+            AuditAction::SaveCuratedOrg,
+            &[org_id],
+            Some(boost.to_string().as_str()),
+            None,
+        )?;
+        place_ids
+    };
+    reindex_curated_places(connections, indexer, place_ids)
+}
+
+pub fn delete_curated_org(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    account_email: &str,
+    org_id: &str,
+) -> Result<()> {
+    let place_ids = {
+        let connection = connections.exclusive()?;
+        let place_ids = usecases::delete_curated_org(&*connection, account_email, org_id)?;
+        usecases::log_audit_action(
+            &*connection,
+            account_email,
+            AuditAction::DeleteCuratedOrg,
+            &[org_id],
+            None,
+            None,
+        )?;
+        place_ids
+    };
+    reindex_curated_places(connections, indexer, place_ids)
+}
+
+fn reindex_curated_places(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    place_ids: Vec<Id>,
+) -> Result<()> {
+    let curated_tags = usecases::load_curated_tag_boosts(&*connections.shared()?)?;
+    indexer.set_curated_tags(&curated_tags);
+    let mut delta = PlaceReindexDelta::default();
+    delta.extend_place_ids(place_ids);
+    reindex_places(connections, indexer, delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    fn new_place(title: &str, tag: &str) -> NewPlace {
+        NewPlace {
+            pos: MapPoint::from_lat_lng_deg(1.0, 1.0),
+            title: title.into(),
+            description: "Organic food".into(),
+            categories: vec![],
+            tags: vec![tag.into()],
+        }
+    }
+
+    fn search_organic(fixture: &EnvFixture) -> Vec<String> {
+        fixture
+            .query_places(&IndexQuery {
+                text: Some("organic".into()),
+                ..Default::default()
+            })
+            .into_iter()
+            .map(|p| p.id)
+            .collect()
+    }
+
+    #[test]
+    fn prefer_places_with_curated_tags() {
+        let fixture = EnvFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "admin@foo.tld".into(),
+                password: "123456".into(),
+            },
+            Some(Role::Admin),
+        );
+        // The first place matches the search terms better
+        let a = fixture.create_place(new_place("Organic shop", "a"), None);
+        let b = fixture.create_place(new_place("Shop", "b"), None);
+        assert_eq!(vec![a.clone(), b.clone()], search_organic(&fixture));
+
+        flows::save_curated_tag(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "admin@foo.tld",
+            "b",
+            5.0,
+        )
+        .unwrap();
+        assert_eq!(vec![b.clone(), a.clone()], search_organic(&fixture));
+
+        flows::delete_curated_tag(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "admin@foo.tld",
+            "b",
+        )
+        .unwrap();
+        assert_eq!(vec![a, b], search_organic(&fixture));
+    }
+
+    #[test]
+    fn prefer_places_of_curated_organizations() {
+        let fixture = EnvFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "admin@foo.tld".into(),
+                password: "123456".into(),
+            },
+            Some(Role::Admin),
+        );
+        // The places are created before their tag is owned
+        let a = fixture.create_place(new_place("Organic shop", "a"), None);
+        let b = fixture.create_place(new_place("Shop", "b"), None);
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .create_org(Organization {
+                id: "org".into(),
+                name: "Org".into(),
+                owned_tags: vec!["b".into()],
+            })
+            .unwrap();
+        assert_eq!(vec![a.clone(), b.clone()], search_organic(&fixture));
+
+        flows::save_curated_org(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "admin@foo.tld",
+            "org",
+            5.0,
+        )
+        .unwrap();
+        assert_eq!(vec![b.clone(), a.clone()], search_organic(&fixture));
+
+        flows::delete_curated_org(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "admin@foo.tld",
+            "org",
+        )
+        .unwrap();
+        assert_eq!(vec![a, b], search_organic(&fixture));
+    }
+}
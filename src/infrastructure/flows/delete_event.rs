@@ -0,0 +1,9 @@
+use super::*;
+
+pub fn delete_event(connections: &sqlite::Connections, token: &str, id: &EventId) -> Result<()> {
+    usecases::delete_event(&mut *connections.exclusive()?, token, id)?;
+    connections
+        .live_changes()
+        .publish(ChangedEntity::Event, id.as_str(), ChangeAction::Deleted);
+    Ok(())
+}
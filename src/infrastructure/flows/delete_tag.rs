@@ -0,0 +1,119 @@
+use super::{reindex::*, *};
+
+use diesel::connection::Connection;
+
+/// Delete a tag from all places and events.
+///
+/// In dry-run mode nothing is modified, logged or reindexed.
+pub fn delete_tag<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    account_email: &str,
+    tag: &str,
+    dry_run: bool,
+) -> Result<usecases::DeletedTag> {
+    let deleted = {
+        let connection = connections.exclusive()?;
+        let mut usecase_err = None;
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let deleted = usecases::delete_tag(&*connection, account_email, tag, dry_run)
+                    .map_err(|err| {
+                        warn!("Failed to delete tag {}: {}", tag, err);
+                        usecase_err = Some(err);
+                        diesel::result::Error::RollbackTransaction
+                    })?;
+                if dry_run {
+                    return Ok(deleted);
+                }
+                usecases::log_audit_action(
+                    &*connection,
+                    account_email,
+                    AuditAction::DeleteTag,
+                    &[tag],
+                    None,
+                    None,
+                )
+                .map_err(|err| {
+                    warn!("Failed to log deletion of tag {}: {}", tag, err);
+                    usecase_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })?;
+                Ok(deleted)
+            })
+            .map_err(|err| {
+                if let Some(err) = usecase_err {
+                    err
+                } else {
+                    RepoError::from(err).into()
+                }
+            })
+    }?;
+
+    if !dry_run {
+        reindex_tagged_places_and_events(
+            connections,
+            indexer,
+            deleted.place_ids.clone(),
+            &deleted.event_ids,
+        )?;
+        publish_tagged_places_and_events(connections, &deleted.place_ids, &deleted.event_ids);
+    }
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn delete_tag_and_reindex_places() {
+        let fixture = EnvFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "admin@example.com".into(),
+                password: "secret".into(),
+            },
+            Some(Role::Admin),
+        );
+        let place_id = fixture.create_place(0.into(), None);
+        assert_eq!(1, fixture.query_places_by_tag("tag_0").len());
+
+        let deleted = flows::delete_tag(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "admin@example.com",
+            "tag_0",
+            true,
+        )
+        .unwrap();
+        assert_eq!(vec![Id::from(place_id.as_str())], deleted.place_ids);
+        assert_eq!(1, fixture.query_places_by_tag("tag_0").len());
+
+        flows::delete_tag(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "admin@example.com",
+            "tag_0",
+            false,
+        )
+        .unwrap();
+        let (place, status) = fixture.try_get_place(&place_id).unwrap();
+        assert!(place.tags.iter().all(|t| t != "tag_0"));
+        assert_eq!(Revision::from(1), place.revision);
+        assert_eq!(ReviewStatus::Created, status);
+        assert!(fixture.query_places_by_tag("tag_0").is_empty());
+        let tags = fixture.db_connections.shared().unwrap().all_tags().unwrap();
+        assert!(tags.iter().all(|t| t.id != "tag_0"));
+
+        let audit_log = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_audit_log(&Default::default(), &Default::default())
+            .unwrap();
+        assert_eq!(1, audit_log.len());
+        assert_eq!(AuditAction::DeleteTag, audit_log[0].action);
+        assert_eq!("tag_0", audit_log[0].item);
+    }
+}
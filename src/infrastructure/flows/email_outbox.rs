@@ -0,0 +1,179 @@
+use super::*;
+use ofdb_core::EmailTransport;
+
+// The number of pending e-mails that are attempted at once
+const DUE_OUTGOING_EMAILS_LIMIT: u64 = 100;
+
+fn enqueue_outgoing_emails(
+    connections: &sqlite::Connections,
+    recipients: &[Email],
+    subject: &str,
+    body: &str,
+) -> Result<usize> {
+    let connection = connections.exclusive()?;
+    Ok(usecases::enqueue_emails(
+        &*connection,
+        recipients,
+        subject,
+        body,
+    )?)
+}
+
+/// Put the e-mails into the outbox instead of sending them directly.
+///
+/// Failures are only logged and must not affect the operation
+/// that caused the e-mails.
+pub fn enqueue_emails(
+    connections: &sqlite::Connections,
+    recipients: &[Email],
+    subject: &str,
+    body: &str,
+) {
+    match enqueue_outgoing_emails(connections, recipients, subject, body) {
+        Ok(count) => {
+            debug!("Enqueued {} e-mail(s) with subject '{}'", count, subject);
+        }
+        Err(err) => {
+            error!(
+                "Failed to enqueue e-mails to {:?} with subject '{}': {}",
+                recipients, subject, err
+            );
+        }
+    }
+}
+
+/// Attempt to send all pending e-mails that are due.
+///
+/// The database connection is not held while sending.
+/// Returns the number of e-mails that have been sent.
+pub fn send_due_emails(
+    connections: &sqlite::Connections,
+    transport: &dyn EmailTransport,
+) -> Result<usize> {
+    let emails = connections
+        .shared()?
+        .load_due_outgoing_emails(TimestampMs::now(), DUE_OUTGOING_EMAILS_LIMIT)?;
+    let mut sent = 0;
+    for email in emails {
+        let attempted_at = TimestampMs::now();
+        let error = match transport.send_email(&email.recipient, &email.subject, &email.body) {
+            Ok(()) => None,
+            Err(err) => {
+                warn!(
+                    "Failed to send e-mail {} to {}: {}",
+                    email.id, email.recipient, err
+                );
+                Some(err.to_string())
+            }
+        };
+        let email = usecases::finish_email_delivery_attempt(
+            &*connections.exclusive()?,
+            email,
+            error,
+            attempted_at,
+        )?;
+        if email.status == OutgoingEmailStatus::Sent {
+            sent += 1;
+        }
+    }
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use super::*;
+    use std::{
+        cell::RefCell,
+        io::{Error, ErrorKind},
+    };
+
+    #[derive(Default)]
+    struct MockTransport {
+        fail: bool,
+        sent: RefCell<Vec<(Email, String)>>,
+    }
+
+    impl EmailTransport for MockTransport {
+        fn send_email(&self, recipient: &Email, subject: &str, _: &str) -> std::io::Result<()> {
+            if self.fail {
+                return Err(Error::new(ErrorKind::Other, "connection refused"));
+            }
+            self.sent
+                .borrow_mut()
+                .push((recipient.clone(), subject.to_owned()));
+            Ok(())
+        }
+    }
+
+    fn outbox(fixture: &EnvFixture) -> Vec<OutgoingEmail> {
+        fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_outgoing_emails(None, &Default::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn send_enqueued_emails() {
+        let fixture = EnvFixture::new();
+        flows::enqueue_emails(
+            &fixture.db_connections,
+            &[Email::from("a@example.com"), Email::from("b@example.com")],
+            "Hello",
+            "World",
+        );
+        let transport = MockTransport::default();
+        assert_eq!(
+            2,
+            flows::send_due_emails(&fixture.db_connections, &transport).unwrap()
+        );
+        assert_eq!(
+            vec![
+                (Email::from("a@example.com"), "Hello".to_owned()),
+                (Email::from("b@example.com"), "Hello".to_owned()),
+            ],
+            *transport.sent.borrow()
+        );
+        // Nothing left to send
+        assert_eq!(
+            0,
+            flows::send_due_emails(&fixture.db_connections, &transport).unwrap()
+        );
+        // Sent e-mails are removed from the outbox
+        assert!(outbox(&fixture).is_empty());
+    }
+
+    #[test]
+    fn retry_failed_emails_later() {
+        let fixture = EnvFixture::new();
+        flows::enqueue_emails(
+            &fixture.db_connections,
+            &[Email::from("a@example.com")],
+            "Hello",
+            "World",
+        );
+        let transport = MockTransport {
+            fail: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            0,
+            flows::send_due_emails(&fixture.db_connections, &transport).unwrap()
+        );
+        let emails = outbox(&fixture);
+        assert_eq!(OutgoingEmailStatus::Pending, emails[0].status);
+        assert_eq!(1, emails[0].attempts);
+        assert_eq!(Some("connection refused"), emails[0].last_error.as_deref());
+        assert!(emails[0].next_attempt_at.unwrap() > TimestampMs::now());
+
+        // Not due again immediately
+        let transport = MockTransport::default();
+        assert_eq!(
+            0,
+            flows::send_due_emails(&fixture.db_connections, &transport).unwrap()
+        );
+        assert!(transport.sent.borrow().is_empty());
+    }
+}
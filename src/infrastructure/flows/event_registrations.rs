@@ -0,0 +1,145 @@
+use super::*;
+use crate::core::error::RepoError;
+use diesel::Connection;
+use ofdb_core::NotificationGateway;
+
+/// Register an attendee for an event and send the confirmation
+/// that contains the link for cancelling the registration.
+pub fn register_for_event(
+    connections: &sqlite::Connections,
+    notify: &dyn NotificationGateway,
+    event_id: &EventId,
+    email: &str,
+) -> Result<EventRegistration> {
+    let (event, registration) = {
+        let connection = connections.exclusive()?;
+        let mut prepare_err = None;
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                usecases::register_for_event(&*connection, event_id, email).map_err(|err| {
+                    warn!("Failed to register for event {}: {}", event_id, err);
+                    prepare_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })
+            })
+            .map_err(|err| {
+                if let Some(err) = prepare_err {
+                    err
+                } else {
+                    RepoError::from(err).into()
+                }
+            })
+    }?;
+
+    notify.event_registration_created(&event, &registration);
+
+    Ok(registration)
+}
+
+/// Cancel a registration and notify the attendee who took over
+/// the place from the waitlist.
+pub fn cancel_event_registration(
+    connections: &sqlite::Connections,
+    notify: &dyn NotificationGateway,
+    token: &str,
+) -> Result<EventRegistration> {
+    let usecases::CancelledEventRegistration {
+        event,
+        cancelled,
+        promoted,
+    } = {
+        let connection = connections.exclusive()?;
+        let mut prepare_err = None;
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                usecases::cancel_event_registration(&*connection, token).map_err(|err| {
+                    warn!("Failed to cancel event registration: {}", err);
+                    prepare_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })
+            })
+            .map_err(|err| {
+                if let Some(err) = prepare_err {
+                    err
+                } else {
+                    RepoError::from(err).into()
+                }
+            })
+    }?;
+
+    if let Some(ref registration) = promoted {
+        notify.event_registration_promoted(&event, registration);
+    }
+
+    Ok(cancelled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn register_and_cancel_with_waitlist() {
+        let fixture = EnvFixture::new();
+        let event_id = flows::create_event(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            None,
+            usecases::NewEvent {
+                title: "Meetup".into(),
+                start: chrono::Utc::now().naive_utc().timestamp(),
+                email: Some("organizer@example.com".into()),
+                created_by: Some("organizer@example.com".into()),
+                registration: Some("email".into()),
+                capacity: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .id;
+
+        let first = flows::register_for_event(
+            &fixture.db_connections,
+            &fixture.notify,
+            &event_id.as_str().into(),
+            "first@example.com",
+        )
+        .unwrap();
+        assert_eq!(EventRegistrationStatus::Confirmed, first.status);
+        let second = flows::register_for_event(
+            &fixture.db_connections,
+            &fixture.notify,
+            &event_id.as_str().into(),
+            "second@example.com",
+        )
+        .unwrap();
+        assert_eq!(EventRegistrationStatus::Waitlisted, second.status);
+
+        let cancelled = flows::cancel_event_registration(
+            &fixture.db_connections,
+            &fixture.notify,
+            &first.email_nonce.encode_to_string(),
+        )
+        .unwrap();
+        assert_eq!(EventRegistrationStatus::Cancelled, cancelled.status);
+
+        let registrations = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_event_registrations(&event_id.as_str().into())
+            .unwrap();
+        assert_eq!(2, registrations.len());
+        assert_eq!(EventRegistrationStatus::Cancelled, registrations[0].status);
+        assert_eq!("second@example.com", registrations[1].email_nonce.email);
+        assert_eq!(EventRegistrationStatus::Confirmed, registrations[1].status);
+
+        assert!(flows::cancel_event_registration(
+            &fixture.db_connections,
+            &fixture.notify,
+            "invalid"
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use super::*;
+use crate::infrastructure::db::tantivy;
+
+#[derive(Debug, Default)]
+pub struct GlobalSearchResults {
+    pub places: Vec<IndexedPlace>,
+    pub events: Vec<Event>,
+    pub tags: Vec<String>,
+}
+
+/// Search for places, events, and tags at once.
+pub fn global_search(
+    connections: &sqlite::Connections,
+    search_engine: &tantivy::SearchEngine,
+    txt: &str,
+    limit: usize,
+) -> Result<GlobalSearchResults> {
+    if txt.trim().is_empty() {
+        return Ok(Default::default());
+    }
+    let query = usecases::EventQuery {
+        text: Some(txt.to_owned()),
+        limit: Some(limit),
+        ..Default::default()
+    };
+    let events = usecases::query_events(&*connections.shared()?, search_engine, query)?;
+    let tags = usecases::search_tags(search_engine, txt, limit)?;
+    let places = usecases::global_search(search_engine, txt, limit)?;
+    Ok(GlobalSearchResults {
+        places,
+        events,
+        tags,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn search_places_events_and_tags() {
+        let fixture = EnvFixture::new();
+        let place_id = fixture.create_place(
+            NewPlace {
+                pos: MapPoint::from_lat_lng_deg(1.0, 1.0),
+                title: "Organic market".into(),
+                description: "Food".into(),
+                categories: vec![],
+                tags: vec!["organic-food".into()],
+            },
+            None,
+        );
+        let event_id = flows::create_event(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            None,
+            usecases::NewEvent {
+                title: "Organic market day".into(),
+                start: chrono::Utc::now().naive_utc().timestamp(),
+                created_by: Some("organizer@example.com".into()),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .id;
+
+        let results = flows::global_search(
+            &fixture.db_connections,
+            &*fixture.search_engine.borrow(),
+            "organic",
+            10,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![place_id],
+            results.places.into_iter().map(|p| p.id).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![event_id],
+            results.events.into_iter().map(|e| e.id).collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["organic-food".to_string()], results.tags);
+
+        let results = flows::global_search(
+            &fixture.db_connections,
+            &*fixture.search_engine.borrow(),
+            " ",
+            10,
+        )
+        .unwrap();
+        assert!(results.places.is_empty());
+    }
+}
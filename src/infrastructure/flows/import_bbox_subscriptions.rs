@@ -0,0 +1,29 @@
+use super::*;
+use diesel::connection::Connection;
+
+pub fn import_bbox_subscriptions(
+    connections: &sqlite::Connections,
+    account_email: &str,
+    subscriptions: Vec<BboxSubscription>,
+) -> Result<usize> {
+    let mut repo_err = None;
+    let connection = connections.exclusive()?;
+    // All or nothing
+    Ok(connection
+        .transaction::<_, diesel::result::Error, _>(|| {
+            usecases::import_bbox_subscriptions(&*connection, account_email, subscriptions).map_err(
+                |err| {
+                    warn!("Failed to import bbox subscriptions: {}", err);
+                    repo_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                },
+            )
+        })
+        .map_err(|err| {
+            if let Some(repo_err) = repo_err {
+                repo_err
+            } else {
+                RepoError::from(err).into()
+            }
+        })?)
+}
@@ -0,0 +1,77 @@
+use super::*;
+use diesel::connection::Connection;
+
+/// Import all users or none of them.
+///
+/// A dry run imports the users within a transaction that is
+/// always rolled back and only returns the report.
+pub fn import_users(
+    connections: &sqlite::Connections,
+    users: Vec<(String, Role)>,
+    dry_run: bool,
+) -> Result<usecases::UserImportReport> {
+    let mut repo_err = None;
+    let mut dry_run_report = None;
+    let connection = connections.exclusive()?;
+    let res = connection.transaction::<_, diesel::result::Error, _>(|| {
+        let report = usecases::import_users(&*connection, users).map_err(|err| {
+            warn!("Failed to import users: {}", err);
+            repo_err = Some(err);
+            diesel::result::Error::RollbackTransaction
+        })?;
+        if dry_run {
+            dry_run_report = Some(report);
+            return Err(diesel::result::Error::RollbackTransaction);
+        }
+        Ok(report)
+    });
+    if let Some(report) = dry_run_report {
+        return Ok(report);
+    }
+    Ok(res.map_err(|err| {
+        if let Some(repo_err) = repo_err {
+            repo_err
+        } else {
+            RepoError::from(err).into()
+        }
+    })?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn import_users_after_dry_run() {
+        let fixture = EnvFixture::new();
+        let users = vec![
+            ("scout@example.com".to_string(), Role::Scout),
+            ("user@example.com".to_string(), Role::User),
+        ];
+
+        let report = flows::import_users(&fixture.db_connections, users.clone(), true).unwrap();
+        assert_eq!(users, report.created);
+        assert!(fixture.try_get_user("scout@example.com").is_none());
+
+        let report = flows::import_users(&fixture.db_connections, users.clone(), false).unwrap();
+        assert_eq!(users, report.created);
+        let scout = fixture.try_get_user("scout@example.com").unwrap();
+        assert!(scout.email_confirmed);
+        assert_eq!(Role::Scout, scout.role);
+
+        let report = flows::import_users(&fixture.db_connections, users, false).unwrap();
+        assert!(report.created.is_empty());
+        assert_eq!(2, report.skipped.len());
+    }
+
+    #[test]
+    fn import_all_users_or_none() {
+        let fixture = EnvFixture::new();
+        let users = vec![
+            ("user@example.com".to_string(), Role::User),
+            ("invalid".to_string(), Role::User),
+        ];
+        assert!(flows::import_users(&fixture.db_connections, users, false).is_err());
+        assert!(fixture.try_get_user("user@example.com").is_none());
+    }
+}
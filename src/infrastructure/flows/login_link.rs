@@ -0,0 +1,86 @@
+use super::*;
+use crate::core::error::Error;
+use diesel::connection::Connection;
+use ofdb_core::NotificationGateway;
+
+/// Send a one-time login link by e-mail.
+///
+/// Repeated requests are silently ignored for a while and
+/// deactivated users don't receive any links.
+pub fn request_login_link(
+    connections: &sqlite::Connections,
+    notify: &dyn NotificationGateway,
+    email: &str,
+) -> Result<Option<EmailNonce>> {
+    let email_nonce = {
+        let mut rollback_err: Option<Error> = None;
+        let connection = connections.exclusive()?;
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                usecases::create_login_link(&*connection, email).map_err(|err| {
+                    rollback_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })
+            })
+            .map_err(|err| rollback_err.unwrap_or_else(|| Error::from(RepoError::from(err))))
+    }?;
+    if let Some(email_nonce) = &email_nonce {
+        notify.user_login_link_requested(email_nonce);
+    }
+    Ok(email_nonce)
+}
+
+pub fn login_with_login_link(
+    connections: &sqlite::Connections,
+    email_nonce: &EmailNonce,
+) -> Result<Role> {
+    let mut rollback_err: Option<Error> = None;
+    let connection = connections.exclusive()?;
+    Ok(connection
+        .transaction::<_, diesel::result::Error, _>(|| {
+            usecases::login_with_login_link(&*connection, email_nonce).map_err(|err| {
+                warn!(
+                    "Failed to login with link of user '{}': {}",
+                    email_nonce.email, err
+                );
+                rollback_err = Some(err);
+                diesel::result::Error::RollbackTransaction
+            })
+        })
+        .map_err(|err| rollback_err.unwrap_or_else(|| Error::from(RepoError::from(err))))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn login_with_a_link_without_registration() {
+        let fixture = EnvFixture::new();
+        let email = "new@example.com";
+        let email_nonce =
+            super::request_login_link(&fixture.db_connections, &fixture.notify, email)
+                .unwrap()
+                .unwrap();
+        // Repeated requests are ignored
+        assert!(
+            super::request_login_link(&fixture.db_connections, &fixture.notify, email)
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(email, email_nonce.email);
+        assert_eq!(
+            Role::User,
+            super::login_with_login_link(&fixture.db_connections, &email_nonce).unwrap()
+        );
+        let user = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .get_user_by_email(email)
+            .unwrap();
+        assert!(user.email_confirmed);
+        // Each link can only be used once
+        assert!(super::login_with_login_link(&fixture.db_connections, &email_nonce).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use super::{reindex::*, webhooks::*, *};
+
+use diesel::connection::Connection;
+
+/// Merge a duplicate into the primary place and reindex both places.
+pub fn merge_places(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    account_email: &str,
+    primary_id: &PlaceId,
+    duplicate_id: &PlaceId,
+) -> Result<usecases::MergedPlaces> {
+    let merged = {
+        let connection = connections.exclusive()?;
+        let mut prepare_err = None;
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                usecases::merge_places(&*connection, account_email, primary_id, duplicate_id)
+                    .and_then(|merged| {
+                        usecases::log_audit_action(
+                            &*connection,
+                            account_email,
+                            AuditAction::MergePlaces,
+                            &[duplicate_id.as_str()],
+                            Some(primary_id.as_str()),
+                            None,
+                        )?;
+                        Ok(merged)
+                    })
+                    .map_err(|err| {
+                        warn!(
+                            "Failed to merge place {} into {}: {}",
+                            duplicate_id, primary_id, err
+                        );
+                        prepare_err = Some(err);
+                        diesel::result::Error::RollbackTransaction
+                    })
+            })
+            .map_err(|err| {
+                if let Some(err) = prepare_err {
+                    err
+                } else {
+                    RepoError::from(err).into()
+                }
+            })
+    }?;
+
+    let mut delta = PlaceReindexDelta::default();
+    delta.extend_place_ids(vec![primary_id.as_str(), duplicate_id.as_str()]);
+    reindex_places(connections, indexer, delta)?;
+
+    notify_webhooks(
+        connections,
+        WebhookEvent::PlaceUpdated,
+        usecases::place_webhook_data(&merged.primary),
+    );
+    notify_webhooks(
+        connections,
+        WebhookEvent::PlaceArchived,
+        usecases::place_webhook_data(&merged.duplicate),
+    );
+    connections.live_changes().publish(
+        ChangedEntity::Place,
+        merged.primary.id.as_ref(),
+        ChangeAction::Updated,
+    );
+    connections.live_changes().publish(
+        ChangedEntity::Place,
+        merged.duplicate.id.as_ref(),
+        ChangeAction::Archived,
+    );
+    connections.place_changes().publish(
+        merged.primary.id.as_ref(),
+        PlaceChange::Revision(merged.primary.revision.into()),
+    );
+    connections.place_changes().publish(
+        merged.duplicate.id.as_ref(),
+        PlaceChange::Status(ReviewStatus::Archived),
+    );
+
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn merge_duplicate_with_ratings_into_primary_place() {
+        let fixture = EnvFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "admin@example.com".into(),
+                password: "secret".into(),
+            },
+            Some(Role::Admin),
+        );
+        let primary_id = fixture.create_place(0.into(), None);
+        let duplicate_id = fixture.create_place(1.into(), None);
+        let (rating_id, comment_id) = fixture.create_rating(new_entry_rating(
+            0,
+            &duplicate_id,
+            RatingContext::Fairness,
+            RatingValue::new(2),
+        ));
+
+        let merged = flows::merge_places(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "admin@example.com",
+            &primary_id.as_str().into(),
+            &duplicate_id.as_str().into(),
+        )
+        .unwrap();
+        assert_eq!(1, merged.moved_ratings);
+
+        assert!(fixture.place_exists(&primary_id));
+        assert!(!fixture.place_exists(&duplicate_id));
+        let rating = fixture.try_get_rating(&rating_id).unwrap();
+        assert_eq!(primary_id, rating.place_id.as_str());
+        assert!(fixture.comment_exists(&comment_id));
+        let (primary, _) = fixture.try_get_place(&primary_id).unwrap();
+        assert!(primary.tags.contains(&"tag_1".to_string()));
+        assert_eq!(
+            Some(primary_id.as_str().into()),
+            fixture
+                .db_connections
+                .shared()
+                .unwrap()
+                .get_place_merged_into(&duplicate_id.as_str().into())
+                .unwrap()
+        );
+
+        // The index only contains the primary place
+        let indexed = fixture.query_places_by_tag("tag_1");
+        assert_eq!(1, indexed.len());
+        assert_eq!(primary_id, indexed[0].id);
+
+        let audit_log = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_audit_log(&Default::default(), &Default::default())
+            .unwrap();
+        assert_eq!(AuditAction::MergePlaces, audit_log[0].action);
+        assert_eq!(duplicate_id, audit_log[0].item);
+        assert_eq!(
+            Some(primary_id.as_str()),
+            audit_log[0].activity.context.as_deref()
+        );
+    }
+
+    #[test]
+    fn publish_changes_of_both_places() {
+        use crate::infrastructure::place_changes::PlaceChange;
+        use std::time::Duration;
+
+        let fixture = EnvFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "admin@example.com".into(),
+                password: "secret".into(),
+            },
+            Some(Role::Admin),
+        );
+        let primary_id = fixture.create_place(0.into(), None);
+        let duplicate_id = fixture.create_place(1.into(), None);
+
+        let merged = flows::merge_places(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "admin@example.com",
+            &primary_id.as_str().into(),
+            &duplicate_id.as_str().into(),
+        )
+        .unwrap();
+
+        let place_changes = fixture.db_connections.place_changes();
+        let (changes, _) =
+            place_changes.wait_for_changes(&primary_id, Some(0), Duration::from_secs(0));
+        assert_eq!(
+            vec![PlaceChange::Revision(merged.primary.revision.into())],
+            changes.into_iter().map(|(_, c)| c).collect::<Vec<_>>()
+        );
+        let (changes, _) =
+            place_changes.wait_for_changes(&duplicate_id, Some(0), Duration::from_secs(0));
+        assert_eq!(
+            vec![PlaceChange::Status(ReviewStatus::Archived)],
+            changes.into_iter().map(|(_, c)| c).collect::<Vec<_>>()
+        );
+    }
+}
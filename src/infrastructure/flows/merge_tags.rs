@@ -0,0 +1,171 @@
+use super::{reindex::*, *};
+
+use diesel::connection::Connection;
+
+pub fn rename_tag<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    account_email: &str,
+    old_tag: &str,
+    new_tag: &str,
+) -> Result<()> {
+    replace_tags(connections, indexer, |connection| {
+        let replaced = usecases::rename_tag(connection, account_email, old_tag, new_tag)?;
+        usecases::log_audit_action(
+            connection,
+            account_email,
+            AuditAction::RenameTag,
+            &[old_tag],
+            Some(new_tag),
+            None,
+        )?;
+        Ok(replaced)
+    })
+}
+
+pub fn merge_tags<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    account_email: &str,
+    old_tags: &[&str],
+    new_tag: &str,
+) -> Result<()> {
+    replace_tags(connections, indexer, |connection| {
+        let replaced = usecases::merge_tags(connection, account_email, old_tags, new_tag)?;
+        usecases::log_audit_action(
+            connection,
+            account_email,
+            AuditAction::MergeTags,
+            old_tags,
+            Some(new_tag),
+            None,
+        )?;
+        Ok(replaced)
+    })
+}
+
+fn replace_tags<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    replace: impl FnOnce(&sqlite::Connection) -> std::result::Result<usecases::ReplacedTags, Error>,
+) -> Result<()> {
+    let usecases::ReplacedTags {
+        place_ids,
+        event_ids,
+    } = {
+        let connection = connections.exclusive()?;
+        let mut usecase_err = None;
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                replace(&*connection).map_err(|err| {
+                    warn!("Failed to replace tags: {}", err);
+                    usecase_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })
+            })
+            .map_err(|err| {
+                if let Some(err) = usecase_err {
+                    err
+                } else {
+                    RepoError::from(err).into()
+                }
+            })
+    }?;
+
+    publish_tagged_places_and_events(connections, &place_ids, &event_ids);
+    reindex_tagged_places_and_events(connections, indexer, place_ids, &event_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn merge_tags_and_reindex_places() {
+        let fixture = EnvFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "admin@example.com".into(),
+                password: "secret".into(),
+            },
+            Some(Role::Admin),
+        );
+        let place_id = fixture.create_place(0.into(), None);
+        let (mut place, _) = fixture.try_get_place(&place_id).unwrap();
+        place.revision = place.revision.next();
+        place.tags = vec!["vegen".into()];
+        place.created = Activity::now(None);
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .create_or_update_place(place)
+            .unwrap();
+        for tag in &["vegen", "vegan"] {
+            fixture
+                .db_connections
+                .exclusive()
+                .unwrap()
+                .create_tag_if_it_does_not_exist(&Tag {
+                    id: (*tag).to_string(),
+                })
+                .unwrap();
+        }
+
+        flows::merge_tags(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "admin@example.com",
+            &["vegen"],
+            "vegan",
+        )
+        .unwrap();
+
+        let (place, _) = fixture.try_get_place(&place_id).unwrap();
+        assert_eq!(vec!["vegan"], place.tags);
+        let tags = fixture.db_connections.shared().unwrap().all_tags().unwrap();
+        assert!(!tags.contains(&Tag { id: "vegen".into() }));
+        assert!(tags.contains(&Tag { id: "vegan".into() }));
+        assert!(fixture.query_places_by_tag("vegen").is_empty());
+        assert_eq!(1, fixture.query_places_by_tag("vegan").len());
+
+        let audit_log = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_audit_log(&Default::default(), &Default::default())
+            .unwrap();
+        assert_eq!(1, audit_log.len());
+        assert_eq!(AuditAction::MergeTags, audit_log[0].action);
+        assert_eq!("vegen", audit_log[0].item);
+        assert_eq!(Some("vegan"), audit_log[0].activity.context.as_deref());
+    }
+
+    #[test]
+    fn rename_tag_only_as_admin() {
+        let fixture = EnvFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "user@example.com".into(),
+                password: "secret".into(),
+            },
+            None,
+        );
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .create_tag_if_it_does_not_exist(&Tag { id: "vegen".into() })
+            .unwrap();
+        assert!(flows::rename_tag(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "user@example.com",
+            "vegen",
+            "vegan",
+        )
+        .is_err());
+        let tags = fixture.db_connections.shared().unwrap().all_tags().unwrap();
+        assert_eq!(vec![Tag { id: "vegen".into() }], tags);
+    }
+}
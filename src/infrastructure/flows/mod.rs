@@ -1,30 +1,67 @@
+mod anonymize_user;
+mod archive_batch;
 mod archive_comments;
 mod archive_events;
 mod archive_ratings;
+mod backup;
+mod change_rating;
 mod change_user_role;
+mod change_user_status;
 mod create_event;
 mod create_place;
 mod create_rating;
+mod curate_tags;
+mod delete_event;
+mod delete_tag;
+mod email_outbox;
+mod event_registrations;
+mod global_search;
+mod import_bbox_subscriptions;
+mod import_users;
+mod login_link;
+mod merge_places;
+mod merge_tags;
+mod notification_digests;
+mod organizations;
+mod place_clearance;
+mod reindex;
+mod report_content;
 mod reset_password;
+mod restore_place_revision;
 mod review_places;
+mod scout_reports;
 mod update_event;
 mod update_place;
+mod upsert_event;
+mod webhooks;
 
 pub mod prelude {
     pub use super::{
-        archive_comments::*, archive_events::*, archive_ratings::*, change_user_role::*,
-        create_event::*, create_place::*, create_rating::*, reset_password::*, review_places::*,
-        update_event::*, update_place::*,
+        anonymize_user::*, archive_batch::*, archive_comments::*, archive_events::*,
+        archive_ratings::*, backup::*, change_rating::*, change_user_role::*,
+        change_user_status::*, create_event::*, create_place::*, create_rating::*, curate_tags::*,
+        delete_event::*, delete_tag::*, email_outbox::*, event_registrations::*, global_search::*,
+        import_bbox_subscriptions::*, import_users::*, login_link::*, merge_places::*,
+        merge_tags::*, notification_digests::*, organizations::*, place_clearance::*, reindex::*,
+        report_content::*, reset_password::*, restore_place_revision::*, review_places::*,
+        scout_reports::*, update_event::*, update_place::*, upsert_event::*, webhooks::*,
     };
 }
 
 pub type Result<T> = std::result::Result<T, error::AppError>;
 
-pub(crate) use super::{db::sqlite, error};
+pub(crate) use super::{
+    db::sqlite,
+    error,
+    live_changes::{ChangeAction, ChangedEntity},
+    place_changes::PlaceChange,
+};
 pub(crate) use crate::core::{prelude::*, usecases};
 
-#[cfg(test)]
-mod tests {
+/// An in-memory database and search index for running the flows
+/// in tests, see the `test-fixtures` feature.
+#[cfg(any(test, feature = "test-fixtures"))]
+pub mod tests {
     pub mod prelude {
         pub use crate::core::{prelude::*, usecases};
         pub mod sqlite {
@@ -44,7 +81,7 @@ mod tests {
             response::Response,
         };
 
-        use crate::ports::web::{rocket_instance, tests::DummyNotifyGW};
+        use crate::ports::web::rocket_instance;
 
         use rocket::{
             config::{Config, Environment},
@@ -54,6 +91,28 @@ mod tests {
 
         embed_migrations!();
 
+        pub struct DummyNotifyGW;
+
+        impl ofdb_core::NotificationGateway for DummyNotifyGW {
+            fn place_added(&self, _: &[String], _: &Place, _: Vec<Category>) {}
+            fn place_updated(&self, _: &[String], _: &Place, _: Vec<Category>) {}
+            fn event_created(&self, _: &[String], _: &Event) {}
+            fn event_updated(&self, _: &[String], _: &Event) {}
+            fn place_archived(&self, _: &[String], _: &Place, _: Option<&str>) {}
+            fn event_archived(&self, _: &[String], _: &Event, _: Option<&str>) {}
+            fn user_registered_kvm(&self, _: &User) {}
+            fn user_registered_ofdb(&self, _: &User) {}
+            fn user_registered(&self, _: &User, _: &str) {}
+            fn user_reset_password_requested(&self, _: &EmailNonce) {}
+            fn user_login_link_requested(&self, _: &EmailNonce) {}
+            fn place_contact_verification_requested(&self, _: &Place, _: &EmailNonce) {}
+            fn scout_report_created(&self, _: &str, _: &ScoutReport) {}
+            fn notification_digest_created(&self, _: &str, _: &NotificationDigest) {}
+            fn event_registration_created(&self, _: &Event, _: &EventRegistration) {}
+            fn event_registration_promoted(&self, _: &Event, _: &EventRegistration) {}
+            fn content_reported(&self, _: &[String], _: &Report, _: &str) {}
+        }
+
         pub struct EnvFixture {
             pub client: Client,
             pub db_connections: sqlite::Connections,
@@ -75,6 +134,7 @@ mod tests {
                     search_engine.clone(),
                     vec![("/", api::routes())],
                     Some(cfg),
+                    Default::default(),
                 );
                 let client = Client::new(rocket).unwrap();
                 Self {
@@ -126,7 +186,7 @@ mod tests {
             }
 
             pub fn try_get_place(self: &EnvFixture, id: &str) -> Option<(Place, ReviewStatus)> {
-                match self.db_connections.shared().unwrap().get_place(id) {
+                match self.db_connections.shared().unwrap().get_place(&id.into()) {
                     Ok(x) => Some(x),
                     Err(RepoError::NotFound) => None,
                     x => x.map(|_| None).unwrap(),
@@ -145,12 +205,19 @@ mod tests {
                     &self.db_connections,
                     &mut *self.search_engine.borrow_mut(),
                     rate_entry,
+                    None,
+                    false,
                 )
                 .unwrap()
             }
 
             pub fn try_get_rating(self: &EnvFixture, id: &str) -> Option<Rating> {
-                match self.db_connections.shared().unwrap().load_rating(id) {
+                match self
+                    .db_connections
+                    .shared()
+                    .unwrap()
+                    .load_rating(&id.into())
+                {
                     Ok(rating) => Some(rating),
                     Err(RepoError::NotFound) => None,
                     x => x.map(|_| None).unwrap(),
@@ -162,7 +229,12 @@ mod tests {
             }
 
             pub fn try_get_comment(self: &EnvFixture, id: &str) -> Option<Comment> {
-                match self.db_connections.shared().unwrap().load_comment(id) {
+                match self
+                    .db_connections
+                    .shared()
+                    .unwrap()
+                    .load_comment(&id.into())
+                {
                     Ok(comment) => Some(comment),
                     Err(RepoError::NotFound) => None,
                     x => x.map(|_| None).unwrap(),
@@ -238,6 +310,8 @@ mod tests {
                     opening_hours: None,
                     image_url: None,
                     image_link_url: None,
+                    source: None,
+                    additional_locations: vec![],
                 }
             }
         }
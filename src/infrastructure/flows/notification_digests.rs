@@ -0,0 +1,148 @@
+use super::*;
+use ofdb_core::NotificationGateway;
+
+/// Send a summary of all queued changes to the users
+/// whose digest period has ended.
+///
+/// Empty digests are not sent, but the next period starts
+/// nevertheless. Errors for a single user are logged and
+/// don't abort the remaining digests. Returns the number
+/// of digests that have been sent.
+pub fn send_notification_digests(
+    connections: &sqlite::Connections,
+    notify: &dyn NotificationGateway,
+    now: TimestampMs,
+) -> Result<usize> {
+    let due = usecases::due_notification_digests(&*connections.shared()?, now)?;
+    let mut count = 0;
+    for settings in due {
+        let digest = {
+            let db = connections.shared()?;
+            usecases::create_notification_digest(&*db, &settings, now)
+        };
+        match digest {
+            Ok(digest) => {
+                if !digest.is_empty() {
+                    notify.notification_digest_created(&settings.user_email, &digest);
+                    count += 1;
+                }
+            }
+            Err(err) => {
+                error!(
+                    "Failed to create notification digest for {}: {}",
+                    settings.user_email, err
+                );
+                continue;
+            }
+        }
+        let db = connections.exclusive()?;
+        if let Err(err) = usecases::finish_notification_digest(&*db, &settings, now) {
+            error!(
+                "Failed to finish notification digest for {}: {}",
+                settings.user_email, err
+            );
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use ofdb_core::NotificationGateway;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct DigestGW {
+        added: RefCell<Vec<String>>,
+        digests: RefCell<Vec<(String, NotificationDigest)>>,
+    }
+
+    impl NotificationGateway for DigestGW {
+        fn place_added(&self, email_addresses: &[String], _: &Place, _: Vec<Category>) {
+            self.added.borrow_mut().extend_from_slice(email_addresses);
+        }
+        fn place_updated(&self, _: &[String], _: &Place, _: Vec<Category>) {}
+        fn event_created(&self, _: &[String], _: &Event) {}
+        fn event_updated(&self, _: &[String], _: &Event) {}
+        fn place_archived(&self, _: &[String], _: &Place, _: Option<&str>) {}
+        fn event_archived(&self, _: &[String], _: &Event, _: Option<&str>) {}
+        fn user_registered_kvm(&self, _: &User) {}
+        fn user_registered_ofdb(&self, _: &User) {}
+        fn user_registered(&self, _: &User, _: &str) {}
+        fn user_reset_password_requested(&self, _: &EmailNonce) {}
+        fn user_login_link_requested(&self, _: &EmailNonce) {}
+        fn place_contact_verification_requested(&self, _: &Place, _: &EmailNonce) {}
+        fn scout_report_created(&self, _: &str, _: &ScoutReport) {}
+        fn notification_digest_created(&self, email: &str, digest: &NotificationDigest) {
+            self.digests
+                .borrow_mut()
+                .push((email.to_owned(), digest.clone()));
+        }
+        fn event_registration_created(&self, _: &Event, _: &EventRegistration) {}
+        fn event_registration_promoted(&self, _: &Event, _: &EventRegistration) {}
+        fn content_reported(&self, _: &[String], _: &Report, _: &str) {}
+    }
+
+    #[test]
+    fn send_digests_instead_of_individual_notifications() {
+        let fixture = EnvFixture::new();
+        let email = "digest@example.com";
+        fixture.create_user(
+            usecases::NewUser {
+                email: email.into(),
+                password: "secret".into(),
+            },
+            None,
+        );
+        {
+            let db = fixture.db_connections.exclusive().unwrap();
+            db.create_bbox_subscription(&BboxSubscription {
+                id: Id::new(),
+                user_email: email.into(),
+                bbox: MapBbox::new(
+                    MapPoint::from_lat_lng_deg(0.5, -1.5),
+                    MapPoint::from_lat_lng_deg(1.5, -0.5),
+                ),
+            })
+            .unwrap();
+            usecases::set_digest_interval(&*db, email, Some(DigestInterval::Daily)).unwrap();
+        }
+
+        let notify = DigestGW::default();
+        let place = flows::create_place(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &notify,
+            NewPlace::from(1).into(),
+            None,
+        )
+        .unwrap();
+        assert!(notify.added.borrow().is_empty());
+
+        let now = TimestampMs::now();
+        assert_eq!(
+            0,
+            flows::send_notification_digests(&fixture.db_connections, &notify, now).unwrap()
+        );
+
+        let tomorrow =
+            TimestampMs::from_seconds(now.into_seconds() + DigestInterval::Daily.seconds());
+        assert_eq!(
+            1,
+            flows::send_notification_digests(&fixture.db_connections, &notify, tomorrow).unwrap()
+        );
+        let (recipient, digest) = notify.digests.borrow_mut().pop().unwrap();
+        assert_eq!(email, recipient);
+        assert_eq!(1, digest.new_places.len());
+        assert_eq!(place.id, digest.new_places[0].place_id);
+        assert!(digest.updated_places.is_empty());
+        assert!(fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_queued_notifications(email)
+            .unwrap()
+            .is_empty());
+    }
+}
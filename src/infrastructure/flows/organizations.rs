@@ -0,0 +1,144 @@
+use super::{reindex::*, *};
+
+use diesel::connection::Connection;
+
+pub fn create_organization<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    account_email: &str,
+    new_org: usecases::NewOrganization,
+) -> Result<(Organization, OrganizationApiToken)> {
+    let (org, api_token, place_ids) = manage_organization(connections, |connection| {
+        usecases::create_organization(connection, account_email, new_org)
+    })?;
+    reindex_tagged_places_and_events(connections, indexer, place_ids, &[])?;
+    Ok((org, api_token))
+}
+
+pub fn update_organization<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    account_email: &str,
+    id: &str,
+    new_org: usecases::NewOrganization,
+) -> Result<Organization> {
+    let (org, place_ids) = manage_organization(connections, |connection| {
+        usecases::update_organization(connection, account_email, id, new_org)
+    })?;
+    reindex_tagged_places_and_events(connections, indexer, place_ids, &[])?;
+    Ok(org)
+}
+
+pub fn delete_organization<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    account_email: &str,
+    id: &str,
+) -> Result<()> {
+    let place_ids = manage_organization(connections, |connection| {
+        usecases::delete_organization(connection, account_email, id)
+    })?;
+    reindex_tagged_places_and_events(connections, indexer, place_ids, &[])
+}
+
+pub fn regenerate_organization_api_token(
+    connections: &sqlite::Connections,
+    account_email: &str,
+    id: &str,
+) -> Result<(Organization, OrganizationApiToken)> {
+    manage_organization(connections, |connection| {
+        usecases::regenerate_organization_api_token(connection, account_email, id)
+    })
+}
+
+fn manage_organization<T>(
+    connections: &sqlite::Connections,
+    manage: impl FnOnce(&sqlite::Connection) -> std::result::Result<T, Error>,
+) -> Result<T> {
+    let connection = connections.exclusive()?;
+    let mut usecase_err = None;
+    let managed = connection
+        .transaction::<_, diesel::result::Error, _>(|| {
+            manage(&*connection).map_err(|err| {
+                warn!("Failed to manage organization: {}", err);
+                usecase_err = Some(err);
+                diesel::result::Error::RollbackTransaction
+            })
+        })
+        .map_err(|err| {
+            if let Some(err) = usecase_err {
+                err
+            } else {
+                RepoError::from(err).into()
+            }
+        })?;
+    Ok(managed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn create_update_and_delete_organization() {
+        let fixture = EnvFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "admin@example.com".into(),
+                password: "secret".into(),
+            },
+            Some(Role::Admin),
+        );
+        fixture.create_place(0.into(), None);
+        let owned_tags = || {
+            fixture
+                .db_connections
+                .shared()
+                .unwrap()
+                .get_all_tags_owned_by_orgs()
+                .unwrap()
+        };
+
+        let (org, _) = flows::create_organization(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "admin@example.com",
+            usecases::NewOrganization {
+                name: "Org".into(),
+                owned_tags: vec!["tag_0".into()],
+            },
+        )
+        .unwrap();
+        assert_eq!(vec!["tag_0"], owned_tags());
+        assert_eq!(1, fixture.query_places_by_tag("tag_0").len());
+
+        flows::update_organization(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "admin@example.com",
+            &org.id,
+            usecases::NewOrganization {
+                name: "Org".into(),
+                owned_tags: vec![],
+            },
+        )
+        .unwrap();
+        assert!(owned_tags().is_empty());
+        assert_eq!(1, fixture.query_places_by_tag("tag_0").len());
+
+        flows::delete_organization(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "admin@example.com",
+            &org.id,
+        )
+        .unwrap();
+        assert!(fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .all_orgs()
+            .unwrap()
+            .is_empty());
+    }
+}
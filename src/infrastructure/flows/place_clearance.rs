@@ -0,0 +1,145 @@
+use super::{reindex::*, *};
+
+use diesel::connection::Connection;
+
+pub fn clear_places_of_organization(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    org: &Organization,
+    clearances: &[ClearanceForPlace],
+) -> Result<()> {
+    let restored_places = {
+        let connection = connections.exclusive()?;
+        let mut usecase_err = None;
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                usecases::clear_places_of_organization(&*connection, org, clearances).map_err(
+                    |err| {
+                        warn!("Failed to clear places of organization {}: {}", org.id, err);
+                        usecase_err = Some(err);
+                        diesel::result::Error::RollbackTransaction
+                    },
+                )
+            })
+            .map_err(|err| {
+                if let Some(err) = usecase_err {
+                    err
+                } else {
+                    RepoError::from(err).into()
+                }
+            })
+    }?;
+
+    for place in &restored_places {
+        connections.live_changes().publish(
+            ChangedEntity::Place,
+            place.id.as_ref(),
+            ChangeAction::Updated,
+        );
+        connections.place_changes().publish(
+            place.id.as_ref(),
+            PlaceChange::Revision(place.revision.into()),
+        );
+    }
+    let mut delta = PlaceReindexDelta::default();
+    delta.extend_place_ids(restored_places.into_iter().map(|place| place.id));
+    reindex_places(connections, indexer, delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    fn update_title(fixture: &EnvFixture, place_id: &str, title: &str) {
+        let (place, _) = fixture.try_get_place(place_id).unwrap();
+        let update = usecases::UpdatePlace {
+            version: u64::from(place.revision.next()),
+            title: title.into(),
+            description: place.description.clone(),
+            lat: place.location.pos.lat().to_deg(),
+            lng: place.location.pos.lng().to_deg(),
+            street: None,
+            zip: None,
+            city: None,
+            country: None,
+            state: None,
+            email: None,
+            telephone: None,
+            homepage: None,
+            opening_hours: None,
+            categories: vec![],
+            tags: place.tags,
+            image_url: None,
+            image_link_url: None,
+            source: None,
+            additional_locations: None,
+        };
+        flows::update_place(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            place_id.into(),
+            update,
+            None,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn override_edit_of_place_with_owned_tag() {
+        let fixture = EnvFixture::new();
+        let place_id = fixture.create_place(0.into(), None);
+        let (original_place, _) = fixture.try_get_place(&place_id).unwrap();
+        let org = Organization {
+            id: "org".into(),
+            name: "Org".into(),
+            owned_tags: vec!["tag_0".into()],
+        };
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .create_org(org.clone())
+            .unwrap();
+
+        update_title(&fixture, &place_id, "vandalized");
+        update_title(&fixture, &place_id, "vandalized again");
+        let pending = usecases::load_pending_clearances_for_places(
+            &*fixture.db_connections.shared().unwrap(),
+            &org,
+        )
+        .unwrap();
+        assert_eq!(1, pending.len());
+        assert_eq!(place_id, pending[0].place_id.as_str());
+        assert_eq!(
+            Some(original_place.revision),
+            pending[0].last_cleared_revision
+        );
+
+        flows::clear_places_of_organization(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &org,
+            &[ClearanceForPlace {
+                place_id: place_id.as_str().into(),
+                cleared_revision: original_place.revision,
+            }],
+        )
+        .unwrap();
+
+        let (place, _) = fixture.try_get_place(&place_id).unwrap();
+        assert_eq!(Revision::from(3), place.revision);
+        assert_eq!(original_place.title, place.title);
+        assert!(usecases::load_pending_clearances_for_places(
+            &*fixture.db_connections.shared().unwrap(),
+            &org,
+        )
+        .unwrap()
+        .is_empty());
+        let query = IndexQuery {
+            text: Some("vandalized".into()),
+            ..Default::default()
+        };
+        assert!(fixture.query_places(&query).is_empty());
+    }
+}
@@ -0,0 +1,243 @@
+use super::*;
+
+use std::collections::BTreeSet;
+
+// Stay well below the maximum number of host parameters
+// in a single SQLite statement (default: 999)
+const MAX_CHUNK_SIZE: usize = 500;
+
+/// Ids of places that have been modified by a transaction.
+///
+/// The ids are collected while executing the transaction. The
+/// affected places are reindexed all at once after the transaction
+/// has been committed successfully.
+#[derive(Debug, Default)]
+pub struct PlaceReindexDelta {
+    place_ids: BTreeSet<String>,
+}
+
+impl PlaceReindexDelta {
+    pub fn extend_place_ids<T: Into<String>>(&mut self, ids: impl IntoIterator<Item = T>) {
+        self.place_ids.extend(ids.into_iter().map(Into::into));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.place_ids.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.place_ids.len()
+    }
+}
+
+/// Reindex only the places that have been collected in the delta.
+///
+/// Places that don't exist anymore are removed from the index.
+/// Errors are logged and don't abort the reindexing of the
+/// remaining places.
+pub fn reindex_places(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    delta: PlaceReindexDelta,
+) -> Result<()> {
+    if delta.is_empty() {
+        return Ok(());
+    }
+    debug!("Reindexing {} modified place(s)", delta.len());
+    let ids: Vec<_> = delta.place_ids.iter().map(String::as_str).collect();
+    let db = connections.shared()?;
+    for chunk in ids.chunks(MAX_CHUNK_SIZE) {
+        let places_with_status = db.get_places(chunk)?;
+        for id in chunk {
+            if places_with_status
+                .iter()
+                .any(|(place, _)| place.id.as_str() == *id)
+            {
+                continue;
+            }
+            if let Err(err) = indexer.remove_by_id(&Id::from(*id)) {
+                error!("Failed to remove place {} from search index: {}", id, err);
+            }
+        }
+        for (place, status) in places_with_status {
+            let ratings = match db.load_ratings_of_place(&place.id.as_str().into()) {
+                Ok(ratings) => ratings,
+                Err(err) => {
+                    error!(
+                        "Failed to load ratings of place {} for reindexing: {}",
+                        place.id, err
+                    );
+                    // Skip place
+                    continue;
+                }
+            };
+            if let Err(err) = usecases::reindex_place(indexer, &place, status, &ratings) {
+                error!("Failed to reindex place {}: {}", place.id, err);
+            }
+        }
+    }
+    if let Err(err) = indexer.flush_index() {
+        error!(
+            "Failed to flush search index after reindexing {} place(s): {}",
+            ids.len(),
+            err
+        );
+    }
+    Ok(())
+}
+
+/// Reindex places and events after their tags have been
+/// modified, e.g. by renaming or deleting a tag.
+///
+/// Curated tags and tag ownerships are reloaded before, because
+/// they might have been affected by the modification.
+pub fn reindex_tagged_places_and_events<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    place_ids: Vec<Id>,
+    event_ids: &[Id],
+) -> Result<()> {
+    let (curated_tags, tag_ownerships, events) = {
+        let db = connections.shared()?;
+        let event_ids: Vec<_> = event_ids.iter().map(Id::as_str).collect();
+        (
+            usecases::load_curated_tag_boosts(&*db)?,
+            db.get_all_tag_ownerships()?,
+            db.get_events_chronologically(&event_ids)?,
+        )
+    };
+    indexer.set_curated_tags(&curated_tags);
+    indexer.set_tag_ownerships(&tag_ownerships);
+    for event in &events {
+        if let Err(err) = usecases::index_event(indexer, event) {
+            error!("Failed to reindex event {}: {}", event.id, err);
+        }
+    }
+    if !events.is_empty() {
+        if let Err(err) = indexer.flush_index() {
+            error!(
+                "Failed to flush search index after reindexing {} event(s): {}",
+                events.len(),
+                err
+            );
+        }
+    }
+    let mut delta = PlaceReindexDelta::default();
+    delta.extend_place_ids(place_ids);
+    reindex_places(connections, indexer, delta)
+}
+
+/// Publish places and events as updated after their tags
+/// have been modified.
+pub fn publish_tagged_places_and_events(
+    connections: &sqlite::Connections,
+    place_ids: &[Id],
+    event_ids: &[Id],
+) {
+    let live_changes = connections.live_changes();
+    for id in place_ids {
+        live_changes.publish(ChangedEntity::Place, id.as_str(), ChangeAction::Updated);
+    }
+    for id in event_ids {
+        live_changes.publish(ChangedEntity::Event, id.as_str(), ChangeAction::Updated);
+    }
+}
+
+/// Populate an empty index with all places including
+/// their ratings and all events.
+pub fn rebuild_index<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+) -> Result<()> {
+    let (place_ids, curated_tags, tag_ownerships, categories) = {
+        let db = connections.shared()?;
+        (
+            db.all_place_ids()?,
+            usecases::load_curated_tag_boosts(&*db)?,
+            db.get_all_tag_ownerships()?,
+            db.all_categories()?,
+        )
+    };
+    // Required for boosting places while indexing them
+    indexer.set_curated_tags(&curated_tags);
+    // Required for filtering places by organization
+    indexer.set_tag_ownerships(&tag_ownerships);
+    // Required for searching places by category
+    indexer.set_categories(&categories);
+    info!("Indexing {} place(s)...", place_ids.len());
+    let mut delta = PlaceReindexDelta::default();
+    delta.extend_place_ids(place_ids);
+    reindex_places(connections, indexer, delta)?;
+    let events = connections.shared()?.all_events_chronologically()?;
+    info!("Indexing {} event(s)...", events.len());
+    for event in events {
+        if let Err(err) = usecases::index_event(indexer, &event) {
+            error!("Failed to index event {}: {}", event.id, err);
+        }
+    }
+    indexer.flush_index()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use super::PlaceReindexDelta;
+
+    #[test]
+    fn reindex_only_modified_places() {
+        let fixture = EnvFixture::new();
+        let place_ids = vec![
+            fixture.create_place(0.into(), None),
+            fixture.create_place(1.into(), None),
+        ];
+        assert_eq!(1, fixture.query_places_by_tag("tag_0").len());
+        assert_eq!(1, fixture.query_places_by_tag("tag_1").len());
+
+        // Modify the database without updating the search index
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .review_places(
+                &[place_ids[0].as_str(), place_ids[1].as_str()],
+                ReviewStatus::Archived,
+                &ActivityLog {
+                    activity: Activity::now(None),
+                    context: None,
+                    comment: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(1, fixture.query_places_by_tag("tag_0").len());
+        assert_eq!(1, fixture.query_places_by_tag("tag_1").len());
+
+        let mut delta = PlaceReindexDelta::default();
+        // Duplicate ids are only reindexed once
+        delta.extend_place_ids(vec![place_ids[0].as_str(), place_ids[0].as_str()]);
+        assert_eq!(1, delta.len());
+        flows::reindex_places(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            delta,
+        )
+        .unwrap();
+        assert!(fixture.query_places_by_tag("tag_0").is_empty());
+        // Not reindexed
+        assert_eq!(1, fixture.query_places_by_tag("tag_1").len());
+    }
+
+    #[test]
+    fn rebuild_index_from_scratch() {
+        let fixture = EnvFixture::new();
+        fixture.create_place(0.into(), None);
+        fixture.create_place(1.into(), None);
+
+        let mut search_engine = tantivy::SearchEngine::init_in_ram().unwrap();
+        flows::rebuild_index(&fixture.db_connections, &mut search_engine).unwrap();
+        let ids = search_engine
+            .query_ids(IndexQueryMode::WithoutRating, &IndexQuery::default(), 10)
+            .unwrap();
+        assert_eq!(2, ids.len());
+    }
+}
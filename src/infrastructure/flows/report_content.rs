@@ -0,0 +1,122 @@
+use super::*;
+use crate::core::error::RepoError;
+use diesel::Connection;
+use ofdb_core::NotificationGateway;
+
+/// Flag content and inform the scouts of the affected region.
+pub fn report_content(
+    connections: &sqlite::Connections,
+    notify: &dyn NotificationGateway,
+    new_report: usecases::NewReport,
+) -> Result<Report> {
+    let usecases::ReportedContent { report, title, pos } = {
+        let connection = connections.exclusive()?;
+        let mut prepare_err = None;
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                usecases::report_content(&*connection, new_report).map_err(|err| {
+                    warn!("Failed to report content: {}", err);
+                    prepare_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })
+            })
+            .map_err(|err| {
+                if let Some(err) = prepare_err {
+                    err
+                } else {
+                    RepoError::from(err).into()
+                }
+            })
+    }?;
+
+    let recipients = if let Some(pos) = pos {
+        usecases::content_report_recipients(&*connections.shared()?, pos).unwrap_or_else(|err| {
+            error!(
+                "Failed to find recipients for report {}: {}",
+                report.id, err
+            );
+            vec![]
+        })
+    } else {
+        vec![]
+    };
+    // Alerts for the operators are sent even if no
+    // scouts are responsible for the region
+    notify.content_reported(&recipients, &report, &title);
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn report_place_and_event() {
+        let fixture = EnvFixture::new();
+        let place_id = fixture.create_place(0.into(), None);
+        let event_id = flows::create_event(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            None,
+            usecases::NewEvent {
+                title: "Meetup".into(),
+                start: chrono::Utc::now().naive_utc().timestamp(),
+                created_by: Some("organizer@example.com".into()),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .id;
+
+        let report = flows::report_content(
+            &fixture.db_connections,
+            &fixture.notify,
+            usecases::NewReport {
+                content_kind: ContentKind::Place,
+                content_id: place_id.clone(),
+                reason: ReportReason::Spam,
+                comment: None,
+                reporter_email: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(place_id, report.content_id.as_str());
+        flows::report_content(
+            &fixture.db_connections,
+            &fixture.notify,
+            usecases::NewReport {
+                content_kind: ContentKind::Event,
+                content_id: event_id.into(),
+                reason: ReportReason::Offensive,
+                comment: Some("Insulting".into()),
+                reporter_email: None,
+            },
+        )
+        .unwrap();
+        assert!(flows::report_content(
+            &fixture.db_connections,
+            &fixture.notify,
+            usecases::NewReport {
+                content_kind: ContentKind::Comment,
+                content_id: place_id,
+                reason: ReportReason::Spam,
+                comment: None,
+                reporter_email: None,
+            },
+        )
+        .is_err());
+
+        let reports = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_open_reports()
+            .unwrap();
+        assert_eq!(2, reports.len());
+        assert_eq!(ContentKind::Place, reports[0].content_kind);
+        assert_eq!(ContentKind::Event, reports[1].content_kind);
+        assert_eq!(Some("Insulting"), reports[1].comment.as_deref());
+    }
+}
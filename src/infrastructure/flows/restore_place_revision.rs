@@ -0,0 +1,190 @@
+use super::{reindex::*, *};
+
+use diesel::connection::Connection;
+
+pub fn restore_place_revision(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    account_email: &str,
+    id: &PlaceId,
+    revision: Revision,
+) -> Result<Place> {
+    let place = {
+        let connection = connections.exclusive()?;
+        let mut usecase_err = None;
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let place =
+                    usecases::restore_place_revision(&*connection, account_email, id, revision)
+                        .map_err(|err| {
+                            warn!("Failed to restore revision of place {}: {}", id, err);
+                            usecase_err = Some(err);
+                            diesel::result::Error::RollbackTransaction
+                        })?;
+                usecases::log_audit_action(
+                    &*connection,
+                    account_email,
+                    AuditAction::RestorePlaceRevision,
+                    &[id.as_str()],
+                    Some(u64::from(revision).to_string().as_str()),
+                    None,
+                )
+                .map_err(|err| {
+                    warn!("Failed to log restoring of place {}: {}", id, err);
+                    usecase_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })?;
+                Ok(place)
+            })
+            .map_err(|err| {
+                if let Some(err) = usecase_err {
+                    err
+                } else {
+                    RepoError::from(err).into()
+                }
+            })
+    }?;
+
+    let mut delta = PlaceReindexDelta::default();
+    delta.extend_place_ids(Some(place.id.as_str()));
+    reindex_places(connections, indexer, delta)?;
+
+    connections.live_changes().publish(
+        ChangedEntity::Place,
+        place.id.as_ref(),
+        ChangeAction::Updated,
+    );
+    connections.place_changes().publish(
+        place.id.as_ref(),
+        PlaceChange::Revision(place.revision.into()),
+    );
+
+    Ok(place)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    fn create_scout(fixture: &EnvFixture, email: &str) {
+        fixture.create_user(
+            usecases::NewUser {
+                email: email.into(),
+                password: "test123".into(),
+            },
+            Some(Role::Scout),
+        );
+    }
+
+    #[test]
+    fn restore_vandalized_place() {
+        let fixture = EnvFixture::new();
+        create_scout(&fixture, "scout@example.com");
+        let place_id = fixture.create_place(0.into(), None);
+        let (original_place, _) = fixture.try_get_place(&place_id).unwrap();
+
+        let mut vandalized_place = original_place.clone();
+        vandalized_place.revision = original_place.revision.next();
+        vandalized_place.title = "vandalized".into();
+        vandalized_place.created = Activity::now(None);
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .create_or_update_place(vandalized_place)
+            .unwrap();
+
+        let restored_place = flows::restore_place_revision(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "scout@example.com",
+            &place_id.as_str().into(),
+            Revision::initial(),
+        )
+        .unwrap();
+        assert_eq!(Revision::from(2), restored_place.revision);
+
+        let (current_place, status) = fixture.try_get_place(&place_id).unwrap();
+        assert_eq!(restored_place.revision, current_place.revision);
+        assert_eq!(original_place.title, current_place.title);
+        assert_eq!(original_place.tags, current_place.tags);
+        assert_eq!(ReviewStatus::Confirmed, status);
+
+        let history = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .get_place_history(&place_id.as_str().into())
+            .unwrap();
+        assert_eq!(3, history.revisions.len());
+        let (_, logs) = history
+            .revisions
+            .iter()
+            .find(|(r, _)| r.revision == restored_place.revision)
+            .unwrap();
+        assert!(logs.iter().any(|l| l.status == ReviewStatus::Confirmed
+            && l.activity.comment.as_deref() == Some("Restored revision 0")));
+
+        let audit_log = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_audit_log(&Default::default(), &Default::default())
+            .unwrap();
+        assert_eq!(1, audit_log.len());
+        assert_eq!(AuditAction::RestorePlaceRevision, audit_log[0].action);
+        assert_eq!(place_id, audit_log[0].item);
+        assert_eq!(Some("0"), audit_log[0].activity.context.as_deref());
+
+        let query = IndexQuery {
+            text: Some(original_place.title),
+            ..Default::default()
+        };
+        assert_eq!(1, fixture.query_places(&query).len());
+    }
+
+    #[test]
+    fn reject_invalid_restore_requests() {
+        let fixture = EnvFixture::new();
+        create_scout(&fixture, "scout@example.com");
+        fixture.create_user(
+            usecases::NewUser {
+                email: "user@example.com".into(),
+                password: "test123".into(),
+            },
+            None,
+        );
+        let place_id = fixture.create_place(0.into(), None);
+        let (mut place, _) = fixture.try_get_place(&place_id).unwrap();
+        place.revision = place.revision.next();
+        place.title = "updated".into();
+        place.created = Activity::now(None);
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .create_or_update_place(place)
+            .unwrap();
+
+        assert!(flows::restore_place_revision(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "user@example.com",
+            &place_id.as_str().into(),
+            Revision::initial(),
+        )
+        .is_err());
+        // The current revision cannot be restored
+        assert!(flows::restore_place_revision(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "scout@example.com",
+            &place_id.as_str().into(),
+            Revision::initial().next(),
+        )
+        .is_err());
+        let (place, _) = fixture.try_get_place(&place_id).unwrap();
+        assert_eq!(Revision::initial().next(), place.revision);
+        assert_eq!("updated", place.title);
+    }
+}
@@ -1,21 +1,57 @@
-use super::*;
+use super::{reindex::*, webhooks::*, *};
 
 use diesel::connection::Connection;
+use ofdb_core::NotificationGateway;
 
 fn exec_review_places(
     connections: &sqlite::Connections,
     ids: &[&str],
     review: usecases::Review,
-) -> Result<usize> {
+    delta: &mut PlaceReindexDelta,
+) -> Result<(usize, Vec<Place>)> {
     let mut repo_err = None;
     let connection = connections.exclusive()?;
     Ok(connection
         .transaction::<_, diesel::result::Error, _>(|| {
-            usecases::review_places(&*connection, ids, review).map_err(|err| {
+            let archived_places = if review.status == ReviewStatus::Archived {
+                // Only places that have not been archived yet are affected
+                connection
+                    .get_places(ids)
+                    .map_err(|err| {
+                        warn!("Failed to load {} places for archiving: {}", ids.len(), err);
+                        repo_err = Some(err.into());
+                        diesel::result::Error::RollbackTransaction
+                    })?
+                    .into_iter()
+                    .filter(|(_, status)| *status != ReviewStatus::Archived)
+                    .map(|(place, _)| place)
+                    .collect()
+            } else {
+                vec![]
+            };
+            let reviewer_email = review.reviewer_email.clone();
+            let status = review.status;
+            let comment = review.comment.clone();
+            let count = usecases::review_places(&*connection, ids, review).map_err(|err| {
                 warn!("Failed to review {} places: {}", ids.len(), err);
                 repo_err = Some(err);
                 diesel::result::Error::RollbackTransaction
-            })
+            })?;
+            usecases::log_audit_action(
+                &*connection,
+                reviewer_email.as_ref(),
+                AuditAction::ReviewPlace,
+                ids,
+                Some(format!("{:?}", status).to_lowercase().as_str()),
+                comment.as_deref(),
+            )
+            .map_err(|err| {
+                warn!("Failed to log review of {} places: {}", ids.len(), err);
+                repo_err = Some(err);
+                diesel::result::Error::RollbackTransaction
+            })?;
+            delta.extend_place_ids(ids.iter().copied());
+            Ok((count, archived_places))
         })
         .map_err(|err| {
             if let Some(repo_err) = repo_err {
@@ -26,53 +62,57 @@ fn exec_review_places(
         })?)
 }
 
-fn post_review_places(
-    connections: &sqlite::Connections,
-    indexer: &mut dyn PlaceIndexer,
-    ids: &[&str],
-) -> Result<()> {
-    let db = connections.shared()?;
-    let places_with_status = db.get_places(ids)?;
-    for (place, status) in places_with_status {
-        let ratings = match db.load_ratings_of_place(place.id.as_str()) {
-            Ok(ratings) => ratings,
-            Err(err) => {
-                log::error!(
-                    "Failed to load ratings of place {} after reviewing: {}",
-                    place.id,
-                    err
-                );
-                continue;
-            }
-        };
-        if let Err(err) = usecases::reindex_place(indexer, &place, status, &ratings) {
-            error!(
-                "Failed to (re-)index place {} after reviewing: {}",
-                place.id, err
-            );
-        }
-    }
-    if let Err(err) = indexer.flush_index() {
-        error!(
-            "Failed to flush search index after reviewing places: {}",
-            err
-        );
-    }
-    Ok(())
-}
-
 pub fn review_places(
     connections: &sqlite::Connections,
     indexer: &mut dyn PlaceIndexer,
+    notify: &dyn NotificationGateway,
     ids: &[&str],
     review: usecases::Review,
 ) -> Result<usize> {
-    let count = exec_review_places(connections, ids, review)?;
+    let mut delta = PlaceReindexDelta::default();
+    let comment = review.comment.clone();
+    let status = review.status;
+    let action = if status.exists() {
+        ChangeAction::Updated
+    } else {
+        ChangeAction::Archived
+    };
+    let (count, archived_places) = exec_review_places(connections, ids, review, &mut delta)?;
     // TODO: Move post processing to a separate task/thread that doesn't delay this request?
-    post_review_places(connections, indexer, ids)?;
+    reindex_places(connections, indexer, delta)?;
+    notify_places_archived(notify, &archived_places, comment.as_deref());
+    for place in &archived_places {
+        notify_webhooks(
+            connections,
+            WebhookEvent::PlaceArchived,
+            usecases::place_webhook_data(place),
+        );
+    }
+    for id in ids {
+        connections
+            .live_changes()
+            .publish(ChangedEntity::Place, id, action);
+        connections
+            .place_changes()
+            .publish(id, PlaceChange::Status(status));
+    }
     Ok(count)
 }
 
+fn notify_places_archived(
+    notify: &dyn NotificationGateway,
+    places: &[Place],
+    comment: Option<&str>,
+) {
+    for place in places {
+        // Inform the author of the current revision instead
+        // of removing the place silently
+        if let Some(ref email) = place.created.by {
+            notify.place_archived(&[email.to_string()], place, comment);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::tests::prelude::*;
@@ -85,6 +125,7 @@ mod tests {
         super::review_places(
             &fixture.db_connections,
             &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
             ids,
             review,
         )
@@ -293,4 +334,114 @@ mod tests {
         assert!(fixture.comment_exists(&rating_comment_ids[2].1));
         assert!(fixture.comment_exists(&rating_comment_ids[3].1));
     }
+
+    #[test]
+    fn should_reject_and_confirm_places_with_comments() {
+        let fixture = EnvFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "scout@example.com".into(),
+                password: "test123".into(),
+            },
+            Some(Role::Scout),
+        );
+        let place_id = fixture.create_place(0.into(), None);
+        let tag = fixture
+            .try_get_place(&place_id)
+            .unwrap()
+            .0
+            .tags
+            .into_iter()
+            .next()
+            .unwrap();
+        let reviewed_by = |status, comment: &str| usecases::Review {
+            context: None,
+            reviewer_email: "scout@example.com".into(),
+            status,
+            comment: Some(comment.into()),
+        };
+
+        assert_eq!(
+            1,
+            review_places(
+                &fixture,
+                &[&*place_id],
+                reviewed_by(ReviewStatus::Rejected, "Spam")
+            )
+            .unwrap()
+        );
+        assert!(!fixture.place_exists(&place_id));
+        assert!(fixture.query_places_by_tag(&tag).is_empty());
+
+        assert_eq!(
+            1,
+            review_places(
+                &fixture,
+                &[&*place_id],
+                reviewed_by(ReviewStatus::Confirmed, "No spam")
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            Some(ReviewStatus::Confirmed),
+            fixture.try_get_place(&place_id).map(|(_, s)| s)
+        );
+        assert_eq!(1, fixture.query_places_by_tag(&tag).len());
+
+        // Reviews that don't change the status are ignored
+        assert_eq!(
+            0,
+            review_places(
+                &fixture,
+                &[&*place_id],
+                reviewed_by(ReviewStatus::Confirmed, "Still no spam")
+            )
+            .unwrap()
+        );
+
+        let history = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .get_place_history(&place_id.as_str().into())
+            .unwrap();
+        let comments: Vec<_> = history.revisions[0]
+            .1
+            .iter()
+            .filter_map(|l| l.activity.comment.as_deref())
+            .collect();
+        assert!(comments.contains(&"Spam"));
+        assert!(comments.contains(&"No spam"));
+        assert!(!comments.contains(&"Still no spam"));
+    }
+
+    #[test]
+    fn should_publish_archived_places_as_live_changes() {
+        use crate::infrastructure::live_changes::{ChangeAction, ChangedEntity};
+        use std::time::Duration;
+
+        let fixture = EnvFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "test@example.com".into(),
+                password: "test123".into(),
+            },
+            None,
+        );
+        let place_id = fixture.create_place(0.into(), None);
+        let live_changes = fixture.db_connections.live_changes();
+        let after = live_changes.resume_after(None);
+
+        assert_eq!(
+            1,
+            review_places(&fixture, &[&*place_id], archived_by("test@example.com")).unwrap()
+        );
+
+        let changes = live_changes.wait_for_changes(after, Duration::from_secs(0));
+        assert_eq!(1, changes.len());
+        let (_, change) = &changes[0];
+        assert_eq!(ChangedEntity::Place, change.entity);
+        assert_eq!(place_id, change.id);
+        assert_eq!(ChangeAction::Archived, change.action);
+    }
 }
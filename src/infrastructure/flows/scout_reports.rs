@@ -0,0 +1,176 @@
+use super::*;
+use ofdb_core::NotificationGateway;
+
+/// Send a report about their region to all scouts who
+/// didn't opt out.
+///
+/// Empty reports are not sent. Errors while creating the report
+/// for a single scout are logged and don't abort the remaining
+/// reports. Returns the number of reports that have been sent.
+pub fn send_scout_reports(
+    connections: &sqlite::Connections,
+    index: &dyn PlaceIndex,
+    notify: &dyn NotificationGateway,
+    since: TimestampMs,
+) -> Result<usize> {
+    let reports = {
+        let db = connections.shared()?;
+        let mut reports = vec![];
+        for user in usecases::scout_report_recipients(&*db)? {
+            match usecases::create_scout_report(&*db, index, &user.email, since) {
+                Ok(Some(report)) => {
+                    if !report.is_empty() {
+                        reports.push((user.email, report));
+                    }
+                }
+                Ok(None) => {
+                    debug!("Scout {} has not subscribed to any region", user.email);
+                }
+                Err(err) => {
+                    error!("Failed to create report for scout {}: {}", user.email, err);
+                }
+            }
+        }
+        reports
+    };
+    // The database lock is released before notifying
+    for (email, report) in &reports {
+        notify.scout_report_created(email, report);
+    }
+    Ok(reports.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use ofdb_core::NotificationGateway;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct ScoutReportsGW {
+        reports: RefCell<Vec<(String, ScoutReport)>>,
+    }
+
+    impl NotificationGateway for ScoutReportsGW {
+        fn place_added(&self, _: &[String], _: &Place, _: Vec<Category>) {}
+        fn place_updated(&self, _: &[String], _: &Place, _: Vec<Category>) {}
+        fn event_created(&self, _: &[String], _: &Event) {}
+        fn event_updated(&self, _: &[String], _: &Event) {}
+        fn place_archived(&self, _: &[String], _: &Place, _: Option<&str>) {}
+        fn event_archived(&self, _: &[String], _: &Event, _: Option<&str>) {}
+        fn user_registered_kvm(&self, _: &User) {}
+        fn user_registered_ofdb(&self, _: &User) {}
+        fn user_registered(&self, _: &User, _: &str) {}
+        fn user_reset_password_requested(&self, _: &EmailNonce) {}
+        fn user_login_link_requested(&self, _: &EmailNonce) {}
+        fn place_contact_verification_requested(&self, _: &Place, _: &EmailNonce) {}
+        fn scout_report_created(&self, email: &str, report: &ScoutReport) {
+            self.reports
+                .borrow_mut()
+                .push((email.to_owned(), report.clone()));
+        }
+        fn notification_digest_created(&self, _: &str, _: &NotificationDigest) {}
+        fn event_registration_created(&self, _: &Event, _: &EventRegistration) {}
+        fn event_registration_promoted(&self, _: &Event, _: &EventRegistration) {}
+        fn content_reported(&self, _: &[String], _: &Report, _: &str) {}
+    }
+
+    fn send_scout_reports(
+        fixture: &EnvFixture,
+        notify: &ScoutReportsGW,
+        since: TimestampMs,
+    ) -> usize {
+        super::send_scout_reports(
+            &fixture.db_connections,
+            &*fixture.search_engine.borrow(),
+            notify,
+            since,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn send_reports_about_places_within_the_region_of_scouts() {
+        let fixture = EnvFixture::new();
+        let scout_email = "scout@example.com";
+        fixture.create_user(
+            usecases::NewUser {
+                email: scout_email.into(),
+                password: "test123".into(),
+            },
+            Some(Role::Scout),
+        );
+        let mut scout = fixture.try_get_user(scout_email).unwrap();
+        scout.email_confirmed = true;
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .update_user(&scout)
+            .unwrap();
+
+        let since = TimestampMs::now();
+        let place_ids = vec![
+            fixture.create_place(1.into(), None),
+            fixture.create_place(2.into(), None),
+        ];
+        let notify = ScoutReportsGW::default();
+
+        // Scouts without a region don't receive any reports
+        assert_eq!(0, send_scout_reports(&fixture, &notify, since));
+
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .create_bbox_subscription(&BboxSubscription {
+                id: Id::new(),
+                user_email: scout_email.into(),
+                bbox: MapBbox::new(
+                    MapPoint::from_lat_lng_deg(0.5, -1.5),
+                    MapPoint::from_lat_lng_deg(1.5, -0.5),
+                ),
+            })
+            .unwrap();
+        for place_id in &place_ids {
+            usecases::report_content(
+                &*fixture.db_connections.exclusive().unwrap(),
+                usecases::NewReport {
+                    content_kind: ContentKind::Place,
+                    content_id: place_id.clone(),
+                    reason: ReportReason::Closed,
+                    comment: None,
+                    reporter_email: None,
+                },
+            )
+            .unwrap();
+        }
+
+        assert_eq!(1, send_scout_reports(&fixture, &notify, since));
+        let (email, report) = notify.reports.borrow_mut().pop().unwrap();
+        assert_eq!(scout_email, email);
+        assert_eq!(since, report.since);
+        let new_place_ids: Vec<_> = report
+            .new_places
+            .iter()
+            .map(|p| p.place_id.as_str())
+            .collect();
+        assert_eq!(vec![place_ids[0].as_str()], new_place_ids);
+        assert_eq!(report.new_places, report.reported_places);
+        assert!(report.stale_places.is_empty());
+
+        // Places that have been created before are not new anymore
+        assert_eq!(1, send_scout_reports(&fixture, &notify, TimestampMs::now()));
+        let (_, report) = notify.reports.borrow_mut().pop().unwrap();
+        assert!(report.new_places.is_empty());
+        assert_eq!(1, report.reported_places.len());
+
+        usecases::enable_scout_reports(
+            &*fixture.db_connections.exclusive().unwrap(),
+            scout_email,
+            false,
+        )
+        .unwrap();
+        assert_eq!(0, send_scout_reports(&fixture, &notify, since));
+    }
+}
@@ -48,7 +48,6 @@ pub fn update_event(
     }?;
 
     // Index newly added event
-    // TODO: Move to a separate task/thread that doesn't delay this request
     if let Err(err) = usecases::index_event(indexer, &event).and_then(|_| indexer.flush_index()) {
         error!("Failed to re-index updated event {}: {}", event.id, err);
     }
@@ -62,10 +61,16 @@ pub fn update_event(
         );
     }
 
+    connections.live_changes().publish(
+        ChangedEntity::Event,
+        event.id.as_ref(),
+        ChangeAction::Updated,
+    );
+
     Ok(event)
 }
 
-fn notify_event_updated(
+pub(super) fn notify_event_updated(
     connections: &sqlite::Connections,
     notify: &dyn NotificationGateway,
     event: &Event,
@@ -1,4 +1,4 @@
-use super::*;
+use super::{webhooks::*, *};
 use diesel::connection::Connection;
 use ofdb_core::NotificationGateway;
 
@@ -13,7 +13,7 @@ pub fn update_place(
     // Update existing entry
     let (place, ratings) = {
         let connection = connections.exclusive()?;
-        let mut prepare_err = None;
+        let mut usecase_err = None;
         connection
             .transaction::<_, diesel::result::Error, _>(|| {
                 match usecases::prepare_updated_place(&*connection, id, update_place, account_email)
@@ -23,19 +23,20 @@ pub fn update_place(
                             usecases::store_updated_place(&*connection, storable).map_err(
                                 |err| {
                                     warn!("Failed to store updated place: {}", err);
+                                    usecase_err = Some(err);
                                     diesel::result::Error::RollbackTransaction
                                 },
                             )?;
                         Ok((place, ratings))
                     }
                     Err(err) => {
-                        prepare_err = Some(err);
+                        usecase_err = Some(err);
                         Err(diesel::result::Error::RollbackTransaction)
                     }
                 }
             })
             .map_err(|err| {
-                if let Some(err) = prepare_err {
+                if let Some(err) = usecase_err {
                     err
                 } else {
                     RepoError::from(err).into()
@@ -44,7 +45,6 @@ pub fn update_place(
     }?;
 
     // Reindex updated place
-    // TODO: Move to a separate task/thread that doesn't delay this request
     if let Err(err) = usecases::reindex_place(indexer, &place, ReviewStatus::Created, &ratings)
         .and_then(|_| indexer.flush_index())
     {
@@ -60,6 +60,21 @@ pub fn update_place(
         );
     }
 
+    notify_webhooks(
+        connections,
+        WebhookEvent::PlaceUpdated,
+        usecases::place_webhook_data(&place),
+    );
+    connections.live_changes().publish(
+        ChangedEntity::Place,
+        place.id.as_ref(),
+        ChangeAction::Updated,
+    );
+    connections.place_changes().publish(
+        place.id.as_ref(),
+        PlaceChange::Revision(place.revision.into()),
+    );
+
     Ok(place)
 }
 
@@ -69,12 +84,134 @@ fn notify_place_updated(
     place: &Place,
 ) -> Result<()> {
     let (email_addresses, all_categories) = {
-        let connection = connections.shared()?;
-        let email_addresses =
-            usecases::email_addresses_by_coordinate(&*connection, place.location.pos)?;
+        let connection = connections.exclusive()?;
+        let email_addresses = usecases::email_addresses_by_place(&*connection, place)?;
+        // Subscribers who prefer a digest are notified later
+        let email_addresses = usecases::enqueue_digest_notifications(
+            &*connection,
+            email_addresses,
+            NotificationKind::PlaceUpdated,
+            place,
+        )?;
         let all_categories = connection.all_categories()?;
         (email_addresses, all_categories)
     };
     notify.place_updated(&email_addresses, &place, all_categories);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    fn update_place_from(place: &Place, version: u64, title: &str) -> usecases::UpdatePlace {
+        usecases::UpdatePlace {
+            version,
+            title: title.into(),
+            description: place.description.clone(),
+            lat: place.location.pos.lat().to_deg(),
+            lng: place.location.pos.lng().to_deg(),
+            street: None,
+            zip: None,
+            city: None,
+            country: None,
+            state: None,
+            email: None,
+            telephone: None,
+            homepage: None,
+            opening_hours: None,
+            categories: vec![],
+            tags: place.tags.clone(),
+            image_url: None,
+            image_link_url: None,
+            source: None,
+            additional_locations: None,
+        }
+    }
+
+    #[test]
+    fn keep_current_revision_if_storing_fails() {
+        let fixture = EnvFixture::new();
+        let place_id = fixture.create_place(0.into(), None);
+        let (place, _) = fixture.try_get_place(&place_id).unwrap();
+        assert_eq!(Revision::initial(), place.revision);
+
+        // Storing fails after the new revision has been prepared
+        let mut invalid_place = place.clone();
+        invalid_place.revision = place.revision.next();
+        invalid_place.title = "invalid".into();
+        invalid_place.created = Activity::now(Some("unknown@example.com".into()));
+        assert!(fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .create_or_update_place(invalid_place)
+            .is_err());
+        let (unchanged_place, _) = fixture.try_get_place(&place_id).unwrap();
+        assert_eq!(place.revision, unchanged_place.revision);
+        assert_eq!(place.title, unchanged_place.title);
+        let history = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .get_place_history(&place_id.as_str().into())
+            .unwrap();
+        assert_eq!(1, history.revisions.len());
+
+        // The next revision is still available
+        let updated_place = flows::update_place(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            place_id.as_str().into(),
+            update_place_from(&place, 1, "updated"),
+            None,
+        )
+        .unwrap();
+        assert_eq!(place.revision.next(), updated_place.revision);
+        let (current_place, _) = fixture.try_get_place(&place_id).unwrap();
+        assert_eq!(updated_place.revision, current_place.revision);
+        assert_eq!("updated", current_place.title);
+    }
+
+    #[test]
+    fn reject_conflicting_revisions() {
+        let fixture = EnvFixture::new();
+        let place_id = fixture.create_place(0.into(), None);
+        let (place, _) = fixture.try_get_place(&place_id).unwrap();
+
+        assert!(flows::update_place(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            place_id.as_str().into(),
+            update_place_from(&place, 1, "first"),
+            None,
+        )
+        .is_ok());
+        let err = flows::update_place(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            place_id.as_str().into(),
+            update_place_from(&place, 1, "second"),
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::Business(Error::Repo(RepoError::InvalidVersion))
+        ));
+
+        let (current_place, _) = fixture.try_get_place(&place_id).unwrap();
+        assert_eq!(place.revision.next(), current_place.revision);
+        assert_eq!("first", current_place.title);
+        let history = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .get_place_history(&place_id.as_str().into())
+            .unwrap();
+        assert_eq!(2, history.revisions.len());
+    }
+}
@@ -0,0 +1,160 @@
+use super::{create_event::*, update_event::*, webhooks::*, *};
+use crate::core::error::RepoError;
+use diesel::Connection;
+use ofdb_core::NotificationGateway;
+
+/// Create or update an event of an organization by the id
+/// in its own system.
+pub fn upsert_event_by_external_id(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn EventIndexer,
+    notify: &dyn NotificationGateway,
+    token: &str,
+    external_id: &str,
+    new_event: usecases::NewEvent,
+) -> Result<usecases::EventUpsert> {
+    let upsert = {
+        let connection = connections.exclusive()?;
+        let mut prepare_err = None;
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                usecases::upsert_event_by_external_id(&*connection, token, external_id, new_event)
+                    .map_err(|err| {
+                        warn!(
+                            "Failed to store event with external id {}: {}",
+                            external_id, err
+                        );
+                        prepare_err = Some(err);
+                        diesel::result::Error::RollbackTransaction
+                    })
+            })
+            .map_err(|err| {
+                if let Some(err) = prepare_err {
+                    err
+                } else {
+                    RepoError::from(err).into()
+                }
+            })
+    }?;
+
+    let event = upsert.event();
+    if let Err(err) = usecases::index_event(indexer, event).and_then(|_| indexer.flush_index()) {
+        error!("Failed to index event {}: {}", event.id, err);
+    }
+
+    match upsert {
+        usecases::EventUpsert::Created(ref event) => {
+            if let Err(err) = notify_event_created(connections, notify, event) {
+                error!(
+                    "Failed to send notifications for newly added event {}: {}",
+                    event.id, err
+                );
+            }
+            notify_webhooks(
+                connections,
+                WebhookEvent::EventCreated,
+                usecases::event_webhook_data(event),
+            );
+            connections.live_changes().publish(
+                ChangedEntity::Event,
+                event.id.as_ref(),
+                ChangeAction::Created,
+            );
+        }
+        usecases::EventUpsert::Updated(ref event) => {
+            if let Err(err) = notify_event_updated(connections, notify, event) {
+                error!(
+                    "Failed to send notifications for updated event {}: {}",
+                    event.id, err
+                );
+            }
+            connections.live_changes().publish(
+                ChangedEntity::Event,
+                event.id.as_ref(),
+                ChangeAction::Updated,
+            );
+        }
+    }
+
+    Ok(upsert)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    fn new_event(title: &str) -> usecases::NewEvent {
+        usecases::NewEvent {
+            title: title.into(),
+            start: chrono::Utc::now().naive_utc().timestamp(),
+            created_by: Some("org@example.com".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn upsert_events_by_external_id_of_organization() {
+        let fixture = EnvFixture::new();
+        let org = Organization {
+            id: Id::new().to_string(),
+            name: "org".into(),
+            owned_tags: vec!["org-tag".into()],
+        };
+        usecases::tests::create_org_with_api_token(
+            &*fixture.db_connections.exclusive().unwrap(),
+            org,
+            "secret",
+        );
+
+        let upsert = flows::upsert_event_by_external_id(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            "secret",
+            "ext-1",
+            new_event("foo"),
+        )
+        .unwrap();
+        let id = match upsert {
+            usecases::EventUpsert::Created(event) => event.id,
+            _ => panic!("Expected a new event"),
+        };
+
+        let upsert = flows::upsert_event_by_external_id(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            "secret",
+            " ext-1 ",
+            new_event("bar"),
+        )
+        .unwrap();
+        match upsert {
+            usecases::EventUpsert::Updated(event) => {
+                assert_eq!(id, event.id);
+                assert_eq!("bar", event.title);
+                assert!(event.tags.contains(&"org-tag".to_string()));
+            }
+            _ => panic!("Expected an updated event"),
+        }
+
+        assert!(flows::upsert_event_by_external_id(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            "invalid",
+            "ext-2",
+            new_event("baz"),
+        )
+        .is_err());
+        assert!(flows::upsert_event_by_external_id(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            "secret",
+            " ",
+            new_event("baz"),
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use super::*;
+use ofdb_core::WebhookGateway;
+use std::collections::BTreeMap;
+
+// The number of pending deliveries that are attempted at once
+const DUE_WEBHOOK_DELIVERIES_LIMIT: u64 = 100;
+
+fn enqueue_webhook_deliveries(
+    connections: &sqlite::Connections,
+    event: WebhookEvent,
+    data: serde_json::Value,
+) -> Result<usize> {
+    let connection = connections.exclusive()?;
+    Ok(usecases::enqueue_webhook_deliveries(
+        &*connection,
+        event,
+        data,
+    )?)
+}
+
+/// Enqueue requests to all webhooks that are subscribed to the event.
+///
+/// Failures are only logged and must not affect the operation
+/// that caused the event.
+pub fn notify_webhooks(
+    connections: &sqlite::Connections,
+    event: WebhookEvent,
+    data: serde_json::Value,
+) {
+    match enqueue_webhook_deliveries(connections, event, data) {
+        Ok(count) => {
+            if count > 0 {
+                debug!(
+                    "Enqueued {} webhook deliveries of {}",
+                    count,
+                    event.as_str()
+                );
+            }
+        }
+        Err(err) => {
+            error!(
+                "Failed to enqueue webhook deliveries of {}: {}",
+                event.as_str(),
+                err
+            );
+        }
+    }
+}
+
+/// Attempt all pending deliveries that are due.
+///
+/// The database connection is not held while waiting for
+/// responses. Returns the number of successful deliveries.
+pub fn deliver_due_webhooks(
+    connections: &sqlite::Connections,
+    gateway: &dyn WebhookGateway,
+) -> Result<usize> {
+    let (webhooks, deliveries) = {
+        let connection = connections.shared()?;
+        let deliveries = connection
+            .load_due_webhook_deliveries(TimestampMs::now(), DUE_WEBHOOK_DELIVERIES_LIMIT)?;
+        if deliveries.is_empty() {
+            return Ok(0);
+        }
+        let webhooks: BTreeMap<_, _> = connection
+            .all_webhooks()?
+            .into_iter()
+            .map(|webhook| (webhook.id.clone(), webhook))
+            .collect();
+        (webhooks, deliveries)
+    };
+    let mut delivered = 0;
+    for delivery in deliveries {
+        let webhook = match webhooks.get(&delivery.webhook_id) {
+            Some(webhook) => webhook,
+            // Deleted in the meantime
+            None => continue,
+        };
+        let attempted_at = TimestampMs::now();
+        let response_status = match gateway.deliver_webhook(webhook, &delivery) {
+            Ok(status) => Some(status),
+            Err(err) => {
+                warn!(
+                    "Failed to deliver {} to webhook {}: {}",
+                    delivery.id, webhook.id, err
+                );
+                None
+            }
+        };
+        let delivery = usecases::finish_webhook_delivery_attempt(
+            &*connections.exclusive()?,
+            delivery,
+            response_status,
+            attempted_at,
+        )?;
+        if delivery.status == WebhookDeliveryStatus::Delivered {
+            delivered += 1;
+        }
+    }
+    Ok(delivered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct MockWebhooks {
+        response_status: u16,
+        delivered: RefCell<Vec<(String, String)>>,
+    }
+
+    impl WebhookGateway for MockWebhooks {
+        fn deliver_webhook(
+            &self,
+            webhook: &Webhook,
+            delivery: &WebhookDelivery,
+        ) -> std::io::Result<u16> {
+            self.delivered
+                .borrow_mut()
+                .push((webhook.url.to_string(), delivery.payload.clone()));
+            Ok(self.response_status)
+        }
+    }
+
+    fn create_webhook(fixture: &EnvFixture, events: Vec<WebhookEvent>) -> Webhook {
+        fixture.create_user(
+            usecases::NewUser {
+                email: "admin@example.com".into(),
+                password: "secret".into(),
+            },
+            Some(Role::Admin),
+        );
+        usecases::create_webhook(
+            &*fixture.db_connections.exclusive().unwrap(),
+            "admin@example.com",
+            usecases::NewWebhook {
+                url: "https://example.com/hook".into(),
+                events,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn deliver_created_places_to_webhooks() {
+        let fixture = EnvFixture::new();
+        let webhook = create_webhook(&fixture, vec![WebhookEvent::PlaceCreated]);
+        let place_id = fixture.create_place(0.into(), None);
+
+        let gateway = MockWebhooks {
+            response_status: 200,
+            ..Default::default()
+        };
+        assert_eq!(
+            1,
+            flows::deliver_due_webhooks(&fixture.db_connections, &gateway).unwrap()
+        );
+        let delivered = gateway.delivered.borrow();
+        assert_eq!(1, delivered.len());
+        assert_eq!("https://example.com/hook", delivered[0].0);
+        assert!(delivered[0].1.contains(&place_id));
+
+        // Nothing left to deliver
+        assert_eq!(
+            0,
+            flows::deliver_due_webhooks(&fixture.db_connections, &gateway).unwrap()
+        );
+        let deliveries = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_webhook_deliveries(webhook.id.as_str(), &Default::default())
+            .unwrap();
+        assert_eq!(1, deliveries.len());
+        assert_eq!(WebhookDeliveryStatus::Delivered, deliveries[0].status);
+        assert_eq!(Some(200), deliveries[0].response_status);
+    }
+
+    #[test]
+    fn retry_failed_webhook_deliveries_later() {
+        let fixture = EnvFixture::new();
+        let webhook = create_webhook(&fixture, vec![WebhookEvent::PlaceCreated]);
+        fixture.create_place(0.into(), None);
+
+        let gateway = MockWebhooks {
+            response_status: 503,
+            ..Default::default()
+        };
+        assert_eq!(
+            0,
+            flows::deliver_due_webhooks(&fixture.db_connections, &gateway).unwrap()
+        );
+        // Not due again immediately
+        flows::deliver_due_webhooks(&fixture.db_connections, &gateway).unwrap();
+        assert_eq!(1, gateway.delivered.borrow().len());
+        let deliveries = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_webhook_deliveries(webhook.id.as_str(), &Default::default())
+            .unwrap();
+        assert_eq!(WebhookDeliveryStatus::Pending, deliveries[0].status);
+        assert_eq!(1, deliveries[0].attempts);
+        assert!(deliveries[0].next_attempt_at.unwrap() > TimestampMs::now());
+    }
+}
@@ -0,0 +1,208 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Condvar, Mutex},
+    time::{Duration, Instant},
+};
+
+const MAX_RECENT_CHANGES: usize = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangedEntity {
+    Place,
+    Event,
+    Rating,
+}
+
+impl ChangedEntity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChangedEntity::Place => "place",
+            ChangedEntity::Event => "event",
+            ChangedEntity::Rating => "rating",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeAction {
+    Created,
+    Updated,
+    Archived,
+    Deleted,
+}
+
+impl ChangeAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChangeAction::Created => "created",
+            ChangeAction::Updated => "updated",
+            ChangeAction::Archived => "archived",
+            ChangeAction::Deleted => "deleted",
+        }
+    }
+}
+
+/// A change of any place, event, or rating
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveChange {
+    pub entity: ChangedEntity,
+    pub id: String,
+    pub action: ChangeAction,
+}
+
+#[derive(Default)]
+struct State {
+    last_seq: u64,
+    recent: VecDeque<(u64, LiveChange)>,
+    streaming: usize,
+    max_streaming: usize,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+/// Recent changes of all places, events, and ratings for
+/// clients that follow them continuously, e.g. a map.
+///
+/// Changes are numbered consecutively. The sequence
+/// starts again at 0 whenever the server is restarted.
+///
+/// Clones share the same changes and outlive requests
+/// while responses are streamed.
+#[derive(Default, Clone)]
+pub struct LiveChanges(Arc<Shared>);
+
+/// Occupies one of the limited slots for clients that
+/// wait for changes until dropped.
+pub struct StreamingClient(LiveChanges);
+
+impl Drop for StreamingClient {
+    fn drop(&mut self) {
+        (self.0).0.state.lock().unwrap().streaming -= 1;
+    }
+}
+
+impl LiveChanges {
+    pub fn publish(&self, entity: ChangedEntity, id: &str, action: ChangeAction) {
+        let mut state = self.0.state.lock().unwrap();
+        state.last_seq += 1;
+        let seq = state.last_seq;
+        if state.recent.len() >= MAX_RECENT_CHANGES {
+            state.recent.pop_front();
+        }
+        state.recent.push_back((
+            seq,
+            LiveChange {
+                entity,
+                id: id.to_owned(),
+                action,
+            },
+        ));
+        self.0.changed.notify_all();
+    }
+
+    /// Each client that waits for changes blocks a worker thread.
+    /// The changes of single places are waited for within the same
+    /// limit. No client can stream until the limit has been set.
+    pub fn limit_streaming_clients(&self, max: usize) {
+        self.0.state.lock().unwrap().max_streaming = max;
+    }
+
+    /// Returns `None` if too many clients are already streaming.
+    pub fn start_streaming(&self) -> Option<StreamingClient> {
+        let mut state = self.0.state.lock().unwrap();
+        if state.streaming >= state.max_streaming {
+            warn!("Too many clients are streaming live changes");
+            return None;
+        }
+        state.streaming += 1;
+        Some(StreamingClient(self.clone()))
+    }
+
+    /// The current sequence number if the given one is unknown,
+    /// i.e. from before a restart.
+    pub fn resume_after(&self, after: Option<u64>) -> u64 {
+        let state = self.0.state.lock().unwrap();
+        after
+            .filter(|seq| *seq <= state.last_seq)
+            .unwrap_or(state.last_seq)
+    }
+
+    /// Wait until anything has changed after the given sequence
+    /// number or until the timeout expires.
+    pub fn wait_for_changes(&self, after: u64, timeout: Duration) -> Vec<(u64, LiveChange)> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.0.state.lock().unwrap();
+        loop {
+            let changes: Vec<_> = state
+                .recent
+                .iter()
+                .filter(|(seq, _)| *seq > after)
+                .cloned()
+                .collect();
+            let now = Instant::now();
+            if !changes.is_empty() || now >= deadline {
+                return changes;
+            }
+            state = self
+                .0
+                .changed
+                .wait_timeout(state, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn wait_for_changes_of_all_entities() {
+        let changes = LiveChanges::default();
+        changes.publish(ChangedEntity::Place, "a", ChangeAction::Created);
+        changes.publish(ChangedEntity::Rating, "b", ChangeAction::Created);
+
+        assert_eq!(2, changes.resume_after(None));
+        assert_eq!(2, changes.resume_after(Some(100)));
+        assert_eq!(
+            vec![2],
+            changes
+                .wait_for_changes(1, Duration::from_secs(0))
+                .into_iter()
+                .map(|(seq, _)| seq)
+                .collect::<Vec<_>>()
+        );
+        assert!(changes
+            .wait_for_changes(2, Duration::from_millis(1))
+            .is_empty());
+
+        let waiting = {
+            let changes = changes.clone();
+            thread::spawn(move || changes.wait_for_changes(2, Duration::from_secs(10)))
+        };
+        changes.publish(ChangedEntity::Event, "c", ChangeAction::Archived);
+        let event = LiveChange {
+            entity: ChangedEntity::Event,
+            id: "c".into(),
+            action: ChangeAction::Archived,
+        };
+        assert_eq!(vec![(3, event)], waiting.join().unwrap());
+    }
+
+    #[test]
+    fn limit_streaming_clients() {
+        let changes = LiveChanges::default();
+        assert!(changes.start_streaming().is_none());
+        changes.limit_streaming_clients(3);
+        let clients: Vec<_> = (0..3).map(|_| changes.start_streaming().unwrap()).collect();
+        assert!(changes.start_streaming().is_none());
+        drop(clients);
+        assert!(changes.start_streaming().is_some());
+    }
+}
@@ -1,10 +1,16 @@
 pub mod db;
 pub mod error;
 pub mod flows;
+pub mod live_changes;
+pub mod place_changes;
 
+use ofdb_core::EmailTransport;
 use ofdb_entities::email::*;
-use ofdb_gateways::{mailgun::*, opencage::*, sendmail::*};
-use std::env;
+use ofdb_gateways::{
+    alerts::*, captcha::*, mailgun::*, opencage::*, s3::*, sendmail::*, smtp::*,
+    user_communication::*,
+};
+use std::{env, path::Path, sync::Arc};
 
 lazy_static! {
 
@@ -37,6 +43,75 @@ lazy_static! {
 
     };
 
+    pub static ref OBJECT_STORAGE_GW: Option<S3> = {
+        let endpoint = env::var("S3_ENDPOINT");
+        let bucket = env::var("S3_BUCKET");
+        let access_key_id = env::var("S3_ACCESS_KEY_ID");
+        let secret_access_key = env::var("S3_SECRET_ACCESS_KEY");
+
+        if let (Ok(endpoint), Ok(bucket), Ok(access_key_id), Ok(secret_access_key)) =
+            (endpoint, bucket, access_key_id, secret_access_key)
+        {
+            endpoint
+                .parse::<url::Url>()
+                .map_err(|err| warn!("Invalid object storage endpoint '{}': {}", endpoint, err))
+                .ok()
+                .map(|endpoint| S3 {
+                    endpoint,
+                    region: env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".into()),
+                    bucket,
+                    access_key_id,
+                    secret_access_key,
+                })
+        } else {
+            None
+        }
+    };
+
+    pub static ref SMTP_GW: Option<Smtp> = {
+        let host = env::var("SMTP_HOST");
+        let from = env::var("MAIL_GATEWAY_SENDER_ADDRESS");
+
+        if let (Ok(host), Ok(mail)) = (host, from) {
+            let security = match env::var("SMTP_SECURITY") {
+                Ok(security) => security.parse::<SmtpSecurity>(),
+                Err(_) => Ok(SmtpSecurity::default()),
+            };
+            let port = match env::var("SMTP_PORT") {
+                Ok(port) => port
+                    .parse::<u16>()
+                    .map(Some)
+                    .map_err(|err| format!("Invalid SMTP port '{}': {}", port, err)),
+                Err(_) => Ok(None),
+            };
+            match (security, port) {
+                (Ok(security), Ok(port)) => {
+                    let username = env::var("SMTP_USERNAME");
+                    let password = env::var("SMTP_PASSWORD");
+                    let credentials = match (username, password) {
+                        (Ok(username), Ok(password)) => {
+                            Some(SmtpCredentials { username, password })
+                        }
+                        _ => None,
+                    };
+                    Some(Smtp {
+                        port: port.unwrap_or_else(|| security.default_port()),
+                        host,
+                        security,
+                        credentials,
+                        from_email: Email::from(mail),
+                    })
+                }
+                (Err(err), _) | (_, Err(err)) => {
+                    error!("Invalid SMTP configuration: {}", err);
+                    None
+                }
+            }
+        } else {
+            None
+        }
+    };
+
     pub static ref SENDMAIL_GW: Option<Sendmail> = {
         let from = env::var("MAIL_GATEWAY_SENDER_ADDRESS");
         if let Ok(mail) = from {
@@ -48,4 +123,84 @@ lazy_static! {
             None
         }
     };
+
+    pub static ref SLACK_GW: Option<Slack> = {
+        if let Ok(webhook_url) = env::var("SLACK_WEBHOOK_URL") {
+            webhook_url
+                .parse::<url::Url>()
+                .map_err(|err| warn!("Invalid Slack webhook URL '{}': {}", webhook_url, err))
+                .ok()
+                .map(|webhook_url| Slack { webhook_url })
+        } else {
+            None
+        }
+    };
+
+    pub static ref TELEGRAM_GW: Option<Telegram> = {
+        let bot_token = env::var("TELEGRAM_BOT_TOKEN");
+        let chat_id = env::var("TELEGRAM_CHAT_ID");
+
+        if let (Ok(bot_token), Ok(chat_id)) = (bot_token, chat_id) {
+            Some(Telegram { bot_token, chat_id })
+        } else {
+            None
+        }
+    };
+
+    pub static ref ALERT_WEBHOOK_GW: Option<AlertWebhook> = {
+        if let Ok(url) = env::var("ALERT_WEBHOOK_URL") {
+            url.parse::<url::Url>()
+                .map_err(|err| warn!("Invalid alert webhook URL '{}': {}", url, err))
+                .ok()
+                .map(|url| AlertWebhook { url })
+        } else {
+            None
+        }
+    };
+
+    // Anonymous reports must be confirmed with a captcha if configured
+    pub static ref CAPTCHA_GW: Option<SiteVerify> = {
+        if let Ok(secret) = env::var("CAPTCHA_SECRET") {
+            let verify_url =
+                env::var("CAPTCHA_VERIFY_URL").unwrap_or_else(|_| HCAPTCHA_VERIFY_URL.into());
+            verify_url
+                .parse::<url::Url>()
+                .map_err(|err| warn!("Invalid captcha verification URL '{}': {}", verify_url, err))
+                .ok()
+                .map(|verify_url| SiteVerify { verify_url, secret })
+        } else {
+            None
+        }
+    };
+
+    // The gateway that is used for sending the e-mails from the outbox
+    pub static ref EMAIL_TRANSPORT: Option<&'static (dyn EmailTransport + Sync)> = {
+        if let Some(gw) = &*MAILGUN_GW {
+            info!("Use Mailgun gateway");
+            Some(gw)
+        } else if let Some(gw) = &*SMTP_GW {
+            info!("Use SMTP gateway {}:{}", gw.host, gw.port);
+            Some(gw)
+        } else if let Some(gw) = &*SENDMAIL_GW {
+            warn!("Neither Mailgun nor SMTP gateway were configured: use sendmail as fallback");
+            Some(gw)
+        } else {
+            warn!("No eMail gateway was configured");
+            None
+        }
+    };
+
+    // Operators may add their own translations or replace the built-in templates
+    pub static ref EMAIL_TEMPLATES: Arc<EmailTemplates> = {
+        let default_language =
+            env::var("EMAIL_DEFAULT_LANGUAGE").unwrap_or_else(|_| DEFAULT_LANGUAGE.into());
+        let mut templates = EmailTemplates::new(&default_language);
+        if let Ok(dir) = env::var("EMAIL_TEMPLATES_DIR") {
+            match templates.load_dir(Path::new(&dir)) {
+                Ok(count) => info!("Loaded {} e-mail templates from {}", count, dir),
+                Err(err) => error!("Failed to load e-mail templates from {}: {}", dir, err),
+            }
+        }
+        Arc::new(templates)
+    };
 }
@@ -0,0 +1,153 @@
+use crate::core::prelude::ReviewStatus;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Condvar, Mutex},
+    time::{Duration, Instant},
+};
+
+const MAX_RECENT_CHANGES: usize = 1_000;
+
+/// A change of a single place
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlaceChange {
+    Revision(u64),
+    Status(ReviewStatus),
+    Rating { id: String },
+    Comment { id: String, rating_id: String },
+}
+
+struct RecentChange {
+    seq: u64,
+    place_id: String,
+    change: PlaceChange,
+}
+
+#[derive(Default)]
+struct State {
+    last_seq: u64,
+    recent: VecDeque<RecentChange>,
+    // The number of waiting clients is limited by
+    // the live changes that they are counted in
+    waiting: usize,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+/// Recent changes of places for clients that are
+/// waiting for updates of a particular place.
+///
+/// Changes are numbered consecutively. The sequence
+/// starts again at 0 whenever the server is restarted.
+///
+/// Clones share the same changes.
+#[derive(Default, Clone)]
+pub struct PlaceChanges(Arc<Shared>);
+
+impl PlaceChanges {
+    pub fn publish(&self, place_id: &str, change: PlaceChange) {
+        let mut state = self.0.state.lock().unwrap();
+        state.last_seq += 1;
+        let seq = state.last_seq;
+        if state.recent.len() >= MAX_RECENT_CHANGES {
+            state.recent.pop_front();
+        }
+        state.recent.push_back(RecentChange {
+            seq,
+            place_id: place_id.to_owned(),
+            change,
+        });
+        self.0.changed.notify_all();
+    }
+
+    /// Wait until the place has changed after the given sequence
+    /// number or until the timeout expires.
+    ///
+    /// Returns the changes together with the current sequence number.
+    /// Without a sequence number only subsequent changes are returned.
+    pub fn wait_for_changes(
+        &self,
+        place_id: &str,
+        after: Option<u64>,
+        timeout: Duration,
+    ) -> (Vec<(u64, PlaceChange)>, u64) {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.0.state.lock().unwrap();
+        // Sequence numbers from before a restart are reset
+        let after = after
+            .filter(|seq| *seq <= state.last_seq)
+            .unwrap_or(state.last_seq);
+        state.waiting += 1;
+        let changes = loop {
+            let changes = changes_of_place(&state, place_id, after);
+            let now = Instant::now();
+            if !changes.is_empty() || now >= deadline {
+                break changes;
+            }
+            state = self
+                .0
+                .changed
+                .wait_timeout(state, deadline - now)
+                .unwrap()
+                .0;
+        };
+        state.waiting -= 1;
+        (changes, state.last_seq)
+    }
+}
+
+fn changes_of_place(state: &State, place_id: &str, after: u64) -> Vec<(u64, PlaceChange)> {
+    state
+        .recent
+        .iter()
+        .filter(|c| c.seq > after && c.place_id == place_id)
+        .map(|c| (c.seq, c.change.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn wait_for_changes_of_a_single_place() {
+        let changes = PlaceChanges::default();
+        changes.publish("a", PlaceChange::Revision(1));
+        changes.publish("b", PlaceChange::Revision(1));
+
+        assert_eq!(
+            (vec![(1, PlaceChange::Revision(1))], 2),
+            changes.wait_for_changes("a", Some(0), Duration::from_secs(0))
+        );
+        assert_eq!(
+            (vec![], 2),
+            changes.wait_for_changes("a", Some(1), Duration::from_millis(1))
+        );
+
+        let waiting = {
+            let changes = changes.clone();
+            thread::spawn(move || changes.wait_for_changes("b", None, Duration::from_secs(10)))
+        };
+        while changes.0.state.lock().unwrap().waiting == 0 {
+            thread::yield_now();
+        }
+        let rating = PlaceChange::Rating { id: "r".into() };
+        changes.publish("a", PlaceChange::Revision(2));
+        changes.publish("b", rating.clone());
+        assert_eq!((vec![(4, rating)], 4), waiting.join().unwrap());
+    }
+
+    #[test]
+    fn reset_unknown_sequence_numbers() {
+        let changes = PlaceChanges::default();
+        changes.publish("a", PlaceChange::Revision(1));
+        assert_eq!(
+            (vec![], 1),
+            changes.wait_for_changes("a", Some(100), Duration::from_millis(1))
+        );
+    }
+}
@@ -1,20 +1,33 @@
 use crate::{
-    core::prelude::*,
+    adapters::{csv, json},
+    core::{prelude::*, usecases},
     infrastructure::{
         db::{sqlite, tantivy},
-        GEO_CODING_GW,
+        flows::prelude as flows,
+        GEO_CODING_GW, OBJECT_STORAGE_GW,
     },
     ports::web,
 };
 
-use clap::{crate_authors, App, Arg};
+use clap::{crate_authors, App, AppSettings, Arg, ArgMatches, SubCommand};
 use dotenv::dotenv;
 use ofdb_core::GeoCodingGateway;
-use std::{env, path::Path};
+use std::{env, fs, net::IpAddr, path::Path, time::Duration};
 
 const DEFAULT_DB_URL: &str = "openfair.db";
 const DB_CONNECTION_POOL_SIZE: u32 = 10;
 
+// Modifications of the search index become visible after this delay
+const INDEX_COMMIT_INTERVAL: Duration = Duration::from_secs(1);
+
+const DEFAULT_INDEX_OPTIMIZE_INTERVAL_HOURS: u64 = 24;
+
+const DEFAULT_SCOUT_REPORT_INTERVAL_HOURS: u64 = 24;
+
+const DEFAULT_BACKUP_RETENTION: usize = 7;
+
+const DEFAULT_QUERY_LIMIT: usize = 20;
+
 embed_migrations!();
 
 fn update_event_locations<D: Db>(db: &mut D) -> Result<()> {
@@ -39,6 +52,185 @@ fn update_event_locations<D: Db>(db: &mut D) -> Result<()> {
     Ok(())
 }
 
+fn rebuild_index(
+    connections: &sqlite::Connections,
+    idx_path: Option<&Path>,
+    idx_language: Option<tantivy::TextLanguage>,
+) {
+    let idx_path = idx_path.expect("Missing directory of the full-text search index");
+    info!("Rebuilding full-text search index...");
+    tantivy::SearchEngine::rebuild_in_dir(idx_path, idx_language, |search_engine| {
+        Ok(flows::rebuild_index(connections, search_engine)?)
+    })
+    .unwrap();
+    info!("Finished rebuilding full-text search index");
+}
+
+fn validate_retention(count: String) -> std::result::Result<(), String> {
+    match count.parse::<usize>() {
+        Ok(count) if count > 0 => Ok(()),
+        _ => Err("At least one backup must be kept".to_string()),
+    }
+}
+
+// Other backends like PostgreSQL are not supported yet
+// and their URLs would be mistaken for file names
+fn check_sqlite_db_url(db_url: &str) -> std::result::Result<(), String> {
+    match db_url.find("://").map(|pos| &db_url[..pos]) {
+        Some(scheme) if scheme != "file" => Err(format!(
+            "Unsupported database URL '{}': Only SQLite databases are supported",
+            db_url
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn validate_ip_addr(addr: String) -> std::result::Result<(), String> {
+    addr.parse::<IpAddr>()
+        .map(|_| ())
+        .map_err(|_| format!("Invalid IP address: {}", addr))
+}
+
+fn validate_count(count: String) -> std::result::Result<(), String> {
+    count
+        .parse::<usize>()
+        .map(|_| ())
+        .map_err(|_| format!("Invalid number: {}", count))
+}
+
+fn backup_retention(matches: &ArgMatches) -> usize {
+    matches
+        .value_of("retention")
+        .map(|count| count.parse().expect("validated"))
+        .unwrap_or(DEFAULT_BACKUP_RETENTION)
+}
+
+fn query_places(connections: &sqlite::Connections, matches: &ArgMatches) {
+    let bbox = matches
+        .value_of("bbox")
+        .map(|bbox| bbox.parse::<MapBbox>().expect("Invalid bounding box"))
+        .unwrap_or_else(|| {
+            MapBbox::new(
+                MapPoint::from_lat_lng_deg(-90.0, -180.0),
+                MapPoint::from_lat_lng_deg(90.0, 180.0),
+            )
+        });
+    let hash_tags = matches
+        .values_of("tag")
+        .map(Iterator::collect)
+        .unwrap_or_default();
+    let category_ids = matches
+        .values_of("category")
+        .map(Iterator::collect)
+        .unwrap_or_default();
+    let fuzzy_distance = matches
+        .value_of("fuzzy")
+        .map(|distance| distance.parse().unwrap());
+    let limit = matches
+        .value_of("limit")
+        .map(|limit| limit.parse().unwrap())
+        .unwrap_or(DEFAULT_QUERY_LIMIT);
+
+    // The index of a running server is locked and must not
+    // be touched. Instead all places are indexed from the
+    // database in RAM.
+    info!("Indexing all places and events in RAM...");
+    let mut search_engine = tantivy::SearchEngine::init_in_ram().unwrap();
+    flows::rebuild_index(connections, &mut search_engine).unwrap();
+    let categories = Categories::from(connections.shared().unwrap().all_categories().unwrap());
+
+    let req = usecases::SearchRequest {
+        bbox,
+        center: None,
+        radius: None,
+        ids: vec![],
+        categories: category_ids,
+        hash_tags,
+        any_hash_tags: vec![],
+        exclude_hash_tags: vec![],
+        org_id: None,
+        text: matches.value_of("text"),
+        fuzzy_distance,
+        status: vec![],
+        updated_since: None,
+        updated_until: None,
+        sort_by: Default::default(),
+        boost_quality: matches.is_present("boost-quality"),
+        min_total_rating: None,
+        min_ratings: vec![],
+        offset: 0,
+        after: None,
+    };
+    let (visible, _) = usecases::search(&search_engine, req, limit).unwrap();
+
+    if matches.is_present("json") {
+        let results: Vec<_> = visible
+            .into_iter()
+            .map(|place| json::place_search_result_from_indexed_place(place, &categories))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&results).unwrap());
+    } else {
+        println!("{:<32}  {:>8}  {:>6}  TITLE", "ID", "SCORE", "RATING");
+        for place in visible {
+            println!(
+                "{:<32}  {:>8.3}  {:>6.2}  {}",
+                place.id,
+                place.score,
+                f64::from(place.ratings.total()),
+                place.title
+            );
+        }
+    }
+}
+
+fn read_tag_synonyms(path: &str) -> usecases::TagSynonyms {
+    let pairs = csv::read_tag_synonyms(
+        &fs::read_to_string(path).expect("Failed to read tag synonyms file"),
+    )
+    .expect("Invalid tag synonyms file");
+    let mut synonyms = usecases::TagSynonyms::default();
+    for (synonym, tag) in pairs {
+        synonyms.add(&synonym, &tag);
+    }
+    if synonyms.is_empty() {
+        warn!("No tag synonyms found in {}", path);
+    } else {
+        info!("Loaded {} tag synonym(s) from {}", synonyms.len(), path);
+    }
+    synonyms
+}
+
+fn import_users(connections: &sqlite::Connections, matches: &ArgMatches) {
+    let path = matches.value_of("csv").unwrap();
+    let users = csv::read_users(&fs::read_to_string(path).expect("Failed to read CSV file"))
+        .expect("Invalid CSV file");
+    let dry_run = matches.is_present("dry-run");
+    let report = flows::import_users(connections, users, dry_run).unwrap();
+    for (email, role) in &report.created {
+        println!(
+            "created  {:<6}  {}",
+            format!("{:?}", role).to_lowercase(),
+            email
+        );
+    }
+    for email in &report.skipped {
+        println!("skipped  {:<6}  {}", "", email);
+    }
+    if dry_run {
+        info!(
+            "Dry run: {} user(s) would be created and {} existing user(s) skipped",
+            report.created.len(),
+            report.skipped.len()
+        );
+    } else {
+        info!(
+            "Created {} user(s) and skipped {} existing user(s)",
+            report.created.len(),
+            report.skipped.len()
+        );
+    }
+}
+
 #[allow(deprecated)]
 pub fn run() {
     dotenv().ok();
@@ -49,7 +241,7 @@ pub fn run() {
             Arg::with_name("db-url")
                 .long("db-url")
                 .value_name("DATABASE_URL")
-                .help("URL to the database"),
+                .help("URL to the database, i.e. the path of an SQLite database file"),
         )
         .arg(
             Arg::with_name("idx-dir")
@@ -57,22 +249,213 @@ pub fn run() {
                 .value_name("INDEX_DIR")
                 .help("File system directory for the full-text search index"),
         )
+        .arg(
+            Arg::with_name("idx-language")
+                .long("idx-language")
+                .value_name("INDEX_LANGUAGE")
+                .help("Language of the full-text search index for stemming words (en, fr, de)"),
+        )
+        .arg(
+            Arg::with_name("idx-optimize-interval")
+                .long("idx-optimize-interval")
+                .value_name("HOURS")
+                .help("Interval for merging all segments of the full-text search index (0 = disabled, default = 24)"),
+        )
+        .arg(
+            Arg::with_name("scout-report-interval")
+                .long("scout-report-interval")
+                .value_name("HOURS")
+                .help("Interval for sending reports about their region to scouts (0 = disabled, default = 24)"),
+        )
+        .arg(
+            Arg::with_name("dataset-license")
+                .long("dataset-license")
+                .value_name("LICENSE")
+                .help("License of the whole dataset that is included in all exports, e.g. ODbL-1.0"),
+        )
+        .arg(
+            Arg::with_name("tag-synonyms")
+                .long("tag-synonyms")
+                .value_name("CSV_FILE")
+                .help("Synonyms of tags for suggesting tags from text with the columns synonym,tag"),
+        )
         .arg(
             Arg::with_name("enable-cors")
                 .long("enable-cors")
                 .help("Allow requests from any origin"),
         )
+        .arg(
+            Arg::with_name("trusted-proxy")
+                .long("trusted-proxy")
+                .value_name("IP")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(validate_ip_addr)
+                .help("Address of a reverse proxy whose X-Real-IP header identifies the client"),
+        )
+        .arg(
+            Arg::with_name("max-streaming-clients")
+                .long("max-streaming-clients")
+                .value_name("COUNT")
+                .validator(validate_count)
+                .help("Maximum number of clients that wait for changes, lower than the number of workers (default = half of the workers)"),
+        )
+        .arg(
+            Arg::with_name("ratings-require-login")
+                .long("ratings-require-login")
+                .help("Only accept ratings from users with a confirmed e-mail address"),
+        )
+        .arg(
+            Arg::with_name("max-bbox-subscriptions")
+                .long("max-bbox-subscriptions")
+                .value_name("COUNT")
+                .help("Maximum number of bounding box subscriptions per user (0 = disabled, default = 1)"),
+        )
+        .arg(
+            Arg::with_name("max-tag-subscriptions")
+                .long("max-tag-subscriptions")
+                .value_name("COUNT")
+                .help("Maximum number of tag subscriptions per user (0 = disabled, default = 10)"),
+        )
+        .arg(
+            Arg::with_name("max-saved-searches")
+                .long("max-saved-searches")
+                .value_name("COUNT")
+                .help("Maximum number of saved searches per user (0 = disabled, default = 10)"),
+        )
         .arg(
             Arg::with_name("fix-event-address-location")
                 .long("fix-event-address-location")
                 .help("Update the location of ALL events by resolving their address"),
         )
+        .subcommand(
+            SubCommand::with_name("index")
+                .about("Maintain the full-text search index")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("rebuild")
+                        .about("Rebuild the index in INDEX_DIR from all places and events in the database while the server is stopped"),
+                )
+                .subcommand(
+                    SubCommand::with_name("optimize")
+                        .about("Merge all segments of the index in INDEX_DIR"),
+                )
+                .subcommand(
+                    SubCommand::with_name("snapshot")
+                        .about("Upload a snapshot of the index in INDEX_DIR to the object storage (S3_ENDPOINT)")
+                        .arg(
+                            Arg::with_name("retention")
+                                .long("retention")
+                                .value_name("COUNT")
+                                .validator(validate_retention)
+                                .help("Number of snapshots to keep in the object storage (default = 7)"),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("query")
+                .about("Search for places in the database without starting the web server")
+                .arg(
+                    Arg::with_name("text")
+                        .value_name("TEXT")
+                        .help("Search terms including #hashtags"),
+                )
+                .arg(
+                    Arg::with_name("bbox")
+                        .long("bbox")
+                        .value_name("LAT1,LNG1,LAT2,LNG2")
+                        .help("Bounding box of the search area (default = whole world)"),
+                )
+                .arg(
+                    Arg::with_name("tag")
+                        .long("tag")
+                        .value_name("TAG")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Only places with this tag"),
+                )
+                .arg(
+                    Arg::with_name("category")
+                        .long("category")
+                        .value_name("CATEGORY_ID")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Only places of this category"),
+                )
+                .arg(
+                    Arg::with_name("fuzzy")
+                        .long("fuzzy")
+                        .value_name("DISTANCE")
+                        .help("Tolerate typos in the search terms up to this edit distance"),
+                )
+                .arg(
+                    Arg::with_name("boost-quality")
+                        .long("boost-quality")
+                        .help("Prefer more complete places"),
+                )
+                .arg(
+                    Arg::with_name("limit")
+                        .long("limit")
+                        .value_name("COUNT")
+                        .help("Maximum number of results (default = 20)"),
+                )
+                .arg(
+                    Arg::with_name("json")
+                        .long("json")
+                        .help("Print the results as JSON instead of a table"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("backup")
+                .about("Upload a snapshot of the database to the object storage (S3_ENDPOINT)")
+                .arg(
+                    Arg::with_name("retention")
+                        .long("retention")
+                        .value_name("COUNT")
+                        .validator(validate_retention)
+                        .help("Number of backups to keep in the object storage (default = 7)"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("anonymize-user")
+                .about("Delete a user account while keeping all places, events, ratings and comments")
+                .arg(
+                    Arg::with_name("email")
+                        .value_name("EMAIL")
+                        .required(true)
+                        .help("E-mail address of the user"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("user")
+                .about("Manage user accounts")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("import")
+                        .about("Create users with confirmed e-mail addresses from a CSV file with the columns email and role (user, scout, admin)")
+                        .arg(
+                            Arg::with_name("csv")
+                                .value_name("CSV_FILE")
+                                .required(true)
+                                .help("Path of the CSV file"),
+                        )
+                        .arg(
+                            Arg::with_name("dry-run")
+                                .long("dry-run")
+                                .help("Report the users that would be created without importing them"),
+                        ),
+                ),
+        )
         .get_matches();
 
     let db_url = matches
         .value_of("db-url")
         .map(ToString::to_string)
         .unwrap_or_else(|| env::var("DATABASE_URL").unwrap_or_else(|_| DEFAULT_DB_URL.to_string()));
+    if let Err(err) = check_sqlite_db_url(&db_url) {
+        error!("{}", err);
+        std::process::exit(1);
+    }
     info!(
         "Connecting to SQLite database '{}' (pool size = {})",
         db_url, DB_CONNECTION_POOL_SIZE
@@ -87,20 +470,154 @@ pub fn run() {
         .map(ToString::to_string)
         .or_else(|| env::var("INDEX_DIR").map(Option::Some).unwrap_or(None));
     let idx_path = idx_dir.as_ref().map(|dir| Path::new(dir));
-    info!("Initializing Tantivy full-text search engine");
-    let search_engine = tantivy::SearchEngine::init_with_path(idx_path).unwrap();
+    let idx_language = matches
+        .value_of("idx-language")
+        .map(ToString::to_string)
+        .or_else(|| env::var("INDEX_LANGUAGE").map(Option::Some).unwrap_or(None))
+        .map(|language| language.parse::<tantivy::TextLanguage>().unwrap());
 
-    #[allow(clippy::match_single_binding)]
     match matches.subcommand() {
+        ("index", Some(index_matches)) => match index_matches.subcommand() {
+            ("rebuild", Some(_)) => {
+                rebuild_index(&connections, idx_path, idx_language);
+            }
+            ("optimize", Some(_)) => {
+                info!("Optimizing full-text search index...");
+                let idx_path = idx_path.expect("Missing directory of the full-text search index");
+                let mut search_engine =
+                    tantivy::SearchEngine::open_with_path(idx_path, idx_language).unwrap();
+                search_engine.optimize_index().unwrap();
+                info!("Finished optimizing full-text search index");
+            }
+            ("snapshot", Some(snapshot_matches)) => {
+                let storage = OBJECT_STORAGE_GW
+                    .as_ref()
+                    .expect("Missing configuration of the object storage");
+                let idx_path = idx_path.expect("Missing directory of the full-text search index");
+                info!("Uploading full-text search index snapshot...");
+                flows::backup_index(
+                    idx_path,
+                    idx_language,
+                    storage,
+                    backup_retention(snapshot_matches),
+                )
+                .unwrap();
+                info!("Finished uploading full-text search index snapshot");
+            }
+            _ => unreachable!(),
+        },
+        ("query", Some(query_matches)) => {
+            query_places(&connections, query_matches);
+        }
+        ("backup", Some(backup_matches)) => {
+            let storage = OBJECT_STORAGE_GW
+                .as_ref()
+                .expect("Missing configuration of the object storage");
+            info!("Uploading database backup...");
+            flows::backup_database(&connections, storage, backup_retention(backup_matches))
+                .unwrap();
+            info!("Finished uploading database backup");
+        }
+        ("anonymize-user", Some(anonymize_matches)) => {
+            let email = anonymize_matches.value_of("email").unwrap();
+            flows::anonymize_user(&connections, email).unwrap();
+            info!("Anonymized user {}", email);
+        }
+        ("user", Some(user_matches)) => match user_matches.subcommand() {
+            ("import", Some(import_matches)) => {
+                import_users(&connections, import_matches);
+            }
+            _ => unreachable!(),
+        },
         _ => {
-            if matches.is_present("fix-event-address-location") {
+            let fix_event_locations = matches.is_present("fix-event-address-location");
+            if fix_event_locations {
                 info!("Updating all event locations...");
                 update_event_locations(&mut *connections.exclusive().unwrap()).unwrap();
             }
+            // An existing index is reused unless the events have
+            // been modified in the meantime
+            let existing_search_engine =
+                idx_path
+                    .filter(|_| !fix_event_locations)
+                    .and_then(|idx_path| {
+                        tantivy::SearchEngine::open_with_path(idx_path, idx_language)
+                            .map_err(|err| {
+                                warn!("Failed to reuse the full-text search index: {}", err);
+                            })
+                            .ok()
+                    });
+            let mut search_engine = if let Some(search_engine) = existing_search_engine {
+                search_engine
+            } else {
+                info!("Initializing Tantivy full-text search engine");
+                let mut search_engine =
+                    tantivy::SearchEngine::init_with_path(idx_path, idx_language).unwrap();
+                info!("Indexing all places and events...");
+                flows::rebuild_index(&connections, &mut search_engine).unwrap();
+                search_engine
+            };
+            let curated_tags =
+                usecases::load_curated_tag_boosts(&*connections.shared().unwrap()).unwrap();
+            search_engine.set_curated_tags(&curated_tags);
+            let tag_ownerships = connections
+                .shared()
+                .unwrap()
+                .get_all_tag_ownerships()
+                .unwrap();
+            search_engine.set_tag_ownerships(&tag_ownerships);
+            let categories = connections.shared().unwrap().all_categories().unwrap();
+            search_engine.set_categories(&categories);
+            let search_engine = search_engine.with_background_indexing(INDEX_COMMIT_INTERVAL);
+            let mut subscription_quota = usecases::SubscriptionQuota::default();
+            if let Some(max_count) = matches.value_of("max-bbox-subscriptions") {
+                subscription_quota.max_bbox_subscriptions = max_count.parse().unwrap();
+            }
+            if let Some(max_count) = matches.value_of("max-tag-subscriptions") {
+                subscription_quota.max_tag_subscriptions = max_count.parse().unwrap();
+            }
+            if let Some(max_count) = matches.value_of("max-saved-searches") {
+                subscription_quota.max_saved_searches = max_count.parse().unwrap();
+            }
+            let index_optimize_interval_hours = matches
+                .value_of("idx-optimize-interval")
+                .map(|hours| hours.parse().unwrap())
+                .unwrap_or(DEFAULT_INDEX_OPTIMIZE_INTERVAL_HOURS);
+            let scout_report_interval_hours = matches
+                .value_of("scout-report-interval")
+                .map(|hours| hours.parse().unwrap())
+                .unwrap_or(DEFAULT_SCOUT_REPORT_INTERVAL_HOURS);
+            let web_cfg = web::Cfg {
+                ratings_require_login: matches.is_present("ratings-require-login"),
+                subscription_quota,
+                index_optimize_interval: if index_optimize_interval_hours > 0 {
+                    Some(Duration::from_secs(index_optimize_interval_hours * 60 * 60))
+                } else {
+                    None
+                },
+                scout_report_interval: if scout_report_interval_hours > 0 {
+                    Some(Duration::from_secs(scout_report_interval_hours * 60 * 60))
+                } else {
+                    None
+                },
+                dataset_license: matches.value_of("dataset-license").map(ToOwned::to_owned),
+                tag_synonyms: matches
+                    .value_of("tag-synonyms")
+                    .map(read_tag_synonyms)
+                    .unwrap_or_default(),
+                trusted_proxies: matches
+                    .values_of("trusted-proxy")
+                    .map(|addrs| addrs.map(|addr| addr.parse().expect("validated")).collect())
+                    .unwrap_or_default(),
+                max_streaming_clients: matches
+                    .value_of("max-streaming-clients")
+                    .map(|count| count.parse().expect("validated")),
+            };
             web::run(
                 connections,
                 search_engine,
                 matches.is_present("enable-cors"),
+                web_cfg,
             );
         }
     }
@@ -0,0 +1,142 @@
+use super::*;
+
+#[get("/server/announcements")]
+pub fn get_current_announcements(
+    connections: sqlite::Connections,
+) -> Result<Vec<json::Announcement>> {
+    let announcements = usecases::load_current_announcements(&*connections.shared()?)?;
+    Ok(Json(announcements.into_iter().map(Into::into).collect()))
+}
+
+#[get("/admin/announcements")]
+pub fn get_announcements(
+    connections: sqlite::Connections,
+    login: Login,
+) -> Result<Vec<json::Announcement>> {
+    let announcements = usecases::load_announcements(&*connections.shared()?, &login.0)?;
+    Ok(Json(announcements.into_iter().map(Into::into).collect()))
+}
+
+#[post("/admin/announcements", format = "application/json", data = "<data>")]
+pub fn post_announcement(
+    connections: sqlite::Connections,
+    login: Login,
+    data: Json<json::NewAnnouncement>,
+) -> Result<json::Announcement> {
+    let json::NewAnnouncement {
+        message,
+        severity,
+        starts_at,
+        ends_at,
+    } = data.into_inner();
+    let new_announcement = usecases::NewAnnouncement {
+        message,
+        severity: severity.into(),
+        starts_at: starts_at.map(TimestampMs::from_seconds),
+        ends_at: ends_at.map(TimestampMs::from_seconds),
+    };
+    let announcement =
+        usecases::create_announcement(&*connections.exclusive()?, &login.0, new_announcement)?;
+    Ok(Json(announcement.into()))
+}
+
+#[delete("/admin/announcements/<id>")]
+pub fn delete_announcement(
+    connections: sqlite::Connections,
+    login: Login,
+    id: String,
+) -> StatusResult {
+    usecases::delete_announcement(&*connections.exclusive()?, &login.0, &id)?;
+    Ok(Status::NoContent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::web::{api::tests::prelude::*, tests::register_user};
+
+    #[test]
+    fn manage_announcements() {
+        let (client, db) = setup();
+        register_user(&db, "admin@example.com", "secret", true);
+        let mut admin = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("admin@example.com")
+            .unwrap();
+        admin.role = Role::Admin;
+        db.exclusive().unwrap().update_user(&admin).unwrap();
+
+        let new_announcement = r#"{"message":"Maintenance tonight","severity":"warning"}"#;
+
+        // Not logged in
+        let res = client
+            .post("/admin/announcements")
+            .header(ContentType::JSON)
+            .body(new_announcement)
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let mut res = client
+            .post("/admin/announcements")
+            .header(ContentType::JSON)
+            .body(new_announcement)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let announcement: json::Announcement =
+            serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!("Maintenance tonight", announcement.message);
+        assert_eq!(json::AnnouncementSeverity::Warning, announcement.severity);
+
+        // Upcoming announcements are not displayed yet
+        let starts_at = TimestampMs::now().into_seconds() + 3600;
+        let res = client
+            .post("/admin/announcements")
+            .header(ContentType::JSON)
+            .body(format!(
+                r#"{{"message":"Campaign","severity":"info","starts_at":{}}}"#,
+                starts_at
+            ))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client
+            .post("/admin/announcements")
+            .header(ContentType::JSON)
+            .body(r#"{"message":" ","severity":"info"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::BadRequest);
+
+        let mut res = client.get("/admin/announcements").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let announcements: Vec<json::Announcement> =
+            serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!(2, announcements.len());
+
+        let mut res = client.get("/server/announcements").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let announcements: Vec<json::Announcement> =
+            serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!(1, announcements.len());
+        assert_eq!(announcement.id, announcements[0].id);
+
+        let res = client
+            .delete(format!("/admin/announcements/{}", announcement.id))
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let res = client
+            .delete(format!("/admin/announcements/{}", announcement.id))
+            .dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+
+        let mut res = client.get("/server/announcements").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!("[]", res.body_string().unwrap());
+    }
+}
@@ -0,0 +1,36 @@
+use super::*;
+
+#[post("/actions/archive", format = "application/json", data = "<data>")]
+pub fn post_archive_batch(
+    login: Login,
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    notify: Notify,
+    data: Json<json::ArchiveBatch>,
+) -> Result<json::ArchiveBatchReport> {
+    let json::ArchiveBatch { kind, ids, comment } = data.into_inner();
+    let kind = match kind {
+        json::ArchiveKind::Place => usecases::ArchiveKind::Place,
+        json::ArchiveKind::Event => usecases::ArchiveKind::Event,
+        json::ArchiveKind::Rating => usecases::ArchiveKind::Rating,
+    };
+    let ids: Vec<_> = ids.iter().map(String::as_str).collect();
+    let usecases::ArchiveBatchReport {
+        archived,
+        not_found,
+        failed,
+    } = flows::archive_batch(
+        &connections,
+        &mut search_engine,
+        &*notify,
+        &login.0,
+        kind,
+        &ids,
+        comment.as_deref(),
+    )?;
+    Ok(Json(json::ArchiveBatchReport {
+        archived,
+        not_found,
+        failed,
+    }))
+}
@@ -0,0 +1,123 @@
+use super::*;
+use serde::{de::IntoDeserializer, Deserialize};
+
+// Limit the number of entries per request
+const AUDIT_LOG_MAX_COUNT: u64 = 500;
+
+#[allow(clippy::too_many_arguments)]
+#[get("/audit-log?<action>&<created_by>&<item>&<since>&<until>&<offset>&<limit>")]
+pub fn get_audit_log(
+    connections: sqlite::Connections,
+    login: Login,
+    action: Option<String>,
+    created_by: Option<String>,
+    item: Option<String>,
+    since: Option<i64>, // in seconds
+    until: Option<i64>, // in seconds
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<json::AuditLogEntry>> {
+    let action = action
+        .as_deref()
+        .map(parse_audit_action)
+        .transpose()
+        .map_err(Error::Parameter)?;
+    let filter = AuditLogFilter {
+        action,
+        created_by,
+        item,
+        since: since.map(TimestampMs::from_seconds),
+        until: until.map(TimestampMs::from_seconds),
+    };
+    let pagination = Pagination {
+        offset,
+        limit: Some(
+            limit
+                .unwrap_or(AUDIT_LOG_MAX_COUNT)
+                .min(AUDIT_LOG_MAX_COUNT),
+        ),
+    };
+    let entries =
+        usecases::load_audit_log(&*connections.shared()?, &login.0, &filter, &pagination)?;
+    Ok(Json(entries.into_iter().map(Into::into).collect()))
+}
+
+fn parse_audit_action(action: &str) -> result::Result<AuditAction, ParameterError> {
+    json::AuditAction::deserialize(action.trim().into_deserializer())
+        .map(Into::into)
+        .map_err(|_: serde::de::value::Error| ParameterError::InvalidAuditAction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::web::{api::tests::prelude::*, tests::register_user};
+
+    fn register_user_with_role(db: &sqlite::Connections, email: &str, role: Role) {
+        register_user(db, email, "secret", true);
+        let mut user = db.shared().unwrap().get_user_by_email(email).unwrap();
+        user.role = role;
+        db.exclusive().unwrap().update_user(&user).unwrap();
+    }
+
+    fn login(client: &Client, email: &str) {
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(format!(r#"{{"email":"{}","password":"secret"}}"#, email))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+    }
+
+    fn audit_log(client: &Client, query: &str) -> Vec<json::AuditLogEntry> {
+        let mut res = client.get(format!("/audit-log{}", query)).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        serde_json::from_str(&body).unwrap()
+    }
+
+    #[test]
+    fn record_actions_of_admins() {
+        let (client, db) = setup();
+        register_user_with_role(&db, "admin@example.com", Role::Admin);
+        register_user_with_role(&db, "scout@example.com", Role::Scout);
+
+        // Not logged in
+        let res = client.get("/audit-log").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        login(&client, "admin@example.com");
+        assert!(audit_log(&client, "").is_empty());
+        let res = client
+            .post("/admin/curated-tags")
+            .header(ContentType::JSON)
+            .body(r#"{"tag":"foo","boost":2.0}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let res = client.delete("/admin/curated-tags/foo").dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+
+        // Most recent first
+        let entries = audit_log(&client, "");
+        assert_eq!(2, entries.len());
+        assert_eq!(json::AuditAction::DeleteCuratedTag, entries[0].action);
+        assert_eq!(json::AuditAction::SaveCuratedTag, entries[1].action);
+        assert_eq!("foo", entries[1].item);
+        assert_eq!(Some("admin@example.com"), entries[1].act.by.as_deref());
+        assert_eq!(Some("2"), entries[1].act.ctx.as_deref());
+
+        let entries = audit_log(&client, "?action=save_curated_tag");
+        assert_eq!(1, entries.len());
+        assert_eq!(json::AuditAction::SaveCuratedTag, entries[0].action);
+        assert!(audit_log(&client, "?created_by=scout@example.com").is_empty());
+        assert!(audit_log(&client, "?item=bar").is_empty());
+        assert_eq!(1, audit_log(&client, "?limit=1").len());
+        let res = client.get("/audit-log?action=foo").dispatch();
+        assert_eq!(res.status(), Status::BadRequest);
+
+        // Only admins are allowed to inspect the audit log
+        login(&client, "scout@example.com");
+        let res = client.get("/audit-log").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+    }
+}
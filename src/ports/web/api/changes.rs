@@ -0,0 +1,260 @@
+use super::*;
+
+use crate::infrastructure::{
+    live_changes::{LiveChange, LiveChanges, StreamingClient},
+    place_changes::{PlaceChange, PlaceChanges},
+};
+use rocket::{
+    http::Header,
+    request::{self, FromRequest},
+    Outcome, Request,
+};
+use serde_json::json;
+use std::{
+    io::{self, Cursor, Read},
+    time::{Duration, Instant},
+};
+
+// Requests are answered after this period even
+// if nothing has changed in the meantime
+const WAIT_FOR_CHANGES_TIMEOUT: Duration = Duration::from_secs(20);
+
+// The delay before clients reconnect to continue waiting
+const RECONNECT_DELAY_MS: u64 = 500;
+
+// Streams are finished after this period and
+// continued by reconnecting clients
+const CHANGES_STREAM_DURATION: Duration = Duration::from_secs(5 * 60);
+
+// Comments are sent periodically to keep idle
+// connections open, e.g. through proxies
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+// The delay before clients reconnect if too many
+// clients are already waiting for changes
+const BUSY_RECONNECT_DELAY_MS: u64 = 10_000;
+
+/// The id of the last received event when reconnecting
+pub struct LastEventId(Option<u64>);
+
+impl<'a, 'r> FromRequest<'a, 'r> for LastEventId {
+    type Error = !;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, !> {
+        let id = request
+            .headers()
+            .get_one("Last-Event-ID")
+            .and_then(|id| id.trim().parse().ok());
+        Outcome::Success(LastEventId(id))
+    }
+}
+
+fn event(seq: u64, change: PlaceChange) -> String {
+    let (event, data) = match change {
+        PlaceChange::Revision(revision) => ("revision", json!({ "revision": revision })),
+        PlaceChange::Status(status) => (
+            "status",
+            json!({ "status": json::ReviewStatus::from(status) }),
+        ),
+        PlaceChange::Rating { id } => ("rating", json!({ "id": id })),
+        PlaceChange::Comment { id, rating_id } => {
+            ("comment", json!({ "id": id, "rating": rating_id }))
+        }
+    };
+    format!("id: {}\nevent: {}\ndata: {}\n\n", seq, event, data)
+}
+
+/// Server-sent events (SSE) with the changes of a single place.
+///
+/// The response is finished after the first changes or when the
+/// timeout expires. Clients reconnect with the id of the last
+/// event to continue, i.e. like `EventSource` does.
+///
+/// Waiting for changes shares the limited slots with the
+/// stream of all changes.
+#[get("/entries/<id>/events/stream?<last_event_id>")]
+pub fn get_entry_events_stream(
+    connections: sqlite::Connections,
+    live_changes: State<LiveChanges>,
+    place_changes: State<PlaceChanges>,
+    header: LastEventId,
+    id: String,
+    last_event_id: Option<u64>,
+) -> result::Result<Response<'static>, AppError> {
+    // Fails if the place doesn't exist
+    let _ = connections.shared()?.get_place(&id.as_str().into())?;
+    let _client = match live_changes.start_streaming() {
+        Some(client) => client,
+        None => {
+            return Ok(Response::build()
+                .header(ContentType::new("text", "event-stream"))
+                .header(Header::new("Cache-Control", "no-cache"))
+                .sized_body(Cursor::new(format!(
+                    "retry: {}\n\n",
+                    BUSY_RECONNECT_DELAY_MS
+                )))
+                .finalize())
+        }
+    };
+    let after = header.0.or(last_event_id);
+    let (changes, last_seq) = place_changes.wait_for_changes(&id, after, WAIT_FOR_CHANGES_TIMEOUT);
+    let mut stream = format!("retry: {}\n\n", RECONNECT_DELAY_MS);
+    for (seq, change) in changes {
+        stream.push_str(&event(seq, change));
+    }
+    // Continue after the most recent change of all places
+    // when reconnecting without dispatching an event
+    stream.push_str(&format!("id: {}\n\n", last_seq));
+    Ok(Response::build()
+        .header(ContentType::new("text", "event-stream"))
+        .header(Header::new("Cache-Control", "no-cache"))
+        .sized_body(Cursor::new(stream))
+        .finalize())
+}
+
+fn live_change_event(seq: u64, change: &LiveChange) -> String {
+    let data = json!({
+        "entity": change.entity.as_str(),
+        "id": change.id,
+        "action": change.action.as_str(),
+    });
+    format!("id: {}\ndata: {}\n\n", seq, data)
+}
+
+/// Server-sent events (SSE) with the changes of all places,
+/// events, and ratings.
+///
+/// Changes are pushed while the response is streamed. The stream
+/// is finished after a while and clients reconnect with the id of
+/// the last event to continue, i.e. like `EventSource` does.
+#[get("/stream/changes?<last_event_id>")]
+pub fn get_changes_stream(
+    live_changes: State<LiveChanges>,
+    header: LastEventId,
+    last_event_id: Option<u64>,
+) -> Response<'static> {
+    let stream = ChangesStream::new(
+        live_changes.inner().clone(),
+        header.0.or(last_event_id),
+        CHANGES_STREAM_DURATION,
+    );
+    Response::build()
+        .header(ContentType::new("text", "event-stream"))
+        .header(Header::new("Cache-Control", "no-cache"))
+        .streamed_body(stream)
+        .finalize()
+}
+
+/// Waits for changes and writes them as events while
+/// the response is streamed.
+pub struct ChangesStream {
+    live_changes: LiveChanges,
+    // Missing if too many clients are streaming
+    client: Option<StreamingClient>,
+    after: u64,
+    deadline: Instant,
+    chunk: Cursor<Vec<u8>>,
+    flushed: bool,
+}
+
+impl ChangesStream {
+    fn new(live_changes: LiveChanges, last_event_id: Option<u64>, duration: Duration) -> Self {
+        let client = live_changes.start_streaming();
+        let after = live_changes.resume_after(last_event_id);
+        let head = if client.is_some() {
+            // Continue after the most recent change when
+            // reconnecting without receiving any events
+            format!("retry: {}\nid: {}\n\n", RECONNECT_DELAY_MS, after)
+        } else {
+            format!("retry: {}\n\n", BUSY_RECONNECT_DELAY_MS)
+        };
+        Self {
+            live_changes,
+            client,
+            after,
+            deadline: Instant::now() + duration,
+            chunk: Cursor::new(head.into_bytes()),
+            flushed: false,
+        }
+    }
+}
+
+impl Read for ChangesStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let n = self.chunk.read(buf)?;
+            if n > 0 || buf.is_empty() {
+                self.flushed = false;
+                return Ok(n);
+            }
+            if !self.flushed {
+                // Rocket sends the pending events immediately
+                // instead of waiting for a full chunk
+                self.flushed = true;
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            let now = Instant::now();
+            if self.client.is_none() || now >= self.deadline {
+                return Ok(0);
+            }
+            let timeout = KEEP_ALIVE_INTERVAL.min(self.deadline - now);
+            let changes = self.live_changes.wait_for_changes(self.after, timeout);
+            let mut chunk = String::new();
+            if changes.is_empty() {
+                if Instant::now() >= self.deadline {
+                    return Ok(0);
+                }
+                chunk.push_str(": keep-alive\n\n");
+            }
+            for (seq, change) in changes {
+                chunk.push_str(&live_change_event(seq, &change));
+                self.after = seq;
+            }
+            self.chunk = Cursor::new(chunk.into_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::live_changes::{ChangeAction, ChangedEntity};
+
+    fn read_until_end(mut stream: ChangesStream) -> String {
+        let mut body = String::new();
+        let mut buf = [0; 64];
+        loop {
+            match stream.read(&mut buf) {
+                Ok(0) => return body,
+                Ok(n) => body.push_str(std::str::from_utf8(&buf[..n]).unwrap()),
+                Err(err) => assert_eq!(io::ErrorKind::WouldBlock, err.kind()),
+            }
+        }
+    }
+
+    #[test]
+    fn stream_changes_after_last_event_id() {
+        let live_changes = LiveChanges::default();
+        live_changes.limit_streaming_clients(1);
+        live_changes.publish(ChangedEntity::Place, "a", ChangeAction::Created);
+        live_changes.publish(ChangedEntity::Event, "b", ChangeAction::Deleted);
+        let stream = ChangesStream::new(live_changes, Some(1), Duration::from_millis(10));
+        assert_eq!(
+            "retry: 500\nid: 1\n\n\
+             id: 2\ndata: {\"action\":\"deleted\",\"entity\":\"event\",\"id\":\"b\"}\n\n",
+            read_until_end(stream)
+        );
+    }
+
+    #[test]
+    fn finish_stream_if_too_many_clients_are_streaming() {
+        let live_changes = LiveChanges::default();
+        live_changes.limit_streaming_clients(2);
+        let clients: Vec<_> = (0..2)
+            .map(|_| live_changes.start_streaming().unwrap())
+            .collect();
+        let stream = ChangesStream::new(live_changes, None, Duration::from_secs(60));
+        assert_eq!("retry: 10000\n\n", read_until_end(stream));
+        drop(clients);
+    }
+}
@@ -0,0 +1,199 @@
+use super::*;
+
+#[get("/admin/curated-tags")]
+pub fn get_curated_tags(
+    connections: sqlite::Connections,
+    login: Login,
+) -> Result<Vec<json::CuratedTag>> {
+    let curated_tags = usecases::load_curated_tags(&*connections.shared()?, &login.0)?;
+    Ok(Json(curated_tags.into_iter().map(Into::into).collect()))
+}
+
+#[post("/admin/curated-tags", format = "application/json", data = "<data>")]
+pub fn post_curated_tag(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    login: Login,
+    data: Json<json::CuratedTag>,
+) -> StatusResult {
+    let json::CuratedTag { tag, boost } = data.into_inner();
+    flows::save_curated_tag(&connections, &mut search_engine, &login.0, &tag, boost)?;
+    Ok(Status::NoContent)
+}
+
+#[delete("/admin/curated-tags/<tag>")]
+pub fn delete_curated_tag(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    login: Login,
+    tag: String,
+) -> StatusResult {
+    flows::delete_curated_tag(&connections, &mut search_engine, &login.0, &tag)?;
+    Ok(Status::NoContent)
+}
+
+#[get("/admin/curated-organizations")]
+pub fn get_curated_orgs(
+    connections: sqlite::Connections,
+    login: Login,
+) -> Result<Vec<json::CuratedOrganization>> {
+    let curated_orgs = usecases::load_curated_orgs(&*connections.shared()?, &login.0)?;
+    Ok(Json(curated_orgs.into_iter().map(Into::into).collect()))
+}
+
+#[post(
+    "/admin/curated-organizations",
+    format = "application/json",
+    data = "<data>"
+)]
+pub fn post_curated_org(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    login: Login,
+    data: Json<json::CuratedOrganization>,
+) -> StatusResult {
+    let json::CuratedOrganization { org_id, boost } = data.into_inner();
+    flows::save_curated_org(&connections, &mut search_engine, &login.0, &org_id, boost)?;
+    Ok(Status::NoContent)
+}
+
+#[delete("/admin/curated-organizations/<org_id>")]
+pub fn delete_curated_org(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    login: Login,
+    org_id: String,
+) -> StatusResult {
+    flows::delete_curated_org(&connections, &mut search_engine, &login.0, &org_id)?;
+    Ok(Status::NoContent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::web::{api::tests::prelude::*, tests::register_user};
+
+    #[test]
+    fn curate_tags() {
+        let (client, db) = setup();
+        register_user(&db, "admin@example.com", "secret", true);
+        let mut admin = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("admin@example.com")
+            .unwrap();
+        admin.role = Role::Admin;
+        db.exclusive().unwrap().update_user(&admin).unwrap();
+
+        // Not logged in
+        let res = client
+            .post("/admin/curated-tags")
+            .header(ContentType::JSON)
+            .body(r#"{"tag":"foo","boost":2.0}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let res = client
+            .post("/admin/curated-tags")
+            .header(ContentType::JSON)
+            .body(r#"{"tag":"foo","boost":2.0}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let res = client
+            .post("/admin/curated-tags")
+            .header(ContentType::JSON)
+            .body(r#"{"tag":"bar","boost":-1.0}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::BadRequest);
+
+        let mut res = client.get("/admin/curated-tags").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        let curated_tags: Vec<json::CuratedTag> = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            vec![json::CuratedTag {
+                tag: "foo".into(),
+                boost: 2.0
+            }],
+            curated_tags
+        );
+
+        let res = client.delete("/admin/curated-tags/foo").dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let res = client.delete("/admin/curated-tags/foo").dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn curate_orgs() {
+        let (client, db) = setup();
+        register_user(&db, "admin@example.com", "secret", true);
+        let mut admin = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("admin@example.com")
+            .unwrap();
+        admin.role = Role::Admin;
+        db.exclusive().unwrap().update_user(&admin).unwrap();
+        db.exclusive()
+            .unwrap()
+            .create_org(Organization {
+                id: "org".into(),
+                name: "Org".into(),
+                owned_tags: vec!["foo".into()],
+            })
+            .unwrap();
+
+        // Not logged in
+        let res = client
+            .post("/admin/curated-organizations")
+            .header(ContentType::JSON)
+            .body(r#"{"org_id":"org","boost":2.0}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let res = client
+            .post("/admin/curated-organizations")
+            .header(ContentType::JSON)
+            .body(r#"{"org_id":"unknown","boost":2.0}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+        let res = client
+            .post("/admin/curated-organizations")
+            .header(ContentType::JSON)
+            .body(r#"{"org_id":"org","boost":2.0}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+
+        let mut res = client.get("/admin/curated-organizations").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        let curated_orgs: Vec<json::CuratedOrganization> = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            vec![json::CuratedOrganization {
+                org_id: "org".into(),
+                boost: 2.0
+            }],
+            curated_orgs
+        );
+
+        let res = client.delete("/admin/curated-organizations/org").dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let res = client.delete("/admin/curated-organizations/org").dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+    }
+}
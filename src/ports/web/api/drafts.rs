@@ -0,0 +1,158 @@
+use super::*;
+
+use serde_json::{Map, Value};
+
+#[derive(Serialize, Debug)]
+pub struct EntryDraft {
+    id: String,
+    created_at: i64,
+    updated_at: i64,
+    data: Value,
+}
+
+impl From<PlaceDraft> for EntryDraft {
+    fn from(from: PlaceDraft) -> Self {
+        let PlaceDraft {
+            id,
+            created_at,
+            updated_at,
+            data,
+            ..
+        } = from;
+        Self {
+            id: id.into(),
+            created_at: created_at.into_inner(),
+            updated_at: updated_at.into_inner(),
+            // Only JSON objects are stored
+            data: serde_json::from_str(&data).unwrap_or_default(),
+        }
+    }
+}
+
+#[get("/drafts/entries")]
+pub fn get_entry_drafts(connections: sqlite::Connections, login: Login) -> Result<Vec<EntryDraft>> {
+    let drafts = usecases::load_place_drafts(&*connections.shared()?, &login.0)?;
+    Ok(Json(drafts.into_iter().map(Into::into).collect()))
+}
+
+#[get("/drafts/entries/<id>")]
+pub fn get_entry_draft(
+    connections: sqlite::Connections,
+    login: Login,
+    id: String,
+) -> Result<EntryDraft> {
+    let draft = usecases::load_place_draft(&*connections.shared()?, &login.0, &id)?;
+    Ok(Json(draft.into()))
+}
+
+#[post("/drafts/entries", format = "application/json", data = "<data>")]
+pub fn post_entry_draft(
+    connections: sqlite::Connections,
+    login: Login,
+    data: Json<Map<String, Value>>,
+) -> Result<String> {
+    let data = serde_json::to_string(&data.into_inner())?;
+    let draft = usecases::create_place_draft(&*connections.exclusive()?, &login.0, data)?;
+    Ok(Json(draft.id.into()))
+}
+
+#[put("/drafts/entries/<id>", format = "application/json", data = "<data>")]
+pub fn put_entry_draft(
+    connections: sqlite::Connections,
+    login: Login,
+    id: String,
+    data: Json<Map<String, Value>>,
+) -> StatusResult {
+    let data = serde_json::to_string(&data.into_inner())?;
+    usecases::update_place_draft(&*connections.exclusive()?, &login.0, &id, data)?;
+    Ok(Status::NoContent)
+}
+
+#[delete("/drafts/entries/<id>")]
+pub fn delete_entry_draft(
+    connections: sqlite::Connections,
+    login: Login,
+    id: String,
+) -> StatusResult {
+    usecases::delete_place_draft(&*connections.exclusive()?, &login.0, &id)?;
+    Ok(Status::NoContent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::web::{api::tests::prelude::*, tests::register_user};
+
+    fn login(client: &Client, email: &str) {
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(format!(r#"{{"email":"{}","password":"secret"}}"#, email))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+    }
+
+    #[test]
+    fn save_and_resume_entry_drafts() {
+        let (client, db) = setup();
+        register_user(&db, "a@example.com", "secret", true);
+        register_user(&db, "b@example.com", "secret", true);
+
+        // Not logged in
+        let res = client
+            .post("/drafts/entries")
+            .header(ContentType::JSON)
+            .body(r#"{"title":"foo"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        login(&client, "a@example.com");
+        let res = client
+            .post("/drafts/entries")
+            .header(ContentType::JSON)
+            .body(r#"["foo"]"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::UnprocessableEntity);
+        let mut res = client
+            .post("/drafts/entries")
+            .header(ContentType::JSON)
+            .body(r#"{"title":"foo","lat":"48."}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let id: String =
+            serde_json::from_str(&res.body().and_then(|b| b.into_string()).unwrap()).unwrap();
+
+        let res = client
+            .put(format!("/drafts/entries/{}", id))
+            .header(ContentType::JSON)
+            .body(r#"{"title":"foo","lat":"48.7"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let mut res = client.get("/drafts/entries").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let drafts: serde_json::Value =
+            serde_json::from_str(&res.body().and_then(|b| b.into_string()).unwrap()).unwrap();
+        assert_eq!(1, drafts.as_array().unwrap().len());
+        assert_eq!(id, drafts[0]["id"]);
+        assert_eq!("48.7", drafts[0]["data"]["lat"]);
+
+        // Drafts are private
+        let res = client.post("/logout").header(ContentType::JSON).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        login(&client, "b@example.com");
+        let mut res = client.get("/drafts/entries").dispatch();
+        assert_eq!("[]", res.body().and_then(|b| b.into_string()).unwrap());
+        let res = client.get(format!("/drafts/entries/{}", id)).dispatch();
+        assert_eq!(res.status(), Status::Forbidden);
+        let res = client.delete(format!("/drafts/entries/{}", id)).dispatch();
+        assert_eq!(res.status(), Status::Forbidden);
+
+        let res = client.post("/logout").header(ContentType::JSON).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        login(&client, "a@example.com");
+        let res = client.delete(format!("/drafts/entries/{}", id)).dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let res = client.get(format!("/drafts/entries/{}", id)).dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+    }
+}
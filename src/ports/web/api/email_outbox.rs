@@ -0,0 +1,103 @@
+use super::*;
+use serde::{de::IntoDeserializer, Deserialize};
+
+// Limit the number of e-mails per request
+const OUTGOING_EMAILS_MAX_COUNT: u64 = 500;
+
+#[get("/admin/emails?<status>&<offset>&<limit>")]
+pub fn get_outgoing_emails(
+    connections: sqlite::Connections,
+    login: Login,
+    status: Option<String>,
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<json::OutgoingEmail>> {
+    let status = status
+        .as_deref()
+        .map(parse_email_status)
+        .transpose()
+        .map_err(Error::Parameter)?;
+    let pagination = Pagination {
+        offset,
+        limit: Some(
+            limit
+                .unwrap_or(OUTGOING_EMAILS_MAX_COUNT)
+                .min(OUTGOING_EMAILS_MAX_COUNT),
+        ),
+    };
+    let emails =
+        usecases::load_outgoing_emails(&*connections.shared()?, &login.0, status, &pagination)?;
+    Ok(Json(emails.into_iter().map(Into::into).collect()))
+}
+
+fn parse_email_status(status: &str) -> result::Result<OutgoingEmailStatus, ParameterError> {
+    json::OutgoingEmailStatus::deserialize(status.trim().into_deserializer())
+        .map(Into::into)
+        .map_err(|_: serde::de::value::Error| ParameterError::InvalidEmailStatus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::web::{api::tests::prelude::*, tests::register_user};
+
+    fn register_user_with_role(db: &sqlite::Connections, email: &str, role: Role) {
+        register_user(db, email, "secret", true);
+        let mut user = db.shared().unwrap().get_user_by_email(email).unwrap();
+        user.role = role;
+        db.exclusive().unwrap().update_user(&user).unwrap();
+    }
+
+    fn login(client: &Client, email: &str) {
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(format!(r#"{{"email":"{}","password":"secret"}}"#, email))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+    }
+
+    fn outgoing_emails(client: &Client, query: &str) -> Vec<json::OutgoingEmail> {
+        let mut res = client.get(format!("/admin/emails{}", query)).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        serde_json::from_str(&body).unwrap()
+    }
+
+    #[test]
+    fn inspect_outgoing_emails() {
+        let (client, db) = setup();
+        register_user_with_role(&db, "admin@example.com", Role::Admin);
+        register_user_with_role(&db, "scout@example.com", Role::Scout);
+        flows::enqueue_emails(&db, &[Email::from("foo@example.com")], "Hello", "World");
+
+        // Not logged in
+        let res = client.get("/admin/emails").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        login(&client, "admin@example.com");
+        let emails = outgoing_emails(&client, "");
+        assert_eq!(1, emails.len());
+        assert_eq!("foo@example.com", emails[0].recipient);
+        assert_eq!("Hello", emails[0].subject);
+        assert_eq!(json::OutgoingEmailStatus::Pending, emails[0].status);
+        assert_eq!(0, emails[0].attempts);
+        assert_eq!(1, outgoing_emails(&client, "?status=pending").len());
+        assert!(outgoing_emails(&client, "?status=failed").is_empty());
+        assert!(outgoing_emails(&client, "?offset=1").is_empty());
+        let res = client.get("/admin/emails?status=foo").dispatch();
+        assert_eq!(res.status(), Status::BadRequest);
+        // Sent e-mails are not kept
+        let res = client.get("/admin/emails?status=sent").dispatch();
+        assert_eq!(res.status(), Status::BadRequest);
+        // The body might contain secret links
+        let mut res = client.get("/admin/emails").dispatch();
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        assert!(!body.contains("World"));
+
+        // Only admins are allowed to inspect the outbox
+        login(&client, "scout@example.com");
+        let res = client.get("/admin/emails").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+    }
+}
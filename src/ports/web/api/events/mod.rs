@@ -1,6 +1,8 @@
-use super::{super::guards::Bearer, *};
+use super::{
+    super::guards::{scopes, ScopedBearer},
+    *,
+};
 use crate::{
-    adapters,
     core::{
         prelude::Result as CoreResult,
         util::{geo::MapBbox, validate},
@@ -59,7 +61,7 @@ pub fn post_event_with_token(
     connections: sqlite::Connections,
     mut search_engine: tantivy::SearchEngine,
     notify: Notify,
-    token: Bearer,
+    token: ScopedBearer<scopes::WriteEvents>,
     e: Json<usecases::NewEvent>,
 ) -> Result<String> {
     let mut e = e.into_inner();
@@ -68,7 +70,7 @@ pub fn post_event_with_token(
         &connections,
         &mut search_engine,
         &*notify,
-        Some(&token.0),
+        Some(&token.token),
         e,
     )?;
     Ok(Json(event.id.to_string()))
@@ -93,7 +95,7 @@ pub fn post_event(mut _db: sqlite::Connections, _e: Json<usecases::NewEvent>) ->
 
 #[get("/events/<id>")]
 pub fn get_event(db: sqlite::Connections, id: String) -> Result<json::Event> {
-    let mut ev = usecases::get_event(&*db.shared()?, &id)?;
+    let mut ev = usecases::get_event(&*db.shared()?, &id.into())?;
     ev.created_by = None; // don't show creators email to unregistered users
     Ok(Json(ev.into()))
 }
@@ -114,7 +116,7 @@ pub fn put_event_with_token(
     connections: sqlite::Connections,
     mut search_engine: tantivy::SearchEngine,
     notify: Notify,
-    token: Bearer,
+    token: ScopedBearer<scopes::WriteEvents>,
     id: &RawStr,
     e: Json<usecases::NewEvent>,
 ) -> Result<()> {
@@ -124,13 +126,55 @@ pub fn put_event_with_token(
         &connections,
         &mut search_engine,
         &*notify,
-        Some(&token.0),
+        Some(&token.token),
         id.to_string().into(),
         e,
     )?;
     Ok(Json(()))
 }
 
+#[put(
+    "/events/by-external-id/<_external_id>",
+    format = "application/json",
+    data = "<_e>",
+    rank = 2
+)]
+// Only organizations are allowed to upsert events
+pub fn put_event_by_external_id_without_token(
+    _external_id: &RawStr,
+    _e: Json<usecases::NewEvent>,
+) -> HttpStatus {
+    HttpStatus::Unauthorized
+}
+
+/// Create or update an event by the id in the system of the
+/// organization that is authorized by the token.
+#[put(
+    "/events/by-external-id/<external_id>",
+    format = "application/json",
+    data = "<e>"
+)]
+pub fn put_event_by_external_id(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    notify: Notify,
+    token: ScopedBearer<scopes::WriteEvents>,
+    external_id: String,
+    e: Json<usecases::NewEvent>,
+) -> Result<String> {
+    let mut e = e.into_inner();
+    check_and_set_address_location(&mut e);
+    let upsert = flows::upsert_event_by_external_id(
+        &connections,
+        &mut search_engine,
+        &*notify,
+        &token.token,
+        &external_id,
+        e,
+    )?;
+    Ok(Json(upsert.event().id.to_string()))
+}
+
 impl<'q> FromQuery<'q> for usecases::EventQuery {
     type Error = crate::core::prelude::Error;
 
@@ -241,16 +285,15 @@ fn validate_and_adjust_query_limit(limit: usize) -> CoreResult<usize> {
 pub fn get_events_with_token(
     connections: sqlite::Connections,
     search_engine: tantivy::SearchEngine,
-    token: Bearer,
+    token: ScopedBearer<scopes::Read>,
     query: usecases::EventQuery,
 ) -> Result<Vec<json::Event>> {
     let db = connections.shared()?;
-    let org = usecases::authorize_organization_by_token(&*db, &token.0)?;
     let events = usecases::query_events(&*db, &search_engine, query)?;
     // Release the database connection asap
     drop(db);
 
-    let owned_tags = org.owned_tags;
+    let owned_tags = token.org.owned_tags;
     let events: Vec<_> = events
         .into_iter()
         .map(|e| usecases::filter_event(e, owned_tags.iter().map(String::as_str)))
@@ -285,40 +328,111 @@ pub fn get_events_chronologically(
     Ok(Json(events))
 }
 
-#[get("/export/events.csv?<query..>")]
-pub fn csv_export_with_token(
+const DEFAULT_NEARBY_RADIUS_KM: f64 = 10.0;
+const MAX_NEARBY_RADIUS_KM: f64 = 500.0;
+
+#[allow(clippy::too_many_arguments)]
+#[get("/events/nearby?<lat>&<lng>&<radius_km>&<from>&<to>&<limit>")]
+pub fn get_events_nearby(
+    connections: sqlite::Connections,
+    search_engine: tantivy::SearchEngine,
+    lat: Option<f64>,
+    lng: Option<f64>,
+    radius_km: Option<f64>,
+    from: Option<i64>,
+    to: Option<i64>,
+    limit: Option<usize>,
+) -> Result<Vec<json::Event>> {
+    let center = match (lat, lng) {
+        (Some(lat), Some(lng)) => MapPoint::try_from_lat_lng_deg(lat, lng),
+        _ => None,
+    }
+    .ok_or(Error::Parameter(ParameterError::InvalidPosition))?;
+    let radius_km = radius_km.unwrap_or(DEFAULT_NEARBY_RADIUS_KM);
+    if !(radius_km > 0.0 && radius_km <= MAX_NEARBY_RADIUS_KM) {
+        return Err(Error::Parameter(ParameterError::InvalidRadius).into());
+    }
+    let query = usecases::NearbyEventQuery {
+        center,
+        radius: Distance::from_meters(radius_km * 1000.0),
+        from: from.map(Timestamp::from_inner),
+        to: to.map(Timestamp::from_inner),
+        limit: limit.map(validate_and_adjust_query_limit).transpose()?,
+    };
+
+    let db = connections.shared()?;
+    let events = usecases::query_events_nearby(&*db, &search_engine, query)?;
+    // Release the database connection asap
+    drop(db);
+
+    let owned_tags = vec![];
+    let events: Vec<_> = events
+        .into_iter()
+        .map(|e| usecases::filter_event(e, owned_tags.iter().map(String::as_str)))
+        .map(json::Event::from)
+        .collect();
+
+    Ok(Json(events))
+}
+
+pub struct EventsExportFile(&'static dyn ExportFormat);
+
+impl<'a> FromParam<'a> for EventsExportFile {
+    type Error = &'a RawStr;
+
+    fn from_param(param: &'a RawStr) -> result::Result<Self, Self::Error> {
+        parse_export_file(param, "events").map(Self).ok_or(param)
+    }
+}
+
+// Ranked after the export of entries
+#[get("/export/<file>?<query..>", rank = 3)]
+pub fn export_with_token(
     connections: sqlite::Connections,
     search_engine: tantivy::SearchEngine,
-    token: Bearer,
+    file: EventsExportFile,
+    token: ScopedBearer<scopes::Read>,
     login: Login,
+    cfg: State<Cfg>,
     query: usecases::EventQuery,
 ) -> result::Result<Content<String>, AppError> {
-    let organization =
-        usecases::authorize_organization_by_token(&*connections.shared()?, &token.0)?;
-    csv_export(connections, search_engine, Some(organization), login, query)
+    events_export(
+        connections,
+        search_engine,
+        file.0,
+        Some(token.org),
+        login,
+        &cfg,
+        query,
+    )
 }
 
-#[get("/export/events.csv?<query..>", rank = 2)]
-pub fn csv_export_without_token(
+#[get("/export/<file>?<query..>", rank = 4)]
+pub fn export_without_token(
     connections: sqlite::Connections,
     search_engine: tantivy::SearchEngine,
+    file: EventsExportFile,
     login: Login,
+    cfg: State<Cfg>,
     query: usecases::EventQuery,
 ) -> result::Result<Content<String>, AppError> {
-    csv_export(connections, search_engine, None, login, query)
+    events_export(connections, search_engine, file.0, None, login, &cfg, query)
 }
 
-fn csv_export(
+fn events_export(
     connections: sqlite::Connections,
     search_engine: tantivy::SearchEngine,
+    format: &dyn ExportFormat,
     org: Option<Organization>,
     login: Login,
+    cfg: &Cfg,
     query: usecases::EventQuery,
 ) -> result::Result<Content<String>, AppError> {
     let owned_tags = org.map(|org| org.owned_tags).unwrap_or_default();
 
     let db = connections.shared()?;
-    let user = usecases::authorize_user_by_email(&*db, &login.0, Role::Scout)?;
+    let user = usecases::authorize_user_by_email(&*db, &login.0, Permission::ExportData)?;
+    let permissions = usecases::user_permissions(&*db, &user)?;
     let limit = if let Some(limit) = query.limit {
         // Limited
         limit
@@ -336,28 +450,21 @@ fn csv_export(
 
     let events = events
         .into_iter()
-        .map(|e| usecases::export_event(e, user.role, owned_tags.iter().map(String::as_str)));
-
-    let records: Vec<_> = events.map(adapters::csv::EventRecord::from).collect();
-
-    let buff: Vec<u8> = vec![];
-    let mut wtr = csv::Writer::from_writer(buff);
-
-    for r in records {
-        wtr.serialize(r)?;
-    }
-    wtr.flush()?;
-    let data = String::from_utf8(wtr.into_inner()?)?;
+        .map(|e| usecases::export_event(e, &permissions, owned_tags.iter().map(String::as_str)))
+        .collect();
 
-    Ok(Content(ContentType::CSV, data))
+    let data = format.export_events(events, cfg.dataset_license.as_deref())?;
+    export_content(format, data)
 }
 
-#[post("/events/<ids>/archive")]
+#[post("/events/<ids>/archive", data = "<archive>")]
 pub fn post_events_archive(
     login: Login,
     db: sqlite::Connections,
     mut search_engine: tantivy::SearchEngine,
+    notify: Notify,
     ids: String,
+    archive: Option<Json<json::Archive>>,
 ) -> StatusResult {
     let ids = util::split_ids(&ids);
     if ids.is_empty() {
@@ -366,9 +473,18 @@ pub fn post_events_archive(
     let archived_by_email = {
         let db = db.shared()?;
         // Only scouts and admins are entitled to review events
-        usecases::authorize_user_by_email(&*db, &login.0, Role::Scout)?.email
+        usecases::authorize_user_by_email(&*db, &login.0, Permission::ArchiveEvents)?.email
     };
-    let update_count = flows::archive_events(&db, &mut search_engine, &ids, &archived_by_email)?;
+    // The request body with the reason for archiving is optional
+    let json::Archive { comment } = archive.map(Json::into_inner).unwrap_or_default();
+    let update_count = flows::archive_events(
+        &db,
+        &mut search_engine,
+        &*notify,
+        &ids,
+        &archived_by_email,
+        comment.as_deref(),
+    )?;
     if update_count < ids.len() {
         log::info!(
             "Archived only {} of {} event(s): {:?}",
@@ -380,6 +496,28 @@ pub fn post_events_archive(
     Ok(HttpStatus::NoContent)
 }
 
+#[post("/events/<id>/register")]
+pub fn post_event_registration(
+    login: Login,
+    connections: sqlite::Connections,
+    notify: Notify,
+    id: String,
+) -> Result<json::EventRegistration> {
+    let registration = flows::register_for_event(&connections, &*notify, &id.into(), &login.0)?;
+    Ok(Json(registration.into()))
+}
+
+#[get("/events/<id>/registrations")]
+pub fn get_event_registrations(
+    db: sqlite::Connections,
+    token: ScopedBearer<scopes::WriteEvents>,
+    id: String,
+) -> Result<Vec<json::EventRegistration>> {
+    let registrations =
+        usecases::load_event_registrations(&*db.shared()?, &token.token, &id.into())?;
+    Ok(Json(registrations.into_iter().map(Into::into).collect()))
+}
+
 #[delete("/events/<_id>", rank = 2)]
 pub fn delete_event(mut _db: sqlite::Connections, _id: &RawStr) -> HttpStatus {
     HttpStatus::Unauthorized
@@ -388,10 +526,10 @@ pub fn delete_event(mut _db: sqlite::Connections, _id: &RawStr) -> HttpStatus {
 #[delete("/events/<id>")]
 pub fn delete_event_with_token(
     db: sqlite::Connections,
-    token: Bearer,
+    token: ScopedBearer<scopes::WriteEvents>,
     id: &RawStr,
 ) -> StatusResult {
-    usecases::delete_event(&mut *db.exclusive()?, &token.0, &id.to_string())?;
+    flows::delete_event(&db, &token.token, &id.as_str().into())?;
     // TODO: Replace with HttpStatus::NoContent
     Ok(HttpStatus::Ok)
 }
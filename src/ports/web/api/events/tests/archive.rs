@@ -10,18 +10,21 @@ fn only_scouts_and_admins_can_archive_events() {
             email_confirmed: true,
             password: "secret".parse::<Password>().unwrap(),
             role: Role::Admin,
+            language: None,
         },
         User {
             email: "scout@example.com".into(),
             email_confirmed: true,
             password: "secret".parse::<Password>().unwrap(),
             role: Role::Scout,
+            language: None,
         },
         User {
             email: "user@example.com".into(),
             email_confirmed: true,
             password: "secret".parse::<Password>().unwrap(),
             role: Role::User,
+            language: None,
         },
     ];
     for u in users {
@@ -68,19 +71,20 @@ fn archive_events() {
         email_confirmed: true,
         password: "secret".parse::<Password>().unwrap(),
         role: Role::Admin,
+        language: None,
     };
     db.exclusive().unwrap().create_user(&admin).unwrap();
 
     // Create 2 events
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "bar".into(),
             owned_tags: vec!["tag".into()],
-            api_token: "foo".into(),
-        })
-        .unwrap();
+        },
+        "foo",
+    );
     let e1 = usecases::NewEvent {
         title: "x".into(),
         start: Utc::now().naive_utc().timestamp(),
@@ -135,3 +139,44 @@ fn archive_events() {
     assert!(!body_str.contains(&format!("\"id\":\"{}\"", id1)));
     assert!(!body_str.contains(&format!("\"id\":\"{}\"", id2)));
 }
+
+#[test]
+fn archive_events_with_comment() {
+    let (client, db, mut search_engine, notify) = setup2();
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "scout@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Scout,
+            language: None,
+        })
+        .unwrap();
+    let e = usecases::NewEvent {
+        title: "x".into(),
+        start: Utc::now().naive_utc().timestamp(),
+        created_by: Some("author@example.com".into()),
+        ..Default::default()
+    };
+    let id = flows::create_event(&db, &mut search_engine, &notify, None, e)
+        .unwrap()
+        .id;
+    let login = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "scout@example.com", "password": "secret"}"#)
+        .dispatch();
+    assert_eq!(login.status(), Status::Ok);
+
+    let response = client
+        .post(format!("/events/{}/archive", id))
+        .header(ContentType::JSON)
+        .body(r#"{"comment": "Duplicate of another event"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+    assert!(matches!(
+        db.shared().unwrap().get_event(&id.as_str().into()),
+        Err(RepoError::NotFound)
+    ));
+}
@@ -67,15 +67,15 @@ mod with_api_token {
     #[test]
     fn for_organization_without_any_owned_tags() {
         let (client, db) = setup();
-        db.exclusive()
-            .unwrap()
-            .create_org(Organization {
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
                 id: "foo".into(),
                 name: "bar".into(),
                 owned_tags: vec![],
-                api_token: "foo".into(),
-            })
-            .unwrap();
+            },
+            "foo",
+        );
         let mut res = client
             .post("/events")
             .header(ContentType::JSON)
@@ -94,15 +94,15 @@ mod with_api_token {
     #[test]
     fn with_creator_email() {
         let (client, db) = setup();
-        db.exclusive()
-            .unwrap()
-            .create_org(Organization {
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
                 id: "foo".into(),
                 name: "bar".into(),
                 owned_tags: vec!["org-tag".into()],
-                api_token: "foo".into(),
-            })
-            .unwrap();
+            },
+            "foo",
+        );
         let mut res = client
             .post("/events")
             .header(ContentType::JSON)
@@ -121,15 +121,15 @@ mod with_api_token {
     #[test]
     fn with_a_very_long_email() {
         let (client, db) = setup();
-        db.exclusive()
-            .unwrap()
-            .create_org(Organization {
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
                 id: "foo".into(),
                 name: "bar".into(),
                 owned_tags: vec!["org-tag".into()],
-                api_token: "foo".into(),
-            })
-            .unwrap();
+            },
+            "foo",
+        );
         let res = client
                     .post("/events")
                     .header(ContentType::JSON)
@@ -147,15 +147,15 @@ mod with_api_token {
     #[test]
     fn with_empty_strings_for_optional_fields() {
         let (client, db) = setup();
-        db.exclusive()
-            .unwrap()
-            .create_org(Organization {
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
                 id: "foo".into(),
                 name: "bar".into(),
                 owned_tags: vec!["org-tag".into()],
-                api_token: "foo".into(),
-            })
-            .unwrap();
+            },
+            "foo",
+        );
         let res = client
                     .post("/events")
                     .header(ContentType::JSON)
@@ -173,15 +173,15 @@ mod with_api_token {
     #[test]
     fn with_registration_type() {
         let (client, db) = setup();
-        db.exclusive()
-            .unwrap()
-            .create_org(Organization {
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
                 id: "foo".into(),
                 name: "bar".into(),
                 owned_tags: vec!["org-tag".into()],
-                api_token: "foo".into(),
-            })
-            .unwrap();
+            },
+            "foo",
+        );
         let res = client
                     .post("/events")
                     .header(ContentType::JSON)
@@ -197,24 +197,24 @@ mod with_api_token {
     #[test]
     fn with_reseved_tag_from_foreign_org() {
         let (client, db) = setup();
-        db.exclusive()
-            .unwrap()
-            .create_org(Organization {
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
                 id: "a".into(),
                 name: "a".into(),
                 owned_tags: vec!["a".into()],
-                api_token: "a".into(),
-            })
-            .unwrap();
-        db.exclusive()
-            .unwrap()
-            .create_org(Organization {
+            },
+            "a",
+        );
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
                 id: "b".into(),
                 name: "b".into(),
                 owned_tags: vec!["b".into()],
-                api_token: "b".into(),
-            })
-            .unwrap();
+            },
+            "b",
+        );
         let res = client
             .post("/events")
             .header(ContentType::JSON)
@@ -241,15 +241,15 @@ mod with_api_token {
     #[test]
     fn with_spaces_in_tags() {
         let (client, db) = setup();
-        db.exclusive()
-            .unwrap()
-            .create_org(Organization {
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
                 id: "foo".into(),
                 name: "bar".into(),
                 owned_tags: vec!["org-tag".into()],
-                api_token: "foo".into(),
-            })
-            .unwrap();
+            },
+            "foo",
+        );
         let res = client
                     .post("/events")
                     .header(ContentType::JSON)
@@ -274,15 +274,15 @@ mod with_api_token {
     #[test]
     fn with_invalid_registration_type() {
         let (client, db) = setup();
-        db.exclusive()
-            .unwrap()
-            .create_org(Organization {
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
                 id: "foo".into(),
                 name: "bar".into(),
                 owned_tags: vec!["org-tag".into()],
-                api_token: "foo".into(),
-            })
-            .unwrap();
+            },
+            "foo",
+        );
         let res = client
                     .post("/events")
                     .header(ContentType::JSON)
@@ -295,15 +295,15 @@ mod with_api_token {
     #[test]
     fn without_creator_email() {
         let (client, db) = setup();
-        db.exclusive()
-            .unwrap()
-            .create_org(Organization {
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
                 id: "foo".into(),
                 name: "bar".into(),
                 owned_tags: vec!["org-tag".into()],
-                api_token: "foo".into(),
-            })
-            .unwrap();
+            },
+            "foo",
+        );
         let res = client
             .post("/events")
             .header(ContentType::JSON)
@@ -316,15 +316,15 @@ mod with_api_token {
     #[test]
     fn with_empty_title() {
         let (client, db) = setup();
-        db.exclusive()
-            .unwrap()
-            .create_org(Organization {
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
                 id: "foo".into(),
                 name: "bar".into(),
                 owned_tags: vec!["org-tag".into()],
-                api_token: "foo".into(),
-            })
-            .unwrap();
+            },
+            "foo",
+        );
         let res = client
             .post("/events")
             .header(ContentType::JSON)
@@ -337,15 +337,15 @@ mod with_api_token {
     #[test]
     fn with_phone_registration_but_without_phone_nr() {
         let (client, db) = setup();
-        db.exclusive()
-            .unwrap()
-            .create_org(Organization {
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
                 id: "foo".into(),
                 name: "bar".into(),
                 owned_tags: vec!["org-tag".into()],
-                api_token: "foo".into(),
-            })
-            .unwrap();
+            },
+            "foo",
+        );
         let res = client
                     .post("/events")
                     .header(ContentType::JSON)
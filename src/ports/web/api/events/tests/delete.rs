@@ -13,15 +13,15 @@ fn without_api_token() {
 #[test]
 fn with_invalid_api_token() {
     let (client, db) = setup();
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "bar".into(),
             owned_tags: vec!["org-tag".into()],
-            api_token: "foo".into(),
-        })
-        .unwrap();
+        },
+        "foo",
+    );
     let res = client
         .delete("/events/foo")
         .header(ContentType::JSON)
@@ -33,15 +33,15 @@ fn with_invalid_api_token() {
 #[test]
 fn with_api_token() {
     let (client, db, mut search_engine, notify) = setup2();
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "bar".into(),
             owned_tags: vec!["tag".into()],
-            api_token: "foo".into(),
-        })
-        .unwrap();
+        },
+        "foo",
+    );
     let e1 = usecases::NewEvent {
         title: "x".into(),
         start: Utc::now().naive_utc().timestamp(),
@@ -63,7 +63,11 @@ fn with_api_token() {
         .unwrap()
         .id;
     // Manually delete the implicitly added org tag from the 2nd event!
-    let mut e2 = db.shared().unwrap().get_event(id2.as_ref()).unwrap();
+    let mut e2 = db
+        .shared()
+        .unwrap()
+        .get_event(&id2.as_str().into())
+        .unwrap();
     e2.tags.retain(|t| t != "tag");
     db.exclusive().unwrap().update_event(&e2).unwrap();
     assert_eq!(db.shared().unwrap().count_events().unwrap(), 2);
@@ -89,15 +93,15 @@ fn with_api_token() {
 #[ignore]
 fn with_api_token_by_organization_without_any_owned_tags() {
     let (client, db, mut search_engine, notify) = setup2();
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "bar".into(),
             owned_tags: vec![],
-            api_token: "foo".into(),
-        })
-        .unwrap();
+        },
+        "foo",
+    );
     let e = usecases::NewEvent {
         title: "x".into(),
         start: Utc::now().naive_utc().timestamp(),
@@ -11,42 +11,45 @@ fn export_csv() {
             email_confirmed: true,
             password: "secret".parse::<Password>().unwrap(),
             role: Role::Admin,
+            language: None,
         },
         User {
             email: "scout@example.com".into(),
             email_confirmed: true,
             password: "secret".parse::<Password>().unwrap(),
             role: Role::Scout,
+            language: None,
         },
         User {
             email: "user@example.com".into(),
             email_confirmed: true,
             password: "secret".parse::<Password>().unwrap(),
             role: Role::User,
+            language: None,
         },
     ];
     for u in users {
         db.exclusive().unwrap().create_user(&u).unwrap();
     }
 
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "foo_name".into(),
             owned_tags: vec!["tag".into()],
-            api_token: "foo".into(),
-        })
-        .unwrap();
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+        },
+        "foo",
+    );
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "bar".into(),
             name: "bar_name".into(),
             owned_tags: vec!["tag2".into()],
-            api_token: "bar".into(),
-        })
-        .unwrap();
+        },
+        "bar",
+    );
     let start1 = Utc::now().naive_utc().timestamp();
     let e1 = usecases::NewEvent {
         title: "title1".into(),
@@ -100,13 +103,13 @@ fn export_csv() {
     assert_eq!(response.status(), Status::Ok);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
     eprintln!("{}", body_str);
-    assert!(body_str.starts_with("id,created_by,organizer,title,description,start,end,lat,lng,street,zip,city,country,state,email,phone,homepage,image_url,image_link_url,tags\n"));
+    assert!(body_str.starts_with("id,created_by,organizer,title,description,start,end,lat,lng,street,zip,city,country,state,email,phone,homepage,image_url,image_link_url,tags,dataset_license\n"));
     assert!(body_str.contains(&format!(
-        "{},,,title1,,{},,,,,,,,state,email1@example.com,phone1,,,,\"bla,tag\"\n",
+        "{},,,title1,,{},,,,,,,,state,email1@example.com,phone1,,,,\"bla,tag\",\n",
         id1, start1
     )));
     assert!(body_str.contains(&format!(
-        "{},,,title2,,{},,,,,,,,,email2@example.com,phone2,,,,\"bli,tag2\"\n",
+        "{},,,title2,,{},,,,,,,,,email2@example.com,phone2,,,,\"bli,tag2\",\n",
         id2, start2
     )));
     assert!(!body_str.contains("createdby1@example.com"));
@@ -125,10 +128,10 @@ fn export_csv() {
         .dispatch();
     assert_eq!(response.status(), Status::Ok);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(body_str.starts_with("id,created_by,organizer,title,description,start,end,lat,lng,street,zip,city,country,state,email,phone,homepage,image_url,image_link_url,tags\n"));
-    assert!(body_str.contains(&format!("{},createdby1@example.com,,title1,,{},,,,,,,,state,email1@example.com,phone1,,,,\"bla,tag\"\n", id1, start1)));
+    assert!(body_str.starts_with("id,created_by,organizer,title,description,start,end,lat,lng,street,zip,city,country,state,email,phone,homepage,image_url,image_link_url,tags,dataset_license\n"));
+    assert!(body_str.contains(&format!("{},createdby1@example.com,,title1,,{},,,,,,,,state,email1@example.com,phone1,,,,\"bla,tag\",\n", id1, start1)));
     assert!(body_str.contains(&format!(
-        "{},,,title2,,{},,,,,,,,,email2@example.com,phone2,,,,\"bli,tag2\"\n",
+        "{},,,title2,,{},,,,,,,,,email2@example.com,phone2,,,,\"bli,tag2\",\n",
         id2, start2
     )));
     assert!(!body_str.contains("createdby2@example.com"));
@@ -143,10 +146,10 @@ fn export_csv() {
     let mut response = client.get("/export/events.csv").dispatch();
     assert_eq!(response.status(), Status::Ok);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(body_str.starts_with("id,created_by,organizer,title,description,start,end,lat,lng,street,zip,city,country,state,email,phone,homepage,image_url,image_link_url,tags\n"));
-    assert!(body_str.contains(&format!("{},createdby1@example.com,,title1,,{},,,,,,,,state,email1@example.com,phone1,,,,\"bla,tag\"\n", id1, start1)));
+    assert!(body_str.starts_with("id,created_by,organizer,title,description,start,end,lat,lng,street,zip,city,country,state,email,phone,homepage,image_url,image_link_url,tags,dataset_license\n"));
+    assert!(body_str.contains(&format!("{},createdby1@example.com,,title1,,{},,,,,,,,state,email1@example.com,phone1,,,,\"bla,tag\",\n", id1, start1)));
     assert!(body_str.contains(&format!(
-        "{},createdby2@example.com,,title2,,{},,,,,,,,,email2@example.com,phone2,,,,\"bli,tag2\"\n",
+        "{},createdby2@example.com,,title2,,{},,,,,,,,,email2@example.com,phone2,,,,\"bli,tag2\",\n",
         id2, start2
     )));
 }
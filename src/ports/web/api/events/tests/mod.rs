@@ -8,4 +8,6 @@ mod create;
 mod delete;
 mod export_csv;
 mod read;
+mod register;
 mod update;
+mod upsert;
@@ -50,6 +50,8 @@ fn all() {
                 archived: None,
                 image_url: None,
                 image_link_url: None,
+                recurrence: None,
+                capacity: None,
             })
             .unwrap();
     }
@@ -141,6 +143,49 @@ fn filtered_by_tags() {
     assert!(body_str.contains("\"tags\":[\"a\",\"b\"]"));
 }
 
+#[test]
+fn filtered_by_text() {
+    let (client, db, mut search_engine, notify) = setup2();
+    let now = Utc::now().naive_utc().timestamp();
+    let events = vec![
+        usecases::NewEvent {
+            title: "Harvest festival".into(),
+            description: Some("Celebrating organic farming".into()),
+            start: now,
+            created_by: Some("test@example.com".into()),
+            ..Default::default()
+        },
+        usecases::NewEvent {
+            title: "Repair cafe".into(),
+            start: now,
+            lat: Some(48.1),
+            lng: Some(11.5),
+            city: Some("Munich".into()),
+            state: Some("Bavaria".into()),
+            created_by: Some("test@example.com".into()),
+            ..Default::default()
+        },
+    ];
+    for e in events {
+        flows::create_event(&db, &mut search_engine, &notify, None, e).unwrap();
+    }
+
+    let req = client.get("/events?text=farming").header(ContentType::JSON);
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), HttpStatus::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains("Harvest festival"));
+    assert!(!body_str.contains("Repair cafe"));
+
+    let req = client.get("/events?text=bavaria").header(ContentType::JSON);
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), HttpStatus::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(!body_str.contains("Harvest festival"));
+    assert!(body_str.contains("Repair cafe"));
+}
+
 #[test]
 fn filtered_by_creator_without_api_token() {
     let (client, _db) = setup();
@@ -154,15 +199,15 @@ fn filtered_by_creator_without_api_token() {
 #[test]
 fn filtered_by_creator_with_valid_api_token() {
     let (client, db, mut search_engine, notify) = setup2();
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "bar".into(),
             owned_tags: vec!["org-tag".into()],
-            api_token: "foo".into(),
-        })
-        .unwrap();
+        },
+        "foo",
+    );
     let ids: Vec<_> = ["foo@bar.com", "test@test.com", "bla@bla.bla"]
         .iter()
         .map(|m| {
@@ -193,15 +238,15 @@ fn filtered_by_creator_with_valid_api_token() {
 #[test]
 fn filtered_by_creator_with_invalid_api_token() {
     let (client, db) = setup();
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "bar".into(),
             owned_tags: vec!["org-tag".into()],
-            api_token: "foo".into(),
-        })
-        .unwrap();
+        },
+        "foo",
+    );
 
     let res = client
         .get("/events?created_by=foo@bar.com")
@@ -269,6 +314,54 @@ fn filtered_by_start_max() {
     assert!(objects[3].contains(&format!("\"start\":{}", now + 200)));
 }
 
+#[test]
+fn occurrences_of_recurring_event_within_time_window() {
+    let (client, db, mut search_engine, notify) = setup2();
+    let now = Utc::now().naive_utc().timestamp();
+    let week = 7 * 24 * 60 * 60;
+    let e = usecases::NewEvent {
+        title: "weekly".into(),
+        start: now,
+        end: Some(now + 3600),
+        created_by: Some("test@example.com".into()),
+        recurrence: Some(usecases::NewEventRecurrence {
+            frequency: "weekly".into(),
+            interval: None,
+            until: Some(now + 4 * week),
+        }),
+        ..Default::default()
+    };
+    let e = flows::create_event(&db, &mut search_engine, &notify, None, e).unwrap();
+    let mut res = client
+        .get(format!(
+            "/events?start_min={}&start_max={}",
+            now + week - 10,
+            now + 3 * week + 10
+        ))
+        .header(ContentType::JSON)
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Ok);
+    test_json(&res);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    let objects: Vec<_> = body_str.split("},{").collect();
+    assert_eq!(objects.len(), 3);
+    for (i, object) in objects.iter().enumerate() {
+        let start = now + (i as i64 + 1) * week;
+        assert!(object.contains(&format!("\"id\":\"{}\"", e.id)));
+        assert!(object.contains(&format!("\"start\":{},\"end\":{}", start, start + 3600)));
+    }
+    assert!(body_str.contains(&format!(
+        "\"recurrence\":{{\"frequency\":\"weekly\",\"interval\":1,\"until\":{}}}",
+        now + 4 * week
+    )));
+
+    // The event itself is only returned once
+    let mut res = client.get("/events").header(ContentType::JSON).dispatch();
+    assert_eq!(res.status(), HttpStatus::Ok);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    assert_eq!(body_str.matches(&format!("\"id\":\"{}\"", e.id)).count(), 1);
+}
+
 #[test]
 fn filtered_by_bounding_box() {
     let (client, db, mut search_engine, notify) = setup2();
@@ -308,3 +401,118 @@ fn filtered_by_bounding_box() {
     assert!(!body_str.contains("\"title\":\"0.3-5\""));
     assert!(body_str.contains("\"title\":\"12-0\""));
 }
+
+#[test]
+fn nearby_within_time_window() {
+    let (client, db, mut search_engine, notify) = setup2();
+    let now = Utc::now().naive_utc().timestamp();
+    let events = &[
+        ("past", 48.78, 9.18, now - 7200, Some(now - 3600)),
+        ("now", 48.78, 9.18, now - 600, Some(now + 3600)),
+        ("later", 48.78, 9.19, now + 86400, None),
+        ("corner", 48.86, 9.30, now, None),
+        ("far", 52.52, 13.40, now, None),
+    ];
+    for &(title, lat, lng, start, end) in events {
+        let e = usecases::NewEvent {
+            title: title.into(),
+            start,
+            end,
+            lat: Some(lat),
+            lng: Some(lng),
+            created_by: Some("test@example.com".into()),
+            ..Default::default()
+        };
+        flows::create_event(&db, &mut search_engine, &notify, None, e).unwrap();
+    }
+
+    let mut res = client
+        .get("/events/nearby?lat=48.7755&lng=9.1827&radius_km=10")
+        .header(ContentType::JSON)
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Ok);
+    test_json(&res);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    let objects: Vec<_> = body_str.split("},{").collect();
+    assert_eq!(objects.len(), 3);
+    assert!(objects[0].contains("\"title\":\"past\""));
+    assert!(objects[1].contains("\"title\":\"now\""));
+    assert!(objects[2].contains("\"title\":\"later\""));
+
+    let mut res = client
+        .get(format!(
+            "/events/nearby?lat=48.7755&lng=9.1827&radius_km=10&from={}&to={}",
+            now,
+            now + 7200
+        ))
+        .header(ContentType::JSON)
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Ok);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains("\"title\":\"now\""));
+    assert!(!body_str.contains("\"title\":\"past\""));
+    assert!(!body_str.contains("\"title\":\"later\""));
+    assert!(!body_str.contains("\"title\":\"corner\""));
+    assert!(!body_str.contains("\"title\":\"far\""));
+}
+
+#[test]
+fn nearby_filters_events_before_limiting_the_results() {
+    let (client, db, mut search_engine, notify) = setup2();
+    let now = Utc::now().naive_utc().timestamp();
+    for i in 0..10 {
+        let e = usecases::NewEvent {
+            title: format!("past{}", i),
+            start: now - 7200 - i,
+            end: Some(now - 3600),
+            lat: Some(48.78),
+            lng: Some(9.18),
+            created_by: Some("test@example.com".into()),
+            ..Default::default()
+        };
+        flows::create_event(&db, &mut search_engine, &notify, None, e).unwrap();
+    }
+    let e = usecases::NewEvent {
+        title: "now".into(),
+        start: now - 600,
+        end: Some(now + 3600),
+        lat: Some(48.78),
+        lng: Some(9.18),
+        created_by: Some("test@example.com".into()),
+        ..Default::default()
+    };
+    flows::create_event(&db, &mut search_engine, &notify, None, e).unwrap();
+
+    let mut res = client
+        .get(format!(
+            "/events/nearby?lat=48.7755&lng=9.1827&radius_km=10&from={}&limit=1",
+            now
+        ))
+        .header(ContentType::JSON)
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Ok);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    let objects: Vec<_> = body_str.split("},{").collect();
+    assert_eq!(objects.len(), 1);
+    assert!(objects[0].contains("\"title\":\"now\""));
+}
+
+#[test]
+fn nearby_with_invalid_parameters() {
+    let (client, _, _, _) = setup2();
+    let res = client
+        .get("/events/nearby?lat=48.7755")
+        .header(ContentType::JSON)
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::BadRequest);
+    let res = client
+        .get("/events/nearby?lat=48.7755&lng=9.1827&radius_km=0")
+        .header(ContentType::JSON)
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::BadRequest);
+    let res = client
+        .get("/events/nearby?lat=48.7755&lng=9.1827&from=200&to=100")
+        .header(ContentType::JSON)
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::BadRequest);
+}
@@ -0,0 +1,125 @@
+use super::*;
+
+fn create_user(db: &sqlite::Connections, email: &str) {
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: email.into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::User,
+            language: None,
+        })
+        .unwrap();
+}
+
+fn login(client: &Client, email: &str) {
+    let res = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(format!(r#"{{"email": "{}", "password": "secret"}}"#, email))
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Ok);
+}
+
+fn register(client: &Client, id: &str) -> (HttpStatus, Option<json::EventRegistration>) {
+    let mut res = client.post(format!("/events/{}/register", id)).dispatch();
+    let registration = if res.status() == HttpStatus::Ok {
+        Some(serde_json::from_str(&res.body_string().unwrap()).unwrap())
+    } else {
+        None
+    };
+    (res.status(), registration)
+}
+
+#[test]
+fn register_with_capacity_limit_and_waitlist() {
+    let (client, db) = setup();
+    for (id, token) in &[("foo", "foo-token"), ("bar", "bar-token")] {
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
+                id: (*id).into(),
+                name: (*id).into(),
+                owned_tags: vec![format!("{}-tag", id)],
+            },
+            token,
+        );
+    }
+    create_user(&db, "first@example.com");
+    create_user(&db, "second@example.com");
+
+    let mut res = client
+        .post("/events")
+        .header(ContentType::JSON)
+        .header(Header::new("Authorization", "Bearer foo-token"))
+        .body(
+            r#"{"title":"x","start":4132508400,"created_by":"foo@bar.com","email":"foo@bar.com","registration":"email","capacity":1}"#,
+        )
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Ok);
+    let id: String = serde_json::from_str(&res.body_string().unwrap()).unwrap();
+
+    // Only registered users can register for events
+    let (status, _) = register(&client, &id);
+    assert_eq!(status, HttpStatus::Unauthorized);
+
+    login(&client, "first@example.com");
+    let (status, registration) = register(&client, &id);
+    assert_eq!(status, HttpStatus::Ok);
+    let registration = registration.unwrap();
+    assert_eq!("first@example.com", registration.email);
+    assert_eq!(
+        json::EventRegistrationStatus::Confirmed,
+        registration.status
+    );
+    let (status, _) = register(&client, &id);
+    assert_eq!(status.code, 409);
+
+    login(&client, "second@example.com");
+    let (_, registration) = register(&client, &id);
+    assert_eq!(
+        json::EventRegistrationStatus::Waitlisted,
+        registration.unwrap().status
+    );
+
+    // Only the organization that owns the event can see the registrations
+    let res = client
+        .get(format!("/events/{}/registrations", id))
+        .header(Header::new("Authorization", "Bearer bar-token"))
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Forbidden);
+    let mut res = client
+        .get(format!("/events/{}/registrations", id))
+        .header(Header::new("Authorization", "Bearer foo-token"))
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Ok);
+    let registrations: Vec<json::EventRegistration> =
+        serde_json::from_str(&res.body_string().unwrap()).unwrap();
+    assert_eq!(2, registrations.len());
+
+    // Cancelling confirms the first registration on the waitlist
+    let token = db
+        .shared()
+        .unwrap()
+        .load_event_registrations(&id.as_str().into())
+        .unwrap()
+        .into_iter()
+        .find(|r| r.email_nonce.email == "first@example.com")
+        .unwrap()
+        .email_nonce
+        .encode_to_string();
+    let res = client
+        .post("/cancel-event-registration")
+        .header(ContentType::JSON)
+        .body(format!(r#"{{"token":"{}"}}"#, token))
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Ok);
+    let registrations = db
+        .shared()
+        .unwrap()
+        .load_event_registrations(&id.as_str().into())
+        .unwrap();
+    assert_eq!(EventRegistrationStatus::Cancelled, registrations[0].status);
+    assert_eq!(EventRegistrationStatus::Confirmed, registrations[1].status);
+}
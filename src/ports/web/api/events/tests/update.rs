@@ -14,15 +14,15 @@ fn without_api_token() {
 #[test]
 fn with_invalid_api_token() {
     let (client, db) = setup();
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "bar".into(),
             owned_tags: vec!["org-tag".into()],
-            api_token: "foo".into(),
-        })
-        .unwrap();
+        },
+        "foo",
+    );
     let res = client
         .put("/events/foo")
         .header(ContentType::JSON)
@@ -35,15 +35,15 @@ fn with_invalid_api_token() {
 #[test]
 fn with_api_token() {
     let (client, db, mut search_engine, notify) = setup2();
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "bar".into(),
             owned_tags: vec!["org-tag".into()],
-            api_token: "foo".into(),
-        })
-        .unwrap();
+        },
+        "foo",
+    );
     let e = usecases::NewEvent {
         title: "x".into(),
         tags: Some(vec!["bla".into(), "org-tag".into()]),
@@ -54,7 +54,7 @@ fn with_api_token() {
     let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e)
         .unwrap()
         .id;
-    assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
+    assert!(db.shared().unwrap().get_event(&id.as_str().into()).is_ok());
     let res = client
         .put(format!("/events/{}", id))
         .header(ContentType::JSON)
@@ -62,7 +62,7 @@ fn with_api_token() {
         .body(r#"{"title":"new","start":4132508400,"created_by":"changed@bar.com"}"#)
         .dispatch();
     assert_eq!(res.status(), HttpStatus::Ok);
-    let new = db.shared().unwrap().get_event(id.as_ref()).unwrap();
+    let new = db.shared().unwrap().get_event(&id.as_str().into()).unwrap();
     assert_eq!(new.title, "new");
     assert_eq!(new.start.timestamp(), 4_132_508_400);
     assert_eq!(new.created_by.unwrap(), "changed@bar.com");
@@ -71,15 +71,15 @@ fn with_api_token() {
 #[test]
 fn with_api_token_for_organization_without_any_owned_tags() {
     let (client, db, mut search_engine, notify) = setup2();
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "bar".into(),
             owned_tags: vec![],
-            api_token: "foo".into(),
-        })
-        .unwrap();
+        },
+        "foo",
+    );
     let e = usecases::NewEvent {
         title: "x".into(),
         tags: Some(vec!["bla".into()]),
@@ -90,7 +90,7 @@ fn with_api_token_for_organization_without_any_owned_tags() {
     let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e)
         .unwrap()
         .id;
-    assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
+    assert!(db.shared().unwrap().get_event(&id.as_str().into()).is_ok());
     let res = client
         .put(format!("/events/{}", id))
         .header(ContentType::JSON)
@@ -98,7 +98,7 @@ fn with_api_token_for_organization_without_any_owned_tags() {
         .body(r#"{"title":"new","start":4132508400,"created_by":"changed@bar.com"}"#)
         .dispatch();
     assert_eq!(res.status(), HttpStatus::Ok);
-    let new = db.shared().unwrap().get_event(id.as_ref()).unwrap();
+    let new = db.shared().unwrap().get_event(&id.as_str().into()).unwrap();
     assert_eq!(new.title, "new");
     assert_eq!(new.start.timestamp(), 4_132_508_400);
     assert_eq!(new.created_by.unwrap(), "changed@bar.com");
@@ -107,26 +107,26 @@ fn with_api_token_for_organization_without_any_owned_tags() {
 #[test]
 fn with_api_token_but_mismatching_tag() {
     let (client, db, mut search_engine, notify) = setup2();
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "bar".into(),
             owned_tags: vec!["org-tag".into()],
-            api_token: "foo".into(),
-        })
-        .unwrap();
+        },
+        "foo",
+    );
     // The events needs an owner, otherwise the test may fail
     // with a debug assertion.
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "bar".into(),
             name: "foo".into(),
             owned_tags: vec!["bla".into()],
-            api_token: "bar".into(),
-        })
-        .unwrap();
+        },
+        "bar",
+    );
     let e = usecases::NewEvent {
         title: "x".into(),
         tags: Some(vec!["bla".into()]),
@@ -137,7 +137,7 @@ fn with_api_token_but_mismatching_tag() {
     let id = flows::create_event(&db, &mut search_engine, &notify, Some("bar"), e)
         .unwrap()
         .id;
-    assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
+    assert!(db.shared().unwrap().get_event(&id.as_str().into()).is_ok());
     let res = client
         .put(format!("/events/{}", id))
         .header(ContentType::JSON)
@@ -148,17 +148,63 @@ fn with_api_token_but_mismatching_tag() {
 }
 
 #[test]
-fn with_api_token_keep_org_tag() {
+fn with_api_token_but_mismatching_tag_and_own_tag() {
     let (client, db, mut search_engine, notify) = setup2();
-    db.exclusive()
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
+            id: "foo".into(),
+            name: "bar".into(),
+            owned_tags: vec!["org-tag".into()],
+        },
+        "foo",
+    );
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
+            id: "bar".into(),
+            name: "foo".into(),
+            owned_tags: vec!["bla".into()],
+        },
+        "bar",
+    );
+    let e = usecases::NewEvent {
+        title: "x".into(),
+        tags: Some(vec!["bla".into()]),
+        created_by: Some("foo@bar.com".into()),
+        start: Utc::now().naive_utc().timestamp(),
+        ..Default::default()
+    };
+    let id = flows::create_event(&db, &mut search_engine, &notify, Some("bar"), e)
         .unwrap()
-        .create_org(Organization {
+        .id;
+    // Adding an owned tag must not allow to capture events owned by other orgs
+    let res = client
+        .put(format!("/events/{}", id))
+        .header(ContentType::JSON)
+        .header(Header::new("Authorization", "Bearer foo"))
+        .body(
+            r#"{"title":"new","start":4132508400,"created_by":"changed@bar.com","tags":["org-tag"]}"#,
+        )
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Forbidden);
+    let old = db.shared().unwrap().get_event(&id.as_str().into()).unwrap();
+    assert_eq!(old.title, "x");
+    assert_eq!(old.tags, vec!["bla"]);
+}
+
+#[test]
+fn with_api_token_keep_org_tag() {
+    let (client, db, mut search_engine, notify) = setup2();
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "bar".into(),
             owned_tags: vec!["org-tag".into()],
-            api_token: "foo".into(),
-        })
-        .unwrap();
+        },
+        "foo",
+    );
     let e = usecases::NewEvent {
         title: "x".into(),
         tags: Some(vec!["bla".into(), "org-tag".into()]),
@@ -169,7 +215,7 @@ fn with_api_token_keep_org_tag() {
     let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e)
         .unwrap()
         .id;
-    assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
+    assert!(db.shared().unwrap().get_event(&id.as_str().into()).is_ok());
     let res = client
         .put(format!("/events/{}", id))
         .header(ContentType::JSON)
@@ -179,22 +225,26 @@ fn with_api_token_keep_org_tag() {
         )
         .dispatch();
     assert_eq!(res.status(), HttpStatus::Ok);
-    let new = db.exclusive().unwrap().get_event(id.as_ref()).unwrap();
+    let new = db
+        .exclusive()
+        .unwrap()
+        .get_event(&id.as_str().into())
+        .unwrap();
     assert_eq!(new.tags, vec!["bla2", "org-tag"]);
 }
 
 #[test]
 fn with_api_token_and_removing_tag() {
     let (client, db, mut search_engine, notify) = setup2();
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "bar".into(),
             owned_tags: vec!["org-tag1".into(), "org-tag2".into()],
-            api_token: "foo".into(),
-        })
-        .unwrap();
+        },
+        "foo",
+    );
     let e = usecases::NewEvent {
         title: "x".into(),
         tags: Some(vec![
@@ -211,7 +261,7 @@ fn with_api_token_and_removing_tag() {
     let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e)
         .unwrap()
         .id;
-    assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
+    assert!(db.shared().unwrap().get_event(&id.as_str().into()).is_ok());
     let res = client
                 .put(format!("/events/{}", id))
                 .header(ContentType::JSON)
@@ -219,22 +269,26 @@ fn with_api_token_and_removing_tag() {
                 .body(r#"{"title":"new","start":4132508400,"created_by":"changed@bar.com","tags":["blub","new","org-tag2"]}"#)
                 .dispatch();
     assert_eq!(res.status(), HttpStatus::Ok);
-    let new = db.exclusive().unwrap().get_event(id.as_ref()).unwrap();
+    let new = db
+        .exclusive()
+        .unwrap()
+        .get_event(&id.as_str().into())
+        .unwrap();
     assert_eq!(new.tags, vec!["blub", "new", "org-tag2"]);
 }
 
 #[test]
 fn with_api_token_created_by() {
     let (client, db, mut search_engine, notify) = setup2();
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "bar".into(),
             owned_tags: vec!["bla".into()],
-            api_token: "foo".into(),
-        })
-        .unwrap();
+        },
+        "foo",
+    );
     let created_by = Some("foo@bar.com".into());
     let start = Utc::now().naive_utc().timestamp();
     let e = usecases::NewEvent {
@@ -247,7 +301,7 @@ fn with_api_token_created_by() {
     let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e)
         .unwrap()
         .id;
-    assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
+    assert!(db.shared().unwrap().get_event(&id.as_str().into()).is_ok());
 
     // Without created_by
     let res = client
@@ -257,7 +311,7 @@ fn with_api_token_created_by() {
         .body("{\"title\":\"Changed\",\"start\":4132508400}")
         .dispatch();
     assert_eq!(res.status(), HttpStatus::Ok);
-    let new = db.shared().unwrap().get_event(id.as_ref()).unwrap();
+    let new = db.shared().unwrap().get_event(&id.as_str().into()).unwrap();
     assert_eq!(new.title, "Changed");
     // created_by is unmodified
     assert_eq!(new.created_by, created_by);
@@ -273,7 +327,7 @@ fn with_api_token_created_by() {
         ))
         .dispatch();
     assert_eq!(res.status(), HttpStatus::Ok);
-    let new = db.shared().unwrap().get_event(id.as_ref()).unwrap();
+    let new = db.shared().unwrap().get_event(&id.as_str().into()).unwrap();
     assert_eq!(new.title, "Changed again");
     // created_by has been updated
     assert_eq!(new.created_by, Some("changed@bar.com".into()));
@@ -282,15 +336,15 @@ fn with_api_token_created_by() {
 #[test]
 fn update_geo_location() {
     let (client, db, mut search_engine, notify) = setup2();
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "foo".into(),
             name: "bar".into(),
             owned_tags: vec!["org-tag".into()],
-            api_token: "foo".into(),
-        })
-        .unwrap();
+        },
+        "foo",
+    );
     let e = usecases::NewEvent {
         title: "x".into(),
         tags: Some(vec!["bla".into(), "org-tag".into()]),
@@ -303,7 +357,7 @@ fn update_geo_location() {
     let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e)
         .unwrap()
         .id;
-    let created = db.shared().unwrap().get_event(id.as_ref()).unwrap();
+    let created = db.shared().unwrap().get_event(&id.as_str().into()).unwrap();
     assert_eq!(
         Some((
             LatCoord::from_deg(1.0).to_deg(),
@@ -318,7 +372,7 @@ fn update_geo_location() {
                 .body(r#"{"title":"new title","start":4132508400,"created_by":"updated@example.com","lat":-1.0,"lng":-2.0}"#)
                 .dispatch();
     assert_eq!(res.status(), HttpStatus::Ok);
-    let updated = db.shared().unwrap().get_event(id.as_ref()).unwrap();
+    let updated = db.shared().unwrap().get_event(&id.as_str().into()).unwrap();
     assert_eq!(
         Some((
             LatCoord::from_deg(-1.0).to_deg(),
@@ -0,0 +1,77 @@
+use super::*;
+
+fn create_org(db: &sqlite::Connections, id: &str, api_token: &str) {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
+            id: id.into(),
+            name: id.into(),
+            owned_tags: vec![format!("{}-tag", id)],
+        },
+        api_token,
+    );
+}
+
+#[test]
+fn without_api_token() {
+    let (client, _) = setup();
+    let res = client
+        .put("/events/by-external-id/ext-1")
+        .header(ContentType::JSON)
+        .body(r#"{"title":"x","start":4132508400,"created_by":"foo@bar.com"}"#)
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Unauthorized);
+}
+
+#[test]
+fn create_and_update_with_api_token() {
+    let (client, db) = setup();
+    create_org(&db, "foo", "foo-token");
+    create_org(&db, "bar", "bar-token");
+
+    let mut res = client
+        .put("/events/by-external-id/ext-1")
+        .header(ContentType::JSON)
+        .header(Header::new("Authorization", "Bearer foo-token"))
+        .body(r#"{"title":"x","start":4132508400,"created_by":"foo@bar.com"}"#)
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Ok);
+    let id: String = serde_json::from_str(&res.body_string().unwrap()).unwrap();
+
+    // Idempotent
+    for _ in 0..2 {
+        let mut res = client
+            .put("/events/by-external-id/ext-1")
+            .header(ContentType::JSON)
+            .header(Header::new("Authorization", "Bearer foo-token"))
+            .body(r#"{"title":"y","start":4132508400,"created_by":"foo@bar.com"}"#)
+            .dispatch();
+        assert_eq!(res.status(), HttpStatus::Ok);
+        assert_eq!(
+            id,
+            serde_json::from_str::<String>(&res.body_string().unwrap()).unwrap()
+        );
+    }
+    let event = db.shared().unwrap().get_event(&id.as_str().into()).unwrap();
+    assert_eq!("y", event.title);
+    assert_eq!(vec!["foo-tag".to_string()], event.tags);
+
+    // External ids are unique per organization
+    let mut res = client
+        .put("/events/by-external-id/ext-1")
+        .header(ContentType::JSON)
+        .header(Header::new("Authorization", "Bearer bar-token"))
+        .body(r#"{"title":"z","start":4132508400,"created_by":"foo@bar.com"}"#)
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Ok);
+    let other_id: String = serde_json::from_str(&res.body_string().unwrap()).unwrap();
+    assert_ne!(id, other_id);
+    assert_eq!(
+        2,
+        db.shared()
+            .unwrap()
+            .all_events_chronologically()
+            .unwrap()
+            .len()
+    );
+}
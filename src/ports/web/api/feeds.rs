@@ -0,0 +1,25 @@
+use super::*;
+use crate::adapters::atom;
+
+const DEFAULT_FEED_LIMIT: usize = 100;
+
+#[get("/feed/entries.atom?<bbox>&<tags>")]
+pub fn get_entries_feed(
+    connections: sqlite::Connections,
+    bbox: Option<String>,
+    tags: Option<String>,
+) -> result::Result<Content<String>, AppError> {
+    let bbox = bbox
+        .map(|bbox| bbox.parse::<geo::MapBbox>())
+        .transpose()
+        .map_err(|_| Error::Parameter(ParameterError::Bbox))?;
+    let tags = tags
+        .as_deref()
+        .map(|tags| usecases::prepare_tag_list(tags.split(',')))
+        .unwrap_or_default();
+    let filter = usecases::PlacesFeedFilter { bbox, tags };
+    let changes = usecases::load_places_feed(&*connections.shared()?, &filter, DEFAULT_FEED_LIMIT)?;
+    let content_type = ContentType::parse_flexible(atom::MEDIA_TYPE)
+        .ok_or_else(|| anyhow::anyhow!("Invalid media type: {}", atom::MEDIA_TYPE))?;
+    Ok(Content(content_type, atom::places_feed(changes)?))
+}
@@ -1,6 +1,9 @@
 use super::guards::*;
 use crate::{
-    adapters::{self, json},
+    adapters::{
+        export::{self, ExportFormat},
+        json,
+    },
     core::{
         prelude::*,
         usecases::{self, DuplicateType},
@@ -11,25 +14,41 @@ use crate::{
         error::AppError,
         flows::prelude as flows,
     },
-    ports::web::notify::*,
+    ports::web::{notify::*, rating_timelines::RatingTimelines, Cfg},
 };
 use rocket::{
     self,
-    http::{ContentType, Cookie, Cookies, Status},
-    request::Form,
+    http::{Accept, ContentType, Cookie, Cookies, RawStr, Status},
+    request::{Form, FromParam},
     response::{content::Content, Responder, Response},
-    Route,
+    Route, State,
 };
 use rocket_contrib::json::Json;
+use serde::Serialize;
 use std::result;
 
+mod announcements;
+mod archive;
+mod audit_log;
+mod changes;
 mod count;
+mod curated_tags;
+mod drafts;
+mod email_outbox;
 pub mod events;
+mod feeds;
+mod moderation;
+mod organizations;
+mod places_export;
 mod ratings;
+mod reports;
 mod search;
+mod subscriptions;
+mod tags;
 #[cfg(test)]
 pub mod tests;
 mod users;
+mod webhooks;
 
 type Result<T> = result::Result<Json<T>, AppError>;
 
@@ -40,69 +59,356 @@ pub fn routes() -> Vec<Route> {
         login,
         logout,
         confirm_email_address,
+        post_entry_contact_verification,
+        confirm_contact_email,
+        cancel_event_registration,
         subscribe_to_bbox,
         get_bbox_subscriptions,
         unsubscribe_all_bboxes,
+        subscriptions::get_bbox_subscriptions_csv,
+        subscriptions::post_bbox_subscriptions_csv,
+        subscriptions::get_tag_subscriptions,
+        subscriptions::post_tag_subscription,
+        subscriptions::delete_tag_subscription,
+        subscriptions::get_saved_searches,
+        subscriptions::post_saved_search,
+        subscriptions::delete_saved_search,
         get_entry,
+        get_entry_quality,
+        get_entry_duplicates,
+        get_entry_diff,
+        changes::get_entry_events_stream,
+        changes::get_changes_stream,
+        get_entry_by_source,
         get_entries_recently_changed,
+        feeds::get_entries_feed,
         get_entries_most_popular_tags,
         get_place,
         get_place_history,
+        get_place_external_ids,
+        put_place_external_ids,
         post_places_review,
+        post_merge_places,
         post_entry,
+        post_entry_duplicates,
         put_entry,
+        post_entry_restore,
+        drafts::get_entry_drafts,
+        drafts::get_entry_draft,
+        drafts::post_entry_draft,
+        drafts::put_entry_draft,
+        drafts::delete_entry_draft,
         events::post_event,
         events::post_event_with_token,
         events::get_event,
         events::get_events_chronologically,
         events::get_events_with_token,
+        events::get_events_nearby,
         events::put_event,
         events::put_event_with_token,
+        events::put_event_by_external_id,
+        events::put_event_by_external_id_without_token,
         events::post_events_archive,
+        events::post_event_registration,
+        events::get_event_registrations,
+        archive::post_archive_batch,
         events::delete_event,
         events::delete_event_with_token,
-        events::csv_export_with_token,
-        events::csv_export_without_token,
+        events::export_with_token,
+        events::export_without_token,
         users::post_request_password_reset,
         users::post_reset_password,
+        users::post_request_login_link,
+        users::post_login_with_token,
         users::post_user,
         ratings::post_rating,
+        ratings::post_ratings_batch,
+        ratings::put_rating,
+        ratings::delete_rating,
         ratings::load_rating,
+        ratings::get_entry_ratings_timeline,
+        reports::post_entry_report,
+        reports::post_report,
+        reports::get_reports,
+        reports::post_reports_resolve,
+        moderation::post_moderation_assignment,
+        moderation::post_moderation_assignment_state,
+        moderation::get_moderation_assigned_to_me,
+        moderation::get_moderation_comments,
+        moderation::get_moderation_places,
+        moderation::post_comment_flag,
         users::get_user,
         users::get_current_user,
+        users::get_scout_report_settings,
+        users::post_scout_report_settings,
+        users::get_notification_digest_settings,
+        users::post_notification_digest_settings,
+        users::get_user_language,
+        users::post_user_language,
+        users::delete_current_user,
         users::delete_user,
+        users::post_deactivate_user,
+        users::post_reactivate_user,
+        users::get_user_status_log,
+        users::get_user_permissions,
+        users::post_user_permission,
+        users::get_user_tokens,
+        users::get_user_api_tokens,
+        users::post_user_api_token,
+        users::delete_user_api_token,
+        announcements::get_current_announcements,
+        announcements::get_announcements,
+        announcements::post_announcement,
+        announcements::delete_announcement,
+        webhooks::get_webhooks,
+        webhooks::post_webhook,
+        webhooks::delete_webhook,
+        webhooks::get_webhook_deliveries,
+        email_outbox::get_outgoing_emails,
+        audit_log::get_audit_log,
+        curated_tags::get_curated_tags,
+        curated_tags::post_curated_tag,
+        curated_tags::delete_curated_tag,
+        curated_tags::get_curated_orgs,
+        curated_tags::post_curated_org,
+        curated_tags::delete_curated_org,
+        tags::post_rename_tag,
+        tags::post_merge_tags,
+        tags::delete_tag,
+        organizations::get_organizations,
+        organizations::get_organization,
+        organizations::post_organization,
+        organizations::put_organization,
+        organizations::delete_organization,
+        organizations::post_organization_api_token,
+        organizations::get_organization_api_tokens,
+        organizations::post_organization_api_tokens,
+        organizations::delete_organization_api_token,
+        organizations::get_pending_place_clearances,
+        organizations::post_place_clearances,
         get_categories,
         get_category,
         get_tags,
+        get_tag_owner,
+        get_tag_owners,
         search::get_search,
+        search::get_global_search,
+        search::get_search_suggestions,
+        search::get_entries_suggest_tags,
+        search::get_search_clusters,
         get_duplicates,
         count::get_count_entries,
         count::get_count_tags,
         get_version,
         get_api,
-        entries_csv_export_with_token,
-        entries_csv_export_without_token,
+        entries_export_with_token,
+        entries_export_without_token,
+        places_export::get_export_places_csv,
     ]
 }
 
-#[get("/entries/<ids>")]
-fn get_entry(db: sqlite::Connections, ids: String) -> Result<Vec<json::Entry>> {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlacesFormat {
+    Json,
+    GeoJson,
+}
+
+/// Clients either request GeoJSON explicitly with the query
+/// parameter `format=geojson` or by preferring the media type
+/// `application/geo+json` in the `Accept` header.
+fn places_format(
+    format: Option<&str>,
+    accept: Option<&Accept>,
+) -> result::Result<PlacesFormat, AppError> {
+    match format {
+        Some("json") => Ok(PlacesFormat::Json),
+        Some("geojson") => Ok(PlacesFormat::GeoJson),
+        Some(_) => Err(Error::Parameter(ParameterError::InvalidResponseFormat).into()),
+        None => {
+            let geo_json_preferred = accept
+                .map(|accept| {
+                    let media_type = accept.preferred().media_type();
+                    media_type.top() == "application" && media_type.sub() == "geo+json"
+                })
+                .unwrap_or(false);
+            if geo_json_preferred {
+                Ok(PlacesFormat::GeoJson)
+            } else {
+                Ok(PlacesFormat::Json)
+            }
+        }
+    }
+}
+
+/// Places that are either rendered as plain JSON or exported
+/// as a GeoJSON feature collection.
+pub enum PlacesResponse<T> {
+    Json(T),
+    Export(&'static dyn ExportFormat, String),
+}
+
+impl<'r, T: Serialize> Responder<'r> for PlacesResponse<T> {
+    fn respond_to(self, req: &rocket::Request) -> result::Result<Response<'r>, Status> {
+        match self {
+            PlacesResponse::Json(body) => Json(body).respond_to(req),
+            PlacesResponse::Export(format, data) => {
+                let content_type =
+                    ContentType::parse_flexible(format.media_type()).ok_or_else(|| {
+                        error!("Invalid media type: {}", format.media_type());
+                        Status::InternalServerError
+                    })?;
+                Content(content_type, data).respond_to(req)
+            }
+        }
+    }
+}
+
+// Splits the categories from the tags of the place and
+// orders them by their definition
+fn place_export(
+    mut place: Place,
+    all_categories: &Categories,
+    avg_rating: AvgRatingValue,
+) -> export::PlaceExport {
+    let (tags, categories) = all_categories.split_from_tags(place.tags);
+    place.tags = tags;
+    let categories = all_categories
+        .iter()
+        .filter(|c1| categories.iter().any(|c2| c1.id == c2.id))
+        .cloned()
+        .collect();
+    (place, categories, avg_rating)
+}
+
+// Places on a map are rendered like a public export
+// without any activity details
+fn export_places_as_geojson<T>(
+    places: Vec<export::PlaceExport>,
+    cfg: &Cfg,
+) -> result::Result<PlacesResponse<T>, AppError> {
+    let format: &'static dyn ExportFormat = &export::GeoJson;
+    let places = places
+        .into_iter()
+        .map(|(place, categories, avg_rating)| {
+            (
+                usecases::filter_place(place, std::iter::empty()),
+                categories,
+                avg_rating,
+            )
+        })
+        .collect();
+    let data = format.export_places(places, cfg.dataset_license.as_deref())?;
+    Ok(PlacesResponse::Export(format, data))
+}
+
+#[get("/entries/<ids>?<format>")]
+fn get_entry(
+    db: sqlite::Connections,
+    cfg: State<Cfg>,
+    ids: String,
+    format: Option<String>,
+    accept: Option<&Accept>,
+) -> result::Result<PlacesResponse<Vec<json::Entry>>, AppError> {
+    let format = places_format(format.as_deref(), accept)?;
     // TODO: Only lookup and return a single entity
     // TODO: Add a new method for searching multiple ids
     let ids = util::split_ids(&ids);
     if ids.is_empty() {
-        return Ok(Json(vec![]));
+        return match format {
+            PlacesFormat::Json => Ok(PlacesResponse::Json(vec![])),
+            PlacesFormat::GeoJson => export_places_as_geojson(vec![], &cfg),
+        };
     }
     let results = {
         let mut results = Vec::with_capacity(ids.len());
         let db = db.shared()?;
-        for (place, _) in db.get_places(&ids)?.into_iter() {
-            let r = db.load_ratings_of_place(place.id.as_ref())?;
-            results.push(json::entry_from_place_with_ratings(place, r));
+        let places = db.get_places(&ids)?;
+        // Requesting a single archived entry explicitly
+        // should not look like a successful response
+        if ids.len() == 1 && places.iter().any(|(_, s)| *s == ReviewStatus::Archived) {
+            if let Some(primary_id) = db.get_place_merged_into(&ids[0].into())? {
+                return Err(Error::Repo(RepoError::MergedInto(primary_id)).into());
+            }
+            return Err(Error::Repo(RepoError::Archived).into());
+        }
+        let categories = Categories::from(db.all_categories()?);
+        if format == PlacesFormat::GeoJson {
+            let mut exports = Vec::with_capacity(places.len());
+            for (place, _) in places.into_iter() {
+                let mut avg_ratings = AvgRatingsBuilder::default();
+                for r in db.load_ratings_of_place(&place.id.as_str().into())? {
+                    avg_ratings += (r.context, r.value);
+                }
+                exports.push(place_export(
+                    place,
+                    &categories,
+                    avg_ratings.build().total(),
+                ));
+            }
+            return export_places_as_geojson(exports, &cfg);
+        }
+        for (place, _) in places.into_iter() {
+            let r = db.load_ratings_of_place(&place.id.as_str().into())?;
+            let email_verified = usecases::is_contact_email_verified(&*db, &place)?;
+            let mut entry = json::entry_from_place_with_ratings(place, r, &categories);
+            entry.email_verified = email_verified;
+            results.push(entry);
         }
         results
     };
-    Ok(Json(results))
+    Ok(PlacesResponse::Json(results))
+}
+
+#[get("/entries/by-source?<importer>&<external_ref>")]
+fn get_entry_by_source(
+    db: sqlite::Connections,
+    importer: String,
+    external_ref: String,
+) -> Result<json::Entry> {
+    let db = db.shared()?;
+    let (place, _) = usecases::find_place_by_source(&*db, &importer, &external_ref)?;
+    let ratings = db.load_ratings_of_place(&place.id.as_str().into())?;
+    let categories = Categories::from(db.all_categories()?);
+    let email_verified = usecases::is_contact_email_verified(&*db, &place)?;
+    let mut entry = json::entry_from_place_with_ratings(place, ratings, &categories);
+    entry.email_verified = email_verified;
+    Ok(Json(entry))
+}
+
+#[get("/entries/<id>/quality")]
+fn get_entry_quality(db: sqlite::Connections, id: String) -> Result<json::PlaceQuality> {
+    let (place, _) = db.shared()?.get_place(&id.into())?;
+    let quality = util::quality::place_quality(&place, TimestampMs::now());
+    Ok(Json(quality.into()))
+}
+
+#[get("/entries/<id>/duplicates")]
+fn get_entry_duplicates(
+    db: sqlite::Connections,
+    search_engine: tantivy::SearchEngine,
+    id: String,
+) -> Result<Vec<json::PlaceDuplicate>> {
+    let duplicates = {
+        let db = db.shared()?;
+        let (place, _) = db.get_place(&id.into())?;
+        usecases::find_place_duplicates(&*db, &search_engine, &place)?
+    };
+    Ok(Json(duplicates.into_iter().map(Into::into).collect()))
+}
+
+#[get("/entries/<id>/diff?<from>&<to>")]
+fn get_entry_diff(
+    db: sqlite::Connections,
+    login: Login,
+    id: String,
+    from: u64,
+    to: u64,
+) -> Result<json::PlaceRevisionDiff> {
+    let diff = {
+        let db = db.shared()?;
+        usecases::diff_place_revisions(&*db, &login.0, &id.into(), from.into(), to.into())?
+    };
+    Ok(Json(diff.into()))
 }
 
 // Limit the total number of recently changed entries to avoid cloning
@@ -114,15 +420,64 @@ const ENTRIES_RECECENTLY_CHANGED_MAX_AGE_IN_DAYS: i64 = 100;
 
 const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
 
-#[get("/entries/recently-changed?<since>&<until>&<with_ratings>&<offset>&<limit>")]
+/// The header that contains the cursor for requesting the next page
+/// of recently changed entries. Only present if the current page has
+/// been filled up to the limit.
+const NEXT_CURSOR_HEADER: &str = "X-Next-Cursor";
+
+pub struct RecentlyChangedEntries {
+    entries: Vec<json::Entry>,
+    next_cursor: Option<String>,
+}
+
+impl<'r> Responder<'r> for RecentlyChangedEntries {
+    fn respond_to(self, req: &rocket::Request) -> result::Result<Response<'r>, Status> {
+        let mut res = Json(self.entries).respond_to(req)?;
+        if let Some(next_cursor) = self.next_cursor {
+            res.set_raw_header(NEXT_CURSOR_HEADER, next_cursor);
+        }
+        Ok(res)
+    }
+}
+
+fn encode_recently_changed_cursor(cursor: &RecentlyChangedEntriesCursor) -> String {
+    format!("{}-{}", cursor.created_at.into_inner(), cursor.log_id)
+}
+
+fn decode_recently_changed_cursor(
+    token: &str,
+) -> result::Result<RecentlyChangedEntriesCursor, ParameterError> {
+    let mut parts = token.trim().splitn(2, '-');
+    let created_at = parts.next().and_then(|t| t.parse().ok());
+    let log_id = parts.next().and_then(|id| id.parse().ok());
+    match (created_at, log_id) {
+        (Some(created_at), Some(log_id)) => Ok(RecentlyChangedEntriesCursor {
+            created_at: TimestampMs::from_inner(created_at),
+            log_id,
+        }),
+        _ => Err(ParameterError::InvalidCursor),
+    }
+}
+
+#[get("/entries/recently-changed?<since>&<until>&<with_ratings>&<offset>&<limit>&<cursor>")]
 fn get_entries_recently_changed(
     db: sqlite::Connections,
     since: Option<i64>, // in seconds
     until: Option<i64>, // in seconds
     with_ratings: Option<bool>,
-    offset: Option<u64>,
+    mut offset: Option<u64>,
     mut limit: Option<u64>,
-) -> Result<Vec<json::Entry>> {
+    cursor: Option<String>,
+) -> result::Result<RecentlyChangedEntries, AppError> {
+    let after = cursor
+        .as_deref()
+        .map(decode_recently_changed_cursor)
+        .transpose()
+        .map_err(Error::Parameter)?;
+    if after.is_some() {
+        // The cursor overrides the offset
+        offset = None;
+    }
     let since_min = Timestamp::now().into_seconds()
         - ENTRIES_RECECENTLY_CHANGED_MAX_AGE_IN_DAYS * SECONDS_PER_DAY;
     let since = if let Some(since) = since {
@@ -167,33 +522,48 @@ fn get_entries_recently_changed(
     let params = RecentlyChangedEntriesParams {
         since: since.map(TimestampMs::from_seconds),
         until: until.map(TimestampMs::from_seconds),
+        after,
     };
     let pagination = Pagination { offset, limit };
-    let results = {
+    let (results, next_cursor) = {
         let db = db.shared()?;
         let entries = db.recently_changed_places(&params, &pagination)?;
-        if with_ratings.unwrap_or(false) {
+        let next_cursor = if entries.len() as u64 == limit.unwrap_or_default() {
+            entries
+                .last()
+                .map(|(_, _, _, cursor)| encode_recently_changed_cursor(cursor))
+        } else {
+            None
+        };
+        let categories = Categories::from(db.all_categories()?);
+        let results = if with_ratings.unwrap_or(false) {
             let mut results = Vec::with_capacity(entries.len());
-            for (place, _, _) in entries.into_iter() {
-                let r = db.load_ratings_of_place(place.id.as_ref())?;
-                results.push(json::entry_from_place_with_ratings(place, r));
+            for (place, _, _, _) in entries.into_iter() {
+                let r = db.load_ratings_of_place(&place.id.as_str().into())?;
+                results.push(json::entry_from_place_with_ratings(place, r, &categories));
             }
             results
         } else {
             entries
                 .into_iter()
-                .map(|(place, _, _)| json::entry_from_place_with_ratings(place, vec![]))
+                .map(|(place, _, _, _)| {
+                    json::entry_from_place_with_ratings(place, vec![], &categories)
+                })
                 .collect()
-        }
+        };
+        (results, next_cursor)
     };
-    Ok(Json(results))
+    Ok(RecentlyChangedEntries {
+        entries: results,
+        next_cursor,
+    })
 }
 
 const ENTRIES_MOST_POPULAR_TAGS_PAGINATION_LIMIT_MAX: u64 = 1000;
 
 #[get("/entries/most-popular-tags?<min_count>&<max_count>&<offset>&<limit>")]
 pub fn get_entries_most_popular_tags(
-    db: sqlite::Connections,
+    search_engine: tantivy::SearchEngine,
     min_count: Option<u64>,
     max_count: Option<u64>,
     offset: Option<u64>,
@@ -209,10 +579,7 @@ pub fn get_entries_most_popular_tags(
             .min(ENTRIES_MOST_POPULAR_TAGS_PAGINATION_LIMIT_MAX),
     );
     let pagination = Pagination { offset, limit };
-    let results = {
-        let db = db.shared()?;
-        db.most_popular_place_revision_tags(&params, &pagination)?
-    };
+    let results = search_engine.most_popular_tags(&params, &pagination)?;
     Ok(Json(results.into_iter().map(Into::into).collect()))
 }
 
@@ -223,7 +590,7 @@ pub fn get_place(
 ) -> Result<(json::PlaceRoot, json::PlaceRevision, json::ReviewStatus)> {
     let (place, status) = {
         let db = db.shared()?;
-        db.get_place(&id)?
+        db.get_place(&id.into())?
     };
     let (place_root, place_revision) = place.into();
     Ok(Json((
@@ -233,6 +600,35 @@ pub fn get_place(
     )))
 }
 
+#[get("/places/<id>/external-ids")]
+pub fn get_place_external_ids(
+    db: sqlite::Connections,
+    id: String,
+) -> Result<Vec<json::ExternalId>> {
+    let external_ids = usecases::load_place_external_ids(&*db.shared()?, &id.into())?;
+    Ok(Json(external_ids.into_iter().map(Into::into).collect()))
+}
+
+#[put(
+    "/places/<id>/external-ids",
+    format = "application/json",
+    data = "<data>"
+)]
+pub fn put_place_external_ids(
+    db: sqlite::Connections,
+    login: Login,
+    id: String,
+    data: Json<Vec<json::ExternalId>>,
+) -> Result<Vec<json::ExternalId>> {
+    let external_ids = usecases::replace_place_external_ids(
+        &*db.exclusive()?,
+        &login.0,
+        &id.into(),
+        data.into_inner().into_iter().map(Into::into).collect(),
+    )?;
+    Ok(Json(external_ids.into_iter().map(Into::into).collect()))
+}
+
 #[get("/places/<id>/history")]
 pub fn get_place_history(
     db: sqlite::Connections,
@@ -244,9 +640,9 @@ pub fn get_place_history(
 
         // The history contains e-mail addresses of registered users
         // and is only permitted for scouts and admins!
-        usecases::authorize_user_by_email(&*db, &login.0, Role::Scout)?;
+        usecases::authorize_user_by_email(&*db, &login.0, Permission::ViewPlaceHistory)?;
 
-        db.get_place_history(&id)?
+        db.get_place_history(&id.into())?
     };
     Ok(Json(place_history.into()))
 }
@@ -256,6 +652,7 @@ pub fn post_places_review(
     login: Login,
     db: sqlite::Connections,
     mut search_engine: tantivy::SearchEngine,
+    notify: Notify,
     ids: String,
     review: Json<json::Review>,
 ) -> Result<()> {
@@ -266,18 +663,19 @@ pub fn post_places_review(
     let reviewer_email = {
         let db = db.shared()?;
         // Only scouts and admins are entitled to review places
-        usecases::authorize_user_by_email(&*db, &login.0, Role::Scout)?.email
+        usecases::authorize_user_by_email(&*db, &login.0, Permission::ReviewPlaces)?.email
     };
     let json::Review { status, comment } = review.into_inner();
+    let status = ReviewStatus::from(status);
     // TODO: Record context information
     let context = None;
     let review = usecases::Review {
         context,
         reviewer_email: reviewer_email.into(),
-        status: status.into(),
+        status,
         comment,
     };
-    let update_count = flows::review_places(&db, &mut search_engine, &ids, review)?;
+    let update_count = flows::review_places(&db, &mut search_engine, &*notify, &ids, review)?;
     if update_count < ids.len() {
         log::warn!(
             "Applied review to only {} of {} place(s): {:?}",
@@ -289,6 +687,22 @@ pub fn post_places_review(
     Ok(Json(()))
 }
 
+#[post("/admin/places/merge", format = "application/json", data = "<data>")]
+pub fn post_merge_places(
+    login: Login,
+    db: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    rating_timelines: State<RatingTimelines>,
+    data: Json<json::MergePlaces>,
+) -> StatusResult {
+    let json::MergePlaces { primary, duplicate } = data.into_inner();
+    let (primary, duplicate) = (PlaceId::from(primary), PlaceId::from(duplicate));
+    flows::merge_places(&db, &mut search_engine, &login.0, &primary, &duplicate)?;
+    rating_timelines.invalidate(primary.as_str());
+    rating_timelines.invalidate(duplicate.as_str());
+    Ok(Status::NoContent)
+}
+
 #[get("/duplicates/<ids>")]
 fn get_duplicates(
     db: sqlite::Connections,
@@ -367,6 +781,50 @@ fn confirm_email_address(db: sqlite::Connections, token: Json<ConfirmationToken>
     Ok(Json(()))
 }
 
+#[post("/entries/<id>/contact/verification")]
+fn post_entry_contact_verification(
+    db: sqlite::Connections,
+    notify: Notify,
+    _user: Login,
+    id: String,
+) -> Result<()> {
+    let pending = usecases::request_contact_verification(&*db.exclusive()?, &id.into())?;
+    // Nothing to do if the contact has already been verified
+    if let Some((place, email_nonce)) = pending {
+        notify.place_contact_verification_requested(&place, &email_nonce);
+    }
+    Ok(Json(()))
+}
+
+#[post(
+    "/confirm-contact-email",
+    format = "application/json",
+    data = "<token>"
+)]
+fn confirm_contact_email(
+    db: sqlite::Connections,
+    token: Json<ConfirmationToken>,
+) -> Result<String> {
+    let token = token.into_inner().token;
+    let place_id = usecases::confirm_contact_verification(&*db.exclusive()?, &token)?;
+    Ok(Json(place_id.into()))
+}
+
+#[post(
+    "/cancel-event-registration",
+    format = "application/json",
+    data = "<token>"
+)]
+fn cancel_event_registration(
+    connections: sqlite::Connections,
+    notify: Notify,
+    token: Json<ConfirmationToken>,
+) -> Result<()> {
+    let token = token.into_inner().token;
+    flows::cancel_event_registration(&connections, &*notify, &token)?;
+    Ok(Json(()))
+}
+
 #[post(
     "/subscribe-to-bbox",
     format = "application/json",
@@ -374,6 +832,7 @@ fn confirm_email_address(db: sqlite::Connections, token: Json<ConfirmationToken>
 )]
 fn subscribe_to_bbox(
     db: sqlite::Connections,
+    cfg: State<Cfg>,
     user: Login,
     coordinates: Json<Vec<json::Coordinate>>,
 ) -> Result<()> {
@@ -387,7 +846,7 @@ fn subscribe_to_bbox(
     }
     let bbox = geo::MapBbox::new(sw_ne[0], sw_ne[1]);
     let Login(email) = user;
-    usecases::subscribe_to_bbox(&*db.exclusive()?, email, bbox)?;
+    usecases::subscribe_to_bbox(&*db.exclusive()?, email, bbox, cfg.subscription_quota)?;
     Ok(Json(()))
 }
 
@@ -417,48 +876,106 @@ fn get_bbox_subscriptions(
     Ok(Json(user_subscriptions))
 }
 
+// Only organizations with an API token that grants the scope
+// `import_places` may record the source of imported places.
+fn authorize_place_source(
+    importer: Option<ScopedBearer<scopes::ImportPlaces>>,
+    source: Option<&mut usecases::NewPlaceSource>,
+) -> result::Result<(), AppError> {
+    if let Some(source) = source {
+        let importer = importer.ok_or(Error::Parameter(ParameterError::Unauthorized))?;
+        source.importer = importer.org.id;
+    }
+    Ok(())
+}
+
 #[post("/entries", format = "application/json", data = "<body>")]
 fn post_entry(
     account: Option<Account>,
+    importer: Option<ScopedBearer<scopes::ImportPlaces>>,
     connections: sqlite::Connections,
     notify: Notify,
     mut search_engine: tantivy::SearchEngine,
     body: Json<usecases::NewPlace>,
 ) -> Result<String> {
-    Ok(Json(
-        flows::create_place(
-            &connections,
-            &mut search_engine,
-            &*notify,
-            body.into_inner(),
-            account.as_ref().map(|a| a.email()),
-        )?
-        .id
-        .to_string(),
-    ))
+    let mut new_place = body.into_inner();
+    authorize_place_source(importer, new_place.source.as_mut())?;
+    let place = flows::create_place(
+        &connections,
+        &mut search_engine,
+        &*notify,
+        new_place,
+        account.as_ref().map(|a| a.email()),
+    )?;
+    Ok(Json(place.id.to_string()))
+}
+
+/// Check a new place for duplicates before actually creating it.
+#[post("/entries/duplicates", format = "application/json", data = "<body>")]
+fn post_entry_duplicates(
+    account: Option<Account>,
+    db: sqlite::Connections,
+    search_engine: tantivy::SearchEngine,
+    body: Json<usecases::NewPlace>,
+) -> Result<Vec<json::PlaceDuplicate>> {
+    let new_place = usecases::NewPlace {
+        // The source is irrelevant for finding duplicates
+        source: None,
+        ..body.into_inner()
+    };
+    let duplicates = usecases::find_duplicates_of_new_place(
+        &*db.shared()?,
+        &search_engine,
+        new_place,
+        account.as_ref().map(|a| a.email()),
+    )?;
+    Ok(Json(duplicates.into_iter().map(Into::into).collect()))
 }
 
 #[put("/entries/<id>", format = "application/json", data = "<data>")]
 fn put_entry(
     account: Option<Account>,
+    importer: Option<ScopedBearer<scopes::ImportPlaces>>,
     connections: sqlite::Connections,
     mut search_engine: tantivy::SearchEngine,
     notify: Notify,
     id: String,
     data: Json<usecases::UpdatePlace>,
 ) -> Result<String> {
-    Ok(Json(
-        flows::update_place(
-            &connections,
-            &mut search_engine,
-            &*notify,
-            id.into(),
-            data.into_inner(),
-            account.as_ref().map(|a| a.email()),
-        )?
-        .id
-        .into(),
-    ))
+    let mut update_place = data.into_inner();
+    authorize_place_source(importer, update_place.source.as_mut())?;
+    let place = flows::update_place(
+        &connections,
+        &mut search_engine,
+        &*notify,
+        id.into(),
+        update_place,
+        account.as_ref().map(|a| a.email()),
+    )?;
+    Ok(Json(place.id.into()))
+}
+
+#[post(
+    "/entries/<id>/actions/restore",
+    format = "application/json",
+    data = "<data>"
+)]
+fn post_entry_restore(
+    login: Login,
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    id: String,
+    data: Json<json::RestorePlaceRevision>,
+) -> Result<u64> {
+    let json::RestorePlaceRevision { revision } = data.into_inner();
+    let place = flows::restore_place_revision(
+        &connections,
+        &mut search_engine,
+        &login.0,
+        &id.into(),
+        revision.into(),
+    )?;
+    Ok(Json(place.revision.into()))
 }
 
 #[get("/tags")]
@@ -467,6 +984,34 @@ fn get_tags(connections: sqlite::Connections) -> Result<Vec<String>> {
     Ok(Json(tags.into_iter().map(|t| t.id).collect()))
 }
 
+/// The organizations that own a tag, i.e. an empty
+/// list if the tag could be used by anyone.
+#[get("/tags/<tag>/owner")]
+fn get_tag_owner(connections: sqlite::Connections, tag: String) -> Result<Vec<json::TagOwner>> {
+    let owners = usecases::load_tag_owners(&*connections.shared()?, &tag)?;
+    Ok(Json(owners.into_iter().map(Into::into).collect()))
+}
+
+/// All tags that are owned by organizations (only for scouts).
+#[get("/tags/owners")]
+fn get_tag_owners(connections: sqlite::Connections, login: Login) -> Result<Vec<json::OwnedTag>> {
+    let ownerships = usecases::load_all_tag_ownerships(&*connections.shared()?, &login.0)?;
+    let mut owned_tags: Vec<json::OwnedTag> = vec![];
+    // The ownerships are ordered by tag
+    for ownership in ownerships {
+        match owned_tags.last_mut() {
+            Some(owned_tag) if owned_tag.tag == ownership.tag => {
+                owned_tag.owners.push(ownership.into());
+            }
+            _ => owned_tags.push(json::OwnedTag {
+                tag: ownership.tag.clone(),
+                owners: vec![ownership.into()],
+            }),
+        }
+    }
+    Ok(Json(owned_tags))
+}
+
 #[get("/categories")]
 fn get_categories(connections: sqlite::Connections) -> Result<Vec<json::Category>> {
     let categories = connections
@@ -496,46 +1041,92 @@ fn get_category(connections: sqlite::Connections, ids: String) -> Result<Vec<jso
     Ok(Json(categories))
 }
 
-#[get("/export/entries.csv?<query..>")]
-fn entries_csv_export_with_token(
+// Splits an export file name like `entries.csv` into its base
+// name and extension and looks up the corresponding format.
+// Requests for unknown files or formats are forwarded.
+fn parse_export_file(file: &RawStr, name: &str) -> Option<&'static dyn ExportFormat> {
+    let mut parts = file.as_str().rsplitn(2, '.');
+    let extension = parts.next()?;
+    if parts.next()? != name {
+        return None;
+    }
+    export::export_format_by_extension(extension)
+}
+
+fn export_content(
+    format: &dyn ExportFormat,
+    data: String,
+) -> result::Result<Content<String>, AppError> {
+    let content_type = ContentType::parse_flexible(format.media_type())
+        .ok_or_else(|| anyhow::anyhow!("Invalid media type: {}", format.media_type()))?;
+    Ok(Content(content_type, data))
+}
+
+pub struct EntriesExportFile(&'static dyn ExportFormat);
+
+impl<'a> FromParam<'a> for EntriesExportFile {
+    type Error = &'a RawStr;
+
+    fn from_param(param: &'a RawStr) -> result::Result<Self, Self::Error> {
+        parse_export_file(param, "entries").map(Self).ok_or(param)
+    }
+}
+
+#[get("/export/<file>?<query..>", rank = 1)]
+fn entries_export_with_token(
     connections: sqlite::Connections,
     search_engine: tantivy::SearchEngine,
-    token: Bearer,
+    file: EntriesExportFile,
+    token: ScopedBearer<scopes::Read>,
     login: Login,
+    cfg: State<Cfg>,
     query: Form<search::SearchQuery>,
 ) -> result::Result<Content<String>, AppError> {
-    let organization =
-        usecases::authorize_organization_by_token(&*connections.shared()?, &token.0)?;
-    entries_csv_export(
+    entries_export(
         connections,
         search_engine,
-        Some(organization),
+        file.0,
+        Some(token.org),
         login,
+        &cfg,
         query.into_inner(),
     )
 }
 
-#[get("/export/entries.csv?<query..>", rank = 2)]
-fn entries_csv_export_without_token(
+#[get("/export/<file>?<query..>", rank = 2)]
+fn entries_export_without_token(
     connections: sqlite::Connections,
     search_engine: tantivy::SearchEngine,
+    file: EntriesExportFile,
     login: Login,
+    cfg: State<Cfg>,
     query: Form<search::SearchQuery>,
 ) -> result::Result<Content<String>, AppError> {
-    entries_csv_export(connections, search_engine, None, login, query.into_inner())
+    entries_export(
+        connections,
+        search_engine,
+        file.0,
+        None,
+        login,
+        &cfg,
+        query.into_inner(),
+    )
 }
 
-fn entries_csv_export(
+fn entries_export(
     connections: sqlite::Connections,
     search_engine: tantivy::SearchEngine,
+    format: &dyn ExportFormat,
     org: Option<Organization>,
     login: Login,
+    cfg: &Cfg,
     query: search::SearchQuery,
 ) -> result::Result<Content<String>, AppError> {
     let owned_tags = org.map(|org| org.owned_tags).unwrap_or_default();
 
     let db = connections.shared()?;
-    let user = usecases::authorize_user_by_email(&*db, &login.0, Role::Scout)?;
+    let user = usecases::authorize_user_by_email(&*db, &login.0, Permission::ExportData)?;
+    let permissions = usecases::user_permissions(&*db, &user)?;
 
     let (req, limit) = search::parse_search_query(&query)?;
     let limit = if let Some(limit) = limit {
@@ -547,7 +1138,7 @@ fn entries_csv_export(
     };
 
     let entries_categories_and_ratings = {
-        let all_categories: Vec<_> = db.all_categories()?;
+        let all_categories = Categories::from(db.all_categories()?);
         usecases::search(&search_engine, req, limit)?
             .0
             .into_iter()
@@ -557,20 +1148,13 @@ fn entries_csv_export(
                     ref ratings,
                     ..
                 } = indexed_entry;
-                if let Ok((mut place, _)) = db.get_place(id) {
-                    let (tags, categories) = Category::split_from_tags(place.tags);
-                    place.tags = tags;
-                    let categories = all_categories
-                        .iter()
-                        .filter(|c1| categories.iter().any(|c2| c1.id == c2.id))
-                        .cloned()
-                        .collect::<Vec<Category>>();
+                if let Ok((place, _)) = db.get_place(&id.as_str().into()) {
                     let place = usecases::export_place(
                         place,
-                        user.role,
+                        &permissions,
                         owned_tags.iter().map(String::as_str),
                     );
-                    Some((place, categories, ratings.total()))
+                    Some(place_export(place, &all_categories, ratings.total()))
                 } else {
                     None
                 }
@@ -580,29 +1164,19 @@ fn entries_csv_export(
     // Release the database connection asap
     drop(db);
 
-    let records: Vec<_> = entries_categories_and_ratings
-        .into_iter()
-        .map(adapters::csv::CsvRecord::from)
-        .collect();
-
-    let buf: Vec<u8> = vec![];
-    let mut wtr = csv::Writer::from_writer(buf);
-
-    for r in records {
-        wtr.serialize(r)?;
-    }
-    wtr.flush()?;
-    let data = String::from_utf8(wtr.into_inner()?)?;
-
-    Ok(Content(ContentType::CSV, data))
+    let data = format.export_places(
+        entries_categories_and_ratings,
+        cfg.dataset_license.as_deref(),
+    )?;
+    export_content(format, data)
 }
 
 impl<'r> Responder<'r> for AppError {
-    fn respond_to(self, _: &rocket::Request) -> result::Result<Response<'r>, Status> {
+    fn respond_to(self, req: &rocket::Request) -> result::Result<Response<'r>, Status> {
         if let AppError::Business(ref err) = self {
             match *err {
                 Error::Parameter(ref err) => {
-                    return Err(match *err {
+                    let status = match *err {
                         ParameterError::Credentials | ParameterError::Unauthorized => {
                             Status::Unauthorized
                         }
@@ -610,15 +1184,53 @@ impl<'r> Responder<'r> for AppError {
                         ParameterError::EmailNotConfirmed => {
                             <Status>::new(403, "EmailNotConfirmed")
                         }
+                        ParameterError::LoginRequired => <Status>::new(401, "LoginRequired"),
+                        ParameterError::UserDeactivated => <Status>::new(403, "UserDeactivated"),
+                        ParameterError::TooManyRequests => <Status>::new(429, "TooManyRequests"),
+                        ParameterError::DuplicatePlaceSource => {
+                            <Status>::new(409, "DuplicatePlaceSource")
+                        }
+                        ParameterError::DuplicateExternalId => {
+                            <Status>::new(409, "DuplicateExternalId")
+                        }
+                        ParameterError::TagExists => <Status>::new(409, "TagExists"),
+                        ParameterError::AlreadyRegistered => {
+                            <Status>::new(409, "AlreadyRegistered")
+                        }
+                        ParameterError::SubscriptionQuotaExceeded => {
+                            <Status>::new(403, "SubscriptionQuotaExceeded")
+                        }
+                        ParameterError::GracePeriodExpired => {
+                            <Status>::new(403, "GracePeriodExpired")
+                        }
                         ParameterError::Forbidden | ParameterError::OwnedTag => Status::Forbidden,
                         _ => Status::BadRequest,
-                    });
-                }
-                Error::Repo(ref err) => {
-                    if let RepoError::NotFound = *err {
-                        return Err(Status::NotFound);
+                    };
+                    if Status::from_code(status.code) == Some(status) {
+                        return Err(status);
                     }
+                    // The catchers would replace the reason that tells
+                    // clients what went wrong by the default reason
+                    return Response::build().status(status).ok();
                 }
+                Error::Repo(ref err) => match *err {
+                    RepoError::NotFound => return Err(Status::NotFound),
+                    RepoError::Archived => {
+                        return Response::build()
+                            .status(<Status>::new(410, "Archived"))
+                            .ok();
+                    }
+                    RepoError::MergedInto(ref primary_id) => {
+                        // Clients could follow the primary entry
+                        let mut response = Json(json::MergedEntry {
+                            merged_into: primary_id.to_string(),
+                        })
+                        .respond_to(req)?;
+                        response.set_status(<Status>::new(410, "Archived"));
+                        return Ok(response);
+                    }
+                    _ => {}
+                },
                 _ => {}
             }
         }
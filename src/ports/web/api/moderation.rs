@@ -0,0 +1,383 @@
+use super::*;
+
+#[post(
+    "/moderation/assignments",
+    format = "application/json",
+    data = "<data>"
+)]
+pub fn post_moderation_assignment(
+    connections: sqlite::Connections,
+    login: Login,
+    data: Json<json::NewModerationAssignment>,
+) -> Result<json::ModerationAssignment> {
+    let json::NewModerationAssignment {
+        item_kind,
+        item_id,
+        assignee,
+    } = data.into_inner();
+    let assignment = usecases::assign_moderation_item(
+        &*connections.exclusive()?,
+        &login.0,
+        item_kind.into(),
+        &item_id,
+        &assignee,
+    )?;
+    Ok(Json(assignment.into()))
+}
+
+#[post(
+    "/moderation/assignments/state",
+    format = "application/json",
+    data = "<data>"
+)]
+pub fn post_moderation_assignment_state(
+    connections: sqlite::Connections,
+    login: Login,
+    data: Json<json::ModerationAssignmentStateChange>,
+) -> StatusResult {
+    let json::ModerationAssignmentStateChange {
+        item_kind,
+        item_id,
+        state,
+    } = data.into_inner();
+    usecases::change_moderation_assignment_state(
+        &*connections.exclusive()?,
+        &login.0,
+        item_kind.into(),
+        &item_id,
+        state.into(),
+    )?;
+    Ok(Status::NoContent)
+}
+
+#[get("/moderation/assigned-to-me")]
+pub fn get_moderation_assigned_to_me(
+    connections: sqlite::Connections,
+    login: Login,
+) -> Result<Vec<json::ModerationAssignment>> {
+    let assignments =
+        usecases::load_open_moderation_assignments(&*connections.shared()?, &login.0)?;
+    Ok(Json(assignments.into_iter().map(Into::into).collect()))
+}
+
+// Limit the number of comments per request
+const MODERATION_COMMENTS_MAX_COUNT: u64 = 500;
+
+#[get("/moderation/comments?<flagged>&<since>&<offset>&<limit>")]
+pub fn get_moderation_comments(
+    connections: sqlite::Connections,
+    login: Login,
+    flagged: Option<bool>,
+    since: Option<i64>, // in seconds
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<json::ModeratedComment>> {
+    let params = CommentModerationParams {
+        flagged: flagged.unwrap_or(false),
+        since: since.map(TimestampMs::from_seconds),
+    };
+    let pagination = Pagination {
+        offset,
+        limit: Some(
+            limit
+                .unwrap_or(MODERATION_COMMENTS_MAX_COUNT)
+                .min(MODERATION_COMMENTS_MAX_COUNT),
+        ),
+    };
+    let comments = usecases::load_comments_for_moderation(
+        &*connections.shared()?,
+        &login.0,
+        &params,
+        &pagination,
+    )?;
+    Ok(Json(comments.into_iter().map(Into::into).collect()))
+}
+
+// Limit the number of places per request
+const MODERATION_PLACES_MAX_COUNT: u64 = 500;
+
+#[get("/moderation/places?<offset>&<limit>")]
+pub fn get_moderation_places(
+    connections: sqlite::Connections,
+    login: Login,
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<json::Entry>> {
+    let pagination = Pagination {
+        offset,
+        limit: Some(
+            limit
+                .unwrap_or(MODERATION_PLACES_MAX_COUNT)
+                .min(MODERATION_PLACES_MAX_COUNT),
+        ),
+    };
+    let db = connections.shared()?;
+    let places = usecases::load_places_pending_review(&*db, &login.0, &pagination)?;
+    let categories = Categories::from(db.all_categories()?);
+    Ok(Json(
+        places
+            .into_iter()
+            .map(|place| json::entry_from_place_with_ratings(place, vec![], &categories))
+            .collect(),
+    ))
+}
+
+#[post("/comments/<id>/flag")]
+pub fn post_comment_flag(
+    connections: sqlite::Connections,
+    login: Login,
+    id: String,
+) -> StatusResult {
+    usecases::flag_comment(&*connections.exclusive()?, &login.0, &id.into())?;
+    Ok(Status::NoContent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::web::{api::tests::prelude::*, tests::register_user};
+
+    fn register_scout(db: &sqlite::Connections, email: &str) {
+        register_user(db, email, "secret", true);
+        let mut scout = db.shared().unwrap().get_user_by_email(email).unwrap();
+        scout.role = Role::Scout;
+        db.exclusive().unwrap().update_user(&scout).unwrap();
+    }
+
+    fn login(client: &Client, email: &str) {
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(format!(r#"{{"email":"{}","password":"secret"}}"#, email))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+    }
+
+    fn assigned_to_me(client: &Client) -> Vec<json::ModerationAssignment> {
+        let mut res = client.get("/moderation/assigned-to-me").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        serde_json::from_str(&body).unwrap()
+    }
+
+    #[test]
+    fn assign_reports_to_scouts() {
+        let (client, db) = setup();
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(Place::build().id("foo").finish())
+            .unwrap();
+        let report = usecases::report_content(
+            &*db.exclusive().unwrap(),
+            usecases::NewReport {
+                content_kind: ContentKind::Place,
+                content_id: "foo".into(),
+                reason: ReportReason::Fraud,
+                comment: None,
+                reporter_email: None,
+            },
+        )
+        .unwrap()
+        .report;
+        register_scout(&db, "lead@example.com");
+        register_scout(&db, "scout@example.com");
+        register_user(&db, "user@example.com", "secret", true);
+
+        let assign = |assignee: &str| {
+            client
+                .post("/moderation/assignments")
+                .header(ContentType::JSON)
+                .body(format!(
+                    r#"{{"item_kind":"report","item_id":"{}","assignee":"{}"}}"#,
+                    report.id, assignee
+                ))
+                .dispatch()
+                .status()
+        };
+        let change_state = |state: &str| {
+            client
+                .post("/moderation/assignments/state")
+                .header(ContentType::JSON)
+                .body(format!(
+                    r#"{{"item_kind":"report","item_id":"{}","state":"{}"}}"#,
+                    report.id, state
+                ))
+                .dispatch()
+                .status()
+        };
+
+        // Not logged in
+        assert_eq!(Status::Unauthorized, assign("scout@example.com"));
+
+        login(&client, "lead@example.com");
+        assert_eq!(Status::BadRequest, assign("user@example.com"));
+        assert_eq!(Status::Ok, assign("scout@example.com"));
+        assert!(assigned_to_me(&client).is_empty());
+        // Only the assignee is allowed to change the state
+        assert_eq!(Status::NotFound, change_state("in_progress"));
+
+        login(&client, "scout@example.com");
+        let assignments = assigned_to_me(&client);
+        assert_eq!(1, assignments.len());
+        assert_eq!(json::ModerationItemKind::Report, assignments[0].item_kind);
+        assert_eq!(report.id.as_str(), assignments[0].item_id);
+        assert_eq!(json::AssignmentState::Open, assignments[0].state);
+        assert_eq!(
+            Some("lead@example.com"),
+            assignments[0].assigned.by.as_deref()
+        );
+
+        assert_eq!(Status::NoContent, change_state("in_progress"));
+        assert_eq!(
+            json::AssignmentState::InProgress,
+            assigned_to_me(&client)[0].state
+        );
+
+        // Resolving the report also resolves the assignment
+        let res = client
+            .post(format!("/reports/{}/resolve", report.id))
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        assert!(assigned_to_me(&client).is_empty());
+    }
+
+    fn create_rating(
+        connections: &sqlite::Connections,
+        search_engine: &mut tantivy::SearchEngine,
+        comment: &str,
+        context: json::RatingContext,
+    ) -> String {
+        let (_, comment_id) = flows::create_rating(
+            connections,
+            search_engine,
+            usecases::NewPlaceRating {
+                context,
+                value: json::RatingValue::from(1),
+                title: "title".into(),
+                user: None,
+                entry: "foo".into(),
+                comment: comment.into(),
+                source: None,
+            },
+            None,
+            false,
+        )
+        .unwrap();
+        comment_id
+    }
+
+    fn moderation_comments(client: &Client, query: &str) -> Vec<json::ModeratedComment> {
+        let mut res = client
+            .get(format!("/moderation/comments{}", query))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        serde_json::from_str(&body).unwrap()
+    }
+
+    #[test]
+    fn list_flagged_comments_for_scouts() {
+        let (client, db, mut search_engine, _) = setup2();
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(Place::build().id("foo").title("Foo").finish())
+            .unwrap();
+        let first_comment_id = create_rating(
+            &db,
+            &mut search_engine,
+            "first",
+            json::RatingContext::Fairness,
+        );
+        let second_comment_id = create_rating(
+            &db,
+            &mut search_engine,
+            "second",
+            json::RatingContext::Humanity,
+        );
+        register_scout(&db, "scout@example.com");
+        register_user(&db, "user@example.com", "secret", true);
+
+        let flag = |id: &str| {
+            client
+                .post(format!("/comments/{}/flag", id))
+                .dispatch()
+                .status()
+        };
+
+        // Not logged in
+        assert_eq!(Status::Unauthorized, flag(&second_comment_id));
+
+        login(&client, "user@example.com");
+        assert_eq!(Status::NoContent, flag(&second_comment_id));
+        // Repeated flags of the same user are ignored
+        assert_eq!(Status::NoContent, flag(&second_comment_id));
+        assert_eq!(Status::NotFound, flag("unknown"));
+        // Only scouts are entitled to moderate comments
+        let res = client.get("/moderation/comments").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        login(&client, "scout@example.com");
+        assert_eq!(Status::NoContent, flag(&second_comment_id));
+        let comments = moderation_comments(&client, "");
+        assert_eq!(2, comments.len());
+        assert_eq!(second_comment_id, comments[0].id);
+        assert_eq!(2, comments[0].flag_count);
+        assert_eq!("second", comments[0].text);
+        assert_eq!("foo", comments[0].place_id);
+        assert_eq!("Foo", comments[0].place_title);
+        assert_eq!(first_comment_id, comments[1].id);
+        assert_eq!(0, comments[1].flag_count);
+
+        let comments = moderation_comments(&client, "?flagged=true");
+        assert_eq!(1, comments.len());
+        assert_eq!(second_comment_id, comments[0].id);
+
+        let since = TimestampMs::now().into_seconds() + 1;
+        assert!(moderation_comments(&client, &format!("?since={}", since)).is_empty());
+    }
+
+    fn moderation_places(client: &Client) -> Vec<json::Entry> {
+        let mut res = client.get("/moderation/places").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        serde_json::from_str(&body).unwrap()
+    }
+
+    #[test]
+    fn list_places_pending_review_starting_with_the_oldest() {
+        let (client, db) = setup();
+        for id in &["old", "new", "confirmed"] {
+            db.exclusive()
+                .unwrap()
+                .create_or_update_place(Place::build().id(id).finish())
+                .unwrap();
+        }
+        db.exclusive()
+            .unwrap()
+            .review_places(
+                &["confirmed"],
+                ReviewStatus::Confirmed,
+                &ActivityLog {
+                    activity: Activity::now(None),
+                    context: None,
+                    comment: None,
+                },
+            )
+            .unwrap();
+        register_scout(&db, "scout@example.com");
+        register_user(&db, "user@example.com", "secret", true);
+
+        let res = client.get("/moderation/places").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+        login(&client, "user@example.com");
+        let res = client.get("/moderation/places").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        login(&client, "scout@example.com");
+        let ids: Vec<_> = moderation_places(&client)
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(vec!["old", "new"], ids);
+    }
+}
@@ -0,0 +1,450 @@
+use super::*;
+
+fn new_organization(data: Json<json::NewOrganization>) -> usecases::NewOrganization {
+    let json::NewOrganization { name, owned_tags } = data.into_inner();
+    usecases::NewOrganization { name, owned_tags }
+}
+
+fn with_api_token(org: Organization, api_token: OrganizationApiToken) -> json::Organization {
+    json::Organization {
+        api_token: Some(api_token.token),
+        ..org.into()
+    }
+}
+
+#[get("/organizations")]
+pub fn get_organizations(
+    connections: sqlite::Connections,
+    login: Login,
+) -> Result<Vec<json::Organization>> {
+    let orgs = usecases::load_organizations(&*connections.shared()?, &login.0)?;
+    Ok(Json(orgs.into_iter().map(Into::into).collect()))
+}
+
+#[get("/organizations/<id>")]
+pub fn get_organization(
+    connections: sqlite::Connections,
+    login: Login,
+    id: String,
+) -> Result<json::Organization> {
+    let org = usecases::load_organization(&*connections.shared()?, &login.0, &id)?;
+    Ok(Json(org.into()))
+}
+
+#[post("/organizations", format = "application/json", data = "<data>")]
+pub fn post_organization(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    login: Login,
+    data: Json<json::NewOrganization>,
+) -> Result<json::Organization> {
+    let (org, api_token) = flows::create_organization(
+        &connections,
+        &mut search_engine,
+        &login.0,
+        new_organization(data),
+    )?;
+    Ok(Json(with_api_token(org, api_token)))
+}
+
+#[put("/organizations/<id>", format = "application/json", data = "<data>")]
+pub fn put_organization(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    login: Login,
+    id: String,
+    data: Json<json::NewOrganization>,
+) -> Result<json::Organization> {
+    let org = flows::update_organization(
+        &connections,
+        &mut search_engine,
+        &login.0,
+        &id,
+        new_organization(data),
+    )?;
+    Ok(Json(org.into()))
+}
+
+#[delete("/organizations/<id>")]
+pub fn delete_organization(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    login: Login,
+    id: String,
+) -> StatusResult {
+    flows::delete_organization(&connections, &mut search_engine, &login.0, &id)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/organizations/<id>/api-token")]
+pub fn post_organization_api_token(
+    connections: sqlite::Connections,
+    login: Login,
+    id: String,
+) -> Result<json::Organization> {
+    let (org, api_token) = flows::regenerate_organization_api_token(&connections, &login.0, &id)?;
+    Ok(Json(with_api_token(org, api_token)))
+}
+
+#[get("/organizations/<id>/api-tokens")]
+pub fn get_organization_api_tokens(
+    connections: sqlite::Connections,
+    login: Login,
+    id: String,
+) -> Result<Vec<json::OrganizationApiToken>> {
+    let tokens = usecases::load_organization_api_tokens(&*connections.shared()?, &login.0, &id)?;
+    Ok(Json(tokens.into_iter().map(Into::into).collect()))
+}
+
+#[post(
+    "/organizations/<id>/api-tokens",
+    format = "application/json",
+    data = "<data>"
+)]
+pub fn post_organization_api_tokens(
+    connections: sqlite::Connections,
+    login: Login,
+    id: String,
+    data: Json<json::NewOrganizationApiToken>,
+) -> Result<json::OrganizationApiToken> {
+    let json::NewOrganizationApiToken { scopes, expires_at } = data.into_inner();
+    let new_token = usecases::NewOrganizationApiToken {
+        scopes: scopes.into_iter().map(Into::into).collect(),
+        expires_at: expires_at.map(TimestampMs::from_seconds),
+    };
+    let token = usecases::create_organization_api_token(
+        &*connections.exclusive()?,
+        &login.0,
+        &id,
+        new_token,
+    )?;
+    let api_token = token.token.clone();
+    Ok(Json(json::OrganizationApiToken {
+        token: Some(api_token),
+        ..token.into()
+    }))
+}
+
+#[delete("/organizations/<id>/api-tokens/<token_id>")]
+pub fn delete_organization_api_token(
+    connections: sqlite::Connections,
+    login: Login,
+    id: String,
+    token_id: String,
+) -> StatusResult {
+    usecases::delete_organization_api_token(&*connections.exclusive()?, &login.0, &id, &token_id)?;
+    Ok(Status::NoContent)
+}
+
+#[get("/organizations/self/places/clearance")]
+pub fn get_pending_place_clearances(
+    connections: sqlite::Connections,
+    token: ScopedBearer<scopes::ClearPlaces>,
+) -> Result<Vec<json::PendingClearanceForPlace>> {
+    let db = connections.shared()?;
+    let pending = usecases::load_pending_clearances_for_places(&*db, &token.org)?;
+    Ok(Json(pending.into_iter().map(Into::into).collect()))
+}
+
+#[post(
+    "/organizations/self/places/clearance",
+    format = "application/json",
+    data = "<data>"
+)]
+pub fn post_place_clearances(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    token: ScopedBearer<scopes::ClearPlaces>,
+    data: Json<Vec<json::ClearanceForPlace>>,
+) -> StatusResult {
+    let clearances: Vec<_> = data.into_inner().into_iter().map(Into::into).collect();
+    flows::clear_places_of_organization(&connections, &mut search_engine, &token.org, &clearances)?;
+    Ok(Status::NoContent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::web::{api::tests::prelude::*, tests::register_user};
+    use rocket::http::Header;
+
+    #[test]
+    fn manage_organizations() {
+        let (client, db) = setup();
+        register_user(&db, "admin@example.com", "secret", true);
+        let mut admin = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("admin@example.com")
+            .unwrap();
+        admin.role = Role::Admin;
+        db.exclusive().unwrap().update_user(&admin).unwrap();
+
+        let new_org = r#"{"name":"Org","owned_tags":["foo"]}"#;
+
+        // Not logged in
+        let res = client
+            .post("/organizations")
+            .header(ContentType::JSON)
+            .body(new_org)
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let mut res = client
+            .post("/organizations")
+            .header(ContentType::JSON)
+            .body(new_org)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let org: json::Organization = serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!("Org", org.name);
+        assert_eq!(vec!["foo"], org.owned_tags);
+        let old_token = org.api_token.unwrap();
+        assert_eq!(
+            org.id,
+            db.shared()
+                .unwrap()
+                .get_org_api_token(&old_token)
+                .unwrap()
+                .org_id
+        );
+
+        let mut res = client
+            .put(format!("/organizations/{}", org.id))
+            .header(ContentType::JSON)
+            .body(r#"{"name":"Renamed","owned_tags":["bar"]}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let updated: json::Organization =
+            serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!("Renamed", updated.name);
+        assert_eq!(vec!["bar"], updated.owned_tags);
+        assert!(updated.api_token.is_none());
+
+        let mut res = client.get("/organizations").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let orgs: Vec<json::Organization> =
+            serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!(1, orgs.len());
+        assert_eq!("Renamed", orgs[0].name);
+
+        // The old token is invalidated
+        let mut res = client
+            .post(format!("/organizations/{}/api-token", org.id))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let regenerated: json::Organization =
+            serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        let new_token = regenerated.api_token.unwrap();
+        assert_ne!(old_token, new_token);
+        assert!(db.shared().unwrap().get_org_api_token(&old_token).is_err());
+        assert!(db.shared().unwrap().get_org_api_token(&new_token).is_ok());
+
+        let res = client
+            .delete(format!("/organizations/{}", org.id))
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let res = client.get(format!("/organizations/{}", org.id)).dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn clear_edits_of_places_with_owned_tags() {
+        let (client, db) = setup();
+        let mut res = client
+            .post("/entries")
+            .header(ContentType::JSON)
+            .body(r#"{"title":"x","description":"y","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":["foo"]}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let place_id: String = serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
+                id: "org".into(),
+                name: "Org".into(),
+                owned_tags: vec!["foo".into()],
+            },
+            "token",
+        );
+
+        // Third parties may keep owned tags when editing places
+        let res = client
+            .put(format!("/entries/{}", place_id))
+            .header(ContentType::JSON)
+            .body(r#"{"version":1,"title":"z","description":"y","lat":0.0,"lng":0.0,"categories":[],"tags":["foo"]}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let res = client
+            .get("/organizations/self/places/clearance")
+            .header(Header::new("Authorization", "Bearer invalid"))
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let mut res = client
+            .get("/organizations/self/places/clearance")
+            .header(Header::new("Authorization", "Bearer token"))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let pending: Vec<json::PendingClearanceForPlace> =
+            serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!(1, pending.len());
+        assert_eq!(place_id, pending[0].place_id);
+        assert_eq!(Some(0), pending[0].last_cleared_revision);
+
+        // Accept the edit
+        let res = client
+            .post("/organizations/self/places/clearance")
+            .header(ContentType::JSON)
+            .header(Header::new("Authorization", "Bearer token"))
+            .body(format!(
+                r#"[{{"place_id":"{}","cleared_revision":1}}]"#,
+                place_id
+            ))
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let mut res = client
+            .get("/organizations/self/places/clearance")
+            .header(Header::new("Authorization", "Bearer token"))
+            .dispatch();
+        assert_eq!("[]", res.body_string().unwrap());
+        let (place, _) = db
+            .shared()
+            .unwrap()
+            .get_place(&place_id.as_str().into())
+            .unwrap();
+        assert_eq!("z", place.title);
+    }
+
+    #[test]
+    fn scoped_api_tokens() {
+        let (client, db) = setup();
+        register_user(&db, "admin@example.com", "secret", true);
+        let mut admin = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("admin@example.com")
+            .unwrap();
+        admin.role = Role::Admin;
+        db.exclusive().unwrap().update_user(&admin).unwrap();
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
+                id: "org".into(),
+                name: "Org".into(),
+                owned_tags: vec![],
+            },
+            "token",
+        );
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let res = client
+            .post("/organizations/org/api-tokens")
+            .header(ContentType::JSON)
+            .body(r#"{"scopes":[]}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::BadRequest);
+
+        let mut res = client
+            .post("/organizations/org/api-tokens")
+            .header(ContentType::JSON)
+            .body(r#"{"scopes":["read"]}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let read_only: json::OrganizationApiToken =
+            serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!(vec![json::ApiTokenScope::Read], read_only.scopes);
+        let read_only_token = read_only.token.unwrap();
+
+        let mut res = client.get("/organizations/org/api-tokens").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let tokens: Vec<json::OrganizationApiToken> =
+            serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!(2, tokens.len());
+        assert_eq!(read_only.id, tokens[1].id);
+        assert!(tokens.iter().all(|token| token.token.is_none()));
+
+        // The scopes are enforced per route
+        let res = client
+            .get("/events")
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", read_only_token),
+            ))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client
+            .get("/organizations/self/places/clearance")
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", read_only_token),
+            ))
+            .dispatch();
+        assert_eq!(res.status(), Status::Forbidden);
+        let res = client
+            .get("/organizations/self/places/clearance")
+            .header(Header::new("Authorization", "Bearer token"))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client
+            .post("/events")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", read_only_token),
+            ))
+            .body(r#"{"title":"x","start":4132508400,"created_by":"foo@bar.com"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Forbidden);
+        assert_eq!(0, db.shared().unwrap().count_events().unwrap());
+
+        // Routes without a scope only accept personal tokens of users
+        let res = client.post("/logout").header(ContentType::JSON).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client
+            .get("/users/current")
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", read_only_token),
+            ))
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+        let res = client
+            .get("/users/current")
+            .header(Header::new("Authorization", "Bearer token"))
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let res = client
+            .delete(format!("/organizations/org/api-tokens/{}", read_only.id))
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let res = client
+            .get("/events")
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", read_only_token),
+            ))
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+    }
+}
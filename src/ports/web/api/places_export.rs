@@ -0,0 +1,127 @@
+use super::*;
+use crate::adapters::export::Csv;
+
+use rocket::response::Stream;
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::{self, Cursor, Read},
+    iter, vec,
+};
+
+/// The number of places that are loaded from the database
+/// and written into the response at once.
+const PLACES_CHUNK_SIZE: usize = 500;
+
+#[get("/export/places.csv?<query..>")]
+pub fn get_export_places_csv(
+    connections: sqlite::Connections,
+    search_engine: tantivy::SearchEngine,
+    login: Option<Login>,
+    cfg: State<Cfg>,
+    query: Form<search::SearchQuery>,
+) -> result::Result<Content<Stream<PlacesCsvReader>>, AppError> {
+    let query = query.into_inner();
+    let (req, limit) = search::parse_search_query(&query)?;
+    let account_email = login.as_ref().map(|login| login.0.as_str());
+    let (permissions, all_categories, limit) = {
+        let db = connections.shared()?;
+        usecases::authorize_search_by_review_status(&*db, account_email, &req.status)?;
+        let permissions = usecases::account_permissions(&*db, account_email)?;
+        let all_categories = Categories::from(db.all_categories()?);
+        // Unlimited unless requested otherwise
+        let limit = match limit {
+            Some(limit) => limit,
+            None => db.count_places()? + 100,
+        };
+        (permissions, all_categories, limit)
+    };
+    let places = usecases::search(&search_engine, req, limit)?.0;
+    let reader = PlacesCsvReader {
+        connections,
+        permissions,
+        all_categories,
+        dataset_license: cfg.dataset_license.clone(),
+        places: places.into_iter(),
+        header_written: false,
+        chunk: Cursor::new(vec![]),
+    };
+    Ok(Content(ContentType::CSV, Stream::from(reader)))
+}
+
+/// Loads and writes the matching places chunk by chunk
+/// while the response is sent.
+///
+/// The response has already been started when loading a
+/// chunk fails. In this case the export is truncated.
+pub struct PlacesCsvReader {
+    connections: sqlite::Connections,
+    permissions: BTreeSet<Permission>,
+    all_categories: Categories,
+    dataset_license: Option<String>,
+    places: vec::IntoIter<IndexedPlace>,
+    header_written: bool,
+    chunk: Cursor<Vec<u8>>,
+}
+
+impl PlacesCsvReader {
+    /// Returns `false` if all places have been written.
+    fn write_next_chunk(&mut self) -> result::Result<bool, AppError> {
+        let indexed_places: Vec<_> = self.places.by_ref().take(PLACES_CHUNK_SIZE).collect();
+        if indexed_places.is_empty() {
+            return Ok(false);
+        }
+        let ids: Vec<_> = indexed_places.iter().map(|p| p.id.as_str()).collect();
+        // The database connection is released between chunks
+        let mut places: HashMap<_, _> = self
+            .connections
+            .shared()?
+            .get_places(&ids)?
+            .into_iter()
+            .map(|(place, _)| (place.id.as_str().to_owned(), place))
+            .collect();
+        let all_categories = &self.all_categories;
+        let permissions = &self.permissions;
+        // Keep the order of the search results
+        let places: Vec<_> = indexed_places
+            .into_iter()
+            .filter_map(|indexed_place| {
+                let place = places.remove(&indexed_place.id)?;
+                let place = usecases::export_place(place, permissions, iter::empty());
+                Some(place_export(
+                    place,
+                    all_categories,
+                    indexed_place.ratings.total(),
+                ))
+            })
+            .collect();
+        if places.is_empty() {
+            return Ok(true);
+        }
+        let data = Csv::export_places_chunk(
+            places,
+            self.dataset_license.as_deref(),
+            !self.header_written,
+        )?;
+        self.header_written = true;
+        self.chunk = Cursor::new(data);
+        Ok(true)
+    }
+}
+
+impl Read for PlacesCsvReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let n = self.chunk.read(buf)?;
+            if n > 0 || buf.is_empty() {
+                return Ok(n);
+            }
+            let more = self.write_next_chunk().map_err(|err| {
+                error!("Failed to export places as CSV: {}", err);
+                io::Error::new(io::ErrorKind::Other, err.to_string())
+            })?;
+            if !more {
+                return Ok(0);
+            }
+        }
+    }
+}
@@ -1,17 +1,112 @@
 use super::*;
 
-use crate::{core::util, infrastructure::flows::prelude as flows};
+use crate::{core::util, infrastructure::flows::prelude as flows, ports::web::Cfg};
+use rocket::State;
 
 #[post("/ratings", format = "application/json", data = "<data>")]
 pub fn post_rating(
     connections: sqlite::Connections,
     mut search_engine: tantivy::SearchEngine,
+    cfg: State<Cfg>,
+    rating_timelines: State<RatingTimelines>,
+    login: Option<Login>,
     data: Json<usecases::NewPlaceRating>,
 ) -> Result<()> {
-    let _ = flows::create_rating(&connections, &mut search_engine, data.into_inner())?;
+    let new_rating = data.into_inner();
+    let place_id = new_rating.entry.clone();
+    flows::create_rating(
+        &connections,
+        &mut search_engine,
+        new_rating,
+        login.as_ref().map(|l| l.0.as_str()),
+        cfg.ratings_require_login,
+    )?;
+    rating_timelines.invalidate(&place_id);
     Ok(Json(()))
 }
 
+/// Rate a single place in multiple contexts at once.
+///
+/// Either all or none of the ratings are stored.
+#[post("/ratings/batch", format = "application/json", data = "<data>")]
+pub fn post_ratings_batch(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    cfg: State<Cfg>,
+    rating_timelines: State<RatingTimelines>,
+    login: Option<Login>,
+    data: Json<Vec<usecases::NewPlaceRating>>,
+) -> Result<Vec<String>> {
+    let new_ratings = data.into_inner();
+    let place_id = new_ratings
+        .first()
+        .map(|r| r.entry.clone())
+        .unwrap_or_default();
+    let ids = flows::create_ratings(
+        &connections,
+        &mut search_engine,
+        new_ratings,
+        login.as_ref().map(|l| l.0.as_str()),
+        cfg.ratings_require_login,
+    )?;
+    rating_timelines.invalidate(&place_id);
+    let rating_ids = ids.into_iter().map(|(rating_id, _)| rating_id).collect();
+    Ok(Json(rating_ids))
+}
+
+/// Change an own rating within the grace period.
+#[put("/ratings/<id>", format = "application/json", data = "<data>")]
+pub fn put_rating(
+    login: Login,
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    rating_timelines: State<RatingTimelines>,
+    id: String,
+    data: Json<json::RatingUpdate>,
+) -> StatusResult {
+    let json::RatingUpdate { title, value } = data.into_inner();
+    let update = usecases::RatingUpdate {
+        title,
+        value: value.into(),
+    };
+    let rating = flows::update_rating(&connections, &mut search_engine, &login.0, &id, update)?;
+    rating_timelines.invalidate(rating.place_id.as_str());
+    Ok(Status::NoContent)
+}
+
+/// Retract an own rating within the grace period.
+#[delete("/ratings/<id>")]
+pub fn delete_rating(
+    login: Login,
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    rating_timelines: State<RatingTimelines>,
+    id: String,
+) -> StatusResult {
+    let rating = flows::retract_rating(&connections, &mut search_engine, &login.0, &id)?;
+    rating_timelines.invalidate(rating.place_id.as_str());
+    Ok(Status::NoContent)
+}
+
+/// The average ratings of a place per month in chronological
+/// order. Months without any ratings are omitted.
+#[get("/entries/<id>/ratings/timeline")]
+pub fn get_entry_ratings_timeline(
+    db: sqlite::Connections,
+    rating_timelines: State<RatingTimelines>,
+    id: String,
+) -> Result<Vec<json::MonthlyRatings>> {
+    let timeline = match rating_timelines.get(&id) {
+        Some(timeline) => timeline,
+        None => {
+            let timeline = usecases::load_rating_timeline(&*db.shared()?, &id.as_str().into())?;
+            rating_timelines.insert(&id, timeline.clone());
+            timeline
+        }
+    };
+    Ok(Json(timeline.into_iter().map(Into::into).collect()))
+}
+
 #[get("/ratings/<ids>")]
 pub fn load_rating(db: sqlite::Connections, ids: String) -> Result<Vec<json::Rating>> {
     // TODO: RESTful API
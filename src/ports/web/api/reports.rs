@@ -0,0 +1,433 @@
+use super::*;
+
+use crate::{infrastructure::CAPTCHA_GW, ports::web::rate_limit::RateLimit};
+use ofdb_core::CaptchaGateway;
+use rocket::State;
+
+fn captcha_gateway() -> Option<&'static dyn CaptchaGateway> {
+    CAPTCHA_GW.as_ref().map(|gw| gw as &dyn CaptchaGateway)
+}
+
+// Anonymous visitors are only allowed to send a limited
+// number of reports that must be confirmed by a captcha
+// if a captcha service is configured
+fn check_anonymous_report(
+    rate_limit: &RateLimit,
+    captcha_gateway: Option<&dyn CaptchaGateway>,
+    login: &Option<Login>,
+    client: &ClientAddr,
+    captcha: Option<&str>,
+) -> result::Result<(), AppError> {
+    if login.is_some() {
+        return Ok(());
+    }
+    if !rate_limit.try_acquire(client.0) {
+        return Err(Error::Parameter(ParameterError::TooManyRequests).into());
+    }
+    if let Some(gw) = captcha_gateway {
+        let captcha = captcha.ok_or(Error::Parameter(ParameterError::InvalidCaptcha))?;
+        if !gw.verify_captcha(captcha, client.0)? {
+            return Err(Error::Parameter(ParameterError::InvalidCaptcha).into());
+        }
+    }
+    Ok(())
+}
+
+#[post("/entries/<id>/report", format = "application/json", data = "<data>")]
+pub fn post_entry_report(
+    connections: sqlite::Connections,
+    notify: Notify,
+    rate_limit: State<RateLimit>,
+    client: ClientAddr,
+    login: Option<Login>,
+    id: String,
+    data: Json<json::NewPlaceReport>,
+) -> Result<String> {
+    let json::NewPlaceReport {
+        reason,
+        comment,
+        captcha,
+    } = data.into_inner();
+    check_anonymous_report(
+        &rate_limit,
+        captcha_gateway(),
+        &login,
+        &client,
+        captcha.as_deref(),
+    )?;
+    let new_report = usecases::NewReport {
+        content_kind: ContentKind::Place,
+        content_id: id,
+        reason: reason.into(),
+        comment,
+        reporter_email: login.map(|Login(email)| email),
+    };
+    let report = flows::report_content(&connections, &*notify, new_report)?;
+    Ok(Json(report.id.into()))
+}
+
+/// Report a place, an event or a comment.
+#[post("/reports", format = "application/json", data = "<data>")]
+pub fn post_report(
+    connections: sqlite::Connections,
+    notify: Notify,
+    rate_limit: State<RateLimit>,
+    client: ClientAddr,
+    login: Option<Login>,
+    data: Json<json::NewContentReport>,
+) -> Result<String> {
+    let json::NewContentReport {
+        content_kind,
+        content_id,
+        reason,
+        comment,
+        captcha,
+    } = data.into_inner();
+    check_anonymous_report(
+        &rate_limit,
+        captcha_gateway(),
+        &login,
+        &client,
+        captcha.as_deref(),
+    )?;
+    let new_report = usecases::NewReport {
+        content_kind: content_kind.into(),
+        content_id,
+        reason: reason.into(),
+        comment,
+        reporter_email: login.map(|Login(email)| email),
+    };
+    let report = flows::report_content(&connections, &*notify, new_report)?;
+    Ok(Json(report.id.into()))
+}
+
+#[get("/reports")]
+pub fn get_reports(login: Login, connections: sqlite::Connections) -> Result<Vec<json::Report>> {
+    let reports = usecases::load_open_reports(&*connections.shared()?, &login.0)?;
+    Ok(Json(reports.into_iter().map(Into::into).collect()))
+}
+
+#[post("/reports/<ids>/resolve")]
+pub fn post_reports_resolve(
+    login: Login,
+    connections: sqlite::Connections,
+    ids: String,
+) -> StatusResult {
+    let ids = util::split_ids(&ids);
+    if ids.is_empty() {
+        return Err(Error::Parameter(ParameterError::EmptyIdList).into());
+    }
+    let count = usecases::resolve_reports(&*connections.exclusive()?, &login.0, &ids)?;
+    if count < ids.len() {
+        log::info!("Resolved only {} of {} report(s)", count, ids.len());
+    }
+    Ok(Status::NoContent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::web::{
+        api::tests::prelude::*,
+        tests::{register_user, setup_with_cfg},
+    };
+    use rocket::http::Header;
+    use std::{
+        io,
+        net::{IpAddr, SocketAddr},
+        time::Duration,
+    };
+
+    #[test]
+    fn report_entry_anonymously() {
+        let (client, db) = setup();
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(Place::build().id("foo").finish())
+            .unwrap();
+
+        let res = client
+            .post("/entries/foo/report")
+            .header(ContentType::JSON)
+            .body(r#"{"reason":"closed","comment":"Moved away"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let reports = db.shared().unwrap().load_open_reports().unwrap();
+        assert_eq!(1, reports.len());
+        assert_eq!(ReportReason::Closed, reports[0].reason);
+        assert_eq!(Some("Moved away"), reports[0].comment.as_deref());
+        assert!(reports[0].created.by.is_none());
+    }
+
+    #[test]
+    fn limit_anonymous_reports() {
+        let (client, db) = setup();
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(Place::build().id("foo").finish())
+            .unwrap();
+        let report = |addr: [u8; 4]| {
+            client
+                .post("/entries/foo/report")
+                .header(ContentType::JSON)
+                .remote(SocketAddr::from((addr, 8000)))
+                .body(r#"{"reason":"closed"}"#)
+                .dispatch()
+                .status()
+        };
+        for _ in 0..10 {
+            assert_eq!(Status::Ok, report([10, 0, 0, 1]));
+        }
+        let status = report([10, 0, 0, 1]);
+        assert_eq!(429, status.code);
+        assert_eq!("TooManyRequests", status.reason);
+        // Other visitors are not affected
+        assert_eq!(Status::Ok, report([10, 0, 0, 2]));
+
+        register_user(&db, "user@example.com", "secret", true);
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .remote(SocketAddr::from(([10, 0, 0, 1], 8000)))
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        // Logged in users are not limited
+        assert_eq!(Status::Ok, report([10, 0, 0, 1]));
+    }
+
+    #[test]
+    fn ignore_real_ip_header_of_untrusted_clients() {
+        let (client, db) = setup();
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(Place::build().id("foo").finish())
+            .unwrap();
+        let report = |real_ip: String| {
+            client
+                .post("/entries/foo/report")
+                .header(ContentType::JSON)
+                .header(Header::new("X-Real-IP", real_ip))
+                .remote(SocketAddr::from(([10, 0, 0, 1], 8000)))
+                .body(r#"{"reason":"closed"}"#)
+                .dispatch()
+                .status()
+        };
+        for i in 0..10 {
+            assert_eq!(Status::Ok, report(format!("192.168.0.{}", i)));
+        }
+        assert_eq!(429, report("192.168.0.100".into()).code);
+    }
+
+    #[test]
+    fn limit_anonymous_reports_behind_trusted_proxy() {
+        let (client, db, _) = setup_with_cfg(
+            vec![("/", routes())],
+            crate::ports::web::Cfg {
+                trusted_proxies: vec![IpAddr::from([10, 0, 0, 1])],
+                ..Default::default()
+            },
+        );
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(Place::build().id("foo").finish())
+            .unwrap();
+        let report = |real_ip: &'static str| {
+            client
+                .post("/entries/foo/report")
+                .header(ContentType::JSON)
+                .header(Header::new("X-Real-IP", real_ip))
+                .remote(SocketAddr::from(([10, 0, 0, 1], 8000)))
+                .body(r#"{"reason":"closed"}"#)
+                .dispatch()
+                .status()
+        };
+        for _ in 0..10 {
+            assert_eq!(Status::Ok, report("192.168.0.1"));
+        }
+        assert_eq!(429, report("192.168.0.1").code);
+        // Other visitors behind the same proxy are not affected
+        assert_eq!(Status::Ok, report("192.168.0.2"));
+    }
+
+    struct DummyCaptcha;
+
+    impl CaptchaGateway for DummyCaptcha {
+        fn verify_captcha(&self, response: &str, _: Option<IpAddr>) -> io::Result<bool> {
+            Ok(response == "solved")
+        }
+    }
+
+    #[test]
+    fn require_captcha_for_anonymous_reports() {
+        let rate_limit = RateLimit::new(10, Duration::from_secs(60));
+        let client = ClientAddr(Some(IpAddr::from([10, 0, 0, 1])));
+        let check = |login: Option<Login>, captcha: Option<&str>| {
+            check_anonymous_report(&rate_limit, Some(&DummyCaptcha), &login, &client, captcha)
+        };
+        assert!(matches!(
+            check(None, None),
+            Err(AppError::Business(Error::Parameter(
+                ParameterError::InvalidCaptcha
+            )))
+        ));
+        assert!(matches!(
+            check(None, Some("wrong")),
+            Err(AppError::Business(Error::Parameter(
+                ParameterError::InvalidCaptcha
+            )))
+        ));
+        assert!(check(None, Some("solved")).is_ok());
+        // Logged in users don't need to solve a captcha
+        assert!(check(Some(Login("user@example.com".into())), None).is_ok());
+        // Without a captcha service only the rate limit applies
+        assert!(check_anonymous_report(&rate_limit, None, &None, &client, None).is_ok());
+    }
+
+    #[test]
+    fn scouts_review_and_resolve_reports() {
+        let (client, db) = setup();
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(Place::build().id("foo").finish())
+            .unwrap();
+        register_user(&db, "user@example.com", "secret", true);
+        register_user(&db, "scout@example.com", "secret", true);
+        let mut scout = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("scout@example.com")
+            .unwrap();
+        scout.role = Role::Scout;
+        db.exclusive().unwrap().update_user(&scout).unwrap();
+
+        // Logged in users are not limited like anonymous visitors
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client
+            .post("/entries/foo/report")
+            .header(ContentType::JSON)
+            .body(r#"{"reason":"fraud"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client
+            .post("/entries/bar/report")
+            .header(ContentType::JSON)
+            .body(r#"{"reason":"fraud"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+
+        // Only scouts are allowed to see the reports
+        let res = client.get("/reports").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"scout@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let mut res = client.get("/reports").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        let reports: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let reports = reports.as_array().unwrap();
+        assert_eq!(1, reports.len());
+        assert_eq!("place", reports[0]["content_kind"]);
+        assert_eq!("foo", reports[0]["content_id"]);
+        assert_eq!("fraud", reports[0]["reason"]);
+        assert_eq!("user@example.com", reports[0]["created"]["by"]);
+
+        let res = client
+            .post(format!(
+                "/reports/{}/resolve",
+                reports[0]["id"].as_str().unwrap()
+            ))
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        assert!(db.shared().unwrap().load_open_reports().unwrap().is_empty());
+    }
+
+    #[test]
+    fn report_content_and_triage_reports() {
+        let (client, db) = setup();
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(Place::build().id("foo").finish())
+            .unwrap();
+        register_user(&db, "user@example.com", "secret", true);
+        register_user(&db, "scout@example.com", "secret", true);
+        let mut scout = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("scout@example.com")
+            .unwrap();
+        scout.role = Role::Scout;
+        db.exclusive().unwrap().update_user(&scout).unwrap();
+
+        let body = r#"{"content_kind":"place","content_id":"foo","reason":"spam"}"#;
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client
+            .post("/reports")
+            .header(ContentType::JSON)
+            .body(body)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client
+            .post("/reports")
+            .header(ContentType::JSON)
+            .body(r#"{"content_kind":"event","content_id":"foo","reason":"offensive"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+
+        let res = client
+            .post("/entries/foo/report")
+            .header(ContentType::JSON)
+            .body(r#"{"reason":"closed"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        // Only scouts and admins are allowed to triage the reports
+        let res = client.get("/reports").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"scout@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let mut res = client.get("/reports").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        let reports: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let reports = reports.as_array().unwrap();
+        // Reports of entries and of other content are triaged together
+        assert_eq!(2, reports.len());
+        assert_eq!("place", reports[0]["content_kind"]);
+        assert_eq!("foo", reports[0]["content_id"]);
+        assert_eq!("spam", reports[0]["reason"]);
+        assert_eq!("user@example.com", reports[0]["created"]["by"]);
+        assert_eq!("closed", reports[1]["reason"]);
+
+        let res = client
+            .post(format!(
+                "/reports/{},{}/resolve",
+                reports[0]["id"].as_str().unwrap(),
+                reports[1]["id"].as_str().unwrap()
+            ))
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        assert!(db.shared().unwrap().load_open_reports().unwrap().is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use super::{export_places_as_geojson, place_export, places_format, PlacesFormat, PlacesResponse};
 use crate::{
     adapters::json,
     core::{
@@ -5,21 +6,41 @@ use crate::{
         usecases,
         util::{self, geo},
     },
-    infrastructure::{db::tantivy, error::AppError},
+    infrastructure::{
+        db::{sqlite, tantivy},
+        error::AppError,
+        flows::prelude as flows,
+    },
+    ports::web::{guards::Login, Cfg},
 };
 
-use rocket::{self, request::Form};
+use rocket::{self, http::Accept, request::Form, State};
 use rocket_contrib::json::Json;
-use std::result;
+use std::{collections::HashMap, result};
 
 #[derive(FromForm, Clone)]
 pub struct SearchQuery {
-    bbox: String,
+    bbox: Option<String>,
+    lat: Option<f64>,
+    lng: Option<f64>,
+    radius: Option<f64>,
     categories: Option<String>,
     ids: Option<String>,
     tags: Option<String>,
+    tags_any: Option<String>,
+    exclude_tags: Option<String>,
+    org_id: Option<String>,
     text: Option<String>,
+    fuzzy: Option<bool>,
     status: Option<String>,
+    since: Option<i64>, // in seconds
+    until: Option<i64>, // in seconds
+    sort_by: Option<String>,
+    boost_quality: Option<bool>,
+    min_rating: Option<f64>,
+    min_ratings: Option<String>,
+    offset: Option<usize>,
+    cursor: Option<String>,
     limit: Option<usize>,
 }
 
@@ -28,19 +49,57 @@ pub fn parse_search_query(
 ) -> result::Result<(usecases::SearchRequest<'_, '_, '_, '_>, Option<usize>), AppError> {
     let SearchQuery {
         bbox,
+        lat,
+        lng,
+        radius,
         ids,
         categories,
         tags,
+        tags_any,
+        exclude_tags,
+        org_id,
         text,
+        fuzzy,
         status,
+        since,
+        until,
+        sort_by,
+        boost_quality,
+        min_rating,
+        min_ratings,
+        offset,
+        cursor,
         limit,
     } = query;
 
-    let bbox = bbox
-        .parse::<geo::MapBbox>()
-        .map_err(|_| ParameterError::Bbox)
-        .map_err(Error::Parameter)
-        .map_err(AppError::Business)?;
+    let circle = match (lat, lng, radius) {
+        (None, None, None) => None,
+        (Some(lat), Some(lng), Some(radius)) => {
+            let center = geo::MapPoint::try_from_lat_lng_deg(*lat, *lng)
+                .ok_or(ParameterError::InvalidPosition)
+                .map_err(Error::Parameter)?;
+            if !radius.is_finite() || *radius <= 0.0 || *radius > MAX_SEARCH_RADIUS_METERS {
+                return Err(Error::Parameter(ParameterError::InvalidRadius).into());
+            }
+            Some((center, geo::Distance::from_meters(*radius)))
+        }
+        // Incomplete circle
+        (None, _, _) | (_, None, _) => {
+            return Err(Error::Parameter(ParameterError::InvalidPosition).into())
+        }
+        (_, _, None) => return Err(Error::Parameter(ParameterError::InvalidRadius).into()),
+    };
+
+    let bbox = match (bbox, circle) {
+        (Some(bbox), _) => bbox
+            .parse::<geo::MapBbox>()
+            .map_err(|_| ParameterError::Bbox)
+            .map_err(Error::Parameter)
+            .map_err(AppError::Business)?,
+        // The bbox is optional when searching within a circle
+        (None, Some((center, radius))) => geo::MapBbox::centered_around(center, radius),
+        (None, None) => return Err(Error::Parameter(ParameterError::Bbox).into()),
+    };
 
     let ids = ids.as_deref().map(util::split_ids).unwrap_or_default();
 
@@ -57,8 +116,26 @@ pub fn parse_search_query(
 
     let hash_tags = tags.as_deref().map(util::split_ids).unwrap_or_default();
 
+    let any_hash_tags = tags_any.as_deref().map(util::split_ids).unwrap_or_default();
+
+    let exclude_hash_tags = exclude_tags
+        .as_deref()
+        .map(util::split_ids)
+        .unwrap_or_default();
+
+    let org_id = org_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|org_id| !org_id.is_empty());
+
     let text = text.as_deref();
 
+    let fuzzy_distance = if fuzzy.unwrap_or(false) {
+        Some(FUZZY_SEARCH_EDIT_DISTANCE)
+    } else {
+        None
+    };
+
     let status = status
         .as_deref()
         .map(util::split_ids)
@@ -75,32 +152,162 @@ pub fn parse_search_query(
         })
         .collect();
 
+    // Conversion from seconds (external) to milliseconds (internal)
+    let updated_since = since.map(TimestampMs::from_seconds);
+    let updated_until = until.map(TimestampMs::from_seconds);
+
+    let sort_by = sort_by
+        .as_deref()
+        .map(parse_sort_order)
+        .transpose()
+        .map_err(Error::Parameter)?
+        .unwrap_or_default();
+
+    let min_total_rating = min_rating
+        .map(parse_min_rating)
+        .transpose()
+        .map_err(Error::Parameter)?;
+
+    let min_ratings = min_ratings
+        .as_deref()
+        .map(util::split_ids)
+        .unwrap_or_default()
+        .into_iter()
+        .map(parse_min_context_rating)
+        .collect::<result::Result<_, _>>()
+        .map_err(Error::Parameter)?;
+
+    // The cursor takes precedence over an explicit offset
+    let (offset, after) = match cursor.as_deref().map(decode_cursor).transpose() {
+        Ok(Some((offset, after))) => (offset, Some(after)),
+        Ok(None) => (offset.unwrap_or_default(), None),
+        Err(err) => return Err(Error::Parameter(err).into()),
+    };
+    // Skipping results requires to load all preceding results
+    if offset > MAX_RESULT_OFFSET {
+        return Err(Error::Parameter(ParameterError::InvalidOffset).into());
+    }
+
     Ok((
         usecases::SearchRequest {
             bbox,
+            center: circle.map(|(center, _)| center),
+            radius: circle.map(|(_, radius)| radius),
             ids,
             categories,
             hash_tags,
+            any_hash_tags,
+            exclude_hash_tags,
+            org_id,
             text,
+            fuzzy_distance,
             status,
+            updated_since,
+            updated_until,
+            sort_by,
+            boost_quality: boost_quality.unwrap_or(false),
+            min_total_rating,
+            min_ratings,
+            offset,
+            after,
         },
         *limit,
     ))
 }
 
+fn parse_sort_order(sort_by: &str) -> result::Result<IndexSortOrder, ParameterError> {
+    match sort_by.trim() {
+        "rating" => Ok(IndexSortOrder::Rating),
+        "relevance" => Ok(IndexSortOrder::Relevance),
+        "distance" => Ok(IndexSortOrder::Distance),
+        "recently_changed" => Ok(IndexSortOrder::RecentlyChanged),
+        "recently_created" => Ok(IndexSortOrder::RecentlyCreated),
+        "title" => Ok(IndexSortOrder::Title),
+        "ratings_count" => Ok(IndexSortOrder::RatingsCount),
+        _ => Err(ParameterError::InvalidSortOrder),
+    }
+}
+
+fn parse_min_rating(min_rating: f64) -> result::Result<AvgRatingValue, ParameterError> {
+    let min_rating = AvgRatingValue::from(min_rating);
+    if !min_rating.is_valid() {
+        return Err(ParameterError::RatingValue);
+    }
+    Ok(min_rating)
+}
+
+// A minimum rating for a single context, e.g. "fairness:1.5"
+fn parse_min_context_rating(
+    min_rating: &str,
+) -> result::Result<(RatingContext, AvgRatingValue), ParameterError> {
+    let mut parts = min_rating.splitn(2, ':');
+    let context = parts.next().unwrap_or_default().trim();
+    let context = serde_json::from_str::<json::RatingContext>(&format!("\"{}\"", context))
+        .map(RatingContext::from)
+        .map_err(|_| ParameterError::RatingContext(context.to_owned()))?;
+    let min_rating = parts
+        .next()
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .ok_or(ParameterError::RatingValue)
+        .and_then(parse_min_rating)?;
+    Ok((context, min_rating))
+}
+
+// The continuation token consists of the number of preceding results
+// and the score and id of the last result
+fn encode_cursor(offset: usize, last: &IndexCursor) -> String {
+    format!("{}-{:016x}-{}", offset, last.score.to_bits(), last.id)
+}
+
+fn decode_cursor(token: &str) -> result::Result<(usize, IndexCursor), ParameterError> {
+    let mut parts = token.trim().splitn(3, '-');
+    let offset = parts.next().and_then(|offset| offset.parse().ok());
+    let score = parts
+        .next()
+        .and_then(|bits| u64::from_str_radix(bits, 16).ok())
+        .map(f64::from_bits);
+    let id = parts.next().filter(|id| !id.is_empty());
+    match (offset, score, id) {
+        (Some(offset), Some(score), Some(id)) => Ok((
+            offset,
+            IndexCursor {
+                score,
+                id: id.to_owned(),
+            },
+        )),
+        _ => Err(ParameterError::InvalidCursor),
+    }
+}
+
 type Result<T> = result::Result<Json<T>, AppError>;
 
+const MAX_SEARCH_RADIUS_METERS: f64 = 500_000.0;
+
+const FUZZY_SEARCH_EDIT_DISTANCE: u8 = 1;
+
 const DEFAULT_RESULT_LIMIT: usize = 100;
 const MAX_RESULT_LIMIT: usize = 500;
+const MAX_RESULT_OFFSET: usize = 10_000;
 
-#[get("/search?<query..>")]
+#[get("/search?<format>&<query..>")]
 #[allow(clippy::absurd_extreme_comparisons)]
 pub fn get_search(
+    connections: sqlite::Connections,
     search_engine: tantivy::SearchEngine,
+    login: Option<Login>,
+    cfg: State<Cfg>,
+    accept: Option<&Accept>,
+    format: Option<String>,
     query: Form<SearchQuery>,
-) -> Result<json::SearchResponse> {
+) -> result::Result<PlacesResponse<json::SearchResponse>, AppError> {
+    let format = places_format(format.as_deref(), accept)?;
     let query = query.into_inner();
     let (req, limit) = parse_search_query(&query)?;
+    usecases::authorize_search_by_review_status(
+        &*connections.shared()?,
+        login.as_ref().map(|login| login.0.as_str()),
+        &req.status,
+    )?;
 
     let limit = if let Some(limit) = limit {
         if limit > MAX_RESULT_LIMIT {
@@ -125,11 +332,149 @@ pub fn get_search(
         DEFAULT_RESULT_LIMIT
     };
 
+    let offset = req.offset;
     let (visible, invisible) = usecases::search(&search_engine, req, limit)?;
 
-    let visible: Vec<json::PlaceSearchResult> = visible.into_iter().map(Into::into).collect();
+    // A full page might be followed by more visible results
+    let next_cursor = if visible.len() < limit {
+        None
+    } else {
+        visible.last().map(|last| {
+            encode_cursor(
+                offset + visible.len(),
+                &IndexCursor {
+                    score: last.score,
+                    id: last.id.clone(),
+                },
+            )
+        })
+    };
+
+    let categories = Categories::from(connections.shared()?.all_categories()?);
+
+    if format == PlacesFormat::GeoJson {
+        // Only the visible places are rendered on a map
+        let ids: Vec<_> = visible.iter().map(|p| p.id.as_str()).collect();
+        let mut places: HashMap<_, _> = connections
+            .shared()?
+            .get_places(&ids)?
+            .into_iter()
+            .map(|(place, _)| (place.id.to_string(), place))
+            .collect();
+        // Keep the order of the search results
+        let places = visible
+            .into_iter()
+            .filter_map(|p| {
+                let place = places.remove(&p.id)?;
+                Some(place_export(place, &categories, p.ratings.total()))
+            })
+            .collect();
+        return export_places_as_geojson(places, &cfg);
+    }
+
+    let visible: Vec<_> = visible
+        .into_iter()
+        .map(|p| json::place_search_result_from_indexed_place(p, &categories))
+        .collect();
+
+    let invisible: Vec<_> = invisible
+        .into_iter()
+        .map(|p| json::place_search_result_from_indexed_place(p, &categories))
+        .collect();
+
+    Ok(PlacesResponse::Json(json::SearchResponse {
+        visible,
+        invisible,
+        next_cursor,
+    }))
+}
+
+const DEFAULT_GLOBAL_RESULT_LIMIT: usize = 10;
 
-    let invisible: Vec<json::PlaceSearchResult> = invisible.into_iter().map(Into::into).collect();
+const DEFAULT_SUGGESTION_LIMIT: usize = 10;
 
-    Ok(Json(json::SearchResponse { visible, invisible }))
+fn limit_or_default(limit: Option<usize>, default_limit: usize) -> result::Result<usize, AppError> {
+    match limit {
+        Some(limit) if limit == 0 => Err(Error::Parameter(ParameterError::InvalidLimit).into()),
+        Some(limit) => Ok(limit.min(MAX_RESULT_LIMIT)),
+        None => Ok(default_limit),
+    }
+}
+
+#[get("/search/global?<q>&<limit>")]
+pub fn get_global_search(
+    connections: sqlite::Connections,
+    search_engine: tantivy::SearchEngine,
+    q: String,
+    limit: Option<usize>,
+) -> Result<Vec<json::GlobalSearchResults>> {
+    let limit = limit_or_default(limit, DEFAULT_GLOBAL_RESULT_LIMIT)?;
+    let flows::GlobalSearchResults {
+        places,
+        events,
+        tags,
+    } = flows::global_search(&connections, &search_engine, &q, limit)?;
+
+    let categories = Categories::from(connections.shared()?.all_categories()?);
+    let places = places
+        .into_iter()
+        .map(|p| json::place_search_result_from_indexed_place(p, &categories))
+        .collect();
+
+    let owned_tags = vec![];
+    let events = events
+        .into_iter()
+        .map(|e| usecases::filter_event(e, owned_tags.iter().map(String::as_str)))
+        .map(json::Event::from)
+        .collect();
+
+    Ok(Json(vec![
+        json::GlobalSearchResults::Place(places),
+        json::GlobalSearchResults::Event(events),
+        json::GlobalSearchResults::Tag(tags),
+    ]))
+}
+
+#[get("/search/suggest?<q>&<limit>")]
+pub fn get_search_suggestions(
+    search_engine: tantivy::SearchEngine,
+    q: String,
+    limit: Option<usize>,
+) -> Result<json::SearchSuggestions> {
+    let limit = limit_or_default(limit, DEFAULT_SUGGESTION_LIMIT)?;
+    let usecases::Suggestions { tags, titles } = usecases::suggest(&search_engine, &q, limit)?;
+    Ok(Json(json::SearchSuggestions { tags, titles }))
+}
+
+#[get("/entries/suggest-tags?<text>&<limit>")]
+pub fn get_entries_suggest_tags(
+    connections: sqlite::Connections,
+    cfg: State<Cfg>,
+    text: String,
+    limit: Option<usize>,
+) -> Result<Vec<String>> {
+    let limit = limit_or_default(limit, DEFAULT_SUGGESTION_LIMIT)?;
+    let tags =
+        usecases::suggest_tags_from_text(&*connections.shared()?, &cfg.tag_synonyms, &text, limit)?;
+    Ok(Json(tags))
+}
+
+#[get("/search/clusters?<zoom>&<query..>")]
+pub fn get_search_clusters(
+    connections: sqlite::Connections,
+    search_engine: tantivy::SearchEngine,
+    login: Option<Login>,
+    zoom: u8,
+    query: Form<SearchQuery>,
+) -> Result<Vec<json::PlaceCluster>> {
+    let query = query.into_inner();
+    // The limit is irrelevant, all matching places are clustered
+    let (req, _) = parse_search_query(&query)?;
+    usecases::authorize_search_by_review_status(
+        &*connections.shared()?,
+        login.as_ref().map(|login| login.0.as_str()),
+        &req.status,
+    )?;
+    let clusters = usecases::search_clusters(&search_engine, req, zoom)?;
+    Ok(Json(clusters.into_iter().map(Into::into).collect()))
 }
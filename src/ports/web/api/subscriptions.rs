@@ -0,0 +1,271 @@
+use super::*;
+use crate::{adapters::csv, ports::web::Cfg};
+use rocket::State;
+
+#[get("/admin/bbox-subscriptions.csv")]
+pub fn get_bbox_subscriptions_csv(
+    connections: sqlite::Connections,
+    login: Login,
+) -> result::Result<Content<String>, AppError> {
+    let subscriptions = usecases::export_bbox_subscriptions(&*connections.shared()?, &login.0)?;
+    let data = csv::write_bbox_subscriptions(subscriptions)?;
+    export_content(&export::Csv, data)
+}
+
+#[post("/admin/bbox-subscriptions", format = "text/csv", data = "<data>")]
+pub fn post_bbox_subscriptions_csv(
+    connections: sqlite::Connections,
+    login: Login,
+    data: String,
+) -> Result<usize> {
+    let subscriptions = csv::read_bbox_subscriptions(&data).map_err(Error::Parameter)?;
+    let count = flows::import_bbox_subscriptions(&connections, &login.0, subscriptions)?;
+    Ok(Json(count))
+}
+
+#[get("/subscriptions/tags")]
+pub fn get_tag_subscriptions(
+    connections: sqlite::Connections,
+    login: Login,
+) -> Result<Vec<json::TagSubscription>> {
+    let subscriptions = usecases::get_tag_subscriptions(&*connections.shared()?, &login.0)?;
+    Ok(Json(subscriptions.into_iter().map(Into::into).collect()))
+}
+
+/// Subscribe to places that contain all of the given tags.
+#[post("/subscriptions/tags", format = "application/json", data = "<data>")]
+pub fn post_tag_subscription(
+    connections: sqlite::Connections,
+    cfg: State<Cfg>,
+    login: Login,
+    data: Json<json::NewTagSubscription>,
+) -> Result<String> {
+    let json::NewTagSubscription { tags, bbox } = data.into_inner();
+    let bbox = match bbox {
+        Some(corners) => {
+            let sw_ne: Vec<_> = corners.into_iter().map(MapPoint::from).collect();
+            if sw_ne.len() != 2 {
+                return Err(Error::Parameter(ParameterError::Bbox).into());
+            }
+            Some(geo::MapBbox::new(sw_ne[0], sw_ne[1]))
+        }
+        None => None,
+    };
+    let subscription = usecases::subscribe_to_tags(
+        &*connections.exclusive()?,
+        &login.0,
+        usecases::NewTagSubscription { tags, bbox },
+        cfg.subscription_quota,
+    )?;
+    Ok(Json(subscription.id.into()))
+}
+
+#[delete("/subscriptions/tags/<id>")]
+pub fn delete_tag_subscription(
+    connections: sqlite::Connections,
+    login: Login,
+    id: String,
+) -> StatusResult {
+    usecases::unsubscribe_from_tags(&*connections.exclusive()?, &login.0, &id)?;
+    Ok(Status::NoContent)
+}
+
+#[get("/saved-searches")]
+pub fn get_saved_searches(
+    connections: sqlite::Connections,
+    login: Login,
+) -> Result<Vec<json::SavedSearch>> {
+    let searches = usecases::get_saved_searches(&*connections.shared()?, &login.0)?;
+    Ok(Json(searches.into_iter().map(Into::into).collect()))
+}
+
+#[post("/saved-searches", format = "application/json", data = "<data>")]
+pub fn post_saved_search(
+    connections: sqlite::Connections,
+    cfg: State<Cfg>,
+    login: Login,
+    data: Json<json::NewSavedSearch>,
+) -> Result<String> {
+    let json::NewSavedSearch { title, query } = data.into_inner();
+    let search = usecases::save_search(
+        &*connections.exclusive()?,
+        &login.0,
+        usecases::NewSavedSearch { title, query },
+        cfg.subscription_quota,
+    )?;
+    Ok(Json(search.id.into()))
+}
+
+#[delete("/saved-searches/<id>")]
+pub fn delete_saved_search(
+    connections: sqlite::Connections,
+    login: Login,
+    id: String,
+) -> StatusResult {
+    usecases::delete_saved_search(&*connections.exclusive()?, &login.0, &id)?;
+    Ok(Status::NoContent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::web::{api::tests::prelude::*, tests::register_user};
+
+    #[test]
+    fn export_and_import_bbox_subscriptions() {
+        let (client, db) = setup();
+        register_user(&db, "admin@example.com", "secret", true);
+        register_user(&db, "user@example.com", "secret", true);
+        let mut admin = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("admin@example.com")
+            .unwrap();
+        admin.role = Role::Admin;
+        db.exclusive().unwrap().update_user(&admin).unwrap();
+        let data = "id,user_email,south_west_lat,south_west_lng,north_east_lat,north_east_lng\n\
+                   a,user@example.com,1.0,2.0,3.0,4.0\n";
+
+        // Not logged in
+        let res = client.get("/admin/bbox-subscriptions.csv").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+        let res = client
+            .post("/admin/bbox-subscriptions")
+            .header(ContentType::CSV)
+            .body(data)
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let res = client
+            .post("/admin/bbox-subscriptions")
+            .header(ContentType::CSV)
+            .body("user_email\nuser@example.com\n")
+            .dispatch();
+        assert_eq!(res.status(), Status::BadRequest);
+        let mut res = client
+            .post("/admin/bbox-subscriptions")
+            .header(ContentType::CSV)
+            .body(data)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!("1", res.body().and_then(|b| b.into_string()).unwrap());
+
+        let mut res = client.get("/admin/bbox-subscriptions.csv").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(
+            Some("text/csv; charset=utf-8"),
+            res.headers().get_one("Content-Type")
+        );
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        let subscriptions = csv::read_bbox_subscriptions(&body).unwrap();
+        assert_eq!(1, subscriptions.len());
+        assert_eq!("a", subscriptions[0].id.as_str());
+        assert_eq!("user@example.com", subscriptions[0].user_email);
+    }
+
+    #[test]
+    fn subscribe_to_tags_and_unsubscribe() {
+        let (client, db) = setup();
+        register_user(&db, "user@example.com", "secret", true);
+        let res = client.get("/subscriptions/tags").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let res = client
+            .post("/subscriptions/tags")
+            .header(ContentType::JSON)
+            .body(r#"{"tags":["Organic","bakery"],"bbox":[{"lat":1.0,"lng":2.0}]}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::BadRequest);
+        let mut res = client
+            .post("/subscriptions/tags")
+            .header(ContentType::JSON)
+            .body(r#"{"tags":["Organic","bakery"],"bbox":[{"lat":1.0,"lng":2.0},{"lat":3.0,"lng":4.0}]}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let id: String =
+            serde_json::from_str(&res.body().and_then(|b| b.into_string()).unwrap()).unwrap();
+
+        let subscriptions = db
+            .shared()
+            .unwrap()
+            .all_tag_subscriptions_by_email("user@example.com")
+            .unwrap();
+        assert_eq!(1, subscriptions.len());
+        assert_eq!(vec!["bakery", "organic"], subscriptions[0].tags);
+        assert!(subscriptions[0].bbox.is_some());
+
+        let mut res = client.get("/subscriptions/tags").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        assert!(body.contains(&id));
+        assert!(body.contains(r#""tags":["bakery","organic"]"#));
+
+        let res = client
+            .delete(format!("/subscriptions/tags/{}", id))
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let res = client
+            .delete(format!("/subscriptions/tags/{}", id))
+            .dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+        assert!(db
+            .shared()
+            .unwrap()
+            .all_tag_subscriptions()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn save_and_delete_searches() {
+        let (client, db) = setup();
+        register_user(&db, "user@example.com", "secret", true);
+        let res = client.get("/saved-searches").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let res = client
+            .post("/saved-searches")
+            .header(ContentType::JSON)
+            .body(r#"{"title":"Organic","query":""}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::BadRequest);
+        let mut res = client
+            .post("/saved-searches")
+            .header(ContentType::JSON)
+            .body(r#"{"title":"Organic","query":"text=organic"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let id: String =
+            serde_json::from_str(&res.body().and_then(|b| b.into_string()).unwrap()).unwrap();
+
+        let mut res = client.get("/saved-searches").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        assert!(body.contains(&id));
+        assert!(body.contains(r#""query":"text=organic""#));
+
+        let res = client.delete(format!("/saved-searches/{}", id)).dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let res = client.delete(format!("/saved-searches/{}", id)).dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+    }
+}
@@ -0,0 +1,163 @@
+use super::*;
+
+#[post("/admin/tags/rename", format = "application/json", data = "<data>")]
+pub fn post_rename_tag(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    login: Login,
+    data: Json<json::RenameTag>,
+) -> StatusResult {
+    let json::RenameTag { tag, new_tag } = data.into_inner();
+    flows::rename_tag(&connections, &mut search_engine, &login.0, &tag, &new_tag)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/admin/tags/merge", format = "application/json", data = "<data>")]
+pub fn post_merge_tags(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    login: Login,
+    data: Json<json::MergeTags>,
+) -> StatusResult {
+    let json::MergeTags { tags, into } = data.into_inner();
+    let tags: Vec<_> = tags.iter().map(String::as_str).collect();
+    flows::merge_tags(&connections, &mut search_engine, &login.0, &tags, &into)?;
+    Ok(Status::NoContent)
+}
+
+#[delete("/tags/<tag>?<dry_run>")]
+pub fn delete_tag(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    login: Login,
+    tag: String,
+    dry_run: Option<bool>,
+) -> Result<json::DeletedTag> {
+    let deleted = flows::delete_tag(
+        &connections,
+        &mut search_engine,
+        &login.0,
+        &tag,
+        dry_run.unwrap_or(false),
+    )?;
+    Ok(Json(json::DeletedTag {
+        places: deleted.place_ids.len(),
+        events: deleted.event_ids.len(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::web::{api::tests::prelude::*, tests::register_user};
+
+    fn create_tag(db: &sqlite::Connections, tag: &str) {
+        db.exclusive()
+            .unwrap()
+            .create_tag_if_it_does_not_exist(&Tag { id: tag.into() })
+            .unwrap();
+    }
+
+    #[test]
+    fn rename_and_merge_tags() {
+        let (client, db) = setup();
+        register_user(&db, "admin@example.com", "secret", true);
+        let mut admin = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("admin@example.com")
+            .unwrap();
+        admin.role = Role::Admin;
+        db.exclusive().unwrap().update_user(&admin).unwrap();
+        for tag in &["vegen", "veggie", "vegan", "bio"] {
+            create_tag(&db, tag);
+        }
+
+        // Not logged in
+        let res = client
+            .post("/admin/tags/rename")
+            .header(ContentType::JSON)
+            .body(r#"{"tag":"vegen","new_tag":"vegan"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let res = client
+            .post("/admin/tags/rename")
+            .header(ContentType::JSON)
+            .body(r#"{"tag":"vegen","new_tag":"vegan"}"#)
+            .dispatch();
+        assert_eq!(res.status().code, 409);
+        let res = client
+            .post("/admin/tags/rename")
+            .header(ContentType::JSON)
+            .body(r#"{"tag":"bio","new_tag":"organic"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let res = client
+            .post("/admin/tags/merge")
+            .header(ContentType::JSON)
+            .body(r#"{"tags":["vegen","veggie"],"into":"vegan"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let res = client
+            .post("/admin/tags/merge")
+            .header(ContentType::JSON)
+            .body(r#"{"tags":["vegen"],"into":"vegan"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+
+        let mut tags: Vec<_> = db
+            .shared()
+            .unwrap()
+            .all_tags()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        tags.sort_unstable();
+        assert_eq!(vec!["organic", "vegan"], tags);
+    }
+
+    #[test]
+    fn delete_tag_with_dry_run() {
+        let (client, db) = setup();
+        register_user(&db, "admin@example.com", "secret", true);
+        let mut admin = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("admin@example.com")
+            .unwrap();
+        admin.role = Role::Admin;
+        db.exclusive().unwrap().update_user(&admin).unwrap();
+        create_tag(&db, "spam");
+
+        let res = client.delete("/tags/spam?dry_run=true").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let mut res = client.delete("/tags/spam?dry_run=true").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        assert_eq!(r#"{"places":0,"events":0}"#, body);
+        assert_eq!(1, db.shared().unwrap().count_tags().unwrap());
+
+        let res = client.delete("/tags/spam").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(0, db.shared().unwrap().count_tags().unwrap());
+        let res = client.delete("/tags/spam").dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+    }
+}
@@ -1,6 +1,7 @@
 use super::*;
 use crate::{adapters::json, core::usecases};
 use ofdb_core::util::sort::Rated;
+use rocket::http::Header;
 
 pub mod prelude {
     pub use crate::{
@@ -54,18 +55,85 @@ fn create_place() {
     assert_eq!(body_str, format!("\"{}\"", eid));
 }
 
+#[test]
+fn create_and_find_imported_place() {
+    let (client, db) = setup();
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
+            id: "bar".into(),
+            name: "bar".into(),
+            owned_tags: vec![],
+        },
+        "secret",
+    );
+    // The importer is the organization of the API token
+    let body = r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":[],"source":{"importer":"baz","external_ref":"42"}}"#;
+    let response = client
+        .post("/entries")
+        .header(ContentType::JSON)
+        .body(body)
+        .dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    let mut response = client
+        .post("/entries")
+        .header(ContentType::JSON)
+        .header(Header::new("Authorization", "Bearer secret"))
+        .body(body)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let id: String = serde_json::from_str(&body_str).unwrap();
+
+    let mut response = client
+        .get("/entries/by-source?importer=bar&external_ref=42")
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let entry: json::Entry = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(id, entry.id);
+    let source = entry.source.unwrap();
+    assert_eq!("bar", source.importer);
+    assert_eq!("42", source.external_ref);
+
+    // Re-importing the same record must fail
+    let response = client
+        .post("/entries")
+        .header(ContentType::JSON)
+        .header(Header::new("Authorization", "Bearer secret"))
+        .body(body)
+        .dispatch();
+    assert_eq!(response.status().code, 409);
+
+    // The imported record is one of the external ids of the place
+    let mut response = client
+        .get(format!("/places/{}/external-ids", id))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let external_ids: Vec<json::ExternalId> = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(1, external_ids.len());
+    assert_eq!("bar", external_ids[0].source);
+    assert_eq!("42", external_ids[0].id);
+
+    let response = client
+        .get("/entries/by-source?importer=bar&external_ref=43")
+        .dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
+
 #[test]
 fn create_place_with_reserved_tag() {
     let (client, db) = setup();
-    db.exclusive()
-        .unwrap()
-        .create_org(Organization {
+    create_org_with_api_token(
+        &*db.exclusive().unwrap(),
+        Organization {
             id: "a".into(),
             name: "a".into(),
             owned_tags: vec!["a".into()],
-            api_token: "a".into(),
-        })
-        .unwrap();
+        },
+        "a",
+    );
     let res = client.post("/entries")
                     .header(ContentType::JSON)
                     .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":["x"],"license":"CC0-1.0","tags":["a"]}"#)
@@ -73,6 +141,121 @@ fn create_place_with_reserved_tag() {
     assert_eq!(res.status(), Status::Forbidden);
 }
 
+#[test]
+fn get_owners_of_tags() {
+    let (client, db) = setup();
+    for (id, owned_tags) in &[("a", vec!["foo"]), ("b", vec!["foo", "bar"])] {
+        create_org_with_api_token(
+            &*db.exclusive().unwrap(),
+            Organization {
+                id: (*id).into(),
+                name: format!("Org {}", id),
+                owned_tags: owned_tags.iter().map(|t| (*t).to_string()).collect(),
+            },
+            id,
+        );
+    }
+
+    let mut res = client.get("/tags/foo/owner").dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    test_json(&res);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    assert_eq!(
+        r#"[{"id":"a","name":"Org a"},{"id":"b","name":"Org b"}]"#,
+        body_str
+    );
+    let mut res = client.get("/tags/baz/owner").dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    assert_eq!("[]", res.body().and_then(|b| b.into_string()).unwrap());
+
+    // Only for scouts
+    let res = client.get("/tags/owners").dispatch();
+    assert_eq!(res.status(), Status::Unauthorized);
+    crate::ports::web::tests::register_user(&db, "scout@example.com", "secret", true);
+    let mut scout = db
+        .shared()
+        .unwrap()
+        .get_user_by_email("scout@example.com")
+        .unwrap();
+    scout.role = Role::Scout;
+    db.exclusive().unwrap().update_user(&scout).unwrap();
+    let res = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"scout@example.com","password":"secret"}"#)
+        .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    let mut res = client.get("/tags/owners").dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    let owned_tags: Vec<json::OwnedTag> = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(
+        vec![("bar", 1), ("foo", 2)],
+        owned_tags
+            .iter()
+            .map(|t| (t.tag.as_str(), t.owners.len()))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn verify_the_contact_email_of_a_place() {
+    let (client, db) = setup();
+    let mut place = Place::build().id("foo").finish();
+    place.contact = Some(Contact {
+        email: Some("contact@example.com".into()),
+        phone: None,
+    });
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(place)
+        .unwrap();
+
+    // Only for registered users
+    let res = client.post("/entries/foo/contact/verification").dispatch();
+    assert_eq!(res.status(), Status::Unauthorized);
+    crate::ports::web::tests::register_user(&db, "user@example.com", "secret", true);
+    let res = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"user@example.com","password":"secret"}"#)
+        .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    let res = client.post("/entries/foo/contact/verification").dispatch();
+    assert_eq!(res.status(), Status::Ok);
+
+    // The token is only sent by e-mail
+    let (_, email_nonce) =
+        usecases::request_contact_verification(&*db.exclusive().unwrap(), &"foo".into())
+            .unwrap()
+            .unwrap();
+    let res = client
+        .post("/confirm-contact-email")
+        .header(ContentType::JSON)
+        .body(r#"{"token":"invalid"}"#)
+        .dispatch();
+    assert_eq!(res.status(), Status::BadRequest);
+    let mut res = client
+        .post("/confirm-contact-email")
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"token":"{}"}}"#,
+            email_nonce.encode_to_string()
+        ))
+        .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    assert_eq!(
+        r#""foo""#,
+        res.body().and_then(|b| b.into_string()).unwrap()
+    );
+
+    let mut res = client.get("/entries/foo").dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    let entries: Vec<json::Entry> = serde_json::from_str(&body_str).unwrap();
+    assert!(entries[0].email_verified);
+}
+
 #[test]
 fn create_place_with_tag_duplicates() {
     let (client, db) = setup();
@@ -158,6 +341,8 @@ fn get_one_entry() {
             comment: "bla".into(),
             source: Some("blabla".into()),
         },
+        None,
+        false,
     )
     .unwrap();
     let req = client.get("/entries/get_one_entry_test");
@@ -170,13 +355,13 @@ fn get_one_entry() {
     let rating = connections
         .shared()
         .unwrap()
-        .load_ratings_of_place("get_one_entry_test")
+        .load_ratings_of_place(&"get_one_entry_test".into())
         .unwrap()[0]
         .clone();
     assert!(body_str.contains(&format!(r#""ratings":["{}"]"#, rating.id)));
     assert_eq!(
         entries[0],
-        json::entry_from_place_with_ratings(e, vec![rating])
+        json::entry_from_place_with_ratings(e, vec![rating], &Default::default())
     );
 }
 
@@ -209,208 +394,1170 @@ fn get_multiple_places() {
     assert_eq!(body_str.as_str().chars().next().unwrap(), '[');
     let entries: Vec<json::Entry> = serde_json::from_str(&body_str).unwrap();
     assert_eq!(entries.len(), 2);
+    let categories = Categories::default();
     assert!(entries
         .iter()
-        .any(|x| *x == json::entry_from_place_with_ratings(one.clone(), vec![])));
+        .any(|x| *x == json::entry_from_place_with_ratings(one.clone(), vec![], &categories)));
     assert!(entries
         .iter()
-        .any(|x| *x == json::entry_from_place_with_ratings(two.clone(), vec![])));
-}
-
-fn default_new_entry() -> usecases::NewPlace {
-    usecases::NewPlace {
-        title: Default::default(),
-        description: Default::default(),
-        categories: Default::default(),
-        email: None,
-        telephone: None,
-        lat: Default::default(),
-        lng: Default::default(),
-        street: None,
-        zip: None,
-        city: None,
-        country: None,
-        state: None,
-        tags: Default::default(),
-        homepage: None,
-        opening_hours: None,
-        license: "CC0-1.0".into(),
-        image_url: None,
-        image_link_url: None,
-    }
-}
-
-fn new_entry_with_category(category: &str, lat: f64, lng: f64) -> usecases::NewPlace {
-    usecases::NewPlace {
-        categories: vec![category.into()],
-        lat,
-        lng,
-        ..default_new_entry()
-    }
+        .any(|x| *x == json::entry_from_place_with_ratings(two.clone(), vec![], &categories)));
 }
 
 #[test]
-fn search_with_categories_and_bbox() {
-    let entries = vec![
-        new_entry_with_category(Category::ID_NON_PROFIT, 1.0, 1.0),
-        new_entry_with_category(Category::ID_NON_PROFIT, 2.0, 2.0),
-        new_entry_with_category(Category::ID_COMMERCIAL, 3.0, 3.0),
-    ];
+fn get_archived_place() {
     let (client, connections, mut search_engine, notify) = setup2();
-    let place_ids: Vec<_> = entries
+    crate::ports::web::tests::register_user(&connections, "scout@example.com", "secret", true);
+    let ids: Vec<_> = vec!["archived", "visible"]
         .into_iter()
-        .map(|e| {
-            flows::create_place(&connections, &mut search_engine, &notify, e, None)
+        .map(|title| {
+            let new_place = new_entry_with_text(title, "bla", 1.0, 1.0);
+            flows::create_place(&connections, &mut search_engine, &notify, new_place, None)
                 .unwrap()
                 .id
                 .to_string()
         })
         .collect();
+    flows::review_places(
+        &connections,
+        &mut search_engine,
+        &notify,
+        &[ids[0].as_str()],
+        usecases::Review {
+            context: None,
+            reviewer_email: "scout@example.com".into(),
+            status: ReviewStatus::Archived,
+            comment: None,
+        },
+    )
+    .unwrap();
 
-    let req = client.get(format!(
-        "/search?bbox=-10,-10,10,10&categories={}",
-        Category::ID_NON_PROFIT
-    ));
-    let mut response = req.dispatch();
-    assert_eq!(response.status(), Status::Ok);
-    test_json(&response);
-    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
-    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
-
-    let req = client.get(format!(
-        "/search?bbox=1.8,0.5,3.0,3.0&categories={}",
-        Category::ID_NON_PROFIT
-    ));
-    let mut response = req.dispatch();
-    assert_eq!(response.status(), Status::Ok);
-    test_json(&response);
-    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(!body_str.contains(&format!("\"{}\"", place_ids[0])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
-    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
-
-    let req = client.get(format!(
-        "/search?bbox=-10,-10,10,10&categories={}",
-        Category::ID_COMMERCIAL
-    ));
-    let mut response = req.dispatch();
-    assert_eq!(response.status(), Status::Ok);
-    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(!body_str.contains(&format!("\"{}\"", place_ids[0])));
-    assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+    let response = client.get(format!("/entries/{}", ids[0])).dispatch();
+    assert_eq!(response.status().code, 410);
+    assert_eq!(response.status().reason, "Archived");
 
-    let req = client.get(format!(
-        "/search?bbox=-10,-10,10,10&categories={},{}",
-        Category::ID_NON_PROFIT,
-        Category::ID_COMMERCIAL
-    ));
-    let mut response = req.dispatch();
+    let response = client.get(format!("/entries/{}", ids[1])).dispatch();
     assert_eq!(response.status(), Status::Ok);
-    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
 
-    let req = client.get(format!(
-        "/search?bbox=0.9,0.5,2.5,2.0&categories={},{}",
-        Category::ID_NON_PROFIT,
-        Category::ID_COMMERCIAL
-    ));
-    let mut response = req.dispatch();
+    // Lists of entries are not affected
+    let mut response = client
+        .get(format!("/entries/{},{}", ids[0], ids[1]))
+        .dispatch();
     assert_eq!(response.status(), Status::Ok);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
-    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
-}
-
-fn new_entry_with_text(title: &str, description: &str, lat: f64, lng: f64) -> usecases::NewPlace {
-    usecases::NewPlace {
-        title: title.into(),
-        description: description.into(),
-        lat,
-        lng,
-        ..default_new_entry()
-    }
+    let entries: Vec<json::Entry> = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(2, entries.len());
 }
 
 #[test]
-fn search_with_text() {
-    let entries = vec![
-        new_entry_with_text("Foo", "bla", 1.0, 1.0),
-        new_entry_with_text("bar", "foo", 2.0, 2.0),
-        new_entry_with_text("baZ", "blub", 3.0, 3.0),
-    ];
+fn archive_batch_of_places_with_unknown_ids() {
     let (client, connections, mut search_engine, notify) = setup2();
-    let place_ids: Vec<_> = entries
+    let ids: Vec<_> = vec!["foo", "bar"]
         .into_iter()
-        .map(|e| {
-            flows::create_place(&connections, &mut search_engine, &notify, e, None)
+        .map(|title| {
+            let new_place = new_entry_with_text(title, "bla", 1.0, 1.0);
+            flows::create_place(&connections, &mut search_engine, &notify, new_place, None)
                 .unwrap()
                 .id
                 .to_string()
         })
         .collect();
+    let body = format!(
+        r#"{{"kind":"place","ids":["{}","unknown","{}"],"comment":"spam"}}"#,
+        ids[0], ids[1]
+    );
 
-    // Search case insensitive "Foo" and "foo"
-    // Limit is required, because all entries match the query and their
-    // rating is equal. The match score is currently not considered when
-    // ordering the results!
-    let req = client.get("/search?bbox=-10,-10,10,10&text=Foo&limit=2");
-    let mut response = req.dispatch();
+    // Only for scouts and admins
+    let res = client
+        .post("/actions/archive")
+        .header(ContentType::JSON)
+        .body(&body)
+        .dispatch();
+    assert_eq!(res.status(), Status::Unauthorized);
+
+    crate::ports::web::tests::register_user(&connections, "scout@example.com", "secret", true);
+    let mut scout = connections
+        .shared()
+        .unwrap()
+        .get_user_by_email("scout@example.com")
+        .unwrap();
+    scout.role = Role::Scout;
+    connections
+        .exclusive()
+        .unwrap()
+        .update_user(&scout)
+        .unwrap();
+    let res = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"scout@example.com","password":"secret"}"#)
+        .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+
+    let mut res = client
+        .post("/actions/archive")
+        .header(ContentType::JSON)
+        .body(&body)
+        .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    let report: json::ArchiveBatchReport =
+        serde_json::from_str(&res.body_string().unwrap()).unwrap();
+    assert_eq!(ids, report.archived);
+    assert_eq!(vec!["unknown".to_string()], report.not_found);
+    assert!(report.failed.is_empty());
+    let (_, status) = connections
+        .shared()
+        .unwrap()
+        .get_place(&ids[0].as_str().into())
+        .unwrap();
+    assert_eq!(ReviewStatus::Archived, status);
+
+    let res = client
+        .post("/actions/archive")
+        .header(ContentType::JSON)
+        .body(r#"{"kind":"place","ids":[]}"#)
+        .dispatch();
+    assert_eq!(res.status(), Status::BadRequest);
+}
+
+#[test]
+fn get_places_as_geojson() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    let id = flows::create_place(
+        &connections,
+        &mut search_engine,
+        &notify,
+        new_entry_with_text("Market", "bla", 1.0, 2.0),
+        None,
+    )
+    .unwrap()
+    .id
+    .to_string();
+
+    let mut response = client
+        .get(format!("/entries/{}?format=geojson", id))
+        .dispatch();
     assert_eq!(response.status(), Status::Ok);
-    test_json(&response);
+    assert_eq!(
+        response.headers().get_one("Content-Type"),
+        Some("application/geo+json")
+    );
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
-    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
+    let collection: serde_json::Value = serde_json::from_str(&body_str).unwrap();
+    assert_eq!("FeatureCollection", collection["type"]);
+    let features = collection["features"].as_array().unwrap();
+    assert_eq!(1, features.len());
+    assert_eq!(id, features[0]["id"]);
+    let pos = MapPoint::from_lat_lng_deg(1.0, 2.0);
+    assert_eq!(
+        serde_json::json!({
+            "type": "Point",
+            "coordinates": [pos.lng().to_deg(), pos.lat().to_deg()],
+        }),
+        features[0]["geometry"]
+    );
+    // Activity details are reserved for exports
+    assert!(features[0]["properties"]["created_by"].is_null());
 
-    // Search case insensitive "Foo" and "foo" with bbox
-    let req = client.get("/search?bbox=1.8,0.5,3.0,3.0&text=Foo");
-    let mut response = req.dispatch();
+    let mut response = client
+        .get("/search?bbox=-10,-10,10,10")
+        .header(rocket::http::Header::new("Accept", "application/geo+json"))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.headers().get_one("Content-Type"),
+        Some("application/geo+json")
+    );
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let collection: serde_json::Value = serde_json::from_str(&body_str).unwrap();
+    let features = collection["features"].as_array().unwrap();
+    assert_eq!(1, features.len());
+    assert_eq!("Market", features[0]["properties"]["title"]);
+
+    // Plain JSON is still the default
+    let response = client.get("/search?bbox=-10,-10,10,10").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+
+    let response = client
+        .get("/search?bbox=-10,-10,10,10&format=kml")
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn get_entry_quality() {
+    let place = Place::build()
+        .id("get_entry_quality_test")
+        .title("some")
+        .description("desc")
+        .tags(vec!["a", "b", "c"])
+        .image_url(Some("https://img.example.com/a.png"))
+        .finish();
+    let (client, db) = setup();
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(place)
+        .unwrap();
+    let mut response = client
+        .get("/entries/get_entry_quality_test/quality")
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let quality: json::PlaceQuality = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(
+        vec![
+            json::QualityHint::MissingAddress,
+            json::QualityHint::MissingContact,
+            json::QualityHint::MissingOpeningHours,
+        ],
+        quality.hints
+    );
+    assert!(quality.score > 0.0 && quality.score < 1.0);
+
+    let response = client.get("/entries/does_not_exist/quality").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[test]
+fn find_duplicates_of_places_nearby() {
+    let entries = vec![
+        new_entry_with_text("Weltladen Regenbogen", "desc", 48.0, 8.0),
+        // ~20 m away
+        new_entry_with_text("Regenbogen-Weltladen e.V. Freiburg", "desc", 48.0002, 8.0),
+        // Titles with few words differ in more than two words
+        new_entry_with_text("Fahrradwerkstatt am Hauptbahnhof", "desc", 48.0, 8.0001),
+        // ~1100 m away
+        new_entry_with_text("Weltladen Regenbogen", "desc", 48.01, 8.0),
+    ];
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_ids: Vec<_> = entries
+        .into_iter()
+        .map(|e| {
+            flows::create_place(&connections, &mut search_engine, &notify, e, None)
+                .unwrap()
+                .id
+                .to_string()
+        })
+        .collect();
+
+    let mut response = client
+        .get(format!("/entries/{}/duplicates", place_ids[0]))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let duplicates: Vec<json::PlaceDuplicate> = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(1, duplicates.len());
+    assert_eq!(place_ids[1], duplicates[0].id);
+    assert_eq!(
+        json::DuplicateType::SimilarTrigrams,
+        duplicates[0].duplicate_type
+    );
+
+    // Check before creating a new place
+    let mut response = client
+        .post("/entries/duplicates")
+        .header(ContentType::JSON)
+        .body(r#"{"title":"Weltladen Regenbogen","description":"desc","lat":48.0,"lng":8.0,"categories":[],"license":"CC0-1.0","tags":[]}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let duplicates: Vec<json::PlaceDuplicate> = serde_json::from_str(&body_str).unwrap();
+    let mut duplicate_ids: Vec<_> = duplicates.into_iter().map(|d| d.id).collect();
+    duplicate_ids.sort();
+    let mut expected_ids = vec![place_ids[0].clone(), place_ids[1].clone()];
+    expected_ids.sort();
+    assert_eq!(expected_ids, duplicate_ids);
+    assert_eq!(4, connections.shared().unwrap().count_places().unwrap());
+
+    let response = client.get("/entries/does_not_exist/duplicates").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[test]
+fn merge_duplicate_into_primary_place_as_admin() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_ids: Vec<_> = vec![
+        new_entry_with_text("Weltladen Regenbogen", "desc", 48.0, 8.0),
+        new_entry_with_text("Regenbogen-Weltladen", "desc", 48.0002, 8.0),
+    ]
+    .into_iter()
+    .map(|e| {
+        flows::create_place(&connections, &mut search_engine, &notify, e, None)
+            .unwrap()
+            .id
+            .to_string()
+    })
+    .collect();
+    crate::ports::web::tests::register_user(&connections, "admin@example.com", "secret", true);
+    let mut admin = connections
+        .shared()
+        .unwrap()
+        .get_user_by_email("admin@example.com")
+        .unwrap();
+    admin.role = Role::Admin;
+    connections
+        .exclusive()
+        .unwrap()
+        .update_user(&admin)
+        .unwrap();
+    let body = format!(
+        r#"{{"primary":"{}","duplicate":"{}"}}"#,
+        place_ids[0], place_ids[1]
+    );
+
+    let response = client
+        .post("/admin/places/merge")
+        .header(ContentType::JSON)
+        .body(&body)
+        .dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let response = client
+        .post("/admin/places/merge")
+        .header(ContentType::JSON)
+        .body(&body)
+        .dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+    let (_, status) = connections
+        .shared()
+        .unwrap()
+        .get_place(&place_ids[1].as_str().into())
+        .unwrap();
+    assert_eq!(ReviewStatus::Archived, status);
+
+    // The archived duplicate refers to the primary place
+    let mut response = client.get(format!("/entries/{}", place_ids[1])).dispatch();
+    assert_eq!(response.status().code, 410);
+    assert_eq!(response.status().reason, "Archived");
+    let merged: json::MergedEntry = serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    assert_eq!(place_ids[0], merged.merged_into);
+
+    // The duplicate has already been archived
+    let response = client
+        .post("/admin/places/merge")
+        .header(ContentType::JSON)
+        .body(&body)
+        .dispatch();
+    assert_eq!(response.status().code, 410);
+}
+
+#[test]
+fn get_all_categories() {
+    let (client, _) = setup();
+    let mut response = client.get("/categories").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let categories: Vec<json::Category> = serde_json::from_str(&body_str).unwrap();
+    let ids: Vec<_> = categories.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(
+        vec![
+            Category::ID_NON_PROFIT,
+            Category::ID_COMMERCIAL,
+            Category::ID_EVENT
+        ],
+        ids
+    );
+}
+
+fn default_new_entry() -> usecases::NewPlace {
+    usecases::NewPlace {
+        title: Default::default(),
+        description: Default::default(),
+        categories: Default::default(),
+        email: None,
+        telephone: None,
+        lat: Default::default(),
+        lng: Default::default(),
+        street: None,
+        zip: None,
+        city: None,
+        country: None,
+        state: None,
+        tags: Default::default(),
+        homepage: None,
+        opening_hours: None,
+        license: "CC0-1.0".into(),
+        image_url: None,
+        image_link_url: None,
+        source: None,
+        additional_locations: vec![],
+    }
+}
+
+fn new_entry_with_category(category: &str, lat: f64, lng: f64) -> usecases::NewPlace {
+    usecases::NewPlace {
+        categories: vec![category.into()],
+        lat,
+        lng,
+        ..default_new_entry()
+    }
+}
+
+#[test]
+fn search_with_categories_of_the_deployment() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_ids: Vec<_> = vec![vec!["custom".into()], vec![]]
+        .into_iter()
+        .map(|tags| {
+            let e = usecases::NewPlace {
+                tags,
+                ..default_new_entry()
+            };
+            flows::create_place(&connections, &mut search_engine, &notify, e, None)
+                .unwrap()
+                .id
+                .to_string()
+        })
+        .collect();
+    {
+        use diesel::RunQueryDsl;
+        diesel::sql_query("INSERT INTO category (id, tag) VALUES ('custom-id', 'custom')")
+            .execute(&*connections.exclusive().unwrap())
+            .unwrap();
+    }
+    // The categories are loaded when building the index
+    flows::rebuild_index(&connections, &mut search_engine).unwrap();
+
+    let mut response = client
+        .get("/search?bbox=-10,-10,10,10&categories=custom-id")
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
+}
+
+#[test]
+fn search_with_categories_and_bbox() {
+    let entries = vec![
+        new_entry_with_category(Category::ID_NON_PROFIT, 1.0, 1.0),
+        new_entry_with_category(Category::ID_NON_PROFIT, 2.0, 2.0),
+        new_entry_with_category(Category::ID_COMMERCIAL, 3.0, 3.0),
+    ];
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_ids: Vec<_> = entries
+        .into_iter()
+        .map(|e| {
+            flows::create_place(&connections, &mut search_engine, &notify, e, None)
+                .unwrap()
+                .id
+                .to_string()
+        })
+        .collect();
+
+    let req = client.get(format!(
+        "/search?bbox=-10,-10,10,10&categories={}",
+        Category::ID_NON_PROFIT
+    ));
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
+
+    let req = client.get(format!(
+        "/search?bbox=1.8,0.5,3.0,3.0&categories={}",
+        Category::ID_NON_PROFIT
+    ));
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
+
+    let req = client.get(format!(
+        "/search?bbox=-10,-10,10,10&categories={}",
+        Category::ID_COMMERCIAL
+    ));
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+
+    let req = client.get(format!(
+        "/search?bbox=-10,-10,10,10&categories={},{}",
+        Category::ID_NON_PROFIT,
+        Category::ID_COMMERCIAL
+    ));
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+
+    let req = client.get(format!(
+        "/search?bbox=0.9,0.5,2.5,2.0&categories={},{}",
+        Category::ID_NON_PROFIT,
+        Category::ID_COMMERCIAL
+    ));
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
+}
+
+#[test]
+fn search_within_radius() {
+    let entries = vec![
+        new_entry_with_category(Category::ID_NON_PROFIT, 1.0, 1.0),
+        // ~940 m away: Inside the enclosing bbox, but outside of the circle
+        new_entry_with_category(Category::ID_NON_PROFIT, 1.006, 1.006),
+        // ~1000 m away
+        new_entry_with_category(Category::ID_NON_PROFIT, 1.0, 1.009),
+    ];
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_ids: Vec<_> = entries
+        .into_iter()
+        .map(|e| {
+            flows::create_place(&connections, &mut search_engine, &notify, e, None)
+                .unwrap()
+                .id
+                .to_string()
+        })
+        .collect();
+
+    let mut response = client.get("/search?lat=1.0&lng=1.0&radius=800").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
+
+    let mut response = client
+        .get("/search?bbox=-10,-10,10,10&lat=1.0&lng=1.0&radius=1200")
+        .dispatch();
     assert_eq!(response.status(), Status::Ok);
-    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+
+    let response = client.get("/search?lat=1.0&lng=1.0&radius=0").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    let response = client.get("/search?lat=1.0&radius=800").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    let response = client.get("/search?lat=1.0&lng=1.0").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+fn new_entry_with_text(title: &str, description: &str, lat: f64, lng: f64) -> usecases::NewPlace {
+    usecases::NewPlace {
+        title: title.into(),
+        description: description.into(),
+        lat,
+        lng,
+        ..default_new_entry()
+    }
+}
+
+#[test]
+fn search_with_text() {
+    let entries = vec![
+        new_entry_with_text("Foo", "bla", 1.0, 1.0),
+        new_entry_with_text("bar", "foo", 2.0, 2.0),
+        new_entry_with_text("baZ", "blub", 3.0, 3.0),
+    ];
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_ids: Vec<_> = entries
+        .into_iter()
+        .map(|e| {
+            flows::create_place(&connections, &mut search_engine, &notify, e, None)
+                .unwrap()
+                .id
+                .to_string()
+        })
+        .collect();
+
+    // Search case insensitive "Foo" and "foo"
+    // Limit is required, because all entries match the query and their
+    // rating is equal. The match score is currently not considered when
+    // ordering the results!
+    let req = client.get("/search?bbox=-10,-10,10,10&text=Foo&limit=2");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
+
+    // Search case insensitive "Foo" and "foo" with bbox
+    let req = client.get("/search?bbox=1.8,0.5,3.0,3.0&text=Foo");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
     assert!(!body_str.contains(&format!("\"{}\"", place_ids[0])));
     assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
     assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
 
-    // Search with whitespace
-    let req = client.get("/search?bbox=-10,-10,10,10&text=blub%20foo");
-    let mut response = req.dispatch();
+    // Search with whitespace
+    let req = client.get("/search?bbox=-10,-10,10,10&text=blub%20foo");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+
+    // Search with whitespace and bbox
+    let req = client.get("/search?bbox=0.9,0.5,2.5,2.0&text=blub%20foo");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
+
+    // Search with punctuation
+    // TODO: Ignore punctuation in query text and make this test pass
+    // See also: https://github.com/slowtec/openfairdb/issues/82
+    /*
+    let req = client.get("/search?bbox=-10,-10,10,10&text=blub,Foo");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+    */
+}
+
+#[test]
+fn search_with_fuzzy_text() {
+    let entries = vec![
+        new_entry_with_text("Kulturcafé", "bla", 1.0, 1.0),
+        new_entry_with_text("Bakery", "blub", 2.0, 2.0),
+    ];
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_ids: Vec<_> = entries
+        .into_iter()
+        .map(|e| {
+            flows::create_place(&connections, &mut search_engine, &notify, e, None)
+                .unwrap()
+                .id
+                .to_string()
+        })
+        .collect();
+
+    let mut response = client
+        .get("/search?bbox=-10,-10,10,10&text=kulturcafe")
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
+
+    let mut response = client
+        .get("/search?bbox=-10,-10,10,10&text=kulturcafe&fuzzy=true")
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
+}
+
+#[test]
+fn search_sorted_by() {
+    let entries = vec![
+        new_entry_with_text("Bakery", "bla", 1.0, 1.02),
+        new_entry_with_text("apple shop", "bla", 1.0, 1.0),
+        new_entry_with_text("Cafe", "bla", 1.0, 1.01),
+    ];
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_ids: Vec<_> = entries
+        .into_iter()
+        .map(|e| {
+            // Ensure distinct time stamps
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            flows::create_place(&connections, &mut search_engine, &notify, e, None)
+                .unwrap()
+                .id
+                .to_string()
+        })
+        .collect();
+
+    let search_ordered = |sort_by: &str| {
+        let mut response = client
+            .get(format!("/search?bbox=0,0,2,2&sort_by={}", sort_by))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+        let mut positions: Vec<_> = place_ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (body_str.find(&format!("\"{}\"", id)).unwrap(), i))
+            .collect();
+        positions.sort_unstable();
+        positions.into_iter().map(|(_, i)| i).collect::<Vec<_>>()
+    };
+    // Around the center of the bbox
+    assert_eq!(vec![1, 2, 0], search_ordered("distance"));
+    assert_eq!(vec![1, 0, 2], search_ordered("title"));
+    assert_eq!(vec![2, 1, 0], search_ordered("recently_changed"));
+
+    let response = client.get("/search?bbox=0,0,2,2&sort_by=foo").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn search_by_time_of_creation_and_change() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_ids: Vec<_> = ["a", "b", "c"]
+        .iter()
+        .map(|title| {
+            // Ensure distinct time stamps
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            flows::create_place(
+                &connections,
+                &mut search_engine,
+                &notify,
+                new_entry_with_text(title, "bla", 1.0, 1.0),
+                None,
+            )
+            .unwrap()
+            .id
+            .to_string()
+        })
+        .collect();
+
+    // Change the first place after all others have been created
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    let (mut place, status) = connections
+        .shared()
+        .unwrap()
+        .get_place(&place_ids[0].as_str().into())
+        .unwrap();
+    place.created.at = TimestampMs::now();
+    search_engine
+        .add_or_update_place(&place, status, &AvgRatings::default())
+        .unwrap();
+    search_engine.flush_index().unwrap();
+
+    let search = |params: &str| {
+        let mut response = client
+            .get(format!("/search?bbox=0,0,2,2{}", params))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+        let response: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+        response
+            .visible
+            .into_iter()
+            .map(|p| place_ids.iter().position(|id| *id == p.id).unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(vec![0, 2, 1], search("&sort_by=recently_changed"));
+    assert_eq!(vec![2, 1, 0], search("&sort_by=recently_created"));
+
+    let later = Timestamp::now().into_seconds() + 60;
+    assert!(search(&format!("&since={}", later)).is_empty());
+    assert_eq!(
+        vec![0, 2, 1],
+        search(&format!("&until={}&sort_by=recently_changed", later))
+    );
+    assert!(search("&until=1").is_empty());
+}
+
+#[test]
+fn search_paginated_with_cursor() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    let mut create_place = |title: &str| {
+        // Ensure distinct time stamps
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        flows::create_place(
+            &connections,
+            &mut search_engine,
+            &notify,
+            new_entry_with_text(title, "bla", 1.0, 1.0),
+            None,
+        )
+        .unwrap()
+        .id
+        .to_string()
+    };
+    let place_ids: Vec<_> = ["a", "b", "c"].iter().map(|t| create_place(t)).collect();
+
+    let search = |params: &str| {
+        let mut response = client
+            .get(format!(
+                "/search?bbox=0,0,2,2&sort_by=recently_changed&limit=2{}",
+                params
+            ))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+        let response: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+        let ids: Vec<_> = response.visible.into_iter().map(|p| p.id).collect();
+        (ids, response.next_cursor)
+    };
+
+    let (ids, next_cursor) = search("");
+    assert_eq!(vec![place_ids[2].clone(), place_ids[1].clone()], ids);
+    let next_cursor = next_cursor.unwrap();
+
+    // A new place in front of the cursor is not considered
+    create_place("d");
+    let (ids, next_cursor) = search(&format!("&cursor={}", next_cursor));
+    assert_eq!(vec![place_ids[0].clone()], ids);
+    assert!(next_cursor.is_none());
+
+    // The offset is affected by the new place
+    let (ids, _) = search("&offset=2");
+    assert_eq!(vec![place_ids[1].clone(), place_ids[0].clone()], ids);
+
+    let response = client.get("/search?bbox=0,0,2,2&cursor=invalid").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let response = client.get("/search?bbox=0,0,2,2&offset=10001").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    let response = client
+        .get("/search?bbox=0,0,2,2&cursor=10001-0000000000000000-a")
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn search_with_min_rating() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_ids: Vec<_> = vec![
+        new_entry_with_text("rated", "bla", 1.0, 1.0),
+        new_entry_with_text("unrated", "bla", 1.0, 1.0),
+    ]
+    .into_iter()
+    .map(|e| {
+        flows::create_place(&connections, &mut search_engine, &notify, e, None)
+            .unwrap()
+            .id
+            .to_string()
+    })
+    .collect();
+    flows::create_rating(
+        &connections,
+        &mut search_engine,
+        usecases::NewPlaceRating {
+            context: ofdb_boundary::RatingContext::Fairness,
+            value: ofdb_boundary::RatingValue::from(2),
+            title: "title".into(),
+            user: None,
+            entry: place_ids[0].clone(),
+            comment: "bla".into(),
+            source: Some("blabla".into()),
+        },
+        None,
+        false,
+    )
+    .unwrap();
+
+    let search = |params: &str| {
+        let mut response = client
+            .get(format!("/search?bbox=0,0,2,2{}", params))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+        let response: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+        let mut ids: Vec<_> = response.visible.into_iter().map(|p| p.id).collect();
+        ids.sort_unstable();
+        ids
+    };
+
+    let mut all_ids = place_ids.clone();
+    all_ids.sort_unstable();
+    assert_eq!(all_ids, search(""));
+    assert_eq!(all_ids, search("&min_rating=0"));
+    assert_eq!(vec![place_ids[0].clone()], search("&min_rating=0.1"));
+    assert_eq!(
+        vec![place_ids[0].clone()],
+        search("&min_ratings=fairness:2")
+    );
+    assert!(search("&min_ratings=fairness:1,humanity:1").is_empty());
+
+    for params in &[
+        "min_rating=2.5",
+        "min_ratings=fairness",
+        "min_ratings=foo:1",
+    ] {
+        let response = client
+            .get(format!("/search?bbox=0,0,2,2&{}", params))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+}
+
+#[test]
+fn search_with_highlighted_snippets() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    flows::create_place(
+        &connections,
+        &mut search_engine,
+        &notify,
+        new_entry_with_text("Organic <market>", "Fresh & organic food", 1.0, 1.0),
+        None,
+    )
+    .unwrap();
+
+    let mut response = client.get("/search?bbox=0,0,2,2&text=organic").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let response: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+    let snippets = response.visible[0].snippets.as_ref().unwrap();
+    assert_eq!(
+        Some("<b>Organic</b> &lt;market&gt;"),
+        snippets.title.as_deref()
+    );
+    assert_eq!(
+        Some("Fresh &amp; <b>organic</b> food"),
+        snippets.description.as_deref()
+    );
+
+    let mut response = client.get("/search?bbox=0,0,2,2").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(!body_str.contains("snippets"));
+}
+
+#[test]
+fn search_by_additional_locations() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    let new_place = usecases::NewPlace {
+        additional_locations: vec![usecases::NewPlaceLocation {
+            lat: 10.0,
+            lng: 10.0,
+            street: None,
+            zip: None,
+            city: Some("Elsewhere".into()),
+            country: None,
+            state: None,
+        }],
+        ..new_entry_with_text("Market", "At two sites", 1.0, 1.0)
+    };
+    let id = flows::create_place(&connections, &mut search_engine, &notify, new_place, None)
+        .unwrap()
+        .id
+        .to_string();
+
+    let mut response = client.get(format!("/entries/{}", id)).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let entries: Vec<json::Entry> = serde_json::from_str(&body_str).unwrap();
+    let locations = &entries[0].additional_locations;
+    assert_eq!(1, locations.len());
+    assert_eq!(Some("Elsewhere"), locations[0].city.as_deref());
+
+    let mut response = client.get("/search?bbox=9,9,11,11").dispatch();
     assert_eq!(response.status(), Status::Ok);
-    test_json(&response);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+    let response: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(1, response.visible.len());
+    assert_eq!(id, response.visible[0].id);
+    assert_eq!(1, response.visible[0].additional_locations.len());
+    assert!(response.invisible.is_empty());
+
+    // Additional locations are preserved if omitted when updating
+    let json = r#"{"version":1,"title":"Market","description":"At two sites","lat":1.0,"lng":1.0,"categories":[],"tags":[]}"#;
+    let response = client
+        .put(format!("/entries/{}", id))
+        .header(ContentType::JSON)
+        .body(json)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let (place, _) = connections
+        .shared()
+        .unwrap()
+        .get_place(&id.as_str().into())
+        .unwrap();
+    assert_eq!(1, place.additional_locations.len());
+}
 
-    // Search with whitespace and bbox
-    let req = client.get("/search?bbox=0.9,0.5,2.5,2.0&text=blub%20foo");
-    let mut response = req.dispatch();
+#[test]
+fn global_search_grouped_by_type() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_id = flows::create_place(
+        &connections,
+        &mut search_engine,
+        &notify,
+        new_entry_with_text("Organic market", "bla", 1.0, 1.0),
+        None,
+    )
+    .unwrap()
+    .id
+    .to_string();
+
+    let mut response = client.get("/search/global?q=organic").dispatch();
     assert_eq!(response.status(), Status::Ok);
     test_json(&response);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
-    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
+    let results: Vec<json::GlobalSearchResults> = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(3, results.len());
+    for group in results {
+        match group {
+            json::GlobalSearchResults::Place(places) => {
+                assert_eq!(
+                    vec![place_id.clone()],
+                    places.into_iter().map(|p| p.id).collect::<Vec<_>>()
+                );
+            }
+            json::GlobalSearchResults::Event(events) => assert!(events.is_empty()),
+            json::GlobalSearchResults::Tag(tags) => assert!(tags.is_empty()),
+        }
+    }
+    assert!(body_str.contains(r#""type":"place""#));
 
-    // Search with punctuation
-    // TODO: Ignore punctuation in query text and make this test pass
-    // See also: https://github.com/slowtec/openfairdb/issues/82
-    /*
-    let req = client.get("/search?bbox=-10,-10,10,10&text=blub,Foo");
-    let mut response = req.dispatch();
+    let response = client.get("/search/global?q=organic&limit=0").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn suggest_tags_from_text() {
+    let mut tag_synonyms = usecases::TagSynonyms::default();
+    tag_synonyms.add("organic", "bio");
+    let (client, connections, _) = crate::ports::web::tests::setup_with_cfg(
+        vec![("/", routes())],
+        crate::ports::web::Cfg {
+            tag_synonyms,
+            ..Default::default()
+        },
+    );
+    for tag in &["bio", "bakery", "market"] {
+        connections
+            .exclusive()
+            .unwrap()
+            .create_tag_if_it_does_not_exist(&Tag {
+                id: (*tag).to_string(),
+            })
+            .unwrap();
+    }
+    let mut response = client
+        .get("/entries/suggest-tags?text=Organic%20bread%20from%20our%20bakery")
+        .dispatch();
     assert_eq!(response.status(), Status::Ok);
     test_json(&response);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
-    */
+    let tags: Vec<String> = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(vec!["bio", "bakery"], tags);
+}
+
+#[test]
+fn search_suggestions_by_prefix() {
+    let entries = vec![
+        usecases::NewPlace {
+            tags: vec!["organic".into(), "market".into()],
+            ..new_entry_with_text("Organic Market", "bla", 1.0, 1.0)
+        },
+        usecases::NewPlace {
+            tags: vec!["organic".into(), "bakery".into()],
+            ..new_entry_with_text("Bakery", "bla", 1.0, 1.0)
+        },
+        usecases::NewPlace {
+            tags: vec!["orange".into()],
+            ..new_entry_with_text("Orange juice", "bla", 1.0, 1.0)
+        },
+    ];
+    let (client, connections, mut search_engine, notify) = setup2();
+    for e in entries {
+        flows::create_place(&connections, &mut search_engine, &notify, e, None).unwrap();
+    }
+
+    let suggest = |q: &str| {
+        let mut response = client.get(format!("/search/suggest?q={}", q)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        test_json(&response);
+        let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+        let mut suggestions: json::SearchSuggestions = serde_json::from_str(&body_str).unwrap();
+        suggestions.titles.sort_unstable();
+        suggestions
+    };
+
+    let suggestions = suggest("Or");
+    // Ranked by frequency
+    assert_eq!(vec!["organic", "orange"], suggestions.tags);
+    assert_eq!(vec!["Orange juice", "Organic Market"], suggestions.titles);
+
+    let suggestions = suggest("organic%20m");
+    assert!(suggestions.tags.is_empty());
+    assert_eq!(vec!["Organic Market"], suggestions.titles);
+
+    let suggestions = suggest("");
+    assert!(suggestions.tags.is_empty());
+    assert!(suggestions.titles.is_empty());
+}
+
+#[test]
+fn search_clusters_depending_on_zoom_level() {
+    let entries = vec![
+        usecases::NewPlace {
+            tags: vec!["organic".into()],
+            ..new_entry_with_text("a", "bla", 1.0, 1.0)
+        },
+        usecases::NewPlace {
+            tags: vec!["organic".into()],
+            ..new_entry_with_text("b", "bla", 1.2, 1.4)
+        },
+        new_entry_with_text("c", "bla", 1.1, 1.3),
+        new_entry_with_text("d", "bla", 8.0, 8.0),
+    ];
+    let (client, connections, mut search_engine, notify) = setup2();
+    for e in entries {
+        flows::create_place(&connections, &mut search_engine, &notify, e, None).unwrap();
+    }
+
+    let clusters = |query: &str| {
+        let mut response = client
+            .get(format!("/search/clusters?bbox=0,0,10,10&{}", query))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        test_json(&response);
+        let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+        let clusters: Vec<json::PlaceCluster> = serde_json::from_str(&body_str).unwrap();
+        clusters
+            .into_iter()
+            .map(|c| (c.count, c.lat, c.lng))
+            .collect::<Vec<_>>()
+    };
+
+    let res = clusters("zoom=4");
+    assert_eq!(2, res.len());
+    assert_eq!(3, res[0].0);
+    assert!((res[0].1 - 1.1).abs() < 0.000_01);
+    assert!((res[0].2 - 1.233_333).abs() < 0.000_01);
+    assert_eq!(1, res[1].0);
+    assert!((res[1].1 - 8.0).abs() < 0.000_01);
+    assert!((res[1].2 - 8.0).abs() < 0.000_01);
+
+    let res = clusters("zoom=0");
+    assert_eq!(1, res.len());
+    assert_eq!(4, res[0].0);
+    assert!((res[0].1 - 2.825).abs() < 0.000_01);
+    assert_eq!(4, clusters("zoom=12").len());
+    assert_eq!(1, clusters("zoom=4&tags=organic").len());
+    assert_eq!(2, clusters("zoom=4&tags=organic")[0].0);
+
+    let response = client
+        .get("/search/clusters?bbox=0,0,10,10&zoom=23")
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
 }
 
 #[test]
@@ -476,49 +1623,208 @@ fn search_with_text_terms_inclusive_exclusive() {
     assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
     assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
 
-    let req = client.get("/search?bbox=-10,-10,10,10&text=-foo%20+bAz%20+BAr");
+    let req = client.get("/search?bbox=-10,-10,10,10&text=-foo%20+bAz%20+BAr");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+
+    let req = client.get("/search?bbox=-10,-10,10,10&text=-foo%20+bar");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+
+    let req = client.get("/search?bbox=-10,-10,10,10&text=+foo+bar");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+}
+
+fn new_entry_with_city(city: &str, latlng: f64) -> usecases::NewPlace {
+    usecases::NewPlace {
+        city: Some(city.into()),
+        lat: latlng,
+        lng: latlng,
+        ..default_new_entry()
+    }
+}
+
+#[test]
+fn search_with_city() {
+    let entries = vec![
+        new_entry_with_city("Stuttgart", 1.0),
+        new_entry_with_city("Mannheim", 2.0),
+        new_entry_with_city("Stuttgart-Möhringen", 3.0),
+    ];
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_ids: Vec<_> = entries
+        .into_iter()
+        .map(|e| {
+            flows::create_place(&connections, &mut search_engine, &notify, e, None)
+                .unwrap()
+                .id
+                .to_string()
+        })
+        .collect();
+    search_engine.flush_index().unwrap();
+
+    // Limit is required, because all entries match the query and their
+    // rating is equal. The match score is currently not considered when
+    // ordering the results!
+    let req = client.get("/search?bbox=-10,-10,10,10&text=stuttgart&limit=2");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+}
+
+#[test]
+fn search_with_tags() {
+    let entries = vec![
+        usecases::NewPlace {
+            categories: vec![Category::ID_NON_PROFIT.to_string()],
+            ..default_new_entry()
+        },
+        usecases::NewPlace {
+            categories: vec![Category::ID_NON_PROFIT.to_string()],
+            tags: vec!["bla-blubb".to_string(), "foo-bar".to_string()],
+            ..default_new_entry()
+        },
+        usecases::NewPlace {
+            categories: vec![Category::ID_NON_PROFIT.to_string()],
+            tags: vec!["foo-bar".to_string()],
+            ..default_new_entry()
+        },
+    ];
+    let (client, connections, mut search_engine, notify) = setup2();
+    connections
+        .exclusive()
+        .unwrap()
+        .create_tag_if_it_does_not_exist(&Tag {
+            id: Category::TAG_NON_PROFIT.into(),
+        })
+        .unwrap();
+    connections
+        .exclusive()
+        .unwrap()
+        .create_tag_if_it_does_not_exist(&Tag {
+            id: "foo-bar".into(),
+        })
+        .unwrap();
+    connections
+        .exclusive()
+        .unwrap()
+        .create_tag_if_it_does_not_exist(&Tag {
+            id: "bla-blubb".into(),
+        })
+        .unwrap();
+    let place_ids: Vec<_> = entries
+        .into_iter()
+        .map(|e| {
+            flows::create_place(&connections, &mut search_engine, &notify, e, None)
+                .unwrap()
+                .id
+                .to_string()
+        })
+        .collect();
+
+    let req = client.get("/search?bbox=-10,-10,10,10&tags=bla-blubb");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!(
+        "\"visible\":[{{\"id\":\"{}\",\"status\":\"created\",\"lat\":0.0,\"lng\":0.0,\"title\":\"\",\"description\":\"\",\"categories\":[\"{}\"],\"tags\":[\"bla-blubb\",\"foo-bar\"],\"ratings\":{{\"total\":0.0,\"diversity\":0.0,\"fairness\":0.0,\"humanity\":0.0,\"renewable\":0.0,\"solidarity\":0.0,\"transparency\":0.0,\"count\":0}}}}]",
+        place_ids[1],
+        Category::ID_NON_PROFIT,
+    )));
+
+    let req = client.get("/search?bbox=-10,-10,10,10&tags=foo-bar");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+}
+
+#[test]
+fn search_with_any_of_the_tags() {
+    let entries = vec![
+        usecases::NewPlace {
+            tags: vec!["organic".to_string()],
+            ..default_new_entry()
+        },
+        usecases::NewPlace {
+            tags: vec!["fair".to_string()],
+            ..default_new_entry()
+        },
+        usecases::NewPlace {
+            tags: vec!["organic".to_string(), "vegan".to_string()],
+            ..default_new_entry()
+        },
+        default_new_entry(),
+    ];
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_ids: Vec<_> = entries
+        .into_iter()
+        .map(|e| {
+            flows::create_place(&connections, &mut search_engine, &notify, e, None)
+                .unwrap()
+                .id
+                .to_string()
+        })
+        .collect();
+
+    let req = client.get("/search?bbox=-10,-10,10,10&tags_any=fair,Vegan");
     let mut response = req.dispatch();
     assert_eq!(response.status(), Status::Ok);
-    test_json(&response);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
     assert!(!body_str.contains(&format!("\"{}\"", place_ids[0])));
-    assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
     assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[3])));
 
-    let req = client.get("/search?bbox=-10,-10,10,10&text=-foo%20+bar");
+    // All of the tags and any of the other tags
+    let req = client.get("/search?bbox=-10,-10,10,10&tags=organic&tags_any=fair,vegan");
     let mut response = req.dispatch();
     assert_eq!(response.status(), Status::Ok);
-    test_json(&response);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
     assert!(!body_str.contains(&format!("\"{}\"", place_ids[0])));
     assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
     assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
-
-    let req = client.get("/search?bbox=-10,-10,10,10&text=+foo+bar");
-    let mut response = req.dispatch();
-    assert_eq!(response.status(), Status::Ok);
-    test_json(&response);
-    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
-}
-
-fn new_entry_with_city(city: &str, latlng: f64) -> usecases::NewPlace {
-    usecases::NewPlace {
-        city: Some(city.into()),
-        lat: latlng,
-        lng: latlng,
-        ..default_new_entry()
-    }
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[3])));
 }
 
 #[test]
-fn search_with_city() {
+fn search_without_excluded_tags() {
     let entries = vec![
-        new_entry_with_city("Stuttgart", 1.0),
-        new_entry_with_city("Mannheim", 2.0),
-        new_entry_with_city("Stuttgart-Möhringen", 3.0),
+        usecases::NewPlace {
+            tags: vec!["organic".to_string()],
+            ..default_new_entry()
+        },
+        usecases::NewPlace {
+            tags: vec!["organic".to_string(), "vegan".to_string()],
+            ..default_new_entry()
+        },
+        usecases::NewPlace {
+            tags: vec!["fair".to_string()],
+            ..default_new_entry()
+        },
     ];
     let (client, connections, mut search_engine, notify) = setup2();
     let place_ids: Vec<_> = entries
@@ -530,61 +1836,42 @@ fn search_with_city() {
                 .to_string()
         })
         .collect();
-    search_engine.flush_index().unwrap();
 
-    // Limit is required, because all entries match the query and their
-    // rating is equal. The match score is currently not considered when
-    // ordering the results!
-    let req = client.get("/search?bbox=-10,-10,10,10&text=stuttgart&limit=2");
+    let req = client.get("/search?bbox=-10,-10,10,10&exclude_tags=Vegan,fair");
     let mut response = req.dispatch();
     assert_eq!(response.status(), Status::Ok);
-    test_json(&response);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
     assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
     assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
+
+    // Excluded hash tags in the text
+    let req = client.get("/search?bbox=-10,-10,10,10&text=%23organic%20-%23vegan");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
 }
 
 #[test]
-fn search_with_tags() {
+fn search_by_organization() {
     let entries = vec![
         usecases::NewPlace {
-            categories: vec![Category::ID_NON_PROFIT.to_string()],
+            tags: vec!["foo".to_string()],
             ..default_new_entry()
         },
         usecases::NewPlace {
-            categories: vec![Category::ID_NON_PROFIT.to_string()],
-            tags: vec!["bla-blubb".to_string(), "foo-bar".to_string()],
+            tags: vec!["bar".to_string(), "baz".to_string()],
             ..default_new_entry()
         },
         usecases::NewPlace {
-            categories: vec![Category::ID_NON_PROFIT.to_string()],
-            tags: vec!["foo-bar".to_string()],
+            tags: vec!["baz".to_string()],
             ..default_new_entry()
         },
     ];
     let (client, connections, mut search_engine, notify) = setup2();
-    connections
-        .exclusive()
-        .unwrap()
-        .create_tag_if_it_does_not_exist(&Tag {
-            id: Category::TAG_NON_PROFIT.into(),
-        })
-        .unwrap();
-    connections
-        .exclusive()
-        .unwrap()
-        .create_tag_if_it_does_not_exist(&Tag {
-            id: "foo-bar".into(),
-        })
-        .unwrap();
-    connections
-        .exclusive()
-        .unwrap()
-        .create_tag_if_it_does_not_exist(&Tag {
-            id: "bla-blubb".into(),
-        })
-        .unwrap();
     let place_ids: Vec<_> = entries
         .into_iter()
         .map(|e| {
@@ -594,25 +1881,38 @@ fn search_with_tags() {
                 .to_string()
         })
         .collect();
+    for (id, owned_tags) in &[("a", vec!["foo", "bar"]), ("b", vec!["baz"])] {
+        create_org_with_api_token(
+            &*connections.exclusive().unwrap(),
+            Organization {
+                id: (*id).into(),
+                name: format!("Org {}", id),
+                owned_tags: owned_tags.iter().map(|t| (*t).to_string()).collect(),
+            },
+            id,
+        );
+    }
+    flows::rebuild_index(&connections, &mut search_engine).unwrap();
 
-    let req = client.get("/search?bbox=-10,-10,10,10&tags=bla-blubb");
-    let mut response = req.dispatch();
-    assert_eq!(response.status(), Status::Ok);
-    test_json(&response);
-    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(body_str.contains(&format!(
-        "\"visible\":[{{\"id\":\"{}\",\"status\":\"created\",\"lat\":0.0,\"lng\":0.0,\"title\":\"\",\"description\":\"\",\"categories\":[\"{}\"],\"tags\":[\"bla-blubb\",\"foo-bar\"],\"ratings\":{{\"total\":0.0,\"diversity\":0.0,\"fairness\":0.0,\"humanity\":0.0,\"renewable\":0.0,\"solidarity\":0.0,\"transparency\":0.0}}}}]",
-        place_ids[1],
-        Category::ID_NON_PROFIT,
-    )));
+    let search = |org_id: &str| {
+        let mut response = client
+            .get(format!("/search?bbox=-10,-10,10,10&org_id={}", org_id))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+        let response: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+        let mut ids: Vec<_> = response.visible.into_iter().map(|p| p.id).collect();
+        ids.sort_unstable();
+        ids
+    };
 
-    let req = client.get("/search?bbox=-10,-10,10,10&tags=foo-bar");
-    let mut response = req.dispatch();
-    assert_eq!(response.status(), Status::Ok);
-    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(!body_str.contains(&format!("\"{}\"", place_ids[0])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[1])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[2])));
+    let mut expected = vec![place_ids[0].clone(), place_ids[1].clone()];
+    expected.sort_unstable();
+    assert_eq!(expected, search("a"));
+    let mut expected = vec![place_ids[1].clone(), place_ids[2].clone()];
+    expected.sort_unstable();
+    assert_eq!(expected, search("b"));
+    assert!(search("c").is_empty());
 }
 
 #[test]
@@ -982,6 +2282,7 @@ fn search_with_status() {
         email_confirmed: true,
         password: "secret".parse::<Password>().unwrap(),
         role: Role::Admin,
+        language: None,
     };
     connections.exclusive().unwrap().create_user(&user).unwrap();
     let response = client
@@ -1030,6 +2331,18 @@ fn search_with_status() {
             assert!(!body_str.contains(&format!("\"{}\"", other_status)));
         }
     }
+
+    // Rejected and archived places are only found by moderators
+    let response = client.post("/logout").header(ContentType::JSON).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    for (_, status) in &places[2..] {
+        let req = client.get(format!("/search?bbox=-10,-10,10,10&status={}", status));
+        let response = req.dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+    let req = client.get("/search?bbox=-10,-10,10,10&status=created,confirmed");
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
 }
 
 #[test]
@@ -1071,7 +2384,7 @@ fn create_rating() {
         connections
             .shared()
             .unwrap()
-            .load_ratings_of_place("foo")
+            .load_ratings_of_place(&"foo".into())
             .unwrap()[0]
             .value,
         RatingValue::from(1)
@@ -1079,6 +2392,264 @@ fn create_rating() {
     test_json(&response);
 }
 
+#[test]
+fn create_multiple_ratings_at_once() {
+    let (client, connections, _, _) = setup2();
+    connections
+        .exclusive()
+        .unwrap()
+        .create_or_update_place(Place::build().id("foo").finish())
+        .unwrap();
+
+    // Duplicate contexts are rejected as a whole
+    let response = client
+        .post("/ratings/batch")
+        .header(ContentType::JSON)
+        .body(
+            r#"[{"value":1,"context":"fairness","entry":"foo","comment":"a","title":"t"},
+                {"value":2,"context":"fairness","entry":"foo","comment":"b","title":"t"}]"#,
+        )
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    assert!(connections
+        .shared()
+        .unwrap()
+        .load_ratings_of_place(&"foo".into())
+        .unwrap()
+        .is_empty());
+
+    let mut response = client
+        .post("/ratings/batch")
+        .header(ContentType::JSON)
+        .body(
+            r#"[{"value":1,"context":"fairness","entry":"foo","comment":"a","title":"t"},
+                {"value":2,"context":"humanity","entry":"foo","comment":"b","title":"t"}]"#,
+        )
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let rating_ids: Vec<String> = serde_json::from_str(&body_str).unwrap();
+    let ratings = connections
+        .shared()
+        .unwrap()
+        .load_ratings_of_place(&"foo".into())
+        .unwrap();
+    assert_eq!(2, ratings.len());
+    for r in ratings {
+        assert!(rating_ids.contains(&r.id.to_string()));
+    }
+}
+
+#[test]
+fn stream_changes_of_an_entry() {
+    let (client, connections, _, _) = setup2();
+    for id in &["foo", "bar"] {
+        connections
+            .exclusive()
+            .unwrap()
+            .create_or_update_place(Place::build().id(id).finish())
+            .unwrap();
+    }
+    for entry in &["bar", "foo"] {
+        let response = client
+            .post("/ratings")
+            .header(ContentType::JSON)
+            .body(format!(
+                r#"{{"value": 1,"context":"fairness","entry":"{}","comment":"test","title":"t"}}"#,
+                entry
+            ))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+    let rating_id = connections
+        .shared()
+        .unwrap()
+        .load_ratings_of_place(&"foo".into())
+        .unwrap()[0]
+        .id
+        .to_string();
+
+    let mut response = client
+        .get("/entries/foo/events/stream")
+        .header(rocket::http::Header::new("Last-Event-ID", "1"))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.content_type(),
+        Some(ContentType::new("text", "event-stream"))
+    );
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!(
+        "id: 3\nevent: rating\ndata: {{\"id\":\"{}\"}}\n\n",
+        rating_id
+    )));
+    assert!(body_str.contains("id: 4\nevent: comment\n"));
+    assert!(body_str.ends_with("id: 4\n\n"));
+
+    // Changes of other entries are skipped
+    let mut response = client
+        .get("/entries/bar/events/stream?last_event_id=1")
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains("id: 2\nevent: comment\n"));
+    assert!(!body_str.contains("id: 3\nevent"));
+
+    let response = client.get("/entries/baz/events/stream").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+
+    // Clients reconnect later if too many clients are waiting
+    let clients: Vec<_> =
+        std::iter::from_fn(|| connections.live_changes().start_streaming()).collect();
+    assert!(!clients.is_empty());
+    let mut response = client.get("/entries/foo/events/stream").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        "retry: 10000\n\n",
+        response.body().and_then(|b| b.into_string()).unwrap()
+    );
+    drop(clients);
+}
+
+#[test]
+fn create_rating_with_login_required() {
+    let (client, connections, _) = crate::ports::web::tests::setup_with_cfg(
+        vec![("/", routes())],
+        crate::ports::web::Cfg {
+            ratings_require_login: true,
+            ..Default::default()
+        },
+    );
+    connections
+        .exclusive()
+        .unwrap()
+        .create_or_update_place(Place::build().id("foo").finish())
+        .unwrap();
+    let body =
+        r#"{"value": 1,"context":"fairness","entry":"foo","comment":"test", "title":"idontcare"}"#;
+
+    // Anonymous
+    let response = client
+        .post("/ratings")
+        .header(ContentType::JSON)
+        .body(body)
+        .dispatch();
+    assert_eq!(response.status().code, 401);
+    assert_eq!(response.status().reason, "LoginRequired");
+
+    // Logged in with a confirmed email address
+    crate::ports::web::tests::register_user(&connections, "foo@bar.com", "secret", true);
+    let login = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "foo@bar.com", "password": "secret"}"#)
+        .dispatch();
+    assert_eq!(login.status(), Status::Ok);
+    let response = client
+        .post("/ratings")
+        .header(ContentType::JSON)
+        .body(body)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        connections
+            .shared()
+            .unwrap()
+            .load_ratings_of_place(&"foo".into())
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn update_and_retract_own_rating() {
+    let (client, connections, _, _) = setup2();
+    connections
+        .exclusive()
+        .unwrap()
+        .create_or_update_place(Place::build().id("foo").finish())
+        .unwrap();
+    crate::ports::web::tests::register_user(&connections, "foo@bar.com", "secret", true);
+    crate::ports::web::tests::register_user(&connections, "baz@bar.com", "secret", true);
+    let login = |email: &str| {
+        let response = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(format!(r#"{{"email": "{}", "password": "secret"}}"#, email))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    };
+    let load_ratings = || {
+        connections
+            .shared()
+            .unwrap()
+            .load_ratings_of_place(&"foo".into())
+            .unwrap()
+    };
+
+    // The author cannot be chosen by anonymous clients
+    let response = client
+        .post("/ratings")
+        .header(ContentType::JSON)
+        .body(r#"{"value": 1,"context":"fairness","entry":"foo","comment":"test", "title":"anonymous", "user":"foo@bar.com"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let anonymous_id = load_ratings()[0].id.clone();
+    assert!(load_ratings()[0].created_by.is_none());
+
+    login("foo@bar.com");
+    let response = client
+        .put(format!("/ratings/{}", anonymous_id))
+        .header(ContentType::JSON)
+        .body(r#"{"title":"changed","value":2}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+    let response = client
+        .post("/ratings")
+        .header(ContentType::JSON)
+        .body(r#"{"value": 1,"context":"humanity","entry":"foo","comment":"test", "title":"mine"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let rating = load_ratings()
+        .into_iter()
+        .find(|r| r.title == "mine")
+        .unwrap();
+    assert_eq!(Some("foo@bar.com"), rating.created_by.as_deref());
+
+    // Only the author can change the rating
+    login("baz@bar.com");
+    let response = client
+        .put(format!("/ratings/{}", rating.id))
+        .header(ContentType::JSON)
+        .body(r#"{"title":"changed","value":2}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+    let response = client.delete(format!("/ratings/{}", rating.id)).dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    login("foo@bar.com");
+    let response = client
+        .put(format!("/ratings/{}", rating.id))
+        .header(ContentType::JSON)
+        .body(r#"{"title":"changed","value":2}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+    let changed = load_ratings()
+        .into_iter()
+        .find(|r| r.id == rating.id)
+        .unwrap();
+    assert_eq!("changed", changed.title);
+    assert_eq!(RatingValue::from(2), changed.value);
+
+    let response = client.delete(format!("/ratings/{}", rating.id)).dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+    assert_eq!(1, load_ratings().len());
+    let response = client.delete(format!("/ratings/{}", rating.id)).dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
+
 #[test]
 fn get_one_rating() {
     let e = Place::build().id("foo").finish();
@@ -1100,24 +2671,85 @@ fn get_one_rating() {
             comment: "bla".into(),
             source: Some("blabla".into()),
         },
+        None,
+        false,
     )
     .unwrap();
     let rid = connections
         .shared()
         .unwrap()
-        .load_ratings_of_place("foo")
+        .load_ratings_of_place(&"foo".into())
         .unwrap()[0]
         .id
         .clone();
     let req = client.get(format!("/ratings/{}", rid));
     let mut response = req.dispatch();
     assert_eq!(response.status(), Status::Ok);
-    test_json(&response);
-    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert_eq!(body_str.as_str().chars().next().unwrap(), '[');
-    let ratings: Vec<json::Rating> = serde_json::from_str(&body_str).unwrap();
-    assert_eq!(ratings[0].comments.len(), 1);
-    assert_eq!(ratings[0].id, rid.to_string());
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert_eq!(body_str.as_str().chars().next().unwrap(), '[');
+    let ratings: Vec<json::Rating> = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(ratings[0].comments.len(), 1);
+    assert_eq!(ratings[0].id, rid.to_string());
+}
+
+#[test]
+fn get_rating_timeline_of_place() {
+    let (client, connections, _, _) = setup2();
+    connections
+        .exclusive()
+        .unwrap()
+        .create_or_update_place(Place::build().id("foo").finish())
+        .unwrap();
+    for (id, created_at, context, value) in &[
+        ("1", 1_583_020_800, RatingContext::Diversity, -1), // 2020-03-01
+        ("2", 1_579_046_400, RatingContext::Fairness, 2),   // 2020-01-15
+        ("3", 1_579_046_400, RatingContext::Fairness, 0),   // 2020-01-15
+    ] {
+        connections
+            .exclusive()
+            .unwrap()
+            .create_rating(Rating {
+                id: (*id).into(),
+                place_id: "foo".into(),
+                created_at: TimestampMs::from_seconds(*created_at),
+                created_by: None,
+                archived_at: None,
+                title: "title".into(),
+                value: RatingValue::from(*value),
+                context: *context,
+                source: None,
+            })
+            .unwrap();
+    }
+
+    let timeline = || {
+        let mut response = client.get("/entries/foo/ratings/timeline").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        test_json(&response);
+        let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+        serde_json::from_str::<Vec<json::MonthlyRatings>>(&body_str).unwrap()
+    };
+    let months = timeline();
+    assert_eq!(2, months.len());
+    assert_eq!("2020-01", months[0].month);
+    assert_eq!(2, months[0].ratings.count);
+    assert!((f64::from(months[0].ratings.fairness) - 1.0).abs() < f64::EPSILON);
+    assert_eq!("2020-03", months[1].month);
+    assert_eq!(1, months[1].ratings.count);
+    assert!((f64::from(months[1].ratings.diversity) + 1.0).abs() < f64::EPSILON);
+
+    // New ratings are included immediately
+    let response = client
+        .post("/ratings")
+        .header(ContentType::JSON)
+        .body(r#"{"value":1,"context":"fairness","entry":"foo","comment":"test","title":"title"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(3, timeline().len());
+
+    let response = client.get("/entries/bar/ratings/timeline").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
 }
 
 #[test]
@@ -1147,6 +2779,8 @@ fn ratings_with_and_without_source() {
             comment: "bla".into(),
             source: Some("blabla blabla".into()),
         },
+        None,
+        false,
     )
     .unwrap();
     flows::create_rating(
@@ -1161,13 +2795,15 @@ fn ratings_with_and_without_source() {
             comment: "bla".into(),
             source: Some("blabla blabla".into()),
         },
+        None,
+        false,
     )
     .unwrap();
 
     let rid = connections
         .shared()
         .unwrap()
-        .load_ratings_of_place("bar")
+        .load_ratings_of_place(&"bar".into())
         .unwrap()[0]
         .id
         .clone();
@@ -1233,6 +2869,7 @@ fn login_with_valid_credentials() {
         email_confirmed: true,
         password: "secret".parse::<Password>().unwrap(),
         role: Role::Guest,
+        language: None,
     }];
     for u in users {
         db.exclusive().unwrap().create_user(&u).unwrap();
@@ -1255,6 +2892,7 @@ fn login_logout_succeeds() {
         email_confirmed: true,
         password: "secret".parse::<Password>().unwrap(),
         role: Role::Guest,
+        language: None,
     }];
     for u in users {
         db.exclusive().unwrap().create_user(&u).unwrap();
@@ -1279,6 +2917,43 @@ fn login_logout_succeeds() {
     assert!(cookie.value().is_empty());
 }
 
+#[test]
+fn delete_own_user_account() {
+    let (client, db) = setup();
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "foo@bar".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Guest,
+            language: None,
+        })
+        .unwrap();
+
+    // Not logged in
+    let response = client.delete("/users/me").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "foo@bar", "password": "secret"}"#)
+        .dispatch();
+    let cookie = user_id_cookie(&response).expect("login cookie");
+
+    let response = client.delete("/users/me").cookie(cookie).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let cookie = user_id_cookie(&response).expect("logout cookie");
+    assert!(cookie.value().is_empty());
+    assert!(db
+        .shared()
+        .unwrap()
+        .try_get_user_by_email("foo@bar")
+        .unwrap()
+        .is_none());
+}
+
 #[test]
 fn confirm_email_address() {
     let (client, db) = setup();
@@ -1287,6 +2962,7 @@ fn confirm_email_address() {
         email_confirmed: false,
         password: "secret".parse::<Password>().unwrap(),
         role: Role::Guest,
+        language: None,
     }];
     for u in users {
         db.exclusive().unwrap().create_user(&u).unwrap();
@@ -1298,7 +2974,7 @@ fn confirm_email_address() {
         .body(r#"{"email": "a@bar.de", "password": "secret"}"#)
         .dispatch();
 
-    assert_eq!(response.status(), Status::Forbidden);
+    assert_eq!(response.status(), <Status>::new(403, "EmailNotConfirmed"));
     assert_eq!(
         db.exclusive().unwrap().all_users().unwrap()[0].email_confirmed,
         false
@@ -1349,6 +3025,7 @@ fn send_confirmation_email() {
         email_confirmed: false,
         password: "secret".parse::<Password>().unwrap(),
         role: Role::Guest,
+        language: None,
     }];
     for u in users {
         db.exclusive().unwrap().create_user(&u).unwrap();
@@ -1370,6 +3047,7 @@ fn subscribe_to_bbox() {
         email_confirmed: true,
         password: "secret".parse::<Password>().unwrap(),
         role: Role::Guest,
+        language: None,
     }];
     for u in users {
         db.exclusive().unwrap().create_user(&u).unwrap();
@@ -1389,6 +3067,103 @@ fn subscribe_to_bbox() {
     assert_eq!(response.status(), Status::Ok);
 }
 
+#[test]
+fn subscribe_to_bbox_exceeding_quota() {
+    let (client, connections, _) = crate::ports::web::tests::setup_with_cfg(
+        vec![("/", routes())],
+        crate::ports::web::Cfg {
+            subscription_quota: usecases::SubscriptionQuota {
+                max_bbox_subscriptions: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+    connections
+        .exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "foo@bar".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Guest,
+            language: None,
+        })
+        .unwrap();
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "foo@bar", "password": "secret"}"#)
+        .dispatch();
+    let cookie = user_id_cookie(&response).unwrap();
+    let response = client
+        .post("/subscribe-to-bbox")
+        .header(ContentType::JSON)
+        .cookie(cookie)
+        .body(r#"[{"lat":-10.0,"lng":-10.0},{"lat":10.0,"lng":10.0}]"#)
+        .dispatch();
+    assert_eq!(response.status().code, 403);
+    assert_eq!(response.status().reason, "SubscriptionQuotaExceeded");
+    assert!(connections
+        .shared()
+        .unwrap()
+        .all_bbox_subscriptions()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn atom_feed_of_recently_changed_entries() {
+    let (client, db) = setup();
+    let places = vec![
+        Place::build()
+            .id("market")
+            .title("Farmers & market")
+            .pos(MapPoint::from_lat_lng_deg(1.0, 1.0))
+            .tags(vec!["organic"])
+            .finish(),
+        Place::build()
+            .id("shop")
+            .title("Shop")
+            .pos(MapPoint::from_lat_lng_deg(1.0, 1.0))
+            .finish(),
+        Place::build()
+            .id("elsewhere")
+            .title("Elsewhere")
+            .pos(MapPoint::from_lat_lng_deg(5.0, 5.0))
+            .tags(vec!["organic"])
+            .finish(),
+    ];
+    for place in places {
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(place)
+            .unwrap();
+    }
+
+    let mut response = client.get("/feed/entries.atom").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        Some("application/atom+xml; charset=utf-8"),
+        response.headers().get_one("Content-Type")
+    );
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#));
+    assert_eq!(3, body_str.matches("<entry>").count());
+    assert!(body_str.contains("<title>Farmers &amp; market</title>"));
+
+    let mut response = client
+        .get("/feed/entries.atom?bbox=0,0,2,2&tags=Organic")
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert_eq!(1, body_str.matches("<entry>").count());
+    assert!(body_str.contains("?entry=market"));
+
+    let response = client.get("/feed/entries.atom?bbox=foo").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
 #[test]
 fn recently_changed_entries() {
     // Check that the requests succeeds on an empty database just
@@ -1463,38 +3238,147 @@ fn recently_changed_entries() {
 }
 
 #[test]
-fn count_most_popular_tags_on_empty_db_to_verify_sql() {
-    // Check that the requests succeeds on an empty database just
-    // to verify that the literal SQL query that is not verified
-    // at compile-time still matches the current database schema!
-    let (client, _) = setup();
-    // All parameters
-    let mut response = client
-        .get("/entries/most-popular-tags?offset=10&limit=1000&min_count=10&max_count=100")
-        .dispatch();
+fn recently_changed_entries_within_same_second() {
+    let (client, db) = setup();
+
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(Place::build().id("first").finish())
+        .unwrap();
+    // Time stamps are stored with millisecond precision
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(Place::build().id("second").finish())
+        .unwrap();
+
+    let mut response = client.get("/entries/recently-changed").dispatch();
     assert_eq!(response.status(), Status::Ok);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert_eq!(body_str, "[]");
+    let first_pos = body_str.find("\"id\":\"first\"").unwrap();
+    let second_pos = body_str.find("\"id\":\"second\"").unwrap();
+    // Most recent changes first
+    assert!(second_pos < first_pos);
+}
 
-    // Only offset parameter
-    let response = client.get("/entries/most-popular-tags?offset=1").dispatch();
-    assert_eq!(response.status(), Status::Ok);
+#[test]
+fn recently_changed_entries_with_cursor() {
+    let (client, db) = setup();
+    for id in &["a", "b", "c"] {
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(Place::build().id(id).finish())
+            .unwrap();
+    }
 
-    // Only limit parameter
-    let response = client.get("/entries/most-popular-tags?limit=1").dispatch();
+    let mut response = client.get("/entries/recently-changed?limit=2").dispatch();
     assert_eq!(response.status(), Status::Ok);
+    let cursor = response
+        .headers()
+        .get_one("X-Next-Cursor")
+        .unwrap()
+        .to_string();
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let entries: Vec<json::Entry> = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(
+        vec!["c", "b"],
+        entries.iter().map(|e| e.id.as_str()).collect::<Vec<_>>()
+    );
 
-    // Only min_count parameter
-    let response = client
-        .get("/entries/most-popular-tags?min_count=1")
+    // Changes that happen in the meantime don't affect the next page
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(Place::build().id("d").finish())
+        .unwrap();
+    let mut response = client
+        .get(format!(
+            "/entries/recently-changed?limit=2&cursor={}",
+            cursor
+        ))
         .dispatch();
     assert_eq!(response.status(), Status::Ok);
+    assert!(response.headers().get_one("X-Next-Cursor").is_none());
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let entries: Vec<json::Entry> = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(
+        vec!["a"],
+        entries.iter().map(|e| e.id.as_str()).collect::<Vec<_>>()
+    );
 
-    // Only max_count parameter
     let response = client
-        .get("/entries/most-popular-tags?max_count=1")
+        .get("/entries/recently-changed?cursor=invalid")
         .dispatch();
-    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn count_most_popular_tags() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    crate::ports::web::tests::register_user(&connections, "scout@example.com", "secret", true);
+    let ids: Vec<_> = vec![
+        vec!["a", "b"],
+        vec!["a", "b", "c"],
+        vec!["a"],
+        vec!["c", "d"],
+    ]
+    .into_iter()
+    .map(|tags| {
+        let new_place = usecases::NewPlace {
+            tags: tags.into_iter().map(Into::into).collect(),
+            ..new_entry_with_text("place", "bla", 1.0, 1.0)
+        };
+        flows::create_place(&connections, &mut search_engine, &notify, new_place, None)
+            .unwrap()
+            .id
+            .to_string()
+    })
+    .collect();
+    // Tags of archived places are not counted
+    flows::review_places(
+        &connections,
+        &mut search_engine,
+        &notify,
+        &[ids[3].as_str()],
+        usecases::Review {
+            context: None,
+            reviewer_email: "scout@example.com".into(),
+            status: ReviewStatus::Archived,
+            comment: None,
+        },
+    )
+    .unwrap();
+
+    let most_popular_tags = |query: &str| {
+        let mut response = client
+            .get(format!("/entries/most-popular-tags{}", query))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        test_json(&response);
+        let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+        serde_json::from_str::<Vec<json::TagFrequency>>(&body_str)
+            .unwrap()
+            .into_iter()
+            .map(|json::TagFrequency(tag, count)| (tag, count))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        vec![("a".into(), 3), ("b".into(), 2), ("c".into(), 1)],
+        most_popular_tags("")
+    );
+    assert_eq!(
+        vec![("b".into(), 2), ("c".into(), 1)],
+        most_popular_tags("?max_count=2")
+    );
+    assert_eq!(
+        vec![("a".into(), 3), ("b".into(), 2)],
+        most_popular_tags("?min_count=2")
+    );
+    assert_eq!(
+        vec![("b".into(), 2)],
+        most_popular_tags("?offset=1&limit=1")
+    );
+    assert!(most_popular_tags("?offset=10&limit=1000&min_count=10&max_count=100").is_empty());
 }
 
 #[test]
@@ -1521,18 +3405,21 @@ fn entries_export_csv() {
             email_confirmed: true,
             password: "secret".parse::<Password>().unwrap(),
             role: Role::Admin,
+            language: None,
         },
         User {
             email: "scout@example.com".into(),
             email_confirmed: true,
             password: "secret".parse::<Password>().unwrap(),
             role: Role::Scout,
+            language: None,
         },
         User {
             email: "user@example.com".into(),
             email_confirmed: true,
             password: "secret".parse::<Password>().unwrap(),
             role: Role::User,
+            language: None,
         },
     ];
     for u in users {
@@ -1613,7 +3500,8 @@ fn entries_export_csv() {
         .create_rating(Rating {
             id: "123".into(),
             place_id: "entry1".into(),
-            created_at: Timestamp::from_seconds(123),
+            created_at: TimestampMs::from_seconds(123),
+            created_by: None,
             archived_at: None,
             title: "rating1".into(),
             value: RatingValue::from(2),
@@ -1626,7 +3514,8 @@ fn entries_export_csv() {
         .create_rating(Rating {
             id: "345".into(),
             place_id: "entry1".into(),
-            created_at: Timestamp::from_seconds(123),
+            created_at: TimestampMs::from_seconds(123),
+            created_by: None,
             archived_at: None,
             title: "rating2".into(),
             value: RatingValue::from(1),
@@ -1640,7 +3529,7 @@ fn entries_export_csv() {
         let ratings = db
             .shared()
             .unwrap()
-            .load_ratings_of_place(place.id.as_ref())
+            .load_ratings_of_place(&place.id.as_str().into())
             .unwrap();
         search_engine
             .add_or_update_place(&place, *status, &place.avg_ratings(&ratings))
@@ -1666,10 +3555,10 @@ fn entries_export_csv() {
     }
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
     //eprintln!("{}", body_str);
-    assert!(body_str.starts_with("id,created_at,created_by,version,title,description,lat,lng,street,zip,city,country,state,homepage,contact_email,contact_phone,opening_hours,categories,tags,license,image_url,image_link_url,avg_rating\n"));
-    assert!(body_str.contains(&format!("entry1,1111,user@example.com,0,title1,desc1,{lat},{lng},street1,zip1,city1,country1,state1,http://homepage1/,,,24/7,\"{cat1},{cat2}\",\"bla,bli\",license1,https://img/,\"https://img,link/\",0.25\n", lat = LatCoord::from_deg(0.1).to_deg(), lng = LngCoord::from_deg(0.2).to_deg(), cat1 = Category::ID_NON_PROFIT, cat2 = Category::ID_COMMERCIAL)));
+    assert!(body_str.starts_with("id,created_at,created_by,version,title,description,lat,lng,street,zip,city,country,state,homepage,contact_email,contact_phone,opening_hours,categories,tags,license,image_url,image_link_url,avg_rating,dataset_license\n"));
+    assert!(body_str.contains(&format!("entry1,1111,user@example.com,0,title1,desc1,{lat},{lng},street1,zip1,city1,country1,state1,http://homepage1/,,,24/7,\"{cat1},{cat2}\",\"bla,bli\",license1,https://img/,\"https://img,link/\",0.25,\n", lat = LatCoord::from_deg(0.1).to_deg(), lng = LngCoord::from_deg(0.2).to_deg(), cat1 = Category::ID_NON_PROFIT, cat2 = Category::ID_COMMERCIAL)));
     assert!(body_str.contains(&format!(
-        "entry2,2222,,0,,,0.0,0.0,,,,,,,,,,{cat},,,,,0.0\n",
+        "entry2,2222,,0,,,0.0,0.0,,,,,,,,,,{cat},,,,,0.0,\n",
         cat = Category::ID_NON_PROFIT
     )));
     assert!(!body_str.contains("entry3"));
@@ -1692,10 +3581,10 @@ fn entries_export_csv() {
     }
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
     //eprintln!("{}", body_str);
-    assert!(body_str.starts_with("id,created_at,created_by,version,title,description,lat,lng,street,zip,city,country,state,homepage,contact_email,contact_phone,opening_hours,categories,tags,license,image_url,image_link_url,avg_rating\n"));
-    assert!(body_str.contains(&format!("entry1,1111,,0,title1,desc1,{lat},{lng},street1,zip1,city1,country1,state1,http://homepage1/,,,24/7,\"{cat1},{cat2}\",\"bla,bli\",license1,https://img/,\"https://img,link/\",0.25\n", lat = LatCoord::from_deg(0.1).to_deg(), lng = LngCoord::from_deg(0.2).to_deg(), cat1 = Category::ID_NON_PROFIT, cat2 = Category::ID_COMMERCIAL)));
+    assert!(body_str.starts_with("id,created_at,created_by,version,title,description,lat,lng,street,zip,city,country,state,homepage,contact_email,contact_phone,opening_hours,categories,tags,license,image_url,image_link_url,avg_rating,dataset_license\n"));
+    assert!(body_str.contains(&format!("entry1,1111,,0,title1,desc1,{lat},{lng},street1,zip1,city1,country1,state1,http://homepage1/,,,24/7,\"{cat1},{cat2}\",\"bla,bli\",license1,https://img/,\"https://img,link/\",0.25,\n", lat = LatCoord::from_deg(0.1).to_deg(), lng = LngCoord::from_deg(0.2).to_deg(), cat1 = Category::ID_NON_PROFIT, cat2 = Category::ID_COMMERCIAL)));
     assert!(body_str.contains(&format!(
-        "entry2,2222,,0,,,0.0,0.0,,,,,,,,,,{cat},,,,,0.0\n",
+        "entry2,2222,,0,,,0.0,0.0,,,,,,,,,,{cat},,,,,0.0,\n",
         cat = Category::ID_NON_PROFIT
     )));
     assert!(!body_str.contains("entry3"));
@@ -1712,3 +3601,194 @@ fn entries_export_csv() {
     let response = req.dispatch();
     assert_eq!(response.status(), Status::Unauthorized);
 }
+
+#[test]
+fn export_in_other_formats() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    crate::ports::web::tests::register_user(&connections, "scout@example.com", "secret", true);
+    let mut scout = connections
+        .shared()
+        .unwrap()
+        .get_user_by_email("scout@example.com")
+        .unwrap();
+    scout.role = Role::Scout;
+    connections
+        .exclusive()
+        .unwrap()
+        .update_user(&scout)
+        .unwrap();
+    flows::create_place(
+        &connections,
+        &mut search_engine,
+        &notify,
+        new_entry_with_text("Organic market", "bla", 1.0, 2.0),
+        None,
+    )
+    .unwrap();
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "scout@example.com", "password": "secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let mut response = client
+        .get("/export/entries.geojson?bbox=0,0,2,2")
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        Some("application/geo+json"),
+        response.headers().get_one("Content-Type")
+    );
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let geojson: serde_json::Value = serde_json::from_str(&body_str).unwrap();
+    assert_eq!("FeatureCollection", geojson["type"]);
+    assert_eq!(
+        "Organic market",
+        geojson["features"][0]["properties"]["title"]
+    );
+
+    let mut response = client.get("/export/entries.jsonl?bbox=0,0,2,2").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert_eq!(1, body_str.lines().count());
+
+    let mut response = client.get("/export/events.kml").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains("<Document>"));
+
+    let response = client.get("/export/entries.pdf?bbox=0,0,2,2").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    let response = client.get("/export/foo.csv").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[test]
+fn export_places_as_csv_stream() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    crate::ports::web::tests::register_user(&connections, "scout@example.com", "secret", true);
+    let mut scout = connections
+        .shared()
+        .unwrap()
+        .get_user_by_email("scout@example.com")
+        .unwrap();
+    scout.role = Role::Scout;
+    connections
+        .exclusive()
+        .unwrap()
+        .update_user(&scout)
+        .unwrap();
+    for i in 0..3 {
+        let new_place = usecases::NewPlace {
+            email: Some(format!("market{}@example.com", i)),
+            ..new_entry_with_text(&format!("Market {}", i), "bla", 1.0, 1.0)
+        };
+        flows::create_place(&connections, &mut search_engine, &notify, new_place, None).unwrap();
+    }
+    flows::create_place(
+        &connections,
+        &mut search_engine,
+        &notify,
+        new_entry_with_text("Elsewhere", "bla", 5.0, 5.0),
+        None,
+    )
+    .unwrap();
+
+    // Anonymous exports don't contain any contact details
+    let mut response = client.get("/export/places.csv?bbox=0,0,2,2").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        Some("text/csv; charset=utf-8"),
+        response.headers().get_one("Content-Type")
+    );
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.starts_with("id,created_at,created_by,version,title,"));
+    assert_eq!(4, body_str.lines().count());
+    assert!(body_str.contains("Market 2"));
+    assert!(!body_str.contains("Elsewhere"));
+    assert!(!body_str.contains("@example.com"));
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "scout@example.com", "password": "secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let mut response = client.get("/export/places.csv?bbox=0,0,2,2").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert_eq!(4, body_str.lines().count());
+    assert!(body_str.contains("market0@example.com"));
+
+    let response = client.get("/export/places.csv").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn replace_external_ids_of_places() {
+    let (client, db) = setup();
+    crate::ports::web::tests::register_user(&db, "scout@example.com", "secret", true);
+    let mut scout = db
+        .shared()
+        .unwrap()
+        .get_user_by_email("scout@example.com")
+        .unwrap();
+    scout.role = Role::Scout;
+    db.exclusive().unwrap().update_user(&scout).unwrap();
+    for id in &["foo", "bar"] {
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(Place::build().id(id).finish())
+            .unwrap();
+    }
+    let external_ids = |id: &str| -> Vec<json::ExternalId> {
+        let mut res = client
+            .get(format!("/places/{}/external-ids", id))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+        serde_json::from_str(&body_str).unwrap()
+    };
+    let replace = |id: &str, body: &str| {
+        client
+            .put(format!("/places/{}/external-ids", id))
+            .header(ContentType::JSON)
+            .body(body)
+            .dispatch()
+            .status()
+    };
+    let body = r#"[{"source":"wikidata","id":"Q42"},{"source":"OSM","id":"node/1","url":"https://www.openstreetmap.org/node/1"}]"#;
+
+    assert!(external_ids("foo").is_empty());
+    assert_eq!(Status::Unauthorized, replace("foo", body));
+
+    let res = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "scout@example.com", "password": "secret"}"#)
+        .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    assert_eq!(Status::Ok, replace("foo", body));
+    let ids = external_ids("foo");
+    assert_eq!(2, ids.len());
+    assert_eq!("osm", ids[0].source);
+    assert_eq!("node/1", ids[0].id);
+    assert!(ids[0].url.is_some());
+    assert_eq!("wikidata", ids[1].source);
+
+    // Each record of a data source is only referenced by a single place
+    assert_eq!(
+        409,
+        replace("bar", r#"[{"source":"osm","id":"node/1"}]"#).code
+    );
+    assert!(external_ids("bar").is_empty());
+    assert_eq!(
+        Status::BadRequest,
+        replace("bar", r#"[{"source":"","id":"1"}]"#)
+    );
+    assert_eq!(Status::NotFound, replace("baz", "[]"));
+
+    assert_eq!(Status::Ok, replace("foo", "[]"));
+    assert!(external_ids("foo").is_empty());
+}
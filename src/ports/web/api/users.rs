@@ -28,6 +28,43 @@ pub fn post_request_password_reset(
     Ok(Json(()))
 }
 
+#[post(
+    "/users/login-link-request",
+    format = "application/json",
+    data = "<data>"
+)]
+pub fn post_request_login_link(
+    connections: sqlite::Connections,
+    notify: Notify,
+    data: Json<json::RequestLoginLink>,
+) -> Result<()> {
+    let req = data.into_inner();
+    flows::request_login_link(&connections, &*notify, &req.email)?;
+    Ok(Json(()))
+}
+
+#[post(
+    "/users/login-with-token",
+    format = "application/json",
+    data = "<data>"
+)]
+pub fn post_login_with_token(
+    connections: sqlite::Connections,
+    mut cookies: Cookies,
+    data: Json<json::LoginWithToken>,
+) -> Result<()> {
+    let req = data.into_inner();
+    let email_nonce = EmailNonce::decode_from_str(&req.token)
+        .map_err(|_| Error::Parameter(ParameterError::TokenInvalid))?;
+    flows::login_with_login_link(&connections, &email_nonce)?;
+    cookies.add_private(
+        Cookie::build(COOKIE_USER_KEY, email_nonce.email)
+            .same_site(rocket::http::SameSite::None)
+            .finish(),
+    );
+    Ok(Json(()))
+}
+
 #[post("/users/reset-password", format = "application/json", data = "<data>")]
 pub fn post_reset_password(
     connections: sqlite::Connections,
@@ -42,18 +79,214 @@ pub fn post_reset_password(
     Ok(Json(()))
 }
 
+#[delete("/users/me")]
+pub fn delete_current_user(
+    connections: sqlite::Connections,
+    login: Login,
+    mut cookies: Cookies,
+) -> Result<()> {
+    flows::anonymize_user(&connections, &login.0)?;
+    cookies.remove_private(Cookie::named(COOKIE_USER_KEY));
+    Ok(Json(()))
+}
+
 #[delete("/users/<email>")]
 pub fn delete_user(db: sqlite::Connections, user: Login, email: String) -> Result<()> {
     usecases::delete_user(&*db.exclusive()?, &user.0, &email)?;
     Ok(Json(()))
 }
 
+#[post(
+    "/admin/users/<email>/deactivate",
+    format = "application/json",
+    data = "<data>"
+)]
+pub fn post_deactivate_user(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    login: Login,
+    email: String,
+    data: Json<json::UserStatusChange>,
+) -> StatusResult {
+    let json::UserStatusChange { comment } = data.into_inner();
+    flows::deactivate_user(&connections, &mut search_engine, &login.0, &email, comment)?;
+    Ok(Status::NoContent)
+}
+
+#[post(
+    "/admin/users/<email>/reactivate",
+    format = "application/json",
+    data = "<data>"
+)]
+pub fn post_reactivate_user(
+    connections: sqlite::Connections,
+    login: Login,
+    email: String,
+    data: Json<json::UserStatusChange>,
+) -> StatusResult {
+    let json::UserStatusChange { comment } = data.into_inner();
+    flows::reactivate_user(&connections, &login.0, &email, comment)?;
+    Ok(Status::NoContent)
+}
+
+#[get("/admin/users/<email>/status-log")]
+pub fn get_user_status_log(
+    db: sqlite::Connections,
+    login: Login,
+    email: String,
+) -> Result<Vec<json::UserStatusLog>> {
+    let logs = usecases::load_user_status_logs(&*db.shared()?, &login.0, &email)?;
+    Ok(Json(logs.into_iter().map(Into::into).collect()))
+}
+
+#[get("/admin/users/<email>/permissions")]
+pub fn get_user_permissions(
+    db: sqlite::Connections,
+    login: Login,
+    email: String,
+) -> Result<Vec<json::Permission>> {
+    let permissions = usecases::load_user_permissions(&*db.shared()?, &login.0, &email)?;
+    Ok(Json(permissions.into_iter().map(Into::into).collect()))
+}
+
+#[post(
+    "/admin/users/<email>/permissions",
+    format = "application/json",
+    data = "<data>"
+)]
+pub fn post_user_permission(
+    db: sqlite::Connections,
+    login: Login,
+    email: String,
+    data: Json<json::PermissionChange>,
+) -> StatusResult {
+    let json::PermissionChange {
+        permission,
+        granted,
+    } = data.into_inner();
+    usecases::change_user_permission(
+        &*db.exclusive()?,
+        &login.0,
+        &email,
+        permission.into(),
+        granted,
+    )?;
+    Ok(Status::NoContent)
+}
+
+#[get("/admin/user-tokens")]
+pub fn get_user_tokens(db: sqlite::Connections, login: Login) -> Result<Vec<json::UserTokenInfo>> {
+    let tokens = usecases::load_valid_user_tokens(&*db.shared()?, &login.0)?;
+    Ok(Json(tokens.into_iter().map(Into::into).collect()))
+}
+
+#[get("/users/me/tokens")]
+pub fn get_user_api_tokens(
+    db: sqlite::Connections,
+    login: Login,
+) -> Result<Vec<json::UserApiToken>> {
+    let tokens = usecases::load_user_api_tokens(&*db.shared()?, &login.0)?;
+    Ok(Json(tokens.into_iter().map(Into::into).collect()))
+}
+
+#[post("/users/me/tokens", format = "application/json", data = "<data>")]
+pub fn post_user_api_token(
+    db: sqlite::Connections,
+    login: Login,
+    data: Json<json::NewUserApiToken>,
+) -> Result<json::UserApiToken> {
+    let json::NewUserApiToken { label } = data.into_inner();
+    let token = usecases::create_user_api_token(&*db.exclusive()?, &login.0, label)?;
+    let api_token = token.token.clone();
+    Ok(Json(json::UserApiToken {
+        token: Some(api_token),
+        ..token.into()
+    }))
+}
+
+#[delete("/users/me/tokens/<id>")]
+pub fn delete_user_api_token(db: sqlite::Connections, login: Login, id: String) -> StatusResult {
+    usecases::delete_user_api_token(&*db.exclusive()?, &login.0, &id)?;
+    Ok(Status::NoContent)
+}
+
 #[get("/users/current", format = "application/json")]
 pub fn get_current_user(db: sqlite::Connections, user: Login) -> Result<json::User> {
     let user = usecases::get_user(&*db.shared()?, &user.0, &user.0)?;
     Ok(Json(user.into()))
 }
 
+#[get("/users/current/scout-reports")]
+pub fn get_scout_report_settings(
+    db: sqlite::Connections,
+    user: Login,
+) -> Result<json::ScoutReportSettings> {
+    let enabled = usecases::scout_reports_enabled(&*db.shared()?, &user.0)?;
+    Ok(Json(json::ScoutReportSettings { enabled }))
+}
+
+#[post(
+    "/users/current/scout-reports",
+    format = "application/json",
+    data = "<data>"
+)]
+pub fn post_scout_report_settings(
+    db: sqlite::Connections,
+    user: Login,
+    data: Json<json::ScoutReportSettings>,
+) -> StatusResult {
+    let json::ScoutReportSettings { enabled } = data.into_inner();
+    usecases::enable_scout_reports(&*db.exclusive()?, &user.0, enabled)?;
+    Ok(Status::NoContent)
+}
+
+#[get("/users/current/notification-digest")]
+pub fn get_notification_digest_settings(
+    db: sqlite::Connections,
+    user: Login,
+) -> Result<json::NotificationDigestSettings> {
+    let interval = usecases::get_digest_interval(&*db.shared()?, &user.0)?;
+    Ok(Json(json::NotificationDigestSettings {
+        interval: interval.map(Into::into),
+    }))
+}
+
+#[post(
+    "/users/current/notification-digest",
+    format = "application/json",
+    data = "<data>"
+)]
+pub fn post_notification_digest_settings(
+    db: sqlite::Connections,
+    user: Login,
+    data: Json<json::NotificationDigestSettings>,
+) -> StatusResult {
+    let json::NotificationDigestSettings { interval } = data.into_inner();
+    usecases::set_digest_interval(&*db.exclusive()?, &user.0, interval.map(Into::into))?;
+    Ok(Status::NoContent)
+}
+
+#[get("/users/current/language")]
+pub fn get_user_language(db: sqlite::Connections, user: Login) -> Result<json::UserLanguage> {
+    let language = usecases::get_user_language(&*db.shared()?, &user.0)?;
+    Ok(Json(json::UserLanguage { language }))
+}
+
+#[post(
+    "/users/current/language",
+    format = "application/json",
+    data = "<data>"
+)]
+pub fn post_user_language(
+    db: sqlite::Connections,
+    user: Login,
+    data: Json<json::UserLanguage>,
+) -> StatusResult {
+    let json::UserLanguage { language } = data.into_inner();
+    usecases::set_user_language(&*db.exclusive()?, &user.0, language.as_deref())?;
+    Ok(Status::NoContent)
+}
+
 #[get("/users/<email>", format = "application/json", rank = 2)]
 pub fn get_user(db: sqlite::Connections, user: Login, email: String) -> Result<json::User> {
     let user = usecases::get_user(&*db.shared()?, &user.0, &email)?;
@@ -77,6 +310,13 @@ mod tests {
             .body(r#"{"email":"user@example.com"}"#)
             .dispatch();
         assert_eq!(res.status(), Status::Ok);
+        // The account is only created when following the link
+        assert!(db
+            .shared()
+            .unwrap()
+            .try_get_user_by_email("new@example.com")
+            .unwrap()
+            .is_none());
 
         // User gets an email with the corresponding token
         let token = db
@@ -155,4 +395,462 @@ mod tests {
         assert_eq!(email_confirmed, current_user.email_confirmed);
         assert_eq!(Role::User, current_user.role.into());
     }
+
+    #[test]
+    fn login_with_a_link_without_registration() {
+        let (client, db) = setup();
+
+        // User requests a link without having an account
+        let res = client
+            .post("/users/login-link-request")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"new@example.com"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        // User gets an email with the corresponding token
+        let token = db
+            .shared()
+            .unwrap()
+            .get_login_link_by_email("new@example.com")
+            .unwrap()
+            .email_nonce
+            .encode_to_string();
+
+        let res = client
+            .post("/users/login-with-token")
+            .header(ContentType::JSON)
+            .body(format!("{{\"token\":\"{}\"}}", token))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let mut res = client
+            .get("/users/current")
+            .header(ContentType::JSON)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        let current_user: json::User = serde_json::from_str(&body).unwrap();
+        assert_eq!("new@example.com", current_user.email);
+        assert!(current_user.email_confirmed);
+
+        // The link can only be used once
+        let res = client
+            .post("/users/login-with-token")
+            .header(ContentType::JSON)
+            .body(format!("{{\"token\":\"{}\"}}", token))
+            .dispatch();
+        assert_eq!(res.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn deactivate_and_reactivate_user() {
+        let (client, db) = setup();
+        register_user(&db, "admin@example.com", "secret", true);
+        register_user(&db, "user@example.com", "secret", true);
+        let mut admin = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("admin@example.com")
+            .unwrap();
+        admin.role = Role::Admin;
+        db.exclusive().unwrap().update_user(&admin).unwrap();
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client
+            .post("/admin/users/user@example.com/deactivate")
+            .header(ContentType::JSON)
+            .body(r#"{"comment":"spam"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+
+        // Deactivated users cannot login
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status().code, 403);
+        assert_eq!(res.status().reason, "UserDeactivated");
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client
+            .post("/admin/users/user@example.com/reactivate")
+            .header(ContentType::JSON)
+            .body(r#"{}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let mut res = client
+            .get("/admin/users/user@example.com/status-log")
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        let logs: Vec<json::UserStatusLog> = serde_json::from_str(&body).unwrap();
+        assert_eq!(2, logs.len());
+        assert!(!logs[0].active);
+        assert_eq!(Some("spam"), logs[0].act.comment.as_deref());
+        assert!(logs[1].active);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+    }
+
+    #[test]
+    fn reject_sessions_of_deactivated_users() {
+        let (client, db) = setup();
+        register_user(&db, "admin@example.com", "secret", true);
+        register_user(&db, "user@example.com", "secret", true);
+        let mut admin = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("admin@example.com")
+            .unwrap();
+        admin.role = Role::Admin;
+        db.exclusive().unwrap().update_user(&admin).unwrap();
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client.get("/users/current").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        usecases::deactivate_user(
+            &*db.exclusive().unwrap(),
+            "admin@example.com",
+            "user@example.com",
+            None,
+        )
+        .unwrap();
+        let res = client.get("/users/current").dispatch();
+        assert_eq!(res.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn grant_single_permissions_to_users() {
+        let (client, db) = setup();
+        register_user(&db, "admin@example.com", "secret", true);
+        register_user(&db, "user@example.com", "secret", true);
+        let mut admin = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("admin@example.com")
+            .unwrap();
+        admin.role = Role::Admin;
+        db.exclusive().unwrap().update_user(&admin).unwrap();
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client.get("/places/unknown/history").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+        let res = client
+            .post("/admin/users/user@example.com/permissions")
+            .header(ContentType::JSON)
+            .body(r#"{"permission":"view_place_history","granted":true}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client
+            .post("/admin/users/user@example.com/permissions")
+            .header(ContentType::JSON)
+            .body(r#"{"permission":"view_place_history","granted":true}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let mut res = client
+            .get("/admin/users/user@example.com/permissions")
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(
+            r#"["view_place_history"]"#,
+            res.body().and_then(|b| b.into_string()).unwrap()
+        );
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client.get("/places/unknown/history").dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn list_valid_user_tokens() {
+        let (client, db) = setup();
+        register_user(&db, "admin@example.com", "secret", true);
+        register_user(&db, "user@example.com", "secret", true);
+        let mut admin = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("admin@example.com")
+            .unwrap();
+        admin.role = Role::Admin;
+        db.exclusive().unwrap().update_user(&admin).unwrap();
+
+        // Expired token
+        db.exclusive()
+            .unwrap()
+            .replace_user_token(UserToken {
+                email_nonce: EmailNonce {
+                    email: "admin@example.com".into(),
+                    nonce: Nonce::new(),
+                },
+                expires_at: Timestamp::from_inner(Timestamp::now().into_inner() - 1),
+            })
+            .unwrap();
+        let res = client
+            .post("/users/reset-password-request")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let token = db
+            .shared()
+            .unwrap()
+            .get_user_token_by_email("user@example.com")
+            .unwrap();
+
+        // Not logged in
+        let res = client.get("/admin/user-tokens").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let mut res = client.get("/admin/user-tokens").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        // The secret nonce is not revealed
+        assert!(!body.contains(&token.email_nonce.nonce.to_string()));
+        let tokens: Vec<json::UserTokenInfo> = serde_json::from_str(&body).unwrap();
+        assert_eq!(1, tokens.len());
+        assert_eq!("user@example.com", tokens[0].email);
+        assert_eq!(token.expires_at.into_inner(), tokens[0].expires_at);
+        let expected_hash: String =
+            openssl::sha::sha256(token.email_nonce.encode_to_string().as_bytes())
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+        assert_eq!(expected_hash, tokens[0].email_nonce_hash);
+    }
+
+    #[test]
+    fn opt_out_of_scout_reports() {
+        let (client, db) = setup();
+        register_user(&db, "scout@example.com", "secret", true);
+
+        let res = client.get("/users/current/scout-reports").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"scout@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let mut res = client.get("/users/current/scout-reports").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        assert_eq!(r#"{"enabled":true}"#, body);
+
+        let res = client
+            .post("/users/current/scout-reports")
+            .header(ContentType::JSON)
+            .body(r#"{"enabled":false}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        assert!(db
+            .shared()
+            .unwrap()
+            .is_scout_report_opted_out("scout@example.com")
+            .unwrap());
+
+        let mut res = client.get("/users/current/scout-reports").dispatch();
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        assert_eq!(r#"{"enabled":false}"#, body);
+    }
+
+    #[test]
+    fn switch_to_notification_digests() {
+        let (client, db) = setup();
+        register_user(&db, "user@example.com", "secret", true);
+
+        let res = client.get("/users/current/notification-digest").dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let mut res = client.get("/users/current/notification-digest").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        assert_eq!(r#"{"interval":null}"#, body);
+
+        let res = client
+            .post("/users/current/notification-digest")
+            .header(ContentType::JSON)
+            .body(r#"{"interval":"weekly"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let settings = db
+            .shared()
+            .unwrap()
+            .try_get_digest_settings("user@example.com")
+            .unwrap()
+            .unwrap();
+        assert_eq!(DigestInterval::Weekly, settings.interval);
+
+        let res = client
+            .post("/users/current/notification-digest")
+            .header(ContentType::JSON)
+            .body(r#"{"interval":null}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let mut res = client.get("/users/current/notification-digest").dispatch();
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        assert_eq!(r#"{"interval":null}"#, body);
+    }
+
+    #[test]
+    fn choose_the_language_of_emails() {
+        let (client, db) = setup();
+        register_user(&db, "user@example.com", "secret", true);
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let mut res = client.get("/users/current/language").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        assert_eq!(r#"{"language":null}"#, body);
+
+        let res = client
+            .post("/users/current/language")
+            .header(ContentType::JSON)
+            .body(r#"{"language":"english"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::BadRequest);
+
+        let res = client
+            .post("/users/current/language")
+            .header(ContentType::JSON)
+            .body(r#"{"language":"en"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        assert_eq!(
+            Some("en".to_string()),
+            db.shared()
+                .unwrap()
+                .get_user_by_email("user@example.com")
+                .unwrap()
+                .language
+        );
+        let mut res = client
+            .get("/users/current")
+            .header(ContentType::JSON)
+            .dispatch();
+        let body = res.body().and_then(|b| b.into_string()).unwrap();
+        let current_user: json::User = serde_json::from_str(&body).unwrap();
+        assert_eq!(Some("en".to_string()), current_user.language);
+    }
+
+    #[test]
+    fn personal_api_tokens() {
+        let (client, db) = setup();
+        register_user(&db, "user@example.com", "secret", true);
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let mut res = client
+            .post("/users/me/tokens")
+            .header(ContentType::JSON)
+            .body(r#"{"label":"script"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let token: json::UserApiToken = serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!(Some("script"), token.label.as_deref());
+        let bearer = || {
+            rocket::http::Header::new(
+                "Authorization",
+                format!("Bearer {}", token.token.as_ref().unwrap()),
+            )
+        };
+
+        let res = client.post("/logout").header(ContentType::JSON).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client
+            .get("/users/current")
+            .header(ContentType::JSON)
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        // The token authorizes requests like a session
+        let mut res = client
+            .get("/users/current")
+            .header(ContentType::JSON)
+            .header(bearer())
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let user: json::User = serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!("user@example.com", user.email);
+        let mut res = client.get("/users/me/tokens").header(bearer()).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let tokens: Vec<json::UserApiToken> =
+            serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!(1, tokens.len());
+        assert!(tokens[0].token.is_none());
+
+        let res = client
+            .delete(format!("/users/me/tokens/{}", token.id))
+            .header(bearer())
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let res = client
+            .get("/users/current")
+            .header(ContentType::JSON)
+            .header(bearer())
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+    }
 }
@@ -0,0 +1,134 @@
+use super::*;
+
+// Limit the number of deliveries per request
+const WEBHOOK_DELIVERIES_MAX_COUNT: u64 = 500;
+
+#[get("/admin/webhooks")]
+pub fn get_webhooks(connections: sqlite::Connections, login: Login) -> Result<Vec<json::Webhook>> {
+    let webhooks = usecases::load_webhooks(&*connections.shared()?, &login.0)?;
+    Ok(Json(webhooks.into_iter().map(Into::into).collect()))
+}
+
+#[post("/admin/webhooks", format = "application/json", data = "<data>")]
+pub fn post_webhook(
+    connections: sqlite::Connections,
+    login: Login,
+    data: Json<json::NewWebhook>,
+) -> Result<json::Webhook> {
+    let json::NewWebhook { url, events } = data.into_inner();
+    let new_webhook = usecases::NewWebhook {
+        url,
+        events: events.into_iter().map(Into::into).collect(),
+    };
+    let webhook = usecases::create_webhook(&*connections.exclusive()?, &login.0, new_webhook)?;
+    let secret = webhook.secret.clone();
+    Ok(Json(json::Webhook {
+        secret: Some(secret),
+        ..json::Webhook::from(webhook)
+    }))
+}
+
+#[delete("/admin/webhooks/<id>")]
+pub fn delete_webhook(connections: sqlite::Connections, login: Login, id: String) -> StatusResult {
+    usecases::delete_webhook(&*connections.exclusive()?, &login.0, &id)?;
+    Ok(Status::NoContent)
+}
+
+#[get("/admin/webhooks/<id>/deliveries?<offset>&<limit>")]
+pub fn get_webhook_deliveries(
+    connections: sqlite::Connections,
+    login: Login,
+    id: String,
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<json::WebhookDelivery>> {
+    let pagination = Pagination {
+        offset,
+        limit: Some(
+            limit
+                .unwrap_or(WEBHOOK_DELIVERIES_MAX_COUNT)
+                .min(WEBHOOK_DELIVERIES_MAX_COUNT),
+        ),
+    };
+    let deliveries =
+        usecases::load_webhook_deliveries(&*connections.shared()?, &login.0, &id, &pagination)?;
+    Ok(Json(deliveries.into_iter().map(Into::into).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::web::{api::tests::prelude::*, tests::register_user};
+
+    #[test]
+    fn manage_webhooks() {
+        let (client, db) = setup();
+        register_user(&db, "admin@example.com", "secret", true);
+        let mut admin = db
+            .shared()
+            .unwrap()
+            .get_user_by_email("admin@example.com")
+            .unwrap();
+        admin.role = Role::Admin;
+        db.exclusive().unwrap().update_user(&admin).unwrap();
+
+        let new_webhook = r#"{"url":"https://example.com/hook","events":["place.created"]}"#;
+
+        // Not logged in
+        let res = client
+            .post("/admin/webhooks")
+            .header(ContentType::JSON)
+            .body(new_webhook)
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let mut res = client
+            .post("/admin/webhooks")
+            .header(ContentType::JSON)
+            .body(new_webhook)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let webhook: json::Webhook = serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!("https://example.com/hook", webhook.url);
+        assert_eq!(vec![json::WebhookEvent::PlaceCreated], webhook.events);
+        assert!(webhook.secret.is_some());
+
+        let res = client
+            .post("/admin/webhooks")
+            .header(ContentType::JSON)
+            .body(r#"{"url":"not a url","events":["place.created"]}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::BadRequest);
+
+        // The secret is not revealed again
+        let mut res = client.get("/admin/webhooks").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let webhooks: Vec<json::Webhook> =
+            serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!(1, webhooks.len());
+        assert_eq!(webhook.id, webhooks[0].id);
+        assert!(webhooks[0].secret.is_none());
+
+        let mut res = client
+            .get(format!("/admin/webhooks/{}/deliveries", webhook.id))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!("[]", res.body_string().unwrap());
+
+        let res = client
+            .delete(format!("/admin/webhooks/{}", webhook.id))
+            .dispatch();
+        assert_eq!(res.status(), Status::NoContent);
+        let res = client
+            .get(format!("/admin/webhooks/{}/deliveries", webhook.id))
+            .dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+    }
+}
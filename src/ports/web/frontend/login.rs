@@ -2,12 +2,13 @@ use super::super::guards::*;
 use super::view;
 use crate::{
     core::{prelude::*, usecases},
+    infrastructure::flows::prelude as flows,
     ports::web::sqlite::Connections,
 };
 use maud::Markup;
 use rocket::{
     self,
-    http::{Cookie, Cookies},
+    http::{Cookie, Cookies, RawStr},
     request::{FlashMessage, Form},
     response::{Flash, Redirect},
 };
@@ -60,6 +61,9 @@ pub fn post_login(
                         Error::Parameter(ParameterError::Credentials) => {
                             "Invalid email or password."
                         }
+                        Error::Parameter(ParameterError::UserDeactivated) => {
+                            "Your account has been deactivated."
+                        }
                         _ => panic!(),
                     };
                     Err(Flash::error(Redirect::to(uri!(get_login)), msg))
@@ -73,6 +77,31 @@ pub fn post_login(
     }
 }
 
+#[get("/login/token/<token>")]
+pub fn get_login_with_token(
+    db: Connections,
+    token: &RawStr,
+    mut cookies: Cookies,
+) -> std::result::Result<Redirect, Flash<Redirect>> {
+    let email = EmailNonce::decode_from_str(token.as_str())
+        .ok()
+        .and_then(|email_nonce| {
+            flows::login_with_login_link(&db, &email_nonce)
+                .ok()
+                .map(|_| email_nonce.email)
+        });
+    match email {
+        Some(email) => {
+            cookies.add_private(Cookie::new(COOKIE_EMAIL_KEY, email));
+            Ok(Redirect::to(uri!(super::get_index)))
+        }
+        None => Err(Flash::error(
+            Redirect::to(uri!(get_login)),
+            "The login link is invalid or has expired.",
+        )),
+    }
+}
+
 #[post("/logout")]
 pub fn post_logout(mut cookies: Cookies) -> Flash<Redirect> {
     cookies.remove_private(Cookie::named(COOKIE_EMAIL_KEY));
@@ -154,4 +183,26 @@ pub mod tests {
             }
         }
     }
+
+    #[test]
+    fn get_login_with_token() {
+        let (client, pool) = setup();
+        register_user(&pool, "foo@bar.com", "baz baz", false);
+        let db = pool.exclusive().unwrap();
+        let token = usecases::create_login_link(&*db, "foo@bar.com")
+            .unwrap()
+            .unwrap()
+            .encode_to_string();
+        drop(db);
+        let res = client.get(format!("/login/token/{}", token)).dispatch();
+        assert_eq!(res.status(), HttpStatus::SeeOther);
+        assert!(user_id_cookie(&res).is_some());
+        assert_eq!(res.headers().get_one("Location"), Some("/"));
+
+        // The token can only be used once
+        let res = client.get(format!("/login/token/{}", token)).dispatch();
+        assert_eq!(res.status(), HttpStatus::SeeOther);
+        assert!(user_id_cookie(&res).is_none());
+        assert_eq!(res.headers().get_one("Location"), Some("/login"));
+    }
 }
@@ -5,19 +5,20 @@ use crate::{
         usecases,
     },
     infrastructure::{db::sqlite, error::*, flows::prelude::*},
-    ports::web::{guards::*, tantivy::SearchEngine},
+    ports::web::{guards::*, notify::*, rating_timelines::RatingTimelines, tantivy::SearchEngine},
 };
 use maud::Markup;
 use num_traits::FromPrimitive;
+use ofdb_core::NotificationGateway;
 use rocket::{
     self,
     http::RawStr,
-    request::Form,
+    request::{FlashMessage, Form},
     response::{
         content::{Css, JavaScript},
         Flash, Redirect,
     },
-    Route,
+    Route, State,
 };
 
 mod login;
@@ -33,18 +34,20 @@ const MAIN_CSS: &str = include_str!("main.css");
 type Result<T> = std::result::Result<T, AppError>;
 
 #[get("/")]
-pub fn get_index_user(account: Account) -> Markup {
-    view::index(Some(&account.email()))
+pub fn get_index_user(db: sqlite::Connections, account: Account) -> Result<Markup> {
+    let announcements = usecases::load_current_announcements(&*db.shared()?)?;
+    Ok(view::index(Some(&account.email()), &announcements))
 }
 
 #[get("/", rank = 2)]
-pub fn get_index() -> Markup {
-    view::index(None)
+pub fn get_index(db: sqlite::Connections) -> Result<Markup> {
+    let announcements = usecases::load_current_announcements(&*db.shared()?)?;
+    Ok(view::index(None, &announcements))
 }
 
 #[get("/index.html")]
-pub fn get_index_html() -> Markup {
-    view::index(None)
+pub fn get_index_html(db: sqlite::Connections) -> Result<Markup> {
+    get_index(db)
 }
 
 #[get("/search?<q>&<limit>")]
@@ -63,7 +66,8 @@ pub fn get_search_users(
     let email = email.url_decode()?;
     {
         let db = pool.shared()?;
-        let admin = usecases::authorize_user_by_email(&*db, account.email(), Role::Admin)?;
+        let admin =
+            usecases::authorize_user_by_email(&*db, account.email(), Permission::ManageUsers)?;
         let users: Vec<_> = db.try_get_user_by_email(&email)?.into_iter().collect();
         Ok(view::user_search_result(&admin.email, &users))
     }
@@ -116,23 +120,73 @@ pub fn get_place_history(db: sqlite::Connections, id: &RawStr, account: Account)
     let place_history = {
         // The history contains e-mail addresses of registered users
         // and is only permitted for scouts and admins!
-        usecases::authorize_user_by_email(&*db, &account.email(), Role::Scout)?;
+        usecases::authorize_user_by_email(&*db, &account.email(), Permission::ViewPlaceHistory)?;
 
-        db.get_place_history(&id)?
+        db.get_place_history(&id.as_str().into())?
     };
     Ok(view::place_history(&user, &place_history))
 }
 
+#[get("/places/<id>/diff?<from>&<to>")]
+pub fn get_place_diff(
+    db: sqlite::Connections,
+    id: &RawStr,
+    from: u64,
+    to: u64,
+    account: Account,
+) -> Result<Markup> {
+    let db = db.shared()?;
+    let user = db
+        .try_get_user_by_email(account.email())?
+        .ok_or(Error::Parameter(ParameterError::Unauthorized))?;
+    let diff = usecases::diff_place_revisions(
+        &*db,
+        &user.email,
+        &id.as_str().into(),
+        from.into(),
+        to.into(),
+    )?;
+    Ok(view::place_revision_diff(&user, &diff))
+}
+
 #[get("/places/<id>/review")]
 pub fn get_place_review(db: sqlite::Connections, id: &RawStr, account: Account) -> Result<Markup> {
     let db = db.shared()?;
     // Only scouts and admins are entitled to review places
     let reviewer_email =
-        usecases::authorize_user_by_email(&*db, &account.email(), Role::Scout)?.email;
-    let (place, review_status) = db.get_place(&id)?;
+        usecases::authorize_user_by_email(&*db, &account.email(), Permission::ReviewPlaces)?.email;
+    let (place, review_status) = db.get_place(&id.as_str().into())?;
     Ok(view::place_review(&reviewer_email, &place, review_status))
 }
 
+const MODERATION_PAGE_SIZE: u64 = 50;
+
+#[get("/moderation?<offset>")]
+pub fn get_moderation(
+    db: sqlite::Connections,
+    account: Account,
+    offset: Option<u64>,
+) -> Result<Markup> {
+    let offset = offset.unwrap_or(0);
+    let pagination = Pagination {
+        offset: Some(offset),
+        limit: Some(MODERATION_PAGE_SIZE),
+    };
+    let places =
+        usecases::load_places_pending_review(&*db.shared()?, account.email(), &pagination)?;
+    // There might be more places on the next page
+    let next_offset = if places.len() as u64 == MODERATION_PAGE_SIZE {
+        Some(offset + MODERATION_PAGE_SIZE)
+    } else {
+        None
+    };
+    Ok(view::places_pending_review(
+        account.email(),
+        &places,
+        next_offset,
+    ))
+}
+
 #[derive(FromForm)]
 pub struct Review {
     pub comment: String,
@@ -143,20 +197,29 @@ pub struct Review {
 pub fn post_place_review(
     db: sqlite::Connections,
     search_engine: SearchEngine,
+    notify: Notify,
     id: &RawStr,
     review: Form<Review>,
     account: Account,
 ) -> std::result::Result<Redirect, Flash<Redirect>> {
     let Review { status, comment } = review.into_inner();
     let id = id.as_str();
-    review_place(&db, account.email(), status, comment, id, search_engine)
-        .map(|_| Redirect::to(uri!(get_entry: id)))
-        .map_err(|_| {
-            Flash::error(
-                Redirect::to(uri!(get_place_review: id)),
-                "Failed to archive the place.",
-            )
-        })
+    review_place(
+        &db,
+        account.email(),
+        status,
+        comment,
+        id,
+        search_engine,
+        &*notify,
+    )
+    .map(|_| Redirect::to(uri!(get_entry: id)))
+    .map_err(|_| {
+        Flash::error(
+            Redirect::to(uri!(get_place_review: id)),
+            "Failed to archive the place.",
+        )
+    })
 }
 
 fn review_place(
@@ -166,13 +229,14 @@ fn review_place(
     comment: String,
     id: &str,
     mut search_engine: SearchEngine,
+    notify: &dyn NotificationGateway,
 ) -> Result<()> {
     let reviewer_email = {
         let db = db.shared()?;
-        usecases::authorize_user_by_email(&*db, email, Role::Scout)?.email
+        usecases::authorize_user_by_email(&*db, email, Permission::ReviewPlaces)?.email
     };
     let status = ReviewStatus::try_from(status)
-        .ok_or_else(|| Error::Parameter(ParameterError::RatingContext(status.to_string())))?;
+        .ok_or_else(|| Error::Parameter(ParameterError::ReviewStatus(status.to_string())))?;
     // TODO: Record context information
     let context = None;
     let review = usecases::Review {
@@ -181,7 +245,7 @@ fn review_place(
         status,
         comment: Some(comment),
     };
-    let update_count = review_places(&db, &mut search_engine, &[&id], review)?;
+    let update_count = review_places(&db, &mut search_engine, notify, &[&id], review)?;
     if update_count == 0 {
         return Err(Error::Repo(RepoError::NotFound).into());
     }
@@ -195,22 +259,70 @@ pub fn get_entry(
     account: Option<Account>,
 ) -> Result<Markup> {
     //TODO: dry out
-    let (user, place, ratings): (Option<User>, _, _) = {
+    let (user, permissions, place, ratings, contact_verified): (Option<User>, _, _, _, _) = {
         let db = pool.shared()?;
-        let (place, _) = db.get_place(id.as_str())?;
-        let ratings = db.load_ratings_of_place(place.id.as_ref())?;
+        let (place, _) = db.get_place(&id.as_str().into())?;
+        let ratings = db.load_ratings_of_place(&place.id.as_str().into())?;
         let ratings_with_comments = db.zip_ratings_with_comments(ratings)?;
-        let user = if let Some(a) = account {
-            db.try_get_user_by_email(a.email())?
+        let contact_verified = usecases::is_contact_email_verified(&*db, &place)?;
+        let account_email = account.as_ref().map(Account::email);
+        let user = if let Some(email) = account_email {
+            db.try_get_user_by_email(email)?
         } else {
             None
         };
-        (user, place, ratings_with_comments)
+        let permissions = usecases::account_permissions(&*db, account_email)?;
+        (
+            user,
+            permissions,
+            place,
+            ratings_with_comments,
+            contact_verified,
+        )
     };
-    Ok(match user {
-        Some(u) => view::entry(Some(&u.email), (place, ratings, u.role).into()),
-        None => view::entry(None, (place, ratings).into()),
-    })
+    let mut entry: view::EntryPresenter = (place, ratings).into();
+    entry.permissions = permissions;
+    entry.contact_verified = contact_verified;
+    let email = user.as_ref().map(|u| u.email.as_str());
+    Ok(view::entry(email, entry))
+}
+
+#[get("/verify-contact/<token>")]
+pub fn get_contact_verification(
+    db: sqlite::Connections,
+    token: &RawStr,
+) -> std::result::Result<Flash<Redirect>, Flash<Redirect>> {
+    let confirmed = match db.exclusive() {
+        Ok(db) => usecases::confirm_contact_verification(&*db, token.as_str()).ok(),
+        Err(_) => None,
+    };
+    match confirmed {
+        Some(place_id) => Ok(Flash::success(
+            Redirect::to(uri!(get_entry: place_id.as_str())),
+            "The contact email address is now verified :)",
+        )),
+        None => Err(Flash::error(
+            Redirect::to(uri!(get_index)),
+            "The verification link is invalid or has expired.",
+        )),
+    }
+}
+
+// Links in e-mails are followed automatically by some
+// mail scanners and must not cancel the registration
+#[get("/cancel-event-registration/<token>")]
+pub fn get_cancel_event_registration(token: &RawStr) -> Markup {
+    view::event_registration_cancellation_form(token.as_str())
+}
+
+#[post("/cancel-event-registration/<token>")]
+pub fn post_cancel_event_registration(
+    db: sqlite::Connections,
+    notify: Notify,
+    token: &RawStr,
+) -> Markup {
+    let cancelled = cancel_event_registration(&db, &*notify, token.as_str()).ok();
+    view::event_registration_cancellation(cancelled.as_ref().map(|r| r.event_id.as_str()))
 }
 
 #[get("/events/<id>")]
@@ -219,36 +331,50 @@ pub fn get_event(
     id: &RawStr,
     account: Option<Account>,
 ) -> Result<Markup> {
-    let (user, mut ev): (Option<User>, _) = {
+    let (user, allow_archiving, mut ev): (Option<User>, _, _) = {
         let db = pool.shared()?;
-        let ev = usecases::get_event(&*db, &id)?;
-        let user = if let Some(a) = account {
-            db.try_get_user_by_email(a.email())?
+        let ev = usecases::get_event(&*db, &id.as_str().into())?;
+        let account_email = account.as_ref().map(Account::email);
+        let user = if let Some(email) = account_email {
+            db.try_get_user_by_email(email)?
         } else {
             None
         };
-        (user, ev)
+        let allow_archiving = usecases::account_permissions(&*db, account_email)?
+            .contains(&Permission::ArchiveEvents);
+        (user, allow_archiving, ev)
     };
 
     // TODO:Make sure within usecase that the creator email
     // is not shown to unregistered users
     ev.created_by = None;
 
-    Ok(view::event(user, ev))
+    Ok(view::event(user, allow_archiving, ev))
+}
+
+#[derive(FromForm)]
+pub struct ArchiveEvent {
+    comment: Option<String>,
 }
 
-#[post("/events/<id>/archive")]
+#[post("/events/<id>/archive", data = "<data>")]
 pub fn post_archive_event(
     account: Account,
     pool: sqlite::Connections,
     mut search_engine: SearchEngine,
+    notify: Notify,
     id: &RawStr,
+    data: Option<Form<ArchiveEvent>>,
 ) -> std::result::Result<Redirect, Flash<Redirect>> {
     let archived_by_email = pool
         .shared()
         .and_then(|db| {
             // Only scouts and admins are entitled to review events
-            let user = usecases::authorize_user_by_email(&*db, &account.email(), Role::Scout)?;
+            let user = usecases::authorize_user_by_email(
+                &*db,
+                &account.email(),
+                Permission::ArchiveEvents,
+            )?;
             Ok(user.email)
         })
         .map_err(|_| {
@@ -257,19 +383,27 @@ pub fn post_archive_event(
                 "Failed to achive the event.",
             )
         })?;
-    archive_events(&pool, &mut search_engine, &[id], &archived_by_email)
-        .map_err(|_| {
-            Flash::error(
-                Redirect::to(uri!(get_event: id)),
-                "Failed to achive the event.",
-            )
-        })
-        .map(|update_count| {
-            if update_count != 1 {
-                log::info!("Archived more than one event: {}", update_count);
-            }
-            Redirect::to("/events") //TODO: use uri! macro
-        })
+    let comment = data.and_then(|d| non_empty(d.into_inner().comment));
+    archive_events(
+        &pool,
+        &mut search_engine,
+        &*notify,
+        &[id],
+        &archived_by_email,
+        comment.as_deref(),
+    )
+    .map_err(|_| {
+        Flash::error(
+            Redirect::to(uri!(get_event: id)),
+            "Failed to achive the event.",
+        )
+    })
+    .map(|update_count| {
+        if update_count != 1 {
+            log::info!("Archived more than one event: {}", update_count);
+        }
+        Redirect::to("/events") //TODO: use uri! macro
+    })
 }
 
 #[get("/events?<query..>")]
@@ -296,8 +430,47 @@ pub fn get_events_chronologically(
     Ok(view::events(email, &events))
 }
 
+const STATS_MONTHS: usize = 24;
+
+const STATS_TOP_TAGS: u64 = 20;
+
+#[get("/stats")]
+pub fn get_stats(
+    db: sqlite::Connections,
+    search_engine: SearchEngine,
+    account: Option<Account>,
+) -> Result<Markup> {
+    let db = db.shared()?;
+    let mut new_places_per_month = db.count_new_places_per_month()?;
+    let skipped_months = new_places_per_month.len().saturating_sub(STATS_MONTHS);
+    new_places_per_month.drain(..skipped_months);
+    let top_tags = search_engine.most_popular_tags(
+        &MostPopularTagsParams {
+            min_count: None,
+            max_count: None,
+        },
+        &Pagination {
+            offset: None,
+            limit: Some(STATS_TOP_TAGS),
+        },
+    )?;
+    Ok(view::statistics(view::StatisticsPresenter {
+        email: account.as_ref().map(Account::email),
+        place_count: db.count_places()?,
+        event_count: db.count_events()?,
+        user_count: db.count_users()?,
+        tag_count: db.count_tags()?,
+        new_places_per_month,
+        top_tags,
+    }))
+}
+
 #[get("/dashboard")]
-pub fn get_dashboard(db: sqlite::Connections, account: Account) -> Result<Markup> {
+pub fn get_dashboard(
+    db: sqlite::Connections,
+    account: Account,
+    flash: Option<FlashMessage>,
+) -> Result<Markup> {
     let db = db.shared()?;
     let tag_count = db.count_tags()?;
     let place_count = db.count_places()?;
@@ -306,22 +479,127 @@ pub fn get_dashboard(db: sqlite::Connections, account: Account) -> Result<Markup
     let user = db
         .try_get_user_by_email(account.email())?
         .ok_or(Error::Parameter(ParameterError::Unauthorized))?;
-    if user.role == Role::Admin {
-        return Ok(view::dashboard(view::DashBoardPresenter {
-            user,
-            place_count,
-            event_count,
-            tag_count,
-            user_count,
-        }));
+    if usecases::has_permission(&*db, &user, Permission::ManageUsers)? {
+        return Ok(view::dashboard(
+            flash,
+            view::DashBoardPresenter {
+                user,
+                place_count,
+                event_count,
+                tag_count,
+                user_count,
+            },
+        ));
     }
     Err(Error::Parameter(ParameterError::Unauthorized).into())
 }
 
+#[derive(FromForm)]
+pub struct RenameTagAction {
+    tag: String,
+    new_tag: String,
+}
+
+#[post("/tags/actions/rename", data = "<data>")]
+pub fn post_rename_tag(
+    account: Account,
+    db: sqlite::Connections,
+    mut search_engine: SearchEngine,
+    data: Form<RenameTagAction>,
+) -> std::result::Result<Flash<Redirect>, Flash<Redirect>> {
+    let d = data.into_inner();
+    match rename_tag(&db, &mut search_engine, account.email(), &d.tag, &d.new_tag) {
+        Err(_) => Err(Flash::error(
+            Redirect::to(uri!(get_dashboard)),
+            "Failed to rename the tag.",
+        )),
+        Ok(_) => Ok(Flash::success(
+            Redirect::to(uri!(get_dashboard)),
+            "The tag has been renamed.",
+        )),
+    }
+}
+
+#[derive(FromForm)]
+pub struct MergeTagsAction {
+    tags: String,
+    into: String,
+}
+
+#[post("/tags/actions/merge", data = "<data>")]
+pub fn post_merge_tags(
+    account: Account,
+    db: sqlite::Connections,
+    mut search_engine: SearchEngine,
+    data: Form<MergeTagsAction>,
+) -> std::result::Result<Flash<Redirect>, Flash<Redirect>> {
+    let d = data.into_inner();
+    let tags: Vec<_> = d
+        .tags
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    match merge_tags(&db, &mut search_engine, account.email(), &tags, &d.into) {
+        Err(_) => Err(Flash::error(
+            Redirect::to(uri!(get_dashboard)),
+            "Failed to merge the tags.",
+        )),
+        Ok(_) => Ok(Flash::success(
+            Redirect::to(uri!(get_dashboard)),
+            "The tags have been merged.",
+        )),
+    }
+}
+
+#[derive(FromForm)]
+pub struct MergePlacesAction {
+    primary_id: String,
+    duplicate_id: String,
+}
+
+#[post("/places/actions/merge", data = "<data>")]
+pub fn post_merge_places(
+    account: Account,
+    db: sqlite::Connections,
+    mut search_engine: SearchEngine,
+    rating_timelines: State<RatingTimelines>,
+    data: Form<MergePlacesAction>,
+) -> std::result::Result<Flash<Redirect>, Flash<Redirect>> {
+    let d = data.into_inner();
+    let primary_id = d.primary_id.trim();
+    match merge_places(
+        &db,
+        &mut search_engine,
+        account.email(),
+        &primary_id.into(),
+        &d.duplicate_id.as_str().into(),
+    ) {
+        Err(_) => Err(Flash::error(
+            Redirect::to(uri!(get_entry: d.duplicate_id)),
+            "Failed to merge the places.",
+        )),
+        Ok(_) => {
+            rating_timelines.invalidate(primary_id);
+            rating_timelines.invalidate(&d.duplicate_id);
+            Ok(Flash::success(
+                Redirect::to(uri!(get_entry: primary_id)),
+                "The places have been merged.",
+            ))
+        }
+    }
+}
+
 #[derive(FromForm)]
 pub struct ArchiveAction {
     ids: String,
     place_id: String,
+    comment: Option<String>,
+}
+
+// Empty text inputs are submitted as empty strings
+fn non_empty(comment: Option<String>) -> Option<String> {
+    comment.filter(|c| !c.trim().is_empty())
 }
 
 #[post("/comments/actions/archive", data = "<data>")]
@@ -333,7 +611,8 @@ pub fn post_comments_archive(
     //TODO: dry out
     let d = data.into_inner();
     let ids: Vec<_> = d.ids.split(',').filter(|id| !id.is_empty()).collect();
-    match archive_comments(&db, account.email(), &ids) {
+    let comment = non_empty(d.comment);
+    match archive_comments(&db, account.email(), &ids, comment.as_deref()) {
         Err(_) => Err(Flash::error(
             Redirect::to(uri!(get_entry:d.place_id)),
             "Failed to achive the comment.",
@@ -347,11 +626,20 @@ pub fn post_ratings_archive(
     account: Account,
     db: sqlite::Connections,
     mut search_engine: SearchEngine,
+    rating_timelines: State<RatingTimelines>,
     data: Form<ArchiveAction>,
 ) -> std::result::Result<Redirect, Flash<Redirect>> {
     let d = data.into_inner();
     let ids: Vec<_> = d.ids.split(',').filter(|id| !id.is_empty()).collect();
-    match archive_ratings(&db, &mut search_engine, account.email(), &ids) {
+    let comment = non_empty(d.comment);
+    rating_timelines.invalidate(&d.place_id);
+    match archive_ratings(
+        &db,
+        &mut search_engine,
+        account.email(),
+        &ids,
+        comment.as_deref(),
+    ) {
         Err(_) => Err(Flash::error(
             Redirect::to(uri!(get_entry:d.place_id)),
             "Failed to archive the rating.",
@@ -366,11 +654,20 @@ pub fn routes() -> Vec<Route> {
         get_index,
         get_index_html,
         get_dashboard,
+        post_rename_tag,
+        post_merge_tags,
+        post_merge_places,
+        get_stats,
         get_search,
         get_entry,
+        get_contact_verification,
+        get_cancel_event_registration,
+        post_cancel_event_registration,
         get_place_history,
+        get_place_diff,
         get_place_review,
         post_place_review,
+        get_moderation,
         get_events_chronologically,
         get_event,
         get_main_css,
@@ -383,6 +680,7 @@ pub fn routes() -> Vec<Route> {
         login::get_login,
         login::get_login_user,
         login::post_login,
+        login::get_login_with_token,
         login::post_logout,
         register::get_register,
         register::post_register,
@@ -45,7 +45,10 @@ pub fn post_register(
                     Err(Flash::error(Redirect::to(uri!(get_register)), msg))
                 }
                 Ok(()) => {
-                    if let Ok(user) = db.get_user_by_email(&credentials.email) {
+                    let user = db.get_user_by_email(&credentials.email);
+                    // Release the lock before notifying
+                    drop(db);
+                    if let Ok(user) = user {
                         debug_assert_eq!(user.email, credentials.email);
                         notify.user_registered_ofdb(&user);
 
@@ -269,6 +269,8 @@ mod events {
             archived: None,
             image_url: None,
             image_link_url: None,
+            recurrence: None,
+            capacity: None,
         }];
 
         {
@@ -284,6 +286,61 @@ mod events {
         assert!(body_str.contains("<h2>A great event</h2>"));
         assert!(body_str.contains("Foo bar baz</p>"));
     }
+
+    #[test]
+    fn cancel_event_registration_with_link_from_email() {
+        let (client, db, mut search_engine) = setup();
+        let gw = DummyNotifyGW;
+        let event_id = flows::create_event(
+            &db,
+            &mut search_engine,
+            &gw,
+            None,
+            usecases::NewEvent {
+                title: "Meetup".into(),
+                start: chrono::Utc::now().naive_utc().timestamp(),
+                registration: Some("email".into()),
+                email: Some("test@example.com".into()),
+                created_by: Some("test@example.com".into()),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .id;
+        let registration =
+            flows::register_for_event(&db, &gw, &event_id.as_str().into(), "attendee@example.com")
+                .unwrap();
+
+        let url = ofdb_gateways::notify::cancel_event_registration_url(&registration);
+        let path = &url[url.find("/cancel-event-registration/").unwrap()..];
+        let load_status = || {
+            db.shared()
+                .unwrap()
+                .load_event_registrations(&event_id.as_str().into())
+                .unwrap()[0]
+                .status
+        };
+
+        // Following the link only asks for a confirmation
+        let mut res = client.get(path).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+        assert!(body_str.contains(&format!("action=\"{}\" method=\"POST\"", path)));
+        assert_eq!(EventRegistrationStatus::Confirmed, load_status());
+
+        let mut res = client.post(path).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+        assert!(body_str.contains("Your registration has been cancelled"));
+        assert!(body_str.contains(&format!("href=\"/events/{}\"", event_id)));
+        assert_eq!(EventRegistrationStatus::Cancelled, load_status());
+
+        // The link can only be used once
+        let mut res = client.post(path).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+        assert!(body_str.contains("The registration could not be cancelled"));
+    }
 }
 
 mod index {
@@ -303,6 +360,34 @@ mod index {
         assert_eq!(index_html_str, index_str);
         assert!(index_str.contains("<form action=\"search\""));
         assert!(index_str.contains("<input type=\"text\""));
+        assert!(!index_str.contains("class=\"announcement"));
+    }
+
+    #[test]
+    fn show_current_announcements() {
+        let (client, db, _) = setup();
+        let now = TimestampMs::now().into_inner();
+        for (id, message, starts_at) in &[
+            ("a", "Maintenance <tonight>", now - 1000),
+            ("b", "Upcoming campaign", now + 3_600_000),
+        ] {
+            db.exclusive()
+                .unwrap()
+                .create_announcement(&Announcement {
+                    id: (*id).into(),
+                    message: (*message).into(),
+                    severity: AnnouncementSeverity::Critical,
+                    starts_at: TimestampMs::from_inner(*starts_at),
+                    ends_at: None,
+                })
+                .unwrap();
+        }
+        let mut index = client.get("/").dispatch();
+        assert_eq!(index.status(), Status::Ok);
+        let index_str = index.body().and_then(|b| b.into_string()).unwrap();
+        assert!(index_str
+            .contains("<div class=\"announcement critical\">Maintenance &lt;tonight&gt;</div>"));
+        assert!(!index_str.contains("Upcoming campaign"));
     }
 }
 
@@ -333,6 +418,8 @@ mod entry {
             license: "CC0-1.0".into(),
             image_url: None,
             image_link_url: None,
+            source: None,
+            additional_locations: vec![],
         };
         let gw = DummyNotifyGW;
         let e_id = flows::prelude::create_place(db, search, &gw, e, None)
@@ -347,7 +434,7 @@ mod entry {
             value: 1.into(),
             entry: e_id.clone().into(),
         };
-        let (r_id, c_id) = flows::prelude::create_rating(db, search, r).unwrap();
+        let (r_id, c_id) = flows::prelude::create_rating(db, search, r, None, false).unwrap();
         (e_id.into(), r_id, c_id)
     }
 
@@ -365,6 +452,20 @@ mod entry {
         );
     }
 
+    #[test]
+    fn get_public_statistics() {
+        let (client, db, mut search) = setup();
+        let res = client.get("/stats").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        create_place_with_rating(&db, &mut search);
+        let mut res = client.get("/stats").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+        assert!(body_str.contains("Number of Places"));
+        assert!(body_str.contains("<svg class=\"chart\""));
+        assert_eq!(1, body_str.matches("<rect").count());
+    }
+
     #[test]
     fn get_entry_details_as_admin() {
         let (client, db, mut search) = setup();
@@ -397,13 +498,63 @@ mod entry {
         );
     }
 
+    #[test]
+    fn get_entry_details_with_revoked_permissions() {
+        let (client, db, mut search) = setup();
+        let (id, _, _) = create_place_with_rating(&db, &mut search);
+        create_user(&db, "foo", Role::Scout);
+        db.exclusive()
+            .unwrap()
+            .replace_permission_override(
+                "foo@example.com",
+                &PermissionOverride {
+                    permission: Permission::ArchiveComments,
+                    granted: false,
+                },
+            )
+            .unwrap();
+        login_user(&client, "foo");
+        let mut res = client.get(format!("/entries/{}", id)).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+        assert!(body_str.contains("action=\"/ratings/actions/archive\""));
+        assert!(!body_str.contains("action=\"/comments/actions/archive\""));
+        assert!(!body_str.contains("action=\"/places/actions/merge\""));
+    }
+
+    #[test]
+    fn get_entry_details_with_granted_permissions() {
+        let (client, db, mut search) = setup();
+        let (id, _, _) = create_place_with_rating(&db, &mut search);
+        create_user(&db, "foo", Role::Scout);
+        db.exclusive()
+            .unwrap()
+            .replace_permission_override(
+                "foo@example.com",
+                &PermissionOverride {
+                    permission: Permission::MergePlaces,
+                    granted: true,
+                },
+            )
+            .unwrap();
+        login_user(&client, "foo");
+        let mut res = client.get(format!("/entries/{}", id)).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+        assert!(body_str.contains("action=\"/places/actions/merge\""));
+    }
+
     #[test]
     fn archive_comment_as_admin() {
         let (client, db, mut search) = setup();
         create_user(&db, "foo", Role::Admin);
         login_user(&client, "foo");
         let (e_id, _, c_id) = create_place_with_rating(&db, &mut search);
-        let comment = db.shared().unwrap().load_comment(&c_id).unwrap();
+        let comment = db
+            .shared()
+            .unwrap()
+            .load_comment(&c_id.as_str().into())
+            .unwrap();
         assert!(comment.archived_at.is_none());
         let res = client
             .post("/comments/actions/archive")
@@ -412,7 +563,12 @@ mod entry {
             .dispatch();
         assert_eq!(res.status(), Status::SeeOther);
         //TODO: archived comments should be loaded too.
-        let err = db.shared().unwrap().load_comment(&c_id).err().unwrap();
+        let err = db
+            .shared()
+            .unwrap()
+            .load_comment(&c_id.as_str().into())
+            .err()
+            .unwrap();
         match err {
             RepoError::NotFound => {}
             _ => panic!("Expected {}", RepoError::NotFound),
@@ -425,7 +581,11 @@ mod entry {
         create_user(&db, "foo", Role::Scout);
         login_user(&client, "foo");
         let (e_id, _, c_id) = create_place_with_rating(&db, &mut search);
-        let comment = db.shared().unwrap().load_comment(&c_id).unwrap();
+        let comment = db
+            .shared()
+            .unwrap()
+            .load_comment(&c_id.as_str().into())
+            .unwrap();
         assert!(comment.archived_at.is_none());
         let res = client
             .post("/comments/actions/archive")
@@ -434,7 +594,12 @@ mod entry {
             .dispatch();
         assert_eq!(res.status(), Status::SeeOther);
         //TODO: archived comments should be loaded too.
-        let err = db.shared().unwrap().load_comment(&c_id).err().unwrap();
+        let err = db
+            .shared()
+            .unwrap()
+            .load_comment(&c_id.as_str().into())
+            .err()
+            .unwrap();
         match err {
             RepoError::NotFound => {}
             _ => panic!("Expected {}", RepoError::NotFound),
@@ -451,7 +616,11 @@ mod entry {
             .body(format!("ids={}&place_id={}", c_id, e_id))
             .dispatch();
         assert_eq!(res.status(), Status::NotFound);
-        let comment = db.shared().unwrap().load_comment(&c_id).unwrap();
+        let comment = db
+            .shared()
+            .unwrap()
+            .load_comment(&c_id.as_str().into())
+            .unwrap();
         assert!(comment.archived_at.is_none());
     }
 
@@ -490,6 +659,28 @@ mod admin {
         let user = get_user(&db, "user");
         assert_eq!(user.role, Role::Scout);
     }
+
+    #[test]
+    fn list_places_pending_review() {
+        let (client, db, _) = setup();
+        create_user(&db, "scout", Role::Scout);
+        create_user(&db, "user", Role::User);
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(Place::build().id("foo").title("Foo").finish())
+            .unwrap();
+
+        login_user(&client, "user");
+        let res = client.get("/moderation").dispatch();
+        assert_ne!(res.status(), Status::Ok);
+
+        login_user(&client, "scout");
+        let mut res = client.get("/moderation").dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+        assert!(body_str.contains("<a href=\"/entries/foo\">Foo</a>"));
+        assert!(body_str.contains("<a href=\"/places/foo/review\">review</a>"));
+    }
 }
 
 mod pw_reset {
@@ -1,6 +1,7 @@
 use super::page;
 use crate::core::entities::*;
 use maud::{html, Markup};
+use rocket::request::FlashMessage;
 
 pub struct DashBoardPresenter {
     pub user: User,
@@ -10,11 +11,11 @@ pub struct DashBoardPresenter {
     pub user_count: usize,
 }
 
-pub fn dashboard(data: DashBoardPresenter) -> Markup {
+pub fn dashboard(flash: Option<FlashMessage>, data: DashBoardPresenter) -> Markup {
     page(
         "Admin Dashboard",
         Some(&data.user.email),
-        None,
+        flash,
         None,
         html! {
             main class="dashboard" {
@@ -39,6 +40,19 @@ pub fn dashboard(data: DashBoardPresenter) -> Markup {
                 }
                 h3 { "User Management" }
                 (super::search_users_form())
+                h3 { "Tag Management" }
+                form action="/tags/actions/rename" method="POST" {
+                    input type="text" name="tag" placeholder="tag" required?;
+                    input type="text" name="new_tag" placeholder="new name" required?;
+                    br;
+                    input type="submit" value="rename";
+                }
+                form action="/tags/actions/merge" method="POST" {
+                    input type="text" name="tags" placeholder="tags (comma separated)" required?;
+                    input type="text" name="into" placeholder="existing tag" required?;
+                    br;
+                    input type="submit" value="merge";
+                }
             }
         },
     )
@@ -1,25 +1,16 @@
 use super::{address_to_html, leaflet_css_link, map_scripts, page};
 use crate::core::prelude::*;
 use maud::{html, Markup};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 type Ratings = Vec<(Rating, Vec<Comment>)>;
 
 pub struct EntryPresenter {
     pub place: Place,
     pub ratings: HashMap<RatingContext, Ratings>,
-    pub allow_archiving: bool,
-}
-
-impl From<(Place, Vec<(Rating, Vec<Comment>)>, Role)> for EntryPresenter {
-    fn from((place, rtngs, role): (Place, Vec<(Rating, Vec<Comment>)>, Role)) -> EntryPresenter {
-        let mut p: EntryPresenter = (place, rtngs).into();
-        p.allow_archiving = match role {
-            Role::Admin | Role::Scout => true,
-            _ => false,
-        };
-        p
-    }
+    /// The effective permissions of the current user
+    pub permissions: BTreeSet<Permission>,
+    pub contact_verified: bool,
 }
 
 impl From<(Place, Vec<(Rating, Vec<Comment>)>)> for EntryPresenter {
@@ -33,11 +24,11 @@ impl From<(Place, Vec<(Rating, Vec<Comment>)>)> for EntryPresenter {
                 ratings.insert(r.context, vec![(r, comments)]);
             }
         }
-        let allow_archiving = false;
         EntryPresenter {
             place,
             ratings,
-            allow_archiving,
+            permissions: Default::default(),
+            contact_verified: false,
         }
     }
 }
@@ -54,19 +45,26 @@ pub fn entry(email: Option<&str>, e: EntryPresenter) -> Markup {
 
 fn entry_detail(e: EntryPresenter) -> Markup {
     let rev = format!("v{}", u64::from(e.place.revision));
+    let allow_viewing_history = e.permissions.contains(&Permission::ViewPlaceHistory);
+    let allow_reviewing = e.permissions.contains(&Permission::ReviewPlaces);
+    let allow_archiving_ratings = e.permissions.contains(&Permission::ArchiveRatings);
+    let allow_archiving_comments = e.permissions.contains(&Permission::ArchiveComments);
+    let allow_merging = e.permissions.contains(&Permission::MergePlaces);
     html! {
         h3 {
             (e.place.title)
             " "
             span class="rev" {
                 "("
-                @if e.allow_archiving {
+                @if allow_viewing_history {
                      a href=(format!("/places/{}/history", e.place.id)) { (rev) }
-                     " | "
-                     a href=(format!("/places/{}/review", e.place.id)) { "review" }
                 } @else {
                     (rev)
                 }
+                @if allow_reviewing {
+                     " | "
+                     a href=(format!("/places/{}/review", e.place.id)) { "review" }
+                }
                 ")"
             }
         }
@@ -85,7 +83,13 @@ fn entry_detail(e: EntryPresenter) -> Markup {
                     @if let Some(ref m) = c.email {
                         tr {
                             td { "eMail" }
-                            td { a href=(format!("mailto:{}",m)) { (m) } }
+                            td {
+                                a href=(format!("mailto:{}",m)) { (m) }
+                                @if e.contact_verified {
+                                    " "
+                                    span class="verified" { "✓ verified" }
+                                }
+                            }
                         }
                     }
                     @if let Some(ref t) = c.phone {
@@ -119,23 +123,39 @@ fn entry_detail(e: EntryPresenter) -> Markup {
             ul {
                 @for (r,comments) in ratings {
                     li {
-                        (rating(e.place.id.as_ref(), e.allow_archiving, &r, &comments))
+                        (rating(e.place.id.as_ref(), allow_archiving_ratings, allow_archiving_comments, &r, &comments))
                     }
                 }
             }
         }
+        @if allow_merging {
+            h3 { "Merge duplicate" }
+            form action="/places/actions/merge" method="POST" {
+                input type="hidden" name="duplicate_id" value=(e.place.id);
+                input type="text" name="primary_id" placeholder="id of the primary place" required?;
+                br;
+                input type="submit" value="merge into";
+            }
+        }
         div id="map" style="height:300px;" { }
         (map_scripts(&[e.place.into()]))
     }
 }
 
-fn rating(place_id: &str, archive: bool, r: &Rating, comments: &[Comment]) -> Markup {
+fn rating(
+    place_id: &str,
+    archive_rating: bool,
+    archive_comments: bool,
+    r: &Rating,
+    comments: &[Comment],
+) -> Markup {
     html! {
       h5 { (r.title) " " span { (format!("({})",i8::from(r.value))) } }
-      @if archive {
+      @if archive_rating {
         form action = "/ratings/actions/archive" method = "POST" {
             input type="hidden" name="ids" value=(r.id.to_string());
             input type="hidden" name="place_id" value=(place_id);
+            input name="comment" placeholder="Reason";
             input type="submit" value="archive rating";
         }
       }
@@ -146,10 +166,11 @@ fn rating(place_id: &str, archive: bool, r: &Rating, comments: &[Comment]) -> Ma
           @for c in comments {
               li {
                   p { (c.text) }
-                  @if archive {
+                  @if archive_comments {
                     form action = "/comments/actions/archive" method = "POST" {
                         input type="hidden" name="ids" value=(c.id.to_string());
                         input type="hidden" name="place_id" value=(place_id);
+                        input name="comment" placeholder="Reason";
                         input type="submit" value="archive comment";
                     }
                   }
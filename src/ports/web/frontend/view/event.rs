@@ -1,7 +1,7 @@
 use super::*;
 use maud::{html, Markup};
 
-pub fn event(user: Option<User>, ev: Event) -> Markup {
+pub fn event(user: Option<User>, allow_archiving: bool, ev: Event) -> Markup {
     page(
         &ev.title,
         user.as_ref().map(|u| &*u.email),
@@ -89,14 +89,10 @@ pub fn event(user: Option<User>, ev: Event) -> Markup {
                         }
                     }
                 }
-                @if let Some(user) = &user {
-                    @match user.role {
-                        Role::Admin | Role::Scout => {
-                            form action=(format!("/events/{}/archive", ev.id)) method="POST" {
-                                input type="submit" value="archive event";
-                            }
-                        }
-                        _=> {}
+                @if allow_archiving {
+                    form action=(format!("/events/{}/archive", ev.id)) method="POST" {
+                        input name="comment" placeholder="Reason";
+                        input type="submit" value="archive event";
                     }
                 }
             }
@@ -171,3 +167,40 @@ pub fn events(email: Option<&str>, events: &[Event]) -> Markup {
         },
     )
 }
+
+pub fn event_registration_cancellation_form(token: &str) -> Markup {
+    page(
+        "Cancel registration",
+        None,
+        None,
+        None,
+        html! {
+            div class="event-registration" {
+                h2 { "Do you want to cancel your registration?" }
+                form action=(format!("/cancel-event-registration/{}", token)) method="POST" {
+                    input type="submit" value="cancel registration";
+                }
+            }
+        },
+    )
+}
+
+pub fn event_registration_cancellation(event_id: Option<&str>) -> Markup {
+    page(
+        "Cancel registration",
+        None,
+        None,
+        None,
+        html! {
+            div class="event-registration" {
+                @if let Some(id) = event_id {
+                    h2 { "Your registration has been cancelled" }
+                    p { a href=(format!("/events/{}", id)) { "Back to the event" } }
+                } @else {
+                    h2 { "The registration could not be cancelled" }
+                    p { "The link is invalid or the registration has already been cancelled." }
+                }
+            }
+        },
+    )
+}
@@ -16,6 +16,7 @@ mod page;
 mod password;
 mod place;
 mod register;
+mod stats;
 
 pub use dashboard::*;
 pub use entry::*;
@@ -25,14 +26,16 @@ use page::*;
 pub use password::*;
 pub use place::*;
 pub use register::*;
+pub use stats::*;
 
-pub fn index(email: Option<&str>) -> Markup {
+pub fn index(email: Option<&str>, announcements: &[Announcement]) -> Markup {
     page(
         "OpenFairDB Search",
         email,
         None,
         None,
         html! {
+            (announcement_banners(announcements))
             div class="search" {
                 h1 {"OpenFairDB Search"}
                 (global_search_form(None))
@@ -41,6 +44,21 @@ pub fn index(email: Option<&str>) -> Markup {
     )
 }
 
+fn announcement_banners(announcements: &[Announcement]) -> Markup {
+    html! {
+        @for a in announcements {
+            @let severity = match a.severity {
+                AnnouncementSeverity::Info => "info",
+                AnnouncementSeverity::Warning => "warning",
+                AnnouncementSeverity::Critical => "critical",
+            };
+            div class=(format!("announcement {}", severity)) {
+                (a.message)
+            }
+        }
+    }
+}
+
 pub fn global_search_form(search_term: Option<&str>) -> Markup {
     html! {
         div class="search-form" {
@@ -48,8 +48,10 @@ fn header(email: Option<&str>) -> Markup {
         nav {
             a href="/" { "places" }
             a href="/events" { "events" }
+            a href="/stats" { "statistics" }
             @if email.is_some() {
                 a href="/dashboard" { "dashboard" }
+                a href="/moderation" { "moderation" }
                 form class="logout" action="/logout" method ="POST" {
                     input type="submit" value="logout";
                 }
@@ -39,7 +39,17 @@ pub fn place_history(user: &User, h: &PlaceHistory) -> Markup {
                     tbody {
                         @for (r,logs) in &h.revisions {
                             tr {
-                                td{ (u64::from(r.revision)) }
+                                td{
+                                    (u64::from(r.revision))
+                                    @if !r.revision.is_initial() {
+                                        br;
+                                        a href=(format!("/places/{}/diff?from={}&to={}",
+                                            h.place.id,
+                                            u64::from(r.revision) - 1,
+                                            u64::from(r.revision)
+                                        )) { "changes" }
+                                    }
+                                }
                                 td{
                                     ul class="log" {
                                         @for l in logs {
@@ -100,6 +110,102 @@ pub fn place_history(user: &User, h: &PlaceHistory) -> Markup {
     )
 }
 
+pub fn place_revision_diff(user: &User, d: &PlaceRevisionDiff) -> Markup {
+    page(
+        "Place Changes",
+        Some(&user.email),
+        None,
+        None,
+        html! {
+            div class="revisions diff" {
+                h3 {
+                    (format!("Changes of place {} from revision {} to {}",
+                        d.place_id,
+                        u64::from(d.from),
+                        u64::from(d.to)
+                    ))
+                }
+                @if d.is_empty() {
+                    p { "No changes" }
+                } @else {
+                    table {
+                        thead {
+                            tr {
+                                th{ "Field" }
+                                th{ (format!("Revision {}", u64::from(d.from))) }
+                                th{ (format!("Revision {}", u64::from(d.to))) }
+                            }
+                        }
+                        tbody {
+                            @if let Some(c) = &d.title {
+                                tr { td{ "Title" } td class="from" { (c.from) } td class="to" { (c.to) } }
+                            }
+                            @if let Some(c) = &d.description {
+                                tr { td{ "Description" } td class="from" { (c.from) } td class="to" { (c.to) } }
+                            }
+                            @if let Some(c) = &d.location {
+                                tr {
+                                    td{ "Location" }
+                                    td class="from" { (location(&c.from)) }
+                                    td class="to" { (location(&c.to)) }
+                                }
+                            }
+                            @if let Some(c) = &d.contact {
+                                tr {
+                                    td{ "Contact" }
+                                    td class="from" { (contact(c.from.as_ref())) }
+                                    td class="to" { (contact(c.to.as_ref())) }
+                                }
+                            }
+                            @if !d.added_tags.is_empty() || !d.removed_tags.is_empty() {
+                                tr {
+                                    td{ "Tags" }
+                                    td class="from" {
+                                        ul class="tags" {
+                                            @for t in &d.removed_tags {
+                                                li { (t) }
+                                            }
+                                        }
+                                    }
+                                    td class="to" {
+                                        ul class="tags" {
+                                            @for t in &d.added_tags {
+                                                li { (t) }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    )
+}
+
+fn location(l: &Location) -> Markup {
+    html! {
+        (format!("{:.5}/{:.5}", l.pos.lat().to_deg(), l.pos.lng().to_deg()))
+        @if let Some(a) = &l.address {
+            br;
+            @if let Some(x) = &a.street { (x) " " }
+            @if let Some(x) = &a.zip { (x) " " }
+            @if let Some(x) = &a.city { (x) " " }
+            @if let Some(x) = &a.country { (x) }
+        }
+    }
+}
+
+fn contact(c: Option<&Contact>) -> Markup {
+    html! {
+        @if let Some(c) = c {
+            @if let Some(x) = &c.email { (x) br; }
+            @if let Some(x) = &c.phone { (x) }
+        }
+    }
+}
+
 fn review_status_log(place_rev: Revision, l: &ReviewStatusLog) -> Markup {
     use ReviewStatus as S;
     let status = match l.status {
@@ -171,3 +277,40 @@ pub fn place_review(email: &str, place: &Place, status: ReviewStatus) -> Markup
         },
     )
 }
+
+pub fn places_pending_review(email: &str, places: &[Place], next_offset: Option<u64>) -> Markup {
+    page(
+        "Moderation",
+        Some(email),
+        None,
+        None,
+        html! {
+            main class="moderation" {
+                h3 { "Places pending review" }
+                @if places.is_empty() {
+                    p { "There are no places that need to be reviewed." }
+                } @else {
+                    table {
+                        tr {
+                            th { "Created" }
+                            th { "Title" }
+                            th { "Revision" }
+                            th {}
+                        }
+                        @for p in places {
+                            tr {
+                                td { (p.created.at) }
+                                td { a href=(format!("/entries/{}", p.id)) { (p.title) } }
+                                td { (u64::from(p.revision)) }
+                                td { a href=(format!("/places/{}/review", p.id)) { "review" } }
+                            }
+                        }
+                    }
+                    @if let Some(offset) = next_offset {
+                        a href=(format!("/moderation?offset={}", offset)) { "more" }
+                    }
+                }
+            }
+        },
+    )
+}
@@ -0,0 +1,85 @@
+use super::page;
+use crate::core::prelude::*;
+use maud::{html, Markup};
+
+const CHART_HEIGHT: usize = 150;
+const CHART_BAR_WIDTH: usize = 20;
+
+pub struct StatisticsPresenter<'a> {
+    pub email: Option<&'a str>,
+    pub place_count: usize,
+    pub event_count: usize,
+    pub user_count: usize,
+    pub tag_count: usize,
+    pub new_places_per_month: Vec<MonthlyCount>,
+    pub top_tags: Vec<TagFrequency>,
+}
+
+pub fn statistics(data: StatisticsPresenter) -> Markup {
+    page(
+        "Statistics",
+        data.email,
+        None,
+        None,
+        html! {
+            main class="stats" {
+                h3 { "Database Statistics" }
+                table {
+                    tr {
+                        td {"Number of Places"}
+                        td {(data.place_count)}
+                    }
+                    tr {
+                        td {"Number of Events"}
+                        td {(data.event_count)}
+                    }
+                    tr {
+                        td {"Number of Users"}
+                        td {(data.user_count)}
+                    }
+                    tr {
+                        td {"Number of Tags"}
+                        td {(data.tag_count)}
+                    }
+                }
+                h3 { "New Places per Month" }
+                @if data.new_places_per_month.is_empty() {
+                    p { "No places have been created yet." }
+                } @else {
+                    (monthly_chart(&data.new_places_per_month))
+                }
+                h3 { "Most Popular Tags" }
+                ol class="tags" {
+                    @for TagFrequency(tag, count) in &data.top_tags {
+                        li { "#" (tag) " (" (count) ")" }
+                    }
+                }
+            }
+        },
+    )
+}
+
+// A bar chart that is rendered on the server, i.e. it
+// doesn't need any JavaScript
+fn monthly_chart(counts: &[MonthlyCount]) -> Markup {
+    let max_count = counts.iter().map(|c| c.count).max().unwrap_or(0).max(1);
+    let width = counts.len() * CHART_BAR_WIDTH;
+    html! {
+        svg class="chart" width=(width) height=(CHART_HEIGHT) viewBox=(format!("0 0 {} {}", width, CHART_HEIGHT)) {
+            @for (i, c) in counts.iter().enumerate() {
+                @let height = (c.count * CHART_HEIGHT / max_count).max(1);
+                @let x = i * CHART_BAR_WIDTH + 1;
+                @let y = CHART_HEIGHT - height;
+                @let bar_width = CHART_BAR_WIDTH - 2;
+                rect x=(x) y=(y) width=(bar_width) height=(height) {
+                    title { (format!("{}-{:02}: {}", c.year, c.month, c.count)) }
+                }
+            }
+        }
+        div class="chart-range" {
+            span { (format!("{}-{:02}", counts[0].year, counts[0].month)) }
+            " – "
+            span { (format!("{}-{:02}", counts[counts.len() - 1].year, counts[counts.len() - 1].month)) }
+        }
+    }
+}
@@ -1,14 +1,41 @@
+use super::{sqlite, Cfg};
+use crate::core::{
+    prelude::{ApiTokenScope, Error, Organization, ParameterError, UserGateway},
+    usecases,
+};
 use rocket::{
     self,
     http::Status,
     outcome::IntoOutcome,
     request::{self, FromRequest, Request},
-    Outcome,
+    Outcome, State,
 };
+use std::{marker::PhantomData, net::IpAddr};
 
 pub const COOKIE_EMAIL_KEY: &str = "ofdb-user-email";
 pub const COOKIE_USER_KEY: &str = "user_id";
 
+/// The address of the client if known, either from the
+/// connection or from the `X-Real-IP` header of a trusted proxy
+#[derive(Debug)]
+pub struct ClientAddr(pub Option<IpAddr>);
+
+impl<'a, 'r> FromRequest<'a, 'r> for ClientAddr {
+    type Error = ();
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        let remote = request.remote().map(|addr| addr.ip());
+        let is_trusted_proxy = match (remote, request.guard::<State<Cfg>>().succeeded()) {
+            (Some(remote), Some(cfg)) => cfg.trusted_proxies.contains(&remote),
+            _ => false,
+        };
+        if is_trusted_proxy {
+            Outcome::Success(ClientAddr(request.real_ip().or(remote)))
+        } else {
+            Outcome::Success(ClientAddr(remote))
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Bearer(pub String);
 
@@ -45,8 +72,134 @@ impl<'a, 'r> FromRequest<'a, 'r> for Login {
             .and_then(|cookie| cookie.value().parse().ok())
             .map(Login);
         match user {
-            Some(user) => Outcome::Success(user),
-            None => Outcome::Failure((Status::Unauthorized, ())),
+            // The session of a user ends with the deactivation
+            // of the account
+            Some(user) => {
+                let connections = request.guard::<sqlite::Connections>()?;
+                let db = match connections.shared() {
+                    Ok(db) => db,
+                    Err(_) => return Outcome::Failure((Status::ServiceUnavailable, ())),
+                };
+                match db.is_user_active(&user.0) {
+                    Ok(true) => Outcome::Success(user),
+                    Ok(false) => Outcome::Failure((Status::Forbidden, ())),
+                    Err(err) => {
+                        error!("Failed to check the status of user {}: {}", user.0, err);
+                        Outcome::Failure((Status::InternalServerError, ()))
+                    }
+                }
+            }
+            // Requests without a session might be authorized
+            // by a personal API token of the user instead
+            None => match request.guard::<BearerLogin>() {
+                Outcome::Success(BearerLogin(email)) => Outcome::Success(Login(email)),
+                Outcome::Failure(failure) => Outcome::Failure(failure),
+                Outcome::Forward(()) => Outcome::Failure((Status::Unauthorized, ())),
+            },
+        }
+    }
+}
+
+/// A user that authorizes a request with one of its personal
+/// API tokens instead of a session cookie.
+#[derive(Debug)]
+pub struct BearerLogin(pub String);
+
+impl<'a, 'r> FromRequest<'a, 'r> for BearerLogin {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<BearerLogin, ()> {
+        let token = request.guard::<Bearer>()?;
+        let connections = request.guard::<sqlite::Connections>()?;
+        let db = match connections.shared() {
+            Ok(db) => db,
+            Err(_) => return Outcome::Failure((Status::ServiceUnavailable, ())),
+        };
+        match usecases::authorize_user_by_api_token(&*db, &token.0) {
+            Ok(user) => Outcome::Success(BearerLogin(user.email)),
+            Err(Error::Parameter(ParameterError::UserDeactivated)) => {
+                Outcome::Failure((Status::Forbidden, ()))
+            }
+            Err(Error::Parameter(_)) => Outcome::Failure((Status::Unauthorized, ())),
+            Err(err) => {
+                error!("Failed to authorize user by API token: {}", err);
+                Outcome::Failure((Status::InternalServerError, ()))
+            }
+        }
+    }
+}
+
+/// The scope that the API token of an organization
+/// must grant to access a route.
+pub trait RequiredScope {
+    const SCOPE: ApiTokenScope;
+}
+
+pub mod scopes {
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct Read;
+
+    impl RequiredScope for Read {
+        const SCOPE: ApiTokenScope = ApiTokenScope::Read;
+    }
+
+    #[derive(Debug)]
+    pub struct WriteEvents;
+
+    impl RequiredScope for WriteEvents {
+        const SCOPE: ApiTokenScope = ApiTokenScope::WriteEvents;
+    }
+
+    #[derive(Debug)]
+    pub struct ClearPlaces;
+
+    impl RequiredScope for ClearPlaces {
+        const SCOPE: ApiTokenScope = ApiTokenScope::ClearPlaces;
+    }
+
+    #[derive(Debug)]
+    pub struct ImportPlaces;
+
+    impl RequiredScope for ImportPlaces {
+        const SCOPE: ApiTokenScope = ApiTokenScope::ImportPlaces;
+    }
+}
+
+/// An organization that authorizes a request with an API token
+/// that grants the scope `S` of the route.
+#[derive(Debug)]
+pub struct ScopedBearer<S> {
+    pub token: String,
+    pub org: Organization,
+    scope: PhantomData<S>,
+}
+
+impl<'a, 'r, S: RequiredScope> FromRequest<'a, 'r> for ScopedBearer<S> {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        let Bearer(token) = request.guard::<Bearer>()?;
+        let connections = request.guard::<sqlite::Connections>()?;
+        let db = match connections.shared() {
+            Ok(db) => db,
+            Err(_) => return Outcome::Failure((Status::ServiceUnavailable, ())),
+        };
+        match usecases::authorize_organization_by_token(&*db, &token, S::SCOPE) {
+            Ok(org) => Outcome::Success(ScopedBearer {
+                token,
+                org,
+                scope: PhantomData,
+            }),
+            Err(Error::Parameter(ParameterError::Forbidden)) => {
+                Outcome::Failure((Status::Forbidden, ()))
+            }
+            Err(Error::Parameter(_)) => Outcome::Failure((Status::Unauthorized, ())),
+            Err(err) => {
+                error!("Failed to authorize organization by API token: {}", err);
+                Outcome::Failure((Status::InternalServerError, ()))
+            }
         }
     }
 }
@@ -1,15 +1,11 @@
 use crate::{
-    core::{
-        db::{EventIndexer, PlaceIndexer},
-        prelude::*,
-        usecases,
-    },
-    infrastructure::error::AppError,
+    core::{db::PlaceIndexer, prelude::*, usecases},
+    infrastructure::{flows::prelude as flows, EMAIL_TRANSPORT},
 };
-use ofdb_core::util::sort::Rated;
+use ofdb_core::EmailTransport;
+use ofdb_gateways::webhooks::HttpWebhooks;
 use rocket::{config::Config, Rocket, Route};
-use rocket_contrib::json::Json;
-use std::result;
+use std::{net::IpAddr, thread, time::Duration};
 
 pub mod api;
 #[cfg(feature = "frontend")]
@@ -18,65 +14,224 @@ mod guards;
 #[cfg(test)]
 mod mockdb;
 pub mod notify;
+mod rate_limit;
+mod rating_timelines;
 mod sqlite;
 mod tantivy;
 #[cfg(test)]
 pub mod tests;
 
-type Result<T> = result::Result<Json<T>, AppError>;
-
-fn index_all_places<D: PlaceRepo + RatingRepository>(
-    db: &D,
-    indexer: &mut dyn PlaceIndexer,
-) -> Result<()> {
-    // TODO: Split into chunks with fixed size instead of
-    // loading all places at once!
-    let places = db.all_places()?;
-    for (place, status) in places {
-        let ratings = db.load_ratings_of_place(place.id.as_ref())?;
-        if let Err(err) =
-            indexer.add_or_update_place(&place, status, &place.avg_ratings(&ratings[..]))
-        {
-            error!("Failed to index place {:?}: {}", place, err);
+const DELETE_EXPIRED_USER_TOKENS_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const DELIVER_WEBHOOKS_INTERVAL: Duration = Duration::from_secs(10);
+
+const SEND_EMAILS_INTERVAL: Duration = Duration::from_secs(10);
+
+// Digests are sent when the daily or weekly period
+// of the individual recipients has ended
+const SEND_NOTIFICATION_DIGESTS_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Deployment specific settings of the web API
+#[derive(Debug, Clone, Default)]
+pub struct Cfg {
+    /// Only accept new ratings and comments from logged in
+    /// users with a confirmed e-mail address
+    pub ratings_require_login: bool,
+    /// Maximum number of subscriptions per user
+    pub subscription_quota: usecases::SubscriptionQuota,
+    /// Merge all segments of the search index periodically
+    pub index_optimize_interval: Option<Duration>,
+    /// Send reports about their region to scouts periodically
+    pub scout_report_interval: Option<Duration>,
+    /// The license of the whole dataset that is included
+    /// in all exports, e.g. `ODbL-1.0`
+    pub dataset_license: Option<String>,
+    /// Synonyms of existing tags for suggesting tags
+    /// from the description of entries
+    pub tag_synonyms: usecases::TagSynonyms,
+    /// Reverse proxies that are trusted to pass the address
+    /// of the client in the `X-Real-IP` header
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Maximum number of clients that wait for changes at the
+    /// same time. Must be lower than the number of workers.
+    /// Half of the workers if not configured.
+    pub max_streaming_clients: Option<usize>,
+}
+
+// Each streaming client blocks a worker thread and at
+// least one worker must remain for all other requests
+fn max_streaming_clients(configured: Option<usize>, workers: u16) -> Result<usize, String> {
+    let workers = usize::from(workers);
+    match configured {
+        Some(max) if max >= workers => Err(format!(
+            "The maximum number of streaming clients ({}) must be lower than the number of workers ({})",
+            max, workers
+        )),
+        Some(max) => Ok(max),
+        None => Ok(workers / 2),
+    }
+}
+
+fn delete_expired_user_tokens(connections: &sqlite::Connections) {
+    let db = match connections.exclusive() {
+        Ok(db) => db,
+        Err(err) => {
+            error!("Failed to delete expired user e-mail tokens: {}", err);
+            return;
+        }
+    };
+    match usecases::delete_expired_user_tokens(&*db) {
+        Ok(count) => {
+            if count > 0 {
+                info!("Deleted {} expired user e-mail token(s)", count);
+            }
+        }
+        Err(err) => {
+            error!("Failed to delete expired user e-mail tokens: {}", err);
         }
     }
-    if let Err(err) = indexer.flush_index() {
-        error!("Failed to build place index: {}", err);
+}
+
+// Expired tokens would otherwise accumulate until the next restart
+fn spawn_delete_expired_user_tokens(connections: sqlite::Connections) {
+    thread::spawn(move || loop {
+        thread::sleep(DELETE_EXPIRED_USER_TOKENS_INTERVAL);
+        delete_expired_user_tokens(&connections);
+    });
+}
+
+fn optimize_index(search_engine: &mut tantivy::SearchEngine) {
+    info!("Optimizing the search index...");
+    if let Err(err) = search_engine.optimize_index() {
+        error!("Failed to optimize the search index: {}", err);
     }
-    Ok(Json(()))
 }
 
-fn index_all_events_chronologically<D: EventGateway>(
-    db: &D,
-    indexer: &mut dyn EventIndexer,
-) -> Result<()> {
-    // TODO: Split into chunks with fixed size instead of
-    // loading all events at once!
-    let events = db.all_events_chronologically()?;
-    for event in events {
-        if let Err(err) = indexer.add_or_update_event(&event) {
-            error!("Failed to index event {:?}: {}", event, err);
+// Long-running servers would otherwise accumulate many small
+// segments in the search index that slow down searches
+fn spawn_optimize_index(mut search_engine: tantivy::SearchEngine, interval: Duration) {
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+        optimize_index(&mut search_engine);
+    });
+}
+
+fn send_scout_reports(
+    connections: &sqlite::Connections,
+    search_engine: &tantivy::SearchEngine,
+    notify: &notify::Notify,
+    since: TimestampMs,
+) {
+    info!("Sending reports to scouts...");
+    match flows::send_scout_reports(connections, search_engine, &**notify, since) {
+        Ok(count) => {
+            info!("Sent {} report(s) to scouts", count);
+        }
+        Err(err) => {
+            error!("Failed to send reports to scouts: {}", err);
         }
     }
-    if let Err(err) = indexer.flush_index() {
-        error!("Failed to build event index: {}", err);
+}
+
+// Scouts are informed about new, reported, and stale
+// places within their region without asking for it
+fn spawn_send_scout_reports(
+    connections: sqlite::Connections,
+    search_engine: tantivy::SearchEngine,
+    notify: notify::Notify,
+    interval: Duration,
+) {
+    thread::spawn(move || {
+        let mut since = TimestampMs::now();
+        loop {
+            thread::sleep(interval);
+            let until = TimestampMs::now();
+            send_scout_reports(&connections, &search_engine, &notify, since);
+            since = until;
+        }
+    });
+}
+
+fn send_notification_digests(connections: &sqlite::Connections, notify: &notify::Notify) {
+    match flows::send_notification_digests(connections, &**notify, TimestampMs::now()) {
+        Ok(count) => {
+            if count > 0 {
+                info!("Sent {} notification digest(s)", count);
+            }
+        }
+        Err(err) => {
+            error!("Failed to send notification digests: {}", err);
+        }
+    }
+}
+
+// Subscribers who prefer a digest only receive a single
+// e-mail with all changes per period
+fn spawn_send_notification_digests(connections: sqlite::Connections, notify: notify::Notify) {
+    thread::spawn(move || loop {
+        thread::sleep(SEND_NOTIFICATION_DIGESTS_INTERVAL);
+        send_notification_digests(&connections, &notify);
+    });
+}
+
+fn deliver_webhooks(connections: &sqlite::Connections, gateway: &HttpWebhooks) {
+    match flows::deliver_due_webhooks(connections, gateway) {
+        Ok(count) => {
+            if count > 0 {
+                debug!("Delivered {} request(s) to webhooks", count);
+            }
+        }
+        Err(err) => {
+            error!("Failed to deliver requests to webhooks: {}", err);
+        }
+    }
+}
+
+// Requests to webhooks are sent and retried independent
+// of the requests that caused them
+fn spawn_deliver_webhooks(connections: sqlite::Connections) {
+    thread::spawn(move || {
+        let gateway = HttpWebhooks::default();
+        loop {
+            thread::sleep(DELIVER_WEBHOOKS_INTERVAL);
+            deliver_webhooks(&connections, &gateway);
+        }
+    });
+}
+
+fn send_emails(connections: &sqlite::Connections, transport: &dyn EmailTransport) {
+    match flows::send_due_emails(connections, transport) {
+        Ok(count) => {
+            if count > 0 {
+                debug!("Sent {} e-mail(s) from the outbox", count);
+            }
+        }
+        Err(err) => {
+            error!("Failed to send e-mails from the outbox: {}", err);
+        }
     }
-    Ok(Json(()))
+}
+
+// E-mails are sent and retried independent
+// of the requests that caused them
+fn spawn_send_emails(connections: sqlite::Connections) {
+    let transport = match *EMAIL_TRANSPORT {
+        Some(transport) => transport,
+        None => return,
+    };
+    thread::spawn(move || loop {
+        thread::sleep(SEND_EMAILS_INTERVAL);
+        send_emails(&connections, transport);
+    });
 }
 
 pub(crate) fn rocket_instance(
     connections: sqlite::Connections,
-    mut search_engine: tantivy::SearchEngine,
+    search_engine: tantivy::SearchEngine,
     mounts: Vec<(&str, Vec<Route>)>,
     cfg: Option<Config>,
+    web_cfg: Cfg,
 ) -> Rocket {
-    info!("Indexing all places...");
-    index_all_places(&*connections.exclusive().unwrap(), &mut search_engine).unwrap();
-
-    info!("Indexing all events...");
-    index_all_events_chronologically(&*connections.exclusive().unwrap(), &mut search_engine)
-        .unwrap();
-
     info!("Deleting expired user e-mail tokens...");
     usecases::delete_expired_user_tokens(&*connections.exclusive().unwrap()).unwrap();
 
@@ -85,7 +240,28 @@ pub(crate) fn rocket_instance(
         Some(cfg) => rocket::custom(cfg),
         None => rocket::ignite(),
     };
-    let mut instance = r.manage(connections).manage(search_engine);
+    match max_streaming_clients(web_cfg.max_streaming_clients, r.config().workers) {
+        Ok(max) => {
+            info!("Accepting up to {} streaming clients", max);
+            connections.live_changes().limit_streaming_clients(max);
+        }
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    }
+    let notify = notify::Notify::new(&connections);
+    let live_changes = connections.live_changes().clone();
+    let place_changes = connections.place_changes().clone();
+    let mut instance = r
+        .manage(connections)
+        .manage(notify)
+        .manage(search_engine)
+        .manage(web_cfg)
+        .manage(rate_limit::RateLimit::anonymous_reports())
+        .manage(live_changes)
+        .manage(place_changes)
+        .manage(rating_timelines::RatingTimelines::default());
 
     for (m, r) in mounts {
         instance = instance.mount(m, r);
@@ -107,17 +283,29 @@ pub fn run(
     connections: sqlite::Connections,
     search_engine: tantivy::SearchEngine,
     enable_cors: bool,
+    web_cfg: Cfg,
 ) {
+    spawn_delete_expired_user_tokens(connections.clone());
+    spawn_deliver_webhooks(connections.clone());
+    spawn_send_emails(connections.clone());
+    let notify = notify::Notify::new(&connections);
+    spawn_send_notification_digests(connections.clone(), notify.clone());
+    if let Some(interval) = web_cfg.index_optimize_interval {
+        spawn_optimize_index(search_engine.clone(), interval);
+    }
+    if let Some(interval) = web_cfg.scout_report_interval {
+        spawn_send_scout_reports(connections.clone(), search_engine.clone(), notify, interval);
+    }
     if enable_cors {
         let cors = rocket_cors::CorsOptions {
             ..Default::default()
         }
         .to_cors()
         .unwrap();
-        rocket_instance(connections, search_engine, mounts(), None)
+        rocket_instance(connections, search_engine, mounts(), None, web_cfg)
             .attach(cors)
             .launch();
     } else {
-        rocket_instance(connections, search_engine, mounts(), None).launch();
+        rocket_instance(connections, search_engine, mounts(), None, web_cfg).launch();
     }
 }
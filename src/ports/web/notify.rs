@@ -1,22 +1,23 @@
-#[cfg(not(test))]
-use crate::infrastructure::{MAILGUN_GW, SENDMAIL_GW};
-#[cfg(test)]
-use crate::ports::web::tests::DummyNotifyGW;
+use super::sqlite;
+use crate::{
+    core::db::UserGateway,
+    infrastructure::{
+        flows::prelude as flows, ALERT_WEBHOOK_GW, EMAIL_TEMPLATES, EMAIL_TRANSPORT, SLACK_GW,
+        TELEGRAM_GW,
+    },
+};
 use core::ops::Deref;
 use ofdb_core::EmailGateway;
 use ofdb_entities::email::*;
-#[cfg(not(test))]
-use ofdb_gateways::notify;
+use ofdb_gateways::{alerts::Alerts, notify};
 use rocket::{
     request::{self, FromRequest},
-    Outcome, Request,
+    Outcome, Request, State,
 };
+use std::sync::Arc;
 
-#[cfg(not(test))]
-pub struct Notify(notify::Notify);
-
-#[cfg(test)]
-pub struct Notify(DummyNotifyGW);
+#[derive(Clone)]
+pub struct Notify(Arc<notify::NotifyAll>);
 
 struct DummyMailGw;
 
@@ -26,31 +27,148 @@ impl EmailGateway for DummyMailGw {
     }
 }
 
+/// Puts all e-mails into the outbox from where they
+/// are sent and retried in the background.
+struct OutboxMailGw(sqlite::Connections);
+
+impl EmailGateway for OutboxMailGw {
+    fn compose_and_send(&self, recipients: &[Email], subject: &str, body: &str) {
+        flows::enqueue_emails(&self.0, recipients, subject, body);
+    }
+}
+
+// Looking up the language of the recipients and putting the
+// e-mails into the outbox both need the database. The caller
+// might still hold a lock on it and must not wait for them.
+fn spawn_email_notifications<G>(
+    connections: &sqlite::Connections,
+    email_gw: G,
+) -> notify::NotifyInBackground
+where
+    G: EmailGateway + Send + Sync + 'static,
+{
+    let language_of = {
+        let connections = connections.clone();
+        move |email: &str| {
+            connections
+                .shared()
+                .ok()?
+                .try_get_user_by_email(email)
+                .ok()??
+                .language
+        }
+    };
+    notify::NotifyInBackground::spawn(notify::Notify::new(
+        email_gw,
+        EMAIL_TEMPLATES.clone(),
+        language_of,
+    ))
+}
+
 impl Deref for Notify {
     type Target = dyn ofdb_core::NotificationGateway;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &*self.0
+    }
+}
+
+impl Notify {
+    /// Should be created only once and then be cloned,
+    /// because e-mails are processed on a separate thread.
+    pub fn new(connections: &sqlite::Connections) -> Self {
+        if EMAIL_TRANSPORT.is_some() {
+            Self::with_email_gateway(connections, OutboxMailGw(connections.clone()))
+        } else {
+            Self::with_email_gateway(connections, DummyMailGw)
+        }
+    }
+
+    fn with_email_gateway<G>(connections: &sqlite::Connections, email_gw: G) -> Self
+    where
+        G: EmailGateway + Send + Sync + 'static,
+    {
+        let mut gateways = notify::NotifyAll::default();
+        gateways.register(spawn_email_notifications(connections, email_gw));
+        // Alerts for the operators of the deployment
+        if let Some(gw) = &*SLACK_GW {
+            gateways.register(Alerts(gw.clone()));
+        }
+        if let Some(gw) = &*TELEGRAM_GW {
+            gateways.register(Alerts(gw.clone()));
+        }
+        if let Some(gw) = &*ALERT_WEBHOOK_GW {
+            gateways.register(Alerts(gw.clone()));
+        }
+        Notify(Arc::new(gateways))
     }
 }
 
 impl<'a, 'r> FromRequest<'a, 'r> for Notify {
     type Error = ();
 
-    #[cfg(not(test))]
-    fn from_request(_: &'a Request<'r>) -> request::Outcome<Self, ()> {
-        if let Some(gw) = &*MAILGUN_GW {
-            info!("Use Mailgun gateway");
-            Outcome::Success(Notify(notify::Notify::new(gw.clone())))
-        } else if let Some(gw) = &*SENDMAIL_GW {
-            warn!("Mailgun gateway was not configured: use sendmail as fallback");
-            Outcome::Success(Notify(notify::Notify::new(gw.clone())))
-        } else {
-            warn!("No eMail gateway was not configured");
-            Outcome::Success(Notify(notify::Notify::new(DummyMailGw)))
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        let notify = request.guard::<State<Notify>>()?;
+        Outcome::Success(notify.inner().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{core::prelude::*, ports::web::tests::setup};
+    use std::{thread::sleep, time::Duration};
+
+    fn new_user(email: &str) -> User {
+        User {
+            email: email.into(),
+            email_confirmed: false,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::User,
+            language: Some("en".into()),
         }
     }
-    #[cfg(test)]
-    fn from_request(_: &'a Request<'r>) -> request::Outcome<Self, ()> {
-        Outcome::Success(Notify(DummyNotifyGW))
+
+    fn outbox(connections: &sqlite::Connections) -> Vec<OutgoingEmail> {
+        connections
+            .shared()
+            .unwrap()
+            .load_outgoing_emails(None, &Default::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn notify_while_holding_a_database_lock() {
+        let (_, connections, _) = setup(vec![]);
+        let notify = Notify::new(&connections);
+        let user = new_user("user@example.com");
+        let db = connections.exclusive().unwrap();
+        db.create_user(&user).unwrap();
+        // Must return without waiting for the lock
+        notify.user_registered_ofdb(&user);
+        drop(db);
+    }
+
+    #[test]
+    fn enqueue_emails_while_holding_a_database_lock() {
+        let (_, connections, _) = setup(vec![]);
+        let notify = Notify::with_email_gateway(&connections, OutboxMailGw(connections.clone()));
+        let user = new_user("user@example.com");
+        {
+            let db = connections.exclusive().unwrap();
+            db.create_user(&user).unwrap();
+            notify.user_registered_ofdb(&user);
+        }
+        let mut emails = outbox(&connections);
+        for _ in 0..100 {
+            if !emails.is_empty() {
+                break;
+            }
+            sleep(Duration::from_millis(10));
+            emails = outbox(&connections);
+        }
+        assert_eq!(1, emails.len());
+        assert_eq!(Email::from("user@example.com"), emails[0].recipient);
+        // In the preferred language of the recipient
+        assert!(emails[0].subject.contains("Please confirm"));
     }
 }
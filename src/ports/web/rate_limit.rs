@@ -0,0 +1,118 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    net::IpAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+const MAX_ANONYMOUS_REPORTS: usize = 10;
+const ANONYMOUS_REPORTS_PERIOD: Duration = Duration::from_secs(60 * 60);
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Limits the number of requests per client address within
+/// a sliding time window.
+///
+/// This is no proof that a human has sent the request and
+/// only protects against floods of automated requests.
+/// Clients with an unknown address share a common limit.
+/// If too many clients are tracked the least recent ones
+/// are forgotten.
+pub struct RateLimit {
+    max_requests: usize,
+    period: Duration,
+    requests: Mutex<HashMap<Option<IpAddr>, VecDeque<Instant>>>,
+}
+
+impl RateLimit {
+    pub fn new(max_requests: usize, period: Duration) -> Self {
+        Self {
+            max_requests,
+            period,
+            requests: Default::default(),
+        }
+    }
+
+    /// Limit for reports from anonymous visitors
+    pub fn anonymous_reports() -> Self {
+        Self::new(MAX_ANONYMOUS_REPORTS, ANONYMOUS_REPORTS_PERIOD)
+    }
+
+    /// Count a request of the client unless it exceeds the limit.
+    pub fn try_acquire(&self, client: Option<IpAddr>) -> bool {
+        self.try_acquire_at(client, Instant::now())
+    }
+
+    fn try_acquire_at(&self, client: Option<IpAddr>, now: Instant) -> bool {
+        let period = self.period;
+        let is_expired = |requested_at: &Instant| now.duration_since(*requested_at) >= period;
+        let mut requests = self.requests.lock().unwrap();
+        if !requests.contains_key(&client) && requests.len() >= MAX_TRACKED_CLIENTS {
+            requests.retain(|_, times| !times.iter().all(is_expired));
+            if requests.len() >= MAX_TRACKED_CLIENTS {
+                warn!("Too many clients with recent requests");
+                let count = requests.len() + 1 - MAX_TRACKED_CLIENTS;
+                evict_least_recent(&mut requests, count);
+            }
+        }
+        let times = requests.entry(client).or_default();
+        while times.front().map(is_expired).unwrap_or(false) {
+            times.pop_front();
+        }
+        if times.len() >= self.max_requests {
+            return false;
+        }
+        times.push_back(now);
+        true
+    }
+}
+
+/// Forget the clients that have not sent a request for the longest time.
+fn evict_least_recent(requests: &mut HashMap<Option<IpAddr>, VecDeque<Instant>>, count: usize) {
+    let mut last_requests: Vec<_> = requests
+        .iter()
+        .map(|(client, times)| (times.back().copied(), *client))
+        .collect();
+    last_requests.sort_unstable();
+    for (_, client) in last_requests.into_iter().take(count) {
+        requests.remove(&client);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_requests_per_client_and_period() {
+        let limit = RateLimit::new(2, Duration::from_secs(60));
+        let client = Some(IpAddr::from([127, 0, 0, 1]));
+        let other_client = Some(IpAddr::from([127, 0, 0, 2]));
+        let now = Instant::now();
+        assert!(limit.try_acquire_at(client, now));
+        assert!(limit.try_acquire_at(client, now + Duration::from_secs(1)));
+        assert!(!limit.try_acquire_at(client, now + Duration::from_secs(2)));
+        assert!(limit.try_acquire_at(other_client, now + Duration::from_secs(2)));
+        assert!(limit.try_acquire_at(None, now + Duration::from_secs(2)));
+        // The first request has expired
+        assert!(limit.try_acquire_at(client, now + Duration::from_secs(60)));
+        assert!(!limit.try_acquire_at(client, now + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn evict_least_recent_clients_if_too_many_are_tracked() {
+        let limit = RateLimit::new(1, Duration::from_secs(60));
+        let now = Instant::now();
+        let first_client = Some(IpAddr::from([10, 0, 0, 0]));
+        assert!(limit.try_acquire_at(first_client, now));
+        for i in 1..MAX_TRACKED_CLIENTS as u32 {
+            let client = Some(IpAddr::from((10 << 24 | i).to_be_bytes()));
+            assert!(limit.try_acquire_at(client, now + Duration::from_secs(1)));
+        }
+        let new_client = Some(IpAddr::from([192, 168, 0, 1]));
+        assert!(limit.try_acquire_at(new_client, now + Duration::from_secs(2)));
+        assert!(!limit.try_acquire_at(new_client, now + Duration::from_secs(2)));
+        assert_eq!(MAX_TRACKED_CLIENTS, limit.requests.lock().unwrap().len());
+        // The least recent client has been forgotten
+        assert!(limit.try_acquire_at(first_client, now + Duration::from_secs(3)));
+    }
+}
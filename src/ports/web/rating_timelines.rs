@@ -0,0 +1,64 @@
+use crate::core::entities::MonthlyAvgRatings;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+const DEFAULT_CAPACITY: usize = 1_000;
+
+struct CachedTimeline {
+    timeline: Vec<MonthlyAvgRatings>,
+    expires_at: Instant,
+}
+
+/// Cached rating timelines of places.
+///
+/// The timeline of a place is discarded when it receives
+/// new ratings or when ratings are archived.
+pub struct RatingTimelines {
+    entries: Mutex<HashMap<String, CachedTimeline>>,
+    ttl: Duration,
+    capacity: usize,
+}
+
+impl Default for RatingTimelines {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            ttl: DEFAULT_TTL,
+            capacity: DEFAULT_CAPACITY,
+        }
+    }
+}
+
+impl RatingTimelines {
+    pub fn get(&self, place_id: &str) -> Option<Vec<MonthlyAvgRatings>> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(place_id)
+            .filter(|entry| entry.expires_at > Instant::now())
+            .map(|entry| entry.timeline.clone())
+    }
+
+    pub fn insert(&self, place_id: &str, timeline: Vec<MonthlyAvgRatings>) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| entry.expires_at > now);
+        if entries.len() >= self.capacity {
+            return;
+        }
+        entries.insert(
+            place_id.to_owned(),
+            CachedTimeline {
+                timeline,
+                expires_at: now + self.ttl,
+            },
+        );
+    }
+
+    pub fn invalidate(&self, place_id: &str) {
+        self.entries.lock().unwrap().remove(place_id);
+    }
+}
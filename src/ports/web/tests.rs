@@ -9,9 +9,11 @@ use rocket::{
     Route,
 };
 
+pub use crate::infrastructure::flows::tests::prelude::DummyNotifyGW;
+
 pub mod prelude {
     pub use super::DummyNotifyGW;
-    pub use crate::core::db::*;
+    pub use crate::core::{db::*, usecases::tests::create_org_with_api_token};
     pub use rocket::{
         http::{ContentType, Cookie, Status},
         local::Client,
@@ -27,6 +29,17 @@ pub fn setup(
     rocket::local::Client,
     sqlite::Connections,
     tantivy::SearchEngine,
+) {
+    setup_with_cfg(mounts, Default::default())
+}
+
+pub fn setup_with_cfg(
+    mounts: Vec<(&'static str, Vec<Route>)>,
+    web_cfg: super::Cfg,
+) -> (
+    rocket::local::Client,
+    sqlite::Connections,
+    tantivy::SearchEngine,
 ) {
     let cfg = Config::build(Environment::Development)
         .log_level(LoggingLevel::Debug)
@@ -40,6 +53,7 @@ pub fn setup(
         search_engine.clone(),
         mounts,
         Some(cfg),
+        web_cfg,
     );
     let client = Client::new(rocket).unwrap();
     (client, connections, search_engine)
@@ -65,15 +79,10 @@ pub fn register_user(pool: &sqlite::Connections, email: &str, pw: &str, confirme
     }
 }
 
-pub struct DummyNotifyGW;
-
-impl ofdb_core::NotificationGateway for DummyNotifyGW {
-    fn place_added(&self, _: &[String], _: &Place, _: Vec<Category>) {}
-    fn place_updated(&self, _: &[String], _: &Place, _: Vec<Category>) {}
-    fn event_created(&self, _: &[String], _: &Event) {}
-    fn event_updated(&self, _: &[String], _: &Event) {}
-    fn user_registered_kvm(&self, _: &User) {}
-    fn user_registered_ofdb(&self, _: &User) {}
-    fn user_registered(&self, _: &User, _: &str) {}
-    fn user_reset_password_requested(&self, _: &EmailNonce) {}
+#[test]
+fn limit_streaming_clients_below_the_number_of_workers() {
+    assert_eq!(Ok(4), super::max_streaming_clients(None, 8));
+    assert_eq!(Ok(0), super::max_streaming_clients(None, 1));
+    assert_eq!(Ok(7), super::max_streaming_clients(Some(7), 8));
+    assert!(super::max_streaming_clients(Some(8), 8).is_err());
 }